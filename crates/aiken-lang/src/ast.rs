@@ -4,7 +4,10 @@ use crate::{
     ast::well_known::VALIDATOR_ELSE,
     expr::{TypedExpr, UntypedExpr},
     line_numbers::LineNumbers,
-    parser::token::{Base, Token},
+    parser::{
+        extra::Cfg,
+        token::{Base, Token},
+    },
     tipo::{PatternConstructor, Type, TypeInfo},
 };
 use indexmap::IndexMap;
@@ -12,6 +15,7 @@ use miette::Diagnostic;
 use ordinal::Ordinal;
 use owo_colors::{OwoColorize, Stream::Stdout};
 use std::{
+    collections::BTreeSet,
     fmt::{self, Display},
     ops::Range,
     rc::Rc,
@@ -100,6 +104,60 @@ impl UntypedModule {
             })
             .collect()
     }
+
+    /// Strip definitions and statements guarded by a `@cfg` attribute whose feature isn't
+    /// enabled. Returns the location of attributes which precede neither a definition nor a
+    /// statement, and therefore do not apply to anything.
+    pub fn strip_cfg(&mut self, cfg: &[Cfg], is_enabled: impl Fn(&str) -> bool) -> Vec<Span> {
+        let remove = cfg
+            .iter()
+            .filter(|attribute| !is_enabled(&attribute.feature))
+            .map(|attribute| attribute.target)
+            .collect::<BTreeSet<_>>();
+
+        let mut targets = BTreeSet::new();
+
+        for def in self.definitions.iter_mut() {
+            targets.insert(def.location().start);
+
+            match def {
+                Definition::Fn(Function { body, .. }) => {
+                    body.strip_statements(&remove, &mut targets);
+                }
+                Definition::Test(Function {
+                    body, arguments, ..
+                })
+                | Definition::Benchmark(Function {
+                    body, arguments, ..
+                }) => {
+                    for argument in arguments.iter_mut() {
+                        argument.via.strip_statements(&remove, &mut targets);
+                    }
+                    body.strip_statements(&remove, &mut targets);
+                }
+                Definition::Validator(Validator {
+                    handlers, fallback, ..
+                }) => {
+                    for handler in handlers.iter_mut() {
+                        handler.body.strip_statements(&remove, &mut targets);
+                    }
+                    fallback.body.strip_statements(&remove, &mut targets);
+                }
+                Definition::ModuleConstant(ModuleConstant { value, .. }) => {
+                    value.strip_statements(&remove, &mut targets);
+                }
+                Definition::TypeAlias(_) | Definition::DataType(_) | Definition::Use(_) => (),
+            }
+        }
+
+        self.definitions
+            .retain(|def| !remove.contains(&def.location().start));
+
+        cfg.iter()
+            .filter(|attribute| !targets.contains(&attribute.target))
+            .map(|attribute| attribute.location)
+            .collect()
+    }
 }
 
 impl TypedModule {
@@ -117,6 +175,7 @@ impl TypedModule {
             Definition::DataType(t) => t.public && t.name == name,
             Definition::Use(_) => false,
             Definition::Test(_) => false,
+            Definition::Benchmark(_) => false,
             Definition::Validator(_) => false,
         })
     }
@@ -133,6 +192,7 @@ impl TypedModule {
             Definition::ModuleConstant(_) => false,
             Definition::Use(_) => false,
             Definition::Test(_) => false,
+            Definition::Benchmark(_) => false,
             Definition::Validator(_) => false,
         })
     }
@@ -176,7 +236,7 @@ impl TypedModule {
                     );
                 }
 
-                Definition::Test(test) => {
+                Definition::Test(test) | Definition::Benchmark(test) => {
                     functions.insert(
                         FunctionAccessKey {
                             module_name: self.name.clone(),
@@ -269,6 +329,9 @@ impl UntypedFunction {
 pub type TypedTest = Function<Rc<Type>, TypedExpr, TypedArgVia>;
 pub type UntypedTest = Function<(), UntypedExpr, UntypedArgVia>;
 
+pub type TypedBenchmark = Function<Rc<Type>, TypedExpr, TypedArgVia>;
+pub type UntypedBenchmark = Function<(), UntypedExpr, UntypedArgVia>;
+
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum OnTestFailure {
     FailImmediately,
@@ -709,6 +772,7 @@ impl TypedValidator {
                         TypedClause {
                             location: Span::empty(),
                             pattern,
+                            guard: None,
                             then: TypedExpr::Sequence {
                                 location: Span::empty(),
                                 expressions: then,
@@ -741,6 +805,7 @@ impl TypedValidator {
                                 name: "_".to_string(),
                                 location: arg.location,
                             },
+                            guard: None,
                             then,
                         }
                     }))
@@ -810,6 +875,8 @@ pub enum Definition<T, Arg, Expr, PackageName> {
 
     Test(Function<T, Expr, ArgVia<Arg, Expr>>),
 
+    Benchmark(Function<T, Expr, ArgVia<Arg, Expr>>),
+
     Validator(Validator<T, Arg, Expr>),
 }
 
@@ -822,7 +889,8 @@ impl<A, B, C, D> Definition<A, B, C, D> {
             | Definition::DataType(DataType { location, .. })
             | Definition::ModuleConstant(ModuleConstant { location, .. })
             | Definition::Validator(Validator { location, .. })
-            | Definition::Test(Function { location, .. }) => *location,
+            | Definition::Test(Function { location, .. })
+            | Definition::Benchmark(Function { location, .. }) => *location,
         }
     }
 
@@ -834,7 +902,8 @@ impl<A, B, C, D> Definition<A, B, C, D> {
             | Definition::DataType(DataType { doc, .. })
             | Definition::ModuleConstant(ModuleConstant { doc, .. })
             | Definition::Validator(Validator { doc, .. })
-            | Definition::Test(Function { doc, .. }) => {
+            | Definition::Test(Function { doc, .. })
+            | Definition::Benchmark(Function { doc, .. }) => {
                 let _ = std::mem::replace(doc, Some(new_doc));
             }
         }
@@ -848,7 +917,8 @@ impl<A, B, C, D> Definition<A, B, C, D> {
             | Definition::DataType(DataType { doc, .. })
             | Definition::ModuleConstant(ModuleConstant { doc, .. })
             | Definition::Validator(Validator { doc, .. })
-            | Definition::Test(Function { doc, .. }) => doc.clone(),
+            | Definition::Test(Function { doc, .. })
+            | Definition::Benchmark(Function { doc, .. }) => doc.clone(),
         }
     }
 }
@@ -860,7 +930,7 @@ impl TypedDefinition {
         let located = match self {
             Definition::Validator(validator) => validator.find_node(byte_index),
             Definition::Fn(func) => func.find_node(byte_index),
-            Definition::Test(func) => func.find_node(byte_index),
+            Definition::Test(func) | Definition::Benchmark(func) => func.find_node(byte_index),
             _ => None,
         };
 
@@ -2183,6 +2253,8 @@ pub type TypedMultiPattern = MultiPattern<PatternConstructor, Rc<Type>>;
 pub struct UntypedClause {
     pub location: Span,
     pub patterns: Vec1<Pattern<(), ()>>,
+    /// An extra boolean condition on the bindings of the patterns, e.g. `Some(x) if x > 0 -> ...`
+    pub guard: Option<UntypedExpr>,
     pub then: UntypedExpr,
 }
 
@@ -2190,6 +2262,7 @@ pub struct UntypedClause {
 pub struct TypedClause {
     pub location: Span,
     pub pattern: Pattern<PatternConstructor, Rc<Type>>,
+    pub guard: Option<TypedExpr>,
     pub then: TypedExpr,
 }
 
@@ -2204,12 +2277,15 @@ impl TypedClause {
     pub fn find_node(&self, byte_index: usize, subject_type: &Rc<Type>) -> Option<Located<'_>> {
         self.pattern
             .find_node(byte_index, subject_type)
+            .or_else(|| {
+                self.guard
+                    .as_ref()
+                    .and_then(|guard| guard.find_node(byte_index))
+            })
             .or_else(|| self.then.find_node(byte_index))
     }
 }
 
-pub struct UntypedClauseGuard {}
-
 pub type TypedIfBranch = IfBranch<TypedExpr, (TypedPattern, Rc<Type>)>;
 pub type UntypedIfBranch = IfBranch<UntypedExpr, AssignmentPattern>;
 
@@ -2262,7 +2338,8 @@ pub enum Tracing {
     All(TraceLevel),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TraceLevel {
     Silent,  // No traces
     Compact, // Line numbers only
@@ -25,6 +25,7 @@ pub const PAIRS: &str = "Pairs";
 pub const PRNG: &str = "PRNG";
 pub const PRNG_CONSTRUCTORS: &[&str] = &["Seeded", "Replayed"];
 pub const REDEEMER_WRAPPER: &str = "RedeemerWrapper";
+pub const SAMPLER: &str = "Sampler";
 pub const STRING: &str = "String";
 pub const VOID: &str = "Void";
 pub const VOID_CONSTRUCTORS: &[&str] = &["Void"];
@@ -56,82 +57,53 @@ pub const VALIDATOR_ELSE: &str = "else";
 // ----------------------------------------------------------------------------
 // Types
 
+// Nullary built-in types are immutable, so each thread shares a single instance of each rather than
+// allocating a new one on every occurrence. Besides saving memory, this lets unification compare
+// them by pointer. Composite types and identifiers aren't interned.
+macro_rules! interned {
+    ($name:expr) => {{
+        thread_local! {
+            static TYPE: Rc<Type> = Rc::new(Type::App {
+                public: true,
+                contains_opaque: false,
+                name: $name.to_string(),
+                module: "".to_string(),
+                args: vec![],
+                alias: None,
+            });
+        }
+
+        TYPE.with(Rc::clone)
+    }};
+}
+
 impl Type {
     pub fn data() -> Rc<Type> {
-        Rc::new(Type::App {
-            public: true,
-            contains_opaque: false,
-            name: DATA.to_string(),
-            module: "".to_string(),
-            args: vec![],
-            alias: None,
-        })
+        interned!(DATA)
     }
 
     pub fn int() -> Rc<Type> {
-        Rc::new(Type::App {
-            public: true,
-            contains_opaque: false,
-            name: INT.to_string(),
-            module: "".to_string(),
-            args: vec![],
-            alias: None,
-        })
+        interned!(INT)
     }
 
     pub fn bool() -> Rc<Self> {
-        Rc::new(Type::App {
-            args: vec![],
-            public: true,
-            contains_opaque: false,
-            name: BOOL.to_string(),
-            module: "".to_string(),
-            alias: None,
-        })
+        interned!(BOOL)
     }
 
     pub fn byte_array() -> Rc<Type> {
-        Rc::new(Type::App {
-            args: vec![],
-            public: true,
-            contains_opaque: false,
-            name: BYTE_ARRAY.to_string(),
-            module: "".to_string(),
-            alias: None,
-        })
+        interned!(BYTE_ARRAY)
     }
 
     pub fn g1_element() -> Rc<Type> {
-        Rc::new(Type::App {
-            public: true,
-            contains_opaque: false,
-            module: "".to_string(),
-            name: G1_ELEMENT.to_string(),
-            args: vec![],
-            alias: None,
-        })
+        interned!(G1_ELEMENT)
     }
 
     pub fn g2_element() -> Rc<Type> {
-        Rc::new(Type::App {
-            public: true,
-            contains_opaque: false,
-            module: "".to_string(),
-            name: G2_ELEMENT.to_string(),
-            args: vec![],
-            alias: None,
-        })
+        interned!(G2_ELEMENT)
     }
 
     pub fn miller_loop_result() -> Rc<Type> {
-        Rc::new(Type::App {
-            public: true,
-            contains_opaque: false,
-            module: "".to_string(),
-            name: MILLER_LOOP_RESULT.to_string(),
-            args: vec![],
-            alias: None,
-        })
+        interned!(MILLER_LOOP_RESULT)
     }
 
     pub fn tuple(elems: Vec<Rc<Type>>) -> Rc<Type> {
@@ -147,36 +119,15 @@ impl Type {
     }
 
     pub fn script_purpose() -> Rc<Type> {
-        Rc::new(Type::App {
-            args: vec![],
-            public: true,
-            contains_opaque: false,
-            name: SCRIPT_PURPOSE.to_string(),
-            module: "".to_string(),
-            alias: None,
-        })
+        interned!(SCRIPT_PURPOSE)
     }
 
     pub fn script_context() -> Rc<Type> {
-        Rc::new(Type::App {
-            args: vec![],
-            public: true,
-            contains_opaque: false,
-            name: SCRIPT_CONTEXT.to_string(),
-            module: "".to_string(),
-            alias: None,
-        })
+        interned!(SCRIPT_CONTEXT)
     }
 
     pub fn prng() -> Rc<Type> {
-        Rc::new(Type::App {
-            args: vec![],
-            public: true,
-            contains_opaque: false,
-            name: PRNG.to_string(),
-            module: "".to_string(),
-            alias: None,
-        })
+        interned!(PRNG)
     }
 
     pub fn fuzzer(a: Rc<Type>) -> Rc<Type> {
@@ -220,6 +171,34 @@ impl Type {
         })
     }
 
+    pub fn sampler(a: Rc<Type>) -> Rc<Type> {
+        Rc::new(Type::Fn {
+            args: vec![Type::int()],
+            ret: Type::fuzzer(a),
+            alias: Some(
+                TypeAliasAnnotation {
+                    alias: SAMPLER.to_string(),
+                    parameters: vec!["a".to_string()],
+                    annotation: Annotation::Fn {
+                        location: Span::empty(),
+                        arguments: vec![Annotation::int(Span::empty())],
+                        ret: Annotation::Constructor {
+                            location: Span::empty(),
+                            module: None,
+                            name: FUZZER.to_string(),
+                            arguments: vec![Annotation::Var {
+                                location: Span::empty(),
+                                name: "a".to_string(),
+                            }],
+                        }
+                        .into(),
+                    },
+                }
+                .into(),
+            ),
+        })
+    }
+
     pub fn map(k: Rc<Type>, v: Rc<Type>) -> Rc<Type> {
         Rc::new(Type::App {
             public: true,
@@ -265,25 +244,11 @@ impl Type {
     }
 
     pub fn string() -> Rc<Type> {
-        Rc::new(Type::App {
-            args: vec![],
-            public: true,
-            contains_opaque: false,
-            name: STRING.to_string(),
-            module: "".to_string(),
-            alias: None,
-        })
+        interned!(STRING)
     }
 
     pub fn void() -> Rc<Type> {
-        Rc::new(Type::App {
-            args: vec![],
-            public: true,
-            contains_opaque: false,
-            name: VOID.to_string(),
-            module: "".to_string(),
-            alias: None,
-        })
+        interned!(VOID)
     }
 
     pub fn option(a: Rc<Type>) -> Rc<Type> {
@@ -298,25 +263,11 @@ impl Type {
     }
 
     pub fn never() -> Rc<Type> {
-        Rc::new(Type::App {
-            public: true,
-            contains_opaque: false,
-            name: NEVER.to_string(),
-            module: "".to_string(),
-            args: vec![],
-            alias: None,
-        })
+        interned!(NEVER)
     }
 
     pub fn ordering() -> Rc<Type> {
-        Rc::new(Type::App {
-            public: true,
-            contains_opaque: false,
-            name: ORDERING.to_string(),
-            module: "".to_string(),
-            args: vec![],
-            alias: None,
-        })
+        interned!(ORDERING)
     }
 
     pub fn function(args: Vec<Rc<Type>>, ret: Rc<Type>) -> Rc<Type> {
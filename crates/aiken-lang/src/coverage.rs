@@ -0,0 +1,229 @@
+//! Instrumentation of programs for code coverage.
+//!
+//! When asked to, the code generator wraps the body of every function and every branch of an
+//! `if` or `when` in a trace of a marker, identifying the region of the source it stems from.
+//! Markers follow the same convention as property-test labels (a leading NUL byte), so they're
+//! collected across all iterations of a property. Regions which never show up in traces are
+//! the ones left uncovered.
+
+use crate::{
+    ast::{Definition, Span, TypedModule},
+    expr::TypedExpr,
+};
+
+const MARKER_PREFIX: &str = "\0aiken::coverage ";
+
+/// A part of the source that execution may or may not go through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Location of the instrumented expression, which identifies the region in markers.
+    pub location: Span,
+    /// Part of the source attributed to the region; that is, the expression along with the
+    /// pattern leading to it, for clauses of a `when`.
+    pub extent: Span,
+    pub kind: RegionKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionKind {
+    /// The body of a function; named after it, unless anonymous.
+    Function(Option<String>),
+    /// One of the branches of the `if` or `when` located at 'block', in order of appearance.
+    Branch { block: Span, index: usize },
+}
+
+/// The trace emitted when entering the region at 'location' in 'module'.
+pub fn marker(module: &str, location: Span) -> String {
+    format!(
+        "{MARKER_PREFIX}{module} {} {}",
+        location.start, location.end
+    )
+}
+
+/// The module and location of the region a trace marks, if it's a coverage marker. Labels
+/// (i.e. without their leading NUL byte) are also recognised.
+pub fn parse_marker(trace: &str) -> Option<(&str, Span)> {
+    let marker = trace
+        .strip_prefix(MARKER_PREFIX)
+        .or_else(|| trace.strip_prefix(&MARKER_PREFIX[1..]))?;
+
+    let mut parts = marker.split(' ');
+
+    let module = parts.next()?;
+    let start = parts.next()?.parse().ok()?;
+    let end = parts.next()?.parse().ok()?;
+
+    Some((module, Span { start, end }))
+}
+
+/// All regions of the functions and validators of a module, in order of appearance. Tests and
+/// benchmarks are left out, as they're what exercises the rest.
+pub fn regions(module: &TypedModule) -> Vec<Region> {
+    let mut regions = Vec::new();
+
+    for def in module.definitions() {
+        match def {
+            Definition::Fn(func) => {
+                function(&mut regions, Some(func.name.clone()), &func.body);
+            }
+            Definition::Validator(validator) => {
+                for handler in validator.handlers.iter() {
+                    let name = format!("{}.{}", validator.name, handler.name);
+                    function(&mut regions, Some(name), &handler.body);
+                }
+
+                // The default fallback is generated, and located at the validator itself.
+                if validator.fallback.location != validator.location {
+                    let name = format!("{}.{}", validator.name, validator.fallback.name);
+                    function(&mut regions, Some(name), &validator.fallback.body);
+                }
+            }
+            Definition::TypeAlias(..)
+            | Definition::DataType(..)
+            | Definition::Use(..)
+            | Definition::ModuleConstant(..)
+            | Definition::Test(..)
+            | Definition::Benchmark(..) => {}
+        }
+    }
+
+    regions.sort_by_key(|region| region.location.start);
+
+    regions
+}
+
+fn function(regions: &mut Vec<Region>, name: Option<String>, body: &TypedExpr) {
+    regions.push(Region {
+        location: body.location(),
+        extent: body.location(),
+        kind: RegionKind::Function(name),
+    });
+
+    walk(regions, body);
+}
+
+fn branch(regions: &mut Vec<Region>, block: Span, index: usize, body: &TypedExpr, extent: Span) {
+    regions.push(Region {
+        location: body.location(),
+        extent,
+        kind: RegionKind::Branch { block, index },
+    });
+
+    walk(regions, body);
+}
+
+fn walk(regions: &mut Vec<Region>, expr: &TypedExpr) {
+    match expr {
+        TypedExpr::UInt { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::ByteArray { .. }
+        | TypedExpr::CurvePoint { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::ModuleSelect { .. }
+        | TypedExpr::ErrorTerm { .. } => {}
+
+        TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+            expressions.iter().for_each(|expr| walk(regions, expr));
+        }
+
+        // Captures are sugar for a call, and don't make for a region of their own.
+        TypedExpr::Fn {
+            body, is_capture, ..
+        } => {
+            if *is_capture {
+                walk(regions, body);
+            } else {
+                function(regions, None, body);
+            }
+        }
+
+        TypedExpr::List { elements, tail, .. } => {
+            elements.iter().for_each(|expr| walk(regions, expr));
+            if let Some(tail) = tail {
+                walk(regions, tail);
+            }
+        }
+
+        TypedExpr::Call { fun, args, .. } => {
+            walk(regions, fun);
+            args.iter().for_each(|arg| walk(regions, &arg.value));
+        }
+
+        TypedExpr::BinOp { left, right, .. } => {
+            walk(regions, left);
+            walk(regions, right);
+        }
+
+        TypedExpr::Assignment { value, .. } => walk(regions, value),
+
+        TypedExpr::Trace { then, text, .. } => {
+            walk(regions, text);
+            walk(regions, then);
+        }
+
+        TypedExpr::When {
+            location,
+            subject,
+            clauses,
+            ..
+        } => {
+            walk(regions, subject);
+            for (index, clause) in clauses.iter().enumerate() {
+                if let Some(guard) = &clause.guard {
+                    walk(regions, guard);
+                }
+                branch(regions, *location, index, &clause.then, clause.location());
+            }
+        }
+
+        TypedExpr::If {
+            location,
+            branches,
+            final_else,
+            ..
+        } => {
+            for (index, if_branch) in branches.iter().enumerate() {
+                walk(regions, &if_branch.condition);
+                let body = &if_branch.body;
+                branch(regions, *location, index, body, body.location());
+            }
+            let body = final_else.as_ref();
+            branch(regions, *location, branches.len(), body, body.location());
+        }
+
+        TypedExpr::RecordAccess { record, .. } => walk(regions, record),
+
+        TypedExpr::Tuple { elems, .. } => elems.iter().for_each(|expr| walk(regions, expr)),
+
+        TypedExpr::Pair { fst, snd, .. } => {
+            walk(regions, fst);
+            walk(regions, snd);
+        }
+
+        TypedExpr::TupleIndex { tuple, .. } => walk(regions, tuple),
+
+        TypedExpr::RecordUpdate { spread, args, .. } => {
+            walk(regions, spread);
+            args.iter().for_each(|arg| walk(regions, &arg.value));
+        }
+
+        TypedExpr::UnOp { value, .. } => walk(regions, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_roundtrip() {
+        let location = Span { start: 14, end: 42 };
+
+        let marker = marker("foo/bar", location);
+
+        assert_eq!(parse_marker(&marker), Some(("foo/bar", location)));
+        assert_eq!(parse_marker(&marker[1..]), Some(("foo/bar", location)));
+        assert_eq!(parse_marker("aiken::coverage"), None);
+        assert_eq!(parse_marker("foo/bar 14 42"), None);
+    }
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// A stable identifier for a diagnostic of the parser or the type-checker, such as 'AK0148'.
+/// Codes are never re-assigned, even when the diagnostic they stand for goes away.
+///
+/// - AK0001-AK0099: syntax errors;
+/// - AK0100-AK0499: type errors;
+/// - AK0500-AK0999: warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ErrorCode(pub(crate) u16);
+
+const EXPLANATIONS: &[(u16, &str)] = &[
+    (1, include_str!("explanations/AK0001.md")),
+    (2, include_str!("explanations/AK0002.md")),
+    (3, include_str!("explanations/AK0003.md")),
+    (4, include_str!("explanations/AK0004.md")),
+    (5, include_str!("explanations/AK0005.md")),
+    (6, include_str!("explanations/AK0006.md")),
+    (7, include_str!("explanations/AK0007.md")),
+    (8, include_str!("explanations/AK0008.md")),
+    (9, include_str!("explanations/AK0009.md")),
+    (10, include_str!("explanations/AK0010.md")),
+    (11, include_str!("explanations/AK0011.md")),
+    (12, include_str!("explanations/AK0012.md")),
+    (13, include_str!("explanations/AK0013.md")),
+    (14, include_str!("explanations/AK0014.md")),
+    (100, include_str!("explanations/AK0100.md")),
+    (101, include_str!("explanations/AK0101.md")),
+    (102, include_str!("explanations/AK0102.md")),
+    (103, include_str!("explanations/AK0103.md")),
+    (104, include_str!("explanations/AK0104.md")),
+    (105, include_str!("explanations/AK0105.md")),
+    (106, include_str!("explanations/AK0106.md")),
+    (107, include_str!("explanations/AK0107.md")),
+    (108, include_str!("explanations/AK0108.md")),
+    (109, include_str!("explanations/AK0109.md")),
+    (110, include_str!("explanations/AK0110.md")),
+    (111, include_str!("explanations/AK0111.md")),
+    (112, include_str!("explanations/AK0112.md")),
+    (113, include_str!("explanations/AK0113.md")),
+    (114, include_str!("explanations/AK0114.md")),
+    (115, include_str!("explanations/AK0115.md")),
+    (116, include_str!("explanations/AK0116.md")),
+    (117, include_str!("explanations/AK0117.md")),
+    (118, include_str!("explanations/AK0118.md")),
+    (119, include_str!("explanations/AK0119.md")),
+    (120, include_str!("explanations/AK0120.md")),
+    (121, include_str!("explanations/AK0121.md")),
+    (122, include_str!("explanations/AK0122.md")),
+    (123, include_str!("explanations/AK0123.md")),
+    (124, include_str!("explanations/AK0124.md")),
+    (125, include_str!("explanations/AK0125.md")),
+    (126, include_str!("explanations/AK0126.md")),
+    (127, include_str!("explanations/AK0127.md")),
+    (128, include_str!("explanations/AK0128.md")),
+    (129, include_str!("explanations/AK0129.md")),
+    (130, include_str!("explanations/AK0130.md")),
+    (131, include_str!("explanations/AK0131.md")),
+    (132, include_str!("explanations/AK0132.md")),
+    (133, include_str!("explanations/AK0133.md")),
+    (134, include_str!("explanations/AK0134.md")),
+    (135, include_str!("explanations/AK0135.md")),
+    (136, include_str!("explanations/AK0136.md")),
+    (137, include_str!("explanations/AK0137.md")),
+    (138, include_str!("explanations/AK0138.md")),
+    (139, include_str!("explanations/AK0139.md")),
+    (140, include_str!("explanations/AK0140.md")),
+    (141, include_str!("explanations/AK0141.md")),
+    (142, include_str!("explanations/AK0142.md")),
+    (143, include_str!("explanations/AK0143.md")),
+    (144, include_str!("explanations/AK0144.md")),
+    (145, include_str!("explanations/AK0145.md")),
+    (146, include_str!("explanations/AK0146.md")),
+    (147, include_str!("explanations/AK0147.md")),
+    (148, include_str!("explanations/AK0148.md")),
+    (149, include_str!("explanations/AK0149.md")),
+    (150, include_str!("explanations/AK0150.md")),
+    (151, include_str!("explanations/AK0151.md")),
+    (152, include_str!("explanations/AK0152.md")),
+    (153, include_str!("explanations/AK0153.md")),
+    (154, include_str!("explanations/AK0154.md")),
+    (155, include_str!("explanations/AK0155.md")),
+    (156, include_str!("explanations/AK0156.md")),
+    (157, include_str!("explanations/AK0157.md")),
+    (158, include_str!("explanations/AK0158.md")),
+    (159, include_str!("explanations/AK0159.md")),
+    (160, include_str!("explanations/AK0160.md")),
+    (161, include_str!("explanations/AK0161.md")),
+    (500, include_str!("explanations/AK0500.md")),
+    (501, include_str!("explanations/AK0501.md")),
+    (502, include_str!("explanations/AK0502.md")),
+    (503, include_str!("explanations/AK0503.md")),
+    (504, include_str!("explanations/AK0504.md")),
+    (505, include_str!("explanations/AK0505.md")),
+    (506, include_str!("explanations/AK0506.md")),
+    (507, include_str!("explanations/AK0507.md")),
+    (508, include_str!("explanations/AK0508.md")),
+    (509, include_str!("explanations/AK0509.md")),
+    (510, include_str!("explanations/AK0510.md")),
+    (511, include_str!("explanations/AK0511.md")),
+    (512, include_str!("explanations/AK0512.md")),
+    (513, include_str!("explanations/AK0513.md")),
+    (514, include_str!("explanations/AK0514.md")),
+    (515, include_str!("explanations/AK0515.md")),
+    (516, include_str!("explanations/AK0516.md")),
+    (517, include_str!("explanations/AK0517.md")),
+];
+
+impl ErrorCode {
+    /// All known codes, in order.
+    pub fn all() -> impl Iterator<Item = ErrorCode> {
+        EXPLANATIONS.iter().map(|(code, _)| ErrorCode(*code))
+    }
+
+    /// The extended explanation of the diagnostic, with examples and common fixes, as markdown.
+    pub fn explanation(&self) -> &'static str {
+        EXPLANATIONS
+            .iter()
+            .find(|(code, _)| *code == self.0)
+            .map(|(_, explanation)| *explanation)
+            .expect("every code has an explanation")
+    }
+
+    /// The first line of the explanation, summing up the diagnostic.
+    pub fn summary(&self) -> &'static str {
+        self.explanation().lines().next().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AK{:04}", self.0)
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = String;
+
+    /// Codes are case-insensitive, and the 'AK' prefix as well as leading zeros may be omitted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .strip_prefix("AK")
+            .or_else(|| s.strip_prefix("ak"))
+            .unwrap_or(s);
+
+        digits
+            .parse::<u16>()
+            .ok()
+            .filter(|code| EXPLANATIONS.iter().any(|(known, _)| known == code))
+            .map(ErrorCode)
+            .ok_or_else(|| format!("unknown error code '{s}'"))
+    }
+}
+
+impl TryFrom<String> for ErrorCode {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<ErrorCode> for String {
+    fn from(code: ErrorCode) -> Self {
+        code.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explanations_are_sorted_and_unique() {
+        assert!(EXPLANATIONS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn explanations_have_a_summary_and_an_example() {
+        for code in ErrorCode::all() {
+            assert!(code.summary().ends_with('.'), "{code}");
+            assert!(code.explanation().contains("```aiken"), "{code}");
+        }
+    }
+
+    #[test]
+    fn parse_codes() {
+        assert_eq!("AK0148".parse(), Ok(ErrorCode(148)));
+        assert_eq!("ak0148".parse(), Ok(ErrorCode(148)));
+        assert_eq!("148".parse(), Ok(ErrorCode(148)));
+        assert_eq!(ErrorCode(1).to_string(), "AK0001");
+        assert!("AK9999".parse::<ErrorCode>().is_err());
+        assert!("E0148".parse::<ErrorCode>().is_err());
+    }
+}
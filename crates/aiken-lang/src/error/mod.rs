@@ -1,3 +1,12 @@
+mod codes;
+
+pub use codes::ErrorCode;
+
 pub trait ExtraData {
     fn extra_data(&self) -> Option<String>;
 }
+
+/// Diagnostics identified by a stable code, with an extended explanation.
+pub trait Coded {
+    fn error_code(&self) -> Option<ErrorCode>;
+}
@@ -16,7 +16,7 @@ pub(crate) use crate::{
 };
 use indexmap::IndexMap;
 use pallas_primitives::alonzo::{Constr, PlutusData};
-use std::{fmt::Debug, rc::Rc};
+use std::{collections::BTreeSet, fmt::Debug, rc::Rc};
 use uplc::{
     ast::Data,
     machine::{runtime::convert_tag_to_constr, value::from_pallas_bigint},
@@ -611,6 +611,13 @@ pub enum UntypedExpr {
         value: String,
     },
 
+    /// A string made of text and interpolated expressions, e.g. `@"found {n} inputs"`. Text
+    /// segments are kept as 'String' expressions.
+    InterpolatedString {
+        location: Span,
+        parts: Vec<Self>,
+    },
+
     Sequence {
         location: Span,
         expressions: Vec<Self>,
@@ -621,6 +628,13 @@ pub enum UntypedExpr {
         name: String,
     },
 
+    /// A placeholder for an expression yet to be written, e.g. `?todo`. Holes type-check as
+    /// anything, and are then reported along with the type they're expected to have.
+    Hole {
+        location: Span,
+        name: String,
+    },
+
     Fn {
         location: Span,
         fn_style: FnStyle,
@@ -1446,6 +1460,7 @@ impl UntypedExpr {
             Self::TraceIfFalse { location, .. }
             | Self::Fn { location, .. }
             | Self::Var { location, .. }
+            | Self::Hole { location, .. }
             | Self::UInt { location, .. }
             | Self::ErrorTerm { location, .. }
             | Self::When { location, .. }
@@ -1456,6 +1471,7 @@ impl UntypedExpr {
             | Self::Tuple { location, .. }
             | Self::Pair { location, .. }
             | Self::String { location, .. }
+            | Self::InterpolatedString { location, .. }
             | Self::Assignment { location, .. }
             | Self::TupleIndex { location, .. }
             | Self::FieldAccess { location, .. }
@@ -1488,6 +1504,167 @@ impl UntypedExpr {
         }
     }
 
+    /// Remove statements starting at any of the given byte indexes, at any depth. Statements are
+    /// expressions of a sequence (except the last one, which is the value of the sequence) and
+    /// traces, whose removal leaves their continuation in place. The start of every statement
+    /// visited is collected in `statements`.
+    pub fn strip_statements(&mut self, remove: &BTreeSet<usize>, statements: &mut BTreeSet<usize>) {
+        match self {
+            Self::Sequence { expressions, .. } => {
+                let last = expressions.len().saturating_sub(1);
+
+                let mut index = 0;
+                expressions.retain(|expr| {
+                    let start = expr.start_byte_index();
+                    let is_statement = index < last
+                        && !matches!(
+                            expr,
+                            Self::Trace {
+                                kind: TraceKind::Trace,
+                                ..
+                            }
+                        );
+                    index += 1;
+                    if is_statement {
+                        statements.insert(start);
+                        !remove.contains(&start)
+                    } else {
+                        true
+                    }
+                });
+
+                for expr in expressions.iter_mut() {
+                    expr.strip_statements(remove, statements);
+                }
+            }
+
+            Self::Trace {
+                kind,
+                location,
+                then,
+                label,
+                arguments,
+            } => {
+                if kind == &TraceKind::Trace {
+                    statements.insert(location.start);
+                }
+
+                if kind == &TraceKind::Trace && remove.contains(&location.start) {
+                    *self = std::mem::replace(
+                        then.as_mut(),
+                        Self::ErrorTerm {
+                            location: *location,
+                        },
+                    );
+                    return self.strip_statements(remove, statements);
+                }
+
+                label.strip_statements(remove, statements);
+                for arg in arguments.iter_mut() {
+                    arg.strip_statements(remove, statements);
+                }
+                then.strip_statements(remove, statements);
+            }
+
+            Self::Fn { body, .. } => body.strip_statements(remove, statements),
+
+            Self::List { elements, tail, .. } => {
+                for element in elements.iter_mut() {
+                    element.strip_statements(remove, statements);
+                }
+                if let Some(tail) = tail {
+                    tail.strip_statements(remove, statements);
+                }
+            }
+
+            Self::Call { arguments, fun, .. } => {
+                fun.strip_statements(remove, statements);
+                for arg in arguments.iter_mut() {
+                    arg.value.strip_statements(remove, statements);
+                }
+            }
+
+            Self::BinOp { left, right, .. } => {
+                left.strip_statements(remove, statements);
+                right.strip_statements(remove, statements);
+            }
+
+            Self::PipeLine { expressions, .. } => {
+                for expr in expressions.iter_mut() {
+                    expr.strip_statements(remove, statements);
+                }
+            }
+
+            Self::Tuple {
+                elems: expressions, ..
+            }
+            | Self::InterpolatedString {
+                parts: expressions, ..
+            }
+            | Self::LogicalOpChain { expressions, .. } => {
+                for expr in expressions.iter_mut() {
+                    expr.strip_statements(remove, statements);
+                }
+            }
+
+            Self::Assignment { value, .. }
+            | Self::TraceIfFalse { value, .. }
+            | Self::UnOp { value, .. }
+            | Self::FieldAccess {
+                container: value, ..
+            }
+            | Self::TupleIndex { tuple: value, .. } => value.strip_statements(remove, statements),
+
+            Self::When {
+                subject, clauses, ..
+            } => {
+                subject.strip_statements(remove, statements);
+                for clause in clauses.iter_mut() {
+                    if let Some(guard) = clause.guard.as_mut() {
+                        guard.strip_statements(remove, statements);
+                    }
+                    clause.then.strip_statements(remove, statements);
+                }
+            }
+
+            Self::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                for branch in branches.iter_mut() {
+                    branch.condition.strip_statements(remove, statements);
+                    branch.body.strip_statements(remove, statements);
+                }
+                final_else.strip_statements(remove, statements);
+            }
+
+            Self::Pair { fst, snd, .. } => {
+                fst.strip_statements(remove, statements);
+                snd.strip_statements(remove, statements);
+            }
+
+            Self::RecordUpdate {
+                constructor,
+                arguments,
+                ..
+            } => {
+                constructor.strip_statements(remove, statements);
+                for arg in arguments.iter_mut() {
+                    arg.value.strip_statements(remove, statements);
+                }
+            }
+
+            Self::UInt { .. }
+            | Self::String { .. }
+            | Self::Var { .. }
+            | Self::Hole { .. }
+            | Self::ByteArray { .. }
+            | Self::CurvePoint { .. }
+            | Self::ErrorTerm { .. } => (),
+        }
+    }
+
     pub fn binop_precedence(&self) -> u8 {
         match self {
             Self::BinOp { name, .. } => name.precedence(),
@@ -1515,6 +1692,21 @@ impl UntypedExpr {
         }
     }
 
+    /// Whether this is a shorthand accessing a field of its argument, e.g. `_.foo.bar`.
+    pub fn is_field_capture(&self) -> bool {
+        match self {
+            Self::Fn {
+                fn_style: FnStyle::Capture,
+                body,
+                ..
+            } => matches!(
+                body.as_ref(),
+                Self::FieldAccess { .. } | Self::TupleIndex { .. }
+            ),
+            _ => false,
+        }
+    }
+
     pub fn lambda(
         names: Vec<(ArgName, Span, Option<Annotation>)>,
         expressions: Vec<UntypedExpr>,
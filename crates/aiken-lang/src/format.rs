@@ -2,7 +2,7 @@ use crate::{
     ast::{
         Annotation, ArgBy, ArgName, ArgVia, AssignmentKind, AssignmentPattern, BinOp,
         ByteArrayFormatPreference, CallArg, CurveType, DataType, Definition, Function,
-        LogicalOpChainKind, ModuleConstant, OnTestFailure, Pattern, RecordConstructor,
+        LogicalOpChainKind, ModuleConstant, ModuleKind, OnTestFailure, Pattern, RecordConstructor,
         RecordConstructorArg, RecordUpdateSpread, Span, TraceKind, TypeAlias, TypedArg,
         TypedValidator, UnOp, UnqualifiedImport, UntypedArg, UntypedArgVia, UntypedAssignmentKind,
         UntypedClause, UntypedDefinition, UntypedFunction, UntypedIfBranch, UntypedModule,
@@ -11,8 +11,9 @@ use crate::{
     docvec,
     expr::{FnStyle, TypedExpr, UntypedExpr, DEFAULT_ERROR_STR, DEFAULT_TODO_STR},
     parser::{
-        extra::{Comment, ModuleExtra},
-        token::Base,
+        extra::{Cfg, Comment, ModuleExtra},
+        lexer::{self, LexInfo},
+        token::{Base, Token},
     },
     pretty::{
         break_, concat, flex_break, join, line, lines, nil, prebreak, Document, Documentable,
@@ -28,7 +29,81 @@ use vec1::Vec1;
 pub const INDENT: isize = 2;
 pub const MAX_COLUMNS: isize = 80;
 
+/// Settings of the formatter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Width past which lines get broken.
+    pub max_width: isize,
+    /// Order of the groups imports are laid out in, each separated by an empty line. Imports from
+    /// groups that aren't listed come last, together; so an empty list means a single block.
+    pub import_groups: Vec<ImportGroup>,
+    /// First segments of the project's own modules (e.g. 'foo' for 'foo/bar'), which tell local
+    /// imports apart from those of external packages.
+    pub local_modules: Vec<String>,
+    /// Whether trailing comments of record fields and constants should start on the same column,
+    /// within each group of consecutive lines.
+    pub align_comments: bool,
+    /// Whether a trailing comma follows the last element of calls and lists broken over lines.
+    pub trailing_commas: bool,
+    /// How the arguments of calls too long for a single line are laid out.
+    pub call_wrapping: CallWrapping,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            max_width: MAX_COLUMNS,
+            import_groups: vec![
+                ImportGroup::Stdlib,
+                ImportGroup::External,
+                ImportGroup::Local,
+            ],
+            local_modules: vec![],
+            align_comments: false,
+            trailing_commas: true,
+            call_wrapping: CallWrapping::OnePerLine,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CallWrapping {
+    /// Each argument goes on its own line.
+    OnePerLine,
+    /// Arguments fill lines up to the maximum width.
+    Packed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportGroup {
+    Stdlib,
+    External,
+    Local,
+}
+
+impl ImportGroup {
+    pub fn of(module: &[String], local_modules: &[String]) -> Self {
+        match module.first().map(String::as_str) {
+            Some(root) if local_modules.iter().any(|local| local == root) => ImportGroup::Local,
+            Some("aiken" | "cardano") => ImportGroup::Stdlib,
+            _ => ImportGroup::External,
+        }
+    }
+}
+
 pub fn pretty(writer: &mut String, module: UntypedModule, extra: ModuleExtra, src: &str) {
+    pretty_with_options(writer, module, extra, src, &FormatOptions::default())
+}
+
+pub fn pretty_with_options(
+    writer: &mut String,
+    module: UntypedModule,
+    extra: ModuleExtra,
+    src: &str,
+    options: &FormatOptions,
+) {
     let intermediate = Intermediate {
         comments: extra
             .comments
@@ -46,11 +121,123 @@ pub fn pretty(writer: &mut String, module: UntypedModule, extra: ModuleExtra, sr
             .iter()
             .map(|span| Comment::from((span, src)))
             .collect(),
+        cfg: &extra.cfg,
+        newlines: src
+            .char_indices()
+            .filter_map(|(i, c)| (c == '\n').then_some(i))
+            .collect(),
     };
 
-    Formatter::with_comments(&intermediate)
+    let mut output = String::new();
+
+    Formatter::with_comments(&intermediate, options.clone())
         .module(&module)
-        .pretty_print(MAX_COLUMNS, writer);
+        .pretty_print(options.max_width, &mut output);
+
+    writer.push_str(&format_doc_snippets(&output, options));
+}
+
+// Format fenced Aiken code blocks found in doc and module comments of an already formatted
+// module. Blocks that fail to parse, as well as blocks in other languages, are left untouched.
+fn format_doc_snippets(src: &str, options: &FormatOptions) -> String {
+    let lines = src.lines().collect::<Vec<_>>();
+
+    let mut output = String::with_capacity(src.len());
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+
+        output.push_str(line);
+        output.push('\n');
+
+        let Some((prefix, "```aiken")) = split_doc_comment(line) else {
+            continue;
+        };
+
+        let Some(len) = lines[i..].iter().position(|line| {
+            split_doc_comment(line)
+                .map_or(true, |(other, content)| other != prefix || content == "```")
+        }) else {
+            continue;
+        };
+
+        if split_doc_comment(lines[i + len]) != Some((prefix, "```")) {
+            continue;
+        }
+
+        let code = lines[i..i + len]
+            .iter()
+            .map(|line| {
+                let content = &line[prefix.len()..];
+                content.strip_prefix(' ').unwrap_or(content)
+            })
+            .join("\n");
+
+        let snippet_options = FormatOptions {
+            max_width: options.max_width - prefix.len() as isize - 1,
+            ..options.clone()
+        };
+
+        if let Some(formatted) = format_snippet(&code, &snippet_options) {
+            for snippet_line in formatted.lines() {
+                output.push_str(prefix);
+                if !snippet_line.is_empty() {
+                    output.push(' ');
+                    output.push_str(snippet_line);
+                }
+                output.push('\n');
+            }
+
+            i += len;
+        }
+    }
+
+    output
+}
+
+// Split a doc or module comment line into its prefix (indentation and slashes) and its trimmed
+// content, or None for other lines.
+fn split_doc_comment(line: &str) -> Option<(&str, &str)> {
+    let indent = line.len() - line.trim_start().len();
+    let slashes = line[indent..].chars().take_while(|c| *c == '/').count();
+
+    if !(3..=4).contains(&slashes) {
+        return None;
+    }
+
+    let (prefix, content) = line.split_at(indent + slashes);
+
+    Some((prefix, content.trim()))
+}
+
+// Format a snippet as a module or, failing that, as the body of a function; so that snippets made
+// of expressions only are formatted too.
+fn format_snippet(code: &str, options: &FormatOptions) -> Option<String> {
+    if code.trim().is_empty() {
+        return None;
+    }
+
+    let format = |src: &str| {
+        let (module, extra) = crate::parser::module(src, ModuleKind::Lib).ok()?;
+        let mut output = String::new();
+        pretty_with_options(&mut output, module, extra, src, options);
+        Some(output)
+    };
+
+    format(code).or_else(|| {
+        let output = format(&format!("fn snippet() {{\n{code}\n}}\n"))?;
+
+        let body = output
+            .strip_prefix("fn snippet() {\n")?
+            .strip_suffix("}\n")?
+            .lines()
+            .map(|line| line.strip_prefix("  ").unwrap_or(line))
+            .join("\n");
+
+        Some(format!("{body}\n"))
+    })
 }
 
 #[derive(Debug)]
@@ -59,6 +246,8 @@ struct Intermediate<'a> {
     doc_comments: Vec<Comment<'a>>,
     module_comments: Vec<Comment<'a>>,
     empty_lines: &'a [usize],
+    cfg: &'a [Cfg],
+    newlines: Vec<usize>,
 }
 
 /// Hayleigh's bane
@@ -68,6 +257,9 @@ pub struct Formatter<'a> {
     doc_comments: &'a [Comment<'a>],
     module_comments: &'a [Comment<'a>],
     empty_lines: &'a [usize],
+    cfg: &'a [Cfg],
+    newlines: &'a [usize],
+    options: FormatOptions,
 }
 
 impl<'comments> Formatter<'comments> {
@@ -75,12 +267,15 @@ impl<'comments> Formatter<'comments> {
         Default::default()
     }
 
-    fn with_comments(extra: &'comments Intermediate<'comments>) -> Self {
+    fn with_comments(extra: &'comments Intermediate<'comments>, options: FormatOptions) -> Self {
         Self {
             comments: &extra.comments,
             doc_comments: &extra.doc_comments,
             module_comments: &extra.module_comments,
             empty_lines: extra.empty_lines,
+            cfg: extra.cfg,
+            newlines: &extra.newlines,
+            options,
         }
     }
 
@@ -110,6 +305,105 @@ impl<'comments> Formatter<'comments> {
         popped
     }
 
+    // Pop the comment that follows a byte-index on the same line, if any.
+    fn pop_trailing_comment(&mut self, end: usize) -> Option<&'comments str> {
+        let comment = self.comments.first()?;
+
+        let next_newline = self
+            .newlines
+            .get(self.newlines.partition_point(|newline| *newline < end));
+
+        if comment.start < end || next_newline.is_some_and(|newline| *newline < comment.start) {
+            return None;
+        }
+
+        self.comments = &self.comments[1..];
+
+        Some(comment.content)
+    }
+
+    // Lay out items one per line, each followed by its trailing comment (if any). Items flagged as
+    // preceded by an empty line start a new group, which is also the scope of comments alignment.
+    fn trailing_commented<'a>(
+        &self,
+        items: Vec<(Document<'a>, Option<&'comments str>, bool)>,
+    ) -> Document<'a> {
+        let mut paddings = vec![0; items.len()];
+
+        if self.options.align_comments {
+            let widths = items
+                .iter()
+                .map(|(doc, comment, _)| {
+                    comment.map(|_| {
+                        let mut rendered = String::new();
+                        doc.pretty_print(self.options.max_width, &mut rendered);
+                        rendered.lines().last().unwrap_or_default().chars().count()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let mut start = 0;
+            for end in 1..=items.len() {
+                if end < items.len() && !items[end].2 {
+                    continue;
+                }
+
+                let column = widths[start..end].iter().flatten().max().unwrap_or(&0);
+
+                for (padding, width) in paddings[start..end].iter_mut().zip(&widths[start..end]) {
+                    *padding = width.map_or(0, |width| column - width);
+                }
+
+                start = end;
+            }
+        }
+
+        concat(items.into_iter().zip(paddings).enumerate().map(
+            |(i, ((doc, comment, empty_line_before), padding))| {
+                let separator = match i {
+                    0 => nil(),
+                    _ if empty_line_before => lines(2),
+                    _ => line(),
+                };
+
+                let comment = match comment {
+                    Some(comment) => Document::String(" ".repeat(padding + 1))
+                        .append("//")
+                        .append(Document::String(comment.to_string())),
+                    None => nil(),
+                };
+
+                separator.append(doc).append(comment)
+            },
+        ))
+    }
+
+    // Pop attributes that apply to a byte-index in the source, or before.
+    fn pop_cfg<'a>(&mut self, limit: usize) -> Document<'a> {
+        let end = self
+            .cfg
+            .iter()
+            .position(|attribute| attribute.target > limit)
+            .unwrap_or(self.cfg.len());
+
+        let (popped, rest) = self.cfg.split_at(end);
+
+        self.cfg = rest;
+
+        if popped.is_empty() {
+            return nil();
+        }
+
+        join(
+            popped.iter().map(|attribute| {
+                Document::String(format!("@cfg(feature = \"{}\")", attribute.feature))
+            }),
+            line(),
+        )
+        .append(line())
+        .force_break()
+    }
+
     // Remove between 0 and `limit` empty lines following the current position,
     // returning true if any empty lines were removed.
     fn pop_empty_lines(&mut self, limit: usize) -> bool {
@@ -135,6 +429,8 @@ impl<'comments> Formatter<'comments> {
         let mut has_declarations = false;
         let mut imports = Vec::new();
         let mut declarations = Vec::with_capacity(definitions.len());
+        let mut constants = Vec::new();
+        let mut previous_constant_end = None;
 
         for def in definitions {
             let start = def.location().start;
@@ -145,14 +441,48 @@ impl<'comments> Formatter<'comments> {
 
                     let comments = self.pop_comments(start);
 
-                    let def = self.definition(def);
+                    let def = self.pop_cfg(start).append(self.definition(def));
 
                     imports.push((import, commented(def, comments)))
                 }
 
+                Definition::ModuleConstant(ModuleConstant { location, .. }) => {
+                    has_declarations = true;
+
+                    // Constants that aren't separated by an empty line form a block, kept tight.
+                    let is_tight = match previous_constant_end {
+                        Some(end) => {
+                            // Skip empty lines left within the previous constant, if any.
+                            self.empty_lines = &self.empty_lines
+                                [self.empty_lines.partition_point(|position| *position < end)..];
+                            let limit = self.comments.first().map_or(start, |c| c.start.min(start));
+                            !self.pop_empty_lines(limit)
+                        }
+                        None => false,
+                    };
+
+                    if !is_tight && !constants.is_empty() {
+                        declarations.push(self.trailing_commented(std::mem::take(&mut constants)));
+                    }
+
+                    let comments = self.pop_comments(start);
+
+                    let declaration = commented(self.documented_definition(def), comments);
+
+                    constants.push((declaration, self.pop_trailing_comment(location.end), false));
+
+                    previous_constant_end = Some(location.end);
+                }
+
                 _other => {
                     has_declarations = true;
 
+                    if !constants.is_empty() {
+                        declarations.push(self.trailing_commented(std::mem::take(&mut constants)));
+                    }
+
+                    previous_constant_end = None;
+
                     let comments = self.pop_comments(start);
 
                     let declaration = self.documented_definition(def);
@@ -162,14 +492,33 @@ impl<'comments> Formatter<'comments> {
             }
         }
 
+        if !constants.is_empty() {
+            declarations.push(self.trailing_commented(constants));
+        }
+
+        let group_of = |import: &Use<()>| {
+            let group = ImportGroup::of(&import.module, &self.options.local_modules);
+            self.options
+                .import_groups
+                .iter()
+                .position(|other| other == &group)
+                .unwrap_or(self.options.import_groups.len())
+        };
+
         let imports = join(
             imports
                 .into_iter()
                 .sorted_by(|(import_a, _), (import_b, _)| {
-                    Ord::cmp(&import_a.module, &import_b.module)
+                    Ord::cmp(
+                        &(group_of(import_a), &import_a.module),
+                        &(group_of(import_b), &import_b.module),
+                    )
                 })
-                .map(|(_, doc)| doc),
-            line(),
+                .group_by(|(import, _)| group_of(import))
+                .into_iter()
+                .map(|(_, group)| join(group.map(|(_, doc)| doc), line()))
+                .collect::<Vec<_>>(),
+            lines(2),
         );
 
         let declarations = join(declarations, lines(2));
@@ -260,6 +609,14 @@ impl<'comments> Formatter<'comments> {
                 ..
             }) => self.definition_test(name, args, body, *end_position, on_test_failure),
 
+            Definition::Benchmark(Function {
+                name,
+                arguments: args,
+                body,
+                end_position,
+                ..
+            }) => self.definition_benchmark(name, args, body, *end_position),
+
             Definition::TypeAlias(TypeAlias {
                 alias,
                 parameters: args,
@@ -393,6 +750,7 @@ impl<'comments> Formatter<'comments> {
                     join(elements.iter().map(|e| self.const_expr(e)), comma()),
                     elements.len(),
                     None,
+                    self.options.trailing_commas,
                 )
             }
             TypedExpr::Var { name, .. } => name.to_doc(),
@@ -402,7 +760,11 @@ impl<'comments> Formatter<'comments> {
 
     fn documented_definition<'a>(&mut self, s: &'a UntypedDefinition) -> Document<'a> {
         let comments = self.doc_comments(s.location().start);
-        comments.append(self.definition(s).group()).group()
+        let cfg = self.pop_cfg(s.location().start);
+        comments
+            .append(cfg)
+            .append(self.definition(s).group())
+            .group()
     }
 
     fn doc_comments<'a>(&mut self, limit: usize) -> Document<'a> {
@@ -633,6 +995,36 @@ impl<'comments> Formatter<'comments> {
             .append("}")
     }
 
+    fn definition_benchmark<'a>(
+        &mut self,
+        name: &'a str,
+        args: &'a [UntypedArgVia],
+        body: &'a UntypedExpr,
+        end_location: usize,
+    ) -> Document<'a> {
+        // Fn name and args
+        let head = "bench "
+            .to_doc()
+            .append(name)
+            .append(wrap_args(args.iter().map(|e| (self.fn_arg_via(e), false))))
+            .group();
+
+        // Format body
+        let body = self.expr(body, true);
+
+        // Add any trailing comments
+        let body = match printed_comments(self.pop_comments(end_location), false) {
+            Some(comments) => body.append(line()).append(comments),
+            None => body,
+        };
+
+        // Stick it all together
+        head.append(" {")
+            .append(line().append(body).nest(INDENT).group())
+            .append(line())
+            .append("}")
+    }
+
     fn definition_validator<'a>(
         &mut self,
         name: &'a str,
@@ -760,6 +1152,8 @@ impl<'comments> Formatter<'comments> {
                 documents.push(lines(1));
             }
 
+            documents.push(self.pop_cfg(expression.start_byte_index()));
+
             documents.push(self.expr(expression, false).group());
         }
 
@@ -981,6 +1375,8 @@ impl<'comments> Formatter<'comments> {
 
             UntypedExpr::String { value, .. } => self.string(value),
 
+            UntypedExpr::InterpolatedString { parts, .. } => self.interpolated_string(parts),
+
             UntypedExpr::Sequence { expressions, .. } => {
                 let sequence = self.sequence(expressions);
 
@@ -998,6 +1394,8 @@ impl<'comments> Formatter<'comments> {
 
             UntypedExpr::Var { name, .. } => name.to_doc(),
 
+            UntypedExpr::Hole { name, .. } => "?".to_doc().append(name.as_str()),
+
             UntypedExpr::UnOp { value, op, .. } => self.un_op(value, op),
 
             UntypedExpr::Fn {
@@ -1043,8 +1441,10 @@ impl<'comments> Formatter<'comments> {
                 label,
                 then,
                 arguments,
-                ..
-            } => self.trace(kind, label, arguments, then),
+                location,
+            } => self
+                .pop_cfg(location.start)
+                .append(self.trace(kind, label, arguments, then)),
 
             UntypedExpr::When {
                 subject, clauses, ..
@@ -1094,7 +1494,7 @@ impl<'comments> Formatter<'comments> {
     fn string<'a>(&self, string: &'a str) -> Document<'a> {
         let doc = "@"
             .to_doc()
-            .append(Document::String(escape(string)).surround("\"", "\""));
+            .append(Document::String(escape_literal(string)).surround("\"", "\""));
         if string.contains('\n') {
             doc.force_break()
         } else {
@@ -1102,6 +1502,20 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
+    fn interpolated_string<'a>(&self, parts: &'a [UntypedExpr]) -> Document<'a> {
+        // Interpolated expressions are kept on a single line, as line breaks would otherwise end
+        // up in the text of the string.
+        let parts = parts.iter().map(|part| match part {
+            UntypedExpr::String { value, .. } => Document::String(escape_string(value)),
+            _ => Document::String(format!(
+                "{{{}}}",
+                Formatter::new().expr(part, false).to_pretty_string(999)
+            )),
+        });
+
+        "@".to_doc().append(concat(parts).surround("\"", "\""))
+    }
+
     pub fn trace_if_false<'a>(&mut self, value: &'a UntypedExpr) -> Document<'a> {
         docvec![self.wrap_unary_op(value), "?"]
     }
@@ -1145,6 +1559,7 @@ impl<'comments> Formatter<'comments> {
                 } else {
                     line()
                 })
+                .append(self.pop_cfg(then.start_byte_index()))
                 .append(self.expr(then, true)),
         }
     }
@@ -1252,6 +1667,36 @@ impl<'comments> Formatter<'comments> {
             .group()
     }
 
+    // Like 'wrap_args', but following the configured style for trailing commas and wrapping.
+    fn wrap_call_args<'a>(&self, args: Vec<(Document<'a>, bool)>) -> Document<'a> {
+        let Some(&(_, curly)) = args.first() else {
+            return "()".to_doc();
+        };
+
+        let (open_broken, open_unbroken, close) = if curly {
+            (" {", " { ", "}")
+        } else {
+            ("(", "(", ")")
+        };
+
+        let separator = match self.options.call_wrapping {
+            CallWrapping::OnePerLine => break_(",", ", "),
+            CallWrapping::Packed => flex_break(",", ", "),
+        };
+
+        let trailing_comma = if self.options.trailing_commas {
+            ","
+        } else {
+            ""
+        };
+
+        break_(open_broken, open_unbroken)
+            .append(join(args.into_iter().map(|(arg, _)| arg), separator))
+            .nest(INDENT)
+            .append(break_(trailing_comma, if curly { " " } else { "" }))
+            .append(close)
+    }
+
     fn call<'a>(&mut self, fun: &'a UntypedExpr, args: &'a [CallArg<UntypedExpr>]) -> Document<'a> {
         let is_constr = match fun {
             UntypedExpr::Var { name, .. } => name[0..1].chars().all(|c| c.is_uppercase()),
@@ -1265,11 +1710,13 @@ impl<'comments> Formatter<'comments> {
             false
         };
 
+        let args = args
+            .iter()
+            .map(|a| (self.call_arg(a, needs_curly), needs_curly))
+            .collect::<Vec<_>>();
+
         self.expr(fun, false)
-            .append(wrap_args(
-                args.iter()
-                    .map(|a| (self.call_arg(a, needs_curly), needs_curly)),
-            ))
+            .append(self.wrap_call_args(args))
             .group()
     }
 
@@ -1398,12 +1845,12 @@ impl<'comments> Formatter<'comments> {
         let right_precedence = right.binop_precedence();
 
         let mut left = self.expr(left, false);
-        if left.fits(MAX_COLUMNS) {
+        if left.fits(self.options.max_width) {
             left = left.force_unbroken()
         }
 
         let mut right = self.expr(right, false);
-        if right.fits(MAX_COLUMNS) {
+        if right.fits(self.options.max_width) {
             right = right.force_unbroken()
         }
 
@@ -1522,6 +1969,10 @@ impl<'comments> Formatter<'comments> {
                 arguments: args,
                 ..
             } => (fun, args),
+            // x |> _.foo
+            UntypedExpr::FieldAccess { .. } | UntypedExpr::TupleIndex { .. } => {
+                return self.expr(fun, false)
+            }
             _ => panic!("Function capture found not to have a function call body when formatting"),
         };
 
@@ -1538,18 +1989,23 @@ impl<'comments> Formatter<'comments> {
             self.expr(fun, false)
         } else if hole_in_first_position {
             // x |> fun(_, 2, 3)
-            self.expr(fun, false).append(
-                wrap_args(
-                    args.iter()
-                        .skip(1)
-                        .map(|a| (self.call_arg(a, false), false)),
-                )
-                .group(),
-            )
+            let args = args
+                .iter()
+                .skip(1)
+                .map(|a| (self.call_arg(a, false), false))
+                .collect::<Vec<_>>();
+
+            self.expr(fun, false)
+                .append(self.wrap_call_args(args).group())
         } else {
             // x |> fun(1, _, 3)
+            let args = args
+                .iter()
+                .map(|a| (self.call_arg(a, false), false))
+                .collect::<Vec<_>>();
+
             self.expr(fun, false)
-                .append(wrap_args(args.iter().map(|a| (self.call_arg(a, false), false))).group())
+                .append(self.wrap_call_args(args).group())
         }
     }
 
@@ -1568,11 +2024,22 @@ impl<'comments> Formatter<'comments> {
                         .group()
                 }
 
-                _ => self.expr(fun, false).append(
-                    wrap_args(args.iter().map(|a| (self.call_arg(a, false), false))).group(),
-                ),
+                _ => {
+                    let args = args
+                        .iter()
+                        .map(|a| (self.call_arg(a, false), false))
+                        .collect::<Vec<_>>();
+
+                    self.expr(fun, false)
+                        .append(self.wrap_call_args(args).group())
+                }
             },
 
+            // _.foo.bar
+            UntypedExpr::FieldAccess { .. } | UntypedExpr::TupleIndex { .. } => {
+                self.expr(call, false)
+            }
+
             // The body of a capture being not a fn shouldn't be possible...
             _ => panic!("Function capture body found not to be a call in the formatter",),
         }
@@ -1588,27 +2055,49 @@ impl<'comments> Formatter<'comments> {
         let doc = if constructor.arguments.is_empty() {
             constructor.name.to_doc()
         } else if constructor.sugar {
-            wrap_fields(constructor.arguments.iter().map(
-                |RecordConstructorArg {
-                     label,
-                     annotation,
-                     location,
-                     ..
-                 }| {
-                    let arg_comments = self.pop_comments(location.start);
-
-                    let arg = match label {
-                        Some(l) => l.to_doc().append(": ").append(self.annotation(annotation)),
-                        None => self.annotation(annotation),
-                    };
+            let fields = constructor
+                .arguments
+                .iter()
+                .enumerate()
+                .map(
+                    |(
+                        i,
+                        RecordConstructorArg {
+                            label,
+                            annotation,
+                            location,
+                            ..
+                        },
+                    )| {
+                        let empty_line_before = self.pop_empty_lines(location.start) && i > 0;
 
-                    commented(
-                        self.doc_comments(location.start).append(arg).group(),
-                        arg_comments,
-                    )
-                },
-            ))
-            .group()
+                        let arg_comments = self.pop_comments(location.start);
+
+                        let arg = match label {
+                            Some(l) => l.to_doc().append(": ").append(self.annotation(annotation)),
+                            None => self.annotation(annotation),
+                        };
+
+                        let field = commented(
+                            self.doc_comments(location.start).append(arg).group(),
+                            arg_comments,
+                        )
+                        .append(",");
+
+                        (
+                            field,
+                            self.pop_trailing_comment(location.end),
+                            empty_line_before,
+                        )
+                    },
+                )
+                .collect();
+
+            line()
+                .append(self.trailing_commented(fields))
+                .nest(INDENT)
+                .append(line())
+                .group()
         } else {
             constructor
                 .name
@@ -1796,7 +2285,11 @@ impl<'comments> Formatter<'comments> {
         let doc = head.append(tail.clone()).group();
 
         // Wrap arguments on multi-lines if they are lengthy.
-        if doc.clone().to_pretty_string(MAX_COLUMNS).contains('\n') {
+        if doc
+            .clone()
+            .to_pretty_string(self.options.max_width)
+            .contains('\n')
+        {
             let head = name
                 .to_doc()
                 .append(self.docs_fn_args(args).force_break())
@@ -1916,6 +2409,11 @@ impl<'comments> Formatter<'comments> {
         )
         .group();
 
+        let clause_doc = match &clause.guard {
+            None => clause_doc,
+            Some(guard) => clause_doc.append(" if ").append(self.wrap_expr(guard)),
+        };
+
         if index == 0 {
             clause_doc
         } else if space_before {
@@ -1940,7 +2438,12 @@ impl<'comments> Formatter<'comments> {
             };
         let elements_document = join(elements.iter().map(|e| self.wrap_expr(e)), comma());
         let tail = tail.map(|e| self.expr(e, false));
-        list(elements_document, elements.len(), tail)
+        list(
+            elements_document,
+            elements.len(),
+            tail,
+            self.options.trailing_commas,
+        )
     }
 
     pub fn pattern<'a>(&mut self, pattern: &'a UntypedPattern) -> Document<'a> {
@@ -1992,7 +2495,12 @@ impl<'comments> Formatter<'comments> {
                         self.pattern(e)
                     }
                 });
-                list(elements_document, elements.len(), tail)
+                list(
+                    elements_document,
+                    elements.len(),
+                    tail,
+                    self.options.trailing_commas,
+                )
             }
 
             Pattern::Constructor {
@@ -2151,23 +2659,12 @@ where
         .append(">")
 }
 
-pub fn wrap_fields<'a, I>(args: I) -> Document<'a>
-where
-    I: IntoIterator<Item = Document<'a>>,
-{
-    let mut args = args.into_iter().peekable();
-    if args.peek().is_none() {
-        return nil();
-    }
-
-    line()
-        .append(join(args, ",".to_doc().append(line())))
-        .nest(INDENT)
-        .append(",")
-        .append(line())
-}
-
-fn list<'a>(elements: Document<'a>, length: usize, tail: Option<Document<'a>>) -> Document<'a> {
+fn list<'a>(
+    elements: Document<'a>,
+    length: usize,
+    tail: Option<Document<'a>>,
+    trailing_comma: bool,
+) -> Document<'a> {
     if length == 0 {
         return match tail {
             Some(tail) => tail,
@@ -2178,7 +2675,9 @@ fn list<'a>(elements: Document<'a>, length: usize, tail: Option<Document<'a>>) -
     let doc = break_("[", "[").append(elements);
 
     match tail {
-        None => doc.nest(INDENT).append(break_(",", "")),
+        None => doc
+            .nest(INDENT)
+            .append(break_(if trailing_comma { "," } else { "" }, "")),
 
         // Don't print tail if it is a discard
         Some(Document::String(t)) if t == *"_" => doc
@@ -2317,6 +2816,34 @@ fn is_breakable_expr(expr: &UntypedExpr) -> bool {
     )
 }
 
+/// Like 'escape', but also escaping braces which would otherwise start an interpolation.
+fn escape_string(string: &str) -> String {
+    escape(string).replace('{', "\\{")
+}
+
+/// Like 'escape', but only escaping braces when they would be mistaken for an interpolation; so
+/// that strings such as '@"{}"' are left as they are.
+fn escape_literal(string: &str) -> String {
+    let escaped = escape(string);
+
+    if !string.contains('{') {
+        return escaped;
+    }
+
+    let is_literal = match lexer::run(&format!("@\"{escaped}\"")) {
+        Ok(LexInfo { tokens, .. }) => {
+            matches!(tokens.as_slice(), [(Token::String { value }, _)] if value == string)
+        }
+        Err(..) => false,
+    };
+
+    if is_literal {
+        escaped
+    } else {
+        escape_string(string)
+    }
+}
+
 fn escape(string: &str) -> String {
     string
         .chars()
@@ -1,6 +1,7 @@
 pub mod air;
 pub mod builder;
 pub mod decision_tree;
+pub mod export;
 pub mod interner;
 pub mod stick_break_set;
 pub mod tree;
@@ -21,6 +22,7 @@ use crate::{
         TypedValidator, UnOp,
     },
     builtins::PRELUDE,
+    coverage,
     expr::TypedExpr,
     gen_uplc::{
         air::ExpectLevel,
@@ -49,15 +51,21 @@ use indexmap::IndexMap;
 use interner::AirInterner;
 use itertools::Itertools;
 use petgraph::{algo, Graph};
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+    time::{Duration, Instant},
+};
 use stick_break_set::{Builtins, TreeSet};
 use tree::Fields;
 use uplc::{
     ast::{Constant as UplcConstant, Name, NamedDeBruijn, Program, Term, Type as UplcType},
     builder::{CONSTR_FIELDS_EXPOSER, CONSTR_INDEX_EXPOSER, EXPECT_ON_LIST},
     builtins::DefaultFunction,
-    machine::cost_model::ExBudget,
-    optimize::{aiken_optimize_and_intern, interner::CodeGenInterner, shrinker::NO_INLINE},
+    machine::{cost_model::ExBudget, eval_result::EvalResult},
+    optimize::{
+        interner::CodeGenInterner, optimize_and_intern, shrinker::NO_INLINE, OptimizationLevel,
+    },
 };
 
 type Otherwise = Option<AirTree>;
@@ -65,9 +73,10 @@ type Otherwise = Option<AirTree>;
 const DELAY_ERROR: fn() -> AirTree =
     || AirTree::anon_func(vec![], AirTree::error(Type::void(), false), true);
 
+const CONTEXT_NAME: &str = "__context__";
+
 #[derive(Clone)]
 pub struct CodeGenerator<'a> {
-    #[allow(dead_code)]
     plutus_version: PlutusVersion,
     /// immutable index maps
     functions: IndexMap<&'a FunctionAccessKey, &'a TypedFunction>,
@@ -75,8 +84,11 @@ pub struct CodeGenerator<'a> {
     data_types: IndexMap<&'a DataTypeKey, &'a TypedDataType>,
     module_types: IndexMap<&'a str, &'a TypeInfo>,
     module_src: IndexMap<&'a str, &'a (String, LineNumbers)>,
-    /// immutable option
+    /// immutable options
     tracing: TraceLevel,
+    user_traces: bool,
+    coverage: bool,
+    optimization: OptimizationLevel,
     /// mutable index maps that are reset
     defined_functions: IndexMap<FunctionAccessKey, ()>,
     special_functions: CodeGenSpecialFuncs,
@@ -86,6 +98,17 @@ pub struct CodeGenerator<'a> {
     /// mutable and reset as well
     interner: AirInterner,
     id_gen: IdGenerator,
+    /// values of module constants evaluated so far, which survive resets
+    constant_values: IndexMap<FunctionAccessKey, Term<Name>>,
+    /// time spent generating validators, per module
+    elapsed: BTreeMap<String, Elapsed>,
+}
+
+/// Time spent generating and optimizing the validators of a module.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed {
+    pub generating: Duration,
+    pub optimizing: Duration,
 }
 
 impl<'a> CodeGenerator<'a> {
@@ -110,15 +133,44 @@ impl<'a> CodeGenerator<'a> {
             module_types,
             module_src,
             tracing: tracing.trace_level(true),
+            user_traces: true,
+            coverage: false,
+            optimization: OptimizationLevel::default(),
             defined_functions: IndexMap::new(),
             special_functions: CodeGenSpecialFuncs::new(),
             code_gen_functions: IndexMap::new(),
             cyclic_functions: IndexMap::new(),
             interner: AirInterner::new(),
             id_gen: IdGenerator::new(),
+            constant_values: IndexMap::new(),
+            elapsed: BTreeMap::new(),
         }
     }
 
+    pub fn with_plutus_version(mut self, plutus_version: PlutusVersion) -> Self {
+        self.plutus_version = plutus_version;
+        self
+    }
+
+    /// Override the trace level of compiler-generated traces. User-defined traces are resolved
+    /// during type-checking already, so they can only be dropped (when silent) but not re-enabled.
+    pub fn with_trace_level(mut self, tracing: TraceLevel) -> Self {
+        self.tracing = tracing;
+        self.user_traces = tracing != TraceLevel::Silent;
+        self
+    }
+
+    pub fn with_optimization(mut self, optimization: OptimizationLevel) -> Self {
+        self.optimization = optimization;
+        self
+    }
+
+    /// Instrument function bodies and branches with coverage markers (see 'crate::coverage').
+    pub fn with_coverage(mut self) -> Self {
+        self.coverage = true;
+        self
+    }
+
     pub fn reset(&mut self, reset_special_functions: bool) {
         self.code_gen_functions = IndexMap::new();
         self.defined_functions = IndexMap::new();
@@ -130,9 +182,106 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
+    /// Time spent in code generation so far, per module.
+    pub fn elapsed(&self) -> &BTreeMap<String, Elapsed> {
+        &self.elapsed
+    }
+
+    /// Take over the timings of a generator derived from this one (e.g. with different
+    /// settings), which started off with a copy of ours.
+    pub fn inherit_elapsed(&mut self, derived: CodeGenerator) {
+        self.elapsed = derived.elapsed;
+    }
+
+    /// Evaluate a module constant by running its generated program, so that it can be embedded
+    /// as a value wherever it's used. Each constant is only ever evaluated once; failures come
+    /// with the result of the evaluation, traces included.
+    #[allow(clippy::result_large_err)]
+    pub fn eval_constant(&mut self, module: &str, name: &str) -> Result<Term<Name>, EvalResult> {
+        let access_key = FunctionAccessKey {
+            module_name: module.to_string(),
+            function_name: name.to_string(),
+        };
+
+        if let Some(value) = self.constant_values.get(&access_key) {
+            return Ok(value.clone());
+        }
+
+        let definition = self
+            .constants
+            .get(&access_key)
+            .unwrap_or_else(|| panic!("unknown constant {module}.{name}"));
+
+        let mut value = AirTree::no_op(self.build(definition, module, &[]));
+
+        value.traverse_tree_with(&mut |air_tree, _| {
+            erase_opaque_type_operations(air_tree, &self.data_types);
+        });
+
+        value = self.hoist_functions_to_validator(value);
+
+        let term = self.uplc_code_gen(value.to_vec());
+
+        let mut program = self.new_program(self.special_functions.apply_used_functions(term));
+
+        let mut interner = CodeGenInterner::new();
+
+        interner.program(&mut program);
+
+        let eval_program: Program<NamedDeBruijn> = program.clean_up().try_into().unwrap();
+
+        let result = eval_program.eval(ExBudget::max());
+
+        let value: Term<Name> = match result.result() {
+            Ok(value) => value.try_into().unwrap(),
+            Err(..) => return Err(result),
+        };
+
+        self.constant_values.insert(access_key, value.clone());
+
+        Ok(value)
+    }
+
     pub fn generate(&mut self, validator: &TypedValidator, module_name: &str) -> Program<Name> {
-        let context_name = "__context__".to_string();
-        let context_name_interned = introduce_name(&mut self.interner, &context_name);
+        let start = Instant::now();
+
+        let full_tree = self.validator_air(validator, module_name);
+
+        // optimizations on air tree
+
+        let full_vec = full_tree.to_vec();
+
+        let term = self.uplc_code_gen(full_vec);
+
+        let term = cast_validator_args(term, &validator.params, &self.interner);
+
+        self.pop_validator_names(validator);
+
+        let generated = Instant::now();
+
+        let program = self.finalize(term);
+
+        let elapsed = self.elapsed.entry(module_name.to_string()).or_default();
+        elapsed.generating += generated - start;
+        elapsed.optimizing += generated.elapsed();
+
+        program
+    }
+
+    /// The AIR of a validator, as handed over to UPLC code generation; i.e. once functions are
+    /// hoisted.
+    pub fn generate_air(&mut self, validator: &TypedValidator, module_name: &str) -> AirTree {
+        let full_tree = self.validator_air(validator, module_name);
+
+        self.pop_validator_names(validator);
+
+        self.reset(true);
+
+        full_tree
+    }
+
+    fn validator_air(&mut self, validator: &TypedValidator, module_name: &str) -> AirTree {
+        let context_name_interned = introduce_name(&mut self.interner, &CONTEXT_NAME.to_string());
         validator.params.iter().for_each(|arg| {
             arg.get_variable_name()
                 .iter()
@@ -148,24 +297,16 @@ impl<'a> CodeGenerator<'a> {
 
         let validator_args_tree = AirTree::no_op(air_tree_fun);
 
-        let full_tree = self.hoist_functions_to_validator(validator_args_tree);
-
-        // optimizations on air tree
-
-        let full_vec = full_tree.to_vec();
-
-        let term = self.uplc_code_gen(full_vec);
-
-        let term = cast_validator_args(term, &validator.params, &self.interner);
+        self.hoist_functions_to_validator(validator_args_tree)
+    }
 
-        self.interner.pop_text(context_name);
+    fn pop_validator_names(&mut self, validator: &TypedValidator) {
+        self.interner.pop_text(CONTEXT_NAME.to_string());
         validator.params.iter().for_each(|arg| {
             arg.get_variable_name()
                 .iter()
                 .for_each(|arg_name| self.interner.pop_text(arg_name.to_string()))
         });
-
-        self.finalize(term)
     }
 
     pub fn generate_raw(
@@ -218,7 +359,7 @@ impl<'a> CodeGenerator<'a> {
     fn finalize(&mut self, mut term: Term<Name>) -> Program<Name> {
         term = self.special_functions.apply_used_functions(term);
 
-        let program = aiken_optimize_and_intern(self.new_program(term));
+        let program = optimize_and_intern(self.new_program(term), self.optimization);
 
         // This is very important to call here.
         // If this isn't done, re-using the same instance
@@ -337,7 +478,12 @@ impl<'a> CodeGenerator<'a> {
                     _ => AirTree::var(constructor.clone(), name, ""),
                 },
 
-                TypedExpr::Fn { args, body, .. } => {
+                TypedExpr::Fn {
+                    args,
+                    body,
+                    is_capture,
+                    ..
+                } => {
                     let params = args
                         .iter()
                         .map(|arg| {
@@ -347,8 +493,13 @@ impl<'a> CodeGenerator<'a> {
                         })
                         .collect_vec();
 
-                    let anon =
-                        AirTree::anon_func(params, self.build(body, module_build_name, &[]), false);
+                    let mut air_body = self.build(body, module_build_name, &[]);
+
+                    if !is_capture {
+                        air_body = self.covered(body, module_build_name, air_body);
+                    }
+
+                    let anon = AirTree::anon_func(params, air_body, false);
 
                     args.iter()
                         .filter_map(|arg| arg.get_variable_name())
@@ -554,6 +705,10 @@ impl<'a> CodeGenerator<'a> {
                     left.tipo(),
                 ),
 
+                TypedExpr::Trace { then, .. } if !self.user_traces => {
+                    self.build(then, module_build_name, &[])
+                }
+
                 TypedExpr::Trace {
                     tipo, then, text, ..
                 } => AirTree::trace(
@@ -573,7 +728,7 @@ impl<'a> CodeGenerator<'a> {
                     // TODO: This whole branch can _probably_ be removed, if handle_each_clause
                     // works fine with an empty clauses list. This is orthogonal to the
                     // current refactoring so not changing it now.
-                    } else if clauses.len() == 1 {
+                    } else if clauses.len() == 1 && clauses[0].guard.is_none() {
                         let subject_val = self.build(subject, module_build_name, &[]);
 
                         let last_clause = &clauses[0];
@@ -583,6 +738,9 @@ impl<'a> CodeGenerator<'a> {
 
                         let clause_then = self.build(&last_clause.then, module_build_name, &[]);
 
+                        let clause_then =
+                            self.covered(&last_clause.then, module_build_name, clause_then);
+
                         let subject_type = subject.tipo();
 
                         let tree = self.assignment(
@@ -651,8 +809,10 @@ impl<'a> CodeGenerator<'a> {
                     tipo,
                     ..
                 } => {
+                    let air_final_else = self.build(final_else, module_build_name, &[]);
+
                     branches.iter().rfold(
-                        self.build(final_else, module_build_name, &[]),
+                        self.covered(final_else, module_build_name, air_final_else),
                         |acc, branch| {
                             let condition = self.build(&branch.condition, module_build_name, &[]);
 
@@ -663,6 +823,8 @@ impl<'a> CodeGenerator<'a> {
 
                                     let body = self.build(&branch.body, module_build_name, &[]);
 
+                                    let body = self.covered(&branch.body, module_build_name, body);
+
                                     let acc_var =
                                         self.interner.lookup_interned(&"acc_var".to_string());
 
@@ -693,12 +855,16 @@ impl<'a> CodeGenerator<'a> {
 
                                     tree
                                 }
-                                None => AirTree::if_branch(
-                                    tipo.clone(),
-                                    condition,
-                                    self.build(&branch.body, module_build_name, &[]),
-                                    acc,
-                                ),
+                                None => {
+                                    let body = self.build(&branch.body, module_build_name, &[]);
+
+                                    AirTree::if_branch(
+                                        tipo.clone(),
+                                        condition,
+                                        self.covered(&branch.body, module_build_name, body),
+                                        acc,
+                                    )
+                                }
                             }
                         },
                     )
@@ -944,6 +1110,19 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
+    /// Mark entering 'body' with a trace, when instrumenting for coverage.
+    fn covered(&self, body: &TypedExpr, module_build_name: &str, air_body: AirTree) -> AirTree {
+        if !self.coverage {
+            return air_body;
+        }
+
+        AirTree::trace(
+            AirTree::string(coverage::marker(module_build_name, body.location())),
+            body.tipo(),
+            air_body,
+        )
+    }
+
     pub fn assignment(
         &mut self,
         pattern: &TypedPattern,
@@ -2711,26 +2890,89 @@ impl<'a> CodeGenerator<'a> {
                     air_args.into_iter().map(|i| i.1).collect_vec(),
                 );
 
-                handle_assigns(subject_name, subject_tipo, &args, &mut stick_set, then)
+                handle_assigns(subject_name, subject_tipo, &args, &mut stick_set, |_| then)
+            }
+            DecisionTree::GuardedLeaf {
+                guard,
+                then,
+                assigns,
+                otherwise,
+            } => {
+                let air_args = assigns
+                    .iter()
+                    .map(|item| {
+                        let current_tipo = get_tipo_by_path(subject_tipo.clone(), &item.path);
+
+                        (
+                            current_tipo.clone(),
+                            AirTree::local_var(item.assigned.clone(), current_tipo),
+                        )
+                    })
+                    .collect_vec();
+
+                let arg_tipos = air_args.iter().map(|i| i.0.clone()).collect_vec();
+
+                let air_args = air_args.into_iter().map(|i| i.1).collect_vec();
+
+                let guard = AirTree::call(
+                    AirTree::local_var(guard, Type::function(arg_tipos.clone(), Type::bool())),
+                    Type::bool(),
+                    air_args.clone(),
+                );
+
+                let then = AirTree::call(
+                    AirTree::local_var(then, Type::function(arg_tipos, return_tipo.clone())),
+                    Type::void(),
+                    air_args,
+                );
+
+                // The bindings extracted for the guard remain in scope of the clauses which
+                // follow, so they get reused rather than extracted again.
+                handle_assigns(
+                    subject_name,
+                    subject_tipo.clone(),
+                    &assigns,
+                    &mut stick_set,
+                    |stick_set| {
+                        let otherwise = self.handle_decision_tree(
+                            subject_name,
+                            subject_tipo.clone(),
+                            return_tipo.clone(),
+                            module_build_name,
+                            *otherwise,
+                            stick_set.clone(),
+                        );
+
+                        AirTree::if_branch(return_tipo.clone(), guard, then, otherwise)
+                    },
+                )
             }
             DecisionTree::HoistThen {
                 name,
                 assigns,
                 pattern,
                 then,
+                is_guard,
             } => {
+                let params = assigns
+                    .iter()
+                    .map(|i| introduce_name(&mut self.interner, &i.assigned))
+                    .collect_vec();
+
+                // The one reason we have to pass in mutable self
+                // So we can build the TypedExpr into Air
+                let air_then = self.build(then, module_build_name, &[]);
+
+                // Guards aren't branches of their own, so they aren't instrumented for coverage.
+                let air_then = if is_guard {
+                    air_then
+                } else {
+                    self.covered(then, module_build_name, air_then)
+                };
+
                 let assign = AirTree::let_assignment(
                     name,
-                    AirTree::anon_func(
-                        assigns
-                            .iter()
-                            .map(|i| introduce_name(&mut self.interner, &i.assigned))
-                            .collect_vec(),
-                        // The one reason we have to pass in mutable self
-                        // So we can build the TypedExpr into Air
-                        self.build(then, module_build_name, &[]),
-                        true,
-                    ),
+                    AirTree::anon_func(params, air_then, true),
                     self.handle_decision_tree(
                         subject_name,
                         subject_tipo,
@@ -3442,7 +3684,7 @@ impl<'a> CodeGenerator<'a> {
                         function_name: func_name.clone(),
                     };
 
-                    let function_def = self.functions.get(&generic_function_key);
+                    let function_def = self.functions.get(&generic_function_key).copied();
 
                     let Some(function_def) = function_def else {
                         let code_gen_func = self
@@ -3566,10 +3808,16 @@ impl<'a> CodeGenerator<'a> {
                                 })
                                 .collect_vec();
 
-                            let mut function_air_tree_body = AirTree::no_op(self.build(
+                            let air_body = self.build(
                                 &function_def.body,
                                 &generic_function_key.module_name,
                                 &[],
+                            );
+
+                            let mut function_air_tree_body = AirTree::no_op(self.covered(
+                                &function_def.body,
+                                &generic_function_key.module_name,
+                                air_body,
                             ));
 
                             function_air_tree_body.traverse_tree_with(&mut |air_tree, _| {
@@ -3608,10 +3856,13 @@ impl<'a> CodeGenerator<'a> {
                             })
                             .collect_vec();
 
-                        let mut function_air_tree_body = AirTree::no_op(self.build(
+                        let air_body =
+                            self.build(&function_def.body, &generic_function_key.module_name, &[]);
+
+                        let mut function_air_tree_body = AirTree::no_op(self.covered(
                             &function_def.body,
                             &generic_function_key.module_name,
-                            &[],
+                            air_body,
                         ));
 
                         function_air_tree_body.traverse_tree_with(&mut |air_tree, _| {
@@ -3683,45 +3934,12 @@ impl<'a> CodeGenerator<'a> {
                     .into(),
                 )),
                 ValueConstructorVariant::ModuleConstant { module, name, .. } => {
-                    let access_key = FunctionAccessKey {
-                        module_name: module.clone(),
-                        function_name: name.clone(),
-                    };
-
-                    let definition = self
-                        .constants
-                        .get(&access_key)
-                        .unwrap_or_else(|| panic!("unknown constant {module}.{name}"));
-
-                    let mut value =
-                        AirTree::no_op(self.build(definition, &access_key.module_name, &[]));
-
-                    value.traverse_tree_with(&mut |air_tree, _| {
-                        erase_opaque_type_operations(air_tree, &self.data_types);
-                    });
-
-                    value = self.hoist_functions_to_validator(value);
-
-                    let term = self.uplc_code_gen(value.to_vec());
-
-                    let mut program =
-                        self.new_program(self.special_functions.apply_used_functions(term));
-
-                    let mut interner = CodeGenInterner::new();
-
-                    interner.program(&mut program);
-
-                    let eval_program: Program<NamedDeBruijn> =
-                        program.clean_up().try_into().unwrap();
-
-                    Some(
-                        eval_program
-                            .eval(ExBudget::max())
-                            .result()
-                            .unwrap_or_else(|e| panic!("Failed to evaluate constant: {e:#?}"))
-                            .try_into()
-                            .unwrap(),
-                    )
+                    Some(self.eval_constant(module, name).unwrap_or_else(|e| {
+                        panic!(
+                            "Failed to evaluate constant: {:#?}",
+                            e.result().unwrap_err()
+                        )
+                    }))
                 }
                 ValueConstructorVariant::ModuleFn {
                     name: func_name,
@@ -5228,10 +5446,10 @@ fn handle_assigns(
     subject_tipo: Rc<Type>,
     assigns: &[Assigned],
     stick_set: &mut TreeSet,
-    then: AirTree,
+    then: impl FnOnce(&mut TreeSet) -> AirTree,
 ) -> AirTree {
     match assigns {
-        [] => then,
+        [] => then(stick_set),
         [assign, rest @ ..] => {
             let Assigned { path, assigned } = assign;
 
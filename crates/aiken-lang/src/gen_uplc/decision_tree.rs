@@ -1,12 +1,12 @@
 use core::fmt;
 use pretty::RcDoc;
-use std::{cmp::Ordering, fmt::Display, rc::Rc};
+use std::{cmp::Ordering, collections::HashSet, fmt::Display, rc::Rc};
 
 use indexmap::IndexMap;
 use itertools::{Either, Itertools, Position};
 
 use crate::{
-    ast::{DataTypeKey, Pattern, TypedClause, TypedDataType, TypedPattern},
+    ast::{DataTypeKey, Pattern, Span, TypedClause, TypedDataType, TypedPattern},
     expr::{lookup_data_type_by_tipo, Type, TypeVar, TypedExpr},
 };
 
@@ -104,6 +104,7 @@ struct Row<'a> {
     assigns: Vec<Assigned>,
     columns: Vec<RowItem<'a>>,
     then: String,
+    guard: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -167,11 +168,20 @@ pub enum DecisionTree<'a> {
         default: Option<Box<DecisionTree<'a>>>,
     },
     HoistedLeaf(String, Vec<Assigned>),
+    /// A leaf whose clause has a guard; when the guard doesn't hold, matching carries on with
+    /// the clauses that follow, through 'otherwise'.
+    GuardedLeaf {
+        guard: String,
+        then: String,
+        assigns: Vec<Assigned>,
+        otherwise: Box<DecisionTree<'a>>,
+    },
     HoistThen {
         name: String,
         assigns: Vec<Assigned>,
         pattern: Box<DecisionTree<'a>>,
         then: &'a TypedExpr,
+        is_guard: bool,
     },
 }
 
@@ -376,6 +386,21 @@ impl<'a> DecisionTree<'a> {
                 )
                 .append(RcDoc::text(")")),
             DecisionTree::HoistedLeaf(name, _) => RcDoc::text(format!("Leaf({})", name)),
+            DecisionTree::GuardedLeaf {
+                guard,
+                then,
+                otherwise,
+                ..
+            } => RcDoc::text(format!("GuardedLeaf({}, {})", guard, then))
+                .append(
+                    RcDoc::line()
+                        .append(RcDoc::text("otherwise : "))
+                        .append(RcDoc::line())
+                        .append(otherwise.to_doc())
+                        .append(RcDoc::line())
+                        .nest(4),
+                )
+                .append(RcDoc::text(")")),
             DecisionTree::HoistThen { name, pattern, .. } => RcDoc::text("HoistThen(")
                 .append(
                     RcDoc::line()
@@ -405,6 +430,11 @@ impl<'a> DecisionTree<'a> {
         let mut scope_map: IndexMap<&String, Scope> =
             names.into_iter().map(|item| (item, Scope::new())).collect();
 
+        // An empty scope is also the root of the tree, so it can't tell whether a name was met
+        // already. Otherwise, leaves whose only common ancestor is the root would end up scoped
+        // to whichever of them comes last.
+        let mut seen = HashSet::new();
+
         loop {
             match tree {
                 DecisionTree::Switch { cases, default, .. } => {
@@ -474,12 +504,34 @@ impl<'a> DecisionTree<'a> {
                         .get_mut(leaf_name)
                         .expect("Impossible, Leaf is based off of given names");
 
-                    if scope_for_name.is_empty() {
+                    if seen.insert(leaf_name) {
                         *scope_for_name = current_path.clone();
                     } else {
                         scope_for_name.common_ancestor(&current_path);
                     }
                 }
+                DecisionTree::GuardedLeaf {
+                    guard,
+                    then,
+                    otherwise,
+                    ..
+                } => {
+                    for leaf_name in [guard, then] {
+                        let scope_for_name = scope_map
+                            .get_mut(leaf_name)
+                            .expect("Impossible, Leaf is based off of given names");
+
+                        if seen.insert(leaf_name) {
+                            *scope_for_name = current_path.clone();
+                        } else {
+                            scope_for_name.common_ancestor(&current_path);
+                        }
+                    }
+
+                    prev.push(Marker::Pop);
+
+                    prev.push(Marker::Push(ScopePath::Fallback, otherwise.as_ref()));
+                }
                 // These are not generated by do_build_tree, but
                 // added afterwards
                 DecisionTree::HoistThen { .. } => unreachable!(),
@@ -515,7 +567,7 @@ impl<'a> DecisionTree<'a> {
         &mut self,
         current_path: &mut Scope,
         name_paths: &mut Vec<(String, Scope)>,
-        hoistables: &mut IndexMap<String, (Vec<Assigned>, &'a TypedExpr)>,
+        hoistables: &mut IndexMap<String, (Vec<Assigned>, &'a TypedExpr, bool)>,
     ) {
         match self {
             DecisionTree::Switch { cases, default, .. } => {
@@ -559,6 +611,11 @@ impl<'a> DecisionTree<'a> {
                 current_path.pop();
             }
             DecisionTree::HoistedLeaf(_, _) => (),
+            DecisionTree::GuardedLeaf { otherwise, .. } => {
+                current_path.push(ScopePath::Fallback);
+                otherwise.hoist_by_path(current_path, name_paths, hoistables);
+                current_path.pop();
+            }
             DecisionTree::HoistThen { .. } => unreachable!(),
         }
 
@@ -566,7 +623,7 @@ impl<'a> DecisionTree<'a> {
         // This ensures we will visit each node in the order we would pop it off
         while let Some(name_path) = name_paths.pop() {
             if name_path.1 == *current_path {
-                let (assigns, then) = hoistables.remove(&name_path.0).unwrap();
+                let (assigns, then, is_guard) = hoistables.remove(&name_path.0).unwrap();
                 let pattern =
                     std::mem::replace(self, DecisionTree::HoistedLeaf("".to_string(), vec![]));
 
@@ -575,6 +632,7 @@ impl<'a> DecisionTree<'a> {
                     assigns,
                     pattern: pattern.into(),
                     then,
+                    is_guard,
                 };
             } else {
                 name_paths.push(name_path);
@@ -621,6 +679,12 @@ impl<'a, 'b> TreeGen<'a, 'b> {
 
         let mut columns_added = vec![];
 
+        // Alternatives of a clause (i.e. `Foo(x) | Bar(x) -> ...`) come out of the type-checker
+        // as consecutive clauses sharing the same location and body. They share a single
+        // hoisted body instead of each getting a copy of it. Clauses generated by the compiler
+        // (e.g. for validator handlers) have no location, and are never alternatives.
+        let mut alternatives: Option<(Span, String, Option<String>)> = None;
+
         let rows = {
             let rows_initial = clauses
                 .iter()
@@ -631,12 +695,46 @@ impl<'a, 'b> TreeGen<'a, 'b> {
                     let (assign, row_items) =
                         self.map_pattern_to_row(&clause.pattern, subject_tipo, vec![]);
 
-                    self.interner.intern(format!("__clause_then_{}", index));
-                    let clause_then_name = self
-                        .interner
-                        .lookup_interned(&format!("__clause_then_{}", index));
+                    let (clause_then_name, clause_guard_name) = match &alternatives {
+                        Some((location, then, guard))
+                            if *location == clause.location && *location != Span::empty() =>
+                        {
+                            (then.clone(), guard.clone())
+                        }
+                        _ => {
+                            self.interner.intern(format!("__clause_then_{}", index));
+                            let clause_then_name = self
+                                .interner
+                                .lookup_interned(&format!("__clause_then_{}", index));
+
+                            hoistables
+                                .insert(clause_then_name.clone(), (vec![], &clause.then, false));
+
+                            self.interner.pop_text(format!("__clause_then_{}", index));
+
+                            // Guards are hoisted like bodies, taking the same arguments.
+                            let clause_guard_name = clause.guard.as_ref().map(|guard| {
+                                self.interner.intern(format!("__clause_guard_{}", index));
+                                let clause_guard_name = self
+                                    .interner
+                                    .lookup_interned(&format!("__clause_guard_{}", index));
+
+                                hoistables.insert(clause_guard_name.clone(), (vec![], guard, true));
+
+                                self.interner.pop_text(format!("__clause_guard_{}", index));
 
-                    hoistables.insert(clause_then_name.clone(), (vec![], &clause.then));
+                                clause_guard_name
+                            });
+
+                            alternatives = Some((
+                                clause.location,
+                                clause_then_name.clone(),
+                                clause_guard_name.clone(),
+                            ));
+
+                            (clause_then_name, clause_guard_name)
+                        }
+                    };
 
                     // Some good ol' mutation to track added columns per relevant path
                     // relevant path indicating a column that has a pattern to test at some point in
@@ -647,15 +745,12 @@ impl<'a, 'b> TreeGen<'a, 'b> {
                         }
                     });
 
-                    let row = Row {
+                    Row {
                         assigns: assign.into_iter().collect_vec(),
                         columns: row_items,
                         then: clause_then_name,
-                    };
-
-                    self.interner.pop_text(format!("__clause_then_{}", index));
-
-                    row
+                        guard: clause_guard_name,
+                    }
                 })
                 .collect_vec();
 
@@ -720,7 +815,7 @@ impl<'a, 'b> TreeGen<'a, 'b> {
         &mut self,
         subject_tipo: &Rc<Type>,
         matrix: PatternMatrix<'a>,
-        then_map: &mut IndexMap<String, (Vec<Assigned>, &'a TypedExpr)>,
+        then_map: &mut IndexMap<String, (Vec<Assigned>, &'a TypedExpr, bool)>,
     ) -> DecisionTree<'a> {
         let column_length = matrix.rows[0].columns.len();
 
@@ -738,20 +833,59 @@ impl<'a, 'b> TreeGen<'a, 'b> {
             // No more patterns to match on so we grab the first default row and return that
             let mut fallback = matrix.rows;
 
-            let row = fallback.swap_remove(0);
+            let row = fallback.remove(0);
 
-            let Some((assigns, _)) = then_map.get_mut(&row.then) else {
+            let Some((assigns, _, _)) = then_map.get_mut(&row.then) else {
                 unreachable!()
             };
 
             // This is just to prevent repeated assigning clones for the same fallback
             // used in multiple places
             // So we could just overwrite it everytime too.
-            if assigns.is_empty() {
+            let args = if assigns.is_empty() {
                 *assigns = row.assigns.clone();
+
+                row.assigns
+            } else {
+                // Alternatives may bind the same variables in a different order, whereas
+                // arguments must follow the order of the parameters of the hoisted body.
+                assigns
+                    .iter()
+                    .map(|param| {
+                        row.assigns
+                            .iter()
+                            .find(|arg| arg.assigned == param.assigned)
+                            .expect("alternatives bind the same variables")
+                            .clone()
+                    })
+                    .collect_vec()
+            };
+
+            let Some(guard) = row.guard else {
+                return DecisionTree::HoistedLeaf(row.then, args);
+            };
+
+            let Some((guard_assigns, _, _)) = then_map.get_mut(&guard) else {
+                unreachable!()
+            };
+
+            if guard_assigns.is_empty() {
+                *guard_assigns = args.clone();
             }
 
-            return DecisionTree::HoistedLeaf(row.then, row.assigns);
+            // Guarded clauses aren't accounted for by exhaustiveness checks, so there's always
+            // a clause to fall back to when the guard doesn't hold.
+            assert!(!fallback.is_empty(), "guarded clause without fallback");
+
+            let otherwise =
+                self.do_build_tree(subject_tipo, PatternMatrix { rows: fallback }, then_map);
+
+            return DecisionTree::GuardedLeaf {
+                guard,
+                then: row.then,
+                assigns: args,
+                otherwise: otherwise.into(),
+            };
         };
 
         let mut longest_elems_no_tail = None;
@@ -1373,7 +1507,10 @@ mod tester {
         },
         builtins,
         expr::TypedExpr,
-        gen_uplc::{decision_tree::TreeGen, interner::AirInterner},
+        gen_uplc::{
+            decision_tree::{Assigned, DecisionTree, TreeGen},
+            interner::AirInterner,
+        },
         parser,
         tipo::error::{Error, Warning},
         utils, IdGenerator,
@@ -1692,4 +1829,287 @@ mod tester {
 
         println!("{}", tree);
     }
+
+    #[test]
+    fn alternatives_share_body() {
+        let source_code = r#"
+            test thing(){
+                when [1, 2, 3] is {
+                  [a, 1] | [1, a] -> a == 2
+                  [a, b] | [b, a, _] -> a + b == 3
+                  _ -> False
+                }
+            }
+        "#;
+
+        let (_, ast) = check(parse(source_code)).unwrap();
+
+        let Definition::Test(function) = &ast.definitions[0] else {
+            panic!()
+        };
+
+        let TypedExpr::When {
+            clauses, subject, ..
+        } = &function.body
+        else {
+            panic!()
+        };
+
+        let mut air_interner = AirInterner::new();
+
+        let data_types = IndexMap::new();
+
+        let pattern = TypedPattern::Discard {
+            name: "_".to_string(),
+            location: Span::empty(),
+        };
+
+        let tree_gen = TreeGen::new(&mut air_interner, &data_types, &pattern);
+
+        let tree = tree_gen.build_tree(&subject.tipo(), clauses);
+
+        fn collect<'a>(
+            tree: &'a DecisionTree<'_>,
+            bodies: &mut Vec<(&'a String, Vec<&'a String>)>,
+            leaves: &mut Vec<(&'a String, Vec<&'a String>)>,
+        ) {
+            let names = |assigns: &'a [Assigned]| -> Vec<&'a String> {
+                assigns.iter().map(|a| &a.assigned).collect()
+            };
+
+            match tree {
+                DecisionTree::Switch { cases, default, .. } => {
+                    cases.iter().for_each(|(_, t)| collect(t, bodies, leaves));
+                    default.iter().for_each(|t| collect(t, bodies, leaves));
+                }
+                DecisionTree::ListSwitch {
+                    cases,
+                    tail_cases,
+                    default,
+                    ..
+                } => {
+                    cases
+                        .iter()
+                        .chain(tail_cases)
+                        .for_each(|(_, t)| collect(t, bodies, leaves));
+                    default.iter().for_each(|t| collect(t, bodies, leaves));
+                }
+                DecisionTree::HoistedLeaf(name, args) => leaves.push((name, names(args))),
+                DecisionTree::GuardedLeaf {
+                    guard,
+                    then,
+                    assigns,
+                    otherwise,
+                } => {
+                    leaves.push((guard, names(assigns)));
+                    leaves.push((then, names(assigns)));
+                    collect(otherwise, bodies, leaves);
+                }
+                DecisionTree::HoistThen {
+                    name,
+                    assigns,
+                    pattern,
+                    ..
+                } => {
+                    bodies.push((name, names(assigns)));
+                    collect(pattern, bodies, leaves);
+                }
+            }
+        }
+
+        let mut bodies = vec![];
+        let mut leaves = vec![];
+
+        collect(&tree, &mut bodies, &mut leaves);
+
+        // One body per clause, not per alternative.
+        assert_eq!(bodies.len(), 3, "{tree}");
+
+        for (name, args) in leaves.iter() {
+            let (_, params) = bodies.iter().find(|(body, _)| body == name).unwrap();
+            assert_eq!(args, params, "{tree}");
+        }
+
+        // Both alternative clauses have their body reached from several leaves.
+        let shared = bodies
+            .iter()
+            .filter(|(body, _)| leaves.iter().filter(|(leaf, _)| leaf == body).count() >= 2)
+            .count();
+
+        assert_eq!(shared, 2, "{tree}");
+    }
+
+    #[test]
+    fn guards_fall_through() {
+        let source_code = r#"
+            test thing(){
+                when [1, 2, 3] is {
+                  [a, ..] if a > 1 -> True
+                  [_, b, ..] if b > 1 -> True
+                  _ -> False
+                }
+            }
+        "#;
+
+        let (_, ast) = check(parse(source_code)).unwrap();
+
+        let Definition::Test(function) = &ast.definitions[0] else {
+            panic!()
+        };
+
+        let TypedExpr::When {
+            clauses, subject, ..
+        } = &function.body
+        else {
+            panic!()
+        };
+
+        let mut air_interner = AirInterner::new();
+
+        let data_types = IndexMap::new();
+
+        let pattern = TypedPattern::Discard {
+            name: "_".to_string(),
+            location: Span::empty(),
+        };
+
+        let tree_gen = TreeGen::new(&mut air_interner, &data_types, &pattern);
+
+        let tree = tree_gen.build_tree(&subject.tipo(), clauses);
+
+        let tree = tree.to_string();
+
+        assert!(tree.contains("GuardedLeaf("), "{tree}");
+
+        // Three bodies and two guards, each hoisted once.
+        assert_eq!(tree.matches("HoistThen(").count(), 5, "{tree}");
+    }
+
+    #[test]
+    fn literals_in_lists_hoisted_above_all_leaves() {
+        let source_code = r#"
+            test thing(){
+                when [#"deadbeef", "ko"] is {
+                  ["ok", ..] -> True
+                  [#"deadbeef", "ko"] -> True
+                  [_, ..] -> False
+                  [] -> False
+                }
+            }
+        "#;
+
+        let (_, ast) = check(parse(source_code)).unwrap();
+
+        let Definition::Test(function) = &ast.definitions[0] else {
+            panic!()
+        };
+
+        let TypedExpr::When {
+            clauses, subject, ..
+        } = &function.body
+        else {
+            panic!()
+        };
+
+        let mut air_interner = AirInterner::new();
+
+        let data_types = IndexMap::new();
+
+        let pattern = TypedPattern::Discard {
+            name: "_".to_string(),
+            location: Span::empty(),
+        };
+
+        let tree_gen = TreeGen::new(&mut air_interner, &data_types, &pattern);
+
+        let tree = tree_gen.build_tree(&subject.tipo(), clauses);
+
+        // Bodies reached from several branches of the list switch must be hoisted above all of
+        // them, or they'd end up out of scope in some branches.
+        fn assert_in_scope<'a>(tree: &'a DecisionTree<'_>, hoisted: &mut Vec<&'a String>) {
+            match tree {
+                DecisionTree::Switch { cases, default, .. } => {
+                    cases.iter().for_each(|(_, t)| assert_in_scope(t, hoisted));
+                    default.iter().for_each(|t| assert_in_scope(t, hoisted));
+                }
+                DecisionTree::ListSwitch {
+                    cases,
+                    tail_cases,
+                    default,
+                    ..
+                } => {
+                    cases
+                        .iter()
+                        .chain(tail_cases)
+                        .for_each(|(_, t)| assert_in_scope(t, hoisted));
+                    default.iter().for_each(|t| assert_in_scope(t, hoisted));
+                }
+                DecisionTree::HoistedLeaf(name, _) => {
+                    assert!(hoisted.contains(&name), "{name} out of scope")
+                }
+                DecisionTree::GuardedLeaf {
+                    guard,
+                    then,
+                    otherwise,
+                    ..
+                } => {
+                    assert!(hoisted.contains(&guard), "{guard} out of scope");
+                    assert!(hoisted.contains(&then), "{then} out of scope");
+                    assert_in_scope(otherwise, hoisted);
+                }
+                DecisionTree::HoistThen { name, pattern, .. } => {
+                    hoisted.push(name);
+                    assert_in_scope(pattern, hoisted);
+                    hoisted.pop();
+                }
+            }
+        }
+
+        assert_in_scope(&tree, &mut vec![]);
+    }
+
+    #[test]
+    fn bytearray_literals() {
+        let source_code = r#"
+            test thing(){
+                when #"deadbeef" is {
+                  #"deadbeef" -> True
+                  "ok" -> False
+                  _ -> False
+                }
+            }
+        "#;
+
+        let (_, ast) = check(parse(source_code)).unwrap();
+
+        let Definition::Test(function) = &ast.definitions[0] else {
+            panic!()
+        };
+
+        let TypedExpr::When {
+            clauses, subject, ..
+        } = &function.body
+        else {
+            panic!()
+        };
+
+        let mut air_interner = AirInterner::new();
+
+        let data_types = IndexMap::new();
+
+        let pattern = TypedPattern::Discard {
+            name: "_".to_string(),
+            location: Span::empty(),
+        };
+
+        let tree_gen = TreeGen::new(&mut air_interner, &data_types, &pattern);
+
+        let tree = tree_gen.build_tree(&subject.tipo(), clauses);
+
+        let tree = tree.to_string();
+
+        assert!(tree.contains("Switch("), "{tree}");
+        assert!(tree.contains("Bytes([222, 173, 190, 239])"), "{tree}");
+        assert!(tree.contains("Bytes([111, 107])"), "{tree}");
+    }
 }
@@ -0,0 +1,739 @@
+//! A stable serialisation of the AIR, for external tools (e.g. cost analysers or verifiers) to
+//! consume the compiler's intermediate representation without linking against its internals.
+//!
+//! The exported tree is the one handed over to UPLC code generation: functions are already
+//! hoisted and monomorphised. Nodes are tagged by their 'node' field, and types are given as
+//! they'd be written in Aiken. Any breaking change to the format bumps [`AIR_FORMAT_VERSION`].
+
+use super::{air::ExpectLevel, tree::AirTree};
+use crate::{
+    ast::{BinOp, Bls12_381Point, Curve, UnOp},
+    tipo::{Type, ValueConstructorVariant},
+};
+use std::rc::Rc;
+
+pub const AIR_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct AirExport {
+    pub version: u32,
+    /// The validator's title, as '{module}.{validator}'.
+    pub title: String,
+    pub tree: Node,
+}
+
+impl AirExport {
+    pub fn new(title: impl ToString, tree: &AirTree) -> Self {
+        AirExport {
+            version: AIR_FORMAT_VERSION,
+            title: title.to_string(),
+            tree: tree.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "node", rename_all = "snake_case")]
+pub enum Node {
+    // Statements
+    Let {
+        name: String,
+        value: Box<Node>,
+        then: Box<Node>,
+    },
+    SoftCastLet {
+        name: String,
+        #[serde(rename = "type")]
+        tipo: String,
+        value: Box<Node>,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    DefineFunc {
+        name: String,
+        module: String,
+        variant: String,
+        params: Vec<String>,
+        recursive: bool,
+        recursive_nonstatic_params: Vec<String>,
+        body: Box<Node>,
+        then: Box<Node>,
+    },
+    DefineCyclicFuncs {
+        name: String,
+        module: String,
+        variant: String,
+        functions: Vec<Function>,
+        then: Box<Node>,
+    },
+    AssertBool {
+        is_true: bool,
+        value: Box<Node>,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    FieldsExpose {
+        fields: Vec<Field>,
+        record: Box<Node>,
+        is_expect: bool,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    ListAccessor {
+        #[serde(rename = "type")]
+        tipo: String,
+        names: Vec<String>,
+        tail: bool,
+        list: Box<Node>,
+        expect: Expect,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    TupleAccessor {
+        #[serde(rename = "type")]
+        tipo: String,
+        names: Vec<String>,
+        tuple: Box<Node>,
+        is_expect: bool,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    PairAccessor {
+        #[serde(rename = "type")]
+        tipo: String,
+        fst: Option<String>,
+        snd: Option<String>,
+        pair: Box<Node>,
+        is_expect: bool,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    ExtractField {
+        #[serde(rename = "type")]
+        tipo: String,
+        arg: Box<Node>,
+    },
+    FieldsEmpty {
+        constr: Box<Node>,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    ListEmpty {
+        list: Box<Node>,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    NoOp {
+        then: Box<Node>,
+    },
+
+    // Expressions
+    Int {
+        value: String,
+    },
+    String {
+        value: String,
+    },
+    ByteArray {
+        /// Base16-encoded.
+        bytes: String,
+    },
+    CurvePoint {
+        /// 'G1' or 'G2'.
+        group: String,
+        /// Base16-encoded, compressed.
+        point: String,
+    },
+    Bool {
+        value: bool,
+    },
+    List {
+        #[serde(rename = "type")]
+        tipo: String,
+        tail: bool,
+        items: Vec<Node>,
+    },
+    Tuple {
+        #[serde(rename = "type")]
+        tipo: String,
+        items: Vec<Node>,
+    },
+    Pair {
+        #[serde(rename = "type")]
+        tipo: String,
+        fst: Box<Node>,
+        snd: Box<Node>,
+    },
+    Void,
+    Var {
+        name: String,
+        variant: String,
+        #[serde(rename = "type")]
+        tipo: String,
+        origin: Origin,
+    },
+    Call {
+        #[serde(rename = "type")]
+        tipo: String,
+        func: Box<Node>,
+        args: Vec<Node>,
+    },
+    Fn {
+        params: Vec<String>,
+        body: Box<Node>,
+        allow_inline: bool,
+    },
+    Builtin {
+        /// The builtin's name, as in UPLC (e.g. 'addInteger').
+        func: String,
+        #[serde(rename = "type")]
+        tipo: String,
+        args: Vec<Node>,
+    },
+    BinOp {
+        op: String,
+        #[serde(rename = "type")]
+        tipo: String,
+        argument_type: String,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+    UnOp {
+        op: String,
+        arg: Box<Node>,
+    },
+    CastFromData {
+        #[serde(rename = "type")]
+        tipo: String,
+        full_cast: bool,
+        value: Box<Node>,
+    },
+    CastToData {
+        #[serde(rename = "type")]
+        tipo: String,
+        value: Box<Node>,
+    },
+    When {
+        #[serde(rename = "type")]
+        tipo: String,
+        subject_name: String,
+        subject_type: String,
+        subject: Box<Node>,
+        clauses: Box<Node>,
+    },
+    Clause {
+        subject_name: String,
+        subject_type: String,
+        pattern: Box<Node>,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    ListClause {
+        subject_type: String,
+        tail_name: String,
+        next_tail_name: Option<(String, String)>,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    If {
+        #[serde(rename = "type")]
+        tipo: String,
+        condition: Box<Node>,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+    },
+    Constr {
+        tag: usize,
+        #[serde(rename = "type")]
+        tipo: String,
+        args: Vec<Node>,
+    },
+    RecordUpdate {
+        highest_index: usize,
+        fields: Vec<FieldUpdate>,
+        #[serde(rename = "type")]
+        tipo: String,
+        record: Box<Node>,
+        args: Vec<Node>,
+    },
+    Error {
+        #[serde(rename = "type")]
+        tipo: String,
+        validator: bool,
+    },
+    Trace {
+        #[serde(rename = "type")]
+        tipo: String,
+        msg: Box<Node>,
+        then: Box<Node>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Function {
+    pub params: Vec<String>,
+    pub body: Node,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Field {
+    pub index: usize,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub tipo: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FieldUpdate {
+    pub index: usize,
+    #[serde(rename = "type")]
+    pub tipo: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expect {
+    Full,
+    Items,
+    None,
+}
+
+/// What a variable refers to.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Origin {
+    Local,
+    Constant {
+        module: String,
+        name: String,
+    },
+    Function {
+        module: String,
+        name: String,
+        builtin: Option<String>,
+    },
+    Constructor {
+        module: String,
+        name: String,
+        arity: usize,
+    },
+}
+
+fn tipo(tipo: &Rc<Type>) -> String {
+    tipo.to_pretty(0)
+}
+
+fn boxed(tree: &AirTree) -> Box<Node> {
+    Box::new(tree.into())
+}
+
+fn nodes(trees: &[AirTree]) -> Vec<Node> {
+    trees.iter().map(Node::from).collect()
+}
+
+impl From<&AirTree> for Node {
+    fn from(tree: &AirTree) -> Self {
+        match tree {
+            AirTree::Let { name, value, then } => Node::Let {
+                name: name.clone(),
+                value: boxed(value),
+                then: boxed(then),
+            },
+            AirTree::SoftCastLet {
+                name,
+                tipo: t,
+                value,
+                then,
+                otherwise,
+            } => Node::SoftCastLet {
+                name: name.clone(),
+                tipo: tipo(t),
+                value: boxed(value),
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::DefineFunc {
+                func_name,
+                module_name,
+                variant_name,
+                params,
+                recursive,
+                recursive_nonstatic_params,
+                func_body,
+                then,
+            } => Node::DefineFunc {
+                name: func_name.clone(),
+                module: module_name.clone(),
+                variant: variant_name.clone(),
+                params: params.clone(),
+                recursive: *recursive,
+                recursive_nonstatic_params: recursive_nonstatic_params.clone(),
+                body: boxed(func_body),
+                then: boxed(then),
+            },
+            AirTree::DefineCyclicFuncs {
+                func_name,
+                module_name,
+                variant_name,
+                contained_functions,
+                then,
+            } => Node::DefineCyclicFuncs {
+                name: func_name.clone(),
+                module: module_name.clone(),
+                variant: variant_name.clone(),
+                functions: contained_functions
+                    .iter()
+                    .map(|(params, body)| Function {
+                        params: params.clone(),
+                        body: body.into(),
+                    })
+                    .collect(),
+                then: boxed(then),
+            },
+            AirTree::AssertBool {
+                is_true,
+                value,
+                then,
+                otherwise,
+            } => Node::AssertBool {
+                is_true: *is_true,
+                value: boxed(value),
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::FieldsExpose {
+                indices,
+                record,
+                is_expect,
+                then,
+                otherwise,
+            } => Node::FieldsExpose {
+                fields: indices
+                    .iter()
+                    .map(|(index, name, t)| Field {
+                        index: *index,
+                        name: name.clone(),
+                        tipo: tipo(t),
+                    })
+                    .collect(),
+                record: boxed(record),
+                is_expect: *is_expect,
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::ListAccessor {
+                tipo: t,
+                names,
+                tail,
+                list,
+                expect_level,
+                then,
+                otherwise,
+            } => Node::ListAccessor {
+                tipo: tipo(t),
+                names: names.clone(),
+                tail: *tail,
+                list: boxed(list),
+                expect: match expect_level {
+                    ExpectLevel::Full => Expect::Full,
+                    ExpectLevel::Items => Expect::Items,
+                    ExpectLevel::None => Expect::None,
+                },
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::TupleAccessor {
+                names,
+                tipo: t,
+                tuple,
+                is_expect,
+                then,
+                otherwise,
+            } => Node::TupleAccessor {
+                tipo: tipo(t),
+                names: names.clone(),
+                tuple: boxed(tuple),
+                is_expect: *is_expect,
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::PairAccessor {
+                fst,
+                snd,
+                tipo: t,
+                is_expect,
+                pair,
+                then,
+                otherwise,
+            } => Node::PairAccessor {
+                tipo: tipo(t),
+                fst: fst.clone(),
+                snd: snd.clone(),
+                pair: boxed(pair),
+                is_expect: *is_expect,
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::ExtractField { tipo: t, arg } => Node::ExtractField {
+                tipo: tipo(t),
+                arg: boxed(arg),
+            },
+            AirTree::FieldsEmpty {
+                constr,
+                then,
+                otherwise,
+            } => Node::FieldsEmpty {
+                constr: boxed(constr),
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::ListEmpty {
+                list,
+                then,
+                otherwise,
+            } => Node::ListEmpty {
+                list: boxed(list),
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::NoOp { then } => Node::NoOp { then: boxed(then) },
+            AirTree::Int { value } => Node::Int {
+                value: value.clone(),
+            },
+            AirTree::String { value } => Node::String {
+                value: value.clone(),
+            },
+            AirTree::ByteArray { bytes } => Node::ByteArray {
+                bytes: hex::encode(bytes),
+            },
+            AirTree::CurvePoint { point } => Node::CurvePoint {
+                group: match point {
+                    Curve::Bls12_381(Bls12_381Point::G1(..)) => "G1",
+                    Curve::Bls12_381(Bls12_381Point::G2(..)) => "G2",
+                }
+                .to_string(),
+                point: hex::encode(point.compress()),
+            },
+            AirTree::Bool { value } => Node::Bool { value: *value },
+            AirTree::List {
+                tipo: t,
+                tail,
+                items,
+            } => Node::List {
+                tipo: tipo(t),
+                tail: *tail,
+                items: nodes(items),
+            },
+            AirTree::Tuple { tipo: t, items } => Node::Tuple {
+                tipo: tipo(t),
+                items: nodes(items),
+            },
+            AirTree::Pair { tipo: t, fst, snd } => Node::Pair {
+                tipo: tipo(t),
+                fst: boxed(fst),
+                snd: boxed(snd),
+            },
+            AirTree::Void => Node::Void,
+            AirTree::Var {
+                constructor,
+                name,
+                variant_name,
+            } => Node::Var {
+                name: name.clone(),
+                variant: variant_name.clone(),
+                tipo: tipo(&constructor.tipo),
+                origin: match &constructor.variant {
+                    ValueConstructorVariant::LocalVariable { .. } => Origin::Local,
+                    ValueConstructorVariant::ModuleConstant { module, name, .. } => {
+                        Origin::Constant {
+                            module: module.clone(),
+                            name: name.clone(),
+                        }
+                    }
+                    ValueConstructorVariant::ModuleFn {
+                        module,
+                        name,
+                        builtin,
+                        ..
+                    } => Origin::Function {
+                        module: module.clone(),
+                        name: name.clone(),
+                        builtin: builtin.map(|builtin| builtin.to_string()),
+                    },
+                    ValueConstructorVariant::Record {
+                        module,
+                        name,
+                        arity,
+                        ..
+                    } => Origin::Constructor {
+                        module: module.clone(),
+                        name: name.clone(),
+                        arity: *arity,
+                    },
+                },
+            },
+            AirTree::Call {
+                tipo: t,
+                func,
+                args,
+            } => Node::Call {
+                tipo: tipo(t),
+                func: boxed(func),
+                args: nodes(args),
+            },
+            AirTree::Fn {
+                params,
+                func_body,
+                allow_inline,
+            } => Node::Fn {
+                params: params.clone(),
+                body: boxed(func_body),
+                allow_inline: *allow_inline,
+            },
+            AirTree::Builtin {
+                func,
+                tipo: t,
+                args,
+            } => Node::Builtin {
+                func: func.to_string(),
+                tipo: tipo(t),
+                args: nodes(args),
+            },
+            AirTree::BinOp {
+                name,
+                tipo: t,
+                left,
+                right,
+                argument_tipo,
+            } => Node::BinOp {
+                op: bin_op(name).to_string(),
+                tipo: tipo(t),
+                argument_type: tipo(argument_tipo),
+                left: boxed(left),
+                right: boxed(right),
+            },
+            AirTree::UnOp { op, arg } => Node::UnOp {
+                op: match op {
+                    UnOp::Not => "!",
+                    UnOp::Negate => "-",
+                }
+                .to_string(),
+                arg: boxed(arg),
+            },
+            AirTree::CastFromData {
+                tipo: t,
+                value,
+                full_cast,
+            } => Node::CastFromData {
+                tipo: tipo(t),
+                full_cast: *full_cast,
+                value: boxed(value),
+            },
+            AirTree::CastToData { tipo: t, value } => Node::CastToData {
+                tipo: tipo(t),
+                value: boxed(value),
+            },
+            AirTree::When {
+                tipo: t,
+                subject_name,
+                subject,
+                subject_tipo,
+                clauses,
+            } => Node::When {
+                tipo: tipo(t),
+                subject_name: subject_name.clone(),
+                subject_type: tipo(subject_tipo),
+                subject: boxed(subject),
+                clauses: boxed(clauses),
+            },
+            AirTree::Clause {
+                subject_tipo,
+                subject_name,
+                pattern,
+                then,
+                otherwise,
+            } => Node::Clause {
+                subject_name: subject_name.clone(),
+                subject_type: tipo(subject_tipo),
+                pattern: boxed(pattern),
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::ListClause {
+                subject_tipo,
+                tail_name,
+                next_tail_name,
+                then,
+                otherwise,
+            } => Node::ListClause {
+                subject_type: tipo(subject_tipo),
+                tail_name: tail_name.clone(),
+                next_tail_name: next_tail_name.clone(),
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::If {
+                tipo: t,
+                condition,
+                then,
+                otherwise,
+            } => Node::If {
+                tipo: tipo(t),
+                condition: boxed(condition),
+                then: boxed(then),
+                otherwise: boxed(otherwise),
+            },
+            AirTree::Constr { tag, tipo: t, args } => Node::Constr {
+                tag: *tag,
+                tipo: tipo(t),
+                args: nodes(args),
+            },
+            AirTree::RecordUpdate {
+                highest_index,
+                indices,
+                tipo: t,
+                record,
+                args,
+            } => Node::RecordUpdate {
+                highest_index: *highest_index,
+                fields: indices
+                    .iter()
+                    .map(|(index, t)| FieldUpdate {
+                        index: *index,
+                        tipo: tipo(t),
+                    })
+                    .collect(),
+                tipo: tipo(t),
+                record: boxed(record),
+                args: nodes(args),
+            },
+            AirTree::ErrorTerm { tipo: t, validator } => Node::Error {
+                tipo: tipo(t),
+                validator: *validator,
+            },
+            AirTree::Trace { tipo: t, msg, then } => Node::Trace {
+                tipo: tipo(t),
+                msg: boxed(msg),
+                then: boxed(then),
+            },
+        }
+    }
+}
+
+fn bin_op(op: &BinOp) -> &'static str {
+    match op {
+        BinOp::And => "&&",
+        BinOp::Or => "||",
+        BinOp::Eq => "==",
+        BinOp::NotEq => "!=",
+        BinOp::LtInt => "<",
+        BinOp::LtEqInt => "<=",
+        BinOp::GtEqInt => ">=",
+        BinOp::GtInt => ">",
+        BinOp::AddInt => "+",
+        BinOp::SubInt => "-",
+        BinOp::MultInt => "*",
+        BinOp::DivInt => "/",
+        BinOp::ModInt => "%",
+    }
+}
@@ -5,6 +5,7 @@ use std::sync::{
 
 pub mod ast;
 pub mod builtins;
+pub mod coverage;
 pub mod error;
 pub mod expr;
 pub mod format;
@@ -14,6 +15,7 @@ pub mod line_numbers;
 pub mod parser;
 pub mod plutus_version;
 pub mod pretty;
+pub mod smt;
 pub mod test_framework;
 pub mod tipo;
 pub mod utils;
@@ -32,6 +34,11 @@ impl IdGenerator {
     pub fn next(&self) -> u64 {
         self.id.fetch_add(1, Ordering::Relaxed)
     }
+
+    /// Make sure that ids generated from now on are greater than the given one.
+    pub fn skip_past(&self, id: u64) {
+        self.id.fetch_max(id + 1, Ordering::Relaxed);
+    }
 }
 
 #[macro_export]
@@ -19,30 +19,61 @@ pub use expr::parser as expression;
 use extra::ModuleExtra;
 use indexmap::IndexMap;
 pub use pattern::parser as pattern;
+use std::collections::BTreeSet;
 
 pub fn module(
     src: &str,
     kind: ast::ModuleKind,
 ) -> Result<(ast::UntypedModule, ModuleExtra), Vec<ParseError>> {
-    let lexer::LexInfo { tokens, extra } = lexer::run(src)?;
+    match module_with_recovery(src, kind) {
+        (Some(module), errors) if errors.is_empty() => Ok(module),
+        (_, errors) => Err(errors),
+    }
+}
+
+/// Parse a module, recovering from syntax errors at definition boundaries so that all of them
+/// are reported at once. Alongside errors, this returns the module made of the definitions that
+/// did parse, unless the source couldn't be tokenized at all.
+pub fn module_with_recovery(
+    src: &str,
+    kind: ast::ModuleKind,
+) -> (Option<(ast::UntypedModule, ModuleExtra)>, Vec<ParseError>) {
+    let lexer::LexInfo { tokens, extra } = match lexer::run(src) {
+        Ok(info) => info,
+        Err(errors) => return (None, errors),
+    };
 
     let stream = chumsky::Stream::from_iter(ast::Span::create(tokens.len(), 1), tokens.into_iter());
 
+    // Imports guarded by an attribute are kept apart, for they may not always be there.
+    let guarded = extra
+        .cfg
+        .iter()
+        .map(|attribute| attribute.target)
+        .collect::<BTreeSet<_>>();
+
     let definitions = import()
         .repeated()
-        .map(|imports| {
+        .map(move |imports| {
             let mut store = IndexMap::new();
 
             for import in imports.into_iter() {
-                let key = (import.module, import.as_name);
+                let guard = Some(import.location.start).filter(|start| guarded.contains(start));
+                let key = (import.module, import.as_name, guard);
                 match store.remove(&key) {
                     None => {
                         store.insert(key, (import.location, import.unqualified));
                     }
                     Some((location, unqualified)) => {
-                        let mut merged_unqualified = Vec::new();
-                        merged_unqualified.extend(unqualified);
-                        merged_unqualified.extend(import.unqualified);
+                        let mut merged_unqualified: Vec<ast::UnqualifiedImport> = Vec::new();
+                        for unqualified in unqualified.into_iter().chain(import.unqualified) {
+                            if !merged_unqualified.iter().any(|other| {
+                                other.name == unqualified.name
+                                    && other.as_name == unqualified.as_name
+                            }) {
+                                merged_unqualified.push(unqualified);
+                            }
+                        }
                         store.insert(key, (location, merged_unqualified));
                     }
                 }
@@ -50,7 +81,7 @@ pub fn module(
 
             store
                 .into_iter()
-                .map(|((module, as_name), (location, unqualified))| {
+                .map(|((module, as_name, _), (location, unqualified))| {
                     ast::Definition::Use(ast::Use {
                         module,
                         as_name,
@@ -61,7 +92,12 @@ pub fn module(
                 })
                 .collect::<Vec<ast::UntypedDefinition>>()
         })
-        .then(definition().repeated())
+        .then(
+            // On a syntax error, skip ahead until the next token which starts a valid definition.
+            definition()
+                .recover_with(skip_then_retry_until([]))
+                .repeated(),
+        )
         .map(|(imports, others)| {
             let mut defs = Vec::new();
             defs.extend(imports);
@@ -69,11 +105,15 @@ pub fn module(
             defs
         })
         .then_ignore(end())
-        .parse(stream)?;
+        .parse_recovery(stream);
+
+    let (Some(definitions), mut errors) = definitions else {
+        return (None, definitions.1);
+    };
 
     let lines = LineNumbers::new(src);
 
-    let module = ast::UntypedModule {
+    let mut module = ast::UntypedModule {
         kind,
         lines,
         definitions,
@@ -82,12 +122,16 @@ pub fn module(
         type_info: (),
     };
 
-    Ok((module, extra))
+    let misplaced = module.strip_cfg(&extra.cfg, |_| true);
+    errors.extend(misplaced.into_iter().map(ParseError::misplaced_attribute));
+
+    (Some((module, extra)), errors)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::assert_module;
+    use crate::{assert_module, ast, format::Formatter, parser::error::ErrorKind};
+    use indoc::indoc;
 
     #[test]
     fn merge_imports() {
@@ -99,6 +143,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn merge_imports_dedup() {
+        assert_module!(
+            r#"
+            use aiken/list.{bar, foo}
+            use aiken/list.{foo as qux, foo}
+            "#
+        );
+    }
+
+    #[test]
+    fn merge_imports_cfg() {
+        let src = indoc! {r#"
+            use aiken/list.{foo}
+            @cfg(feature = "debug")
+            use aiken/list.{bar}
+            use aiken/list.{baz}
+        "#};
+
+        let (module, extra) = super::module(src, ast::ModuleKind::Lib).unwrap();
+
+        assert_eq!(module.definitions.len(), 2);
+        assert_eq!(extra.cfg.len(), 1);
+    }
+
     #[test]
     fn windows_newline() {
         assert_module!("use aiken/list\r\n");
@@ -164,4 +233,112 @@ mod tests {
             "#
         );
     }
+
+    #[test]
+    fn strip_disabled_cfg() {
+        let src = indoc! {r#"
+            @cfg(feature = "debug")
+            fn debug() {
+              True
+            }
+
+            fn foo(n: Int) {
+              @cfg(feature = "debug")
+              trace @"foo": n
+              @cfg(feature = "debug")
+              expect n > 0
+              @cfg(feature = "extra")
+              expect n > 1
+              n
+            }
+        "#};
+
+        let (mut module, extra) = super::module(src, ast::ModuleKind::Lib).unwrap();
+
+        assert!(module
+            .strip_cfg(&extra.cfg, |feature| feature == "extra")
+            .is_empty());
+
+        assert_eq!(
+            Formatter::new()
+                .definitions(&module.definitions)
+                .to_pretty_string(80),
+            indoc! {r#"
+                fn foo(n: Int) {
+                  expect n > 1
+                  n
+                }"#}
+        );
+    }
+
+    #[test]
+    fn misplaced_cfg() {
+        let src = indoc! {r#"
+            fn foo() {
+              @cfg(feature = "debug")
+              42
+            }
+        "#};
+
+        let errors = super::module(src, ast::ModuleKind::Lib).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ErrorKind::MisplacedAttribute);
+    }
+
+    #[test]
+    fn use_without_backpassing() {
+        let src = indoc! {r#"
+            fn foo() {
+              use x = bar()
+              x
+            }
+        "#};
+
+        let errors = super::module(src, ast::ModuleKind::Lib).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ErrorKind::UseWithoutBackpassing);
+    }
+
+    #[test]
+    fn recover_from_syntax_errors() {
+        let src = indoc! {r#"
+            fn foo( {
+              1
+            }
+
+            pub fn bar() {
+              2
+            }
+
+            fn baz() {
+              let = 3
+            }
+
+            const qux = 4
+        "#};
+
+        let (module, errors) = super::module_with_recovery(src, ast::ModuleKind::Lib);
+
+        assert_eq!(errors.len(), 2, "{errors:#?}");
+        assert!(errors[0].span.start < errors[1].span.start);
+
+        let (module, _) = module.expect("recovered module");
+        let names = module
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                ast::Definition::Fn(f) => Some(f.name.as_str()),
+                ast::Definition::ModuleConstant(c) => Some(c.name.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["bar", "qux"]);
+        assert_eq!(
+            super::module(src, ast::ModuleKind::Lib).unwrap_err().len(),
+            2
+        );
+    }
 }
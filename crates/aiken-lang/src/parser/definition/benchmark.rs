@@ -0,0 +1,67 @@
+use crate::{
+    ast,
+    ast::OnTestFailure,
+    expr::UntypedExpr,
+    parser::{definition::test::via, error::ParseError, expr, token::Token},
+};
+use chumsky::prelude::*;
+
+pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError> {
+    // 'bench' is only a keyword at the start of a definition, and remains a valid name elsewhere.
+    select! {Token::Name {name} if name == "bench" => name}
+        .ignore_then(select! {Token::Name {name} => name})
+        .then(
+            via()
+                .separated_by(just(Token::Comma))
+                .allow_trailing()
+                .delimited_by(just(Token::LeftParen), just(Token::RightParen)),
+        )
+        .map_with_span(|name, span| (name, span))
+        .then(
+            expr::sequence()
+                .or_not()
+                .delimited_by(just(Token::LeftBrace), just(Token::RightBrace)),
+        )
+        .map_with_span(|(((name, arguments), span_end), body), span| {
+            ast::UntypedDefinition::Benchmark(ast::Function {
+                arguments,
+                body: body.unwrap_or_else(|| UntypedExpr::todo(None, span)),
+                doc: None,
+                location: span_end,
+                end_position: span.end - 1,
+                name,
+                public: false,
+                return_annotation: None,
+                return_type: (),
+                on_test_failure: OnTestFailure::FailImmediately,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_definition;
+
+    #[test]
+    fn bench_as_variable() {
+        assert_definition!(
+            r#"
+            fn foo() {
+                let bench = 1
+                bench
+            }
+            "#
+        );
+    }
+
+    #[test]
+    fn def_benchmark() {
+        assert_definition!(
+            r#"
+            bench foo(xs via list_of_size) {
+                list.length(xs)
+            }
+            "#
+        );
+    }
+}
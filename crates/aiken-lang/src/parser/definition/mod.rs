@@ -1,5 +1,6 @@
 use chumsky::prelude::*;
 
+mod benchmark;
 pub mod constant;
 mod data_type;
 mod function;
@@ -10,6 +11,7 @@ mod validator;
 
 use super::{error::ParseError, token::Token};
 use crate::ast;
+pub use benchmark::parser as benchmark;
 pub use constant::parser as constant;
 pub use data_type::parser as data_type;
 pub use function::parser as function;
@@ -24,6 +26,7 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
         validator(),
         function(),
         test(),
+        benchmark(),
         constant(),
     ))
 }
@@ -1,5 +1,6 @@
 use crate::{
     ast::{CurveType, Span},
+    error::{Coded, ErrorCode},
     parser::token::Token,
 };
 use indoc::formatdoc;
@@ -83,16 +84,6 @@ impl ParseError {
         }
     }
 
-    pub fn deprecated_when_clause_guard(span: Span) -> Self {
-        Self {
-            kind: ErrorKind::DeprecatedWhenClause,
-            span,
-            while_parsing: None,
-            expected: HashSet::new(),
-            label: Some("deprecated"),
-        }
-    }
-
     pub fn point_not_on_curve(curve: CurveType, span: Span) -> Self {
         Self {
             kind: ErrorKind::PointNotOnCurve { curve },
@@ -159,6 +150,26 @@ impl ParseError {
         }
     }
 
+    pub fn misplaced_attribute(span: Span) -> Self {
+        Self {
+            kind: ErrorKind::MisplacedAttribute,
+            span,
+            while_parsing: None,
+            expected: HashSet::new(),
+            label: Some("doesn't apply to anything"),
+        }
+    }
+
+    pub fn use_without_backpassing(span: Span) -> Self {
+        Self {
+            kind: ErrorKind::UseWithoutBackpassing,
+            span,
+            while_parsing: None,
+            expected: HashSet::new(),
+            label: Some("expected '<-'"),
+        }
+    }
+
     pub fn match_string(span: Span) -> Self {
         Self {
             kind: ErrorKind::PatternMatchOnString,
@@ -170,6 +181,28 @@ impl ParseError {
     }
 }
 
+impl Coded for ParseError {
+    fn error_code(&self) -> Option<ErrorCode> {
+        let code = match self.kind {
+            ErrorKind::UnexpectedEnd => 1,
+            ErrorKind::Unexpected(..) => 2,
+            ErrorKind::InvalidTupleIndex { .. } => 3,
+            ErrorKind::UnfinishedAssignmentRightHandSide => 4,
+            ErrorKind::PointNotOnCurve { .. } => 5,
+            ErrorKind::UnknownCurvePoint { .. } => 6,
+            ErrorKind::MalformedBase16Digits => 7,
+            ErrorKind::MalformedBase16StringLiteral => 8,
+            ErrorKind::HybridNotationInByteArray => 9,
+            ErrorKind::PatternMatchOnCurvePoint => 11,
+            ErrorKind::PatternMatchOnString => 12,
+            ErrorKind::MisplacedAttribute => 13,
+            ErrorKind::UseWithoutBackpassing => 14,
+        };
+
+        Some(ErrorCode(code))
+    }
+}
+
 impl PartialEq for ParseError {
     fn eq(&self, other: &Self) -> bool {
         self.kind == other.kind && self.span == other.span && self.label == other.label
@@ -274,13 +307,6 @@ pub enum ErrorKind {
     #[diagnostic(help("Either use decimal or hexadecimal notation, but don't mix them."))]
     HybridNotationInByteArray,
 
-    #[error("I found a now-deprecated clause guard in a when/is expression.")]
-    #[diagnostic(help("{}", formatdoc! {
-        r#"Clause guards have been removed from Aiken. They were underused, considered potentially harmful and created needless complexity in the compiler. If you were using clause guards, our apologies, but you can now update your code and move the clause guards patterns inside a nested if/else expression.
-        "#
-    }))]
-    DeprecatedWhenClause,
-
     #[error("I choked on a curve point in a bytearray pattern.")]
     #[diagnostic(help(
         "You can pattern-match on bytearrays just fine, but not on G1 nor G2 elements. Use if/else with an equality if you have to compare those."
@@ -292,6 +318,22 @@ pub enum ErrorKind {
         "You can pattern-match on bytearrays but not on strings. Note that I can parse utf-8 encoded bytearrays just fine, so you probably want to drop the extra '@' and only manipulate bytearrays wherever you need to. On-chain, strings shall be avoided as much as possible."
     ))]
     PatternMatchOnString,
+
+    #[error("I found an attribute that doesn't apply to anything.")]
+    #[diagnostic(help("{}", formatdoc! {
+        r#"Attributes such as {} must be placed right before a definition, or before a statement in a block (but not its last expression, which is the value of the block)."#,
+        "@cfg(feature = \"...\")".if_supports_color(Stdout, |s| s.purple()),
+    }))]
+    MisplacedAttribute,
+
+    #[error("I found a {} binding without a callback.", "use".if_supports_color(Stdout, |s| s.yellow()))]
+    #[diagnostic(help("{}", formatdoc! {
+        r#"{} turns the rest of the block into a callback passed as last argument to a function, as in {}. For plain bindings, use {} instead."#,
+        "use".if_supports_color(Stdout, |s| s.yellow()),
+        "use x <- f(a)".if_supports_color(Stdout, |s| s.cyan()),
+        "let".if_supports_color(Stdout, |s| s.yellow()),
+    }))]
+    UseWithoutBackpassing,
 }
 
 fn fmt_curve_type(curve: &CurveType) -> String {
@@ -32,6 +32,39 @@ pub fn let_(
         })
 }
 
+/// Gleam-style backpassing, `use x <- f(a)`; equivalent to `let x <- f(a)`.
+pub fn use_(
+    r: Recursive<'_, Token, UntypedExpr, ParseError>,
+) -> impl Parser<Token, UntypedExpr, Error = ParseError> + '_ {
+    just(Token::Use)
+        .ignore_then(assignment_patterns())
+        .then(
+            choice((just(Token::Equal), just(Token::LArrow)))
+                .map_with_span(|kind, span| (kind, span)),
+        )
+        .then(r.clone())
+        .validate(move |((patterns, (kind, kind_span)), value), span, emit| {
+            if kind != Token::LArrow {
+                emit(ParseError::use_without_backpassing(kind_span))
+            }
+
+            if matches!(value, UntypedExpr::Assignment { .. }) {
+                emit(ParseError::invalid_assignment_right_hand_side(span))
+            }
+
+            let patterns = patterns
+                .try_into()
+                .expect("We use at_least(1) so this should never be empty");
+
+            UntypedExpr::Assignment {
+                location: span,
+                value: Box::new(value),
+                patterns,
+                kind: ast::AssignmentKind::Let { backpassing: true },
+            }
+        })
+}
+
 fn assignment_patterns() -> impl Parser<Token, Vec<ast::AssignmentPattern>, Error = ParseError> {
     assignment_pattern()
         .separated_by(just(Token::Comma))
@@ -98,6 +131,11 @@ mod tests {
         assert_expr!("let thing = [ 1, 2, a ]");
     }
 
+    #[test]
+    fn use_backpassing() {
+        assert_expr!("use x, y <- foo(a)");
+    }
+
     #[test]
     fn expect() {
         assert_expr!("expect Some(x) = something.field");
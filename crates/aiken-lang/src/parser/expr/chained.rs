@@ -1,10 +1,10 @@
 use super::{
     and_or_chain, anonymous_binop::parser as anonymous_binop,
     anonymous_function::parser as anonymous_function, assignment, block::parser as block,
-    bytearray::parser as bytearray, if_else::parser as if_else, int::parser as int,
-    list::parser as list, pair::parser as pair, record::parser as record,
-    record_update::parser as record_update, string::parser as string, tuple::parser as tuple,
-    var::parser as var, when::parser as when,
+    bytearray::parser as bytearray, field_capture::parser as field_capture, hole::parser as hole,
+    if_else::parser as if_else, int::parser as int, list::parser as list, pair::parser as pair,
+    record::parser as record, record_update::parser as record_update, string::interpolated,
+    string::parser as string, tuple::parser as tuple, var::parser as var, when::parser as when,
 };
 use crate::{
     expr::UntypedExpr,
@@ -49,6 +49,7 @@ pub fn chain_start<'a>(
 ) -> impl Parser<Token, UntypedExpr, Error = ParseError> + 'a {
     choice((
         string(),
+        interpolated(expression.clone()),
         int(),
         pair(expression.clone()),
         record_update(expression.clone()),
@@ -56,6 +57,8 @@ pub fn chain_start<'a>(
         field_access::constructor(),
         and_or_chain(expression.clone()),
         var(),
+        field_capture(),
+        hole(),
         tuple(expression.clone()),
         bytearray(),
         list(expression.clone()),
@@ -65,6 +68,7 @@ pub fn chain_start<'a>(
         when(expression.clone()),
         assignment::let_(expression.clone()),
         assignment::expect(expression.clone()),
+        assignment::use_(expression.clone()),
         if_else(sequence, expression.clone()),
     ))
 }
@@ -0,0 +1,73 @@
+use chumsky::prelude::*;
+
+use crate::{
+    ast::{self, ArgBy, ArgName, Span},
+    expr::{FnStyle, UntypedExpr},
+    parser::{
+        chain::{field_access, tuple_index::parser as tuple_index, Chain},
+        error::ParseError,
+        token::Token,
+    },
+};
+
+/// Shorthand for anonymous functions accessing a field (or tuple element) of their argument;
+/// `_.foo.bar` stands for `fn(x) { x.foo.bar }`.
+pub fn parser() -> impl Parser<Token, UntypedExpr, Error = ParseError> {
+    just(Token::DiscardName {
+        name: "_".to_string(),
+    })
+    .map_with_span(|_, span| span)
+    .then(
+        choice((tuple_index(), field_access::parser()))
+            .repeated()
+            .at_least(1),
+    )
+    .map_with_span(|(hole, chain), location| {
+        let name = format!("{}__0", ast::CAPTURE_VARIABLE);
+
+        let body = chain.into_iter().fold(
+            UntypedExpr::Var {
+                location: hole,
+                name: name.clone(),
+            },
+            |expr, chain| match chain {
+                Chain::FieldAccess(label, span) => expr.field_access(label, span),
+                Chain::TupleIndex(index, span) => expr.tuple_index(index, span),
+                Chain::Call(..) => unreachable!("calls aren't part of field captures"),
+            },
+        );
+
+        UntypedExpr::Fn {
+            location,
+            fn_style: FnStyle::Capture,
+            arguments: vec![ast::UntypedArg {
+                location: Span::empty(),
+                annotation: None,
+                doc: None,
+                by: ArgBy::ByName(ArgName::Named {
+                    label: name.clone(),
+                    name,
+                    location: Span::empty(),
+                }),
+                is_validator_param: false,
+            }],
+            body: Box::new(body),
+            return_annotation: None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_expr;
+
+    #[test]
+    fn field_capture() {
+        assert_expr!("_.amount");
+    }
+
+    #[test]
+    fn field_capture_nested() {
+        assert_expr!("list.map(outputs, _.value.1st)");
+    }
+}
@@ -0,0 +1,30 @@
+use chumsky::prelude::*;
+
+use crate::{
+    expr::UntypedExpr,
+    parser::{error::ParseError, token::Token},
+};
+
+pub fn parser() -> impl Parser<Token, UntypedExpr, Error = ParseError> {
+    just(Token::Question)
+        .ignore_then(select! { Token::Name { name } => name })
+        .map_with_span(|name, span| UntypedExpr::Hole {
+            location: span,
+            name,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_expr;
+
+    #[test]
+    fn hole() {
+        assert_expr!("?placeholder");
+    }
+
+    #[test]
+    fn hole_as_argument() {
+        assert_expr!("foo(?bar, 42)");
+    }
+}
@@ -9,6 +9,8 @@ mod block;
 pub(crate) mod bytearray;
 mod chained;
 mod fail_todo_trace;
+mod field_capture;
+mod hole;
 mod if_else;
 mod int;
 mod list;
@@ -29,6 +31,8 @@ pub use block::parser as block;
 pub use bytearray::parser as bytearray;
 pub use chained::parser as chained;
 pub use fail_todo_trace::parser as fail_todo_trace;
+pub use field_capture::parser as field_capture;
+pub use hole::parser as hole;
 pub use if_else::parser as if_else;
 pub use int::parser as int;
 pub use list::parser as list;
@@ -1,8 +1,15 @@
 use chumsky::prelude::*;
 
 use crate::{
+    ast::Span,
     expr::UntypedExpr,
-    parser::{error::ParseError, literal::string, literal::utf8_string, token::Token},
+    parser::{
+        error::ParseError,
+        lexer::{self, LexInfo},
+        literal::string,
+        literal::utf8_string,
+        token::{StringPart, Token},
+    },
 };
 
 pub fn parser() -> impl Parser<Token, UntypedExpr, Error = ParseError> {
@@ -12,6 +19,57 @@ pub fn parser() -> impl Parser<Token, UntypedExpr, Error = ParseError> {
     })
 }
 
+pub fn interpolated<'a>(
+    expression: Recursive<'a, Token, UntypedExpr, ParseError>,
+) -> impl Parser<Token, UntypedExpr, Error = ParseError> + 'a {
+    select! { Token::InterpolatedString { parts } => parts }.try_map(move |parts, location| {
+        let parts = parts
+            .into_iter()
+            .map(|part| match part {
+                StringPart::Text(value) => Ok(UntypedExpr::String { location, value }),
+                StringPart::Expr(source, offset) => interpolation(&expression, &source, offset),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|errors| {
+                errors
+                    .into_iter()
+                    .reduce(ParseError::merge)
+                    .expect("failed to parse interpolation without errors")
+            })?;
+
+        Ok(UntypedExpr::InterpolatedString { location, parts })
+    })
+}
+
+/// Parse the source of an interpolated expression, found at 'offset' in the module.
+fn interpolation(
+    expression: &Recursive<'_, Token, UntypedExpr, ParseError>,
+    source: &str,
+    offset: usize,
+) -> Result<UntypedExpr, Vec<ParseError>> {
+    let shift = |span: Span| span.map(|start, end| (start + offset, end + offset));
+
+    let LexInfo { tokens, .. } = lexer::run(source).map_err(|errors| {
+        errors
+            .into_iter()
+            .map(|mut error| {
+                error.span = shift(error.span);
+                error
+            })
+            .collect::<Vec<_>>()
+    })?;
+
+    expression
+        .clone()
+        .then_ignore(end())
+        .parse(chumsky::Stream::from_iter(
+            Span::create(offset + source.len(), 1),
+            tokens
+                .into_iter()
+                .map(move |(token, span)| (token, shift(span))),
+        ))
+}
+
 pub fn hybrid() -> impl Parser<Token, UntypedExpr, Error = ParseError> {
     choice((
         string(),
@@ -31,4 +89,14 @@ mod tests {
     fn string_basic() {
         assert_expr!("@\"aiken\"");
     }
+
+    #[test]
+    fn string_interpolation() {
+        assert_expr!("@\"found {n} inputs for {policy.id}\"");
+    }
+
+    #[test]
+    fn string_interpolation_escaped() {
+        assert_expr!("@\"\\{n}\"");
+    }
 }
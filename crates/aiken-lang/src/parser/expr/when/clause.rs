@@ -1,4 +1,3 @@
-use super::guard;
 use crate::{
     ast,
     expr::UntypedExpr,
@@ -22,30 +21,22 @@ pub fn parser(
             .repeated()
             .or_not(),
         )
-        .then(choice((just(Token::If)
-            .ignore_then(guard())
-            .or_not()
-            .then_ignore(just(Token::RArrow)),)))
+        .then(just(Token::If).ignore_then(expression.clone()).or_not())
+        .then_ignore(just(Token::RArrow))
         // TODO: add hint "Did you mean to wrap a multi line clause in curly braces?"
         .then(expression)
-        .validate(
-            |(((pattern, alternative_patterns_opt), guard), then), span, emit| {
-                if guard.is_some() {
-                    emit(ParseError::deprecated_when_clause_guard(span));
+        .map_with_span(
+            |(((pattern, alternative_patterns_opt), guard), then), span| {
+                let mut patterns = vec1![pattern];
+                patterns.append(&mut alternative_patterns_opt.unwrap_or_default());
+                ast::UntypedClause {
+                    location: span,
+                    patterns,
+                    guard,
+                    then,
                 }
-
-                (pattern, alternative_patterns_opt, then)
             },
         )
-        .map_with_span(|(pattern, alternative_patterns_opt, then), span| {
-            let mut patterns = vec1![pattern];
-            patterns.append(&mut alternative_patterns_opt.unwrap_or_default());
-            ast::UntypedClause {
-                location: span,
-                patterns,
-                then,
-            }
-        })
 }
 
 #[cfg(test)]
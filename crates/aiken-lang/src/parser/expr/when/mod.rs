@@ -1,14 +1,12 @@
 use chumsky::prelude::*;
 
 mod clause;
-mod guard;
 
 use crate::{
     expr::UntypedExpr,
     parser::{error::ParseError, token::Token},
 };
 pub use clause::parser as clause;
-pub use guard::parser as guard;
 
 pub fn parser(
     expression: Recursive<'_, Token, UntypedExpr, ParseError>,
@@ -49,11 +47,11 @@ mod tests {
     }
 
     #[test]
-    fn when_guard_deprecation() {
+    fn when_guard() {
         assert_expr!(
             r#"
             when a is {
-              2 if x > 1 -> 3
+              Some(x) if x > 1 -> 3
               _ -> 1
             }
             "#
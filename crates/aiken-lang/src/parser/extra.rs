@@ -7,6 +7,8 @@ pub struct ModuleExtra {
     pub doc_comments: Vec<Span>,
     pub comments: Vec<Span>,
     pub empty_lines: Vec<usize>,
+    #[serde(default)]
+    pub cfg: Vec<Cfg>,
 }
 
 impl ModuleExtra {
@@ -15,6 +17,16 @@ impl ModuleExtra {
     }
 }
 
+/// A `@cfg(feature = "...")` attribute, guarding the definition or expression that immediately
+/// follows it.
+#[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Cfg {
+    pub location: Span,
+    pub feature: String,
+    /// Byte index of the first token following the attribute.
+    pub target: usize,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Comment<'a> {
     pub start: usize,
@@ -1,7 +1,7 @@
 use super::{
     error::ParseError,
-    extra::ModuleExtra,
-    token::{Base, Token},
+    extra::{Cfg, ModuleExtra},
+    token::{Base, StringPart, Token},
 };
 use crate::ast::Span;
 use chumsky::prelude::*;
@@ -30,6 +30,9 @@ pub fn run(src: &str) -> Result<LexInfo, Vec<ParseError>> {
 
     let mut previous_is_newline = false;
 
+    // Attributes awaiting the token they apply to.
+    let mut pending_cfg = vec![];
+
     let tokens = tokens
         .into_iter()
         .filter_map(|(token, ref span)| {
@@ -51,6 +54,14 @@ pub fn run(src: &str) -> Result<LexInfo, Vec<ParseError>> {
                     extra.empty_lines.push(span.start);
                     None
                 }
+                Token::Cfg { feature } => {
+                    pending_cfg.push(Cfg {
+                        location: *span,
+                        feature,
+                        target: len,
+                    });
+                    None
+                }
                 Token::LeftParen => {
                     if previous_is_newline {
                         Some((Token::NewLineLeftParen, *span))
@@ -78,10 +89,21 @@ pub fn run(src: &str) -> Result<LexInfo, Vec<ParseError>> {
 
             previous_is_newline = current_is_newline;
 
+            if let Some((_, span)) = result {
+                for cfg in pending_cfg.drain(..) {
+                    extra.cfg.push(Cfg {
+                        target: span.start,
+                        ..cfg
+                    });
+                }
+            }
+
             result
         })
         .collect::<Vec<(Token, Span)>>();
 
+    extra.cfg.append(&mut pending_cfg);
+
     Ok(LexInfo { tokens, extra })
 }
 
@@ -200,17 +222,119 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = ParseError> {
             .or(just('n').to('\n'))
             .or(just('r').to('\r'))
             .or(just('t').to('\t'))
-            .or(just('0').to('\0')),
+            .or(just('0').to('\0'))
+            .or(just('{'))
+            .or(just('}')),
     );
 
+    // The source of an interpolated expression, up to the closing brace matching the opening one.
+    // Strings nested in the expression are skipped over as a whole; any other double quote means
+    // that this wasn't an interpolation to begin with (e.g. '@"{" <> key <> @"}"').
+    let interpolation = recursive(|interpolation| {
+        let braced = interpolation
+            .delimited_by(just('{'), just('}'))
+            .map(|source| format!("{{{source}}}"));
+
+        let nested_string = just('@')
+            .ignore_then(just('"'))
+            .ignore_then(
+                choice((
+                    just('\\').chain(any()).collect::<String>(),
+                    braced.clone(),
+                    filter(|c| *c != '\\' && *c != '"').map(|c: char| c.to_string()),
+                ))
+                .repeated()
+                .collect::<String>(),
+            )
+            .then_ignore(just('"'))
+            .map(|source| format!("@\"{source}\""));
+
+        choice((
+            nested_string,
+            braced,
+            filter(|c| *c != '{' && *c != '}' && *c != '"').map(|c: char| c.to_string()),
+        ))
+        .repeated()
+        .collect::<String>()
+    });
+
+    // Braces only start an interpolation when they enclose something; others (e.g. '@"{}"' or
+    // a lone '@"{"') are taken literally.
     let string = just('@')
         .ignore_then(just('"'))
-        .ignore_then(filter(|c| *c != '\\' && *c != '"').or(escape).repeated())
+        .ignore_then(
+            choice((
+                interpolation.delimited_by(just('{'), just('}')).try_map(
+                    |source: String, span: Span| {
+                        if source.trim().is_empty() {
+                            Err(<ParseError as chumsky::Error<char>>::expected_input_found(
+                                span, None, None,
+                            ))
+                        } else {
+                            Ok(StringPart::Expr(source, span.start + 1))
+                        }
+                    },
+                ),
+                filter(|c| *c != '\\' && *c != '"')
+                    .or(escape)
+                    .map(|c: char| StringPart::Text(c.to_string())),
+            ))
+            .repeated()
+            .map(|parts| {
+                parts.into_iter().fold(vec![], |mut parts, part| {
+                    match (parts.last_mut(), part) {
+                        (Some(StringPart::Text(text)), StringPart::Text(more)) => {
+                            text.push_str(&more)
+                        }
+                        (_, part) => parts.push(part),
+                    }
+                    parts
+                })
+            }),
+        )
         .then_ignore(just('"'))
-        .collect::<String>()
-        .map(|value| Token::String { value })
+        .map(|parts| {
+            if parts
+                .iter()
+                .any(|part| matches!(part, StringPart::Expr(..)))
+            {
+                Token::InterpolatedString { parts }
+            } else {
+                Token::String {
+                    value: parts
+                        .into_iter()
+                        .map(|part| match part {
+                            StringPart::Text(text) => text,
+                            StringPart::Expr(..) => unreachable!(),
+                        })
+                        .collect(),
+                }
+            }
+        })
         .labelled("string");
 
+    let cfg = just('@')
+        .ignore_then(just("cfg"))
+        .ignore_then(
+            just("feature")
+                .padded()
+                .ignore_then(just('='))
+                .ignore_then(
+                    filter(|c: &char| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+                        .repeated()
+                        .at_least(1)
+                        .collect::<String>()
+                        .delimited_by(just('"'), just('"'))
+                        .padded(),
+                )
+                .delimited_by(just('('), just(')')),
+        )
+        .map(|feature| Token::Cfg { feature })
+        .labelled("attribute");
+
+    // NOTE: Must come after strings and attributes, which also start with '@'.
+    let at = just('@').to(Token::At);
+
     let bytestring = just('"')
         .ignore_then(filter(|c| *c != '\\' && *c != '"').or(escape).repeated())
         .then_ignore(just('"'))
@@ -298,7 +422,7 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = ParseError> {
         comment_parser(Token::DocComment),
         comment_parser(Token::Comment),
         choice((
-            ordinal, keyword, int, op, newlines, grouping, bytestring, string,
+            ordinal, keyword, int, op, newlines, grouping, bytestring, cfg, string, at,
         ))
         .or(any().map(Token::Error).validate(|t, span, emit| {
             emit(ParseError::expected_input_found(
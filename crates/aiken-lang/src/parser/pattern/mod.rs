@@ -26,7 +26,18 @@ pub use var::parser as var;
 
 pub fn parser() -> impl Parser<Token, UntypedPattern, Error = ParseError> {
     recursive(|pattern| {
+        // An alternative notation for `pattern as name`, naming the whole pattern upfront.
+        let named = select! { Token::Name {name} => name}
+            .then_ignore(just(Token::At))
+            .then(pattern.clone())
+            .map_with_span(|(name, pattern), span| UntypedPattern::Assign {
+                name,
+                location: span,
+                pattern: Box::new(pattern),
+            });
+
         choice((
+            named,
             var(pattern.clone()),
             pair(pattern.clone()),
             constructor(pattern.clone()),
@@ -114,4 +125,9 @@ mod tests {
     fn pattern_list_spread() {
         assert_pattern!("[head, ..]");
     }
+
+    #[test]
+    fn pattern_named_upfront() {
+        assert_pattern!("whole @ Some(inner)");
+    }
 }
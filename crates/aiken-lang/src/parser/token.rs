@@ -6,6 +6,14 @@ pub enum Base {
     Hexadecimal,
 }
 
+/// A segment of an interpolated string: either plain text, or the source of an expression found
+/// between braces, along with the offset at which it starts in the module.
+#[derive(Clone, Debug, PartialEq, Hash, Eq)]
+pub enum StringPart {
+    Text(String),
+    Expr(String, usize),
+}
+
 #[derive(Clone, Debug, PartialEq, Hash, Eq)]
 pub enum Token {
     Error(char),
@@ -16,6 +24,7 @@ pub enum Token {
     Int { value: String, base: Base },
     ByteString { value: String },
     String { value: String },
+    InterpolatedString { parts: Vec<StringPart> },
     // Groupings
     NewLineLeftParen, // ↳(
     LeftParen,        // (
@@ -48,6 +57,7 @@ pub enum Token {
     Colon,
     Comma,
     Hash,     // '#'
+    At,       // '@'
     Bang,     // '!'
     Question, // '?'
     Equal,
@@ -67,6 +77,7 @@ pub enum Token {
     EndOfFile,
     // Docs/Extra
     Comment,
+    Cfg { feature: String },
     DocComment,
     ModuleComment,
     EmptyLine,
@@ -111,6 +122,7 @@ impl fmt::Display for Token {
             Token::DiscardName { name } => name,
             Token::Int { value, .. } => value,
             Token::String { value } => value,
+            Token::InterpolatedString { .. } => "interpolated string",
             Token::ByteString { value } => value,
             Token::NewLineLeftParen => "↳(",
             Token::LeftParen => "(",
@@ -140,6 +152,7 @@ impl fmt::Display for Token {
             Token::Colon => ":",
             Token::Comma => ",",
             Token::Hash => "#",
+            Token::At => "@",
             Token::Bang => "!",
             Token::Equal => "=",
             Token::Question => "?",
@@ -158,6 +171,7 @@ impl fmt::Display for Token {
             Token::DotDot => "..",
             Token::EndOfFile => "EOF",
             Token::Comment => "//",
+            Token::Cfg { .. } => "@cfg",
             Token::DocComment => "///",
             Token::ModuleComment => "////",
             Token::EmptyLine => "EMPTYLINE",
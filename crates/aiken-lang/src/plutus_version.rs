@@ -1,7 +1,15 @@
+use crate::{
+    ast::{Definition, Span, TypedModule},
+    builtins::BUILTIN,
+    expr::TypedExpr,
+    tipo::{ModuleValueConstructor, ValueConstructorVariant},
+};
 use pallas_primitives::conway::Language;
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use uplc::builtins::DefaultFunction;
 
-#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub enum PlutusVersion {
     V1,
@@ -49,3 +57,200 @@ impl PlutusVersion {
         }
     }
 }
+
+/// Builtins (and BLS12-381 points, which come with them) used in a module but unavailable in the
+/// given Plutus version, along with where they're used. For instance, BLS12-381 primitives and
+/// bitwise operations on byte arrays require Plutus V3.
+pub fn unavailable_builtins(module: &TypedModule, version: PlutusVersion) -> Vec<(String, Span)> {
+    let mut found = Vec::new();
+
+    for def in module.definitions() {
+        match def {
+            Definition::Fn(func) => {
+                found.extend(unavailable_builtins_in(&func.body, version).builtins)
+            }
+            Definition::Test(func) | Definition::Benchmark(func) => {
+                for arg in func.arguments.iter() {
+                    found.extend(unavailable_builtins_in(&arg.via, version).builtins);
+                }
+                found.extend(unavailable_builtins_in(&func.body, version).builtins);
+            }
+            Definition::Validator(validator) => {
+                for handler in validator.handlers.iter() {
+                    found.extend(unavailable_builtins_in(&handler.body, version).builtins);
+                }
+                found.extend(unavailable_builtins_in(&validator.fallback.body, version).builtins);
+            }
+            Definition::ModuleConstant(constant) => {
+                found.extend(unavailable_builtins_in(&constant.value, version).builtins)
+            }
+            Definition::TypeAlias(..) | Definition::DataType(..) | Definition::Use(..) => {}
+        }
+    }
+
+    found.sort_by_key(|(_, location)| location.start);
+    found.dedup();
+
+    found
+}
+
+/// Same as [`unavailable_builtins`], for a single expression. Also collects the module functions
+/// and constants the expression refers to, so that callers can follow them and check everything
+/// a validator or a test ends up running.
+pub fn unavailable_builtins_in(expr: &TypedExpr, version: PlutusVersion) -> Found {
+    let language = Language::from(version);
+
+    let mut found = Found::default();
+
+    walk(&mut found, &language, expr);
+
+    found.builtins.sort_by_key(|(_, location)| location.start);
+    found.builtins.dedup();
+
+    found
+}
+
+#[derive(Debug, Default)]
+pub struct Found {
+    /// Unavailable builtins, along with where they're used.
+    pub builtins: Vec<(String, Span)>,
+    /// Module functions and constants referred to, as (module, name) pairs.
+    pub references: Vec<(String, String)>,
+}
+
+fn walk(found: &mut Found, language: &Language, expr: &TypedExpr) {
+    match expr {
+        TypedExpr::UInt { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::ByteArray { .. }
+        | TypedExpr::ErrorTerm { .. } => {}
+
+        TypedExpr::CurvePoint { location, .. } => {
+            if *language != Language::PlutusV3 {
+                found
+                    .builtins
+                    .push(("BLS12-381 points".to_string(), *location));
+            }
+        }
+
+        TypedExpr::Var {
+            constructor,
+            location,
+            ..
+        } => match &constructor.variant {
+            ValueConstructorVariant::ModuleFn {
+                builtin: Some(fun), ..
+            } => builtin(found, language, *fun, *location),
+            ValueConstructorVariant::ModuleFn { module, name, .. }
+            | ValueConstructorVariant::ModuleConstant { module, name, .. } => {
+                found.references.push((module.clone(), name.clone()))
+            }
+            ValueConstructorVariant::LocalVariable { .. }
+            | ValueConstructorVariant::Record { .. } => {}
+        },
+
+        TypedExpr::ModuleSelect {
+            module_name,
+            constructor: ModuleValueConstructor::Fn { name, .. },
+            location,
+            ..
+        } if module_name == BUILTIN => {
+            if let Some(fun) = DefaultFunction::iter().find(|fun| &fun.aiken_name() == name) {
+                builtin(found, language, fun, *location);
+            }
+        }
+
+        TypedExpr::ModuleSelect {
+            constructor:
+                ModuleValueConstructor::Fn { module, name, .. }
+                | ModuleValueConstructor::Constant { module, name, .. },
+            ..
+        } => found.references.push((module.clone(), name.clone())),
+
+        TypedExpr::ModuleSelect { .. } => {}
+
+        TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+            expressions
+                .iter()
+                .for_each(|expr| walk(found, language, expr));
+        }
+
+        TypedExpr::Fn { body, .. } => walk(found, language, body),
+
+        TypedExpr::List { elements, tail, .. } => {
+            elements.iter().for_each(|expr| walk(found, language, expr));
+            if let Some(tail) = tail {
+                walk(found, language, tail);
+            }
+        }
+
+        TypedExpr::Call { fun, args, .. } => {
+            walk(found, language, fun);
+            args.iter()
+                .for_each(|arg| walk(found, language, &arg.value));
+        }
+
+        TypedExpr::BinOp { left, right, .. } => {
+            walk(found, language, left);
+            walk(found, language, right);
+        }
+
+        TypedExpr::Assignment { value, .. } => walk(found, language, value),
+
+        TypedExpr::Trace { then, text, .. } => {
+            walk(found, language, text);
+            walk(found, language, then);
+        }
+
+        TypedExpr::When {
+            subject, clauses, ..
+        } => {
+            walk(found, language, subject);
+            clauses.iter().for_each(|clause| {
+                if let Some(guard) = &clause.guard {
+                    walk(found, language, guard);
+                }
+                walk(found, language, &clause.then);
+            });
+        }
+
+        TypedExpr::If {
+            branches,
+            final_else,
+            ..
+        } => {
+            for branch in branches.iter() {
+                walk(found, language, &branch.condition);
+                walk(found, language, &branch.body);
+            }
+            walk(found, language, final_else);
+        }
+
+        TypedExpr::RecordAccess { record, .. } => walk(found, language, record),
+
+        TypedExpr::Tuple { elems, .. } => elems.iter().for_each(|expr| walk(found, language, expr)),
+
+        TypedExpr::Pair { fst, snd, .. } => {
+            walk(found, language, fst);
+            walk(found, language, snd);
+        }
+
+        TypedExpr::TupleIndex { tuple, .. } => walk(found, language, tuple),
+
+        TypedExpr::RecordUpdate { spread, args, .. } => {
+            walk(found, language, spread);
+            args.iter()
+                .for_each(|arg| walk(found, language, &arg.value));
+        }
+
+        TypedExpr::UnOp { value, .. } => walk(found, language, value),
+    }
+}
+
+fn builtin(found: &mut Found, language: &Language, builtin: DefaultFunction, location: Span) {
+    if !builtin.is_available_in(language) {
+        found
+            .builtins
+            .push((format!("builtin '{}'", builtin.aiken_name()), location));
+    }
+}
@@ -0,0 +1,1050 @@
+//! Translation of (a subset of) Aiken into SMT-LIB verification conditions, so that properties
+//! and validator handlers can be checked by an SMT solver for all of their inputs, instead of
+//! only for sampled ones.
+//!
+//! Only expressions over integers and booleans are supported: literals, variables, module
+//! constants, arithmetic and boolean operators, comparisons, `if`, `when`, `let`, `expect`,
+//! traces, `fail`, and calls to (non-recursive) module functions, which get inlined. Anything
+//! else is reported as unsupported.
+//!
+//! The same translation also drives a symbolic execution of handlers: every branch, failure and
+//! success of a program comes with the condition under which it is reached, which the solver can
+//! either satisfy with concrete inputs, or prove impossible.
+
+use crate::{
+    ast::{
+        BinOp, FunctionAccessKey, OnTestFailure, Pattern, Span, TypedArg, TypedAssignmentKind,
+        TypedFunction, TypedTest, UnOp,
+    },
+    expr::TypedExpr,
+    tipo::{ModuleValueConstructor, PatternConstructor, Type, ValueConstructorVariant},
+};
+use indexmap::IndexMap;
+use std::{collections::HashMap, fmt::Write, rc::Rc};
+
+/// What a query asks of the solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    /// The program succeeds for all inputs.
+    Valid,
+    /// The program succeeds for at least one input.
+    Satisfiable,
+}
+
+/// A verification condition, as an SMT-LIB script ready to be fed to a solver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    pub goal: Goal,
+    /// Inputs of the program, as (name, SMT symbol).
+    pub inputs: Vec<(String, String)>,
+    pub script: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// The goal holds. For satisfiability goals, this comes with a witness.
+    Holds(Vec<(String, String)>),
+    /// The goal doesn't hold. For validity goals, this comes with a counterexample.
+    Fails(Vec<(String, String)>),
+    /// The solver couldn't decide, or answered something unexpected.
+    Unknown(String),
+}
+
+/// A construct which is out of the supported subset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+    pub location: Span,
+    pub reason: String,
+}
+
+/// A point of a program that execution may reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Point {
+    /// A branch of an `if` or a clause of a `when`.
+    Branch,
+    /// A `fail`, an `expect` not matching, or a division by zero.
+    Failure,
+    /// The program returning `True`.
+    Success,
+}
+
+/// A path through a program, to some point. Its query is satisfiable exactly when the point is
+/// reachable, in which case the solver provides inputs reaching it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub point: Point,
+    pub location: Span,
+    pub query: Query,
+}
+
+/// The module-level definitions that programs may refer to.
+#[derive(Debug, Clone, Copy)]
+pub struct Context<'a> {
+    functions: &'a IndexMap<FunctionAccessKey, TypedFunction>,
+    constants: &'a IndexMap<FunctionAccessKey, TypedExpr>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(
+        functions: &'a IndexMap<FunctionAccessKey, TypedFunction>,
+        constants: &'a IndexMap<FunctionAccessKey, TypedExpr>,
+    ) -> Self {
+        Context {
+            functions,
+            constants,
+        }
+    }
+
+    /// A property test holds when it succeeds for every possible value of its arguments; not only
+    /// those its fuzzers would produce.
+    pub fn property(&self, test: &TypedTest) -> Result<Query, Unsupported> {
+        if test.on_test_failure != OnTestFailure::FailImmediately {
+            return Err(Unsupported {
+                location: test.location,
+                reason: "tests expected to fail".to_string(),
+            });
+        }
+
+        let args = test
+            .arguments
+            .iter()
+            .map(|arg| arg.arg.clone())
+            .collect::<Vec<_>>();
+
+        self.query(Goal::Valid, &args, &test.body)
+    }
+
+    /// A handler should succeed for at least some inputs; otherwise, it locks funds forever.
+    pub fn handler(
+        &self,
+        params: &[TypedArg],
+        handler: &TypedFunction,
+    ) -> Result<Query, Unsupported> {
+        let args = params
+            .iter()
+            .chain(handler.arguments.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        self.query(Goal::Satisfiable, &args, &handler.body)
+    }
+
+    /// Enumerate the paths of a handler to each of its branches, failures and to its success.
+    /// Points within inlined functions are left out, as they belong to other definitions.
+    pub fn paths(
+        &self,
+        params: &[TypedArg],
+        handler: &TypedFunction,
+    ) -> Result<Vec<Path>, Unsupported> {
+        let args = params
+            .iter()
+            .chain(handler.arguments.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let (translator, success) = self.translate(&args, &handler.body)?;
+
+        let mut points = translator.points;
+        points.push((Point::Success, handler.body.location(), success));
+
+        Ok(points
+            .into_iter()
+            .map(|(point, location, condition)| Path {
+                point,
+                location,
+                query: Query {
+                    goal: Goal::Satisfiable,
+                    inputs: translator.inputs.clone(),
+                    script: script(&translator.declarations, &format!("(assert {condition})")),
+                },
+            })
+            .collect())
+    }
+
+    fn query(&self, goal: Goal, args: &[TypedArg], body: &TypedExpr) -> Result<Query, Unsupported> {
+        let (translator, success) = self.translate(args, body)?;
+
+        let assertion = match goal {
+            Goal::Valid => format!("(assert (not {success}))"),
+            Goal::Satisfiable => format!("(assert {success})"),
+        };
+
+        Ok(Query {
+            goal,
+            script: script(&translator.declarations, &assertion),
+            inputs: translator.inputs,
+        })
+    }
+
+    /// Translate a program, returning the condition under which it succeeds.
+    fn translate(
+        &self,
+        args: &[TypedArg],
+        body: &TypedExpr,
+    ) -> Result<(Translator<'a>, String), Unsupported> {
+        let mut translator = Translator::new(*self);
+
+        for arg in args {
+            // Inputs of unsupported types are fine, as long as they are never looked at.
+            let (Some(name), Ok(sort)) = (arg.get_variable_name(), sort(&arg.tipo, arg.location))
+            else {
+                continue;
+            };
+
+            let symbol = translator.fresh(name);
+            translator
+                .declarations
+                .push(format!("(declare-const {symbol} {sort})"));
+            translator.scope.insert(name.to_string(), symbol.clone());
+            translator.inputs.push((name.to_string(), symbol));
+        }
+
+        let result = translator.expr(body)?;
+
+        if !body.tipo().is_bool() {
+            return Err(Unsupported {
+                location: body.location(),
+                reason: format!("programs returning {}", body.tipo().to_pretty(0)),
+            });
+        }
+
+        let success = format!("(and (not {}) {result})", disjunction(&translator.failures));
+
+        Ok((translator, success))
+    }
+}
+
+impl Query {
+    /// Interpret the output of a solver run on this query.
+    pub fn verdict(&self, output: &str) -> Verdict {
+        let sexprs = match parse(output) {
+            Ok(sexprs) => sexprs,
+            Err(()) => return Verdict::Unknown(output.trim().to_string()),
+        };
+
+        let (sat, model) = match sexprs.as_slice() {
+            [SExpr::Atom(answer), ..] if answer == "unsat" => (false, vec![]),
+            [SExpr::Atom(answer), model @ ..] if answer == "sat" => {
+                (true, self.model(model.first()))
+            }
+            _ => return Verdict::Unknown(output.trim().to_string()),
+        };
+
+        match (self.goal, sat) {
+            (Goal::Valid, false) | (Goal::Satisfiable, true) => Verdict::Holds(model),
+            (Goal::Valid, true) | (Goal::Satisfiable, false) => Verdict::Fails(model),
+        }
+    }
+
+    fn model(&self, model: Option<&SExpr>) -> Vec<(String, String)> {
+        let mut values = HashMap::new();
+
+        if let Some(SExpr::List(definitions)) = model {
+            for definition in definitions {
+                if let SExpr::List(parts) = definition {
+                    if let [SExpr::Atom(keyword), SExpr::Atom(symbol), _, _, value] =
+                        parts.as_slice()
+                    {
+                        if keyword == "define-fun" {
+                            values.insert(symbol.trim_matches('|').to_string(), value.to_aiken());
+                        }
+                    }
+                }
+            }
+        }
+
+        self.inputs
+            .iter()
+            .filter_map(|(name, symbol)| Some((name.clone(), values.remove(symbol)?)))
+            .collect()
+    }
+}
+
+struct Translator<'a> {
+    context: Context<'a>,
+    /// Inputs of the program, as (name, SMT symbol).
+    inputs: Vec<(String, String)>,
+    declarations: Vec<String>,
+    scope: HashMap<String, String>,
+    path: Vec<String>,
+    failures: Vec<String>,
+    points: Vec<(Point, Span, String)>,
+    inlining: Vec<FunctionAccessKey>,
+    elsewhere: bool,
+    next: usize,
+}
+
+impl<'a> Translator<'a> {
+    fn new(context: Context<'a>) -> Self {
+        Translator {
+            context,
+            inputs: vec![],
+            declarations: vec![],
+            scope: HashMap::new(),
+            path: vec![],
+            failures: vec![],
+            points: vec![],
+            inlining: vec![],
+            elsewhere: false,
+            next: 0,
+        }
+    }
+
+    fn fresh(&mut self, name: &str) -> String {
+        self.next += 1;
+        format!("{name}!{}", self.next)
+    }
+
+    fn define(&mut self, name: &str, sort: &str, term: String) -> String {
+        let symbol = self.fresh(name);
+        self.declarations
+            .push(format!("(define-fun {symbol} () {sort} {term})"));
+        symbol
+    }
+
+    /// Record that the program fails whenever the current path is taken.
+    fn fail(&mut self, location: Span) {
+        self.reach(Point::Failure, location);
+        let path = conjunction(&self.path);
+        self.failures.push(path);
+    }
+
+    /// Record that some point is reached whenever the current path is taken, and the program
+    /// didn't fail before.
+    fn reach(&mut self, point: Point, location: Span) {
+        if self.elsewhere {
+            return;
+        }
+
+        let mut conditions = self.path.clone();
+        if !self.failures.is_empty() {
+            conditions.push(format!("(not {})", disjunction(&self.failures)));
+        }
+
+        self.points
+            .push((point, location, conjunction(&conditions)));
+    }
+
+    /// Translate code from another definition, whose points aren't part of the program's.
+    fn elsewhere<T>(&mut self, translate: impl FnOnce(&mut Self) -> T) -> T {
+        let elsewhere = std::mem::replace(&mut self.elsewhere, true);
+        let result = translate(self);
+        self.elsewhere = elsewhere;
+        result
+    }
+
+    /// Translate under the assumption that some conditions hold; i.e. within a branch.
+    fn under<T>(&mut self, conditions: &[String], translate: impl FnOnce(&mut Self) -> T) -> T {
+        let depth = self.path.len();
+        self.path.extend(conditions.iter().cloned());
+        let result = translate(self);
+        self.path.truncate(depth);
+        result
+    }
+
+    /// Translate in a nested scope, dropping whatever it binds.
+    fn scoped<T>(&mut self, translate: impl FnOnce(&mut Self) -> T) -> T {
+        let scope = self.scope.clone();
+        let result = translate(self);
+        self.scope = scope;
+        result
+    }
+
+    fn expr(&mut self, expr: &TypedExpr) -> Result<String, Unsupported> {
+        match expr {
+            TypedExpr::UInt { value, .. } => Ok(int(value)),
+
+            TypedExpr::Var {
+                constructor,
+                name,
+                location,
+            } => match &constructor.variant {
+                ValueConstructorVariant::LocalVariable { .. } => {
+                    self.scope.get(name).cloned().ok_or_else(|| Unsupported {
+                        location: *location,
+                        reason: format!("values of type {}", constructor.tipo.to_pretty(0)),
+                    })
+                }
+                ValueConstructorVariant::ModuleConstant { module, name, .. } => {
+                    self.constant(module, name, *location)
+                }
+                ValueConstructorVariant::Record { name, .. } if constructor.tipo.is_bool() => {
+                    Ok((if name == "True" { "true" } else { "false" }).to_string())
+                }
+                _ => Err(unsupported(expr)),
+            },
+
+            TypedExpr::ModuleSelect {
+                constructor: ModuleValueConstructor::Constant { module, name, .. },
+                location,
+                ..
+            } => self.constant(module, name, *location),
+
+            TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                self.scoped(|this| {
+                    let mut result = None;
+
+                    for expression in expressions {
+                        result = Some(this.expr(expression)?);
+                    }
+
+                    result.ok_or_else(|| unsupported(expr))
+                })
+            }
+
+            TypedExpr::Assignment {
+                value,
+                pattern,
+                kind,
+                location,
+                ..
+            } => self.assignment(value, pattern, kind, *location),
+
+            TypedExpr::Trace { then, .. } => self.expr(then),
+
+            TypedExpr::ErrorTerm { tipo, location } => {
+                self.fail(*location);
+                let symbol = self.fresh("fail");
+                let sort = sort(tipo, *location)?;
+                self.declarations
+                    .push(format!("(declare-const {symbol} {sort})"));
+                Ok(symbol)
+            }
+
+            TypedExpr::UnOp { value, op, .. } => {
+                let value = self.expr(value)?;
+                Ok(match op {
+                    UnOp::Not => format!("(not {value})"),
+                    UnOp::Negate => format!("(- {value})"),
+                })
+            }
+
+            TypedExpr::BinOp {
+                name, left, right, ..
+            } => self.bin_op(name, left, right),
+
+            TypedExpr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                let mut previous = vec![];
+                let mut alternatives = vec![];
+
+                for branch in branches.iter() {
+                    if branch.is.is_some() {
+                        return Err(unsupported(expr));
+                    }
+
+                    let condition = self.under(&previous, |this| this.expr(&branch.condition))?;
+
+                    previous.push(condition.clone());
+                    let body = self.under(&previous, |this| {
+                        this.reach(Point::Branch, branch.body.location());
+                        this.scoped(|this| this.expr(&branch.body))
+                    })?;
+                    previous.pop();
+
+                    previous.push(format!("(not {condition})"));
+                    alternatives.push((condition, body));
+                }
+
+                let otherwise = self.under(&previous, |this| {
+                    this.reach(Point::Branch, final_else.location());
+                    this.scoped(|this| this.expr(final_else))
+                })?;
+
+                Ok(alternatives
+                    .into_iter()
+                    .rev()
+                    .fold(otherwise, |otherwise, (condition, body)| {
+                        format!("(ite {condition} {body} {otherwise})")
+                    }))
+            }
+
+            TypedExpr::When {
+                subject, clauses, ..
+            } => {
+                let sort = sort(&subject.tipo(), subject.location())?;
+                let subject = self.expr(subject)?;
+                let subject = self.define("subject", sort, subject);
+
+                let mut previous = vec![];
+                let mut alternatives = vec![];
+
+                for clause in clauses {
+                    let (condition, body) = self.scoped(|this| {
+                        let mut condition = this.pattern(&clause.pattern, &subject)?;
+                        if let Some(guard) = &clause.guard {
+                            previous.push(condition.clone());
+                            let guard = this.under(&previous, |this| this.expr(guard));
+                            previous.pop();
+                            condition = format!("(and {condition} {})", guard?);
+                        }
+                        previous.push(condition.clone());
+                        let body = this.under(&previous, |this| {
+                            this.reach(Point::Branch, clause.then.location());
+                            this.expr(&clause.then)
+                        });
+                        previous.pop();
+                        Ok::<_, Unsupported>((condition, body?))
+                    })?;
+
+                    previous.push(format!("(not {condition})"));
+                    alternatives.push((condition, body));
+                }
+
+                // Patterns are exhaustive, so the last clause applies whenever no other does.
+                let (_, otherwise) = alternatives.pop().ok_or_else(|| unsupported(expr))?;
+
+                Ok(alternatives
+                    .into_iter()
+                    .rev()
+                    .fold(otherwise, |otherwise, (condition, body)| {
+                        format!("(ite {condition} {body} {otherwise})")
+                    }))
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                let key = match fun.as_ref() {
+                    TypedExpr::Var { constructor, .. } => match &constructor.variant {
+                        ValueConstructorVariant::ModuleFn {
+                            name,
+                            module,
+                            builtin: None,
+                            ..
+                        } => FunctionAccessKey {
+                            module_name: module.clone(),
+                            function_name: name.clone(),
+                        },
+                        _ => return Err(unsupported(expr)),
+                    },
+                    TypedExpr::ModuleSelect {
+                        constructor: ModuleValueConstructor::Fn { module, name, .. },
+                        ..
+                    } => FunctionAccessKey {
+                        module_name: module.clone(),
+                        function_name: name.clone(),
+                    },
+                    _ => return Err(unsupported(expr)),
+                };
+
+                self.call(expr, key, args.iter().map(|arg| &arg.value))
+            }
+
+            _ => Err(unsupported(expr)),
+        }
+    }
+
+    fn constant(
+        &mut self,
+        module: &str,
+        name: &str,
+        location: Span,
+    ) -> Result<String, Unsupported> {
+        let key = FunctionAccessKey {
+            module_name: module.to_string(),
+            function_name: name.to_string(),
+        };
+
+        let value = self.context.constants.get(&key).ok_or(Unsupported {
+            location,
+            reason: format!("the constant '{name}'"),
+        })?;
+
+        // Constants are closed terms.
+        let scope = std::mem::take(&mut self.scope);
+        let result = self.elsewhere(|this| this.expr(value));
+        self.scope = scope;
+        result
+    }
+
+    fn call<'e>(
+        &mut self,
+        expr: &TypedExpr,
+        key: FunctionAccessKey,
+        args: impl Iterator<Item = &'e TypedExpr>,
+    ) -> Result<String, Unsupported> {
+        let function = self
+            .context
+            .functions
+            .get(&key)
+            .ok_or_else(|| Unsupported {
+                location: expr.location(),
+                reason: format!("the function '{}'", key.function_name),
+            })?;
+
+        if self.inlining.contains(&key) {
+            return Err(Unsupported {
+                location: expr.location(),
+                reason: format!("the recursive function '{}'", key.function_name),
+            });
+        }
+
+        let mut scope = HashMap::new();
+
+        for (param, arg) in function.arguments.iter().zip(args) {
+            let value = self.expr(arg)?;
+            let sort = sort(&arg.tipo(), arg.location())?;
+            if let Some(name) = param.get_variable_name() {
+                let symbol = self.define(name, sort, value);
+                scope.insert(name.to_string(), symbol);
+            }
+        }
+
+        // Functions are closed, besides their arguments.
+        let scope = std::mem::replace(&mut self.scope, scope);
+        self.inlining.push(key);
+        let result = self.elsewhere(|this| this.expr(&function.body));
+        self.inlining.pop();
+        self.scope = scope;
+        result
+    }
+
+    fn assignment(
+        &mut self,
+        value: &TypedExpr,
+        pattern: &Pattern<PatternConstructor, Rc<Type>>,
+        kind: &TypedAssignmentKind,
+        location: Span,
+    ) -> Result<String, Unsupported> {
+        let sort = sort(&value.tipo(), value.location())?;
+
+        let term = self.expr(value)?;
+        let symbol = self.define("value", sort, term);
+
+        let condition = self.pattern(pattern, &symbol)?;
+
+        if condition != "true" {
+            if !kind.is_expect() {
+                return Err(Unsupported {
+                    location: pattern.location(),
+                    reason: "refutable patterns outside of 'expect'".to_string(),
+                });
+            }
+
+            self.under(&[format!("(not {condition})")], |this| this.fail(location));
+        }
+
+        Ok(symbol)
+    }
+
+    /// Bind the variables of a pattern matched against some subject, and return the condition
+    /// under which it matches.
+    fn pattern(
+        &mut self,
+        pattern: &Pattern<PatternConstructor, Rc<Type>>,
+        subject: &str,
+    ) -> Result<String, Unsupported> {
+        match pattern {
+            Pattern::Int { value, .. } => Ok(format!("(= {subject} {})", int(value))),
+            Pattern::Var { name, .. } => {
+                self.scope.insert(name.clone(), subject.to_string());
+                Ok("true".to_string())
+            }
+            Pattern::Discard { .. } => Ok("true".to_string()),
+            Pattern::Assign { name, pattern, .. } => {
+                self.scope.insert(name.clone(), subject.to_string());
+                self.pattern(pattern, subject)
+            }
+            Pattern::Constructor { name, tipo, .. } if tipo.is_bool() => Ok(if name == "True" {
+                subject.to_string()
+            } else {
+                format!("(not {subject})")
+            }),
+            _ => Err(Unsupported {
+                location: pattern.location(),
+                reason: "this kind of pattern".to_string(),
+            }),
+        }
+    }
+
+    fn bin_op(
+        &mut self,
+        name: &BinOp,
+        left: &TypedExpr,
+        right: &TypedExpr,
+    ) -> Result<String, Unsupported> {
+        sort(&left.tipo(), left.location())?;
+
+        let l = self.expr(left)?;
+
+        // Boolean operators short-circuit, which matters for failures on the right-hand side.
+        let r = match name {
+            BinOp::And => self.under(std::slice::from_ref(&l), |this| this.expr(right))?,
+            BinOp::Or => self.under(&[format!("(not {l})")], |this| this.expr(right))?,
+            _ => self.expr(right)?,
+        };
+
+        if matches!(name, BinOp::DivInt | BinOp::ModInt) {
+            self.under(&[format!("(= {r} 0)")], |this| this.fail(right.location()));
+        }
+
+        // Division rounds towards negative infinity, whereas SMT-LIB's is euclidean.
+        let div = |l: &str, r: &str| format!("(ite (> {r} 0) (div {l} {r}) (div (- {l}) (- {r})))");
+
+        Ok(match name {
+            BinOp::And => format!("(and {l} {r})"),
+            BinOp::Or => format!("(or {l} {r})"),
+            BinOp::Eq => format!("(= {l} {r})"),
+            BinOp::NotEq => format!("(not (= {l} {r}))"),
+            BinOp::LtInt => format!("(< {l} {r})"),
+            BinOp::LtEqInt => format!("(<= {l} {r})"),
+            BinOp::GtEqInt => format!("(>= {l} {r})"),
+            BinOp::GtInt => format!("(> {l} {r})"),
+            BinOp::AddInt => format!("(+ {l} {r})"),
+            BinOp::SubInt => format!("(- {l} {r})"),
+            BinOp::MultInt => format!("(* {l} {r})"),
+            BinOp::DivInt => div(&l, &r),
+            BinOp::ModInt => format!("(- {l} (* {r} {}))", div(&l, &r)),
+        })
+    }
+}
+
+fn script(declarations: &[String], assertion: &str) -> String {
+    let mut script = String::from("(set-option :produce-models true)\n");
+
+    for declaration in declarations {
+        let _ = writeln!(script, "{declaration}");
+    }
+
+    let _ = writeln!(script, "{assertion}");
+
+    script.push_str("(check-sat)\n(get-model)\n");
+
+    script
+}
+
+fn sort(tipo: &Rc<Type>, location: Span) -> Result<&'static str, Unsupported> {
+    if tipo.is_int() {
+        Ok("Int")
+    } else if tipo.is_bool() {
+        Ok("Bool")
+    } else {
+        Err(Unsupported {
+            location,
+            reason: format!("values of type {}", tipo.to_pretty(0)),
+        })
+    }
+}
+
+fn unsupported(expr: &TypedExpr) -> Unsupported {
+    Unsupported {
+        location: expr.location(),
+        reason: "this kind of expression".to_string(),
+    }
+}
+
+fn int(value: &str) -> String {
+    match value.strip_prefix('-') {
+        Some(value) => format!("(- {value})"),
+        None => value.to_string(),
+    }
+}
+
+fn conjunction(terms: &[String]) -> String {
+    match terms {
+        [] => "true".to_string(),
+        [term] => term.clone(),
+        _ => format!("(and {})", terms.join(" ")),
+    }
+}
+
+fn disjunction(terms: &[String]) -> String {
+    match terms {
+        [] => "false".to_string(),
+        [term] => term.clone(),
+        _ => format!("(or {})", terms.join(" ")),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    fn to_aiken(&self) -> String {
+        match self {
+            SExpr::Atom(atom) if atom == "true" => "True".to_string(),
+            SExpr::Atom(atom) if atom == "false" => "False".to_string(),
+            SExpr::Atom(atom) => atom.clone(),
+            SExpr::List(parts) => match parts.as_slice() {
+                [SExpr::Atom(minus), value] if minus == "-" => format!("-{}", value.to_aiken()),
+                _ => parts
+                    .iter()
+                    .map(SExpr::to_aiken)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            },
+        }
+    }
+}
+
+fn parse(output: &str) -> Result<Vec<SExpr>, ()> {
+    let mut stack = vec![vec![]];
+    let mut atom = String::new();
+
+    for c in output.chars() {
+        if c == '(' || c == ')' || c.is_whitespace() {
+            if !atom.is_empty() {
+                stack
+                    .last_mut()
+                    .ok_or(())?
+                    .push(SExpr::Atom(std::mem::take(&mut atom)));
+            }
+
+            if c == '(' {
+                stack.push(vec![]);
+            } else if c == ')' {
+                let list = stack.pop().ok_or(())?;
+                stack.last_mut().ok_or(())?.push(SExpr::List(list));
+            }
+        } else {
+            atom.push(c);
+        }
+    }
+
+    if !atom.is_empty() {
+        stack.last_mut().ok_or(())?.push(SExpr::Atom(atom));
+    }
+
+    match stack.as_slice() {
+        [sexprs] => Ok(sexprs.clone()),
+        _ => Err(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ast::{Definition, ModuleKind, Tracing, TypedModule},
+        builtins, IdGenerator,
+    };
+
+    fn check(src: &str) -> TypedModule {
+        let id_gen = IdGenerator::new();
+        let (mut ast, _) = crate::parser::module(src, ModuleKind::Lib).expect("parse");
+        ast.name = "test_module".to_string();
+
+        let mut module_types = HashMap::new();
+        module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
+        module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
+
+        ast.infer(
+            &id_gen,
+            ModuleKind::Lib,
+            "test/project",
+            &module_types,
+            Tracing::All(crate::ast::TraceLevel::Verbose),
+            &mut vec![],
+            None,
+        )
+        .expect("type-check")
+    }
+
+    type Definitions = (
+        IndexMap<FunctionAccessKey, TypedFunction>,
+        IndexMap<FunctionAccessKey, TypedExpr>,
+    );
+
+    fn definitions(module: &TypedModule) -> Definitions {
+        let mut functions = IndexMap::new();
+        let mut constants = IndexMap::new();
+
+        for def in module.definitions() {
+            let key = |function_name: &str| FunctionAccessKey {
+                module_name: module.name.clone(),
+                function_name: function_name.to_string(),
+            };
+
+            match def {
+                Definition::Fn(f) => {
+                    functions.insert(key(&f.name), f.clone());
+                }
+                Definition::ModuleConstant(c) => {
+                    constants.insert(key(&c.name), c.value.clone());
+                }
+                _ => (),
+            }
+        }
+
+        (functions, constants)
+    }
+
+    fn property(src: &str, name: &str) -> Result<Query, Unsupported> {
+        let module = check(src);
+        let (functions, constants) = definitions(&module);
+
+        let test = module
+            .definitions()
+            .find_map(|def| match def {
+                Definition::Test(test) if test.name == name => Some(test.clone()),
+                _ => None,
+            })
+            .expect("test");
+
+        Context::new(&functions, &constants).property(&test)
+    }
+
+    #[test]
+    fn property_to_smtlib() {
+        let query = property(
+            r#"
+            fn int() -> Fuzzer<Int> {
+              todo
+            }
+
+            const limit = 10
+
+            fn clamp(n: Int) -> Int {
+              if n > limit {
+                limit
+              } else {
+                n
+              }
+            }
+
+            test prop_clamp(n via int()) {
+              expect n != 0
+              clamp(n) <= limit && 100 / n != 0
+            }
+            "#,
+            "prop_clamp",
+        )
+        .unwrap();
+
+        assert_eq!(query.goal, Goal::Valid);
+        assert_eq!(query.inputs, vec![("n".to_string(), "n!1".to_string())]);
+        insta::with_settings!({
+            prepend_module_to_snapshot => false,
+        }, {
+            insta::assert_snapshot!(query.script);
+        });
+    }
+
+    #[test]
+    fn property_unsupported() {
+        let unsupported = property(
+            r#"
+            fn list() -> Fuzzer<List<Int>> {
+              todo
+            }
+
+            test prop_list(xs via list()) {
+              xs == xs
+            }
+            "#,
+            "prop_list",
+        )
+        .unwrap_err();
+
+        assert_eq!(unsupported.reason, "values of type List<Int>");
+    }
+
+    #[test]
+    fn handler_paths() {
+        let module = check(
+            r#"
+            fn handler(n: Int, flag: Bool) -> Bool {
+              expect n > 0
+              if n < 0 {
+                fail
+              } else if flag {
+                100 / n > 1
+              } else {
+                True
+              }
+            }
+            "#,
+        );
+
+        let (functions, constants) = definitions(&module);
+
+        let handler = functions.values().next().expect("handler");
+
+        let paths = Context::new(&functions, &constants)
+            .paths(&[], handler)
+            .unwrap()
+            .into_iter()
+            .map(|path| {
+                let assertion = path
+                    .query
+                    .script
+                    .lines()
+                    .find(|line| line.starts_with("(assert"))
+                    .unwrap_or_default()
+                    .to_string();
+                (path.point, assertion)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            vec![
+                (
+                    Point::Failure,
+                    "(assert (not value!3))".to_string()
+                ),
+                (
+                    Point::Branch,
+                    "(assert (and (< n!1 0) (not (not value!3))))".to_string()
+                ),
+                (
+                    Point::Failure,
+                    "(assert (and (< n!1 0) (not (not value!3))))".to_string()
+                ),
+                (
+                    Point::Branch,
+                    "(assert (and (not (< n!1 0)) flag!2 (not (or (not value!3) (< n!1 0)))))".to_string()
+                ),
+                (
+                    Point::Failure,
+                    "(assert (and (not (< n!1 0)) flag!2 (= n!1 0) (not (or (not value!3) (< n!1 0)))))".to_string()
+                ),
+                (
+                    Point::Branch,
+                    "(assert (and (not (< n!1 0)) (not flag!2) (not (or (not value!3) (< n!1 0) (and (not (< n!1 0)) flag!2 (= n!1 0))))))".to_string()
+                ),
+                (
+                    Point::Success,
+                    "(assert (and (not (or (not value!3) (< n!1 0) (and (not (< n!1 0)) flag!2 (= n!1 0)))) (ite (< n!1 0) fail!4 (ite flag!2 (> (ite (> n!1 0) (div 100 n!1) (div (- 100) (- n!1))) 1) true))))".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn verdict_from_model() {
+        let query = Query {
+            goal: Goal::Valid,
+            inputs: vec![
+                ("n".to_string(), "n!1".to_string()),
+                ("b".to_string(), "b!2".to_string()),
+            ],
+            script: String::new(),
+        };
+
+        assert_eq!(
+            query.verdict("unsat\n(error \"no model\")"),
+            Verdict::Holds(vec![])
+        );
+
+        assert_eq!(
+            query.verdict(
+                "sat\n(\n  (define-fun b!2 () Bool\n    false)\n  (define-fun n!1 () Int\n    (- 3))\n)"
+            ),
+            Verdict::Fails(vec![
+                ("n".to_string(), "-3".to_string()),
+                ("b".to_string(), "False".to_string()),
+            ])
+        );
+
+        assert_eq!(
+            query.verdict("unknown"),
+            Verdict::Unknown("unknown".to_string())
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    ast::{BinOp, DataTypeKey, IfBranch, OnTestFailure, Span, TypedArg, TypedDataType, TypedTest},
+    ast::{
+        BinOp, DataTypeKey, IfBranch, OnTestFailure, Span, TypedArg, TypedBenchmark, TypedDataType,
+        TypedTest,
+    },
     expr::{TypedExpr, UntypedExpr},
     format::Formatter,
     gen_uplc::CodeGenerator,
@@ -10,11 +13,11 @@ use cryptoxide::{blake2b::Blake2b, digest::Digest};
 use indexmap::IndexMap;
 use itertools::Itertools;
 use owo_colors::{OwoColorize, Stream, Stream::Stderr};
-use pallas_primitives::alonzo::{Constr, PlutusData};
+use pallas_primitives::alonzo::{BigInt, Constr, PlutusData};
 use patricia_tree::PatriciaMap;
 use std::{
     borrow::Borrow,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     convert::TryFrom,
     fmt::{Debug, Display},
     ops::Deref,
@@ -24,7 +27,7 @@ use std::{
 };
 use uplc::{
     ast::{Constant, Data, Name, NamedDeBruijn, Program, Term},
-    machine::{cost_model::ExBudget, eval_result::EvalResult},
+    machine::{cost_model::ExBudget, eval_result::EvalResult, profile::Profile},
 };
 use vec1::{vec1, Vec1};
 
@@ -202,6 +205,15 @@ impl UnitTest {
             assertion: self.assertion,
         })
     }
+
+    /// Evaluate the test, attributing the spent budget to the functions it goes through.
+    pub fn profile(&self, plutus_version: &PlutusVersion) -> Profile {
+        let (_, profile) = Program::<NamedDeBruijn>::try_from(self.program.clone())
+            .unwrap()
+            .eval_with_profile(ExBudget::max(), &plutus_version.into());
+
+        profile
+    }
 }
 
 /// ----- PropertyTest -----------------------------------------------------------------
@@ -230,6 +242,9 @@ pub struct Fuzzer<T> {
     pub stripped_type_info: Rc<Type>,
 }
 
+/// A value produced by a fuzzer, along with the choices it was produced from.
+pub type Sample = (Vec<u8>, PlutusData);
+
 #[derive(Debug, Clone, thiserror::Error, miette::Diagnostic)]
 #[error("Fuzzer exited unexpectedly: {uplc_error}")]
 pub struct FuzzerError {
@@ -276,6 +291,14 @@ impl Display for Event {
 impl PropertyTest {
     pub const DEFAULT_MAX_SUCCESS: usize = 100;
 
+    /// Maximum number of fuzzer runs spent enumerating a fuzzer's domain, when running
+    /// exhaustively. That's enough to cover any domain drawn from two choices.
+    pub const MAX_ENUMERATION: usize = 256 * 256 + 256 + 1;
+
+    /// Choices available to a fuzzer beyond those being enumerated, so that it can run to
+    /// completion and tell how many it actually needs.
+    const ENUMERATION_PADDING: usize = 256;
+
     /// Run a property test from a given seed. The property is run at most DEFAULT_MAX_SUCCESS times. It
     /// may stops earlier on failure; in which case a 'counterexample' is returned.
     pub fn run<U>(
@@ -319,9 +342,99 @@ impl PropertyTest {
             iterations,
             labels,
             traces,
+            exhaustive: false,
         })
     }
 
+    /// Run a property test over every value its fuzzer can produce, when there are few enough
+    /// of them (see 'enumerate'). Otherwise, fall back to running it from a seed like 'run'.
+    pub fn run_exhaustive<U>(
+        self,
+        seed: u32,
+        n: usize,
+        plutus_version: &PlutusVersion,
+    ) -> TestResult<U, PlutusData> {
+        let values = match self.enumerate(PropertyTest::MAX_ENUMERATION) {
+            Ok(Some(values)) => values,
+            Ok(None) | Err(..) => return self.run(seed, n, plutus_version),
+        };
+
+        let mut labels = BTreeMap::new();
+        let mut iterations = 0;
+        let mut counterexample = None;
+
+        // Values come in order of their choices; so the first counterexample found is also the
+        // simplest one, and there's no need for shrinking it.
+        for (choices, value) in values {
+            iterations += 1;
+            if let Some(found) = self.check(value, choices, &mut labels, plutus_version) {
+                counterexample = Some(found.value);
+                break;
+            }
+        }
+
+        let traces = match counterexample {
+            Some(ref value) => self
+                .eval(value, plutus_version)
+                .logs()
+                .into_iter()
+                .filter(|s| PropertyTest::extract_label(s).is_none())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        TestResult::PropertyTestResult(PropertyTestResult {
+            test: self,
+            counterexample: Ok(counterexample),
+            iterations,
+            labels,
+            traces,
+            exhaustive: true,
+        })
+    }
+
+    /// Enumerate every distinct value the fuzzer can produce, along with the choices producing
+    /// it, by replaying all possible sequences of choices; smaller choices first. Gives up when
+    /// that takes more than 'budget' runs of the fuzzer, or when a fuzzer rejects a sequence.
+    pub fn enumerate(&self, budget: usize) -> Result<Option<Vec<Sample>>, FuzzerError> {
+        let mut values = Vec::new();
+        let mut seen = BTreeSet::new();
+        let mut runs = 0;
+        let mut prefixes = vec![vec![]];
+
+        let fuzzer = Program::<NamedDeBruijn>::try_from(self.fuzzer.program.clone()).unwrap();
+
+        while let Some(prefix) = prefixes.pop() {
+            runs += 1;
+
+            let mut choices = prefix.clone();
+            choices.resize(prefix.len() + PropertyTest::ENUMERATION_PADDING, 0);
+
+            let Some((prng, value)) = Prng::from_choices(&choices).sample_debruijn(&fuzzer)? else {
+                return Ok(None);
+            };
+
+            let consumed = choices.len() - prng.remaining().unwrap_or_default();
+
+            if consumed > prefix.len() {
+                // The fuzzer needs more choices than the prefix provides; so try them all.
+                prefixes.extend((0..=u8::MAX).rev().map(|choice| {
+                    let mut next = prefix.clone();
+                    next.push(choice);
+                    next
+                }));
+            } else if seen.insert(value.clone()) {
+                values.push((prefix, value));
+            }
+
+            if runs + prefixes.len() > budget {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(values))
+    }
+
     pub fn run_n_times<'a>(
         &'a self,
         remaining: &mut usize,
@@ -346,12 +459,34 @@ impl PropertyTest {
         labels: &mut BTreeMap<String, usize>,
         plutus_version: &'a PlutusVersion,
     ) -> Result<(Prng, Option<Counterexample<'a>>), FuzzerError> {
-        use OnTestFailure::*;
-
         let (next_prng, value) = prng
             .sample(&self.fuzzer.program)?
             .expect("A seeded PRNG returned 'None' which indicates a fuzzer is ill-formed and implemented wrongly; please contact library's authors.");
 
+        let choices = next_prng.choices();
+
+        let mut counterexample = self.check(value, choices, labels, plutus_version);
+
+        if let Some(ref mut counterexample) = counterexample {
+            if !counterexample.choices.is_empty() {
+                counterexample.simplify();
+            }
+        }
+
+        Ok((next_prng, counterexample))
+    }
+
+    /// Run the property on a value, produced by the given choices. Yields a counterexample
+    /// when the run goes against what the test expects.
+    fn check<'a>(
+        &'a self,
+        value: PlutusData,
+        choices: Vec<u8>,
+        labels: &mut BTreeMap<String, usize>,
+        plutus_version: &'a PlutusVersion,
+    ) -> Option<Counterexample<'a>> {
+        use OnTestFailure::*;
+
         let mut result = self.eval(&value, plutus_version);
 
         for s in result.logs() {
@@ -376,9 +511,9 @@ impl PropertyTest {
         };
 
         if keep_counterexample {
-            let mut counterexample = Counterexample {
+            Some(Counterexample {
                 value,
-                choices: next_prng.choices(),
+                choices,
                 cache: Cache::new(|choices| {
                     match Prng::from_choices(choices).sample(&self.fuzzer.program) {
                         Err(..) => Status::Invalid,
@@ -408,15 +543,9 @@ impl PropertyTest {
                         }
                     }
                 }),
-            };
-
-            if !counterexample.choices.is_empty() {
-                counterexample.simplify();
-            }
-
-            Ok((next_prng, Some(counterexample)))
+            })
         } else {
-            Ok((next_prng, None))
+            None
         }
     }
 
@@ -437,6 +566,160 @@ impl PropertyTest {
     }
 }
 
+/// ----- Benchmark -----------------------------------------------------------------
+///
+/// A benchmark is, like a property, a lambda taking an extra argument. The argument is however
+/// drawn from a sampler; that is, a function from a size to a fuzzer. The benchmark runs once for
+/// every size up to a maximum, measuring the execution budget spent on each run; so that one can
+/// see how the cost of a function grows with its input.
+///
+#[derive(Debug, Clone)]
+pub struct Benchmark {
+    pub input_path: PathBuf,
+    pub module: String,
+    pub name: String,
+    pub program: Program<Name>,
+    pub sampler: Fuzzer<Name>,
+}
+
+unsafe impl Send for Benchmark {}
+
+#[derive(Debug, Clone, thiserror::Error, miette::Diagnostic)]
+pub enum BenchmarkError {
+    #[error("Sampler exited unexpectedly at size {size}: {}", error.uplc_error)]
+    Sampler { size: usize, error: FuzzerError },
+
+    #[error("Benchmark exited unexpectedly at size {size}: {uplc_error}")]
+    Run {
+        size: usize,
+        traces: Vec<String>,
+        uplc_error: uplc::machine::Error,
+    },
+}
+
+impl Benchmark {
+    pub const DEFAULT_MAX_SIZE: usize = 30;
+
+    pub fn from_function_definition(
+        generator: &mut CodeGenerator<'_>,
+        bench: TypedBenchmark,
+        module_name: String,
+        input_path: PathBuf,
+    ) -> Benchmark {
+        let parameter = bench
+            .arguments
+            .first()
+            .expect("benchmarks have exactly one argument")
+            .to_owned();
+
+        let via = parameter.via.clone();
+
+        let type_info = parameter.arg.tipo.clone();
+
+        let stripped_type_info = convert_opaque_type(&type_info, generator.data_types(), true);
+
+        let program = generator.clone().generate_raw(
+            &bench.body,
+            &[TypedArg {
+                tipo: stripped_type_info.clone(),
+                ..parameter.clone().into()
+            }],
+            &module_name,
+        );
+
+        let sampler = generator.clone().generate_raw(&via, &[], &module_name);
+
+        Benchmark {
+            input_path,
+            module: module_name,
+            name: bench.name,
+            program,
+            sampler: Fuzzer {
+                program: sampler,
+                stripped_type_info,
+                type_info,
+            },
+        }
+    }
+
+    /// Run the benchmark for every size from 0 to 'max_size' (included), drawing values from a
+    /// PRNG made from the given seed. Stops at the first run which fails.
+    pub fn run(
+        self,
+        seed: u32,
+        max_size: usize,
+        plutus_version: &PlutusVersion,
+    ) -> BenchmarkResult {
+        let mut measures = Vec::with_capacity(max_size + 1);
+        let mut prng = Prng::from_seed(seed);
+        let mut error = None;
+
+        for size in 0..=max_size {
+            let fuzzer = self
+                .sampler
+                .program
+                .apply_constant(Constant::Integer(size.into()));
+
+            let (next_prng, value) = match prng.sample(&fuzzer) {
+                Ok(Some(sample)) => sample,
+                Ok(None) => panic!("A seeded PRNG returned 'None' which indicates a sampler is ill-formed and implemented wrongly; please contact library's authors."),
+                Err(fuzzer_error) => {
+                    error = Some(BenchmarkError::Sampler {
+                        size,
+                        error: fuzzer_error,
+                    });
+                    break;
+                }
+            };
+
+            prng = next_prng;
+
+            let mut result = self.eval(&value, plutus_version);
+
+            if let Err(uplc_error) = result.result() {
+                error = Some(BenchmarkError::Run {
+                    size,
+                    traces: result.logs(),
+                    uplc_error,
+                });
+                break;
+            }
+
+            measures.push((size, result.cost()));
+        }
+
+        BenchmarkResult {
+            bench: self,
+            measures,
+            error,
+        }
+    }
+
+    pub fn eval(&self, value: &PlutusData, plutus_version: &PlutusVersion) -> EvalResult {
+        let program = self.program.apply_data(value.clone());
+
+        Program::<NamedDeBruijn>::try_from(program)
+            .unwrap()
+            .eval_version(ExBudget::max(), &plutus_version.into())
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchmarkResult {
+    pub bench: Benchmark,
+    /// Budget spent by each run, along with the size it ran for.
+    pub measures: Vec<(usize, ExBudget)>,
+    pub error: Option<BenchmarkError>,
+}
+
+unsafe impl Send for BenchmarkResult {}
+
+impl BenchmarkResult {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
 /// ----- PRNG -----------------------------------------------------------------
 ///
 /// A Pseudo-random generator (PRNG) used to produce random values for fuzzers.
@@ -479,6 +762,22 @@ impl Prng {
         }
     }
 
+    /// Number of choices a replayed Prng has left.
+    fn remaining(&self) -> Option<usize> {
+        match self {
+            Prng::Seeded { .. } => None,
+            Prng::Replayed { uplc, .. } => match uplc {
+                PlutusData::Constr(Constr { fields, .. }) => match fields.first() {
+                    Some(PlutusData::BigInt(BigInt::Int(cursor))) => {
+                        usize::try_from(i128::from(*cursor)).ok()
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+        }
+    }
+
     pub fn choices(&self) -> Vec<u8> {
         match self {
             Prng::Seeded { choices, .. } => {
@@ -528,8 +827,16 @@ impl Prng {
         &self,
         fuzzer: &Program<Name>,
     ) -> Result<Option<(Prng, PlutusData)>, FuzzerError> {
-        let program = Program::<NamedDeBruijn>::try_from(fuzzer.apply_data(self.uplc())).unwrap();
-        let mut result = program.eval(ExBudget::max());
+        self.sample_debruijn(&Program::<NamedDeBruijn>::try_from(fuzzer.clone()).unwrap())
+    }
+
+    /// Same as 'sample', for a fuzzer already converted to De Bruijn indices. This spares the
+    /// conversion when sampling the same fuzzer many times over.
+    pub fn sample_debruijn(
+        &self,
+        fuzzer: &Program<NamedDeBruijn>,
+    ) -> Result<Option<(Prng, PlutusData)>, FuzzerError> {
+        let mut result = fuzzer.apply_data(self.uplc()).eval(ExBudget::max());
         result
             .result()
             .map_err(|uplc_error| FuzzerError {
@@ -1065,6 +1372,8 @@ pub struct PropertyTestResult<T> {
     pub iterations: usize,
     pub labels: BTreeMap<String, usize>,
     pub traces: Vec<String>,
+    /// Whether the property ran over every value its fuzzer can produce, rather than a sample.
+    pub exhaustive: bool,
 }
 
 unsafe impl<T> Send for PropertyTestResult<T> {}
@@ -1085,6 +1394,7 @@ impl PropertyTestResult<PlutusData> {
             test: self.test,
             labels: self.labels,
             traces: self.traces,
+            exhaustive: self.exhaustive,
         }
     }
 }
@@ -1,8 +1,9 @@
 use crate::{
-    ast::{Definition, ModuleKind, Pattern, TraceLevel, Tracing, TypedModule, UntypedModule},
+    ast::{Definition, ModuleKind, Pattern, Span, TraceLevel, Tracing, TypedModule, UntypedModule},
     builtins,
     expr::TypedExpr,
     parser,
+    plutus_version::{unavailable_builtins, PlutusVersion},
     tipo::error::{Error, UnifyErrorSituation, Warning},
     IdGenerator,
 };
@@ -1349,6 +1350,26 @@ fn backpassing_basic() {
     assert!(check(parse(source_code)).is_ok())
 }
 
+#[test]
+fn backpassing_use() {
+    let source_code = r#"
+        fn and_then(opt: Option<a>, then: fn(a) -> Option<b>) -> Option<b> {
+          when opt is {
+            None -> None
+            Some(a) -> then(a)
+          }
+        }
+
+        fn backpassing(opt_i: Option<Int>, opt_j: Option<Int>) -> Option<Int> {
+          use i <- and_then(opt_i)
+          let j <- and_then(opt_j)
+          Some(i + j)
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
 #[test]
 fn backpassing_expect_simple() {
     let source_code = r#"
@@ -1872,6 +1893,61 @@ fn fuzzer_err_unify_3() {
     ))
 }
 
+#[test]
+fn sampler_ok_basic() {
+    let source_code = r#"
+        fn int() -> Fuzzer<Int> { todo }
+        fn list(a: Fuzzer<a>) -> Sampler<List<a>> { todo }
+
+        bench length(xs via list(int())) { xs }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn sampler_ok_explicit() {
+    let source_code = r#"
+        fn int(size: Int) -> Fuzzer<Int> { todo }
+
+        bench length(n: Int via int) { n + 1 }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn sampler_err_fuzzer() {
+    let source_code = r#"
+        fn int() -> Fuzzer<Int> { todo }
+
+        bench length(n via int()) { n }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((
+            _,
+            Error::CouldNotUnify {
+                situation: None,
+                ..
+            }
+        ))
+    ))
+}
+
+#[test]
+fn sampler_err_arity() {
+    let source_code = r#"
+        bench length() { todo }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::IncorrectBenchmarkArity { count: 0, .. }))
+    ))
+}
+
 #[test]
 fn utf8_hex_literal_warning() {
     let source_code = r#"
@@ -3445,3 +3521,477 @@ fn constant_generic_empty() {
     let source_code = r#"const foo: List<a> = []"#;
     assert!(check_validator(parse(source_code)).is_ok());
 }
+
+#[test]
+fn bitwise_builtins() {
+    let source_code = r#"
+        use aiken/builtin
+
+        fn bitmap(self: ByteArray, ix: Int) -> Bool {
+          builtin.read_bit(self, ix)
+        }
+
+        test bitwise() {
+          let mask = builtin.and_bytearray(True, #"f0", #"ff")
+          let flags = builtin.or_bytearray(False, mask, #"0f")
+          let flipped = builtin.xor_bytearray(True, flags, #"ff")
+          and {
+            bitmap(builtin.shift_bytearray(flags, 1), 0),
+            builtin.rotate_bytearray(flipped, 4) == #"00",
+            builtin.count_set_bits(flags) == 8,
+          }
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn bitwise_builtins_arity() {
+    let source_code = r#"
+        use aiken/builtin
+
+        fn foo() {
+          builtin.and_bytearray(#"f0", #"ff")
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::IncorrectFunctionCallArity { .. }))
+    ));
+}
+
+#[test]
+fn bls_builtins_require_plutus_v3() {
+    let source_code = r#"
+        use aiken/builtin.{bls12_381_g1_neg}
+
+        const generator: G1Element =
+          #<Bls12_381, G1>"97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+
+        pub fn double(point: G1Element) -> G1Element {
+          builtin.bls12_381_g1_add(point, point)
+        }
+
+        pub fn negated() -> G1Element {
+          bls12_381_g1_neg(generator)
+        }
+    "#;
+
+    let (_, module) = check(parse(source_code)).unwrap();
+
+    assert_eq!(
+        unavailable_builtins(&module, PlutusVersion::V2)
+            .into_iter()
+            .map(|(what, _)| what)
+            .collect::<Vec<_>>(),
+        vec![
+            "BLS12-381 points".to_string(),
+            "builtin 'bls12_381_g1_add'".to_string(),
+            "builtin 'bls12_381_g1_neg'".to_string(),
+        ]
+    );
+
+    assert!(unavailable_builtins(&module, PlutusVersion::V3).is_empty());
+}
+
+#[test]
+fn hashing_builtins_require_plutus_v3() {
+    let source_code = r#"
+        use aiken/builtin
+
+        pub fn eth_digest(message: ByteArray) -> ByteArray {
+          builtin.keccak_256(message)
+        }
+
+        pub fn key_hash(key: ByteArray) -> ByteArray {
+          builtin.blake2b_224(key)
+        }
+
+        pub fn tx_hash(tx: ByteArray) -> ByteArray {
+          builtin.blake2b_256(tx)
+        }
+    "#;
+
+    let (_, module) = check(parse(source_code)).unwrap();
+
+    assert_eq!(
+        unavailable_builtins(&module, PlutusVersion::V2)
+            .into_iter()
+            .map(|(what, _)| what)
+            .collect::<Vec<_>>(),
+        vec![
+            "builtin 'keccak_256'".to_string(),
+            "builtin 'blake2b_224'".to_string(),
+        ]
+    );
+
+    assert!(unavailable_builtins(&module, PlutusVersion::V3).is_empty());
+}
+
+#[test]
+fn alternative_patterns() {
+    let source_code = r#"
+        type Shape {
+          Square(Int)
+          Rectangle(Int, Int)
+          Circle(Int)
+        }
+
+        fn side(shape: Shape) -> Int {
+          when shape is {
+            Square(x) | Rectangle(x, _) | Circle(x) -> x
+          }
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn alternative_patterns_missing_var() {
+    let source_code = r#"
+        fn first(xs: List<Int>) -> Int {
+          when xs is {
+            [x] | [_, _] -> x
+            _ -> 0
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::MissingVarInAlternativePattern { name, .. })) if name == "x"
+    ));
+}
+
+#[test]
+fn alternative_patterns_mismatched_types() {
+    let source_code = r#"
+        fn first(xs: List<(Int, ByteArray)>) -> Int {
+          when xs is {
+            [(x, _)] | [(_, x)] -> x
+            _ -> 0
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ));
+}
+
+#[test]
+fn alternative_patterns_duplicate_var() {
+    let source_code = r#"
+        fn first(xs: List<Int>) -> Int {
+          when xs is {
+            [x, y] | [x, x] -> x + y
+            _ -> 0
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::DuplicateVarInPattern { name, .. })) if name == "x"
+    ));
+}
+
+#[test]
+fn when_guard() {
+    let source_code = r#"
+        fn sign(n: Option<Int>) -> Int {
+          when n is {
+            Some(x) if x > 0 -> 1
+            Some(x) if x < 0 -> -1
+            _ -> 0
+          }
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn when_guard_not_bool() {
+    let source_code = r#"
+        fn sign(n: Option<Int>) -> Int {
+          when n is {
+            Some(x) if x -> 1
+            _ -> 0
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ));
+}
+
+#[test]
+fn when_guard_not_exhaustive() {
+    let source_code = r#"
+        fn sign(n: Option<Int>) -> Int {
+          when n is {
+            Some(x) if x > 0 -> 1
+            None -> 0
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::NotExhaustivePatternMatch { .. }))
+    ));
+}
+
+#[test]
+fn named_pattern_upfront() {
+    let source_code = r#"
+        fn or_default(opt: Option<Int>) -> Option<Int> {
+          when opt is {
+            whole @ Some(_) -> whole
+            None -> Some(0)
+          }
+        }
+
+        fn head(xs: List<Int>) -> (List<Int>, Int) {
+          expect all @ [x, ..] = xs
+          (all, x)
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn named_pattern_upfront_type_mismatch() {
+    let source_code = r#"
+        fn or_default(opt: Option<Int>) -> Int {
+          when opt is {
+            whole @ Some(_) -> whole
+            None -> 0
+          }
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ));
+}
+
+#[test]
+fn string_interpolation() {
+    let source_code = r#"
+        type Asset {
+          policy_id: ByteArray,
+          quantity: Int,
+        }
+
+        fn describe(asset: Asset, label: String) -> String {
+          @"{label}: found {asset.quantity} of {asset.policy_id}"
+        }
+
+        test foo() {
+          trace @"nested {@"strings"} and {[1, 2, 3]}"
+          True
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn string_interpolation_constant_folding() {
+    let source_code = r#"
+        fn greet() -> String {
+          @"hello {@"world"}\{!\}"
+        }
+    "#;
+
+    let (_, ast) = check(parse(source_code)).unwrap();
+
+    assert!(matches!(
+        ast.definitions.first(),
+        Some(Definition::Fn(def)) if matches!(
+            &def.body,
+            TypedExpr::String { value, .. } if value == "hello world{!}"
+        )
+    ));
+}
+
+#[test]
+fn string_interpolation_unknown_variable() {
+    let source_code = r#"
+        fn greet(name: String) -> String {
+          @"hello {nme}"
+        }
+    "#;
+
+    let start = source_code.find("nme").unwrap();
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::UnknownVariable { name, location, .. }))
+            if name == "nme" && location == Span { start, end: start + 3 }
+    ))
+}
+
+#[test]
+fn string_interpolation_function() {
+    let source_code = r#"
+        fn greet(name: fn() -> String) -> String {
+          @"hello {name}"
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_err())
+}
+
+#[test]
+fn typed_hole() {
+    let source_code = r#"
+        fn add(a: Int, b: Int, s: String) -> Int {
+          a + ?rest
+        }
+    "#;
+
+    let start = source_code.find("?rest").unwrap();
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::TypedHole { name, expected, candidates, location }))
+            if name == "rest"
+            && expected.to_pretty(0) == "Int"
+            && candidates == vec!["a".to_string(), "b".to_string()]
+            && location == Span { start, end: start + 5 }
+    ))
+}
+
+#[test]
+fn typed_hole_inferred_from_later_usage() {
+    let source_code = r#"
+        fn foo(xs: List<ByteArray>) -> Bool {
+          let x = ?first
+          [x, ..xs] == xs
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::TypedHole { expected, candidates, .. }))
+            if expected.to_pretty(0) == "ByteArray" && candidates.is_empty()
+    ))
+}
+
+#[test]
+fn field_capture() {
+    let source_code = r#"
+        type Output {
+          address: ByteArray,
+          value: (Int, Int),
+        }
+
+        fn map(xs: List<a>, f: fn(a) -> b) -> List<b> {
+          when xs is {
+            [] -> []
+            [x, ..rest] -> [f(x), ..map(rest, f)]
+          }
+        }
+
+        fn addresses(outputs: List<Output>) -> List<ByteArray> {
+          map(outputs, _.address)
+        }
+
+        fn quantities(outputs: List<Output>) -> List<Int> {
+          outputs |> map(_.value.1st)
+        }
+
+        fn address(output: Output) -> ByteArray {
+          output |> _.address
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn field_capture_type_mismatch() {
+    let source_code = r#"
+        type Output {
+          address: ByteArray,
+        }
+
+        fn map(xs: List<a>, f: fn(a) -> b) -> List<b> {
+          when xs is {
+            [] -> []
+            [x, ..rest] -> [f(x), ..map(rest, f)]
+          }
+        }
+
+        fn addresses(outputs: List<Output>) -> List<Int> {
+          map(outputs, _.address)
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ))
+}
+
+#[test]
+fn pipe_placeholder() {
+    let source_code = r#"
+        type Output {
+          address: ByteArray,
+        }
+
+        fn map(xs: List<a>, f: fn(a) -> b) -> List<b> {
+          when xs is {
+            [] -> []
+            [x, ..rest] -> [f(x), ..map(rest, f)]
+          }
+        }
+
+        fn sub(a: Int, b: Int) -> Int {
+          a - b
+        }
+
+        fn foo(outputs: List<Output>) -> List<ByteArray> {
+          let _ = 1 |> sub(10, _) |> sub(b: 2, a: _)
+          outputs |> map(_, _.address)
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok())
+}
+
+#[test]
+fn pipe_placeholder_wrong_type() {
+    let source_code = r#"
+        fn bar(n: Int, l: Bool) -> Int {
+          n
+        }
+
+        fn foo() -> Int {
+          1 |> bar(1, _)
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((
+            _,
+            Error::CouldNotUnify {
+                situation: Some(UnifyErrorSituation::PipeTypeMismatch),
+                ..
+            }
+        ))
+    ))
+}
@@ -1,4 +1,7 @@
-use crate::assert_format;
+use crate::{
+    assert_format,
+    format::{CallWrapping, FormatOptions, ImportGroup},
+};
 
 #[test]
 fn format_comment_at_end_of_file() {
@@ -272,6 +275,92 @@ fn format_when() {
     );
 }
 
+#[test]
+fn format_when_guard() {
+    assert_format!(
+        r#"
+        pub fn foo(a) {
+          when a is {
+            Some(x)   if x>0 && x<10 -> x
+            Some(_)|None -> 0
+          }
+        }
+    "#
+    );
+}
+
+#[test]
+fn format_named_pattern_upfront() {
+    assert_format!(
+        r#"
+        pub fn foo(a) {
+          when a is {
+            whole@Some(_) -> whole
+            None -> None
+          }
+        }
+    "#
+    );
+}
+
+#[test]
+fn format_string_interpolation() {
+    assert_format!(
+        r#"
+        pub fn foo(n) {
+          trace @"found {  n+1 } items in {list.length([1,2])}, \{ok}"
+          n
+        }
+    "#
+    );
+}
+
+#[test]
+fn format_string_literal_braces() {
+    assert_format!(
+        r#"
+        pub fn foo() {
+          [@"{}", @"{", @"\{ok}", @"{ }"]
+        }
+    "#
+    );
+}
+
+#[test]
+fn format_typed_hole() {
+    assert_format!(
+        r#"
+        pub fn foo(n) {
+          n + ? rest
+        }
+    "#
+    );
+}
+
+#[test]
+fn format_field_capture() {
+    assert_format!(
+        r#"
+        pub fn foo(outputs) {
+          outputs |> list.map(_ .value .1st) |> list.filter(_, fn(x) { x > 0 })
+        }
+    "#
+    );
+}
+
+#[test]
+fn format_use_backpassing() {
+    assert_format!(
+        r#"
+        fn foo(opt_i, opt_j) {
+          use i <- and_then(opt_i)
+          use Foo { j } <- and_then(opt_j)
+          Some(i + j)
+        }
+    "#
+    );
+}
+
 #[test]
 fn format_nested_if() {
     assert_format!(
@@ -890,6 +979,17 @@ fn fuzzer_annotations() {
     );
 }
 
+#[test]
+fn format_benchmark() {
+    assert_format!(
+        r#"
+        bench foo(xs: List<Int> via list(int())) {
+          list.length(xs)
+        }
+        "#
+    );
+}
+
 #[test]
 fn preserve_associativity_parens_in_binop() {
     assert_format!(
@@ -1420,3 +1520,274 @@ fn multiline_if_is_2() {
         "#
     );
 }
+
+#[test]
+fn format_cfg_attributes() {
+    assert_format!(
+        r#"
+        @cfg(feature="debug")
+        use aiken/builtin
+
+        /// Only with debug
+        @cfg(  feature = "debug" )
+        pub fn foo(n: Int) -> Int {
+            @cfg(feature = "debug")
+            trace @"foo": n
+            @cfg(feature = "debug")
+            expect n > 0
+
+            let m = n + 1
+            m
+        }
+        "#
+    );
+}
+
+#[test]
+fn format_narrow_max_width() {
+    assert_format!(
+        r#"
+        pub fn foo(amount: Int, recipient: ByteArray, deadline: Int) -> Bool {
+            amount > 0 && deadline > 1000
+        }
+        "#,
+        FormatOptions {
+            max_width: 40,
+            ..FormatOptions::default()
+        }
+    );
+}
+
+#[test]
+fn format_wide_max_width() {
+    assert_format!(
+        r#"
+        pub fn foo(
+            amount: Int,
+            recipient: ByteArray,
+            deadline: Int,
+        ) -> Bool {
+            let total = list.foldl([amount_in_lovelace, fee_in_lovelace, deposit], 0, fn(n, acc) { n + acc })
+            total > 0
+        }
+        "#,
+        FormatOptions {
+            max_width: 120,
+            ..FormatOptions::default()
+        }
+    );
+}
+
+#[test]
+fn format_merge_duplicate_imports() {
+    assert_format!(
+        r#"
+        use aiken/collection/list.{map}
+        @cfg(feature = "debug")
+        use aiken/collection/list
+        use aiken/collection/list
+        use aiken/collection/list.{foldr, map}
+        use aiken/collection/list as l
+        "#
+    );
+}
+
+#[test]
+fn format_group_imports() {
+    assert_format!(
+        r#"
+        use foo/utils
+        use sundae/multisig
+        use cardano/transaction.{Transaction}
+        use env
+        use aiken/collection/list
+        use calculus/math
+        use foo
+        "#,
+        FormatOptions {
+            local_modules: vec!["foo".to_string(), "env".to_string()],
+            ..FormatOptions::default()
+        }
+    );
+}
+
+#[test]
+fn format_group_imports_custom_order() {
+    assert_format!(
+        r#"
+        use foo/utils
+        use sundae/multisig
+        use cardano/transaction.{Transaction}
+        use aiken/collection/list
+        "#,
+        FormatOptions {
+            import_groups: vec![ImportGroup::Local],
+            local_modules: vec!["foo".to_string()],
+            ..FormatOptions::default()
+        }
+    );
+}
+
+#[test]
+fn format_ungrouped_imports() {
+    assert_format!(
+        r#"
+        use foo/utils
+        use sundae/multisig
+        use aiken/collection/list
+        "#,
+        FormatOptions {
+            import_groups: vec![],
+            local_modules: vec!["foo".to_string()],
+            ..FormatOptions::default()
+        }
+    );
+}
+
+#[test]
+fn format_record_fields_empty_lines() {
+    assert_format!(
+        r#"
+        type Settings {
+          owner: ByteArray,
+          delegate: Option<ByteArray>,
+
+
+          fee: Int,
+
+          deadline: Int,
+        }
+        "#
+    );
+}
+
+#[test]
+fn format_constant_blocks() {
+    assert_format!(
+        r#"
+        const min_fee = 1
+        const max_fee = 100
+
+        const fee_address = #"00"
+
+
+
+        const treasury = #"01"
+        "#
+    );
+}
+
+#[test]
+fn format_trailing_comments() {
+    assert_format!(
+        r#"
+        const min_fee = 1 // in lovelace
+        const max_fee: Int = 1_000_000 // also in lovelace
+
+        type Settings {
+          owner: ByteArray, // payment key hash
+          delegate: Option<ByteArray>,
+          fee: Int, // per transaction
+        }
+        "#
+    );
+}
+
+#[test]
+fn format_align_trailing_comments() {
+    assert_format!(
+        r#"
+        const min_fee = 1 // in lovelace
+        const max_fee: Int = 1_000_000 // also in lovelace
+
+        const threshold = 3 // alone
+
+        type Settings {
+          owner: ByteArray, // payment key hash
+          delegate: Option<ByteArray>,
+          fee: Int, // per transaction
+
+          deadline: PosixTime, // in milliseconds
+          id: Int, // unique
+        }
+        "#,
+        FormatOptions {
+            align_comments: true,
+            ..FormatOptions::default()
+        }
+    );
+}
+
+#[test]
+fn format_doc_comment_code_blocks() {
+    assert_format!(
+        r#"
+        //// ```aiken
+        //// use aiken/collection/list
+        //// const xs = [1,2,3]
+        //// ```
+
+        /// Adds one to every element.
+        ///
+        /// ```aiken
+        /// increment([1,2,  3]) == [2,3,4]
+        /// ```
+        ///
+        /// ```
+        /// increment([1,2,  3])
+        /// ```
+        ///
+        /// ```haskell
+        /// increment   xs = map (+1) xs
+        /// ```
+        ///
+        /// ```aiken
+        /// increment(
+        /// ```
+        pub fn increment(xs: List<Int>) -> List<Int> {
+          xs
+        }
+
+        type Foo {
+          /// ```aiken
+          /// let foo = Foo { bar: 1,
+          ///   }
+          /// foo.bar
+          /// ```
+          bar: Int,
+        }
+        "#
+    );
+}
+
+#[test]
+fn format_no_trailing_commas() {
+    assert_format!(
+        r#"
+        fn foo() {
+          let xs = [some_long_element_name, some_other_long_element_name, yet_another_long_one, foo(bar)]
+          let [first, second, ..] = xs
+          bar(some_long_argument_name, some_other_long_argument_name, yet_another_long_one)
+        }
+        "#,
+        FormatOptions {
+            trailing_commas: false,
+            ..FormatOptions::default()
+        }
+    );
+}
+
+#[test]
+fn format_packed_call_wrapping() {
+    assert_format!(
+        r#"
+        fn foo() {
+          bar(some_long_argument_name, some_other_long_argument_name, yet_another_long_one, and_one_more)
+        }
+        "#,
+        FormatOptions {
+            call_wrapping: CallWrapping::Packed,
+            ..FormatOptions::default()
+        }
+    );
+}
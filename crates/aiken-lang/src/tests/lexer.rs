@@ -1,6 +1,10 @@
 use chumsky::prelude::*;
 
-use crate::{ast::Span, parser::lexer, parser::token::Token};
+use crate::{
+    ast::Span,
+    parser::lexer,
+    parser::token::{StringPart, Token},
+};
 
 #[test]
 fn tokens() {
@@ -35,3 +39,42 @@ fn tokens() {
         ]),
     );
 }
+
+#[test]
+fn interpolated_string() {
+    let code = r#"@"a{x {y}}b\{" @"{}" @"{", @"}" @"{f(@"}")}""#;
+    let len = code.chars().count();
+
+    let span = |i| Span::new((), i..i + 1);
+
+    assert_eq!(
+        lexer::lexer()
+            .parse(chumsky::Stream::from_iter(
+                span(len),
+                code.chars().enumerate().map(|(i, c)| (c, span(i))),
+            ))
+            .map(|tokens| tokens.into_iter().map(|(tok, _)| tok).collect::<Vec<_>>()),
+        Ok(vec![
+            Token::InterpolatedString {
+                parts: vec![
+                    StringPart::Text("a".to_string()),
+                    StringPart::Expr("x {y}".to_string(), 4),
+                    StringPart::Text("b{".to_string()),
+                ]
+            },
+            Token::String {
+                value: "{}".to_string()
+            },
+            Token::String {
+                value: "{".to_string()
+            },
+            Token::Comma,
+            Token::String {
+                value: "}".to_string()
+            },
+            Token::InterpolatedString {
+                parts: vec![StringPart::Expr(r#"f(@"}")"#.to_string(), 35)]
+            },
+        ]),
+    );
+}
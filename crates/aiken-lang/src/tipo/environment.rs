@@ -26,6 +26,41 @@ pub struct ScopeResetData {
     local_values: HashMap<String, ValueConstructor>,
 }
 
+/// A hole found during inference, along with the values that were in scope there.
+#[derive(Debug, Clone)]
+pub struct Hole {
+    pub name: String,
+    pub location: Span,
+    pub tipo: Rc<Type>,
+    pub scope: Vec<(String, Rc<Type>)>,
+}
+
+impl Hole {
+    /// Report the hole, along with the values in scope which have the type it's expected to have.
+    pub fn to_error(&self) -> Error {
+        let expected = self.tipo.to_pretty(0);
+
+        let mut candidates: Vec<String> = if self.tipo.is_monomorphic() {
+            self.scope
+                .iter()
+                .filter(|(_, tipo)| tipo.is_monomorphic() && tipo.to_pretty(0) == expected)
+                .map(|(name, _)| name.clone())
+                .collect()
+        } else {
+            vec![]
+        };
+
+        candidates.sort();
+
+        Error::TypedHole {
+            location: self.location,
+            name: self.name.clone(),
+            expected: self.tipo.clone(),
+            candidates,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Environment<'a> {
     /// Accessors defined in the current module
@@ -86,6 +121,9 @@ pub struct Environment<'a> {
     /// The user-defined target environment referred to as the module 'env'.
     pub target_env: Option<&'a str>,
 
+    /// Holes found so far, reported once the module has been inferred.
+    pub holes: Vec<Hole>,
+
     /// Warnings
     pub warnings: &'a mut Vec<Warning>,
 }
@@ -367,6 +405,7 @@ impl<'a> Environment<'a> {
             | Definition::DataType { .. }
             | Definition::Use { .. }
             | Definition::Test { .. }
+            | Definition::Benchmark { .. }
             | Definition::ModuleConstant { .. }) => definition,
         }
     }
@@ -798,6 +837,7 @@ impl<'a> Environment<'a> {
             current_module,
             current_kind,
             annotations: HashMap::new(),
+            holes: vec![],
             warnings,
             entity_usages: vec![HashMap::new()],
             validator_params: HashSet::new(),
@@ -1061,7 +1101,8 @@ impl<'a> Environment<'a> {
                         | Definition::Validator { .. }
                         | Definition::Use { .. }
                         | Definition::ModuleConstant { .. }
-                        | Definition::Test { .. } => None,
+                        | Definition::Test { .. }
+                        | Definition::Benchmark { .. } => None,
                     })
                     .collect::<Vec<Span>>();
 
@@ -1184,6 +1225,7 @@ impl<'a> Environment<'a> {
             Definition::Fn { .. }
             | Definition::Validator { .. }
             | Definition::Test { .. }
+            | Definition::Benchmark { .. }
             | Definition::Use { .. }
             | Definition::ModuleConstant { .. } => {}
         }
@@ -1387,7 +1429,7 @@ impl<'a> Environment<'a> {
                 })
             }
 
-            Definition::Test(test) => {
+            Definition::Test(test) | Definition::Benchmark(test) => {
                 let arguments = test
                     .arguments
                     .iter()
@@ -1532,7 +1574,7 @@ impl<'a> Environment<'a> {
         location: Span,
         allow_cast: bool,
     ) -> Result<(), Error> {
-        if lhs == rhs {
+        if Rc::ptr_eq(&lhs, &rhs) || lhs == rhs {
             return Ok(());
         }
 
@@ -1,7 +1,7 @@
 use super::Type;
 use crate::{
     ast::{Annotation, BinOp, CallArg, LogicalOpChainKind, Span, UntypedFunction, UntypedPattern},
-    error::ExtraData,
+    error::{Coded, ErrorCode, ExtraData},
     expr::{self, AssignmentPattern, UntypedAssignmentKind, UntypedExpr},
     format::Formatter,
     levenshtein,
@@ -1029,6 +1029,20 @@ The best thing to do from here is to remove it."#))]
         location: Span,
     },
 
+    #[error("I caught a benchmark with the wrong number of arguments.\n")]
+    #[diagnostic(code("illegal::bench::arity"))]
+    #[diagnostic(help(
+        "Benchmarks take exactly one argument, drawn {via} a {Sampler}; that is, a function from a size to a {Fuzzer}. Here I've found a benchmark definition with {count} arguments. If you need to provide multiple values to a benchmark, use a Record or a Tuple.",
+        via = "via".if_supports_color(Stderr, |s| s.yellow()),
+        Sampler = "Sampler".if_supports_color(Stderr, |s| s.cyan()),
+        Fuzzer = "Fuzzer".if_supports_color(Stderr, |s| s.cyan()),
+    ))]
+    IncorrectBenchmarkArity {
+        count: usize,
+        #[label("expected exactly one argument")]
+        location: Span,
+    },
+
     #[error("I caught a test with an illegal return type.\n")]
     #[diagnostic(code("illegal::test::return"))]
     #[diagnostic(help(
@@ -1097,6 +1111,34 @@ The best thing to do from here is to remove it."#))]
         #[label("redundant fallback handler")]
         fallback: Span,
     },
+
+    #[error(
+        "I found a hole '{}' where I expected a value of type {}.\n",
+        format!("?{name}").if_supports_color(Stdout, |s| s.purple()),
+        expected.to_pretty(0).if_supports_color(Stdout, |s| s.green()),
+    )]
+    #[diagnostic(code("typed_hole"))]
+    #[diagnostic(help(
+        "{}",
+        if candidates.is_empty() {
+            "There's nothing of that type in scope; you'll have to build one.".to_string()
+        } else {
+            format!(
+                "Values of that type in scope:\n{}",
+                candidates
+                    .iter()
+                    .map(|name| format!("-> {}", name.if_supports_color(Stdout, |s| s.cyan())))
+                    .join("\n")
+            )
+        }
+    ))]
+    TypedHole {
+        #[label("{}", expected.to_pretty(0))]
+        location: Span,
+        name: String,
+        expected: Rc<Type>,
+        candidates: Vec<String>,
+    },
 }
 
 impl ExtraData for Error {
@@ -1150,6 +1192,7 @@ impl ExtraData for Error {
             | Error::UpdateMultiConstructorType { .. }
             | Error::ValidatorImported { .. }
             | Error::IncorrectTestArity { .. }
+            | Error::IncorrectBenchmarkArity { .. }
             | Error::IllegalTestType { .. }
             | Error::GenericLeftAtBoundary { .. }
             | Error::UnexpectedMultiPatternAssignment { .. }
@@ -1158,6 +1201,7 @@ impl ExtraData for Error {
             | Error::UnknownPurpose { .. }
             | Error::UnknownValidatorHandler { .. }
             | Error::UnexpectedValidatorFallback { .. }
+            | Error::TypedHole { .. }
             | Error::MustInferFirst { .. } => None,
 
             Error::UnknownType { name, .. }
@@ -1168,6 +1212,79 @@ impl ExtraData for Error {
     }
 }
 
+impl Coded for Error {
+    fn error_code(&self) -> Option<ErrorCode> {
+        let code = match self {
+            Error::LogicalOpChainMissingExpr { .. } => 100,
+            Error::CastDataNoAnn { .. } => 101,
+            Error::CouldNotUnify { .. } => 102,
+            Error::CyclicTypeDefinitions { .. } => 103,
+            Error::DuplicateArgument { .. } => 104,
+            Error::DuplicateConstName { .. } => 105,
+            Error::DuplicateField { .. } => 106,
+            Error::DuplicateImport { .. } => 107,
+            Error::DuplicateName { .. } => 108,
+            Error::DuplicateTypeName { .. } => 109,
+            Error::DuplicateVarInPattern { .. } => 110,
+            Error::ExtraVarInAlternativePattern { .. } => 111,
+            Error::ExpectOnOpaqueType { .. } => 112,
+            Error::FunctionTypeInData { .. } => 113,
+            Error::IllegalTypeInData { .. } => 114,
+            Error::IllegalComparison { .. } => 115,
+            Error::ImplicitlyDiscardedExpression { .. } => 116,
+            Error::IncorrectFieldsArity { .. } => 117,
+            Error::IncorrectFunctionCallArity { .. } => 118,
+            Error::IncorrectPatternArity { .. } => 119,
+            Error::IncorrectTupleArity { .. } => 120,
+            Error::IncorrectTypeArity { .. } => 121,
+            Error::KeywordInModuleName { .. } => 122,
+            Error::LastExpressionIsAssignment { .. } => 123,
+            Error::MissingVarInAlternativePattern { .. } => 124,
+            Error::NotIndexable { .. } => 125,
+            Error::NotExhaustivePatternMatch { .. } => 126,
+            Error::NotFn { .. } => 127,
+            Error::PositionalArgumentAfterLabeled { .. } => 128,
+            Error::PrivateTypeLeak { .. } => 129,
+            Error::RedundantMatchClause { .. } => 130,
+            Error::RecordAccessUnknownType { .. } => 131,
+            Error::RecordUpdateInvalidConstructor { .. } => 132,
+            Error::RecursiveType { .. } => 133,
+            Error::TupleIndexOutOfBound { .. } => 134,
+            Error::PairIndexOutOfBound { .. } => 135,
+            Error::UnexpectedLabeledArg { .. } => 136,
+            Error::UnexpectedLabeledArgInPattern { .. } => 137,
+            Error::UnexpectedMultiPatternAssignment { .. } => 138,
+            Error::UnknownLabels { .. } => 139,
+            Error::UnknownModule { .. } => 140,
+            Error::UnknownEnvironment { .. } => 141,
+            Error::UnknownModuleField { .. } => 142,
+            Error::UnknownModuleType { .. } => 143,
+            Error::UnknownModuleValue { .. } => 144,
+            Error::UnknownRecordField { .. } => 145,
+            Error::UnknownType { .. } => 146,
+            Error::UnknownTypeConstructor { .. } => 147,
+            Error::UnknownVariable { .. } => 148,
+            Error::UnnecessarySpreadOperator { .. } => 149,
+            Error::UpdateMultiConstructorType { .. } => 150,
+            Error::ValidatorImported { .. } => 151,
+            Error::ValidatorMustReturnBool { .. } => 152,
+            Error::IncorrectValidatorArity { .. } => 153,
+            Error::IncorrectTestArity { .. } => 154,
+            Error::IncorrectBenchmarkArity { .. } => 155,
+            Error::IllegalTestType { .. } => 156,
+            Error::GenericLeftAtBoundary { .. } => 157,
+            Error::UnknownPurpose { .. } => 158,
+            Error::UnknownValidatorHandler { .. } => 159,
+            Error::UnexpectedValidatorFallback { .. } => 160,
+            Error::TypedHole { .. } => 161,
+            // Internal to the type-checker, never reported.
+            Error::MustInferFirst { .. } => return None,
+        };
+
+        Some(ErrorCode(code))
+    }
+}
+
 impl Error {
     pub fn call_situation(mut self) -> Self {
         if let Error::UnknownRecordField {
@@ -1854,6 +1971,33 @@ impl ExtraData for Warning {
     }
 }
 
+impl Coded for Warning {
+    fn error_code(&self) -> Option<ErrorCode> {
+        let code = match self {
+            Warning::AllFieldsRecordUpdate { .. } => 500,
+            Warning::ImplicitlyDiscardedResult { .. } => 501,
+            Warning::NoFieldsRecordUpdate { .. } => 502,
+            Warning::SingleWhenClause { .. } => 503,
+            Warning::SingleConstructorExpect { .. } => 504,
+            Warning::Todo { .. } => 505,
+            Warning::UnexpectedTypeHole { .. } => 506,
+            Warning::UnusedConstructor { .. } => 507,
+            Warning::UnusedImportedModule { .. } => 508,
+            Warning::UnusedImportedValueOrType { .. } => 509,
+            Warning::UnusedPrivateFunction { .. } => 510,
+            Warning::UnusedPrivateModuleConstant { .. } => 511,
+            Warning::UnusedType { .. } => 512,
+            Warning::UnusedVariable { .. } => 513,
+            Warning::UseWhenInstead { .. } => 514,
+            Warning::DiscardedLetAssignment { .. } => 515,
+            Warning::ValidatorInLibraryModule { .. } => 516,
+            Warning::Utf8ByteArrayIsValidHexString { .. } => 517,
+        };
+
+        Some(ErrorCode(code))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UnifyErrorSituation {
     /// Clauses in a case expression were found to return different types.
@@ -1,6 +1,6 @@
 use super::{
     environment::{
-        assert_no_labeled_arguments, collapse_links, generalise, EntityKind, Environment,
+        assert_no_labeled_arguments, collapse_links, generalise, EntityKind, Environment, Hole,
     },
     error::{Error, Warning},
     hydrator::Hydrator,
@@ -15,7 +15,7 @@ use crate::{
         RecordUpdateSpread, Span, TraceKind, TraceLevel, Tracing, TypedArg, TypedCallArg,
         TypedClause, TypedIfBranch, TypedPattern, TypedRecordUpdateArg, TypedValidator, UnOp,
         UntypedArg, UntypedAssignmentKind, UntypedClause, UntypedFunction, UntypedIfBranch,
-        UntypedPattern, UntypedRecordUpdateArg,
+        UntypedPattern, UntypedRecordUpdateArg, PIPE_VARIABLE,
     },
     builtins::{from_default_function, BUILTIN},
     expr::{FnStyle, TypedExpr, UntypedExpr},
@@ -253,12 +253,14 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         typed_clauses: &[TypedClause],
         location: Span,
     ) -> Result<(), Error> {
-        // Currently guards in exhaustiveness checking are assumed that they can fail,
+        // Guards in exhaustiveness checking are assumed that they can fail,
         // so we go through all clauses and pluck out only the patterns
         // for clauses that don't have guards.
         let mut patterns = Vec::new();
         for clause in typed_clauses {
-            patterns.push(&clause.pattern);
+            if clause.guard.is_none() {
+                patterns.push(&clause.pattern);
+            }
         }
 
         self.environment
@@ -310,19 +312,22 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
         let mut arguments = Vec::new();
 
-        for (index, (tipo, arg)) in args_types.iter_mut().zip(args).enumerate() {
+        for (tipo, arg) in args_types.iter_mut().zip(args) {
             let CallArg {
                 label,
                 value,
                 location,
             } = arg;
 
+            let is_piped = matches!(&value, UntypedExpr::Var { name, .. } if name == PIPE_VARIABLE);
+
             let value = self.infer_call_argument(value, tipo.clone());
 
             // This is so that we can annotate the error properly
             // with the pipe type mismatch situation when this is called from
-            // `infer_pipeline`
-            let value = if index == 0 {
+            // `infer_pipeline`; the piped value may be in any position when
+            // piping into a function capture.
+            let value = if is_piped {
                 value.map_err(map_err)?
             } else {
                 value?
@@ -437,6 +442,8 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         match expr {
             UntypedExpr::ErrorTerm { location } => Ok(self.infer_error_term(location)),
 
+            UntypedExpr::Hole { location, name } => Ok(self.infer_hole(name, location)),
+
             UntypedExpr::Var { location, name } => self.infer_var(name, location),
 
             UntypedExpr::UInt {
@@ -456,6 +463,10 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
 
             UntypedExpr::String { location, value } => Ok(self.infer_string(value, location)),
 
+            UntypedExpr::InterpolatedString { location, parts } => {
+                self.infer_interpolated_string(parts, location)
+            }
+
             UntypedExpr::LogicalOpChain {
                 kind,
                 expressions,
@@ -1521,7 +1532,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     }
 
     #[allow(clippy::result_large_err)]
-    fn infer_call_argument(
+    pub(crate) fn infer_call_argument(
         &mut self,
         value: UntypedExpr,
         tipo: Rc<Type>,
@@ -1535,7 +1546,8 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             // about what the expected arguments are. This extra information
             // when type checking the function body means that the
             // `record.field` access syntax can be used, and improves error
-            // messages.
+            // messages. Field captures (`_.field`) rely on it to know the type
+            // of the record they access.
             (
                 Type::Fn {
                     args: expected_arguments,
@@ -1549,12 +1561,18 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                     location,
                     fn_style,
                 },
-            ) if fn_style != FnStyle::Capture && expected_arguments.len() == arguments.len() => {
+            ) if (fn_style != FnStyle::Capture
+                || matches!(
+                    body.as_ref(),
+                    UntypedExpr::FieldAccess { .. } | UntypedExpr::TupleIndex { .. }
+                ))
+                && expected_arguments.len() == arguments.len() =>
+            {
                 self.infer_fn(
                     arguments,
                     expected_arguments,
                     *body,
-                    false,
+                    fn_style == FnStyle::Capture,
                     return_annotation,
                     location,
                 )
@@ -1577,13 +1595,22 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     ) -> Result<Vec<TypedClause>, Error> {
         let UntypedClause {
             patterns,
+            guard,
             then,
             location,
         } = clause;
 
-        let (then, typed_patterns) = self.in_new_scope(|scope| {
+        let (guard, then, typed_patterns) = self.in_new_scope(|scope| {
             let typed_patterns = scope.infer_clause_pattern(patterns, subject, &location)?;
 
+            let guard = guard
+                .map(|guard| {
+                    let guard = scope.infer(guard)?;
+                    scope.unify(Type::bool(), guard.tipo(), guard.location(), false)?;
+                    Ok::<_, Error>(guard)
+                })
+                .transpose()?;
+
             let then = if let Some(filler) =
                 recover_from_no_assignment(assert_no_assignment(&then), then.location())?
             {
@@ -1592,7 +1619,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 scope.infer(then)?
             };
 
-            Ok::<_, Error>((then, typed_patterns))
+            Ok::<_, Error>((guard, then, typed_patterns))
         })?;
 
         Ok(typed_patterns
@@ -1600,6 +1627,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             .map(|pattern| TypedClause {
                 location,
                 pattern,
+                guard: guard.clone(),
                 then: then.clone(),
             })
             .collect())
@@ -2384,6 +2412,29 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         TypedExpr::ErrorTerm { location, tipo }
     }
 
+    /// Holes are typed like error terms, so that inference carries on and settles the type
+    /// expected in their place; they're only reported once the whole module is inferred.
+    fn infer_hole(&mut self, name: String, location: Span) -> TypedExpr {
+        let tipo = self.new_unbound_var();
+
+        let scope = self
+            .environment
+            .scope
+            .iter()
+            .filter(|(name, _)| name.as_str() != PIPE_VARIABLE)
+            .map(|(name, value)| (name.clone(), value.tipo.clone()))
+            .collect();
+
+        self.environment.holes.push(Hole {
+            name,
+            location,
+            tipo: tipo.clone(),
+            scope,
+        });
+
+        TypedExpr::ErrorTerm { location, tipo }
+    }
+
     #[allow(clippy::result_large_err)]
     fn infer_trace_arg(&mut self, arg: UntypedExpr) -> Result<TypedExpr, Error> {
         let typed_arg = self.infer(arg)?;
@@ -2401,6 +2452,44 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         }
     }
 
+    /// Interpolated expressions are turned into strings the same way trace arguments are, and
+    /// the parts are then appended together; adjacent constant parts are folded upfront.
+    #[allow(clippy::result_large_err)]
+    fn infer_interpolated_string(
+        &mut self,
+        parts: Vec<UntypedExpr>,
+        location: Span,
+    ) -> Result<TypedExpr, Error> {
+        let mut folded: Vec<TypedExpr> = Vec::with_capacity(parts.len());
+
+        for part in parts {
+            let part = self.infer_trace_arg(part)?;
+
+            if let (
+                Some(TypedExpr::String {
+                    location, value, ..
+                }),
+                TypedExpr::String {
+                    location: next_location,
+                    value: next,
+                    ..
+                },
+            ) = (folded.last_mut(), &part)
+            {
+                value.push_str(next);
+                *location = location.union(*next_location);
+                continue;
+            }
+
+            folded.push(part);
+        }
+
+        Ok(folded
+            .into_iter()
+            .reduce(append_string_expr)
+            .unwrap_or_else(|| self.infer_string(String::new(), location)))
+    }
+
     #[allow(clippy::result_large_err)]
     fn infer_trace(
         &mut self,
@@ -2717,6 +2806,7 @@ fn assert_no_assignment(expr: &UntypedExpr) -> Result<(), Error> {
         | UntypedExpr::ByteArray { .. }
         | UntypedExpr::Call { .. }
         | UntypedExpr::ErrorTerm { .. }
+        | UntypedExpr::Hole { .. }
         | UntypedExpr::FieldAccess { .. }
         | UntypedExpr::If { .. }
         | UntypedExpr::UInt { .. }
@@ -2725,6 +2815,7 @@ fn assert_no_assignment(expr: &UntypedExpr) -> Result<(), Error> {
         | UntypedExpr::RecordUpdate { .. }
         | UntypedExpr::Sequence { .. }
         | UntypedExpr::String { .. }
+        | UntypedExpr::InterpolatedString { .. }
         | UntypedExpr::Tuple { .. }
         | UntypedExpr::Pair { .. }
         | UntypedExpr::TupleIndex { .. }
@@ -12,7 +12,7 @@ use crate::{
         TypedDefinition, TypedModule, TypedValidator, UntypedArg, UntypedDefinition, UntypedModule,
         UntypedPattern, UntypedValidator, Use, Validator,
     },
-    expr::{TypedExpr, UntypedAssignmentKind},
+    expr::{TypedExpr, UntypedAssignmentKind, UntypedExpr},
     tipo::{expr::infer_function, Span, Type, TypeVar},
     IdGenerator,
 };
@@ -81,6 +81,7 @@ impl UntypedModule {
                 Definition::Validator { .. } => (),
                 Definition::Fn { .. }
                 | Definition::Test { .. }
+                | Definition::Benchmark { .. }
                 | Definition::TypeAlias { .. }
                 | Definition::DataType { .. }
                 | Definition::Use { .. } => not_consts.push(def),
@@ -94,6 +95,12 @@ impl UntypedModule {
             definitions.push(definition);
         }
 
+        // Holes are only reported now, once their expected type has been settled by the
+        // rest of the module.
+        if let Some(hole) = environment.holes.first() {
+            return Err(hole.to_error());
+        }
+
         // Generalise functions now that the entire module has been inferred
         let definitions = definitions
             .into_iter()
@@ -346,63 +353,15 @@ fn infer_definition(
                         });
                     }
 
-                    let typed_via = ExprTyper::new(environment, tracing).infer(arg.via.clone())?;
-
-                    let hydrator: &mut Hydrator = hydrators.get_mut(&f.name).unwrap();
-
-                    let provided_inner_type = arg
-                        .arg
-                        .annotation
-                        .as_ref()
-                        .map(|ann| hydrator.type_from_annotation(ann, environment))
-                        .transpose()?;
-
-                    let (inferred_annotation, inferred_inner_type) = infer_fuzzer(
+                    let (typed_via, inferred_inner_type, inferred_annotation) = infer_argument_via(
+                        &f.name,
+                        arg,
+                        hydrators,
                         environment,
-                        provided_inner_type.clone(),
-                        &typed_via.tipo(),
-                        &arg.via.location(),
+                        tracing,
+                        infer_fuzzer,
                     )?;
 
-                    // Ensure that the annotation, if any, matches the type inferred from the
-                    // Fuzzer.
-                    if let Some(provided_inner_type) = provided_inner_type {
-                        if !arg
-                            .arg
-                            .annotation
-                            .as_ref()
-                            .unwrap()
-                            .is_logically_equal(&inferred_annotation)
-                        {
-                            return Err(Error::CouldNotUnify {
-                                location: arg.arg.location,
-                                expected: inferred_inner_type.clone(),
-                                given: provided_inner_type.clone(),
-                                situation: Some(UnifyErrorSituation::FuzzerAnnotationMismatch),
-                                rigid_type_names: hydrator.rigid_names(),
-                            });
-                        }
-                    }
-
-                    // Replace the pre-registered type for the test function, to allow inferring
-                    // the function body with the right type arguments.
-                    let scope = environment
-                        .scope
-                        .get_mut(&f.name)
-                        .expect("Could not find preregistered type for test");
-                    if let Type::Fn {
-                        ref ret,
-                        ref alias,
-                        args: _,
-                    } = scope.tipo.as_ref()
-                    {
-                        scope.tipo = Rc::new(Type::Fn {
-                            ret: ret.clone(),
-                            args: vec![inferred_inner_type.clone()],
-                            alias: alias.clone(),
-                        })
-                    }
-
                     Ok((
                         Some((typed_via, inferred_inner_type)),
                         Some(inferred_annotation),
@@ -464,6 +423,53 @@ fn infer_definition(
             }))
         }
 
+        Definition::Benchmark(f) => {
+            let arg = match &f.arguments[..] {
+                [arg] => arg,
+                _ => {
+                    return Err(Error::IncorrectBenchmarkArity {
+                        count: f.arguments.len(),
+                        location: f
+                            .arguments
+                            .get(1)
+                            .map(|arg| arg.arg.location)
+                            .unwrap_or(f.location),
+                    });
+                }
+            };
+
+            let (typed_via, inferred_inner_type, annotation) =
+                infer_argument_via(&f.name, arg, hydrators, environment, tracing, infer_sampler)?;
+
+            let typed_f = infer_function(&f.into(), module_name, hydrators, environment, tracing)?;
+
+            let arg = typed_f
+                .arguments
+                .first()
+                .expect("has exactly one argument")
+                .to_owned();
+
+            Ok(Definition::Benchmark(Function {
+                doc: typed_f.doc,
+                location: typed_f.location,
+                name: typed_f.name,
+                public: typed_f.public,
+                arguments: vec![ArgVia {
+                    arg: TypedArg {
+                        tipo: inferred_inner_type,
+                        annotation: Some(annotation),
+                        ..arg
+                    },
+                    via: typed_via,
+                }],
+                return_annotation: typed_f.return_annotation,
+                return_type: typed_f.return_type,
+                body: typed_f.body,
+                on_test_failure: typed_f.on_test_failure,
+                end_position: typed_f.end_position,
+            }))
+        }
+
         Definition::TypeAlias(TypeAlias {
             doc,
             location,
@@ -689,6 +695,133 @@ fn infer_definition(
     }
 }
 
+/// Infer the type of an argument drawn 'via' a fuzzer (for tests) or a sampler (for benchmarks),
+/// and make it the argument of the pre-registered test or benchmark function, so that its body
+/// can be inferred with the right type arguments.
+#[allow(clippy::result_large_err)]
+#[allow(clippy::type_complexity)]
+fn infer_argument_via(
+    name: &str,
+    arg: &ArgVia<UntypedArg, UntypedExpr>,
+    hydrators: &mut HashMap<String, Hydrator>,
+    environment: &mut Environment<'_>,
+    tracing: Tracing,
+    infer_via: fn(
+        &mut Environment<'_>,
+        Option<Rc<Type>>,
+        &Rc<Type>,
+        &Span,
+    ) -> Result<(Annotation, Rc<Type>), Error>,
+) -> Result<(TypedExpr, Rc<Type>, Annotation), Error> {
+    let typed_via = ExprTyper::new(environment, tracing).infer(arg.via.clone())?;
+
+    let hydrator: &mut Hydrator = hydrators.get_mut(name).unwrap();
+
+    let provided_inner_type = arg
+        .arg
+        .annotation
+        .as_ref()
+        .map(|ann| hydrator.type_from_annotation(ann, environment))
+        .transpose()?;
+
+    let (inferred_annotation, inferred_inner_type) = infer_via(
+        environment,
+        provided_inner_type.clone(),
+        &typed_via.tipo(),
+        &arg.via.location(),
+    )?;
+
+    // Ensure that the annotation, if any, matches the type inferred from the
+    // Fuzzer.
+    if let Some(provided_inner_type) = provided_inner_type {
+        if !arg
+            .arg
+            .annotation
+            .as_ref()
+            .unwrap()
+            .is_logically_equal(&inferred_annotation)
+        {
+            return Err(Error::CouldNotUnify {
+                location: arg.arg.location,
+                expected: inferred_inner_type.clone(),
+                given: provided_inner_type.clone(),
+                situation: Some(UnifyErrorSituation::FuzzerAnnotationMismatch),
+                rigid_type_names: hydrator.rigid_names(),
+            });
+        }
+    }
+
+    // Replace the pre-registered type for the test function, to allow inferring
+    // the function body with the right type arguments.
+    let scope = environment
+        .scope
+        .get_mut(name)
+        .expect("Could not find preregistered type for test");
+    if let Type::Fn {
+        ref ret,
+        ref alias,
+        args: _,
+    } = scope.tipo.as_ref()
+    {
+        scope.tipo = Rc::new(Type::Fn {
+            ret: ret.clone(),
+            args: vec![inferred_inner_type.clone()],
+            alias: alias.clone(),
+        })
+    }
+
+    Ok((typed_via, inferred_inner_type, inferred_annotation))
+}
+
+/// Infer the type of values produced by a sampler; that is, a function from a size to a fuzzer:
+///
+/// `type Sampler<a> = fn(Int) -> Fuzzer<a>`
+#[allow(clippy::result_large_err)]
+fn infer_sampler(
+    environment: &mut Environment<'_>,
+    expected_inner_type: Option<Rc<Type>>,
+    tipo: &Rc<Type>,
+    location: &Span,
+) -> Result<(Annotation, Rc<Type>), Error> {
+    let could_not_unify = || Error::CouldNotUnify {
+        location: *location,
+        expected: Type::sampler(
+            expected_inner_type
+                .clone()
+                .unwrap_or_else(|| Type::generic_var(0)),
+        ),
+        given: tipo.clone(),
+        situation: None,
+        rigid_type_names: HashMap::new(),
+    };
+
+    match tipo.borrow() {
+        Type::Fn { ret, args, .. } if args.len() == 1 => {
+            environment
+                .unify(args[0].clone(), Type::int(), *location, false)
+                .map_err(|_| could_not_unify())?;
+
+            infer_fuzzer(environment, expected_inner_type, ret, location)
+        }
+
+        Type::Var { tipo, alias } => match &*tipo.deref().borrow() {
+            TypeVar::Link { tipo } => infer_sampler(
+                environment,
+                expected_inner_type,
+                &Type::with_alias(tipo.clone(), alias.clone()),
+                location,
+            ),
+            _ => Err(Error::GenericLeftAtBoundary {
+                location: *location,
+            }),
+        },
+
+        Type::Fn { .. } | Type::App { .. } | Type::Tuple { .. } | Type::Pair { .. } => {
+            Err(could_not_unify())
+        }
+    }
+}
+
 #[allow(clippy::result_large_err)]
 fn infer_fuzzer(
     environment: &mut Environment<'_>,
@@ -73,6 +73,13 @@ impl<'a, 'b> PatternTyper<'a, 'b> {
             }
 
             PatternMode::Alternative(assigned) => {
+                if assigned.iter().any(|existing| existing == name) {
+                    return Err(Error::DuplicateVarInPattern {
+                        name: name.to_string(),
+                        location: err_location,
+                    });
+                }
+
                 match self.environment.scope.get(name) {
                     // This variable was defined in the Initial multi-pattern
                     Some(initial) if self.initial_pattern_vars.contains(name) => {
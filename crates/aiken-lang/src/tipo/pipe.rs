@@ -5,7 +5,7 @@ use super::{
 };
 use crate::{
     ast::{AssignmentKind, CallArg, Pattern, Span, PIPE_VARIABLE},
-    expr::{TypedExpr, UntypedExpr},
+    expr::{FnStyle, TypedExpr, UntypedExpr},
 };
 use std::{ops::Deref, rc::Rc};
 use vec1::Vec1;
@@ -103,6 +103,16 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
                     }
                 }
 
+                // left |> right(..args, _, ..args)
+                UntypedExpr::Fn {
+                    fn_style: FnStyle::Capture,
+                    arguments,
+                    body,
+                    ..
+                } if arguments.len() == 1 && matches!(body.as_ref(), UntypedExpr::Call { .. }) => {
+                    self.infer_capture_pipe(*body)?
+                }
+
                 // right(left)
                 call => self.infer_apply_pipe(call)?,
             };
@@ -251,10 +261,62 @@ impl<'a, 'b, 'c> PipeTyper<'a, 'b, 'c> {
         })
     }
 
+    /// Attempt to infer a |> b(c, _, d) as b(c, a, d)
+    #[allow(clippy::result_large_err)]
+    fn infer_capture_pipe(&mut self, call: UntypedExpr) -> Result<TypedExpr, Error> {
+        let UntypedExpr::Call {
+            fun,
+            arguments,
+            location,
+        } = call
+        else {
+            unreachable!("function capture found not to have a function call body")
+        };
+
+        let arguments = arguments
+            .into_iter()
+            .map(|arg| {
+                if arg.is_capture_hole() {
+                    CallArg {
+                        value: self.untyped_left_hand_value_variable(),
+                        ..arg
+                    }
+                } else {
+                    arg
+                }
+            })
+            .collect();
+
+        let function = self.expr_typer.infer(*fun)?;
+
+        let (fun, args, tipo) =
+            self.expr_typer
+                .do_infer_call_with_known_fun(function, arguments, location, |e| {
+                    e.with_unify_error_situation(UnifyErrorSituation::PipeTypeMismatch)
+                })?;
+
+        Ok(TypedExpr::Call {
+            location,
+            tipo,
+            args,
+            fun: Box::new(fun),
+        })
+    }
+
     /// Attempt to infer a |> b as b(a)
     #[allow(clippy::result_large_err)]
     fn infer_apply_pipe(&mut self, func: UntypedExpr) -> Result<TypedExpr, Error> {
-        let func = Box::new(self.expr_typer.infer(func)?);
+        // Field captures (`_.field`) can only be inferred knowing the type of the record they
+        // access, which is that of the left hand side.
+        let func = Box::new(if func.is_field_capture() {
+            let tipo = Type::function(
+                vec![self.argument_type.clone()],
+                self.expr_typer.new_unbound_var(),
+            );
+            self.expr_typer.infer_call_argument(func, tipo)?
+        } else {
+            self.expr_typer.infer(func)?
+        });
         let return_type = self.expr_typer.new_unbound_var();
 
         // Ensure that the function accepts one argument of the correct type
@@ -30,7 +30,11 @@ pub fn parse_document(document: &lsp_types::TextDocumentIdentifier) -> Option<Pa
 
     // NOTE: The 'ModuleKind' second argument doesn't matter. This is just added to the final
     // object but has no influence on the parsing.
-    let (untyped_module, _) = aiken_lang::parser::module(&source_code, ModuleKind::Lib).ok()?;
+    //
+    // Syntax errors are ignored, so that quickfixes remain available while some other part of the
+    // document is being edited.
+    let (untyped_module, _) =
+        aiken_lang::parser::module_with_recovery(&source_code, ModuleKind::Lib).0?;
 
     Some(ParsedDocument {
         definitions: untyped_module.definitions,
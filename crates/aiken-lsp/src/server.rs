@@ -158,13 +158,26 @@ impl Server {
         let path = params.text_document.uri.path();
         let mut new_text = String::new();
 
+        let options = self
+            .config
+            .as_ref()
+            .map(|config| config.fmt.clone())
+            .unwrap_or_default()
+            .options(aiken_project::format::local_modules(&self.root));
+
         match self.edited.get(path) {
             Some(src) => {
                 let (module, extra) = parser::module(src, ModuleKind::Lib).map_err(|errs| {
                     aiken_project::error::Error::from_parse_errors(errs, Path::new(path), src)
                 })?;
 
-                aiken_lang::format::pretty(&mut new_text, module, extra, src);
+                aiken_lang::format::pretty_with_options(
+                    &mut new_text,
+                    module,
+                    extra,
+                    src,
+                    &options,
+                );
             }
             None => {
                 let src = {
@@ -187,7 +200,13 @@ impl Server {
                     aiken_project::error::Error::from_parse_errors(errs, Path::new(path), &src)
                 })?;
 
-                aiken_lang::format::pretty(&mut new_text, module, extra, &src);
+                aiken_lang::format::pretty_with_options(
+                    &mut new_text,
+                    module,
+                    extra,
+                    &src,
+                    &options,
+                );
             }
         }
 
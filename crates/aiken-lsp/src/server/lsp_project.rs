@@ -32,10 +32,13 @@ impl LspProject {
         let result = self.project.check(
             true,
             None,
+            vec![],
             false,
             false,
             u32::default(),
             PropertyTest::DEFAULT_MAX_SUCCESS,
+            false,
+            None,
             Tracing::verbose(),
             None,
         );
@@ -0,0 +1,280 @@
+use crate::{blueprint::Blueprint, error::Error};
+use pallas_addresses::{Address, Network, ShelleyDelegationPart, ShelleyPaymentPart, StakePayload};
+use pallas_crypto::hash::Hash;
+use serde_json::json;
+use std::fmt::{self, Display};
+
+/// What's inside an address: its network, and the credentials controlling its payment and stake
+/// parts, matched against the validators of a blueprint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressInfo {
+    /// The address, bech32-encoded; or base58-encoded for bootstrap (Byron) addresses.
+    pub address: String,
+    pub kind: AddressKind,
+    pub network: Option<Network>,
+    pub payment: Option<Credential>,
+    pub stake: Option<Delegation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressKind {
+    Base,
+    Pointer,
+    Enterprise,
+    Reward,
+    Bootstrap,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub is_script: bool,
+    pub hash: Hash<28>,
+    /// Titles of the validators whose hash is that of the credential, if it's a script.
+    pub validators: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Delegation {
+    Credential(Credential),
+    Pointer { slot: u64, tx_ix: u64, cert_ix: u64 },
+}
+
+impl AddressInfo {
+    /// Decode a bech32 or base16-encoded address.
+    pub fn decode(address: &str) -> Result<Self, Error> {
+        let address = Address::from_bech32(address)
+            .or_else(|_| Address::from_hex(address))
+            .map_err(|error| Error::MalformedAddress { error: Some(error) })?;
+
+        Ok(match &address {
+            Address::Byron(byron) => AddressInfo {
+                address: byron.to_base58(),
+                kind: AddressKind::Bootstrap,
+                network: None,
+                payment: None,
+                stake: None,
+            },
+            Address::Shelley(shelley) => AddressInfo {
+                address: shelley
+                    .to_bech32()
+                    .map_err(|error| Error::MalformedAddress { error: Some(error) })?,
+                kind: match shelley.delegation() {
+                    ShelleyDelegationPart::Key(..) | ShelleyDelegationPart::Script(..) => {
+                        AddressKind::Base
+                    }
+                    ShelleyDelegationPart::Pointer(..) => AddressKind::Pointer,
+                    ShelleyDelegationPart::Null => AddressKind::Enterprise,
+                },
+                network: Some(shelley.network()),
+                payment: Some(match shelley.payment() {
+                    ShelleyPaymentPart::Key(hash) => Credential::key(*hash),
+                    ShelleyPaymentPart::Script(hash) => Credential::script(*hash),
+                }),
+                stake: match shelley.delegation() {
+                    ShelleyDelegationPart::Key(hash) => {
+                        Some(Delegation::Credential(Credential::key(*hash)))
+                    }
+                    ShelleyDelegationPart::Script(hash) => {
+                        Some(Delegation::Credential(Credential::script(*hash)))
+                    }
+                    ShelleyDelegationPart::Pointer(pointer) => Some(Delegation::Pointer {
+                        slot: pointer.slot(),
+                        tx_ix: pointer.tx_idx(),
+                        cert_ix: pointer.cert_idx(),
+                    }),
+                    ShelleyDelegationPart::Null => None,
+                },
+            },
+            Address::Stake(stake) => AddressInfo {
+                address: stake
+                    .to_bech32()
+                    .map_err(|error| Error::MalformedAddress { error: Some(error) })?,
+                kind: AddressKind::Reward,
+                network: Some(stake.network()),
+                payment: None,
+                stake: Some(Delegation::Credential(match stake.payload() {
+                    StakePayload::Stake(hash) => Credential::key(*hash),
+                    StakePayload::Script(hash) => Credential::script(*hash),
+                })),
+            },
+        })
+    }
+
+    /// Find the validators of the blueprint behind the script credentials of the address.
+    pub fn match_validators(&mut self, blueprint: &Blueprint) {
+        let stake = match &mut self.stake {
+            Some(Delegation::Credential(credential)) => Some(credential),
+            Some(Delegation::Pointer { .. }) | None => None,
+        };
+
+        for credential in self.payment.iter_mut().chain(stake) {
+            if credential.is_script {
+                credential.validators = blueprint
+                    .validators
+                    .iter()
+                    .filter(|validator| {
+                        validator.program.compiled_code_and_hash().1 == credential.hash
+                    })
+                    .map(|validator| validator.title.clone())
+                    .collect();
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "address": self.address,
+            "kind": self.kind.to_string(),
+            "network": self.network.map(fmt_network),
+            "payment": self.payment.as_ref().map(Credential::to_json),
+            "stake": match &self.stake {
+                Some(Delegation::Credential(credential)) => credential.to_json(),
+                Some(Delegation::Pointer { slot, tx_ix, cert_ix }) => json!({
+                    "pointer": {
+                        "slot": slot,
+                        "transaction_index": tx_ix,
+                        "certificate_index": cert_ix,
+                    }
+                }),
+                None => serde_json::Value::Null,
+            },
+        })
+    }
+}
+
+impl Credential {
+    fn key(hash: Hash<28>) -> Self {
+        Credential {
+            is_script: false,
+            hash,
+            validators: vec![],
+        }
+    }
+
+    fn script(hash: Hash<28>) -> Self {
+        Credential {
+            is_script: true,
+            hash,
+            validators: vec![],
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        if self.is_script {
+            json!({ "script": self.hash.to_string(), "validators": self.validators })
+        } else {
+            json!({ "key": self.hash.to_string() })
+        }
+    }
+}
+
+impl Display for AddressKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AddressKind::Base => "base",
+            AddressKind::Pointer => "pointer",
+            AddressKind::Enterprise => "enterprise",
+            AddressKind::Reward => "reward",
+            AddressKind::Bootstrap => "bootstrap",
+        })
+    }
+}
+
+impl Display for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_script {
+            return write!(f, "key {}", self.hash);
+        }
+
+        write!(f, "script {}", self.hash)?;
+
+        if self.validators.is_empty() {
+            write!(f, " (no matching validator)")
+        } else {
+            write!(f, " ({})", self.validators.join(", "))
+        }
+    }
+}
+
+impl Display for AddressInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "address  {}", self.address)?;
+        writeln!(f, "kind     {}", self.kind)?;
+
+        if let Some(network) = self.network {
+            writeln!(f, "network  {}", fmt_network(network))?;
+        }
+
+        if let Some(payment) = &self.payment {
+            writeln!(f, "payment  {payment}")?;
+        }
+
+        match &self.stake {
+            Some(Delegation::Credential(credential)) => writeln!(f, "stake    {credential}"),
+            Some(Delegation::Pointer {
+                slot,
+                tx_ix,
+                cert_ix,
+            }) => writeln!(f, "stake    pointer ({slot}, {tx_ix}, {cert_ix})"),
+            None => Ok(()),
+        }
+    }
+}
+
+fn fmt_network(network: Network) -> String {
+    match network {
+        Network::Mainnet => "mainnet".to_string(),
+        Network::Testnet => "testnet".to_string(),
+        Network::Other(tag) => format!("other ({tag})"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pallas_addresses::ShelleyAddress;
+    use std::str::FromStr;
+
+    #[test]
+    fn decode_base_address() {
+        let script =
+            Hash::<28>::from_str("6e1f7b4d6ab1be8c0a6a2bd9f5e3b6d1f1e3e4f4c7a8b9c0d1e2f3a4")
+                .unwrap();
+        let key = Hash::<28>::from_str("00112233445566778899aabbccddeeff00112233445566778899aabb")
+            .unwrap();
+
+        let address = ShelleyAddress::new(
+            Network::Testnet,
+            ShelleyPaymentPart::Script(script),
+            ShelleyDelegationPart::Key(key),
+        );
+
+        let bech32 = address.to_bech32().unwrap();
+
+        let info = AddressInfo::decode(&bech32).unwrap();
+
+        assert_eq!(
+            info,
+            AddressInfo {
+                address: bech32,
+                kind: AddressKind::Base,
+                network: Some(Network::Testnet),
+                payment: Some(Credential::script(script)),
+                stake: Some(Delegation::Credential(Credential::key(key))),
+            }
+        );
+
+        assert_eq!(
+            AddressInfo::decode(&hex::encode(address.to_vec())).unwrap(),
+            info
+        );
+    }
+
+    #[test]
+    fn decode_malformed_address() {
+        assert!(matches!(
+            AddressInfo::decode("addr_test1nope"),
+            Err(Error::MalformedAddress { .. })
+        ));
+    }
+}
@@ -0,0 +1,270 @@
+use crate::error::Error;
+use aiken_lang::test_framework::BenchmarkResult;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Default location of the baseline file, relative to the project's root.
+pub const DEFAULT_BASELINE: &str = "benchmarks.json";
+
+/// Default tolerance, in percents, before an increase of budget counts as a regression.
+pub const DEFAULT_THRESHOLD: f64 = 5.0;
+
+/// Seed used for benchmarks when neither given, nor recorded in a baseline.
+pub const DEFAULT_SEED: u32 = 42;
+
+/// Results of benchmarks, as recorded in a baseline file so that later runs can be compared
+/// against them. Benchmarks are keyed by '{module}.{name}'.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub seed: u32,
+    pub benchmarks: BTreeMap<String, Summary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub mem: Percentiles,
+    pub cpu: Percentiles,
+    pub measures: Vec<Measure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measure {
+    pub size: usize,
+    pub mem: i64,
+    pub cpu: i64,
+}
+
+/// Percentiles of the budget spent over all runs of a benchmark, using the nearest-rank method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Percentiles {
+    pub min: i64,
+    pub p50: i64,
+    pub p90: i64,
+    pub p99: i64,
+    pub max: i64,
+}
+
+/// A benchmark whose budget grew beyond the tolerated threshold, compared to its baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub benchmark: String,
+    pub metric: String,
+    pub baseline: i64,
+    pub current: i64,
+}
+
+impl Percentiles {
+    pub fn new(values: impl IntoIterator<Item = i64>) -> Option<Self> {
+        let mut values = values.into_iter().collect::<Vec<_>>();
+
+        if values.is_empty() {
+            return None;
+        }
+
+        values.sort_unstable();
+
+        let rank = |p: usize| {
+            let ix = ((p * values.len() + 99) / 100).max(1) - 1;
+            values[ix]
+        };
+
+        Some(Percentiles {
+            min: values[0],
+            p50: rank(50),
+            p90: rank(90),
+            p99: rank(99),
+            max: values[values.len() - 1],
+        })
+    }
+
+    fn metrics(&self) -> [(&'static str, i64); 3] {
+        [("p50", self.p50), ("p90", self.p90), ("max", self.max)]
+    }
+}
+
+impl Summary {
+    pub fn new(result: &BenchmarkResult) -> Option<Self> {
+        Some(Summary {
+            mem: Percentiles::new(result.measures.iter().map(|(_, budget)| budget.mem))?,
+            cpu: Percentiles::new(result.measures.iter().map(|(_, budget)| budget.cpu))?,
+            measures: result
+                .measures
+                .iter()
+                .map(|(size, budget)| Measure {
+                    size: *size,
+                    mem: budget.mem,
+                    cpu: budget.cpu,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl Baseline {
+    /// Summarize successful benchmarks; failed ones are left out.
+    pub fn new(seed: u32, results: &[BenchmarkResult]) -> Self {
+        Baseline {
+            seed,
+            benchmarks: results
+                .iter()
+                .filter(|result| result.is_success())
+                .filter_map(|result| Summary::new(result).map(|summary| (key(result), summary)))
+                .collect(),
+        }
+    }
+
+    /// Read a baseline from disk, if there's any.
+    pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(path).map_err(|error| Error::FileIo {
+            error,
+            path: path.to_path_buf(),
+        })?;
+
+        Ok(Some(serde_json::from_str(&contents)?))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(self)?;
+
+        fs::write(path, json).map_err(|error| Error::FileIo {
+            error,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Find benchmarks of 'current' whose median, 90th percentile or maximum budget (memory or
+    /// cpu) exceed the baseline by more than 'threshold' percents. Only the worst regression of
+    /// each benchmark is reported. Benchmarks absent from the baseline, or run over different
+    /// sizes, can't be compared and are ignored.
+    pub fn regressions(&self, current: &Baseline, threshold: f64) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+
+        for (benchmark, summary) in current.benchmarks.iter() {
+            let Some(baseline) = self.benchmarks.get(benchmark) else {
+                continue;
+            };
+
+            let sizes = |summary: &Summary| {
+                summary
+                    .measures
+                    .iter()
+                    .map(|measure| measure.size)
+                    .collect::<Vec<_>>()
+            };
+
+            if sizes(baseline) != sizes(summary) {
+                continue;
+            }
+
+            let worst = [
+                ("mem", &baseline.mem, &summary.mem),
+                ("cpu", &baseline.cpu, &summary.cpu),
+            ]
+            .into_iter()
+            .flat_map(|(unit, before, after)| {
+                before.metrics().into_iter().zip(after.metrics()).map(
+                    move |((metric, before), (_, after))| {
+                        (format!("{unit} {metric}"), before, after)
+                    },
+                )
+            })
+            .filter(|(_, before, after)| *after as f64 > *before as f64 * (1.0 + threshold / 100.0))
+            .max_by(|(_, a_before, a_after), (_, b_before, b_after)| {
+                growth(*a_before, *a_after).total_cmp(&growth(*b_before, *b_after))
+            });
+
+            if let Some((metric, before, after)) = worst {
+                regressions.push(Regression {
+                    benchmark: benchmark.clone(),
+                    metric,
+                    baseline: before,
+                    current: after,
+                });
+            }
+        }
+
+        regressions
+    }
+}
+
+/// The key of a benchmark in baseline files.
+pub fn key(result: &BenchmarkResult) -> String {
+    format!("{}.{}", result.bench.module, result.bench.name)
+}
+
+fn growth(before: i64, after: i64) -> f64 {
+    (after - before) as f64 / before.max(1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(cpu: &[i64]) -> Summary {
+        Summary {
+            mem: Percentiles::new(cpu.iter().map(|n| n / 10)).unwrap(),
+            cpu: Percentiles::new(cpu.iter().copied()).unwrap(),
+            measures: cpu
+                .iter()
+                .enumerate()
+                .map(|(size, cpu)| Measure {
+                    size,
+                    mem: cpu / 10,
+                    cpu: *cpu,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn percentiles_nearest_rank() {
+        assert_eq!(Percentiles::new(vec![]), None);
+
+        assert_eq!(
+            Percentiles::new((1..=10).rev()),
+            Some(Percentiles {
+                min: 1,
+                p50: 5,
+                p90: 9,
+                p99: 10,
+                max: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn regressions_beyond_threshold() {
+        let baseline = Baseline {
+            seed: 42,
+            benchmarks: BTreeMap::from([
+                ("foo.bar".to_string(), summary(&[100, 200, 300, 400])),
+                ("foo.baz".to_string(), summary(&[100, 200, 300, 400])),
+            ]),
+        };
+
+        let current = Baseline {
+            seed: 42,
+            benchmarks: BTreeMap::from([
+                ("foo.bar".to_string(), summary(&[100, 200, 310, 440])),
+                ("foo.baz".to_string(), summary(&[100, 200, 300, 410])),
+                ("foo.new".to_string(), summary(&[1000, 2000])),
+            ]),
+        };
+
+        assert_eq!(
+            baseline.regressions(&current, 5.0),
+            vec![Regression {
+                benchmark: "foo.bar".to_string(),
+                metric: "cpu max".to_string(),
+                baseline: 400,
+                current: 440,
+            }]
+        );
+
+        assert!(baseline.regressions(&current, 10.0).is_empty());
+    }
+}
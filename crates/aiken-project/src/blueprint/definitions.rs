@@ -49,6 +49,18 @@ impl<T> Definitions<T> {
         self.inner.get(&reference.as_key()).and_then(|v| v.as_ref())
     }
 
+    /// Iterate over all resolved definitions, in order of their keys.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.inner
+            .iter()
+            .filter_map(|(key, value)| value.as_ref().map(|value| (key.as_str(), value)))
+    }
+
+    /// Iterate mutably over all resolved definitions, in order of their keys.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.inner.values_mut().flatten()
+    }
+
     /// Merge two set of definitions together. Prioritize callee.
     pub fn merge(&mut self, other: &mut Definitions<T>) {
         self.inner.append(&mut other.inner);
@@ -2,7 +2,7 @@ use super::{
     definitions::Reference,
     schema::{self, Schema},
 };
-use aiken_lang::ast::Span;
+use aiken_lang::{ast::Span, plutus_version::PlutusVersion};
 use miette::{Diagnostic, NamedSource};
 use owo_colors::{OwoColorize, Stream::Stdout};
 use pallas_codec::minicbor as cbor;
@@ -15,7 +15,7 @@ pub enum Error {
     #[diagnostic(help("{}", error.help()))]
     #[diagnostic(code("aiken::blueprint::interface"))]
     Schema {
-        error: schema::Error,
+        error: Box<schema::Error>,
         #[label("invalid validator's boundary")]
         location: Span,
         #[source_code]
@@ -32,6 +32,64 @@ pub enum Error {
     ))]
     InvalidOrMissingFile,
 
+    #[error("I couldn't find the hash of the sources the blueprint was built from.")]
+    #[diagnostic(code("aiken::blueprint::source::missing"))]
+    #[diagnostic(help(
+        "Blueprints only record the hash of their sources when built by {build} from a project. Was it produced by an older version of the compiler?",
+        build = "aiken build"
+            .if_supports_color(Stdout, |s| s.purple())
+            .if_supports_color(Stdout, |s| s.bold())
+    ))]
+    MissingSourceHash,
+
+    #[error("I found that the blueprint wasn't built from these sources.")]
+    #[diagnostic(code("aiken::blueprint::source::mismatch"))]
+    #[diagnostic(help(
+        "The blueprint records sources with hash:\n\n  {expected}\n\nbut the sources at hand hash to:\n\n  {found}\n\nThe configuration (aiken.toml), the manifest (aiken.lock) and modules must be exactly those the blueprint was built from.",
+        expected = expected.if_supports_color(Stdout, |s| s.green()),
+        found = found.if_supports_color(Stdout, |s| s.red()),
+    ))]
+    SourceMismatch { expected: String, found: String },
+
+    #[error("I found two blueprints for the same package: {package}.")]
+    #[diagnostic(code("aiken::blueprint::merge::duplicate"))]
+    #[diagnostic(help(
+        "Validators are namespaced by the title of the package they come from when merging blueprints, which must therefore be unique. Did you give the same blueprint twice?"
+    ))]
+    DuplicatePackage { package: String },
+
+    #[error(
+        "I couldn't merge the blueprint of {package} as it targets Plutus {found:?} where others target Plutus {expected:?}."
+    )]
+    #[diagnostic(code("aiken::blueprint::merge::plutus_version"))]
+    #[diagnostic(help(
+        "A blueprint declares a single Plutus version for all its validators. You may convert the blueprint beforehand with {convert}, or recompile the package for the same version as others.",
+        convert = "aiken blueprint convert --to blueprint --plutus"
+            .if_supports_color(Stdout, |s| s.purple())
+            .if_supports_color(Stdout, |s| s.bold())
+    ))]
+    PlutusVersionMismatch {
+        package: String,
+        expected: PlutusVersion,
+        found: PlutusVersion,
+    },
+
+    #[error("I couldn't derive token metadata from an annotated type: {reason}.")]
+    #[diagnostic(code("aiken::blueprint::metadata"))]
+    #[diagnostic(help(
+        "Types annotated with {cip25} or {cip68} in their doc comment must be records whose fields are byte arrays (as text), integers, lists, other such records or {data}. Optional fields are allowed, but not for keys required by the standard.",
+        cip25 = "@cip25".if_supports_color(Stdout, |s| s.purple()),
+        cip68 = "@cip68".if_supports_color(Stdout, |s| s.purple()),
+        data = "Data".if_supports_color(Stdout, |s| s.green()),
+    ))]
+    Metadata {
+        reason: String,
+        #[label("annotated here")]
+        location: Span,
+        #[source_code]
+        source_code: NamedSource<String>,
+    },
+
     #[error("I didn't find any parameters to apply in the given validator.")]
     #[diagnostic(code("aiken::blueprint::apply::no_parameters"))]
     NoParametersToApply,
@@ -76,6 +134,17 @@ pub enum Error {
     ))]
     NonConstantParameter,
 
+    #[error("I couldn't find any {what} declared for the given validator.")]
+    #[diagnostic(code("aiken::blueprint::check::missing"))]
+    #[diagnostic(help(
+        "Only spending handlers declare a datum, whereas any handler declares a redeemer. Here are the handlers of this validator I know of:\n\n{}",
+        known_handlers.iter().map(|title| format!("→ {}", title.if_supports_color(Stdout, |s| s.purple()))).collect::<Vec<_>>().join("\n")
+    ))]
+    NoSchemaToCheck {
+        what: String,
+        known_handlers: Vec<String>,
+    },
+
     #[error("I couldn't find a definition corresponding to a reference.")]
     #[diagnostic(code("aiken::blueprint::apply::unknown::reference"))]
     #[diagnostic(help(
@@ -87,11 +156,20 @@ pub enum Error {
     #[error("I caught a parameter application that seems off.")]
     #[diagnostic(code("aiken::blueprint::apply::mismatch"))]
     #[diagnostic(help(
-        "When applying parameters to a validator, I control that the shape of the parameter you give me matches what is specified in the blueprint. Unfortunately, it didn't match in this case.\n\nI am looking at the following value:\n\n{term}\n\nbut failed to match it against the specified schema:\n\n{expected}\n\n\nNOTE: this may only represent part of a bigger whole as I am validating the parameter incrementally.",
+        "When applying parameters to a validator, I control that the shape of the parameter you give me matches what is specified in the blueprint. Unfortunately, it didn't match in this case.\n\nI am looking at the following value{at}:\n\n{term}\n\nbut failed to match it against the specified schema:\n\n{expected}{hint}\n\n\nNOTE: this may only represent part of a bigger whole as I am validating the parameter incrementally.",
+        at = if path.is_empty() {
+            String::new()
+        } else {
+            format!(" at {}", path.if_supports_color(Stdout, |s| s.yellow()))
+        },
         expected = serde_json::to_string_pretty(&schema).unwrap().if_supports_color(Stdout, |s| s.green()),
+        hint = match hint {
+            Some(hint) => format!("\n\n{hint}"),
+            None => String::new(),
+        },
         term = {
             let mut buf = vec![];
-            match term {
+            match term.as_ref() {
                 Constant::Data(data) => {
                     cbor::encode(data, &mut buf).unwrap();
                     cbor::display(&buf).to_string()
@@ -100,7 +178,41 @@ pub enum Error {
             }
         }.if_supports_color(Stdout, |s| s.red()),
     ))]
-    SchemaMismatch { schema: Schema, term: Constant },
+    SchemaMismatch {
+        schema: Box<Schema>,
+        term: Box<Constant>,
+        path: String,
+        hint: Option<String>,
+    },
+
+    #[error(
+        "I found a constructor with {} field(s) where its schema declares {}.",
+        found.if_supports_color(Stdout, |s| s.red()),
+        expected.if_supports_color(Stdout, |s| s.green()),
+    )]
+    #[diagnostic(code("aiken::blueprint::apply::constructor::mismatch"))]
+    #[diagnostic(help(
+        "When applying parameters to a validator, I match each field of a constructor against the schema of the corresponding field declared in the blueprint. Yet, the constructor with index {index}{at} doesn't have as many fields as declared. Here are the declared fields ({minus}) next to the ones I found ({plus}):\n\n{diff}{hint}",
+        at = if path.is_empty() {
+            String::new()
+        } else {
+            format!(" at {}", path.if_supports_color(Stdout, |s| s.yellow()))
+        },
+        minus = "-".if_supports_color(Stdout, |s| s.green()),
+        plus = "+".if_supports_color(Stdout, |s| s.red()),
+        hint = match hint {
+            Some(hint) => format!("\n\n{hint}"),
+            None => String::new(),
+        },
+    ))]
+    ConstructorFieldsMismatch {
+        index: usize,
+        expected: usize,
+        found: usize,
+        diff: String,
+        path: String,
+        hint: Option<String>,
+    },
 
     #[error(
         "I discovered a discrepancy of elements between a given tuple and its declared schema."
@@ -117,6 +229,34 @@ pub enum Error {
     #[diagnostic(code("aiken::blueprint::parse::parameter"))]
     #[diagnostic(help("{hint}"))]
     MalformedParameter { hint: String },
+
+    #[error(
+        "I couldn't convert '{validator}' to Plutus {version:?} as it relies on features unavailable in that version."
+    )]
+    #[diagnostic(code("aiken::blueprint::convert::incompatible"))]
+    #[diagnostic(help(
+        "Here's what the compiled code uses that Plutus {version:?} doesn't support:\n\n{}\n\nRecompiling the project for that version may help, with {plutus} set in {aiken_toml}.",
+        incompatibilities.iter().map(|what| format!("→ {}", what.if_supports_color(Stdout, |s| s.red()))).collect::<Vec<_>>().join("\n"),
+        plutus = "plutus".if_supports_color(Stdout, |s| s.purple()),
+        aiken_toml = "aiken.toml".if_supports_color(Stdout, |s| s.bold()),
+    ))]
+    IncompatiblePlutusVersion {
+        validator: String,
+        version: PlutusVersion,
+        incompatibilities: Vec<String>,
+    },
+
+    #[error("I found an example of '{title}' that doesn't match its schema: {example}")]
+    #[diagnostic(code("aiken::blueprint::example"))]
+    #[diagnostic(help(
+        "{error}\n\n{}",
+        error.help().map(|help| help.to_string()).unwrap_or_default()
+    ))]
+    InvalidExample {
+        title: String,
+        example: String,
+        error: Box<Error>,
+    },
 }
 
 unsafe impl Send for Error {}
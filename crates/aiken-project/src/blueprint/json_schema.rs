@@ -0,0 +1,249 @@
+//! Blueprint schemas as standard JSON Schema (draft 2020-12) documents. Values are described in
+//! the 'detailed schema' JSON representation of Plutus data used by cardano-cli (and `aiken
+//! serve`), e.g. `{ "constructor": 0, "fields": [{ "int": 42 }] }`.
+
+use super::{
+    definitions::Reference,
+    metadata::{detailed_bytes, JSON_SCHEMA},
+    parameter::Parameter,
+    schema::{Annotated, Bounds, Constructor, Data, Declaration, Items, Schema},
+    Blueprint,
+};
+use serde_json::{json, Map, Value};
+
+impl Blueprint {
+    /// A JSON Schema document holding, under `$defs`, the blueprint's definitions and the
+    /// interface of each validator: its datum, its redeemer and its parameters (as an array, in
+    /// order). Interfaces are keyed after the validator's title, e.g. `{title}.datum`.
+    pub fn to_json_schema(&self) -> Value {
+        let mut defs = Map::new();
+
+        for (key, definition) in self.definitions.iter() {
+            defs.insert(key.to_string(), annotated(definition, schema));
+        }
+
+        for validator in self.validators.iter() {
+            for (what, parameter) in [
+                ("datum", &validator.datum),
+                ("redeemer", &validator.redeemer),
+            ] {
+                if let Some(parameter) = parameter {
+                    defs.insert(
+                        format!("{}.{what}", validator.title),
+                        self::parameter(parameter),
+                    );
+                }
+            }
+
+            if !validator.parameters.is_empty() {
+                defs.insert(
+                    format!("{}.parameters", validator.title),
+                    array(validator.parameters.iter().map(self::parameter).collect()),
+                );
+            }
+        }
+
+        let mut document = Map::new();
+        document.insert("$schema".to_string(), json!(JSON_SCHEMA));
+        document.insert("title".to_string(), json!(self.preamble.title));
+        if let Some(description) = &self.preamble.description {
+            document.insert("description".to_string(), json!(description));
+        }
+        document.insert("$defs".to_string(), Value::Object(defs));
+
+        Value::Object(document)
+    }
+}
+
+fn reference(reference: &Reference) -> Value {
+    json!({ "$ref": format!("#/$defs/{}", reference.as_key().replace('/', "~1")) })
+}
+
+fn declaration<T>(declaration: &Declaration<T>, inline: fn(&T) -> Value) -> Value {
+    match declaration {
+        Declaration::Referenced(link) => reference(link),
+        Declaration::Inline(inner) => inline(inner),
+    }
+}
+
+fn annotated<T>(annotated: &Annotated<T>, inner: fn(&T) -> Value) -> Value {
+    describe(
+        inner(&annotated.annotated),
+        annotated.title.as_deref(),
+        annotated.description.as_deref(),
+    )
+}
+
+fn describe(value: Value, title: Option<&str>, description: Option<&str>) -> Value {
+    match value {
+        Value::Object(mut value) => {
+            if let Some(title) = title {
+                value.insert("title".to_string(), json!(title));
+            }
+            if let Some(description) = description {
+                value.insert("description".to_string(), json!(description));
+            }
+            Value::Object(value)
+        }
+        value => value,
+    }
+}
+
+fn parameter(parameter: &Parameter) -> Value {
+    describe(
+        declaration(&parameter.schema, schema),
+        parameter.title.as_deref(),
+        None,
+    )
+}
+
+fn schema(schema: &Schema) -> Value {
+    match schema {
+        Schema::Unit => constr(0, vec![]),
+        Schema::Boolean => json!({ "anyOf": [constr(0, vec![]), constr(1, vec![])] }),
+        Schema::Integer => integer(&Bounds::default()),
+        // Strings don't exist as Plutus data; they're serialised as UTF-8 byte arrays.
+        Schema::Bytes | Schema::String => detailed_bytes(),
+        Schema::Pair(left, right) => list(array(vec![
+            declaration(left, self::schema),
+            declaration(right, self::schema),
+        ])),
+        Schema::List(items) => list(self::items(items, self::schema)),
+        Schema::Data(data) => self::data(data),
+    }
+}
+
+fn data(data: &Data) -> Value {
+    match data {
+        Data::Integer(bounds) => integer(bounds),
+        Data::Bytes(bounds) => bytes(bounds),
+        Data::List(items) => list(self::items(items, self::data)),
+        Data::Map(keys, values) => json!({
+            "type": "object",
+            "required": ["map"],
+            "properties": {
+                "map": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["k", "v"],
+                        "properties": {
+                            "k": declaration(keys, self::data),
+                            "v": declaration(values, self::data),
+                        },
+                    },
+                },
+            },
+        }),
+        Data::AnyOf(constructors) => match &constructors[..] {
+            [constructor] => annotated(constructor, self::constructor),
+            _ => json!({
+                "anyOf": constructors
+                    .iter()
+                    .map(|constructor| annotated(constructor, self::constructor))
+                    .collect::<Vec<_>>(),
+            }),
+        },
+        Data::Opaque => json!({}),
+    }
+}
+
+fn items<T>(items: &Items<T>, inline: fn(&T) -> Value) -> Value {
+    match items {
+        Items::One(item) => json!({ "type": "array", "items": declaration(item, inline) }),
+        Items::Many(items) => array(items.iter().map(|item| declaration(item, inline)).collect()),
+    }
+}
+
+fn constructor(constructor: &Constructor) -> Value {
+    constr(
+        constructor.index,
+        constructor
+            .fields
+            .iter()
+            .map(|field| {
+                describe(
+                    declaration(&field.annotated, self::data),
+                    field.title.as_deref(),
+                    field.description.as_deref(),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn constr(index: usize, fields: Vec<Value>) -> Value {
+    json!({
+        "type": "object",
+        "required": ["constructor", "fields"],
+        "properties": {
+            "constructor": { "const": index },
+            "fields": array(fields),
+        },
+    })
+}
+
+fn integer(bounds: &Bounds<i64>) -> Value {
+    let mut int = json!({ "type": "integer" });
+    if let Some(min) = bounds.min {
+        int["minimum"] = json!(min);
+    }
+    if let Some(max) = bounds.max {
+        int["maximum"] = json!(max);
+    }
+
+    json!({
+        "type": "object",
+        "required": ["int"],
+        "properties": { "int": int },
+    })
+}
+
+// Lengths are in bytes, hence doubled once hex-encoded.
+fn bytes(bounds: &Bounds<usize>) -> Value {
+    let mut bytes = detailed_bytes();
+    if let Some(min) = bounds.min {
+        bytes["properties"]["bytes"]["minLength"] = json!(2 * min);
+    }
+    if let Some(max) = bounds.max {
+        bytes["properties"]["bytes"]["maxLength"] = json!(2 * max);
+    }
+    bytes
+}
+
+fn list(items: Value) -> Value {
+    json!({
+        "type": "object",
+        "required": ["list"],
+        "properties": { "list": items },
+    })
+}
+
+/// An array of a fixed number of elements, each with its own schema.
+fn array(items: Vec<Value>) -> Value {
+    // 'prefixItems' may not be empty.
+    if items.is_empty() {
+        return json!({ "type": "array", "maxItems": 0 });
+    }
+
+    json!({
+        "type": "array",
+        "minItems": items.len(),
+        "maxItems": items.len(),
+        "prefixItems": items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gift_card() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/gift_card/plutus.json"))
+                .unwrap();
+
+        insta::assert_json_snapshot!(blueprint.to_json_schema());
+    }
+}
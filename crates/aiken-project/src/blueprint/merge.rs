@@ -0,0 +1,208 @@
+use super::{definitions::Reference, error::Error, Blueprint, Preamble};
+use std::collections::{BTreeMap, BTreeSet};
+
+impl Blueprint {
+    /// Combine the blueprints of several packages (e.g. the members of a monorepo) into one.
+    ///
+    /// Validator titles are namespaced by the title of the package they come from, so that
+    /// validators of different packages may share module and validator names. Definitions
+    /// shared by several packages are only kept once; those that clash (same key, different
+    /// schemas) are namespaced like validators, and references to them are renamed accordingly.
+    ///
+    /// All blueprints must target the same Plutus version, as it determines how validators
+    /// are hashed.
+    pub fn merge(preamble: Preamble, blueprints: Vec<Blueprint>) -> Result<Blueprint, Error> {
+        let mut packages = BTreeSet::new();
+
+        let mut merged = Blueprint {
+            preamble,
+            validators: vec![],
+            definitions: Default::default(),
+            source: None,
+        };
+
+        for mut blueprint in blueprints {
+            let package = blueprint.preamble.title.clone();
+
+            if !packages.insert(package.clone()) {
+                return Err(Error::DuplicatePackage { package });
+            }
+
+            if blueprint.preamble.plutus_version != merged.preamble.plutus_version {
+                return Err(Error::PlutusVersionMismatch {
+                    package,
+                    expected: merged.preamble.plutus_version,
+                    found: blueprint.preamble.plutus_version,
+                });
+            }
+
+            let clashes = blueprint
+                .definitions
+                .iter()
+                .filter(|(key, definition)| {
+                    merged
+                        .definitions
+                        .try_lookup(&Reference::new(key))
+                        .is_some_and(|existing| existing != *definition)
+                })
+                .map(|(key, _)| (key.to_string(), Reference::new(&format!("{package}/{key}"))))
+                .collect::<BTreeMap<_, _>>();
+
+            for (key, renamed) in clashes.iter() {
+                let original = Reference::new(key);
+                if let Some(definition) = blueprint.definitions.lookup(&original).cloned() {
+                    blueprint.definitions.remove(&original);
+                    blueprint.definitions.insert(renamed, definition);
+                }
+            }
+
+            blueprint.rename(&clashes);
+
+            for (key, definition) in blueprint.definitions.iter() {
+                let reference = Reference::new(key);
+                if merged.definitions.try_lookup(&reference).is_none() {
+                    merged.definitions.insert(&reference, definition.clone());
+                }
+            }
+
+            merged
+                .validators
+                .extend(blueprint.validators.into_iter().map(|mut validator| {
+                    validator.title = format!("{package}/{}", validator.title);
+                    validator
+                }));
+        }
+
+        merged.minimize();
+
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blueprint::schema::Declaration;
+    use serde_json::json;
+
+    fn hello_world() -> Blueprint {
+        serde_json::from_str(include_str!("../../../../examples/hello_world/plutus.json")).unwrap()
+    }
+
+    fn gift_card() -> Blueprint {
+        serde_json::from_str(include_str!("../../../../examples/gift_card/plutus.json")).unwrap()
+    }
+
+    #[test]
+    fn merge_namespaces_validators() {
+        let hello_world = hello_world();
+        let gift_card = gift_card();
+
+        let merged = Blueprint::merge(
+            hello_world.preamble.clone(),
+            vec![hello_world.clone(), gift_card.clone()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            merged
+                .validators
+                .iter()
+                .map(|validator| validator.title.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "aiken-lang/hello_world/hello_world.hello_world.spend",
+                "aiken-lang/hello_world/hello_world.hello_world.else",
+                "aiken-lang/gift_card/multi.redeem.spend",
+                "aiken-lang/gift_card/multi.redeem.mint",
+                "aiken-lang/gift_card/multi.redeem.else",
+                "aiken-lang/gift_card/oneshot.gift_card.spend",
+                "aiken-lang/gift_card/oneshot.gift_card.mint",
+                "aiken-lang/gift_card/oneshot.gift_card.else",
+            ]
+        );
+
+        // Shared definitions are only kept once.
+        assert_eq!(
+            merged
+                .definitions
+                .iter()
+                .map(|(key, _)| key)
+                .filter(|key| key.ends_with("ByteArray"))
+                .collect::<Vec<_>>(),
+            vec!["ByteArray"]
+        );
+    }
+
+    #[test]
+    fn merge_namespaces_clashing_definitions() {
+        let hello_world = hello_world();
+
+        let mut other = hello_world.clone();
+        other.preamble.title = "aiken-lang/other".to_string();
+        other.definitions = serde_json::from_value(json!({
+            "ByteArray": { "dataType": "bytes" },
+            "hello_world/Datum": {
+                "title": "Datum",
+                "anyOf": [{
+                    "title": "Datum",
+                    "dataType": "constructor",
+                    "index": 0,
+                    "fields": []
+                }]
+            },
+            "hello_world/Redeemer": hello_world.definitions.lookup(&Reference::new("hello_world/Redeemer")).unwrap(),
+        }))
+        .unwrap();
+
+        let merged =
+            Blueprint::merge(hello_world.preamble.clone(), vec![hello_world, other]).unwrap();
+
+        assert_eq!(
+            merged
+                .definitions
+                .iter()
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>(),
+            vec![
+                "ByteArray",
+                "aiken-lang/other/hello_world/Datum",
+                "hello_world/Datum",
+                "hello_world/Redeemer",
+            ]
+        );
+
+        assert_eq!(
+            merged.validators[0]
+                .datum
+                .as_ref()
+                .map(|datum| &datum.schema),
+            Some(&Declaration::Referenced(Reference::new(
+                "hello_world/Datum"
+            )))
+        );
+
+        assert_eq!(
+            merged.validators[2]
+                .datum
+                .as_ref()
+                .map(|datum| &datum.schema),
+            Some(&Declaration::Referenced(Reference::new(
+                "aiken-lang/other/hello_world/Datum"
+            )))
+        );
+    }
+
+    #[test]
+    fn merge_rejects_duplicate_packages() {
+        let hello_world = hello_world();
+
+        assert!(matches!(
+            Blueprint::merge(
+                hello_world.preamble.clone(),
+                vec![hello_world.clone(), hello_world]
+            ),
+            Err(Error::DuplicatePackage { .. })
+        ));
+    }
+}
@@ -0,0 +1,661 @@
+//! Token metadata derived from Aiken records, following CIP-25 (transaction metadata under the
+//! label 721) and CIP-68 (inline datums of reference tokens).
+//!
+//! Records opt in through a line in their doc comment:
+//!
+//! - `@cip25`: a template of the transaction metadata, and a JSON schema validating it;
+//! - `@cip68` or `@cip68(<label>)`: the schema of the reference token's datum, and a JSON schema
+//!   validating such datum once decoded as JSON (with the 'detailed schema' used by cardano-cli
+//!   and `aiken serve`). The label defaults to 222 (NFT); 333 (FT) and 444 (RFT) also exist.
+//!
+//! Fields map onto metadata keys in camelCase, and optional fields onto optional keys.
+
+use super::{
+    error::Error,
+    schema::{Annotated, Bounds, Constructor, Data, Declaration, Schema},
+};
+use crate::module::{CheckedModule, CheckedModules};
+use aiken_lang::{
+    ast::{Definition, TypedDataType},
+    tipo::Type,
+};
+use miette::NamedSource;
+use serde_json::{json, Map, Value};
+use std::rc::Rc;
+
+pub const CIP25_LABEL: u64 = 721;
+
+const CIP68_LABELS: [u64; 3] = [222, 333, 444];
+
+pub(super) const JSON_SCHEMA: &str = "https://json-schema.org/draft/2020-12/schema";
+
+#[derive(Debug, PartialEq, Clone, serde::Serialize)]
+pub struct Metadata {
+    /// The annotated record, as '{module}.{type}'.
+    pub title: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cip25: Option<Cip25>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cip68: Option<Cip68>,
+}
+
+#[derive(Debug, PartialEq, Clone, serde::Serialize)]
+pub struct Cip25 {
+    /// Transaction metadata to fill in, with placeholders for the policy id and asset name.
+    pub template: Value,
+    /// A JSON schema for the metadata of a single asset.
+    pub schema: Value,
+}
+
+#[derive(Debug, PartialEq, Clone, serde::Serialize)]
+pub struct Cip68 {
+    pub label: u64,
+    /// The reference token's datum, as a blueprint schema.
+    pub datum: Annotated<Schema>,
+    /// A JSON schema for that same datum, in the 'detailed schema' JSON representation.
+    pub schema: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Standard {
+    Cip25,
+    Cip68(u64),
+}
+
+impl Standard {
+    fn required_keys(&self) -> &'static [&'static str] {
+        match self {
+            Standard::Cip25 | Standard::Cip68(222) => &["name", "image"],
+            Standard::Cip68(333) => &["name", "description"],
+            Standard::Cip68(_) => &["name", "image", "decimals"],
+        }
+    }
+}
+
+impl std::fmt::Display for Standard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Standard::Cip25 => write!(f, "CIP-25"),
+            Standard::Cip68(label) => write!(f, "CIP-68 ({label})"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    key: String,
+    description: Option<String>,
+    required: bool,
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Text,
+    Integer,
+    List(Box<Kind>),
+    Object(Vec<Field>),
+    Any,
+}
+
+impl Metadata {
+    /// Collect metadata of all annotated records across the given modules, ordered by title.
+    pub fn collect(modules: &CheckedModules) -> Result<Vec<Metadata>, Error> {
+        let mut metadata = vec![];
+
+        for module in modules.values() {
+            for def in module.ast.definitions() {
+                if let Definition::DataType(data_type) = def {
+                    if let Some(m) = Metadata::from_data_type(modules, module, data_type)? {
+                        metadata.push(m);
+                    }
+                }
+            }
+        }
+
+        metadata.sort_by(|a, b| a.title.cmp(&b.title));
+
+        Ok(metadata)
+    }
+
+    fn from_data_type(
+        modules: &CheckedModules,
+        module: &CheckedModule,
+        data_type: &TypedDataType,
+    ) -> Result<Option<Metadata>, Error> {
+        let fail = |reason: String| Error::Metadata {
+            reason,
+            location: data_type.location,
+            source_code: NamedSource::new(
+                module.input_path.display().to_string(),
+                module.code.clone(),
+            ),
+        };
+
+        let standards = annotations(data_type.doc.as_deref().unwrap_or_default()).map_err(fail)?;
+
+        if standards.is_empty() {
+            return Ok(None);
+        }
+
+        let fields = record_fields(modules, &module.name, data_type, &mut vec![]).map_err(fail)?;
+
+        for standard in standards.iter() {
+            let missing = standard
+                .required_keys()
+                .iter()
+                .filter(|key| {
+                    !fields
+                        .iter()
+                        .any(|field| field.key == **key && field.required)
+                })
+                .map(|key| format!("'{key}'"))
+                .collect::<Vec<_>>();
+
+            if !missing.is_empty() {
+                return Err(fail(format!(
+                    "{standard} requires {}, which must be non-optional fields",
+                    missing.join(", ")
+                )));
+            }
+        }
+
+        let title = format!("{}.{}", module.name, data_type.name);
+        let description = data_type
+            .doc
+            .as_deref()
+            .map(|doc| {
+                doc.lines()
+                    .filter(|line| !line.trim().starts_with("@cip"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+                    .trim()
+                    .to_string()
+            })
+            .filter(|doc| !doc.is_empty());
+
+        let mut metadata = Metadata {
+            title,
+            cip25: None,
+            cip68: None,
+        };
+
+        for standard in standards {
+            match standard {
+                Standard::Cip25 => {
+                    let mut json_schema = json_object(&fields);
+                    json_schema["$defs"] = json!({ "text": json_text() });
+
+                    metadata.cip25 = Some(Cip25 {
+                        template: json!({
+                            CIP25_LABEL.to_string(): {
+                                "<policy_id>": {
+                                    "<asset_name>": template(&fields),
+                                },
+                                "version": "2.0",
+                            }
+                        }),
+                        schema: with_header(&data_type.name, description.as_deref(), json_schema),
+                    });
+                }
+                Standard::Cip68(label) => {
+                    let mut json_schema = detailed_datum(&fields);
+                    json_schema["$defs"] = json!({ "bytes": detailed_bytes() });
+
+                    metadata.cip68 = Some(Cip68 {
+                        label,
+                        datum: datum(&data_type.name, description.as_deref()),
+                        schema: with_header(&data_type.name, description.as_deref(), json_schema),
+                    });
+                }
+            }
+        }
+
+        Ok(Some(metadata))
+    }
+}
+
+fn annotations(doc: &str) -> Result<Vec<Standard>, String> {
+    let mut standards = vec![];
+
+    for line in doc.lines().map(str::trim) {
+        if line == "@cip25" {
+            standards.push(Standard::Cip25);
+        } else if line == "@cip68" {
+            standards.push(Standard::Cip68(222));
+        } else if let Some(label) = line
+            .strip_prefix("@cip68(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            match label.trim().parse() {
+                Ok(label) if CIP68_LABELS.contains(&label) => {
+                    standards.push(Standard::Cip68(label))
+                }
+                _ => {
+                    return Err(format!(
+                        "'{}' isn't a known CIP-68 label; expected one of 222, 333 or 444",
+                        label.trim()
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(standards)
+}
+
+fn record_fields(
+    modules: &CheckedModules,
+    module: &str,
+    data_type: &TypedDataType,
+    visiting: &mut Vec<(String, String)>,
+) -> Result<Vec<Field>, String> {
+    let constructor = match &data_type.constructors[..] {
+        [constructor] if data_type.parameters.is_empty() && !data_type.opaque => constructor,
+        _ => {
+            return Err(format!(
+                "'{}' must be a record, with a single constructor and no type parameters",
+                data_type.name
+            ))
+        }
+    };
+
+    let key = (module.to_string(), data_type.name.clone());
+    if visiting.contains(&key) {
+        return Err(format!("'{}' is recursive", data_type.name));
+    }
+    visiting.push(key);
+
+    let fields = constructor
+        .arguments
+        .iter()
+        .map(|arg| {
+            let label = arg
+                .label
+                .as_deref()
+                .ok_or_else(|| format!("the fields of '{}' must all be labeled", data_type.name))?;
+
+            let (required, tipo) = if arg.tipo.is_option() {
+                (false, arg.tipo.get_inner_types()[0].clone())
+            } else {
+                (true, arg.tipo.clone())
+            };
+
+            Ok(Field {
+                key: camel_case(label),
+                description: arg.doc.as_ref().map(|doc| doc.trim().to_string()),
+                required,
+                kind: kind(modules, &tipo, visiting).map_err(|tipo| {
+                    format!("field '{label}' has type '{tipo}', which has no metadata equivalent")
+                })?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>();
+
+    visiting.pop();
+
+    fields
+}
+
+fn kind(
+    modules: &CheckedModules,
+    tipo: &Rc<Type>,
+    visiting: &mut Vec<(String, String)>,
+) -> Result<Kind, String> {
+    let unsupported = || tipo.to_pretty(0);
+
+    if tipo.is_bytearray() {
+        Ok(Kind::Text)
+    } else if tipo.is_int() {
+        Ok(Kind::Integer)
+    } else if tipo.is_data() {
+        Ok(Kind::Any)
+    } else if tipo.is_map() {
+        Err(unsupported())
+    } else if tipo.is_list() {
+        kind(modules, &tipo.get_inner_types()[0], visiting).map(|item| Kind::List(item.into()))
+    } else {
+        let (module, name) = tipo.qualifier().ok_or_else(unsupported)?;
+
+        let data_type = modules
+            .get(&module)
+            .and_then(|m| {
+                m.ast.definitions().find_map(|def| match def {
+                    Definition::DataType(data_type) if data_type.name == name => Some(data_type),
+                    _ => None,
+                })
+            })
+            .ok_or_else(unsupported)?;
+
+        record_fields(modules, &module, data_type, visiting)
+            .map(Kind::Object)
+            .map_err(|_| unsupported())
+    }
+}
+
+fn camel_case(label: &str) -> String {
+    let mut key = String::with_capacity(label.len());
+    let mut upper = false;
+
+    for c in label.chars() {
+        if c == '_' {
+            upper = !key.is_empty();
+        } else if upper {
+            key.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            key.push(c);
+        }
+    }
+
+    key
+}
+
+fn with_header(title: &str, description: Option<&str>, schema: Value) -> Value {
+    let mut header = Map::new();
+    header.insert("$schema".to_string(), json!(JSON_SCHEMA));
+    header.insert("title".to_string(), json!(title));
+    if let Some(description) = description {
+        header.insert("description".to_string(), json!(description));
+    }
+
+    if let Value::Object(schema) = schema {
+        header.extend(schema);
+    }
+
+    Value::Object(header)
+}
+
+fn describe(schema: Value, field: &Field) -> Value {
+    match (schema, &field.description) {
+        (Value::Object(mut schema), Some(description)) => {
+            schema.insert("description".to_string(), json!(description));
+            Value::Object(schema)
+        }
+        (schema, _) => schema,
+    }
+}
+
+fn required(fields: &[Field]) -> Vec<&str> {
+    fields
+        .iter()
+        .filter(|field| field.required)
+        .map(|field| field.key.as_str())
+        .collect()
+}
+
+fn template(fields: &[Field]) -> Value {
+    fn placeholder(kind: &Kind) -> Value {
+        match kind {
+            Kind::Text => json!(""),
+            Kind::Integer => json!(0),
+            Kind::List(..) => json!([]),
+            Kind::Object(fields) => template(fields),
+            Kind::Any => Value::Null,
+        }
+    }
+
+    Value::Object(
+        fields
+            .iter()
+            .map(|field| (field.key.clone(), placeholder(&field.kind)))
+            .collect(),
+    )
+}
+
+// CIP-25 -------------------------------------------------------------------
+
+fn json_object(fields: &[Field]) -> Value {
+    json!({
+        "type": "object",
+        "required": required(fields),
+        "properties": fields
+            .iter()
+            .map(|field| (field.key.clone(), describe(json_value(&field.kind), field)))
+            .collect::<Map<_, _>>(),
+    })
+}
+
+// Metadata strings are limited to 64 bytes; longer ones are split into arrays of chunks.
+fn json_text() -> Value {
+    json!({
+        "anyOf": [
+            { "type": "string", "maxLength": 64 },
+            { "type": "array", "items": { "type": "string", "maxLength": 64 } },
+        ]
+    })
+}
+
+fn json_value(kind: &Kind) -> Value {
+    match kind {
+        Kind::Text => json!({ "$ref": "#/$defs/text" }),
+        Kind::Integer => json!({ "type": "integer" }),
+        Kind::List(item) => json!({ "type": "array", "items": json_value(item) }),
+        Kind::Object(fields) => json_object(fields),
+        Kind::Any => json!({}),
+    }
+}
+
+// CIP-68 -------------------------------------------------------------------
+
+fn datum(name: &str, description: Option<&str>) -> Annotated<Schema> {
+    let field = |title: &str, data: Data| Annotated {
+        title: Some(title.to_string()),
+        description: None,
+        examples: vec![],
+        annotated: Declaration::Inline(data.into()),
+    };
+
+    Annotated {
+        title: Some(name.to_string()),
+        description: description.map(str::to_string),
+        examples: vec![],
+        annotated: Schema::Data(Data::AnyOf(vec![Annotated {
+            title: Some(name.to_string()),
+            description: None,
+            examples: vec![],
+            annotated: Constructor {
+                index: 0,
+                fields: vec![
+                    field(
+                        "metadata",
+                        Data::Map(
+                            Declaration::Inline(Data::Bytes(Bounds::default()).into()),
+                            Declaration::Inline(Data::Opaque.into()),
+                        ),
+                    ),
+                    field("version", Data::Integer(Bounds::default())),
+                    field("extra", Data::Opaque),
+                ],
+            },
+        }])),
+    }
+}
+
+fn detailed_datum(fields: &[Field]) -> Value {
+    json!({
+        "type": "object",
+        "required": ["constructor", "fields"],
+        "properties": {
+            "constructor": { "const": 0 },
+            "fields": {
+                "type": "array",
+                "minItems": 3,
+                "maxItems": 3,
+                "prefixItems": [
+                    detailed_map(fields),
+                    detailed_value(&Kind::Integer),
+                    {},
+                ],
+            },
+        },
+    })
+}
+
+fn detailed_map(fields: &[Field]) -> Value {
+    let key = |field: &Field| json!({ "bytes": hex::encode(field.key.as_bytes()) });
+
+    let mut constraints = vec![];
+
+    for field in fields {
+        constraints.push(json!({
+            "items": {
+                "if": { "properties": { "k": { "const": key(field) } } },
+                "then": { "properties": { "v": describe(detailed_value(&field.kind), field) } },
+            }
+        }));
+
+        if field.required {
+            constraints.push(json!({
+                "contains": { "properties": { "k": { "const": key(field) } } }
+            }));
+        }
+    }
+
+    json!({
+        "type": "object",
+        "required": ["map"],
+        "properties": {
+            "map": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["k", "v"],
+                },
+                "allOf": constraints,
+            },
+        },
+    })
+}
+
+pub(super) fn detailed_bytes() -> Value {
+    json!({
+        "type": "object",
+        "required": ["bytes"],
+        "properties": { "bytes": { "type": "string", "pattern": "^([0-9a-fA-F]{2})*$" } },
+    })
+}
+
+fn detailed_value(kind: &Kind) -> Value {
+    match kind {
+        Kind::Text => json!({ "$ref": "#/$defs/bytes" }),
+        Kind::Integer => json!({
+            "type": "object",
+            "required": ["int"],
+            "properties": { "int": { "type": "integer" } },
+        }),
+        Kind::List(item) => json!({
+            "type": "object",
+            "required": ["list"],
+            "properties": { "list": { "type": "array", "items": detailed_value(item) } },
+        }),
+        Kind::Object(fields) => detailed_map(fields),
+        Kind::Any => json!({}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestProject;
+
+    fn collect(source_code: &str) -> Result<Vec<Metadata>, Error> {
+        let mut project = TestProject::new();
+
+        let modules = CheckedModules::singleton(project.check(project.parse(source_code)));
+
+        Metadata::collect(&modules)
+    }
+
+    #[test]
+    fn nft_metadata() {
+        let metadata = collect(indoc::indoc! {r#"
+            pub type File {
+              name: Option<ByteArray>,
+              media_type: ByteArray,
+              src: ByteArray,
+            }
+
+            /// Metadata of our collection.
+            ///
+            /// @cip25
+            /// @cip68
+            pub type Nft {
+              /// The token's name.
+              name: ByteArray,
+              image: ByteArray,
+              media_type: Option<ByteArray>,
+              files: List<File>,
+              rarity: Int,
+            }
+        "#})
+        .unwrap();
+
+        insta::assert_json_snapshot!(metadata);
+    }
+
+    #[test]
+    fn fungible_token_label() {
+        let metadata = collect(indoc::indoc! {r#"
+            /// @cip68(333)
+            pub type Token {
+              name: ByteArray,
+              description: ByteArray,
+              decimals: Option<Int>,
+            }
+        "#})
+        .unwrap();
+
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata[0].title, "test_module.Token");
+        assert!(metadata[0].cip25.is_none());
+        assert_eq!(
+            metadata[0].cip68.as_ref().map(|cip68| cip68.label),
+            Some(333)
+        );
+    }
+
+    #[test]
+    fn missing_required_key() {
+        let error = collect(indoc::indoc! {r#"
+            /// @cip25
+            pub type Nft {
+              name: ByteArray,
+              image: Option<ByteArray>,
+            }
+        "#})
+        .unwrap_err();
+
+        assert!(
+            matches!(&error, Error::Metadata { reason, .. } if reason.contains("'image'")),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn unsupported_field() {
+        let error = collect(indoc::indoc! {r#"
+            /// @cip68
+            pub type Nft {
+              name: ByteArray,
+              image: ByteArray,
+              burnable: Bool,
+            }
+        "#})
+        .unwrap_err();
+
+        assert!(
+            matches!(&error, Error::Metadata { reason, .. } if reason.contains("'burnable'")),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn camel_case_keys() {
+        assert_eq!(camel_case("media_type"), "mediaType");
+        assert_eq!(camel_case("name"), "name");
+        assert_eq!(camel_case("_private_key"), "privateKey");
+    }
+}
@@ -0,0 +1,185 @@
+use super::{
+    definitions::{Definitions, Reference},
+    schema::{Annotated, Data, Declaration, Items, Schema},
+    Blueprint,
+};
+use std::collections::BTreeMap;
+
+type Rename<'a> = &'a dyn Fn(&mut Reference);
+
+impl Blueprint {
+    /// Merge structurally identical definitions (e.g. instances of a generic type whose
+    /// parameters don't show in its schema), keeping the first one in order of keys and pointing
+    /// references to the others at it instead. Merging definitions may make others identical, so
+    /// this goes on until there's nothing left to merge.
+    pub fn minimize(&mut self) {
+        let mut duplicates = BTreeMap::new();
+
+        loop {
+            self.rename(&duplicates);
+
+            duplicates = self::duplicates(&self.definitions);
+
+            if duplicates.is_empty() {
+                return;
+            }
+
+            for key in duplicates.keys() {
+                self.definitions.remove(&Reference::new(key));
+            }
+        }
+    }
+
+    /// Point references to the given keys at their replacement. References are also normalized
+    /// along the way, as the same one may be spelled differently (e.g. 'a/b' or 'a~1b').
+    pub(super) fn rename(&mut self, replacements: &BTreeMap<String, Reference>) {
+        let rename = |reference: &mut Reference| {
+            let key = reference.as_key();
+            *reference = replacements
+                .get(&key)
+                .cloned()
+                .unwrap_or_else(|| Reference::new(&key));
+        };
+
+        for definition in self.definitions.values_mut() {
+            schema(&mut definition.annotated, &rename);
+        }
+
+        for validator in self.validators.iter_mut() {
+            for parameter in validator
+                .parameters
+                .iter_mut()
+                .chain(validator.datum.iter_mut())
+                .chain(validator.redeemer.iter_mut())
+            {
+                declaration(&mut parameter.schema, &rename, schema);
+            }
+        }
+    }
+}
+
+/// Keys of definitions identical to one coming before, along with a reference to the latter.
+fn duplicates(definitions: &Definitions<Annotated<Schema>>) -> BTreeMap<String, Reference> {
+    let mut canonicals: Vec<(&str, &Annotated<Schema>)> = vec![];
+    let mut duplicates = BTreeMap::new();
+
+    for (key, definition) in definitions.iter() {
+        match canonicals.iter().find(|(_, other)| *other == definition) {
+            Some((canonical, _)) => {
+                duplicates.insert(key.to_string(), Reference::new(canonical));
+            }
+            None => canonicals.push((key, definition)),
+        }
+    }
+
+    duplicates
+}
+
+fn schema(schema: &mut Schema, rename: Rename) {
+    match schema {
+        Schema::Pair(left, right) => {
+            declaration(left, rename, self::schema);
+            declaration(right, rename, self::schema);
+        }
+        Schema::List(items) => self::items(items, rename, self::schema),
+        Schema::Data(data) => self::data(data, rename),
+        Schema::Unit | Schema::Boolean | Schema::Integer | Schema::Bytes | Schema::String => {}
+    }
+}
+
+fn data(data: &mut Data, rename: Rename) {
+    match data {
+        Data::List(items) => self::items(items, rename, self::data),
+        Data::Map(keys, values) => {
+            declaration(keys, rename, self::data);
+            declaration(values, rename, self::data);
+        }
+        Data::AnyOf(constructors) => {
+            for constructor in constructors.iter_mut() {
+                for field in constructor.annotated.fields.iter_mut() {
+                    declaration(&mut field.annotated, rename, self::data);
+                }
+            }
+        }
+        Data::Integer(..) | Data::Bytes(..) | Data::Opaque => {}
+    }
+}
+
+fn items<T>(items: &mut Items<T>, rename: Rename, inline: fn(&mut T, Rename)) {
+    match items {
+        Items::One(item) => declaration(item, rename, inline),
+        Items::Many(items) => {
+            for item in items.iter_mut() {
+                declaration(item, rename, inline);
+            }
+        }
+    }
+}
+
+fn declaration<T>(declaration: &mut Declaration<T>, rename: Rename, inline: fn(&mut T, Rename)) {
+    match declaration {
+        Declaration::Referenced(reference) => rename(reference),
+        Declaration::Inline(inner) => inline(inner, rename),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_identical_definitions() {
+        let mut blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/hello_world/plutus.json"))
+                .unwrap();
+
+        let foo = json!({
+            "title": "Foo",
+            "anyOf": [{
+                "title": "Foo",
+                "dataType": "constructor",
+                "index": 0,
+                "fields": [{ "title": "bar", "$ref": "#/definitions/Int" }]
+            }]
+        });
+
+        blueprint.definitions = serde_json::from_value(json!({
+            "Int": { "dataType": "integer" },
+            "POSIXTime": { "title": "POSIXTime", "dataType": "integer" },
+            "test/Foo$ByteArray": foo,
+            "test/Foo$Int": foo,
+            "List$test/Foo$ByteArray": {
+                "dataType": "list",
+                "items": { "$ref": "#/definitions/test~1Foo$ByteArray" }
+            },
+            "List$test/Foo$Int": {
+                "dataType": "list",
+                "items": { "$ref": "#/definitions/test~1Foo$Int" }
+            },
+        }))
+        .unwrap();
+
+        blueprint.validators[0].redeemer = Some(Reference::new("List$test/Foo$Int").into());
+
+        blueprint.minimize();
+
+        assert_eq!(
+            serde_json::to_value(&blueprint.definitions).unwrap(),
+            json!({
+                "Int": { "dataType": "integer" },
+                "POSIXTime": { "title": "POSIXTime", "dataType": "integer" },
+                "test/Foo$ByteArray": foo,
+                "List$test/Foo$ByteArray": {
+                    "dataType": "list",
+                    "items": { "$ref": "#/definitions/test~1Foo$ByteArray" }
+                },
+            })
+        );
+
+        assert_eq!(
+            blueprint.validators[0].redeemer,
+            Some(Reference::new("List$test/Foo$ByteArray").into())
+        );
+    }
+}
@@ -1,19 +1,26 @@
 pub mod definitions;
 pub mod error;
+mod json_schema;
 mod memo_program;
+mod merge;
+pub mod metadata;
+mod minimize;
 pub mod parameter;
 pub mod schema;
+pub mod source;
+pub mod summary;
 pub mod validator;
 
 use crate::{
-    config::{self, Config, PlutusVersion},
+    config::{self, Config, PlutusVersion, ValidatorConfig},
     module::CheckedModules,
 };
-use aiken_lang::gen_uplc::CodeGenerator;
+use aiken_lang::{gen_uplc::CodeGenerator, line_numbers::LineNumbers};
 use definitions::Definitions;
 pub use error::Error;
 use schema::{Annotated, Schema};
-use std::fmt::Debug;
+use source::{Location, Source};
+use std::{collections::BTreeMap, fmt::Debug};
 use validator::Validator;
 
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
@@ -22,6 +29,9 @@ pub struct Blueprint {
     pub validators: Vec<Validator>,
     #[serde(skip_serializing_if = "Definitions::is_empty", default)]
     pub definitions: Definitions<Annotated<Schema>>,
+    /// Where the blueprint comes from; absent from blueprints built by older compilers.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub source: Option<Source>,
 }
 
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
@@ -41,6 +51,10 @@ pub struct Preamble {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub license: Option<String>,
+
+    /// The environment the blueprint was built against, when the project defines any.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub environment: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
@@ -61,20 +75,69 @@ impl Blueprint {
         config: &Config,
         modules: &CheckedModules,
         generator: &mut CodeGenerator,
+        env: Option<&str>,
     ) -> Result<Self, Error> {
-        let preamble = config.into();
+        let preamble = Preamble {
+            environment: env.map(str::to_string),
+            ..config.into()
+        };
 
         let mut definitions = Definitions::new();
 
+        let mut locations = BTreeMap::new();
+
         let validators: Result<Vec<_>, Error> = modules
             .validators()
             .flat_map(|(validator, def)| {
-                Validator::from_checked_module(modules, generator, validator, def, &config.plutus)
+                let settings = config.validator_config(&validator.name, &def.name);
+
+                let plutus = settings
+                    .and_then(|settings| settings.plutus)
+                    .unwrap_or(config.plutus);
+
+                let mut overridden =
+                    settings.map(|settings| configure(generator.clone(), settings, plutus));
+
+                let schemas = Validator::from_checked_module(
+                    modules,
+                    overridden.as_mut().unwrap_or(&mut *generator),
+                    validator,
+                    def,
+                    &plutus,
+                );
+
+                if let Some(overridden) = overridden {
+                    generator.inherit_elapsed(overridden);
+                }
+
+                let line_numbers = LineNumbers::new(&validator.code);
+
+                schemas
                     .into_iter()
                     .map(|result| {
                         result.map(|mut schema| {
                             definitions.merge(&mut schema.definitions);
                             schema.definitions = Definitions::new();
+                            if let Some(name) = settings.and_then(|s| s.name.as_ref()) {
+                                let handler = schema.title.rsplit('.').next().unwrap_or_default();
+                                schema.title = format!("{}.{name}.{handler}", validator.name);
+                            }
+                            let handler = schema.title.rsplit('.').next().unwrap_or_default();
+                            if let Some(line) = def
+                                .handlers
+                                .iter()
+                                .chain(std::iter::once(&def.fallback))
+                                .find(|func| func.name == handler)
+                                .and_then(|func| line_numbers.line_number(func.location.start))
+                            {
+                                locations.insert(
+                                    schema.title.clone(),
+                                    Location {
+                                        module: validator.name.clone(),
+                                        line,
+                                    },
+                                );
+                            }
                             schema
                         })
                     })
@@ -82,14 +145,61 @@ impl Blueprint {
             })
             .collect();
 
-        Ok(Blueprint {
+        let mut blueprint = Blueprint {
             preamble,
             validators: validators?,
             definitions,
+            source: Some(Source {
+                hash: None,
+                validators: locations,
+            }),
+        };
+
+        blueprint.minimize();
+
+        Ok(blueprint)
+    }
+}
+
+impl Blueprint {
+    /// Convert every validator to the given Plutus version (see
+    /// [`Validator::with_plutus_version`]), and the preamble along.
+    pub fn with_plutus_version(self, version: PlutusVersion) -> Result<Self, Error> {
+        Ok(Blueprint {
+            preamble: Preamble {
+                plutus_version: version,
+                ..self.preamble
+            },
+            validators: self
+                .validators
+                .into_iter()
+                .map(|validator| validator.with_plutus_version(&version))
+                .collect::<Result<_, _>>()?,
+            definitions: self.definitions,
+            source: self.source,
         })
     }
 }
 
+/// Apply per-validator build settings on top of the project's code generator.
+fn configure<'a>(
+    generator: CodeGenerator<'a>,
+    settings: &ValidatorConfig,
+    plutus: PlutusVersion,
+) -> CodeGenerator<'a> {
+    let mut generator = generator.with_plutus_version(plutus);
+
+    if let Some(optimization) = settings.optimization {
+        generator = generator.with_optimization(optimization);
+    }
+
+    if let Some(trace_level) = settings.trace_level {
+        generator = generator.with_trace_level(trace_level);
+    }
+
+    generator
+}
+
 impl Blueprint {
     pub fn lookup(
         &self,
@@ -178,6 +288,7 @@ impl From<&Config> for Preamble {
             plutus_version: config.plutus,
             version: config.version.clone(),
             license: config.license.clone(),
+            environment: None,
         }
     }
 }
@@ -186,7 +297,7 @@ impl From<&Config> for Preamble {
 mod tests {
     use super::*;
     use aiken_lang::tipo::Type;
-    use schema::{Data, Declaration, Items, Schema};
+    use schema::{Bounds, Data, Declaration, Items, Schema};
     use serde_json::{self, json};
     use std::collections::HashMap;
 
@@ -203,9 +314,11 @@ mod tests {
                     version: "1.0.0".to_string(),
                 }),
                 license: Some("Apache-2.0".to_string()),
+                environment: None,
             },
             validators: vec![],
             definitions: Definitions::new(),
+            source: None,
         };
         assert_eq!(
             serde_json::to_value(&blueprint).unwrap(),
@@ -235,9 +348,11 @@ mod tests {
                 plutus_version: PlutusVersion::V2,
                 compiler: None,
                 license: None,
+                environment: None,
             },
             validators: vec![],
             definitions: Definitions::new(),
+            source: None,
         };
         assert_eq!(
             serde_json::to_value(&blueprint).unwrap(),
@@ -253,12 +368,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_with_environment() {
+        let blueprint = Blueprint {
+            preamble: Preamble {
+                title: "Foo".to_string(),
+                description: None,
+                version: "1.0.0".to_string(),
+                plutus_version: PlutusVersion::V3,
+                compiler: None,
+                license: None,
+                environment: Some("mainnet".to_string()),
+            },
+            validators: vec![],
+            definitions: Definitions::new(),
+            source: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&blueprint).unwrap(),
+            json!({
+                "preamble": {
+                    "title": "Foo",
+                    "version": "1.0.0",
+                    "plutusVersion": "v3",
+                    "environment": "mainnet"
+                },
+                "validators": []
+            }),
+        );
+    }
+
     #[test]
     fn serialize_with_definitions() {
         let mut definitions = Definitions::new();
         definitions
             .register::<_, Error>(&Type::int(), &HashMap::new(), |_| {
-                Ok(Schema::Data(Data::Integer).into())
+                Ok(Schema::Data(Data::Integer(Bounds::default())).into())
             })
             .unwrap();
         definitions
@@ -269,7 +414,7 @@ mod tests {
                     let ref_bytes = definitions.register::<_, Error>(
                         &Type::byte_array(),
                         &HashMap::new(),
-                        |_| Ok(Schema::Data(Data::Bytes).into()),
+                        |_| Ok(Schema::Data(Data::Bytes(Bounds::default())).into()),
                     )?;
                     Ok(
                         Schema::Data(Data::List(Items::One(Declaration::Referenced(ref_bytes))))
@@ -287,9 +432,11 @@ mod tests {
                 plutus_version: PlutusVersion::V2,
                 compiler: None,
                 license: None,
+                environment: None,
             },
             validators: vec![],
             definitions,
+            source: None,
         };
         assert_eq!(
             serde_json::to_value(&blueprint).unwrap(),
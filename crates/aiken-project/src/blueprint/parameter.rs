@@ -1,11 +1,14 @@
 use super::{
     definitions::{Definitions, Reference},
     error::Error,
-    schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+    schema::{Annotated, Bounds, Constructor, Data, Declaration, Items, Schema},
 };
-use std::{iter, ops::Deref};
+use pallas_codec::minicbor as cbor;
+use serde_json::Value as Json;
+use std::{fmt::Display, iter, ops::Deref, str::FromStr};
 use uplc::{
-    ast::{Constant, Data as UplcData},
+    ast::{Constant, Data as UplcData, Type},
+    machine::{runtime::convert_tag_to_constr, value::from_pallas_bigint},
     PlutusData,
 };
 
@@ -32,59 +35,149 @@ impl Parameter {
         definitions: &Definitions<Annotated<Schema>>,
         constant: &Constant,
     ) -> Result<(), Error> {
-        let schema = match &self.schema {
-            Declaration::Inline(schema) => schema,
-            Declaration::Referenced(ref link) => {
-                &definitions
-                    .lookup(link)
-                    .map(Ok)
-                    .unwrap_or_else(|| {
-                        Err(Error::UnresolvedSchemaReference {
-                            reference: link.clone(),
-                        })
-                    })?
-                    .annotated
-            }
-        };
+        let schema = self.schema(definitions)?;
+
+        validate_schema(schema, definitions, constant, "")
+    }
+
+    /// Same as `validate`, but also accepting Data-encoded lists and pairs where the schema
+    /// expects builtin ones, and conversely; tools frequently produce the former. Returns the
+    /// term normalized to the representation expected by the schema.
+    pub fn validate_coerced(
+        &self,
+        definitions: &Definitions<Annotated<Schema>>,
+        constant: &Constant,
+    ) -> Result<Constant, Error> {
+        let schema = self.schema(definitions)?;
+
+        let term = coerce_schema(schema, definitions, constant);
+
+        validate_schema(schema, definitions, &term, "")?;
+
+        Ok(term)
+    }
+
+    /// Construct a value for this parameter from JSON, following its schema. Integers are given
+    /// as numbers (or strings, for big ones), byte arrays as hex-encoded strings, lists and tuples
+    /// as arrays, and maps as objects or arrays of key/value pairs. Constructors are designated
+    /// by their title; nullary ones as a mere string (e.g. `"None"`) and others as an object with
+    /// a single key (e.g. `{ "Some": 42 }`). Records may omit their constructor, and their fields
+    /// are given either as an object keyed by field titles, or as an array. Anything opaque is
+    /// expected as hex-encoded CBOR.
+    pub fn from_json(
+        &self,
+        definitions: &Definitions<Annotated<Schema>>,
+        value: &Json,
+    ) -> Result<PlutusData, Error> {
+        match self.schema(definitions)? {
+            Schema::Data(data) => data_from_json(data, definitions, value, ""),
+            _ => Err(Error::MalformedParameter {
+                hint: "Only parameters whose schema is some Plutus data can be given as JSON."
+                    .to_string(),
+            }),
+        }
+    }
 
-        validate_schema(schema, definitions, constant)
+    fn schema<'a>(
+        &'a self,
+        definitions: &'a Definitions<Annotated<Schema>>,
+    ) -> Result<&'a Schema, Error> {
+        match &self.schema {
+            Declaration::Inline(schema) => Ok(schema),
+            Declaration::Referenced(ref link) => Ok(&definitions
+                .lookup(link)
+                .map(Ok)
+                .unwrap_or_else(|| {
+                    Err(Error::UnresolvedSchemaReference {
+                        reference: link.clone(),
+                    })
+                })?
+                .annotated),
+        }
     }
 }
 
-fn mismatch(term: &Constant, schema: Schema) -> Error {
+fn mismatch(term: &Constant, schema: Schema, path: &str) -> Error {
     Error::SchemaMismatch {
-        schema,
-        term: term.clone(),
+        schema: schema.into(),
+        term: term.clone().into(),
+        path: path.to_string(),
+        hint: None,
+    }
+}
+
+/// Point at the declared constructor closest to the one found, if any: preferably one with as
+/// many fields, and otherwise one with the closest number of fields; then the closest index.
+/// There's no hint to give when that's the constructor found in the first place.
+fn closest_constructor(declared: &[Annotated<Constructor>], term: &Constant) -> Option<String> {
+    let Constant::Data(PlutusData::Constr(constr)) = term else {
+        return None;
+    };
+
+    let index = convert_tag_to_constr(constr.tag).or(constr.any_constructor)? as usize;
+
+    let arity = constr.fields.len();
+
+    let closest = declared.iter().min_by_key(|constructor| {
+        (
+            constructor.annotated.fields.len().abs_diff(arity),
+            constructor.annotated.index.abs_diff(index),
+        )
+    })?;
+
+    if closest.annotated.index == index {
+        return None;
+    }
+
+    Some(format!(
+        "I found constructor {index} with {arity} field(s); did you mean {name} which takes {expected} field(s)?",
+        name = match closest.title {
+            Some(ref title) => format!("'{title}' (constructor {})", closest.annotated.index),
+            None => format!("constructor {}", closest.annotated.index),
+        },
+        expected = closest.annotated.fields.len(),
+    ))
+}
+
+/// Extend the path leading to the element being validated; e.g. `fields[2].map[0].key`.
+fn descend(path: &str, segment: impl Display) -> String {
+    if path.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{path}.{segment}")
     }
 }
 
+// NOTE: References are only ever followed to validate a sub-term, so recursive (or cyclic)
+// definitions can't make validation loop: it ends with the term itself.
 fn validate_schema(
     schema: &Schema,
     definitions: &Definitions<Annotated<Schema>>,
     term: &Constant,
+    path: &str,
 ) -> Result<(), Error> {
     match schema {
-        Schema::Data(data) => validate_data(data, definitions, term),
+        Schema::Data(data) => validate_data(data, definitions, term, path),
 
-        Schema::Unit => expect_unit(term),
+        Schema::Unit => expect_unit(term, path),
 
-        Schema::Integer => expect_integer(term),
+        Schema::Integer => expect_integer(term, path),
 
-        Schema::Bytes => expect_bytes(term),
+        Schema::Bytes => expect_bytes(term, path),
 
-        Schema::String => expect_string(term),
+        Schema::String => expect_string(term, path),
 
-        Schema::Boolean => expect_boolean(term),
+        Schema::Boolean => expect_boolean(term, path),
 
         Schema::Pair(left, right) => {
-            let (term_left, term_right) = expect_pair(term)?;
+            let (term_left, term_right) = expect_pair(term, path)?;
 
             let left =
                 left.schema(definitions)
                     .ok_or_else(|| Error::UnresolvedSchemaReference {
                         reference: left.reference().unwrap().clone(),
                     })?;
-            validate_schema(left, definitions, &term_left)?;
+            validate_schema(left, definitions, &term_left, &descend(path, "left"))?;
 
             let right =
                 right
@@ -92,13 +185,13 @@ fn validate_schema(
                     .ok_or_else(|| Error::UnresolvedSchemaReference {
                         reference: right.reference().unwrap().clone(),
                     })?;
-            validate_schema(right, definitions, &term_right)?;
+            validate_schema(right, definitions, &term_right, &descend(path, "right"))?;
 
             Ok(())
         }
 
         Schema::List(Items::One(item)) => {
-            let terms = expect_list(term)?;
+            let terms = expect_list(term, path)?;
 
             let item =
                 item.schema(definitions)
@@ -106,15 +199,20 @@ fn validate_schema(
                         reference: item.reference().unwrap().clone(),
                     })?;
 
-            for ref term in terms {
-                validate_schema(item, definitions, term)?;
+            for (ix, ref term) in terms.into_iter().enumerate() {
+                validate_schema(
+                    item,
+                    definitions,
+                    term,
+                    &descend(path, format!("list[{ix}]")),
+                )?;
             }
 
             Ok(())
         }
 
         Schema::List(Items::Many(items)) => {
-            let terms = expect_list(term)?;
+            let terms = expect_list(term, path)?;
 
             let items = items
                 .iter()
@@ -133,8 +231,13 @@ fn validate_schema(
                 });
             }
 
-            for (item, ref term) in iter::zip(items, terms) {
-                validate_schema(item, definitions, term)?;
+            for (ix, (item, ref term)) in iter::zip(items, terms).enumerate() {
+                validate_schema(
+                    item,
+                    definitions,
+                    term,
+                    &descend(path, format!("list[{ix}]")),
+                )?;
             }
 
             Ok(())
@@ -146,16 +249,17 @@ fn validate_data(
     data: &Data,
     definitions: &Definitions<Annotated<Schema>>,
     term: &Constant,
+    path: &str,
 ) -> Result<(), Error> {
     match data {
-        Data::Opaque => expect_data(term),
+        Data::Opaque => expect_data(term, path),
 
-        Data::Integer => expect_data_integer(term),
+        Data::Integer(bounds) => expect_data_integer(bounds, term, path),
 
-        Data::Bytes => expect_data_bytes(term),
+        Data::Bytes(bounds) => expect_data_bytes(bounds, term, path),
 
         Data::List(Items::One(item)) => {
-            let terms = expect_data_list(term)?;
+            let terms = expect_data_list(term, path)?;
 
             let item =
                 item.schema(definitions)
@@ -163,15 +267,20 @@ fn validate_data(
                         reference: item.reference().unwrap().clone(),
                     })?;
 
-            for ref term in terms {
-                validate_data(item, definitions, term)?;
+            for (ix, ref term) in terms.into_iter().enumerate() {
+                validate_data(
+                    item,
+                    definitions,
+                    term,
+                    &descend(path, format!("list[{ix}]")),
+                )?;
             }
 
             Ok(())
         }
 
         Data::List(Items::Many(items)) => {
-            let terms = expect_data_list(term)?;
+            let terms = expect_data_list(term, path)?;
 
             let items = items
                 .iter()
@@ -190,15 +299,20 @@ fn validate_data(
                 });
             }
 
-            for (item, ref term) in iter::zip(items, terms) {
-                validate_data(item, definitions, term)?;
+            for (ix, (item, ref term)) in iter::zip(items, terms).enumerate() {
+                validate_data(
+                    item,
+                    definitions,
+                    term,
+                    &descend(path, format!("list[{ix}]")),
+                )?;
             }
 
             Ok(())
         }
 
         Data::Map(keys, values) => {
-            let terms = expect_data_map(term)?;
+            let terms = expect_data_map(term, path)?;
 
             let keys =
                 keys.schema(definitions)
@@ -213,16 +327,17 @@ fn validate_data(
                         reference: values.reference().unwrap().clone(),
                     })?;
 
-            for (ref k, ref v) in terms {
-                validate_data(keys, definitions, k)?;
-                validate_data(values, definitions, v)?;
+            for (ix, (ref k, ref v)) in terms.into_iter().enumerate() {
+                let entry = descend(path, format!("map[{ix}]"));
+                validate_data(keys, definitions, k, &descend(&entry, "key"))?;
+                validate_data(values, definitions, v, &descend(&entry, "value"))?;
             }
 
             Ok(())
         }
 
-        Data::AnyOf(constructors) => {
-            let constructors: Vec<(usize, Vec<&Data>)> = constructors
+        Data::AnyOf(declared) => {
+            let constructors: Vec<(usize, Vec<&Data>)> = declared
                 .iter()
                 .map(|constructor| {
                     constructor
@@ -241,23 +356,30 @@ fn validate_data(
                 })
                 .collect::<Result<_, _>>()?;
 
-            for (index, fields_schema) in constructors.iter() {
-                if let Ok(fields) = expect_data_constr(term, *index) {
+            for (declaration, (index, fields_schema)) in iter::zip(declared, constructors.iter()) {
+                if let Ok(fields) = expect_data_constr(term, *index, path) {
                     if fields_schema.len() != fields.len() {
-                        panic!("fields length different");
+                        return Err(Error::ConstructorFieldsMismatch {
+                            index: *index,
+                            expected: fields_schema.len(),
+                            found: fields.len(),
+                            diff: fields_diff(&declaration.annotated.fields, &fields),
+                            path: path.to_string(),
+                            hint: closest_constructor(declared, term),
+                        });
                     }
 
-                    for (instance, schema) in iter::zip(fields, fields_schema) {
-                        validate_data(schema, definitions, &instance)?;
+                    for (ix, (instance, schema)) in iter::zip(fields, fields_schema).enumerate() {
+                        let path = descend(path, format!("fields[{ix}]"));
+                        validate_data(schema, definitions, &instance, &path)?;
                     }
 
                     return Ok(());
                 }
             }
 
-            Err(mismatch(
-                term,
-                Schema::Data(Data::AnyOf(
+            Err(Error::SchemaMismatch {
+                schema: Schema::Data(Data::AnyOf(
                     constructors
                         .iter()
                         .map(|(index, fields)| {
@@ -271,41 +393,86 @@ fn validate_data(
                             .into()
                         })
                         .collect(),
-                )),
-            ))
+                ))
+                .into(),
+                term: term.clone().into(),
+                path: path.to_string(),
+                hint: closest_constructor(declared, term),
+            })
         }
     }
 }
 
-fn expect_data(term: &Constant) -> Result<(), Error> {
+/// Render the declared fields of a constructor next to the ones actually found, position by
+/// position; fields only declared are prefixed with '-', and those only found with '+'.
+fn fields_diff(declared: &[Annotated<Declaration<Data>>], found: &[Constant]) -> String {
+    let render = |term: &Constant| match term {
+        Constant::Data(data) => {
+            let mut buf = vec![];
+            cbor::encode(data, &mut buf).unwrap();
+            let rendered = cbor::display(&buf).to_string();
+            rendered
+        }
+        _ => term.to_pretty(),
+    };
+
+    (0..declared.len().max(found.len()))
+        .map(|ix| match (declared.get(ix), found.get(ix)) {
+            (Some(field), Some(term)) => format!(
+                "  fields[{ix}]: {} ← {}",
+                serde_json::to_string(field).unwrap(),
+                render(term)
+            ),
+            (Some(field), None) => {
+                format!("- fields[{ix}]: {}", serde_json::to_string(field).unwrap())
+            }
+            (None, Some(term)) => format!("+ fields[{ix}]: {}", render(term)),
+            (None, None) => unreachable!(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn expect_data(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::Data(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Data(Data::Opaque)))
+    Err(mismatch(term, Schema::Data(Data::Opaque), path))
 }
 
-fn expect_data_integer(term: &Constant) -> Result<(), Error> {
-    if let Constant::Data(data) = term {
-        if matches!(data, PlutusData::BigInt(..)) {
+fn expect_data_integer(bounds: &Bounds<i64>, term: &Constant, path: &str) -> Result<(), Error> {
+    if let Constant::Data(PlutusData::BigInt(n)) = term {
+        if bounds
+            .map(|bound| num_bigint::BigInt::from(*bound))
+            .contains(&from_pallas_bigint(n))
+        {
             return Ok(());
         }
     }
 
-    Err(mismatch(term, Schema::Data(Data::Integer)))
+    Err(mismatch(
+        term,
+        Schema::Data(Data::Integer(bounds.clone())),
+        path,
+    ))
 }
 
-fn expect_data_bytes(term: &Constant) -> Result<(), Error> {
-    if let Constant::Data(data) = term {
-        if matches!(data, PlutusData::BoundedBytes(..)) {
+fn expect_data_bytes(bounds: &Bounds<usize>, term: &Constant, path: &str) -> Result<(), Error> {
+    if let Constant::Data(PlutusData::BoundedBytes(bytes)) = term {
+        if bounds.contains(&bytes.len()) {
             return Ok(());
         }
     }
 
-    Err(mismatch(term, Schema::Data(Data::Bytes)))
+    Err(mismatch(
+        term,
+        Schema::Data(Data::Bytes(bounds.clone())),
+        path,
+    ))
 }
 
-fn expect_data_list(term: &Constant) -> Result<Vec<Constant>, Error> {
+fn expect_data_list(term: &Constant, path: &str) -> Result<Vec<Constant>, Error> {
     if let Constant::Data(PlutusData::Array(elems)) = term {
         return Ok(elems
             .iter()
@@ -318,10 +485,11 @@ fn expect_data_list(term: &Constant) -> Result<Vec<Constant>, Error> {
         Schema::Data(Data::List(Items::One(Declaration::Inline(Box::new(
             Data::Opaque,
         ))))),
+        path,
     ))
 }
 
-fn expect_data_map(term: &Constant) -> Result<Vec<(Constant, Constant)>, Error> {
+fn expect_data_map(term: &Constant, path: &str) -> Result<Vec<(Constant, Constant)>, Error> {
     if let Constant::Data(PlutusData::Map(pairs)) = term {
         return Ok(pairs
             .iter()
@@ -335,10 +503,11 @@ fn expect_data_map(term: &Constant) -> Result<Vec<(Constant, Constant)>, Error>
             Declaration::Inline(Box::new(Data::Opaque)),
             Declaration::Inline(Box::new(Data::Opaque)),
         )),
+        path,
     ))
 }
 
-fn expect_data_constr(term: &Constant, index: usize) -> Result<Vec<Constant>, Error> {
+fn expect_data_constr(term: &Constant, index: usize, path: &str) -> Result<Vec<Constant>, Error> {
     if let Constant::Data(PlutusData::Constr(constr)) = term {
         if let PlutusData::Constr(expected) = UplcData::constr(index as u64, vec![]) {
             if expected.tag == constr.tag && expected.any_constructor == constr.any_constructor {
@@ -358,50 +527,51 @@ fn expect_data_constr(term: &Constant, index: usize) -> Result<Vec<Constant>, Er
             fields: vec![],
         }
         .into()])),
+        path,
     ))
 }
 
-fn expect_unit(term: &Constant) -> Result<(), Error> {
+fn expect_unit(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::Unit) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Unit))
+    Err(mismatch(term, Schema::Unit, path))
 }
 
-fn expect_integer(term: &Constant) -> Result<(), Error> {
+fn expect_integer(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::Integer(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Integer))
+    Err(mismatch(term, Schema::Integer, path))
 }
 
-fn expect_bytes(term: &Constant) -> Result<(), Error> {
+fn expect_bytes(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::ByteString(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Bytes))
+    Err(mismatch(term, Schema::Bytes, path))
 }
 
-fn expect_string(term: &Constant) -> Result<(), Error> {
+fn expect_string(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::String(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::String))
+    Err(mismatch(term, Schema::String, path))
 }
 
-fn expect_boolean(term: &Constant) -> Result<(), Error> {
+fn expect_boolean(term: &Constant, path: &str) -> Result<(), Error> {
     if matches!(term, Constant::Bool(..)) {
         return Ok(());
     }
 
-    Err(mismatch(term, Schema::Boolean))
+    Err(mismatch(term, Schema::Boolean, path))
 }
 
-fn expect_pair(term: &Constant) -> Result<(Constant, Constant), Error> {
+fn expect_pair(term: &Constant, path: &str) -> Result<(Constant, Constant), Error> {
     if let Constant::ProtoPair(_, _, left, right) = term {
         return Ok((left.deref().clone(), right.deref().clone()));
     }
@@ -412,10 +582,11 @@ fn expect_pair(term: &Constant) -> Result<(Constant, Constant), Error> {
             Declaration::Inline(Box::new(Schema::Data(Data::Opaque))),
             Declaration::Inline(Box::new(Schema::Data(Data::Opaque))),
         ),
+        path,
     ))
 }
 
-fn expect_list(term: &Constant) -> Result<Vec<Constant>, Error> {
+fn expect_list(term: &Constant, path: &str) -> Result<Vec<Constant>, Error> {
     if let Constant::ProtoList(_, elems) = term {
         return Ok(elems.to_owned());
     }
@@ -425,5 +596,412 @@ fn expect_list(term: &Constant) -> Result<Vec<Constant>, Error> {
         Schema::List(Items::One(Declaration::Inline(Box::new(Schema::Data(
             Data::Opaque,
         ))))),
+        path,
     ))
 }
+
+/// Convert lists and pairs to the representation expected by the schema, be it builtin or
+/// Data-encoded. Anything that doesn't fit is left untouched, for validation to report.
+fn coerce_schema(
+    schema: &Schema,
+    definitions: &Definitions<Annotated<Schema>>,
+    term: &Constant,
+) -> Constant {
+    match schema {
+        Schema::Data(data) => match data_from_builtin(data, definitions, term) {
+            Some(data) => Constant::Data(data),
+            None => term.clone(),
+        },
+
+        Schema::Pair(left, right) => {
+            let (term_left, term_right) = match term {
+                Constant::ProtoPair(_, _, left, right) => {
+                    (left.deref().clone(), right.deref().clone())
+                }
+                Constant::Data(PlutusData::Array(items)) if items.len() == 2 => (
+                    Constant::Data(items[0].clone()),
+                    Constant::Data(items[1].clone()),
+                ),
+                _ => return term.clone(),
+            };
+
+            let (Some(left), Some(right)) = (left.schema(definitions), right.schema(definitions))
+            else {
+                return term.clone();
+            };
+
+            let (Some(left_type), Some(right_type)) = (
+                builtin_type(left, definitions),
+                builtin_type(right, definitions),
+            ) else {
+                return term.clone();
+            };
+
+            Constant::ProtoPair(
+                left_type,
+                right_type,
+                coerce_schema(left, definitions, &term_left).into(),
+                coerce_schema(right, definitions, &term_right).into(),
+            )
+        }
+
+        Schema::List(Items::One(item)) => {
+            let terms = match term {
+                Constant::ProtoList(_, elems) => elems.clone(),
+                Constant::Data(PlutusData::Array(items)) => {
+                    items.iter().cloned().map(Constant::Data).collect()
+                }
+                Constant::Data(PlutusData::Map(pairs)) => pairs
+                    .iter()
+                    .map(|(key, value)| {
+                        Constant::ProtoPair(
+                            Type::Data,
+                            Type::Data,
+                            Constant::Data(key.clone()).into(),
+                            Constant::Data(value.clone()).into(),
+                        )
+                    })
+                    .collect(),
+                _ => return term.clone(),
+            };
+
+            let Some(item) = item.schema(definitions) else {
+                return term.clone();
+            };
+
+            let Some(item_type) = builtin_type(item, definitions) else {
+                return term.clone();
+            };
+
+            Constant::ProtoList(
+                item_type,
+                terms
+                    .iter()
+                    .map(|term| coerce_schema(item, definitions, term))
+                    .collect(),
+            )
+        }
+
+        Schema::Integer => match term {
+            Constant::Data(PlutusData::BigInt(n)) => Constant::Integer(from_pallas_bigint(n)),
+            _ => term.clone(),
+        },
+
+        Schema::Bytes => match term {
+            Constant::Data(PlutusData::BoundedBytes(bytes)) => Constant::ByteString(bytes.to_vec()),
+            _ => term.clone(),
+        },
+
+        Schema::List(Items::Many(..)) | Schema::Unit | Schema::String | Schema::Boolean => {
+            term.clone()
+        }
+    }
+}
+
+/// The Data encoding of builtin lists and pairs, following the schema.
+fn data_from_builtin(
+    data: &Data,
+    definitions: &Definitions<Annotated<Schema>>,
+    term: &Constant,
+) -> Option<PlutusData> {
+    match (data, term) {
+        (_, Constant::Data(data)) => Some(data.clone()),
+
+        (Data::List(Items::One(item)), Constant::ProtoList(_, elems)) => {
+            let item = lookup_data(item, definitions).ok()?;
+            elems
+                .iter()
+                .map(|elem| data_from_builtin(item, definitions, elem))
+                .collect::<Option<_>>()
+                .map(UplcData::list)
+        }
+
+        (Data::List(Items::Many(items)), Constant::ProtoList(_, elems))
+            if items.len() == elems.len() =>
+        {
+            iter::zip(items, elems)
+                .map(|(item, elem)| {
+                    data_from_builtin(lookup_data(item, definitions).ok()?, definitions, elem)
+                })
+                .collect::<Option<_>>()
+                .map(UplcData::list)
+        }
+
+        (Data::List(Items::Many(items)), Constant::ProtoPair(_, _, left, right))
+            if items.len() == 2 =>
+        {
+            let left =
+                data_from_builtin(lookup_data(&items[0], definitions).ok()?, definitions, left)?;
+            let right = data_from_builtin(
+                lookup_data(&items[1], definitions).ok()?,
+                definitions,
+                right,
+            )?;
+            Some(UplcData::list(vec![left, right]))
+        }
+
+        (Data::Map(keys, values), Constant::ProtoList(_, elems)) => {
+            let keys = lookup_data(keys, definitions).ok()?;
+            let values = lookup_data(values, definitions).ok()?;
+            elems
+                .iter()
+                .map(|elem| match elem {
+                    Constant::ProtoPair(_, _, key, value) => Some((
+                        data_from_builtin(keys, definitions, key)?,
+                        data_from_builtin(values, definitions, value)?,
+                    )),
+                    _ => None,
+                })
+                .collect::<Option<_>>()
+                .map(UplcData::map)
+        }
+
+        _ => None,
+    }
+}
+
+/// The type of builtin constants following the schema, if there's a single one.
+fn builtin_type(schema: &Schema, definitions: &Definitions<Annotated<Schema>>) -> Option<Type> {
+    match schema {
+        Schema::Data(..) => Some(Type::Data),
+        Schema::Unit => Some(Type::Unit),
+        Schema::Boolean => Some(Type::Bool),
+        Schema::Integer => Some(Type::Integer),
+        Schema::Bytes => Some(Type::ByteString),
+        Schema::String => Some(Type::String),
+        Schema::Pair(left, right) => Some(Type::Pair(
+            builtin_type(left.schema(definitions)?, definitions)?.into(),
+            builtin_type(right.schema(definitions)?, definitions)?.into(),
+        )),
+        Schema::List(Items::One(item)) => Some(Type::List(
+            builtin_type(item.schema(definitions)?, definitions)?.into(),
+        )),
+        Schema::List(Items::Many(..)) => None,
+    }
+}
+
+fn malformed(expected: &str, value: &Json, path: &str) -> Error {
+    Error::MalformedParameter {
+        hint: if path.is_empty() {
+            format!("Expected {expected}, but got: {value}")
+        } else {
+            format!("Expected {expected} at {path}, but got: {value}")
+        },
+    }
+}
+
+fn lookup_data<'a>(
+    declaration: &'a Declaration<Data>,
+    definitions: &'a Definitions<Annotated<Schema>>,
+) -> Result<&'a Data, Error> {
+    declaration
+        .schema(definitions)
+        .ok_or_else(|| Error::UnresolvedSchemaReference {
+            reference: declaration.reference().unwrap().clone(),
+        })
+}
+
+fn data_from_json(
+    data: &Data,
+    definitions: &Definitions<Annotated<Schema>>,
+    value: &Json,
+    path: &str,
+) -> Result<PlutusData, Error> {
+    match data {
+        Data::Opaque => match value {
+            Json::String(cbor) => hex::decode(cbor)
+                .ok()
+                .and_then(|bytes| uplc::plutus_data(&bytes).ok())
+                .ok_or_else(|| malformed("some hex-encoded CBOR Plutus data", value, path)),
+            _ => Err(malformed("some hex-encoded CBOR Plutus data", value, path)),
+        },
+
+        Data::Integer(..) => {
+            let digits = match value {
+                Json::Number(n) => n.to_string(),
+                Json::String(s) => s.to_string(),
+                _ => return Err(malformed("an integer", value, path)),
+            };
+
+            num_bigint::BigInt::from_str(&digits)
+                .map(UplcData::integer)
+                .map_err(|_| malformed("an integer", value, path))
+        }
+
+        Data::Bytes(..) => match value {
+            Json::String(bytes) => hex::decode(bytes)
+                .map(UplcData::bytestring)
+                .map_err(|_| malformed("a hex-encoded byte array", value, path)),
+            _ => Err(malformed("a hex-encoded byte array", value, path)),
+        },
+
+        Data::List(Items::One(item)) => {
+            let Json::Array(elems) = value else {
+                return Err(malformed("a list", value, path));
+            };
+
+            let item = lookup_data(item, definitions)?;
+
+            elems
+                .iter()
+                .enumerate()
+                .map(|(ix, elem)| {
+                    data_from_json(
+                        item,
+                        definitions,
+                        elem,
+                        &descend(path, format!("list[{ix}]")),
+                    )
+                })
+                .collect::<Result<_, _>>()
+                .map(UplcData::list)
+        }
+
+        Data::List(Items::Many(items)) => {
+            let expected = format!("a {}-tuple", items.len());
+
+            let elems = match value {
+                Json::Array(elems) if elems.len() == items.len() => elems,
+                _ => return Err(malformed(&expected, value, path)),
+            };
+
+            iter::zip(items, elems)
+                .enumerate()
+                .map(|(ix, (item, elem))| {
+                    let item = lookup_data(item, definitions)?;
+                    data_from_json(
+                        item,
+                        definitions,
+                        elem,
+                        &descend(path, format!("list[{ix}]")),
+                    )
+                })
+                .collect::<Result<_, _>>()
+                .map(UplcData::list)
+        }
+
+        Data::Map(keys, values) => {
+            let keys = lookup_data(keys, definitions)?;
+            let values = lookup_data(values, definitions)?;
+
+            let entries: Vec<(Json, &Json)> = match value {
+                Json::Object(entries) => entries
+                    .iter()
+                    .map(|(k, v)| (Json::String(k.to_string()), v))
+                    .collect(),
+                Json::Array(entries) => entries
+                    .iter()
+                    .map(|entry| match entry {
+                        Json::Array(pair) if pair.len() == 2 => Ok((pair[0].clone(), &pair[1])),
+                        _ => Err(malformed("a key/value pair", entry, path)),
+                    })
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(malformed("a map", value, path)),
+            };
+
+            entries
+                .iter()
+                .enumerate()
+                .map(|(ix, (k, v))| {
+                    let entry = descend(path, format!("map[{ix}]"));
+                    Ok((
+                        data_from_json(keys, definitions, k, &descend(&entry, "key"))?,
+                        data_from_json(values, definitions, v, &descend(&entry, "value"))?,
+                    ))
+                })
+                .collect::<Result<_, _>>()
+                .map(UplcData::map)
+        }
+
+        Data::AnyOf(constructors) => constr_from_json(constructors, definitions, value, path),
+    }
+}
+
+fn constr_from_json(
+    constructors: &[Annotated<Constructor>],
+    definitions: &Definitions<Annotated<Schema>>,
+    value: &Json,
+    path: &str,
+) -> Result<PlutusData, Error> {
+    let find = |title: &str| {
+        constructors
+            .iter()
+            .find(|constructor| constructor.title.as_deref() == Some(title))
+    };
+
+    let designated = match value {
+        Json::Bool(b) => find(if *b { "True" } else { "False" }).map(|c| (c, None)),
+        Json::String(title) => find(title).map(|c| (c, None)),
+        Json::Object(entries) if entries.len() == 1 => {
+            let (title, fields) = entries.iter().next().unwrap();
+            find(title).map(|c| (c, Some(fields)))
+        }
+        _ => None,
+    };
+
+    // Records may be given without designating their constructor.
+    let (constructor, fields) = match designated {
+        Some((constructor, fields)) => (Some(constructor), fields),
+        None if constructors.len() == 1 => (constructors.first(), Some(value)),
+        None => (None, None),
+    };
+
+    let Some(constructor) = constructor else {
+        let expected = format!(
+            "one of: {}",
+            constructors
+                .iter()
+                .map(|constructor| match constructor.title {
+                    Some(ref title) => title.to_string(),
+                    None => constructor.annotated.index.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        return Err(malformed(&expected, value, path));
+    };
+
+    let schema = &constructor.annotated.fields;
+
+    let expected = format!(
+        "{} field(s) for {}",
+        schema.len(),
+        constructor.title.as_deref().unwrap_or("the constructor")
+    );
+
+    let is_keyed_by_titles = |entries: &serde_json::Map<String, Json>| {
+        entries.len() == schema.len()
+            && schema.iter().all(|field| {
+                field
+                    .title
+                    .as_ref()
+                    .is_some_and(|title| entries.contains_key(title))
+            })
+    };
+
+    let fields: Vec<&Json> = match fields {
+        None | Some(Json::Null) if schema.is_empty() => vec![],
+        None | Some(Json::Null) => return Err(malformed(&expected, value, path)),
+        Some(Json::Object(entries)) if is_keyed_by_titles(entries) => schema
+            .iter()
+            .filter_map(|field| field.title.as_ref().and_then(|title| entries.get(title)))
+            .collect(),
+        Some(fields) if schema.len() == 1 => vec![fields],
+        Some(Json::Array(fields)) if fields.len() == schema.len() => fields.iter().collect(),
+        Some(_) => return Err(malformed(&expected, value, path)),
+    };
+
+    iter::zip(schema, fields)
+        .enumerate()
+        .map(|(ix, (field, value))| {
+            let field = lookup_data(&field.annotated, definitions)?;
+            data_from_json(
+                field,
+                definitions,
+                value,
+                &descend(path, format!("fields[{ix}]")),
+            )
+        })
+        .collect::<Result<_, _>>()
+        .map(|fields| UplcData::constr(constructor.annotated.index as u64, fields))
+}
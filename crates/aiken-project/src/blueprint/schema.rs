@@ -24,6 +24,10 @@ pub struct Annotated<T> {
     pub title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Example values, as JSON following the schema (see
+    /// [`Parameter::from_json`](super::parameter::Parameter::from_json)).
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub examples: Vec<json::Value>,
     #[serde(flatten)]
     pub annotated: T,
 }
@@ -100,14 +104,55 @@ pub enum Schema {
 /// A schema for Plutus' Data.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Data {
-    Integer,
-    Bytes,
+    Integer(Bounds<i64>),
+    Bytes(Bounds<usize>),
     List(Items<Data>),
     Map(Declaration<Data>, Declaration<Data>),
     AnyOf(Vec<Annotated<Constructor>>),
     Opaque,
 }
 
+/// Optional, inclusive, bounds on the value of integers or on the length of byte arrays. They stem
+/// from annotations in the doc comment of record fields (e.g. `@minimum(1)` or `@maxLength(32)`).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Bounds<T> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+impl<T> Default for Bounds<T> {
+    fn default() -> Self {
+        Bounds {
+            min: None,
+            max: None,
+        }
+    }
+}
+
+impl<T> Bounds<T> {
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Bounds<U> {
+        Bounds {
+            min: self.min.as_ref().map(&f),
+            max: self.max.as_ref().map(&f),
+        }
+    }
+}
+
+impl<T: PartialOrd> Bounds<T> {
+    pub fn contains(&self, value: &T) -> bool {
+        self.min.as_ref().map(|min| value >= min).unwrap_or(true)
+            && self.max.as_ref().map(|max| value <= max).unwrap_or(true)
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    fn len(&self) -> usize {
+        self.min.iter().count() + self.max.iter().count()
+    }
+}
+
 /// A structure that represents either one or many elements.
 #[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
@@ -128,6 +173,7 @@ impl<T> From<T> for Annotated<T> {
         Annotated {
             title: None,
             description: None,
+            examples: vec![],
             annotated,
         }
     }
@@ -147,6 +193,7 @@ impl Annotated<Schema> {
                     Ok::<_, Error>(Annotated {
                         title: Some("Wrapped Redeemer".to_string()),
                         description: Some("A redeemer wrapped in an extra constructor to make multi-validator detection possible on-chain.".to_string()),
+                        examples: vec![],
                         annotated: Schema::Data(Data::AnyOf(vec![Constructor {
                             index: REDEEMER_DISCRIMINANT,
                             fields: vec![Declaration::Referenced(schema).into()],
@@ -183,6 +230,7 @@ impl Annotated<Schema> {
                 Annotated {
                     title: title.cloned(),
                     description: None,
+                    examples: vec![],
                     annotated,
                 }
             } else {
@@ -202,21 +250,30 @@ impl Annotated<Schema> {
                         "Data" => Ok(Annotated {
                             title: title.or(Some("Data".to_string())),
                             description: Some("Any Plutus data.".to_string()),
+                            examples: vec![],
                             annotated: Schema::Data(Data::Opaque),
                         }),
 
-                        "ByteArray" => Ok(with_title(title.as_ref(), Schema::Data(Data::Bytes))),
+                        "ByteArray" => Ok(with_title(
+                            title.as_ref(),
+                            Schema::Data(Data::Bytes(Bounds::default())),
+                        )),
 
-                        "Int" => Ok(with_title(title.as_ref(), Schema::Data(Data::Integer))),
+                        "Int" => Ok(with_title(
+                            title.as_ref(),
+                            Schema::Data(Data::Integer(Bounds::default())),
+                        )),
 
                         "String" => Ok(with_title(title.as_ref(), Schema::String)),
 
                         "Void" => Ok(Annotated {
                             title: title.or(Some("Unit".to_string())),
                             description: None,
+                            examples: vec![],
                             annotated: Schema::Data(Data::AnyOf(vec![Annotated {
                                 title: None,
                                 description: None,
+                                examples: vec![],
                                 annotated: Constructor {
                                     index: 0,
                                     fields: vec![],
@@ -227,10 +284,12 @@ impl Annotated<Schema> {
                         "Bool" => Ok(Annotated {
                             title: title.or(Some("Bool".to_string())),
                             description: None,
+                            examples: vec![],
                             annotated: Schema::Data(Data::AnyOf(vec![
                                 Annotated {
                                     title: Some("False".to_string()),
                                     description: None,
+                                    examples: vec![],
                                     annotated: Constructor {
                                         index: 0,
                                         fields: vec![],
@@ -239,6 +298,7 @@ impl Annotated<Schema> {
                                 Annotated {
                                     title: Some("True".to_string()),
                                     description: None,
+                                    examples: vec![],
                                     annotated: Constructor {
                                         index: 1,
                                         fields: vec![],
@@ -250,10 +310,12 @@ impl Annotated<Schema> {
                         "Ordering" => Ok(Annotated {
                             title: title.or(Some("Ordering".to_string())),
                             description: None,
+                            examples: vec![],
                             annotated: Schema::Data(Data::AnyOf(vec![
                                 Annotated {
                                     title: Some("Less".to_string()),
                                     description: None,
+                                    examples: vec![],
                                     annotated: Constructor {
                                         index: 0,
                                         fields: vec![],
@@ -262,6 +324,7 @@ impl Annotated<Schema> {
                                 Annotated {
                                     title: Some("Equal".to_string()),
                                     description: None,
+                                    examples: vec![],
                                     annotated: Constructor {
                                         index: 1,
                                         fields: vec![],
@@ -270,6 +333,7 @@ impl Annotated<Schema> {
                                 Annotated {
                                     title: Some("Greater".to_string()),
                                     description: None,
+                                    examples: vec![],
                                     annotated: Constructor {
                                         index: 2,
                                         fields: vec![],
@@ -282,10 +346,12 @@ impl Annotated<Schema> {
                             Ok(Annotated {
                                 title: title.or(Some("Never".to_string())),
                                 description: None,
+                                examples: vec![],
                                 annotated: Schema::Data(Data::AnyOf(vec![
                                     Annotated {
                                         title: Some("Never".to_string()),
                                         description: Some("Nothing.".to_string()),
+                                        examples: vec![],
                                         annotated: Constructor {
                                             index: 1,
                                             fields: vec![],
@@ -307,10 +373,12 @@ impl Annotated<Schema> {
                             Ok(Annotated {
                                 title: title.or(Some("Option".to_string())),
                                 description: None,
+                                examples: vec![],
                                 annotated: Schema::Data(Data::AnyOf(vec![
                                     Annotated {
                                         title: Some("Some".to_string()),
                                         description: Some("An optional value.".to_string()),
+                                        examples: vec![],
                                         annotated: Constructor {
                                             index: 0,
                                             fields: vec![Declaration::Referenced(generic).into()],
@@ -319,6 +387,7 @@ impl Annotated<Schema> {
                                     Annotated {
                                         title: Some("None".to_string()),
                                         description: Some("Nothing.".to_string()),
+                                        examples: vec![],
                                         annotated: Constructor {
                                             index: 1,
                                             fields: vec![],
@@ -394,9 +463,25 @@ impl Annotated<Schema> {
                         .map_err(|e| e.backtrack(type_info))?,
                 );
 
+                let (examples, description) =
+                    annotations(data_type.doc.as_deref().unwrap_or_default(), &["example"]);
+
+                let examples = examples
+                    .into_iter()
+                    .map(|(_, example)| {
+                        json::from_str(example).map_err(|e| {
+                            Error::new(
+                                ErrorContext::InvalidExample(format!("'{example}': {e}")),
+                                type_info,
+                            )
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+
                 Ok(Annotated {
                     title: title.or(Some(data_type.name.clone())),
-                    description: data_type.doc.clone().map(|s| s.trim().to_string()),
+                    description: Some(description).filter(|doc| !doc.is_empty()),
+                    examples,
                     annotated,
                 })
             }),
@@ -414,6 +499,7 @@ impl Annotated<Schema> {
                     Ok(Annotated {
                         title: title.or(Some("Pair".to_owned())),
                         description: None,
+                        examples: vec![],
                         annotated: Schema::Pair(left, right),
                     })
                 })
@@ -433,6 +519,7 @@ impl Annotated<Schema> {
                     Ok(Annotated {
                         title: title.or(Some("Tuple".to_owned())),
                         description: None,
+                        examples: vec![],
                         annotated: Schema::Data(Data::List(Items::Many(elems))),
                     })
                 })
@@ -484,16 +571,30 @@ impl Data {
                 let reference =
                     Annotated::do_from_type(&field.tipo, modules, type_parameters, definitions)?;
 
+                let (annotations, description) =
+                    annotations(field.doc.as_deref().unwrap_or_default(), &BOUNDS);
+
+                let annotated = if annotations.is_empty() {
+                    Declaration::Referenced(reference)
+                } else {
+                    let data = bounded(definitions.lookup(&reference), &annotations).map_err(
+                        |reason| Error::new(ErrorContext::InvalidBounds(reason), &field.tipo),
+                    )?;
+                    Declaration::Inline(Box::new(data))
+                };
+
                 fields.push(Annotated {
                     title: field.label.clone(),
-                    description: field.doc.clone().map(|s| s.trim().to_string()),
-                    annotated: Declaration::Referenced(reference),
+                    description: Some(description).filter(|doc| !doc.is_empty()),
+                    examples: vec![],
+                    annotated,
                 });
             }
 
             let variant = Annotated {
                 title: Some(constructor.name.clone()),
                 description: constructor.doc.clone().map(|s| s.trim().to_string()),
+                examples: vec![],
                 annotated: Constructor { index, fields },
             };
 
@@ -504,6 +605,81 @@ impl Data {
     }
 }
 
+const BOUNDS: [&str; 4] = ["minimum", "maximum", "minLength", "maxLength"];
+
+/// Split a doc comment into its annotations with the given keywords (e.g. `@minimum(1)`), as
+/// pairs of keyword and argument, and the remaining text.
+fn annotations<'a>(doc: &'a str, keywords: &[&str]) -> (Vec<(&'a str, &'a str)>, String) {
+    let mut annotations = vec![];
+    let mut rest = vec![];
+
+    for line in doc.lines() {
+        let annotation = line
+            .trim()
+            .strip_prefix('@')
+            .and_then(|line| line.strip_suffix(')'))
+            .and_then(|line| line.split_once('('))
+            .filter(|(keyword, _)| keywords.contains(keyword));
+
+        match annotation {
+            Some((keyword, argument)) => annotations.push((keyword, argument.trim())),
+            None => rest.push(line),
+        }
+    }
+
+    (annotations, rest.join("\n").trim().to_string())
+}
+
+/// The (inlined) schema of an integer or byte array field, with the given bounds annotations.
+fn bounded(
+    schema: Option<&Annotated<Schema>>,
+    annotations: &[(&str, &str)],
+) -> Result<Data, String> {
+    fn parse<T: std::str::FromStr>(keyword: &str, argument: &str) -> Result<Option<T>, String> {
+        argument
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("'{argument}' isn't a valid argument for '@{keyword}'"))
+    }
+
+    fn check<T: PartialOrd + fmt::Display>(bounds: &Bounds<T>) -> Result<(), String> {
+        match (&bounds.min, &bounds.max) {
+            (Some(min), Some(max)) if min > max => Err(format!(
+                "the lower bound ({min}) exceeds the upper bound ({max})"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    match schema.map(|schema| &schema.annotated) {
+        Some(Schema::Data(Data::Integer(..))) => {
+            let mut bounds = Bounds::default();
+            for (keyword, argument) in annotations {
+                match *keyword {
+                    "minimum" => bounds.min = parse(keyword, argument)?,
+                    "maximum" => bounds.max = parse(keyword, argument)?,
+                    _ => return Err(format!("'@{keyword}' only applies to byte arrays")),
+                }
+            }
+            check(&bounds)?;
+            Ok(Data::Integer(bounds))
+        }
+        Some(Schema::Data(Data::Bytes(..))) => {
+            let mut bounds = Bounds::default();
+            for (keyword, argument) in annotations {
+                match *keyword {
+                    "minLength" => bounds.min = parse(keyword, argument)?,
+                    "maxLength" => bounds.max = parse(keyword, argument)?,
+                    _ => return Err(format!("'@{keyword}' only applies to integers")),
+                }
+            }
+            check(&bounds)?;
+            Ok(Data::Bytes(bounds))
+        }
+        _ => Err("bounds only apply to integers and byte arrays".to_string()),
+    }
+}
+
 fn collect_type_parameters<'a>(
     type_parameters: &'a mut HashMap<u64, Rc<Type>>,
     generics: &'a [Rc<Type>],
@@ -540,7 +716,8 @@ fn find_data_type(name: &str, definitions: &[TypedDefinition]) -> Option<TypedDa
             | Definition::TypeAlias { .. }
             | Definition::Use { .. }
             | Definition::ModuleConstant { .. }
-            | Definition::Test { .. } => continue,
+            | Definition::Test { .. }
+            | Definition::Benchmark { .. } => continue,
         }
     }
     None
@@ -614,6 +791,10 @@ where
         Right,
         AnyOf,
         OneOf,
+        Minimum,
+        Maximum,
+        MinLength,
+        MaxLength,
     }
 
     let mut data_type: Option<String> = None;
@@ -623,6 +804,8 @@ where
     let mut right = None;
     let mut values = None;
     let mut any_of = None;
+    let mut integer_bounds = Bounds::default();
+    let mut bytes_bounds = Bounds::default();
 
     while let Some(key) = map.next_key()? {
         match key {
@@ -674,6 +857,30 @@ where
                 }
                 any_of = Some(map.next_value()?);
             }
+            Field::Minimum => {
+                if integer_bounds.min.is_some() {
+                    return Err(de::Error::duplicate_field("minimum"));
+                }
+                integer_bounds.min = Some(map.next_value()?);
+            }
+            Field::Maximum => {
+                if integer_bounds.max.is_some() {
+                    return Err(de::Error::duplicate_field("maximum"));
+                }
+                integer_bounds.max = Some(map.next_value()?);
+            }
+            Field::MinLength => {
+                if bytes_bounds.min.is_some() {
+                    return Err(de::Error::duplicate_field("minLength"));
+                }
+                bytes_bounds.min = Some(map.next_value()?);
+            }
+            Field::MaxLength => {
+                if bytes_bounds.max.is_some() {
+                    return Err(de::Error::duplicate_field("maxLength"));
+                }
+                bytes_bounds.max = Some(map.next_value()?);
+            }
         }
     }
 
@@ -734,6 +941,32 @@ where
         Ok(())
     };
 
+    let expect_no_integer_bounds = || {
+        if !integer_bounds.is_unbounded() {
+            return Err(de::Error::custom(
+                "unexpected field(s) 'minimum' and/or 'maximum' for a non-integer data-type",
+            ));
+        }
+        Ok(())
+    };
+
+    let expect_no_bytes_bounds = || {
+        if !bytes_bounds.is_unbounded() {
+            return Err(de::Error::custom(
+                "unexpected field(s) 'minLength' and/or 'maxLength' for a non-bytes data-type",
+            ));
+        }
+        Ok(())
+    };
+
+    if data_type.as_deref() != Some("integer") {
+        expect_no_integer_bounds()?;
+    }
+
+    if data_type.as_deref() != Some("bytes") {
+        expect_no_bytes_bounds()?;
+    }
+
     match data_type {
         None => {
             expect_no_items()?;
@@ -789,9 +1022,9 @@ where
             expect_no_any_of()?;
             expect_no_left_or_right()?;
             if data_type == "bytes" {
-                Ok(Schema::Data(Data::Bytes))
+                Ok(Schema::Data(Data::Bytes(bytes_bounds)))
             } else if data_type == "integer" {
-                Ok(Schema::Data(Data::Integer))
+                Ok(Schema::Data(Data::Integer(integer_bounds)))
             } else if data_type == "#unit" {
                 Ok(Schema::Unit)
             } else if data_type == "#integer" {
@@ -831,7 +1064,18 @@ impl<'a> Deserialize<'a> for Schema {
         deserializer.deserialize_struct(
             "Schema",
             &[
-                "dataType", "items", "keys", "values", "anyOf", "oneOf", "left", "right",
+                "dataType",
+                "items",
+                "keys",
+                "values",
+                "anyOf",
+                "oneOf",
+                "left",
+                "right",
+                "minimum",
+                "maximum",
+                "minLength",
+                "maxLength",
             ],
             SchemaVisitor,
         )
@@ -845,14 +1089,16 @@ impl Serialize for Data {
                 let s = serializer.serialize_struct("Opaque", 0)?;
                 s.end()
             }
-            Data::Integer => {
-                let mut s = serializer.serialize_struct("Integer", 1)?;
+            Data::Integer(bounds) => {
+                let mut s = serializer.serialize_struct("Integer", 1 + bounds.len())?;
                 s.serialize_field("dataType", "integer")?;
+                serialize_bounds(&mut s, bounds, ("minimum", "maximum"))?;
                 s.end()
             }
-            Data::Bytes => {
-                let mut s = serializer.serialize_struct("Bytes", 1)?;
+            Data::Bytes(bounds) => {
+                let mut s = serializer.serialize_struct("Bytes", 1 + bounds.len())?;
                 s.serialize_field("dataType", "bytes")?;
+                serialize_bounds(&mut s, bounds, ("minLength", "maxLength"))?;
                 s.end()
             }
             Data::List(items) => {
@@ -877,6 +1123,20 @@ impl Serialize for Data {
     }
 }
 
+fn serialize_bounds<S: SerializeStruct, T: Serialize>(
+    s: &mut S,
+    bounds: &Bounds<T>,
+    (min_key, max_key): (&'static str, &'static str),
+) -> Result<(), S::Error> {
+    if let Some(min) = &bounds.min {
+        s.serialize_field(min_key, min)?;
+    }
+    if let Some(max) = &bounds.max {
+        s.serialize_field(max_key, max)?;
+    }
+    Ok(())
+}
+
 impl<'a> Deserialize<'a> for Data {
     fn deserialize<D: Deserializer<'a>>(deserializer: D) -> Result<Self, D::Error> {
         struct DataVisitor;
@@ -902,7 +1162,18 @@ impl<'a> Deserialize<'a> for Data {
 
         deserializer.deserialize_struct(
             "Data",
-            &["dataType", "items", "keys", "values", "anyOf", "oneOf"],
+            &[
+                "dataType",
+                "items",
+                "keys",
+                "values",
+                "anyOf",
+                "oneOf",
+                "minimum",
+                "maximum",
+                "minLength",
+                "maxLength",
+            ],
             DataVisitor,
         )
     }
@@ -997,6 +1268,12 @@ pub enum ErrorContext {
 
     #[error("I caught an opaque type trying to escape")]
     IllegalOpaqueType,
+
+    #[error("I found an invalid bounds annotation: {0}.")]
+    InvalidBounds(String),
+
+    #[error("I found an example that isn't valid JSON: {0}.")]
+    InvalidExample(String),
 }
 
 impl Error {
@@ -1017,7 +1294,7 @@ impl Error {
     }
 
     pub fn help(&self) -> String {
-        match self.context {
+        match &self.context {
             ErrorContext::IllegalOpaqueType => format!(
                 r#"Opaque types cannot figure anywhere in an outward-facing type like a validator's redeemer or datum. This is because an {opaque} type hides its implementation details, and likely enforce invariants that cannot be expressed only structurally. In particular, the {opaque} type {signature} cannot be safely constructed from any Plutus Data.
 
@@ -1069,6 +1346,36 @@ If your contract doesn't need datum or redeemer, you can always give them the ty
                     .if_supports_color(Stdout, |s| s.bold())
             ),
 
+            ErrorContext::InvalidBounds(..) => format!(
+                r#"Fields of type {Int} or {ByteArray} may be constrained through annotations in their doc comment, on a line of their own: {minimum} and {maximum} for integers, {minLength} and {maxLength} for the length (in bytes) of byte arrays. Bounds are inclusive.
+
+I got there when trying to generate a blueprint specification of the following type:
+
+╰─▶ {breadcrumbs}"#,
+                Int = "Int"
+                    .if_supports_color(Stdout, |s| s.bright_blue())
+                    .if_supports_color(Stdout, |s| s.bold()),
+                ByteArray = "ByteArray"
+                    .if_supports_color(Stdout, |s| s.bright_blue())
+                    .if_supports_color(Stdout, |s| s.bold()),
+                minimum = "@minimum(n)".if_supports_color(Stdout, |s| s.purple()),
+                maximum = "@maximum(n)".if_supports_color(Stdout, |s| s.purple()),
+                minLength = "@minLength(n)".if_supports_color(Stdout, |s| s.purple()),
+                maxLength = "@maxLength(n)".if_supports_color(Stdout, |s| s.purple()),
+                breadcrumbs = Error::fmt_breadcrumbs(&self.breadcrumbs)
+            ),
+
+            ErrorContext::InvalidExample(..) => format!(
+                r#"Types may carry example values through annotations in their doc comment, on a line of their own: {example}. Examples are given as JSON following the type's schema, as with {apply}.
+
+I got there when trying to generate a blueprint specification of the following type:
+
+╰─▶ {breadcrumbs}"#,
+                example = "@example(<json>)".if_supports_color(Stdout, |s| s.purple()),
+                apply = "aiken blueprint apply --json".if_supports_color(Stdout, |s| s.bold()),
+                breadcrumbs = Error::fmt_breadcrumbs(&self.breadcrumbs)
+            ),
+
             ErrorContext::UnexpectedFunction => format!(
                 r#"I can't allow that. Functions aren't serializable as data on-chain and thus cannot be used within your datum and/or redeemer types.
 
@@ -1108,7 +1415,7 @@ pub mod tests {
 
     #[test]
     fn serialize_data_integer() {
-        let schema = Schema::Data(Data::Integer);
+        let schema = Schema::Data(Data::Integer(Bounds::default()));
         assert_json(
             &schema,
             json!({
@@ -1119,7 +1426,7 @@ pub mod tests {
 
     #[test]
     fn serialize_data_bytes() {
-        let schema = Schema::Data(Data::Bytes);
+        let schema = Schema::Data(Data::Bytes(Bounds::default()));
         assert_json(
             &schema,
             json!({
@@ -1128,6 +1435,37 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn serialize_data_integer_bounded() {
+        let schema = Schema::Data(Data::Integer(Bounds {
+            min: Some(1),
+            max: None,
+        }));
+        assert_json(
+            &schema,
+            json!({
+                "dataType": "integer",
+                "minimum": 1
+            }),
+        );
+    }
+
+    #[test]
+    fn serialize_data_bytes_bounded() {
+        let schema = Schema::Data(Data::Bytes(Bounds {
+            min: Some(28),
+            max: Some(28),
+        }));
+        assert_json(
+            &schema,
+            json!({
+                "dataType": "bytes",
+                "minLength": 28,
+                "maxLength": 28
+            }),
+        );
+    }
+
     #[test]
     fn serialize_data_list_1() {
         let ref_integer = Reference::new("Int");
@@ -1242,6 +1580,7 @@ pub mod tests {
         let schema = Annotated {
             title: Some("foo".to_string()),
             description: None,
+            examples: vec![],
             annotated: Schema::Integer,
         };
         assert_json(
@@ -1258,6 +1597,7 @@ pub mod tests {
         let schema = Annotated {
             title: Some("foo".to_string()),
             description: Some("Lorem Ipsum".to_string()),
+            examples: vec![],
             annotated: Schema::String,
         };
         assert_json(
@@ -1270,6 +1610,31 @@ pub mod tests {
         )
     }
 
+    #[test]
+    fn serialize_annotated_examples() {
+        let schema = Annotated {
+            title: None,
+            description: None,
+            examples: vec![json!(42), json!("14")],
+            annotated: Schema::Data(Data::Integer(Bounds::default())),
+        };
+        assert_json(
+            &schema,
+            json!({
+                "examples": [42, "14"],
+                "dataType": "integer"
+            }),
+        );
+        assert_eq!(
+            schema,
+            serde_json::from_value(json!({
+                "examples": [42, "14"],
+                "dataType": "integer"
+            }))
+            .unwrap()
+        )
+    }
+
     #[test]
     fn deserialize_data_opaque() {
         assert_eq!(Data::Opaque, serde_json::from_value(json!({})).unwrap())
@@ -1278,7 +1643,7 @@ pub mod tests {
     #[test]
     fn deserialize_data_integer() {
         assert_eq!(
-            Data::Integer,
+            Data::Integer(Bounds::default()),
             serde_json::from_value(json!({
                 "dataType": "integer",
             }))
@@ -1289,14 +1654,38 @@ pub mod tests {
     #[test]
     fn deserialize_data_bytes() {
         assert_eq!(
-            Data::Bytes,
+            Data::Bytes(Bounds::default()),
+            serde_json::from_value(json!({
+                "dataType": "bytes",
+            }))
+            .unwrap()
+        )
+    }
+
+    #[test]
+    fn deserialize_data_bytes_bounded() {
+        assert_eq!(
+            Data::Bytes(Bounds {
+                min: None,
+                max: Some(64),
+            }),
             serde_json::from_value(json!({
                 "dataType": "bytes",
+                "maxLength": 64,
             }))
             .unwrap()
         )
     }
 
+    #[test]
+    fn deserialize_data_bounds_mismatch() {
+        assert!(serde_json::from_value::<Data>(json!({
+            "dataType": "integer",
+            "minLength": 1,
+        }))
+        .is_err())
+    }
+
     #[test]
     fn deserialize_data_list_one() {
         assert_eq!(
@@ -1401,7 +1790,13 @@ pub mod tests {
 
     #[allow(clippy::arc_with_non_send_sync)]
     fn arbitrary_data() -> impl Strategy<Value = Data> {
-        let leaf = prop_oneof![Just(Data::Opaque), Just(Data::Bytes), Just(Data::Integer)];
+        let leaf = prop_oneof![
+            Just(Data::Opaque),
+            (any::<Option<usize>>(), any::<Option<usize>>())
+                .prop_map(|(min, max)| Data::Bytes(Bounds { min, max })),
+            (any::<Option<i64>>(), any::<Option<i64>>())
+                .prop_map(|(min, max)| Data::Integer(Bounds { min, max })),
+        ];
 
         leaf.prop_recursive(3, 8, 3, |inner| {
             let r = prop_oneof![
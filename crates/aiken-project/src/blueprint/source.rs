@@ -0,0 +1,155 @@
+//! Where a blueprint comes from: a hash of the sources it was built from, and where each
+//! validator handler is defined. This makes it possible to check that a blueprint was produced
+//! from a given checkout of a project.
+
+use super::{error::Error, Blueprint};
+use crate::paths;
+use pallas_crypto::hash::Hasher;
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// Folders holding a project's modules, relative to its root.
+const MODULES: [&str; 3] = ["env", "lib", "validators"];
+
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Source {
+    /// The hash (blake2b-256) of the project's sources; see [`Source::hash_tree`]. Only known
+    /// once the blueprint is written out as part of a project.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub hash: Option<String>,
+
+    /// Where each validator handler is defined, by title.
+    pub validators: BTreeMap<String, Location>,
+}
+
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Location {
+    pub module: String,
+    pub line: usize,
+}
+
+impl Source {
+    /// Hash the sources of the project at the given root: its configuration, its manifest (which
+    /// pins dependencies) and every module, in order of their path relative to the root. Build
+    /// artifacts and anything else are left out, so that the hash only changes with sources.
+    pub fn hash_tree(root: &Path) -> io::Result<String> {
+        let mut files: Vec<PathBuf> = [paths::project_config(), paths::manifest()]
+            .into_iter()
+            .filter(|path| root.join(path).is_file())
+            .collect();
+
+        for folder in MODULES {
+            for entry in WalkDir::new(root.join(folder)).follow_links(true) {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e)
+                        if e.io_error().map(io::Error::kind) == Some(io::ErrorKind::NotFound) =>
+                    {
+                        continue
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+
+                let path = entry.path();
+
+                if entry.file_type().is_file() && path.extension().is_some_and(|ext| ext == "ak") {
+                    files.push(path.strip_prefix(root).unwrap().to_path_buf());
+                }
+            }
+        }
+
+        // Paths are compared with '/' separators, so that the hash doesn't depend on the platform.
+        let mut files = files
+            .into_iter()
+            .map(|path| {
+                let key = path
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                (key, path)
+            })
+            .collect::<Vec<_>>();
+
+        files.sort();
+
+        let mut hasher = Hasher::<256>::new();
+
+        for (key, path) in files {
+            let contents = fs::read(root.join(path))?;
+            hasher.input(key.as_bytes());
+            hasher.input(&(contents.len() as u64).to_be_bytes());
+            hasher.input(&contents);
+        }
+
+        Ok(hasher.finalize().to_string())
+    }
+}
+
+impl Blueprint {
+    /// Check that the blueprint was built from sources with the given hash (see
+    /// [`Source::hash_tree`]).
+    pub fn check_source(&self, hash: &str) -> Result<(), Error> {
+        match self
+            .source
+            .as_ref()
+            .and_then(|source| source.hash.as_deref())
+        {
+            None => Err(Error::MissingSourceHash),
+            Some(expected) if expected == hash => Ok(()),
+            Some(expected) => Err(Error::SourceMismatch {
+                expected: expected.to_string(),
+                found: hash.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn hash_tree() {
+        let root = std::env::temp_dir().join(format!("aiken-source-{}", std::process::id()));
+
+        let _ = fs::remove_dir_all(&root);
+
+        write(&root, "aiken.toml", "name = \"foo/bar\"");
+        write(&root, "lib/foo.ak", "pub const foo = 42");
+        write(
+            &root,
+            "validators/bar.ak",
+            "validator bar { else(_) { True } }",
+        );
+
+        let hash = Source::hash_tree(&root).unwrap();
+
+        // Anything but the sources is ignored.
+        write(&root, "build/packages/packages.toml", "");
+        write(&root, "plutus.json", "{}");
+        write(&root, "README.md", "# foo/bar");
+        assert_eq!(Source::hash_tree(&root).unwrap(), hash);
+
+        // Any change to the sources changes the hash.
+        write(&root, "lib/foo.ak", "pub const foo = 14");
+        let changed = Source::hash_tree(&root).unwrap();
+        assert_ne!(changed, hash);
+
+        // So does moving contents around.
+        fs::rename(root.join("lib/foo.ak"), root.join("lib/baz.ak")).unwrap();
+        assert_ne!(Source::hash_tree(&root).unwrap(), changed);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+use super::{parameter::Parameter, Blueprint};
+use aiken_lang::plutus_version::PlutusVersion;
+use pallas_addresses::{Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart};
+use serde::Serialize;
+
+/// An overview of a blueprint's validators, meant for deployment scripts and pipelines.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub title: String,
+    pub version: String,
+    pub plutus_version: PlutusVersion,
+    pub validators: Vec<ValidatorSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorSummary {
+    pub title: String,
+    pub hash: String,
+    /// Size of the compiled code, in bytes.
+    pub size: usize,
+    /// Parameters left to apply. The validator's hash and addresses change once they are.
+    pub parameters: Vec<Parameter>,
+    /// Addresses without delegation part, unless the validator still has parameters.
+    pub address: Option<Addresses>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Addresses {
+    pub mainnet: String,
+    pub testnet: String,
+}
+
+impl Blueprint {
+    pub fn summary(&self) -> Summary {
+        Summary {
+            title: self.preamble.title.clone(),
+            version: self.preamble.version.clone(),
+            plutus_version: self.preamble.plutus_version,
+            validators: self
+                .validators
+                .iter()
+                .map(|validator| {
+                    let (compiled_code, hash) = validator.program.compiled_code_and_hash();
+
+                    let address = |network| {
+                        ShelleyAddress::new(
+                            network,
+                            ShelleyPaymentPart::Script(hash),
+                            ShelleyDelegationPart::Null,
+                        )
+                        .to_bech32()
+                        .expect("script addresses always encode to bech32")
+                    };
+
+                    ValidatorSummary {
+                        title: validator.title.clone(),
+                        hash: hash.to_string(),
+                        size: compiled_code.len() / 2,
+                        parameters: validator.parameters.clone(),
+                        address: if validator.parameters.is_empty() {
+                            Some(Addresses {
+                                mainnet: address(Network::Mainnet),
+                                testnet: address(Network::Testnet),
+                            })
+                        } else {
+                            None
+                        },
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hello_world() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/hello_world/plutus.json"))
+                .unwrap();
+
+        insta::assert_json_snapshot!(blueprint.summary());
+    }
+
+    #[test]
+    fn parameterized() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/gift_card/plutus.json"))
+                .unwrap();
+
+        let summary = blueprint.summary();
+
+        assert_eq!(summary.validators.len(), blueprint.validators.len());
+
+        for (validator, summary) in blueprint.validators.iter().zip(summary.validators) {
+            assert_eq!(summary.parameters, validator.parameters);
+            assert!(summary.address.is_none());
+        }
+    }
+}
@@ -13,10 +13,11 @@ use aiken_lang::{
     tipo::{collapse_links, Type},
 };
 use miette::NamedSource;
+use pallas_primitives::conway::Language;
 use serde;
-use std::borrow::Borrow;
+use std::{borrow::Borrow, collections::BTreeSet};
 use uplc::{
-    ast::{Constant, SerializableProgram},
+    ast::{Constant, DeBruijn, SerializableProgram, Term},
     PlutusData,
 };
 
@@ -114,7 +115,7 @@ impl Validator {
                     schema: Declaration::Referenced(schema),
                 })
                 .map_err(|error| Error::Schema {
-                    error,
+                    error: error.into(),
                     location: param.location,
                     source_code: NamedSource::new(
                         module.input_path.display().to_string(),
@@ -159,7 +160,7 @@ impl Validator {
                                 &mut definitions,
                             )
                             .map_err(|error| Error::Schema {
-                                error,
+                                error: error.into(),
                                 location: datum.location,
                                 source_code: NamedSource::new(
                                     module.input_path.display().to_string(),
@@ -182,7 +183,7 @@ impl Validator {
                 &mut definitions,
             )
             .map_err(|error| Error::Schema {
-                error,
+                error: error.into(),
                 location: redeemer.location,
                 source_code: NamedSource::new(
                     module.input_path.display().to_string(),
@@ -202,6 +203,8 @@ impl Validator {
             schema: Declaration::Inline(Box::new(Schema::Data(Data::Opaque))),
         }));
 
+        check_examples(&definitions)?;
+
         Ok(Validator {
             title: format!("{}.{}.{}", &module.name, &def.name, &func.name,),
             description: func.doc.clone(),
@@ -218,6 +221,106 @@ impl Validator {
     }
 }
 
+/// Ensure that examples given in doc comments (e.g. `@example({ "owner": "abcd" })`) are valid
+/// values of the type they annotate.
+fn check_examples(definitions: &Definitions<Annotated<Schema>>) -> Result<(), Error> {
+    for (key, definition) in definitions.iter() {
+        let parameter = Parameter {
+            title: None,
+            schema: Declaration::Inline(Box::new(definition.annotated.clone())),
+        };
+
+        for example in definition.examples.iter() {
+            parameter
+                .from_json(definitions, example)
+                .and_then(|data| parameter.validate(definitions, &Constant::Data(data)))
+                .map_err(|error| Error::InvalidExample {
+                    title: definition.title.clone().unwrap_or_else(|| key.to_string()),
+                    example: example.to_string(),
+                    error: Box::new(error),
+                })?;
+        }
+    }
+
+    Ok(())
+}
+
+impl Validator {
+    /// Re-encode the validator for another Plutus version, provided its compiled code only uses
+    /// builtins and terms available in that version. Note that this doesn't account for the
+    /// differences in how validators are invoked nor in the script context across versions.
+    pub fn with_plutus_version(self, version: &PlutusVersion) -> Result<Self, Error> {
+        let language = Language::from(version);
+
+        let mut program = self.program.inner().clone();
+
+        let mut incompatibilities = BTreeSet::new();
+        incompatible_terms(&program.term, &language, &mut incompatibilities);
+
+        if !incompatibilities.is_empty() {
+            return Err(Error::IncompatiblePlutusVersion {
+                validator: self.title,
+                version: *version,
+                incompatibilities: incompatibilities.into_iter().collect(),
+            });
+        }
+
+        // NOTE: Plutus V1 & V2 only support UPLC 1.0.0, which is a subset of 1.1.0.
+        if *version != PlutusVersion::V3 {
+            program.version = (1, 0, 0);
+        }
+
+        Ok(Validator {
+            program: match version {
+                PlutusVersion::V1 => SerializableProgram::PlutusV1Program,
+                PlutusVersion::V2 => SerializableProgram::PlutusV2Program,
+                PlutusVersion::V3 => SerializableProgram::PlutusV3Program,
+            }(program),
+            ..self
+        })
+    }
+}
+
+/// Collect the builtins and terms of a program that aren't available in the given version.
+fn incompatible_terms(
+    term: &Term<DeBruijn>,
+    language: &Language,
+    incompatibilities: &mut BTreeSet<String>,
+) {
+    match term {
+        Term::Builtin(builtin) => {
+            if !builtin.is_available_in(language) {
+                incompatibilities.insert(format!("builtin '{builtin}'"));
+            }
+        }
+        Term::Constr { fields, .. } => {
+            if *language != Language::PlutusV3 {
+                incompatibilities.insert("'constr' terms".to_string());
+            }
+            for field in fields {
+                incompatible_terms(field, language, incompatibilities);
+            }
+        }
+        Term::Case { constr, branches } => {
+            if *language != Language::PlutusV3 {
+                incompatibilities.insert("'case' terms".to_string());
+            }
+            incompatible_terms(constr, language, incompatibilities);
+            for branch in branches {
+                incompatible_terms(branch, language, incompatibilities);
+            }
+        }
+        Term::Delay(term) | Term::Force(term) | Term::Lambda { body: term, .. } => {
+            incompatible_terms(term, language, incompatibilities)
+        }
+        Term::Apply { function, argument } => {
+            incompatible_terms(function, language, incompatibilities);
+            incompatible_terms(argument, language, incompatibilities);
+        }
+        Term::Var(..) | Term::Constant(..) | Term::Error => {}
+    }
+}
+
 pub fn tipo_or_annotation<'a>(module: &'a CheckedModule, arg: &'a TypedArg) -> &'a Type {
     match collapse_links(arg.tipo.clone()).borrow() {
         Type::App {
@@ -260,6 +363,27 @@ impl Validator {
         }
     }
 
+    /// Same as `apply`, but tolerating lists and pairs given in a representation other than the
+    /// one expected by the parameter's schema; see [`Parameter::validate_coerced`]. The argument
+    /// is applied once normalized, so it may end up being a builtin list or pair.
+    pub fn apply_coerced(
+        self,
+        definitions: &Definitions<Annotated<Schema>>,
+        arg: &PlutusData,
+    ) -> Result<Self, Error> {
+        match self.parameters.split_first() {
+            None => Err(Error::NoParametersToApply),
+            Some((head, tail)) => {
+                let term = head.validate_coerced(definitions, &Constant::Data(arg.clone()))?;
+                Ok(Self {
+                    program: self.program.map(|program| program.apply_constant(term)),
+                    parameters: tail.to_vec(),
+                    ..self
+                })
+            }
+        }
+    }
+
     pub fn ask_next_parameter<F>(
         &self,
         definitions: &Definitions<Annotated<Schema>>,
@@ -275,6 +399,7 @@ impl Validator {
                     Declaration::Inline(schema) => Annotated {
                         title: head.title.clone(),
                         description: None,
+                        examples: vec![],
                         annotated: schema.as_ref().clone(),
                     },
                     Declaration::Referenced(ref link) => definitions
@@ -283,6 +408,7 @@ impl Validator {
                             Ok(Annotated {
                                 title: s.title.clone().or_else(|| head.title.clone()),
                                 description: s.description.clone(),
+                                examples: vec![],
                                 annotated: s.annotated.clone(),
                             })
                         })
@@ -307,7 +433,7 @@ mod tests {
         super::{
             definitions::{Definitions, Reference},
             error::Error,
-            schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+            schema::{Annotated, Bounds, Constructor, Data, Declaration, Items, Schema},
         },
         *,
     };
@@ -380,7 +506,7 @@ mod tests {
         // }
         definitions
             .register::<_, Error>(&Type::int(), &HashMap::new(), |_| {
-                Ok(Schema::Data(Data::Integer).into())
+                Ok(Schema::Data(Data::Integer(Bounds::default())).into())
             })
             .unwrap();
 
@@ -391,7 +517,7 @@ mod tests {
         // }
         definitions
             .register::<_, Error>(&Type::byte_array(), &HashMap::new(), |_| {
-                Ok(Schema::Data(Data::Bytes).into())
+                Ok(Schema::Data(Data::Bytes(Bounds::default())).into())
             })
             .unwrap();
 
@@ -707,6 +833,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bounded_data() {
+        assert_validator!(
+            r#"
+            pub type Config {
+                /// The owner's verification key hash.
+                /// @minLength(28)
+                /// @maxLength(28)
+                owner: ByteArray,
+                /// @minimum(1)
+                /// @maximum(100)
+                fee: Int,
+            }
+
+            validator bounded_data(config: Config) {
+                mint(redeemer: Data, policy_id: ByteArray, transaction: Data) {
+                    True
+                }
+            }
+            "#
+        );
+    }
+
+    #[test]
+    fn invalid_bounds() {
+        assert_validator!(
+            r#"
+            pub type Config {
+                /// @minLength(1)
+                fee: Int,
+            }
+
+            validator invalid_bounds(config: Config) {
+                mint(redeemer: Data, policy_id: ByteArray, transaction: Data) {
+                    True
+                }
+            }
+            "#
+        );
+    }
+
+    #[test]
+    fn examples() {
+        assert_validator!(
+            r#"
+            /// A configuration.
+            ///
+            /// @example({ "owner": "abcd", "fee": 42 })
+            /// @example([ "", 0 ])
+            pub type Config {
+                owner: ByteArray,
+                fee: Int,
+            }
+
+            /// @example("Cancel")
+            /// @example({ "Claim": { "amount": 14 } })
+            pub type Action {
+                Claim { amount: Int }
+                Cancel
+            }
+
+            validator examples {
+                spend(datum: Option<Config>, redeemer: Action, output_reference: Data, transaction: Data) {
+                    True
+                }
+            }
+            "#
+        );
+    }
+
+    #[test]
+    fn invalid_example() {
+        assert_validator!(
+            r#"
+            /// @example({ "Claim": { "amount": "abcd" } })
+            pub type Action {
+                Claim { amount: Int }
+                Cancel
+            }
+
+            validator invalid_example {
+                mint(redeemer: Action, policy_id: ByteArray, transaction: Data) {
+                    True
+                }
+            }
+            "#
+        );
+    }
+
     #[test]
     fn type_aliases() {
         assert_validator!(
@@ -777,6 +992,52 @@ mod tests {
         assert!(matches!(param.validate(&definitions, &term), Ok { .. }))
     }
 
+    #[test]
+    fn validate_arguments_bounds() {
+        let definitions = fixture_definitions();
+
+        let param = Parameter {
+            title: None,
+            schema: Declaration::Inline(Box::new(Schema::Data(Data::List(Items::Many(vec![
+                Declaration::Inline(Box::new(Data::Integer(Bounds {
+                    min: Some(0),
+                    max: Some(10),
+                }))),
+                Declaration::Inline(Box::new(Data::Bytes(Bounds {
+                    min: Some(2),
+                    max: None,
+                }))),
+            ]))))),
+        };
+
+        let term = |n: i64, bytes: Vec<u8>| {
+            Constant::Data(uplc_ast::Data::list(vec![
+                uplc_ast::Data::integer(n.into()),
+                uplc_ast::Data::bytestring(bytes),
+            ]))
+        };
+
+        assert!(matches!(
+            param.validate(&definitions, &term(10, vec![1, 2])),
+            Ok { .. }
+        ));
+
+        assert!(matches!(
+            param.validate(&definitions, &term(11, vec![1, 2])),
+            Err(Error::SchemaMismatch { path, .. }) if path == "list[0]"
+        ));
+
+        assert!(matches!(
+            param.validate(&definitions, &term(-1, vec![1, 2])),
+            Err(Error::SchemaMismatch { path, .. }) if path == "list[0]"
+        ));
+
+        assert!(matches!(
+            param.validate(&definitions, &term(0, vec![1])),
+            Err(Error::SchemaMismatch { path, .. }) if path == "list[1]"
+        ));
+    }
+
     #[test]
     fn validate_arguments_list_inline() {
         let schema = Reference::new("List$Int");
@@ -791,7 +1052,7 @@ mod tests {
         definitions.insert(
             &schema,
             Schema::Data(Data::List(Items::One(Declaration::Inline(Box::new(
-                Data::Integer,
+                Data::Integer(Bounds::default()),
             )))))
             .into(),
         );
@@ -867,6 +1128,92 @@ mod tests {
         assert!(matches!(param.validate(&definitions, &term), Ok { .. }))
     }
 
+    #[test]
+    fn validate_arguments_coerced() {
+        let schema = Reference::new("List$Pair$Int_Data");
+
+        // #/definitions/List$Pair$Int_Data
+        //
+        // {
+        //   "dataType": "#list",
+        //   "items": {
+        //     "dataType": "#pair",
+        //     "left": { "dataType": "#integer" },
+        //     "right": { "$ref": "#/definitions/Data" }
+        //   }
+        // }
+        let mut definitions = fixture_definitions();
+        definitions.insert(
+            &schema,
+            Schema::List(Items::One(Declaration::Inline(Box::new(Schema::Pair(
+                Declaration::Inline(Box::new(Schema::Integer)),
+                Declaration::Inline(Box::new(Schema::Data(Data::Opaque))),
+            )))))
+            .into(),
+        );
+
+        let param: Parameter = schema.into();
+
+        let term = Constant::Data(uplc_ast::Data::map(vec![(
+            uplc_ast::Data::integer(42.into()),
+            uplc_ast::Data::bytestring(vec![102, 111, 111]),
+        )]));
+
+        let coerced = Constant::ProtoList(
+            uplc_ast::Type::Pair(uplc_ast::Type::Integer.into(), uplc_ast::Type::Data.into()),
+            vec![Constant::ProtoPair(
+                uplc_ast::Type::Integer,
+                uplc_ast::Type::Data,
+                Constant::Integer(42.into()).into(),
+                Constant::Data(uplc_ast::Data::bytestring(vec![102, 111, 111])).into(),
+            )],
+        );
+
+        assert!(matches!(
+            param.validate(&definitions, &term),
+            Err(Error::SchemaMismatch { .. })
+        ));
+
+        assert_eq!(
+            param.validate_coerced(&definitions, &term).ok(),
+            Some(coerced.clone())
+        );
+
+        assert_eq!(
+            param.validate_coerced(&definitions, &coerced).ok(),
+            Some(coerced)
+        );
+
+        // Data-encoded tuples given as builtin pairs
+        let schema = Reference::new("Tuple$Int_ByteArray");
+
+        definitions.insert(
+            &schema,
+            Schema::Data(Data::List(Items::Many(vec![
+                Declaration::Referenced(Reference::new("Int")),
+                Declaration::Referenced(Reference::new("ByteArray")),
+            ])))
+            .into(),
+        );
+
+        let param: Parameter = schema.into();
+
+        let term = Constant::ProtoPair(
+            uplc_ast::Type::Data,
+            uplc_ast::Type::Data,
+            Constant::Data(uplc_ast::Data::integer(42.into())).into(),
+            Constant::Data(uplc_ast::Data::bytestring(vec![102, 111, 111])).into(),
+        );
+
+        assert_eq!(
+            param.validate_coerced(&definitions, &term).ok(),
+            Some(Constant::Data(uplc_ast::Data::list(vec![
+                uplc_ast::Data::integer(42.into()),
+                uplc_ast::Data::bytestring(vec![102, 111, 111]),
+            ])))
+        );
+    }
+
     #[test]
     fn validate_arguments_dict() {
         let schema = Reference::new("Dict$ByteArray_Int");
@@ -882,8 +1229,8 @@ mod tests {
         definitions.insert(
             &Reference::new("Dict$ByteArray_Int"),
             Schema::Data(Data::Map(
-                Declaration::Inline(Box::new(Data::Bytes)),
-                Declaration::Inline(Box::new(Data::Integer)),
+                Declaration::Inline(Box::new(Data::Bytes(Bounds::default()))),
+                Declaration::Inline(Box::new(Data::Integer(Bounds::default()))),
             ))
             .into(),
         );
@@ -1013,4 +1360,502 @@ mod tests {
 
         assert!(matches!(param.validate(&definitions, &term), Ok { .. }))
     }
+
+    #[test]
+    fn validate_arguments_list_recursive() {
+        let schema = Reference::new("Rose");
+
+        // #/definitions/Rose
+        //
+        // {
+        //   "dataType": "list",
+        //   "items": { "$ref": "#/definitions/Rose" }
+        // }
+        let mut definitions = fixture_definitions();
+        definitions.insert(
+            &schema,
+            Schema::Data(Data::List(Items::One(Declaration::Referenced(
+                schema.clone(),
+            ))))
+            .into(),
+        );
+
+        let param: Parameter = schema.into();
+
+        let term = Constant::Data(uplc_ast::Data::list(vec![
+            uplc_ast::Data::list(vec![]),
+            uplc_ast::Data::list(vec![uplc_ast::Data::list(vec![])]),
+        ]));
+
+        assert!(matches!(param.validate(&definitions, &term), Ok { .. }));
+
+        let term = Constant::Data(uplc_ast::Data::list(vec![uplc_ast::Data::list(vec![
+            uplc_ast::Data::integer(42.into()),
+        ])]));
+
+        assert!(matches!(
+            param.validate(&definitions, &term),
+            Err(Error::SchemaMismatch { path, .. }) if path == "list[0].list[0]"
+        ));
+    }
+
+    #[test]
+    fn validate_arguments_constr_fields_mismatch() {
+        let schema = Reference::new("Foo");
+
+        // #/definitions/Foo
+        //
+        // {
+        //   "anyOf": [
+        //      {
+        //          "dataType": "constructor",
+        //          "index": 0,
+        //          "fields": [{
+        //              "$ref": "#/definitions/Int
+        //          }]
+        //      },
+        //   ]
+        // }
+        let mut definitions = fixture_definitions();
+        definitions.insert(
+            &schema,
+            Schema::Data(Data::AnyOf(vec![Constructor {
+                index: 0,
+                fields: vec![Declaration::Referenced(Reference::new("Int")).into()],
+            }
+            .into()]))
+            .into(),
+        );
+
+        let term = Constant::Data(uplc_ast::Data::list(vec![uplc_ast::Data::constr(
+            0,
+            vec![
+                uplc_ast::Data::integer(42.into()),
+                uplc_ast::Data::integer(14.into()),
+            ],
+        )]));
+
+        let param = Parameter {
+            title: None,
+            schema: Declaration::Inline(Box::new(Schema::Data(Data::List(Items::One(
+                Declaration::Referenced(schema),
+            ))))),
+        };
+
+        match param.validate(&definitions, &term) {
+            Err(Error::ConstructorFieldsMismatch {
+                index,
+                expected,
+                found,
+                diff,
+                path,
+                hint,
+            }) => {
+                assert_eq!((index, expected, found), (0, 1, 2));
+                assert_eq!(hint, None);
+                assert_eq!(path, "list[0]");
+                assert_eq!(
+                    diff,
+                    "  fields[0]: {\"$ref\":\"#/definitions/Int\"} ← 42\n+ fields[1]: 14"
+                );
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn validate_arguments_constr_closest() {
+        let schema = Reference::new("Option$Int");
+
+        // #/definitions/Option$Int
+        //
+        // {
+        //   "anyOf": [
+        //      { "title": "Some", "dataType": "constructor", "index": 0, "fields": [{ "$ref": "#/definitions/Int" }] },
+        //      { "title": "None", "dataType": "constructor", "index": 1, "fields": [] },
+        //   ]
+        // }
+        let mut definitions = fixture_definitions();
+        definitions.insert(
+            &schema,
+            Schema::Data(Data::AnyOf(vec![
+                Annotated {
+                    title: Some("Some".to_string()),
+                    description: None,
+                    examples: vec![],
+                    annotated: Constructor {
+                        index: 0,
+                        fields: vec![Declaration::Referenced(Reference::new("Int")).into()],
+                    },
+                },
+                Annotated {
+                    title: Some("None".to_string()),
+                    description: None,
+                    examples: vec![],
+                    annotated: Constructor {
+                        index: 1,
+                        fields: vec![],
+                    },
+                },
+            ]))
+            .into(),
+        );
+
+        let term = Constant::Data(uplc_ast::Data::constr(
+            1,
+            vec![
+                uplc_ast::Data::integer(1.into()),
+                uplc_ast::Data::integer(2.into()),
+                uplc_ast::Data::integer(3.into()),
+            ],
+        ));
+
+        let param: Parameter = schema.into();
+
+        match param.validate(&definitions, &term) {
+            Err(Error::ConstructorFieldsMismatch { hint, .. }) => assert_eq!(
+                hint.as_deref(),
+                Some("I found constructor 1 with 3 field(s); did you mean 'Some' (constructor 0) which takes 1 field(s)?")
+            ),
+            result => panic!("unexpected result: {result:?}"),
+        }
+
+        let term = Constant::Data(uplc_ast::Data::constr(2, vec![]));
+
+        match param.validate(&definitions, &term) {
+            Err(Error::SchemaMismatch { hint, .. }) => assert_eq!(
+                hint.as_deref(),
+                Some("I found constructor 2 with 0 field(s); did you mean 'None' (constructor 1) which takes 0 field(s)?")
+            ),
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn validate_arguments_mismatch_path() {
+        let schema = Reference::new("Dict$ByteArray_List$Int");
+
+        // #/definitions/Dict$ByteArray_List$Int
+        //
+        // {
+        //   "dataType": "map",
+        //   "keys": { "$ref": "#/definitions/ByteArray" },
+        //   "values": {
+        //     "anyOf": [
+        //        {
+        //            "dataType": "constructor",
+        //            "index": 0,
+        //            "fields": [{
+        //                "dataType": "list",
+        //                "items": { "$ref": "#/definitions/Int" }
+        //            }]
+        //        },
+        //     ]
+        //   }
+        // }
+        let mut definitions = fixture_definitions();
+        definitions.insert(
+            &schema,
+            Schema::Data(Data::Map(
+                Declaration::Referenced(Reference::new("ByteArray")),
+                Declaration::Inline(Box::new(Data::AnyOf(vec![Constructor {
+                    index: 0,
+                    fields: vec![Declaration::Inline(Box::new(Data::List(Items::One(
+                        Declaration::Referenced(Reference::new("Int")),
+                    ))))
+                    .into()],
+                }
+                .into()]))),
+            ))
+            .into(),
+        );
+
+        let term = Constant::Data(uplc_ast::Data::map(vec![
+            (
+                uplc_ast::Data::bytestring(vec![102, 111, 111]),
+                uplc_ast::Data::constr(0, vec![uplc_ast::Data::list(vec![])]),
+            ),
+            (
+                uplc_ast::Data::bytestring(vec![98, 97, 114]),
+                uplc_ast::Data::constr(
+                    0,
+                    vec![uplc_ast::Data::list(vec![
+                        uplc_ast::Data::integer(42.into()),
+                        uplc_ast::Data::bytestring(vec![]),
+                    ])],
+                ),
+            ),
+        ]));
+
+        let param: Parameter = schema.into();
+
+        assert!(matches!(
+            param.validate(&definitions, &term),
+            Err(Error::SchemaMismatch { path, .. }) if path == "map[1].value.fields[0].list[1]"
+        ))
+    }
+
+    #[test]
+    fn apply_parameters_partially() {
+        let mut project = TestProject::new();
+
+        let modules = CheckedModules::singleton(project.check(project.parse(indoc::indoc! { r#"
+            validator thing(owner: ByteArray, deadline: Int) {
+              mint(redeemer: Data, policy_id: ByteArray, transaction: Data) {
+                True
+              }
+            }
+        "# })));
+
+        let mut generator = project.new_generator(Tracing::All(TraceLevel::Verbose));
+
+        let (validator, def) = modules.validators().next().unwrap();
+
+        let validator = Validator::from_checked_module(
+            &modules,
+            &mut generator,
+            validator,
+            def,
+            &PlutusVersion::default(),
+        )
+        .remove(0)
+        .unwrap();
+
+        let definitions = validator.definitions.clone();
+
+        let applied = validator
+            .apply(&definitions, &uplc_ast::Data::bytestring(vec![42]))
+            .unwrap();
+
+        assert_eq!(applied.parameters.len(), 1);
+        assert_eq!(applied.parameters[0].title.as_deref(), Some("deadline"));
+
+        assert!(matches!(
+            applied
+                .clone()
+                .apply(&definitions, &uplc_ast::Data::bytestring(vec![42])),
+            Err(Error::SchemaMismatch { .. })
+        ));
+
+        let applied = applied
+            .apply(&definitions, &uplc_ast::Data::integer(42.into()))
+            .unwrap();
+
+        assert!(applied.parameters.is_empty());
+
+        assert!(matches!(
+            applied.apply(&definitions, &uplc_ast::Data::integer(42.into())),
+            Err(Error::NoParametersToApply)
+        ));
+    }
+
+    #[test]
+    fn convert_plutus_version() {
+        let mut project = TestProject::new();
+
+        let modules = CheckedModules::singleton(project.check(project.parse(indoc::indoc! { r#"
+            use aiken/builtin
+
+            validator thing {
+              mint(redeemer: ByteArray, policy_id: ByteArray, transaction: Data) {
+                builtin.blake2b_224(redeemer) == policy_id
+              }
+            }
+        "# })));
+
+        let mut generator = project.new_generator(Tracing::All(TraceLevel::Verbose));
+
+        let (validator, def) = modules.validators().next().unwrap();
+
+        let validator = Validator::from_checked_module(
+            &modules,
+            &mut generator,
+            validator,
+            def,
+            &PlutusVersion::V3,
+        )
+        .remove(0)
+        .unwrap();
+
+        assert!(matches!(
+            validator.clone().with_plutus_version(&PlutusVersion::V2),
+            Err(Error::IncompatiblePlutusVersion { incompatibilities, .. })
+                if incompatibilities == vec!["builtin 'blake2b_224'".to_string()]
+        ));
+
+        let converted = validator
+            .clone()
+            .with_plutus_version(&PlutusVersion::V3)
+            .unwrap();
+
+        assert_eq!(converted, validator);
+    }
+
+    #[test]
+    fn parameter_from_json_record() {
+        let schema = Reference::new("Datum");
+
+        // #/definitions/Datum
+        //
+        // {
+        //   "title": "Datum",
+        //   "anyOf": [
+        //      {
+        //          "title": "Datum",
+        //          "dataType": "constructor",
+        //          "index": 0,
+        //          "fields": [
+        //            { "title": "owner", "$ref": "#/definitions/ByteArray" },
+        //            { "title": "deadline", "$ref": "#/definitions/Int" }
+        //          ]
+        //      },
+        //   ]
+        // }
+        let mut definitions = fixture_definitions();
+        definitions.insert(
+            &schema,
+            Schema::Data(Data::AnyOf(vec![Annotated {
+                title: Some("Datum".to_string()),
+                description: None,
+                examples: vec![],
+                annotated: Constructor {
+                    index: 0,
+                    fields: vec![
+                        Annotated {
+                            title: Some("owner".to_string()),
+                            description: None,
+                            examples: vec![],
+                            annotated: Declaration::Referenced(Reference::new("ByteArray")),
+                        },
+                        Annotated {
+                            title: Some("deadline".to_string()),
+                            description: None,
+                            examples: vec![],
+                            annotated: Declaration::Referenced(Reference::new("Int")),
+                        },
+                    ],
+                },
+            }]))
+            .into(),
+        );
+
+        let param: Parameter = schema.into();
+
+        let expected = uplc_ast::Data::constr(
+            0,
+            vec![
+                uplc_ast::Data::bytestring(vec![102, 111, 111]),
+                uplc_ast::Data::integer(42.into()),
+            ],
+        );
+
+        for json in [
+            serde_json::json!({ "owner": "666f6f", "deadline": 42 }),
+            serde_json::json!({ "Datum": { "deadline": "42", "owner": "666f6f" } }),
+            serde_json::json!(["666f6f", 42]),
+        ] {
+            let data = param.from_json(&definitions, &json).unwrap();
+            assert_eq!(data, expected);
+            assert!(matches!(
+                param.validate(&definitions, &Constant::Data(data)),
+                Ok { .. }
+            ));
+        }
+
+        assert!(matches!(
+            param.from_json(&definitions, &serde_json::json!({ "owner": "666f6f" })),
+            Err(Error::MalformedParameter { .. })
+        ));
+    }
+
+    #[test]
+    fn parameter_from_json_variants() {
+        let schema = Reference::new("Option$Dict$ByteArray_Int");
+
+        // #/definitions/Option$Dict$ByteArray_Int
+        //
+        // {
+        //   "anyOf": [
+        //      {
+        //          "title": "Some",
+        //          "dataType": "constructor",
+        //          "index": 0,
+        //          "fields": [{
+        //              "dataType": "map",
+        //              "keys": { "$ref": "#/definitions/ByteArray" },
+        //              "values": { "$ref": "#/definitions/Int" }
+        //          }]
+        //      },
+        //      {
+        //          "title": "None",
+        //          "dataType": "constructor",
+        //          "index": 1,
+        //          "fields": []
+        //      },
+        //   ]
+        // }
+        let mut definitions = fixture_definitions();
+        definitions.insert(
+            &schema,
+            Schema::Data(Data::AnyOf(vec![
+                Annotated {
+                    title: Some("Some".to_string()),
+                    description: None,
+                    examples: vec![],
+                    annotated: Constructor {
+                        index: 0,
+                        fields: vec![Declaration::Inline(Box::new(Data::Map(
+                            Declaration::Referenced(Reference::new("ByteArray")),
+                            Declaration::Referenced(Reference::new("Int")),
+                        )))
+                        .into()],
+                    },
+                },
+                Annotated {
+                    title: Some("None".to_string()),
+                    description: None,
+                    examples: vec![],
+                    annotated: Constructor {
+                        index: 1,
+                        fields: vec![],
+                    },
+                },
+            ]))
+            .into(),
+        );
+
+        let param: Parameter = schema.into();
+
+        assert_eq!(
+            param
+                .from_json(&definitions, &serde_json::json!("None"))
+                .unwrap(),
+            uplc_ast::Data::constr(1, vec![])
+        );
+
+        let expected = uplc_ast::Data::constr(
+            0,
+            vec![uplc_ast::Data::map(vec![(
+                uplc_ast::Data::bytestring(vec![102, 111, 111]),
+                uplc_ast::Data::integer(42.into()),
+            )])],
+        );
+
+        for json in [
+            serde_json::json!({ "Some": { "666f6f": 42 } }),
+            serde_json::json!({ "Some": [["666f6f", 42]] }),
+        ] {
+            assert_eq!(param.from_json(&definitions, &json).unwrap(), expected);
+        }
+
+        assert!(matches!(
+            param.from_json(&definitions, &serde_json::json!({ "Some": { "666f6f": "foo" } })),
+            Err(Error::MalformedParameter { hint }) if hint.contains("at fields[0].map[0].value")
+        ));
+
+        assert!(matches!(
+            param.from_json(&definitions, &serde_json::json!("Nothing")),
+            Err(Error::MalformedParameter { hint }) if hint.contains("one of: Some, None")
+        ));
+    }
 }
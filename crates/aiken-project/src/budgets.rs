@@ -0,0 +1,252 @@
+use crate::{blueprint::Blueprint, error::Error};
+use aiken_lang::{
+    ast::Span,
+    expr::UntypedExpr,
+    test_framework::{TestResult, UnitTestResult},
+};
+use miette::NamedSource;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Compiled sizes of validators and execution budgets of unit tests, as locked in 'budgets.lock'
+/// so that later builds and checks can detect regressions. Validators are keyed by their title,
+/// and tests by '{module}.{name}'.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct BudgetsLock {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub validators: BTreeMap<String, ValidatorBudget>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tests: BTreeMap<String, TestBudget>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorBudget {
+    /// Size of the compiled code, in bytes.
+    pub size: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestBudget {
+    pub mem: i64,
+    pub cpu: i64,
+}
+
+/// A validator or a test whose size or budget grew beyond the tolerance, compared to the lock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regression {
+    pub name: String,
+    pub metric: &'static str,
+    pub locked: i64,
+    pub current: i64,
+}
+
+impl BudgetsLock {
+    /// Sizes of the validators of a blueprint.
+    pub fn from_blueprint(blueprint: &Blueprint) -> Self {
+        BudgetsLock {
+            validators: blueprint
+                .validators
+                .iter()
+                .map(|validator| {
+                    let (compiled_code, _) = validator.program.compiled_code_and_hash();
+                    (
+                        validator.title.clone(),
+                        ValidatorBudget {
+                            size: (compiled_code.len() / 2) as i64,
+                        },
+                    )
+                })
+                .collect(),
+            tests: BTreeMap::new(),
+        }
+    }
+
+    /// Budgets of successful unit tests; property tests run over random samples, and failed
+    /// tests, are left out.
+    pub fn from_tests(tests: &[TestResult<UntypedExpr, UntypedExpr>]) -> Self {
+        BudgetsLock {
+            validators: BTreeMap::new(),
+            tests: tests
+                .iter()
+                .filter_map(|result| match result {
+                    TestResult::UnitTestResult(UnitTestResult {
+                        success: true,
+                        spent_budget,
+                        test,
+                        ..
+                    }) => Some((
+                        format!("{}.{}", test.module, test.name),
+                        TestBudget {
+                            mem: spent_budget.mem,
+                            cpu: spent_budget.cpu,
+                        },
+                    )),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Read the lock from disk, if there's any.
+    pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let toml = fs::read_to_string(path).map_err(|error| Error::FileIo {
+            error,
+            path: path.to_path_buf(),
+        })?;
+
+        toml::from_str(&toml)
+            .map(Some)
+            .map_err(|e| Error::TomlLoading {
+                path: path.to_path_buf(),
+                src: toml.clone(),
+                named: NamedSource::new(path.display().to_string(), toml.clone()).into(),
+                location: e.span().map(|range| Span {
+                    start: range.start,
+                    end: range.end,
+                }),
+                help: e.to_string(),
+            })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut toml = toml::to_string(self).expect("budgets.lock serialization");
+
+        toml.insert_str(
+            0,
+            "# This file was generated by Aiken\n# Update it with '--lock-budgets' on 'aiken build' or 'aiken check'\n\n",
+        );
+
+        fs::write(path, toml).map_err(|error| Error::FileIo {
+            error,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Lock the sizes and budgets of 'current', keeping whatever else was locked already.
+    pub fn extend(&mut self, current: BudgetsLock) {
+        self.validators.extend(current.validators);
+        self.tests.extend(current.tests);
+    }
+
+    /// Find validators and tests of 'current' whose size or budget exceed the lock by more than
+    /// 'tolerance' percents. Those absent from the lock can't be compared and are ignored.
+    pub fn regressions(&self, current: &BudgetsLock, tolerance: f64) -> Vec<Regression> {
+        let validators = current.validators.iter().flat_map(|(name, budget)| {
+            self.validators
+                .get(name)
+                .map(|locked| (name, "size", locked.size, budget.size))
+        });
+
+        let tests = current.tests.iter().flat_map(|(name, budget)| {
+            self.tests.get(name).into_iter().flat_map(move |locked| {
+                [
+                    (name, "mem", locked.mem, budget.mem),
+                    (name, "cpu", locked.cpu, budget.cpu),
+                ]
+            })
+        });
+
+        validators
+            .chain(tests)
+            .filter(|(_, _, locked, current)| {
+                *current as f64 > *locked as f64 * (1.0 + tolerance / 100.0)
+            })
+            .map(|(name, metric, locked, current)| Regression {
+                name: name.clone(),
+                metric,
+                locked,
+                current,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regressions_beyond_tolerance() {
+        let locked = BudgetsLock {
+            validators: BTreeMap::from([
+                (
+                    "vault.vault.spend".to_string(),
+                    ValidatorBudget { size: 1000 },
+                ),
+                (
+                    "vault.vault.mint".to_string(),
+                    ValidatorBudget { size: 500 },
+                ),
+            ]),
+            tests: BTreeMap::from([(
+                "vault/utils.foo".to_string(),
+                TestBudget {
+                    mem: 100,
+                    cpu: 1000,
+                },
+            )]),
+        };
+
+        let current = BudgetsLock {
+            validators: BTreeMap::from([
+                (
+                    "vault.vault.spend".to_string(),
+                    ValidatorBudget { size: 1040 },
+                ),
+                (
+                    "vault.vault.mint".to_string(),
+                    ValidatorBudget { size: 400 },
+                ),
+                (
+                    "oracle.oracle.spend".to_string(),
+                    ValidatorBudget { size: 9000 },
+                ),
+            ]),
+            tests: BTreeMap::from([(
+                "vault/utils.foo".to_string(),
+                TestBudget {
+                    mem: 100,
+                    cpu: 1100,
+                },
+            )]),
+        };
+
+        assert_eq!(
+            locked.regressions(&current, 5.0),
+            vec![Regression {
+                name: "vault/utils.foo".to_string(),
+                metric: "cpu",
+                locked: 1000,
+                current: 1100,
+            }]
+        );
+
+        assert_eq!(locked.regressions(&current, 0.0).len(), 2);
+        assert!(locked.regressions(&current, 10.0).is_empty());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let lock = BudgetsLock {
+            validators: BTreeMap::from([(
+                "vault.vault.spend".to_string(),
+                ValidatorBudget { size: 1000 },
+            )]),
+            tests: BTreeMap::from([(
+                "vault/utils.foo".to_string(),
+                TestBudget {
+                    mem: 100,
+                    cpu: 1000,
+                },
+            )]),
+        };
+
+        let toml = toml::to_string(&lock).unwrap();
+
+        assert_eq!(toml::from_str::<BudgetsLock>(&toml).unwrap(), lock);
+    }
+}
@@ -0,0 +1,382 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use aiken_lang::tipo::TypeInfo;
+use ciborium::value::Value;
+use pallas_crypto::hash::Hasher;
+
+use crate::{
+    blueprint::Blueprint,
+    config,
+    module::{CheckedModule, ParsedModule},
+    paths,
+};
+
+/// A project-local cache of type-checked modules, so that subsequent runs only type-check the
+/// modules affected by a change.
+///
+/// A cached module is re-used only when its fingerprint is unchanged. The fingerprint covers the
+/// module's source code, the settings it is checked with and the interfaces of the modules it
+/// imports; so that changing the mere implementation of a module doesn't invalidate its
+/// dependents. Modules that raised warnings are never cached, so that their warnings keep being
+/// reported.
+#[derive(Debug, Clone)]
+pub struct BuildCache {
+    dir: Option<PathBuf>,
+    settings: String,
+}
+
+impl BuildCache {
+    /// Create a cache for modules checked with the given settings, which are anything besides
+    /// sources that might affect the outcome of type-checking. Without a project root (e.g. for
+    /// projects held in memory), the cache only computes fingerprints, and stores nothing.
+    pub fn new(root: Option<&Path>, settings: String) -> Self {
+        Self {
+            dir: root.map(|root| root.join(paths::build_cache())),
+            settings,
+        }
+    }
+
+    /// Compute the fingerprint of a module, given the interfaces of the modules it imports.
+    pub fn fingerprint<'a>(
+        &self,
+        module: &ParsedModule,
+        dependencies: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> String {
+        fingerprint(&self.settings, module, dependencies)
+    }
+
+    /// Load a module from the cache, along with its interface, provided that it was stored with
+    /// the same fingerprint.
+    pub fn load(
+        &self,
+        module: &ParsedModule,
+        fingerprint: &str,
+    ) -> Option<(CheckedModule, String)> {
+        let bytes = fs::read(self.module_path(&module.name)?).ok()?;
+
+        let (cached_fingerprint, interface, checked_module): (String, String, CheckedModule) =
+            ciborium::from_reader(&bytes[..]).ok()?;
+
+        if cached_fingerprint != fingerprint {
+            return None;
+        }
+
+        Some((
+            CheckedModule {
+                input_path: module.path.clone(),
+                ..checked_module
+            },
+            interface,
+        ))
+    }
+
+    /// Store a checked module in the cache. This is best-effort: failing to write to the cache
+    /// only means that the module will need to be type-checked again next time.
+    pub fn store(&self, module: &CheckedModule, fingerprint: &str, interface: &str) {
+        let (Some(dir), Some(path)) = (&self.dir, self.module_path(&module.name)) else {
+            return;
+        };
+
+        let mut bytes = vec![];
+
+        if fs::create_dir_all(dir).is_ok()
+            && ciborium::into_writer(&(fingerprint, interface, module), &mut bytes).is_ok()
+        {
+            let _ = fs::write(path, bytes);
+        }
+    }
+
+    fn module_path(&self, name: &str) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        Some(dir.join(format!("{}.cbor", name.replace('/', "."))))
+    }
+}
+
+/// An in-memory counterpart of the [`BuildCache`], for processes compiling the same project over
+/// and over (e.g. `aiken build --watch` or the language server). Besides our modules, it holds
+/// dependency modules and the last blueprint generated, so that iterations which don't touch
+/// validators skip code generation entirely.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCache {
+    modules: HashMap<String, (String, CheckedModule, String)>,
+    blueprint: Option<(String, Blueprint)>,
+    /// The next id of the project which filled the cache; so that the next project doesn't
+    /// generate ids clashing with those of the modules it holds.
+    pub(crate) next_id: u64,
+}
+
+impl MemoryCache {
+    /// Same as [`BuildCache::load`].
+    pub fn load(
+        &self,
+        module: &ParsedModule,
+        fingerprint: &str,
+    ) -> Option<(CheckedModule, String)> {
+        let (cached_fingerprint, checked_module, interface) = self.modules.get(&module.name)?;
+
+        if cached_fingerprint != fingerprint {
+            return None;
+        }
+
+        Some((
+            CheckedModule {
+                input_path: module.path.clone(),
+                ..checked_module.clone()
+            },
+            interface.clone(),
+        ))
+    }
+
+    /// Same as [`BuildCache::store`].
+    pub fn store(&mut self, module: &CheckedModule, fingerprint: &str, interface: &str) {
+        self.modules.insert(
+            module.name.clone(),
+            (
+                fingerprint.to_string(),
+                module.clone(),
+                interface.to_string(),
+            ),
+        );
+    }
+
+    /// Retrieve the last blueprint generated, provided that it was generated from the same
+    /// modules and settings (see [`MemoryCache::blueprint_fingerprint`]).
+    pub fn load_blueprint(&self, fingerprint: &str) -> Option<Blueprint> {
+        self.blueprint
+            .as_ref()
+            .filter(|(cached_fingerprint, _)| cached_fingerprint == fingerprint)
+            .map(|(_, blueprint)| blueprint.clone())
+    }
+
+    pub fn store_blueprint(&mut self, fingerprint: &str, blueprint: &Blueprint) {
+        self.blueprint = Some((fingerprint.to_string(), blueprint.clone()));
+    }
+
+    /// Compute the fingerprint of a blueprint from everything code generation depends on: the
+    /// sources of every module (dependencies included) and the settings they're compiled with.
+    pub fn blueprint_fingerprint<'a>(
+        settings: &str,
+        modules: impl IntoIterator<Item = &'a CheckedModule>,
+    ) -> String {
+        let mut hasher = Hasher::<256>::new();
+
+        hasher.input(config::compiler_version(true).as_bytes());
+        hasher.input(settings.as_bytes());
+
+        let mut modules = modules
+            .into_iter()
+            .map(|module| (module.name.as_str(), module.code.as_str()))
+            .collect::<Vec<_>>();
+        modules.sort();
+
+        for (name, code) in modules {
+            hasher.input(name.as_bytes());
+            hasher.input(&(code.len() as u64).to_be_bytes());
+            hasher.input(code.as_bytes());
+        }
+
+        hasher.finalize().to_string()
+    }
+}
+
+/// A fingerprint of a module's source code and of the interfaces of the modules it imports, for
+/// the given settings.
+pub fn fingerprint<'a>(
+    settings: &str,
+    module: &ParsedModule,
+    dependencies: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> String {
+    let mut hasher = Hasher::<256>::new();
+
+    hasher.input(config::compiler_version(true).as_bytes());
+    hasher.input(settings.as_bytes());
+    hasher.input(module.name.as_bytes());
+    hasher.input(module.code.as_bytes());
+
+    let mut dependencies = dependencies.into_iter().collect::<Vec<_>>();
+    dependencies.sort();
+    dependencies.dedup();
+
+    for (name, interface) in dependencies {
+        hasher.input(name.as_bytes());
+        hasher.input(interface.as_bytes());
+    }
+
+    hasher.finalize().to_string()
+}
+
+/// The largest identifier found in a module restored from the disk. Type variables of such
+/// modules were generated by another process; fresh ones must not clash with them.
+pub fn largest_id(module: &CheckedModule) -> u64 {
+    fn walk(value: &Value) -> u64 {
+        match value {
+            Value::Map(entries) => entries
+                .iter()
+                .map(|(key, value)| match (key, value) {
+                    (Value::Text(key), Value::Integer(id)) if key == "id" => {
+                        u64::try_from(*id).unwrap_or_default()
+                    }
+                    _ => walk(value),
+                })
+                .max()
+                .unwrap_or_default(),
+            Value::Array(values) => values.iter().map(walk).max().unwrap_or_default(),
+            Value::Tag(_, value) => walk(value),
+            _ => 0,
+        }
+    }
+
+    Value::serialized(&module.ast)
+        .map(|value| walk(&value))
+        .unwrap_or_default()
+}
+
+/// A fingerprint of the types and values a module exposes. It depends neither on the iteration
+/// order of hash maps, nor on the identifiers of type variables; both of which vary from one
+/// compilation to another.
+pub fn interface(type_info: &TypeInfo) -> String {
+    let mut value = Value::serialized(type_info).expect("type info should serialize");
+
+    normalize(&mut value, &mut HashMap::new());
+
+    let mut bytes = vec![];
+
+    ciborium::into_writer(&value, &mut bytes).expect("values should serialize");
+
+    Hasher::<256>::hash(&bytes).to_string()
+}
+
+// Sort maps by keys and renumber type variables in order of appearance.
+fn normalize(value: &mut Value, ids: &mut HashMap<i128, usize>) {
+    match value {
+        Value::Map(entries) => {
+            entries.sort_by_cached_key(|(key, _)| {
+                let mut bytes = vec![];
+                let _ = ciborium::into_writer(key, &mut bytes);
+                bytes
+            });
+
+            for (key, value) in entries.iter_mut() {
+                match (key, &value) {
+                    (Value::Text(key), Value::Integer(id)) if key == "id" => {
+                        let next = ids.len();
+                        let id = *ids.entry(i128::from(*id)).or_insert(next);
+                        *value = Value::Integer((id as u64).into());
+                    }
+                    _ => normalize(value, ids),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| normalize(value, ids)),
+        Value::Tag(_, value) => normalize(value, ids),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aiken_lang::{
+        ast::{ModuleKind, Tracing, TypedModule},
+        builtins, IdGenerator,
+    };
+
+    fn type_info(src: &str, id_gen: &IdGenerator) -> TypeInfo {
+        infer(src, id_gen).type_info
+    }
+
+    fn infer(src: &str, id_gen: &IdGenerator) -> TypedModule {
+        let (mut module, _) = aiken_lang::parser::module(src, ModuleKind::Lib).unwrap();
+        module.name = "foo".to_string();
+
+        let mut module_types = HashMap::new();
+        module_types.insert("aiken".to_string(), builtins::prelude(id_gen));
+        module_types.insert("aiken/builtin".to_string(), builtins::plutus(id_gen));
+
+        module
+            .infer(
+                id_gen,
+                ModuleKind::Lib,
+                "test",
+                &module_types,
+                Tracing::verbose(),
+                &mut vec![],
+                None,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn interface_ignores_type_variable_ids() {
+        let src = r#"
+            pub fn identity(x: a) -> a {
+              x
+            }
+
+            pub fn first(x: a, _y: b) -> a {
+              x
+            }
+        "#;
+
+        let id_gen = IdGenerator::new();
+        let left = type_info(src, &id_gen);
+        let right = type_info(src, &id_gen);
+
+        assert_eq!(interface(&left), interface(&right));
+    }
+
+    #[test]
+    fn largest_id_covers_type_variables() {
+        let id_gen = IdGenerator::new();
+
+        let ast = infer("pub fn identity(x: a) -> a { x }", &id_gen);
+
+        let module = CheckedModule {
+            name: "foo".to_string(),
+            code: String::new(),
+            input_path: PathBuf::new(),
+            kind: ModuleKind::Lib,
+            package: "test".to_string(),
+            ast,
+            extra: Default::default(),
+        };
+
+        let fresh = IdGenerator::new();
+        fresh.skip_past(largest_id(&module));
+
+        assert!(largest_id(&module) > 0);
+        assert!(fresh.next() > largest_id(&module));
+    }
+
+    #[test]
+    fn interface_ignores_implementation() {
+        let id_gen = IdGenerator::new();
+
+        let left = type_info("pub fn foo(x: Int) -> Int { x + 1 }", &id_gen);
+        let right = type_info("pub fn foo(x: Int) -> Int { x + 2 }", &id_gen);
+        let other = type_info("pub fn foo(x: Int) -> Bool { x > 2 }", &id_gen);
+
+        assert_eq!(interface(&left), interface(&right));
+        assert_ne!(interface(&left), interface(&other));
+    }
+
+    #[test]
+    fn memory_cache_blueprint() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../examples/hello_world/plutus.json"))
+                .unwrap();
+
+        let mut memory = MemoryCache::default();
+
+        assert_eq!(memory.load_blueprint("foo"), None);
+
+        memory.store_blueprint("foo", &blueprint);
+
+        assert_eq!(memory.load_blueprint("foo"), Some(blueprint));
+        assert_eq!(memory.load_blueprint("bar"), None);
+    }
+}
@@ -1,7 +1,14 @@
-use crate::{github::repo::LatestRelease, package_name::PackageName, paths, Error};
+#[cfg(feature = "native")]
+use crate::github::repo::LatestRelease;
+use crate::{package_name::PackageName, paths, Error};
 use aiken_lang::{
-    ast::{Annotation, ByteArrayFormatPreference, ModuleConstant, Span, UntypedDefinition},
+    ast::{
+        Annotation, ByteArrayFormatPreference, ModuleConstant, Span, TraceLevel, UntypedDefinition,
+    },
+    error::{Coded, ErrorCode},
     expr::UntypedExpr,
+    format::{CallWrapping, FormatOptions, ImportGroup},
+    levenshtein,
     parser::token::Base,
 };
 pub use aiken_lang::{plutus_version::PlutusVersion, version::compiler_version};
@@ -12,9 +19,18 @@ use serde::{
     ser::{self, SerializeSeq, SerializeStruct},
     Deserialize, Serialize,
 };
-use std::{collections::BTreeMap, fmt::Display, fs, io, path::Path};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+use uplc::optimize::OptimizationLevel;
+
+pub const DEFAULT_FEATURE: &str = "default";
 
 #[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     pub name: PackageName,
     pub version: String,
@@ -26,12 +42,38 @@ pub struct Config {
     pub compiler: Version,
     #[serde(default)]
     pub plutus: PlutusVersion,
+    /// Former name of 'plutus', still accepted (and taking precedence) but deprecated.
+    #[serde(default, rename = "plutusVersion", skip_serializing)]
+    pub plutus_version: Option<PlutusVersion>,
     pub license: Option<String>,
     #[serde(default)]
     pub description: String,
+    /// Alternative base URLs to download packages from, tried in order before the default
+    /// registry. Overridden by the 'AIKEN_PACKAGES_MIRRORS' environment variable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<String>,
     pub repository: Option<Repository>,
     #[serde(default)]
     pub dependencies: Vec<Dependency>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub patch: BTreeMap<PackageName, Patch>,
+    /// Optional features which can be enabled to compile in definitions and expressions guarded
+    /// by a `@cfg(feature = "...")` attribute. Each feature lists other features it implies. The
+    /// 'default' feature, when present, is enabled unless explicitly asked otherwise.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub features: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+    #[serde(default, skip_serializing_if = "Fmt::is_empty")]
+    pub fmt: Fmt,
+    #[serde(default, skip_serializing_if = "Diagnostics::is_empty")]
+    pub diagnostics: Diagnostics,
+    #[serde(default, skip_serializing_if = "Budgets::is_empty")]
+    pub budgets: Budgets,
+    /// Build settings overriding the project's defaults for specific validators, keyed by either
+    /// '{module}.{validator}' or, more loosely, by the validator's name alone.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub validators: BTreeMap<String, ValidatorConfig>,
     #[serde(default)]
     pub config: BTreeMap<String, BTreeMap<String, SimpleExpr>>,
 }
@@ -269,7 +311,18 @@ fn default_version() -> Version {
     Version::parse(built_info::PKG_VERSION).unwrap()
 }
 
+/// Latest release of the standard library, or a known one when it can't be looked up.
+fn latest_stdlib_release() -> String {
+    #[cfg(feature = "native")]
+    if let Ok(stdlib) = LatestRelease::of("aiken-lang/stdlib") {
+        return stdlib.tag_name;
+    }
+
+    "1.5.0".to_string()
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct Repository {
     pub user: String,
     pub project: String,
@@ -285,10 +338,165 @@ pub enum Platform {
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct Dependency {
     pub name: PackageName,
     pub version: String,
     pub source: Platform,
+    /// Location of the package within its repository, for packages that don't live at the root
+    /// of their repository (e.g. in monorepos).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdir: Option<PathBuf>,
+}
+
+/// Shell commands to run around a build, in order. A failing command aborts the build.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_build: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_build: Vec<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_build.is_empty() && self.post_build.is_empty()
+    }
+}
+
+/// Settings of the formatter, shared by 'aiken fmt' and the language server.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Fmt {
+    /// Width past which lines get broken; 80 columns when unspecified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<usize>,
+    /// Order of the groups imports are laid out in, among 'stdlib', 'external' and 'local'. An
+    /// empty list keeps all imports together.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_groups: Option<Vec<ImportGroup>>,
+    /// Whether trailing comments of consecutive record fields and constants are aligned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub align_comments: Option<bool>,
+    /// Whether multi-line calls and lists end with a trailing comma; true when unspecified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing_commas: Option<bool>,
+    /// How long calls are wrapped: 'one-per-line' (the default) or 'packed'.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_wrapping: Option<CallWrapping>,
+}
+
+impl Fmt {
+    pub fn is_empty(&self) -> bool {
+        self.max_width.is_none()
+            && self.import_groups.is_none()
+            && self.align_comments.is_none()
+            && self.trailing_commas.is_none()
+            && self.call_wrapping.is_none()
+    }
+
+    pub fn options(&self, local_modules: Vec<String>) -> FormatOptions {
+        let default = FormatOptions::default();
+
+        FormatOptions {
+            max_width: self
+                .max_width
+                .map(|width| width as isize)
+                .unwrap_or(default.max_width),
+            import_groups: self.import_groups.clone().unwrap_or(default.import_groups),
+            local_modules,
+            align_comments: self.align_comments.unwrap_or(default.align_comments),
+            trailing_commas: self.trailing_commas.unwrap_or(default.trailing_commas),
+            call_wrapping: self.call_wrapping.unwrap_or(default.call_wrapping),
+        }
+    }
+}
+
+/// Warnings to silence or to turn into errors, by code (e.g. 'AK0513'). Codes are listed by
+/// 'aiken explain'.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Diagnostics {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<ErrorCode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<ErrorCode>,
+}
+
+impl Diagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    pub fn allows(&self, diagnostic: &impl Coded) -> bool {
+        diagnostic
+            .error_code()
+            .is_some_and(|code| self.allow.contains(&code))
+    }
+
+    pub fn denies(&self, diagnostic: &impl Coded) -> bool {
+        diagnostic
+            .error_code()
+            .is_some_and(|code| self.deny.contains(&code))
+    }
+}
+
+/// How validator sizes and test budgets are compared against 'budgets.lock'.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Budgets {
+    /// Increase, in percents, beyond which a size or budget counts as a regression; 0 when
+    /// unspecified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance: Option<f64>,
+    /// Whether regressions are reported as warnings, rather than failing the command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn_only: Option<bool>,
+}
+
+impl Budgets {
+    pub fn is_empty(&self) -> bool {
+        self.tolerance.is_none() && self.warn_only.is_none()
+    }
+}
+
+/// Per-validator build settings. Anything left unspecified falls back to the project's defaults.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plutus: Option<PlutusVersion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimization: Option<OptimizationLevel>,
+    /// Trace level of compiler-generated traces; 'silent' also strips user-defined traces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_level: Option<TraceLevel>,
+    /// Name under which the validator appears in the blueprint, in place of its source name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// An override for a dependency, replacing it wherever it appears during resolution. A patch
+/// either points at a local directory holding the package, or at an alternative version and
+/// (optionally) an alternative repository, such as a fork.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
+#[serde(untagged)]
+pub enum Patch {
+    Path {
+        path: PathBuf,
+    },
+    Source {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<PackageName>,
+        version: String,
+        #[serde(default = "default_platform")]
+        source: Platform,
+    },
+}
+
+fn default_platform() -> Platform {
+    Platform::Github
 }
 
 impl Display for Platform {
@@ -308,8 +516,10 @@ impl Config {
             version: "0.0.0".to_string(),
             compiler: default_version(),
             plutus: PlutusVersion::default(),
+            plutus_version: None,
             license: Some("Apache-2.0".to_string()),
             description: format!("Aiken contracts for project '{name}'"),
+            mirrors: vec![],
             repository: Some(Repository {
                 user: name.owner.clone(),
                 project: name.repo.clone(),
@@ -320,12 +530,17 @@ impl Config {
                     owner: "aiken-lang".to_string(),
                     repo: "stdlib".to_string(),
                 },
-                version: match LatestRelease::of("aiken-lang/stdlib") {
-                    Ok(stdlib) => stdlib.tag_name,
-                    _ => "1.5.0".to_string(),
-                },
+                version: latest_stdlib_release(),
                 source: Platform::Github,
+                subdir: None,
             }],
+            patch: BTreeMap::new(),
+            features: BTreeMap::new(),
+            hooks: Hooks::default(),
+            fmt: Fmt::default(),
+            diagnostics: Diagnostics::default(),
+            budgets: Budgets::default(),
+            validators: BTreeMap::new(),
             config: BTreeMap::new(),
         }
     }
@@ -342,19 +557,161 @@ impl Config {
             path: dir.to_path_buf(),
         })?;
 
-        let result: Self = toml::from_str(&raw_config).map_err(|e| Error::TomlLoading {
-            path: config_path.clone(),
+        Self::parse(&config_path, raw_config)
+    }
+
+    /// Parse and validate a configuration. Errors point at the offending part of the source,
+    /// and unknown keys come with a suggestion when they look like a known one.
+    pub fn parse(config_path: &Path, raw_config: String) -> Result<Config, Error> {
+        let mut config: Config = toml::from_str(&raw_config).map_err(|e| Error::TomlLoading {
+            path: config_path.to_path_buf(),
             src: raw_config.clone(),
             named: NamedSource::new(config_path.display().to_string(), raw_config).into(),
-            // this isn't actually a legit way to get the span
             location: e.span().map(|range| Span {
                 start: range.start,
                 end: range.end,
             }),
-            help: e.to_string(),
+            help: match suggest_key(e.message()) {
+                Some(suggestion) => format!("{}\nDid you mean '{suggestion}'?", e.message()),
+                None => e.message().to_string(),
+            },
         })?;
 
-        Ok(result)
+        if let Some(plutus) = config.plutus_version {
+            config.plutus = plutus;
+        }
+
+        Ok(config)
+    }
+
+    /// All values of the configuration, as pairs of dotted keys and values. Items of arrays of
+    /// tables are designated by their index (e.g. 'dependencies.0.name'). When a prefix is given,
+    /// only keys starting with that prefix are returned.
+    pub fn entries(&self, prefix: Option<&str>) -> Vec<(String, toml::Value)> {
+        let mut entries = vec![];
+
+        flatten(String::new(), self.to_value(), &mut entries);
+
+        entries
+            .into_iter()
+            .filter(|(key, _)| match prefix {
+                None => true,
+                Some(prefix) => {
+                    key == prefix
+                        || key
+                            .strip_prefix(prefix)
+                            .is_some_and(|rest| rest.starts_with('.'))
+                }
+            })
+            .collect()
+    }
+
+    /// Set the value at a dotted key, creating intermediate tables as needed. Values are parsed
+    /// as TOML when possible (e.g. 'true', '42', '["foo"]') and taken as plain strings otherwise.
+    /// The resulting configuration is validated before being returned.
+    pub fn set(&self, key: &str, value: &str) -> Result<Config, Error> {
+        let invalid = |reason: String| Error::InvalidConfigValue {
+            key: key.to_string(),
+            reason,
+        };
+
+        let value = toml::from_str::<toml::Table>(&format!("value = {value}"))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or_else(|| toml::Value::String(value.to_string()));
+
+        let mut root = self.to_value();
+
+        let mut segments = key.split('.').peekable();
+
+        let mut cursor = &mut root;
+
+        while let Some(segment) = segments.next() {
+            let slot = match cursor {
+                toml::Value::Table(table) => table
+                    .entry(segment)
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new())),
+                toml::Value::Array(items) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|ix| items.get_mut(ix))
+                    .ok_or_else(|| invalid(format!("There's no item '{segment}' here.")))?,
+                _ => return Err(invalid(format!("'{segment}' isn't within a table."))),
+            };
+
+            if segments.peek().is_none() {
+                *slot = value;
+                break;
+            }
+
+            cursor = slot;
+        }
+
+        root.try_into()
+            .map_err(|e: toml::de::Error| invalid(e.message().to_string()))
+    }
+
+    /// Like [`Config::set`], but also writes the change to the project's configuration file,
+    /// editing it in place so that comments and layout are preserved.
+    pub fn set_in_place(dir: &Path, key: &str, value: &str) -> Result<Config, Error> {
+        let config_path = dir.join(paths::project_config());
+
+        let raw_config = fs::read_to_string(&config_path).map_err(|_| Error::MissingManifest {
+            path: dir.to_path_buf(),
+        })?;
+
+        let config = Self::parse(&config_path, raw_config.clone())?.set(key, value)?;
+
+        fs::write(&config_path, edit(&raw_config, key, value)?).map_err(|error| Error::FileIo {
+            error,
+            path: config_path,
+        })?;
+
+        Ok(config)
+    }
+
+    fn to_value(&self) -> toml::Value {
+        toml::Value::try_from(self).expect("configuration is always serializable")
+    }
+
+    /// Find the build settings of a given validator, favoring a fully qualified key over one
+    /// made of the validator's name only.
+    pub fn validator_config(&self, module: &str, validator: &str) -> Option<&ValidatorConfig> {
+        self.validators
+            .get(&format!("{module}.{validator}"))
+            .or_else(|| self.validators.get(validator))
+    }
+
+    /// Resolve the set of enabled features from those requested, including the features they
+    /// imply. Requested features must be declared in the configuration.
+    pub fn resolve_features(
+        &self,
+        requested: &[String],
+        default_features: bool,
+    ) -> Result<BTreeSet<String>, Error> {
+        let mut enabled = BTreeSet::new();
+
+        let mut queue = requested.to_vec();
+
+        if default_features && self.features.contains_key(DEFAULT_FEATURE) {
+            queue.push(DEFAULT_FEATURE.to_string());
+        }
+
+        while let Some(name) = queue.pop() {
+            let implied = self
+                .features
+                .get(&name)
+                .ok_or_else(|| Error::UnknownFeature {
+                    name: name.clone(),
+                    known_features: self.features.keys().cloned().collect(),
+                })?;
+
+            if enabled.insert(name) {
+                queue.extend(implied.iter().cloned());
+            }
+        }
+
+        Ok(enabled)
     }
 
     pub fn insert(mut self, dependency: &Dependency, and_replace: bool) -> Option<Self> {
@@ -373,6 +730,116 @@ impl Config {
     }
 }
 
+/// Set the value at a dotted key of a raw configuration, leaving everything else untouched.
+fn edit(raw_config: &str, key: &str, value: &str) -> Result<String, Error> {
+    let invalid = |reason: String| Error::InvalidConfigValue {
+        key: key.to_string(),
+        reason,
+    };
+
+    let mut document = raw_config
+        .parse::<toml_edit::Document>()
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let mut value = format!("value = {value}")
+        .parse::<toml_edit::Document>()
+        .ok()
+        .and_then(|mut document| document.remove("value"))
+        .unwrap_or_else(|| toml_edit::value(value));
+
+    // The deprecated key would otherwise still take precedence over the one being set.
+    if key == "plutus" {
+        document.remove("plutusVersion");
+    }
+
+    let mut segments = key.split('.').peekable();
+
+    let mut cursor = document.as_item_mut();
+
+    while let Some(segment) = segments.next() {
+        let slot = match segment.parse::<usize>() {
+            Ok(ix) if cursor.is_array() || cursor.is_array_of_tables() => cursor
+                .get_mut(ix)
+                .ok_or_else(|| invalid(format!("There's no item '{segment}' here.")))?,
+            _ => {
+                let is_inline = !cursor.is_table();
+
+                let table = cursor
+                    .as_table_like_mut()
+                    .ok_or_else(|| invalid(format!("'{segment}' isn't within a table.")))?;
+
+                if table.get(segment).is_none() {
+                    table.insert(
+                        segment,
+                        if is_inline {
+                            toml_edit::value(toml_edit::InlineTable::new())
+                        } else {
+                            toml_edit::table()
+                        },
+                    );
+                }
+
+                table.get_mut(segment).expect("just inserted")
+            }
+        };
+
+        if segments.peek().is_none() {
+            // Keep whatever surrounds the previous value, such as a trailing comment.
+            if let (Some(previous), Some(next)) = (slot.as_value(), value.as_value_mut()) {
+                *next.decor_mut() = previous.decor().clone();
+            }
+
+            *slot = value;
+            break;
+        }
+
+        cursor = slot;
+    }
+
+    Ok(document.to_string())
+}
+
+fn flatten(key: String, value: toml::Value, entries: &mut Vec<(String, toml::Value)>) {
+    let child = |segment: &str| {
+        if key.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{key}.{segment}")
+        }
+    };
+
+    match value {
+        toml::Value::Table(table) if !table.is_empty() => {
+            for (segment, value) in table {
+                flatten(child(&segment), value, entries);
+            }
+        }
+        toml::Value::Array(items) if !items.is_empty() && items.iter().all(|v| v.is_table()) => {
+            for (ix, value) in items.into_iter().enumerate() {
+                flatten(child(&ix.to_string()), value, entries);
+            }
+        }
+        value => entries.push((key, value)),
+    }
+}
+
+/// Find a known key close to an unknown one, from serde's error message (e.g. "unknown field
+/// `nmae`, expected one of `name`, `version`").
+fn suggest_key(message: &str) -> Option<&str> {
+    let (unknown, expected) = message.strip_prefix("unknown field `")?.split_once('`')?;
+
+    let threshold = (unknown.len() as f64).sqrt().round() as usize;
+
+    expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(|known| (known, levenshtein::distance(unknown, known)))
+        .min_by_key(|(_, distance)| *distance)
+        .filter(|(_, distance)| *distance <= threshold)
+        .map(|(known, _)| known)
+}
+
 mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
@@ -437,4 +904,276 @@ mod tests {
 
         }
     }
+
+    #[test]
+    fn resolve_features() {
+        let config: Config = toml::from_str(
+            r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [features]
+            default = ["debug"]
+            debug = []
+            full = ["debug", "extra"]
+            extra = []
+            "#,
+        )
+        .unwrap();
+
+        let features = |requested: &[&str], default_features| {
+            config
+                .resolve_features(
+                    &requested.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+                    default_features,
+                )
+                .map(|enabled| enabled.into_iter().collect::<Vec<_>>())
+        };
+
+        assert_eq!(features(&[], true).unwrap(), vec!["debug", "default"]);
+        assert!(features(&[], false).unwrap().is_empty());
+        assert_eq!(
+            features(&["full"], false).unwrap(),
+            vec!["debug", "extra", "full"]
+        );
+        assert!(matches!(
+            features(&["unknown"], true),
+            Err(Error::UnknownFeature { name, .. }) if name == "unknown"
+        ));
+    }
+
+    #[test]
+    fn validator_config() {
+        let config: Config = toml::from_str(
+            r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [validators.mint]
+            optimization = "off"
+
+            [validators."foo/bar.mint"]
+            plutus = "v2"
+            trace_level = "silent"
+            name = "policy"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.validator_config("foo/bar", "mint"),
+            Some(&ValidatorConfig {
+                plutus: Some(PlutusVersion::V2),
+                optimization: None,
+                trace_level: Some(TraceLevel::Silent),
+                name: Some("policy".to_string()),
+            })
+        );
+        assert_eq!(
+            config
+                .validator_config("foo/baz", "mint")
+                .and_then(|settings| settings.optimization),
+            Some(OptimizationLevel::Off)
+        );
+        assert!(config.validator_config("foo/bar", "spend").is_none());
+    }
+
+    #[test]
+    fn fmt_config() {
+        let config: Config = toml::from_str(
+            r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [fmt]
+            max_width = 100
+            import_groups = ["local", "stdlib"]
+            align_comments = true
+            call_wrapping = "packed"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.fmt.options(vec!["foo".to_string()]),
+            FormatOptions {
+                max_width: 100,
+                import_groups: vec![ImportGroup::Local, ImportGroup::Stdlib],
+                local_modules: vec!["foo".to_string()],
+                align_comments: true,
+                trailing_commas: true,
+                call_wrapping: CallWrapping::Packed,
+            }
+        );
+        assert_eq!(Fmt::default().options(vec![]), FormatOptions::default());
+    }
+
+    #[test]
+    fn diagnostics_config() {
+        struct Diagnostic(&'static str);
+
+        impl aiken_lang::error::Coded for Diagnostic {
+            fn error_code(&self) -> Option<ErrorCode> {
+                self.0.parse().ok()
+            }
+        }
+
+        let config: Config = toml::from_str(
+            r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [diagnostics]
+            allow = ["AK0513"]
+            deny = ["ak0500"]
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.diagnostics.allows(&Diagnostic("AK0513")));
+        assert!(!config.diagnostics.allows(&Diagnostic("AK0500")));
+        assert!(config.diagnostics.denies(&Diagnostic("AK0500")));
+        assert!(!config.diagnostics.denies(&Diagnostic("AK0513")));
+        assert!(!config.diagnostics.denies(&Diagnostic("")));
+
+        assert!(toml::from_str::<Config>(
+            r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [diagnostics]
+            allow = ["AK9999"]
+            "#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parse_unknown_key() {
+        let src = indoc::indoc! { r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [repository]
+            user = "aiken-lang"
+            projetc = "test"
+            platform = "github"
+        "# };
+
+        match Config::parse(Path::new("aiken.toml"), src.to_string()) {
+            Err(Error::TomlLoading { location, help, .. }) => {
+                let location = location.expect("no location for unknown key");
+                assert_eq!(&src[location.start..location.end], "projetc");
+                assert!(help.ends_with("Did you mean 'project'?"), "{help}");
+            }
+            _ => panic!("unknown key wasn't rejected"),
+        }
+    }
+
+    #[test]
+    fn parse_type_mismatch() {
+        let src = indoc::indoc! { r#"
+            name = "aiken-lang/test"
+            version = 1
+        "# };
+
+        match Config::parse(Path::new("aiken.toml"), src.to_string()) {
+            Err(Error::TomlLoading { location, help, .. }) => {
+                let location = location.expect("no location for type mismatch");
+                assert_eq!(&src[location.start..location.end], "1");
+                assert!(!help.contains("Did you mean"), "{help}");
+            }
+            _ => panic!("type mismatch wasn't rejected"),
+        }
+    }
+
+    #[test]
+    fn get_and_set_entries() {
+        let config: Config = toml::from_str(
+            r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [[dependencies]]
+            name = "aiken-lang/stdlib"
+            version = "v2"
+            source = "github"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.entries(Some("dependencies")),
+            vec![
+                (
+                    "dependencies.0.name".to_string(),
+                    "aiken-lang/stdlib".into()
+                ),
+                ("dependencies.0.source".to_string(), "github".into()),
+                ("dependencies.0.version".to_string(), "v2".into()),
+            ]
+        );
+
+        let config = config.set("dependencies.0.version", "v3").unwrap();
+        assert_eq!(config.dependencies[0].version, "v3");
+
+        let config = config.set("hooks.pre_build", r#"["make"]"#).unwrap();
+        assert_eq!(config.hooks.pre_build, vec!["make".to_string()]);
+
+        assert!(matches!(
+            config.set("plutus", "v4"),
+            Err(Error::InvalidConfigValue { key, .. }) if key == "plutus"
+        ));
+        assert!(matches!(
+            config.set("repository.usr", "foo"),
+            Err(Error::InvalidConfigValue { .. })
+        ));
+    }
+
+    #[test]
+    fn edit_in_place() {
+        let raw_config = indoc::indoc! { r#"
+            # The project
+            name = "foo/bar"
+            version = "0.0.0"
+            plutus = "v3" # for now
+
+            [[dependencies]]
+            name = "aiken-lang/stdlib"
+            version = "v2"
+            source = "github"
+        "#};
+
+        assert_eq!(
+            edit(raw_config, "plutus", "v2").unwrap(),
+            raw_config.replace(r#""v3" # for now"#, r#""v2" # for now"#)
+        );
+
+        assert_eq!(
+            edit(raw_config, "dependencies.0.version", "v3").unwrap(),
+            raw_config.replace(r#"version = "v2""#, r#"version = "v3""#)
+        );
+
+        assert!(edit(raw_config, "hooks.pre_build", r#"["make"]"#)
+            .unwrap()
+            .ends_with("[hooks]\npre_build = [\"make\"]\n"));
+    }
+
+    #[test]
+    fn legacy_plutus_version() {
+        let raw_config = indoc::indoc! { r#"
+            name = "foo/bar"
+            version = "0.0.0"
+            plutusVersion = "v2"
+        "#};
+
+        let config = Config::parse(Path::new("aiken.toml"), raw_config.to_string()).unwrap();
+        assert_eq!(config.plutus, PlutusVersion::V2);
+        assert_eq!(config.plutus_version, Some(PlutusVersion::V2));
+
+        assert_eq!(
+            edit(raw_config, "plutus", "v3").unwrap(),
+            raw_config.replace("plutusVersion = \"v2\"\n", "plutus = \"v3\"\n")
+        );
+    }
 }
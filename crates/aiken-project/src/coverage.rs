@@ -0,0 +1,497 @@
+use aiken_lang::{
+    ast::Span,
+    coverage::{self, Region, RegionKind},
+    expr::UntypedExpr,
+    test_framework::{PropertyTestResult, TestResult, UnitTestResult},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    path::PathBuf,
+};
+
+/// Default directory where coverage reports are written, relative to the project's root.
+pub const DEFAULT_OUTPUT: &str = "coverage";
+
+/// How many times each region was entered, over all tests.
+#[derive(Debug, Default, Clone)]
+pub struct Hits(HashMap<(String, Span), usize>);
+
+impl Hits {
+    /// Count the coverage markers found in the traces (or labels) of a test result, and strip
+    /// them off, so that they don't get in the way of reporting it.
+    pub fn collect(&mut self, result: &mut TestResult<UntypedExpr, UntypedExpr>) {
+        match result {
+            TestResult::UnitTestResult(UnitTestResult { traces, .. }) => {
+                traces.retain(|trace| !self.record(trace, 1));
+            }
+            TestResult::PropertyTestResult(PropertyTestResult { labels, traces, .. }) => {
+                labels.retain(|label, count| !self.record(label, *count));
+                traces.retain(|trace| coverage::parse_marker(trace).is_none());
+            }
+        }
+    }
+
+    fn record(&mut self, trace: &str, count: usize) -> bool {
+        match coverage::parse_marker(trace) {
+            Some((module, location)) => {
+                *self.0.entry((module.to_string(), location)).or_default() += count;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn get(&self, module: &str, location: Span) -> usize {
+        self.0
+            .get(&(module.to_string(), location))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Coverage of a module's functions, branches and lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleCoverage {
+    pub name: String,
+    /// Path of the module, relative to the project's root.
+    pub path: PathBuf,
+    pub source: String,
+    pub functions: Vec<FunctionCoverage>,
+    pub branches: Vec<BranchCoverage>,
+    /// Hits of each line holding code, by line number (starting at 1).
+    pub lines: BTreeMap<usize, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionCoverage {
+    pub name: String,
+    pub line: usize,
+    pub hits: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchCoverage {
+    /// Line of the `if` or `when` the branch belongs to.
+    pub line: usize,
+    /// Index of that `if` or `when` within the module.
+    pub block: usize,
+    pub index: usize,
+    pub hits: usize,
+}
+
+/// Number of items found, and how many of them were hit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Ratio {
+    pub hit: usize,
+    pub found: usize,
+}
+
+impl Ratio {
+    fn new<T>(items: impl IntoIterator<Item = T>, is_hit: impl Fn(&T) -> bool) -> Self {
+        items
+            .into_iter()
+            .fold(Ratio::default(), |ratio, item| Ratio {
+                hit: ratio.hit + usize::from(is_hit(&item)),
+                found: ratio.found + 1,
+            })
+    }
+
+    pub fn percent(&self) -> f64 {
+        if self.found == 0 {
+            100.0
+        } else {
+            self.hit as f64 * 100.0 / self.found as f64
+        }
+    }
+}
+
+impl std::ops::Add for Ratio {
+    type Output = Ratio;
+
+    fn add(self, other: Ratio) -> Ratio {
+        Ratio {
+            hit: self.hit + other.hit,
+            found: self.found + other.found,
+        }
+    }
+}
+
+impl ModuleCoverage {
+    /// Attribute hits to the functions, branches and lines of a module, given its regions.
+    ///
+    /// A line holds code when its first character (other than whitespaces) belongs to the extent
+    /// of a region, in which case it's hit as many times as the innermost such region. Comments
+    /// and lines starting with a closing delimiter (e.g. '} else {') don't count.
+    pub fn new(name: &str, path: PathBuf, source: &str, regions: &[Region], hits: &Hits) -> Self {
+        let offsets = line_offsets(source);
+
+        let line_of = |offset: usize| offsets.partition_point(|start| *start <= offset);
+
+        let mut functions = Vec::new();
+        let mut branches = Vec::new();
+        let mut blocks = Vec::new();
+
+        for region in regions {
+            let region_hits = hits.get(name, region.location);
+
+            match &region.kind {
+                RegionKind::Function(Some(function)) => functions.push(FunctionCoverage {
+                    name: function.clone(),
+                    line: line_of(region.location.start),
+                    hits: region_hits,
+                }),
+                RegionKind::Function(None) => {}
+                RegionKind::Branch { block, index } => {
+                    let block_index = match blocks.iter().position(|b| b == block) {
+                        Some(ix) => ix,
+                        None => {
+                            blocks.push(*block);
+                            blocks.len() - 1
+                        }
+                    };
+
+                    branches.push(BranchCoverage {
+                        line: line_of(block.start),
+                        block: block_index,
+                        index: *index,
+                        hits: region_hits,
+                    });
+                }
+            }
+        }
+
+        let mut lines = BTreeMap::new();
+
+        for (ix, line) in source.lines().enumerate() {
+            let trimmed = line.trim_start();
+
+            if trimmed.is_empty()
+                || trimmed.starts_with("//")
+                || trimmed.starts_with(['}', ')', ']'])
+            {
+                continue;
+            }
+
+            let offset = offsets[ix] + (line.len() - trimmed.len());
+
+            let innermost = regions
+                .iter()
+                .filter(|region| region.extent.start <= offset && offset < region.extent.end)
+                .min_by_key(|region| region.extent.end - region.extent.start);
+
+            if let Some(region) = innermost {
+                lines.insert(ix + 1, hits.get(name, region.location));
+            }
+        }
+
+        ModuleCoverage {
+            name: name.to_string(),
+            path,
+            source: source.to_string(),
+            functions,
+            branches,
+            lines,
+        }
+    }
+
+    pub fn lines_ratio(&self) -> Ratio {
+        Ratio::new(self.lines.values(), |hits| **hits > 0)
+    }
+
+    pub fn branches_ratio(&self) -> Ratio {
+        Ratio::new(self.branches.iter(), |branch| branch.hits > 0)
+    }
+
+    pub fn functions_ratio(&self) -> Ratio {
+        Ratio::new(self.functions.iter(), |function| function.hits > 0)
+    }
+}
+
+fn line_offsets(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Coverage of all modules of a project.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub modules: Vec<ModuleCoverage>,
+}
+
+impl Report {
+    pub fn lines_ratio(&self) -> Ratio {
+        self.modules
+            .iter()
+            .fold(Ratio::default(), |acc, m| acc + m.lines_ratio())
+    }
+
+    pub fn branches_ratio(&self) -> Ratio {
+        self.modules
+            .iter()
+            .fold(Ratio::default(), |acc, m| acc + m.branches_ratio())
+    }
+
+    pub fn functions_ratio(&self) -> Ratio {
+        self.modules
+            .iter()
+            .fold(Ratio::default(), |acc, m| acc + m.functions_ratio())
+    }
+
+    /// The report in the 'lcov' tracefile format, as understood by genhtml and most coverage
+    /// services (Codecov, Coveralls, ...).
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+
+        for module in self.modules.iter() {
+            let _ = writeln!(lcov, "TN:");
+            let _ = writeln!(lcov, "SF:{}", to_slash(&module.path));
+
+            for function in module.functions.iter() {
+                let _ = writeln!(lcov, "FN:{},{}", function.line, function.name);
+            }
+            for function in module.functions.iter() {
+                let _ = writeln!(lcov, "FNDA:{},{}", function.hits, function.name);
+            }
+            let functions = module.functions_ratio();
+            let _ = writeln!(lcov, "FNF:{}", functions.found);
+            let _ = writeln!(lcov, "FNH:{}", functions.hit);
+
+            for branch in module.branches.iter() {
+                // Branches of blocks which were never reached are marked with '-'.
+                let reached = module
+                    .branches
+                    .iter()
+                    .any(|other| other.block == branch.block && other.hits > 0);
+
+                let _ = writeln!(
+                    lcov,
+                    "BRDA:{},{},{},{}",
+                    branch.line,
+                    branch.block,
+                    branch.index,
+                    if reached {
+                        branch.hits.to_string()
+                    } else {
+                        "-".to_string()
+                    }
+                );
+            }
+            let branches = module.branches_ratio();
+            let _ = writeln!(lcov, "BRF:{}", branches.found);
+            let _ = writeln!(lcov, "BRH:{}", branches.hit);
+
+            for (line, hits) in module.lines.iter() {
+                let _ = writeln!(lcov, "DA:{line},{hits}");
+            }
+            let lines = module.lines_ratio();
+            let _ = writeln!(lcov, "LF:{}", lines.found);
+            let _ = writeln!(lcov, "LH:{}", lines.hit);
+
+            let _ = writeln!(lcov, "end_of_record");
+        }
+
+        lcov
+    }
+
+    /// The report as a standalone HTML page: a summary per module, followed by the source of
+    /// each module with lines highlighted depending on whether they were hit.
+    pub fn to_html(&self, title: &str) -> String {
+        let mut html = String::new();
+
+        let _ = writeln!(html, "<!DOCTYPE html>");
+        let _ = writeln!(html, "<html lang=\"en\">");
+        let _ = writeln!(html, "<head>");
+        let _ = writeln!(html, "<meta charset=\"utf-8\">");
+        let _ = writeln!(html, "<title>Coverage · {}</title>", escape(title));
+        let _ = writeln!(html, "<style>{HTML_STYLE}</style>");
+        let _ = writeln!(html, "</head>");
+        let _ = writeln!(html, "<body>");
+        let _ = writeln!(html, "<h1>Coverage · {}</h1>", escape(title));
+
+        let _ = writeln!(html, "<table class=\"summary\">");
+        let _ = writeln!(
+            html,
+            "<tr><th>Module</th><th>Lines</th><th>Branches</th><th>Functions</th></tr>"
+        );
+        for module in self.modules.iter() {
+            let _ = writeln!(
+                html,
+                "<tr><td><a href=\"#{name}\">{name}</a></td>{}{}{}</tr>",
+                fmt_ratio_html(module.lines_ratio()),
+                fmt_ratio_html(module.branches_ratio()),
+                fmt_ratio_html(module.functions_ratio()),
+                name = escape(&module.name),
+            );
+        }
+        let _ = writeln!(
+            html,
+            "<tr class=\"total\"><td>Total</td>{}{}{}</tr>",
+            fmt_ratio_html(self.lines_ratio()),
+            fmt_ratio_html(self.branches_ratio()),
+            fmt_ratio_html(self.functions_ratio()),
+        );
+        let _ = writeln!(html, "</table>");
+
+        for module in self.modules.iter() {
+            let _ = writeln!(
+                html,
+                "<h2 id=\"{}\">{}</h2>",
+                escape(&module.name),
+                escape(&to_slash(&module.path))
+            );
+            let _ = writeln!(html, "<pre class=\"source\">");
+            for (ix, line) in module.source.lines().enumerate() {
+                let (class, hits) = match module.lines.get(&(ix + 1)) {
+                    Some(0) => ("miss", "0".to_string()),
+                    Some(hits) => ("hit", hits.to_string()),
+                    None => ("none", String::new()),
+                };
+                let _ = writeln!(
+                    html,
+                    "<span class=\"{class}\"><span class=\"line\">{:>5}</span><span class=\"hits\">{hits:>6}</span> {}</span>",
+                    ix + 1,
+                    escape(line),
+                );
+            }
+            let _ = writeln!(html, "</pre>");
+        }
+
+        let _ = writeln!(html, "</body>");
+        let _ = writeln!(html, "</html>");
+
+        html
+    }
+}
+
+const HTML_STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table.summary{border-collapse:collapse}\
+table.summary td,table.summary th{border:1px solid #ccc;padding:.3em .8em;text-align:right}\
+table.summary td:first-child{text-align:left}\
+tr.total{font-weight:bold}\
+pre.source{line-height:1.3}\
+pre.source>span{display:block}\
+.line,.hits{color:#888;padding-right:1em}\
+.hit{background:#e6ffed}\
+.miss{background:#ffeef0}";
+
+fn fmt_ratio_html(ratio: Ratio) -> String {
+    format!(
+        "<td>{:.1}% ({}/{})</td>",
+        ratio.percent(),
+        ratio.hit,
+        ratio.found
+    )
+}
+
+fn to_slash(path: &std::path::Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "fn foo(n) {\n  if n > 0 {\n    1\n  } else {\n    2\n  }\n}\n";
+
+    fn regions() -> Vec<Region> {
+        let body = Span { start: 14, end: 51 };
+        vec![
+            Region {
+                location: body,
+                extent: body,
+                kind: RegionKind::Function(Some("foo".to_string())),
+            },
+            Region {
+                location: Span { start: 29, end: 30 },
+                extent: Span { start: 29, end: 30 },
+                kind: RegionKind::Branch {
+                    block: body,
+                    index: 0,
+                },
+            },
+            Region {
+                location: Span { start: 46, end: 47 },
+                extent: Span { start: 46, end: 47 },
+                kind: RegionKind::Branch {
+                    block: body,
+                    index: 1,
+                },
+            },
+        ]
+    }
+
+    fn hits() -> Hits {
+        let mut hits = Hits::default();
+        hits.record(&coverage::marker("foo", Span { start: 14, end: 51 }), 1);
+        hits.record(
+            &coverage::marker("foo", Span { start: 29, end: 30 })[1..],
+            3,
+        );
+        hits
+    }
+
+    #[test]
+    fn module_coverage() {
+        let module = ModuleCoverage::new(
+            "foo",
+            PathBuf::from("lib/foo.ak"),
+            SOURCE,
+            &regions(),
+            &hits(),
+        );
+
+        assert_eq!(module.lines, BTreeMap::from([(2, 1), (3, 3), (5, 0)]));
+        assert_eq!(module.lines_ratio(), Ratio { hit: 2, found: 3 });
+        assert_eq!(module.branches_ratio(), Ratio { hit: 1, found: 2 });
+        assert_eq!(module.functions_ratio(), Ratio { hit: 1, found: 1 });
+    }
+
+    #[test]
+    fn lcov_tracefile() {
+        let report = Report {
+            modules: vec![ModuleCoverage::new(
+                "foo",
+                PathBuf::from("lib/foo.ak"),
+                SOURCE,
+                &regions(),
+                &hits(),
+            )],
+        };
+
+        assert_eq!(
+            report.to_lcov(),
+            [
+                "TN:",
+                "SF:lib/foo.ak",
+                "FN:2,foo",
+                "FNDA:1,foo",
+                "FNF:1",
+                "FNH:1",
+                "BRDA:2,0,0,3",
+                "BRDA:2,0,1,0",
+                "BRF:2",
+                "BRH:1",
+                "DA:2,1",
+                "DA:3,3",
+                "DA:5,0",
+                "LF:3",
+                "LH:2",
+                "end_of_record",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
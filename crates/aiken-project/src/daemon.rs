@@ -0,0 +1,308 @@
+//! A long-running process holding a project's parsed and type-checked modules in memory, so that
+//! builds, checks and test runs requested from it only re-check the modules affected by changes
+//! since the last request, instead of starting from scratch. 'aiken build', 'aiken check' and the
+//! language server don't go through it: requests come from 'aiken daemon' or other clients.
+//!
+//! The daemon listens on a local TCP socket, whose address is recorded in 'build/daemon.json'
+//! for clients to find it, along with a token generated when the daemon starts. Only the owner of
+//! the project can read that file, and requests without the token are turned down, so that other
+//! users of the machine can't drive the daemon. Requests and responses are JSON documents, one per
+//! line, with requests carrying the token in a `token` field:
+//!
+//! - `{ "command": "build", "env"? }`: build validators, and write the blueprint;
+//! - `{ "command": "check", "env"? }`: type-check the project, without running tests;
+//! - `{ "command": "test", "seed", "match_tests"?, "exact_match"?, "env"? }`: run tests;
+//! - `{ "command": "stop" }`: stop the daemon.
+//!
+//! Responses are the documents printed by 'aiken --output json', along with an `ok` field
+//! telling whether the command succeeded.
+
+use crate::{
+    cache::MemoryCache,
+    error::Error,
+    options::Emit,
+    paths,
+    telemetry::{Event, EventListener, EventTarget, Json},
+    watch::json_document,
+    Project,
+};
+use aiken_lang::{ast::Tracing, test_framework::PropertyTest};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+};
+
+/// How to reach the daemon running for a project, as recorded in 'build/daemon.json'.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lock {
+    pub address: SocketAddr,
+    pub token: String,
+    pub pid: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    Build {
+        #[serde(default)]
+        env: Option<String>,
+    },
+    Check {
+        #[serde(default)]
+        env: Option<String>,
+    },
+    Test {
+        seed: u32,
+        #[serde(default)]
+        match_tests: Option<Vec<String>>,
+        #[serde(default)]
+        exact_match: bool,
+        #[serde(default)]
+        env: Option<String>,
+    },
+    Stop,
+}
+
+/// Serve requests for the project at `root` on the given address, until asked to stop.
+pub fn run(root: PathBuf, address: SocketAddr) -> Result<(), Error> {
+    let listener = TcpListener::bind(address)?;
+
+    let address = listener.local_addr()?;
+
+    let token = hex::encode(rand::thread_rng().gen::<[u8; 32]>());
+
+    let lock = root.join(paths::daemon());
+
+    fs::create_dir_all(root.join(paths::build()))?;
+
+    write_lock(
+        &lock,
+        &Lock {
+            address,
+            token: token.clone(),
+            pid: std::process::id(),
+        },
+    )
+    .map_err(|error| Error::FileIo {
+        error,
+        path: lock.clone(),
+    })?;
+
+    EventTarget::default().handle_event(Event::Listening { address });
+
+    // Diagnostics end up in responses, where terminal colors have no business.
+    owo_colors::set_override(false);
+
+    let mut memory = MemoryCache::default();
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(..) => continue,
+        };
+
+        // A client going away mid-request shouldn't bring the daemon down.
+        if let Ok(false) = handle(&root, &token, stream, &mut memory) {
+            break;
+        }
+    }
+
+    let _ = fs::remove_file(lock);
+
+    Ok(())
+}
+
+/// Write the lock file, readable by the owner only since it holds the token.
+fn write_lock(path: &Path, lock: &Lock) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+
+    // The mode only applies to new files; a lock left behind by a crashed daemon keeps its own.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+
+    writeln!(file, "{}", serde_json::to_string(lock)?)
+}
+
+/// Handle a single request; returns whether the daemon should keep running.
+fn handle(
+    root: &Path,
+    token: &str,
+    stream: TcpStream,
+    memory: &mut MemoryCache,
+) -> std::io::Result<bool> {
+    let mut line = String::new();
+
+    BufReader::new(&stream).read_line(&mut line)?;
+
+    let (response, keep_running) = match authenticate(token, &line) {
+        Ok(Request::Stop) => (json!({ "ok": true }), false),
+        Ok(request) => (execute(root, request, memory), true),
+        Err(error) => (json!({ "ok": false, "error": error }), true),
+    };
+
+    writeln!(&stream, "{response}")?;
+
+    Ok(keep_running)
+}
+
+/// Parse a request, provided it carries the daemon's token.
+fn authenticate(token: &str, line: &str) -> Result<Request, String> {
+    let request: serde_json::Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+
+    let given = request["token"].as_str().unwrap_or_default();
+
+    // Compare in constant time, so that response times don't leak how much of a guess is right.
+    let matches = given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0;
+
+    if !matches {
+        return Err("missing or invalid token; find it in 'build/daemon.json'".to_string());
+    }
+
+    serde_json::from_value(request).map_err(|e| e.to_string())
+}
+
+fn execute(root: &Path, request: Request, memory: &mut MemoryCache) -> serde_json::Value {
+    let mut project = match Project::new(root.to_path_buf(), EventTarget::Json(Json::default())) {
+        Ok(project) => project,
+        Err(e) => {
+            let mut report = json_document(None, &[], &Err(vec![e]));
+            report["ok"] = json!(false);
+            return report;
+        }
+    };
+
+    project.with_memory_cache(std::mem::take(memory));
+
+    let result = match request {
+        Request::Build { env } => {
+            let blueprint_path = project.blueprint_path(None);
+            project.build(Emit::default(), Tracing::silent(), blueprint_path, env)
+        }
+        Request::Check { env } => project.check(
+            true,
+            None,
+            vec![],
+            false,
+            false,
+            0,
+            PropertyTest::DEFAULT_MAX_SUCCESS,
+            false,
+            None,
+            Tracing::verbose(),
+            env,
+        ),
+        Request::Test {
+            seed,
+            match_tests,
+            exact_match,
+            env,
+        } => project.check(
+            false,
+            match_tests,
+            vec![],
+            false,
+            exact_match,
+            seed,
+            PropertyTest::DEFAULT_MAX_SUCCESS,
+            false,
+            None,
+            Tracing::verbose(),
+            env,
+        ),
+        Request::Stop => unreachable!("stop requests are handled by the caller"),
+    };
+
+    *memory = project.take_memory_cache();
+
+    let warnings = project.warnings();
+
+    let mut report = json_document(project.event_listener.take_report(), &warnings, &result);
+
+    report["ok"] = json!(result.is_ok());
+
+    report
+}
+
+/// How to reach the daemon running for the project at `root`, if any.
+pub fn lock(root: &Path) -> Option<Lock> {
+    let lock = fs::read_to_string(root.join(paths::daemon())).ok()?;
+
+    serde_json::from_str(&lock).ok()
+}
+
+/// Send a request to the daemon recorded in `lock`, and wait for its response.
+pub fn request(lock: &Lock, request: &serde_json::Value) -> Result<serde_json::Value, Error> {
+    let mut request = request.clone();
+
+    request["token"] = json!(lock.token);
+
+    let stream = TcpStream::connect(lock.address)?;
+
+    writeln!(&stream, "{request}")?;
+
+    let mut line = String::new();
+
+    BufReader::new(&stream).read_line(&mut line)?;
+
+    Ok(serde_json::from_str(&line)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_requests() {
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{ "command": "build" }"#).unwrap(),
+            Request::Build { env: None }
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Request>(
+                r#"{ "command": "test", "seed": 42, "match_tests": ["foo"] }"#
+            )
+            .unwrap(),
+            Request::Test {
+                seed: 42,
+                match_tests: Some(vec!["foo".to_string()]),
+                exact_match: false,
+                env: None,
+            }
+        );
+
+        assert!(serde_json::from_str::<Request>(r#"{ "command": "deploy" }"#).is_err());
+    }
+
+    #[test]
+    fn authenticate_requests() {
+        assert_eq!(
+            authenticate("c0ffee", r#"{ "command": "stop", "token": "c0ffee" }"#),
+            Ok(Request::Stop)
+        );
+
+        assert!(authenticate("c0ffee", r#"{ "command": "stop" }"#).is_err());
+        assert!(authenticate("c0ffee", r#"{ "command": "stop", "token": "c0ffe" }"#).is_err());
+        assert!(authenticate("c0ffee", r#"{ "command": "stop", "token": "decade" }"#).is_err());
+    }
+}
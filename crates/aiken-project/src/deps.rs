@@ -3,23 +3,28 @@ use std::{collections::HashSet, fs, path::Path};
 use aiken_lang::ast::Span;
 use miette::NamedSource;
 use serde::{Deserialize, Serialize};
-use tokio::time::Instant;
 
+use crate::{config::Dependency, error::Error, package_name::PackageName, paths};
+
+use self::manifest::{Manifest, Package};
+
+#[cfg(feature = "native")]
+use self::downloader::Downloader;
+#[cfg(feature = "native")]
 use crate::{
-    config::{Config, Dependency},
-    error::Error,
-    package_name::PackageName,
-    paths,
+    config::Config,
     telemetry::{DownloadSource, Event, EventListener},
 };
+#[cfg(feature = "native")]
+use std::time::Instant;
 
-use self::{
-    downloader::Downloader,
-    manifest::{Manifest, Package},
-};
-
+pub mod advisories;
+pub mod artifacts;
+#[cfg(feature = "native")]
 pub mod downloader;
 pub mod manifest;
+#[cfg(feature = "native")]
+pub mod releases;
 
 pub enum UseManifest {
     Yes,
@@ -73,6 +78,7 @@ impl LocalPackages {
         Ok(())
     }
 
+    #[cfg(feature = "native")]
     fn remove_extra_packages(&self, manifest: &Manifest, root_path: &Path) -> Result<(), Error> {
         for (package, _version) in self.extra_local_packages(manifest) {
             let path = root_path.join(paths::build_deps_package(&package));
@@ -88,12 +94,12 @@ impl LocalPackages {
         let manifest_packages: HashSet<_> = manifest
             .packages
             .iter()
-            .map(|p| (&p.name, &p.version))
+            .map(|p| (&p.name, p.revision()))
             .collect();
 
         self.packages
             .iter()
-            .filter(|dep| !manifest_packages.contains(&(&dep.name, &dep.version)))
+            .filter(|dep| !manifest_packages.contains(&(&dep.name, dep.version.as_str())))
             .map(|dep| (dep.name.clone(), dep.version.clone()))
             .collect()
     }
@@ -107,9 +113,10 @@ impl LocalPackages {
             .iter()
             .filter(|p| {
                 &p.name != root
+                    && p.path.is_none()
                     && !matches!(
                         self.packages.iter().find(|p2| p2.name == p.name),
-                        Some(Dependency { version, .. }) if paths::is_git_sha_or_tag(version) && &p.version == version,
+                        Some(Dependency { version, subdir, .. }) if paths::is_git_sha_or_tag(version) && p.revision() == version && &p.subdir == subdir,
                     )
             })
             .collect()
@@ -122,16 +129,19 @@ impl From<&Manifest> for LocalPackages {
             packages: value
                 .packages
                 .iter()
+                .filter(|p| p.path.is_none())
                 .map(|p| Dependency {
                     name: p.name.clone(),
-                    version: p.version.clone(),
+                    version: p.revision().to_string(),
                     source: p.source,
+                    subdir: p.subdir.clone(),
                 })
                 .collect(),
         }
     }
 }
 
+#[cfg(feature = "native")]
 pub fn download<T>(event_listener: &T, root_path: &Path, config: &Config) -> Result<Manifest, Error>
 where
     T: EventListener,
@@ -169,6 +179,7 @@ where
         &local,
         project_name,
         root_path,
+        paths::registries(&config.mirrors),
         event_listener,
     ))?;
 
@@ -181,11 +192,73 @@ where
     Ok(manifest)
 }
 
+/// A package whose locked revision changed, from its former revision (if it was locked) to its
+/// new one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocked {
+    pub name: PackageName,
+    pub from: Option<String>,
+    pub to: String,
+}
+
+/// Resolve dependencies anew and save them as the lock file, whether the configuration changed or
+/// not; so that dependencies on branches get pinned to their latest commit. When given some
+/// packages, others keep their locked revision unless their version changed.
+#[cfg(feature = "native")]
+pub fn relock<T>(
+    event_listener: &T,
+    root_path: &Path,
+    config: &Config,
+    only: &[PackageName],
+) -> Result<Vec<Relocked>, Error>
+where
+    T: EventListener,
+{
+    let previous = fs::read_to_string(root_path.join(paths::manifest()))
+        .ok()
+        .and_then(|toml| toml::from_str::<Manifest>(&toml).ok())
+        .map(|manifest| manifest.packages)
+        .unwrap_or_default();
+
+    let mut manifest = Manifest::resolve(event_listener, config, root_path)?;
+
+    let mut changes = vec![];
+
+    for package in manifest.packages.iter_mut() {
+        let locked = previous.iter().find(|p| p.name == package.name);
+
+        if let Some(locked) = locked {
+            if !only.is_empty()
+                && !only.contains(&package.name)
+                && locked.version == package.version
+            {
+                *package = locked.clone();
+            }
+
+            if locked.revision() == package.revision() {
+                continue;
+            }
+        }
+
+        changes.push(Relocked {
+            name: package.name.clone(),
+            from: locked.map(|locked| locked.revision().to_string()),
+            to: package.revision().to_string(),
+        });
+    }
+
+    manifest.save(root_path)?;
+
+    Ok(changes)
+}
+
+#[cfg(feature = "native")]
 async fn fetch_missing_packages<T>(
     manifest: &mut Manifest,
     local: &LocalPackages,
     project_name: PackageName,
     root_path: &Path,
+    registries: Vec<String>,
     event_listener: &T,
 ) -> Result<(), Error>
 where
@@ -205,7 +278,7 @@ where
             name: format!("{project_name}"),
         });
 
-        let downloader = Downloader::new(root_path);
+        let downloader = Downloader::new(root_path, registries);
 
         let statuses = downloader
             .download_packages(event_listener, missing, &project_name, manifest)
@@ -0,0 +1,238 @@
+use std::{
+    env, fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+#[cfg(feature = "native")]
+use reqwest::{blocking::Client, header::USER_AGENT};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Warning, package_name::PackageName, paths};
+
+use super::manifest::{Manifest, Package};
+
+/// Default location of the advisory database. It can be overridden using the
+/// `AIKEN_ADVISORY_DB` environment variable, pointing either to another URL or to a local file.
+pub const DEFAULT_ADVISORY_DB: &str =
+    "https://raw.githubusercontent.com/aiken-lang/advisory-db/main/advisories.toml";
+
+pub const ADVISORY_DB_ENV: &str = "AIKEN_ADVISORY_DB";
+
+/// How long a cached copy of the advisory database is considered fresh. Past that delay, we try
+/// to fetch it again, but still fall back to the cached copy when the network is unavailable.
+/// Failures are remembered for just as long.
+const MAX_AGE: Duration = Duration::from_secs(3600);
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct AdvisoryDb {
+    #[serde(default, rename = "advisory")]
+    pub advisories: Vec<Advisory>,
+    #[serde(default)]
+    pub yanked: Vec<Yanked>,
+}
+
+/// A known issue affecting one or more revisions of a package.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Advisory {
+    pub id: String,
+    pub package: PackageName,
+    pub versions: Vec<String>,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub url: Option<String>,
+}
+
+/// A package revision that was withdrawn by its authors, and shouldn't be depended upon anymore.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Yanked {
+    pub package: PackageName,
+    pub version: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl AdvisoryDb {
+    /// Load the advisory database, preferring a fresh copy from the cache. When stale (or when
+    /// `refresh` is set), the database is fetched again. Any failure to do so falls back to
+    /// whatever is in the cache, possibly nothing, which is then considered fresh again.
+    pub fn load(refresh: bool) -> Self {
+        let source = env::var(ADVISORY_DB_ENV).unwrap_or_else(|_| DEFAULT_ADVISORY_DB.to_string());
+
+        if !source.starts_with("http://") && !source.starts_with("https://") {
+            return Self::from_file(Path::new(&source)).unwrap_or_default();
+        }
+
+        let cache = paths::advisories_cache();
+
+        let is_fresh = fs::metadata(&cache)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map(|age| age < MAX_AGE)
+            .unwrap_or(false);
+
+        if !refresh && is_fresh {
+            return Self::from_file(&cache).unwrap_or_default();
+        }
+
+        // A failed fetch also renews the cached copy, so that builds don't keep waiting on an
+        // unreachable network; it's only tried again once MAX_AGE has passed.
+        let db =
+            Self::fetch(&source).unwrap_or_else(|| Self::from_file(&cache).unwrap_or_default());
+
+        if let Some(parent) = cache.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(&cache, toml::to_string(&db).unwrap_or_default());
+
+        db
+    }
+
+    #[cfg(feature = "native")]
+    fn fetch(url: &str) -> Option<Self> {
+        let response = Client::new()
+            .get(url)
+            .header(USER_AGENT, "aiken")
+            .timeout(Duration::from_secs(5))
+            .send()
+            .ok()?;
+
+        if !response.status().is_success() {
+            return None;
+        }
+
+        toml::from_str(&response.text().ok()?).ok()
+    }
+
+    /// Without network access, only the cached copy (if any) is available.
+    #[cfg(not(feature = "native"))]
+    fn fetch(_url: &str) -> Option<Self> {
+        None
+    }
+
+    fn from_file(path: &Path) -> Option<Self> {
+        toml::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+
+    /// Look for locked packages that are either affected by an advisory, or have been yanked.
+    pub fn audit(&self, manifest: &Manifest) -> Vec<Warning> {
+        manifest
+            .packages
+            .iter()
+            .filter(|package| package.path.is_none())
+            .flat_map(|package| self.audit_package(package))
+            .collect()
+    }
+
+    fn audit_package(&self, package: &Package) -> Vec<Warning> {
+        let advisories = self
+            .advisories
+            .iter()
+            .filter(|advisory| {
+                advisory.package == package.name && advisory.versions.contains(&package.version)
+            })
+            .map(|advisory| Warning::VulnerableDependency {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                advisory: advisory.clone(),
+            });
+
+        let yanked = self
+            .yanked
+            .iter()
+            .filter(|yanked| yanked.package == package.name && yanked.version == package.version)
+            .map(|yanked| Warning::YankedDependency {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                reason: yanked.reason.clone(),
+            });
+
+        advisories.chain(yanked).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Platform;
+    use std::collections::BTreeMap;
+
+    fn package(name: &str, version: &str) -> Package {
+        Package {
+            name: name.parse().unwrap(),
+            version: version.to_string(),
+            requirements: vec![],
+            source: Platform::Github,
+            path: None,
+            subdir: None,
+            revision: None,
+        }
+    }
+
+    #[test]
+    fn audit_flags_vulnerable_and_yanked_packages() {
+        let db: AdvisoryDb = toml::from_str(
+            r#"
+            [[advisory]]
+            id = "AIKEN-2024-0001"
+            package = "aiken-lang/stdlib"
+            versions = ["v2.0.0"]
+            title = "Unsound comparison of values"
+
+            [[yanked]]
+            package = "aiken-lang/fuzz"
+            version = "v1.0.0"
+            reason = "Published by mistake"
+            "#,
+        )
+        .unwrap();
+
+        let manifest = Manifest {
+            requirements: vec![],
+            packages: vec![
+                package("aiken-lang/stdlib", "v2.0.0"),
+                package("aiken-lang/fuzz", "v1.0.0"),
+                package("aiken-lang/merkle-patricia-forestry", "v1.0.0"),
+            ],
+            patches: BTreeMap::new(),
+            etags: BTreeMap::new(),
+        };
+
+        let warnings = db.audit(&manifest);
+
+        assert_eq!(warnings.len(), 2);
+        assert!(matches!(
+            &warnings[0],
+            Warning::VulnerableDependency { advisory, .. } if advisory.id == "AIKEN-2024-0001"
+        ));
+        assert!(matches!(
+            &warnings[1],
+            Warning::YankedDependency { reason, .. } if reason == "Published by mistake"
+        ));
+    }
+
+    #[test]
+    fn audit_ignores_other_revisions() {
+        let db: AdvisoryDb = toml::from_str(
+            r#"
+            [[advisory]]
+            id = "AIKEN-2024-0001"
+            package = "aiken-lang/stdlib"
+            versions = ["v2.0.0"]
+            title = "Unsound comparison of values"
+            "#,
+        )
+        .unwrap();
+
+        let manifest = Manifest {
+            requirements: vec![],
+            packages: vec![package("aiken-lang/stdlib", "v2.1.0")],
+            patches: BTreeMap::new(),
+            etags: BTreeMap::new(),
+        };
+
+        assert!(db.audit(&manifest).is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+use std::{fs, path::PathBuf};
+
+use aiken_lang::ast::TraceLevel;
+
+use crate::{
+    cache, config,
+    module::{CheckedModule, ParsedModule},
+    paths,
+};
+
+use super::manifest::Package;
+
+/// A system-wide cache of type-checked dependency modules, so that separate projects (or fresh
+/// clones of the same project) don't have to type-check the same dependencies over and over.
+///
+/// Entries are keyed by package name, package revision, compiler version and trace level. Only
+/// packages pinned to an immutable revision (i.e. a git tag or commit) are ever cached. Besides,
+/// a cached module is only ever re-used when its fingerprint is unchanged; which covers its source
+/// code and the interfaces of the modules it imports, since the package's own dependencies may be
+/// resolved to different revisions from one project to another.
+#[derive(Debug, Clone)]
+pub struct ArtifactsCache {
+    dir: PathBuf,
+}
+
+impl ArtifactsCache {
+    pub fn new(package: &Package, trace_level: TraceLevel) -> Option<Self> {
+        if package.path.is_some() || !paths::is_git_sha_or_tag(&package.version) {
+            return None;
+        }
+
+        let dir = paths::artifacts_cache().join(format!(
+            "{}-{}-{}-{}-{}",
+            package.name.owner,
+            package.name.repo,
+            package.version,
+            config::compiler_version(true).replace([' ', '+'], "_"),
+            trace_level,
+        ));
+
+        Some(Self { dir })
+    }
+
+    /// Compute the fingerprint of a module, given the interfaces of the modules it imports.
+    pub fn fingerprint<'a>(
+        module: &ParsedModule,
+        dependencies: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> String {
+        cache::fingerprint("", module, dependencies)
+    }
+
+    pub fn load(&self, module: &ParsedModule, fingerprint: &str) -> Option<CheckedModule> {
+        let bytes = fs::read(self.module_path(&module.name)).ok()?;
+
+        let (cached_fingerprint, checked_module): (String, CheckedModule) =
+            ciborium::from_reader(&bytes[..]).ok()?;
+
+        if cached_fingerprint != fingerprint {
+            return None;
+        }
+
+        Some(CheckedModule {
+            input_path: module.path.clone(),
+            ..checked_module
+        })
+    }
+
+    /// Store a checked module in the cache. This is best-effort: failing to write to the cache
+    /// only means that the module will need to be type-checked again next time.
+    pub fn store(&self, module: &CheckedModule, fingerprint: &str) {
+        let mut bytes = vec![];
+
+        if fs::create_dir_all(&self.dir).is_ok()
+            && ciborium::into_writer(&(fingerprint, module), &mut bytes).is_ok()
+        {
+            let _ = fs::write(self.module_path(&module.name), bytes);
+        }
+    }
+
+    fn module_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.cbor", name.replace('/', ".")))
+    }
+}
@@ -20,13 +20,15 @@ use super::manifest::Package;
 pub struct Downloader<'a> {
     http: Client,
     root_path: &'a Path,
+    registries: Vec<String>,
 }
 
 impl<'a> Downloader<'a> {
-    pub fn new(root_path: &'a Path) -> Self {
+    pub fn new(root_path: &'a Path, registries: Vec<String>) -> Self {
         Self {
             http: Client::new(),
             root_path,
+            registries,
         }
     }
 
@@ -44,8 +46,14 @@ impl<'a> Downloader<'a> {
         let mut tasks = vec![];
 
         for package in packages.filter(|package| project_name != &package.name) {
-            let cache_key =
-                paths::CacheKey::new(&self.http, event_listener, package, manifest).await?;
+            let cache_key = paths::CacheKey::new(
+                &self.http,
+                &self.registries,
+                event_listener,
+                package,
+                manifest,
+            )
+            .await?;
             let task = self.ensure_package_in_build_directory(package, cache_key);
             tasks.push(task);
         }
@@ -62,8 +70,7 @@ impl<'a> Downloader<'a> {
             .ensure_package_downloaded(package, &cache_key)
             .await
             .map(|downloaded| (package.name.clone(), downloaded))?;
-        self.extract_package_from_cache(&package.name, &cache_key)
-            .await?;
+        self.extract_package_from_cache(package, &cache_key).await?;
         Ok(downloaded)
     }
 
@@ -84,37 +91,51 @@ impl<'a> Downloader<'a> {
             return Ok(false);
         }
 
-        let url = format!(
-            "https://api.github.com/repos/{}/{}/zipball/{}",
-            package.name.owner, package.name.repo, package.version
-        );
+        let mut error = Error::UnknownPackageVersion {
+            package: package.clone().into(),
+        };
 
-        let response = self
-            .http
-            .get(url)
-            .header("User-Agent", "aiken-lang")
-            .send()
-            .await?;
+        // Try each registry in order, falling back to the next one on failure.
+        for registry in &self.registries {
+            let response = match self
+                .http
+                .get(paths::package_zipball_url(registry, package))
+                .header("User-Agent", "aiken-lang")
+                .send()
+                .await
+            {
+                Ok(response) => response,
+                Err(e) => {
+                    error = e.into();
+                    continue;
+                }
+            };
 
-        if response.status().as_u16() >= 400 {
-            return Err(Error::UnknownPackageVersion {
-                package: package.clone(),
-            });
-        }
+            if response.status().as_u16() >= 400 {
+                error = Error::UnknownPackageVersion {
+                    package: package.clone().into(),
+                };
+                continue;
+            }
 
-        let bytes = response.bytes().await?;
+            let bytes = response.bytes().await?;
 
-        tokio::fs::write(&zipball_path, bytes).await?;
+            tokio::fs::write(&zipball_path, bytes).await?;
 
-        Ok(true)
+            return Ok(true);
+        }
+
+        Err(error)
     }
 
     pub async fn extract_package_from_cache(
         &self,
-        name: &PackageName,
+        package: &Package,
         cache_key: &CacheKey,
     ) -> Result<(), Error> {
-        let destination = self.root_path.join(paths::build_deps_package(name));
+        let destination = self
+            .root_path
+            .join(paths::build_deps_package(&package.name));
 
         tokio::fs::create_dir_all(&destination).await?;
 
@@ -124,12 +145,13 @@ impl<'a> Downloader<'a> {
 
         let result = {
             let d = destination.clone();
+            let subdir = package.subdir.clone().unwrap_or_default();
 
             tokio::task::spawn_blocking(move || {
                 let mut archive =
                     zip::ZipArchive::new(Cursor::new(zipball)).expect("failed to load zip archive");
 
-                extract_zip(&mut archive, &d)
+                extract_zip(&mut archive, &d, &subdir)
             })
             .await?
         };
@@ -144,9 +166,13 @@ impl<'a> Downloader<'a> {
     }
 }
 
+/// Extract a repository archive into the given directory. Archives are expected to wrap the
+/// repository in a single top-level folder, which is stripped. When a sub-directory is given,
+/// only files under it are extracted, relative to it.
 fn extract_zip<R: Read + io::Seek, P: AsRef<Path>>(
     archive: &mut zip::ZipArchive<R>,
     directory: P,
+    subdir: &Path,
 ) -> Result<(), ZipError> {
     use std::fs;
 
@@ -160,6 +186,11 @@ fn extract_zip<R: Read + io::Seek, P: AsRef<Path>>(
             .skip(1)
             .collect::<PathBuf>();
 
+        let filepath = match filepath.strip_prefix(subdir) {
+            Ok(filepath) => filepath.to_path_buf(),
+            Err(_) => continue,
+        };
+
         let outpath = directory.as_ref().join(filepath);
 
         if file.name().ends_with('/') {
@@ -185,3 +216,45 @@ fn extract_zip<R: Read + io::Seek, P: AsRef<Path>>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn archive(files: &[&str]) -> zip::ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+        for file in files {
+            writer
+                .start_file(*file, zip::write::FileOptions::default())
+                .unwrap();
+            writer.write_all(file.as_bytes()).unwrap();
+        }
+
+        zip::ZipArchive::new(Cursor::new(writer.finish().unwrap().into_inner())).unwrap()
+    }
+
+    #[test]
+    fn extract_zip_subdir() {
+        let destination = std::env::temp_dir().join("aiken-extract-zip-subdir");
+        let _ = std::fs::remove_dir_all(&destination);
+
+        let mut archive = archive(&[
+            "repo-abc/README.md",
+            "repo-abc/onchain/aiken.toml",
+            "repo-abc/onchain/lib/foo.ak",
+            "repo-abc/offchain/index.ts",
+        ]);
+
+        extract_zip(&mut archive, &destination, Path::new("onchain")).unwrap();
+
+        assert!(destination.join("aiken.toml").is_file());
+        assert!(destination.join("lib/foo.ak").is_file());
+        assert!(!destination.join("README.md").exists());
+        assert!(!destination.join("index.ts").exists());
+        assert!(!destination.join("offchain").exists());
+
+        std::fs::remove_dir_all(&destination).unwrap();
+    }
+}
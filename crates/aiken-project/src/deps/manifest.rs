@@ -1,30 +1,42 @@
-use aiken_lang::ast::Span;
-use miette::NamedSource;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fs,
-    path::Path,
+    path::{Path, PathBuf},
     time::{Duration, SystemTime},
 };
 
 use crate::{
-    config::{Config, Dependency, Platform},
+    config::{Dependency, Patch, Platform},
     error::Error,
     package_name::PackageName,
     paths,
+};
+
+// Resolving versions may look up branches in the package registry.
+#[cfg(feature = "native")]
+use crate::{
+    config::Config,
+    github,
     telemetry::{Event, EventListener},
 };
+#[cfg(feature = "native")]
+use aiken_lang::ast::Span;
+#[cfg(feature = "native")]
+use miette::NamedSource;
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct Manifest {
     pub requirements: Vec<Dependency>,
     pub packages: Vec<Package>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub patches: BTreeMap<PackageName, Patch>,
     #[serde(default)]
     pub etags: BTreeMap<String, (SystemTime, String)>,
 }
 
 impl Manifest {
+    #[cfg(feature = "native")]
     pub fn load<T>(
         event_listener: &T,
         config: &Config,
@@ -40,7 +52,7 @@ impl Manifest {
         let should_resolve = !manifest_path.exists();
 
         if should_resolve {
-            let manifest = resolve_versions(config, event_listener)?;
+            let manifest = resolve_versions(config, root_path, event_listener)?;
             return Ok((manifest, true));
         }
 
@@ -60,14 +72,23 @@ impl Manifest {
 
         // If the config is unchanged since the manifest was written then it is up
         // to date so we can return it unmodified.
-        if manifest.requirements == config.dependencies {
+        if manifest.requirements == config.dependencies && manifest.patches == config.patch {
             Ok((manifest, false))
         } else {
-            let manifest = resolve_versions(config, event_listener)?;
+            let manifest = resolve_versions(config, root_path, event_listener)?;
             Ok((manifest, true))
         }
     }
 
+    /// Resolve the versions of dependencies anew, regardless of any existing manifest.
+    #[cfg(feature = "native")]
+    pub fn resolve<T>(event_listener: &T, config: &Config, root_path: &Path) -> Result<Self, Error>
+    where
+        T: EventListener,
+    {
+        resolve_versions(config, root_path, event_listener)
+    }
+
     pub fn save(&self, root_path: &Path) -> Result<(), Error> {
         let manifest_path = root_path.join(paths::manifest());
 
@@ -118,28 +139,213 @@ pub struct Package {
     pub version: String,
     pub requirements: Vec<String>,
     pub source: Platform,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdir: Option<PathBuf>,
+    /// The commit a branch was resolved to, when depending on a branch. This pins the package to
+    /// a specific revision until dependencies are resolved again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+}
+
+impl Package {
+    /// The git revision to fetch for this package; that is, the commit it has been pinned to, or
+    /// its declared version otherwise.
+    pub fn revision(&self) -> &str {
+        self.revision.as_deref().unwrap_or(&self.version)
+    }
 }
 
-fn resolve_versions<T>(config: &Config, event_listener: &T) -> Result<Manifest, Error>
+#[cfg(feature = "native")]
+fn resolve_versions<T>(
+    config: &Config,
+    root_path: &Path,
+    event_listener: &T,
+) -> Result<Manifest, Error>
 where
     T: EventListener,
 {
     event_listener.handle_event(Event::ResolvingVersions);
 
+    let registries = paths::registries(&config.mirrors);
+
     let manifest = Manifest {
         packages: config
             .dependencies
             .iter()
-            .map(|dep| Package {
-                name: dep.name.clone(),
-                version: dep.version.clone(),
-                requirements: vec![],
-                source: dep.source,
+            .map(|dep| {
+                resolve_package(dep, config.patch.get(&dep.name), root_path)
+                    .map(|package| pin_revision(package, &registries, event_listener))
             })
-            .collect(),
+            .collect::<Result<_, _>>()?,
         requirements: config.dependencies.clone(),
+        patches: config.patch.clone(),
         etags: BTreeMap::new(),
     };
 
     Ok(manifest)
 }
+
+#[cfg(feature = "native")]
+fn resolve_package(
+    dep: &Dependency,
+    patch: Option<&Patch>,
+    root_path: &Path,
+) -> Result<Package, Error> {
+    match patch {
+        None => Ok(Package {
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            requirements: vec![],
+            source: dep.source,
+            path: None,
+            subdir: dep.subdir.clone(),
+            revision: None,
+        }),
+        Some(Patch::Path { path }) => {
+            if !root_path.join(path).join(paths::project_config()).exists() {
+                return Err(Error::MissingManifest {
+                    path: root_path.join(path),
+                });
+            }
+
+            Ok(Package {
+                name: dep.name.clone(),
+                version: dep.version.clone(),
+                requirements: vec![],
+                source: dep.source,
+                path: Some(path.clone()),
+                subdir: None,
+                revision: None,
+            })
+        }
+        Some(Patch::Source {
+            name,
+            version,
+            source,
+        }) => Ok(Package {
+            name: name.clone().unwrap_or_else(|| dep.name.clone()),
+            version: version.clone(),
+            requirements: vec![],
+            source: *source,
+            path: None,
+            subdir: dep.subdir.clone(),
+            revision: None,
+        }),
+    }
+}
+
+/// Resolve packages that depend on a branch to the commit that branch currently points to, so
+/// that the lock file pins them. When we can't reach the package registry, the package is left
+/// unpinned and will be resolved again next time.
+#[cfg(feature = "native")]
+fn pin_revision<T>(package: Package, registries: &[String], event_listener: &T) -> Package
+where
+    T: EventListener,
+{
+    if package.path.is_some()
+        || package.source != Platform::Github
+        || paths::is_git_sha_or_tag(&package.version)
+    {
+        return package;
+    }
+
+    match github::repo::resolve_commit(registries, package.name.to_string(), &package.version) {
+        Ok(sha) => Package {
+            revision: Some(sha),
+            ..package
+        },
+        Err(_) => {
+            event_listener.handle_event(Event::PackageResolveFallback {
+                name: format!("{}", package.name),
+            });
+            package
+        }
+    }
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+
+    struct NoListener;
+
+    impl EventListener for NoListener {}
+
+    fn config(src: &str) -> Config {
+        toml::from_str(src).expect("invalid test config")
+    }
+
+    #[test]
+    fn resolve_without_patch() {
+        let config = config(
+            r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [[dependencies]]
+            name = "aiken-lang/stdlib"
+            version = "v2.1.0"
+            source = "github"
+            "#,
+        );
+
+        let manifest = resolve_versions(&config, Path::new("."), &NoListener).unwrap();
+
+        assert_eq!(manifest.packages.len(), 1);
+        assert_eq!(manifest.packages[0].name.to_string(), "aiken-lang/stdlib");
+        assert_eq!(manifest.packages[0].version, "v2.1.0");
+        assert!(manifest.packages[0].path.is_none());
+    }
+
+    #[test]
+    fn resolve_with_source_patch() {
+        let config = config(
+            r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [[dependencies]]
+            name = "aiken-lang/stdlib"
+            version = "v2.1.0"
+            source = "github"
+
+            [patch."aiken-lang/stdlib"]
+            name = "KtorZ/stdlib"
+            version = "v2.2.0-fix"
+            "#,
+        );
+
+        let manifest = resolve_versions(&config, Path::new("."), &NoListener).unwrap();
+
+        assert_eq!(manifest.packages.len(), 1);
+        assert_eq!(manifest.packages[0].name.to_string(), "KtorZ/stdlib");
+        assert_eq!(manifest.packages[0].version, "v2.2.0-fix");
+        assert_eq!(manifest.packages[0].source, Platform::Github);
+        assert_eq!(manifest.patches, config.patch);
+    }
+
+    #[test]
+    fn resolve_with_missing_path_patch() {
+        let config = config(
+            r#"
+            name = "aiken-lang/test"
+            version = "0.0.0"
+
+            [[dependencies]]
+            name = "aiken-lang/stdlib"
+            version = "v2.1.0"
+            source = "github"
+
+            [patch."aiken-lang/stdlib"]
+            path = "../does-not-exist"
+            "#,
+        );
+
+        assert!(matches!(
+            resolve_versions(&config, Path::new("."), &NoListener),
+            Err(Error::MissingManifest { .. })
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    config::{Config, Dependency, Platform},
+    error::Error,
+    github,
+    package_name::PackageName,
+    paths,
+};
+use semver::Version;
+
+/// Newer releases of a dependency, found among the tags of its repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Releases {
+    pub name: PackageName,
+    pub current: String,
+    /// The most recent release compatible with the current one, if newer.
+    pub compatible: Option<String>,
+    /// The most recent release, if newer; compatible or not.
+    pub latest: Option<String>,
+}
+
+/// Look up newer releases of the project's dependencies (or only of the given ones). Only
+/// dependencies on a release tag are considered: those on a branch or a commit, as well as those
+/// patched, have no version to compare with.
+pub fn newer(config: &Config, only: &[PackageName]) -> Result<Vec<Releases>, Error> {
+    let registries = paths::registries(&config.mirrors);
+
+    config
+        .dependencies
+        .iter()
+        .filter(|dependency| only.is_empty() || only.contains(&dependency.name))
+        .filter(|dependency| {
+            dependency.source == Platform::Github
+                && !config.patch.contains_key(&dependency.name)
+                && parse(&dependency.version).is_some()
+        })
+        .map(|dependency| {
+            let tags = github::repo::tags(&registries, dependency.name.to_string())?;
+            Ok(releases(dependency, &tags))
+        })
+        .collect()
+}
+
+fn releases(dependency: &Dependency, tags: &[String]) -> Releases {
+    let current = parse(&dependency.version).expect("dependency on a release tag");
+
+    let newer = tags
+        .iter()
+        .filter_map(|tag| parse(tag).map(|version| (tag, version)))
+        .filter(|(_, version)| version.pre.is_empty() && *version > current)
+        .collect::<Vec<_>>();
+
+    let most_recent = |candidates: Vec<&(&String, Version)>| {
+        candidates
+            .into_iter()
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(tag, _)| tag.to_string())
+    };
+
+    Releases {
+        name: dependency.name.clone(),
+        current: dependency.version.clone(),
+        compatible: most_recent(
+            newer
+                .iter()
+                .filter(|(_, version)| is_compatible(&current, version))
+                .collect(),
+        ),
+        latest: most_recent(newer.iter().collect()),
+    }
+}
+
+/// Parse a release tag as a version, allowing a 'v' prefix and leaving out the minor and patch
+/// numbers (e.g. 'v2' for 2.0.0).
+fn parse(tag: &str) -> Option<Version> {
+    let tag = tag.strip_prefix('v').unwrap_or(tag);
+
+    let (numbers, suffix) = match tag.find(['-', '+']) {
+        Some(ix) => tag.split_at(ix),
+        None => (tag, ""),
+    };
+
+    let padding = match numbers.split('.').count() {
+        1 => ".0.0",
+        2 => ".0",
+        _ => "",
+    };
+
+    Version::parse(&format!("{numbers}{padding}{suffix}")).ok()
+}
+
+/// Whether a version may replace another without breaking changes: that is, when it has the same
+/// major version or, before 1.0.0, the same minor version.
+fn is_compatible(current: &Version, candidate: &Version) -> bool {
+    if current.major == 0 {
+        candidate.major == 0 && candidate.minor == current.minor
+    } else {
+        candidate.major == current.major
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dependency(version: &str) -> Dependency {
+        Dependency {
+            name: PackageName::from_str("aiken-lang/stdlib").unwrap(),
+            version: version.to_string(),
+            source: Platform::Github,
+            subdir: None,
+        }
+    }
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_tags() {
+        assert_eq!(parse("v2"), Some(Version::new(2, 0, 0)));
+        assert_eq!(parse("1.9"), Some(Version::new(1, 9, 0)));
+        assert_eq!(parse("v2.1.0"), Some(Version::new(2, 1, 0)));
+        assert_eq!(parse("2.2.0-rc1"), Version::parse("2.2.0-rc1").ok());
+        assert_eq!(parse("main"), None);
+        assert_eq!(parse("a3b9f1c"), None);
+    }
+
+    #[test]
+    fn newer_releases() {
+        let tags = tags(&["v1.9.0", "v2", "v2.1.0", "v2.2.0", "v3.0.0-rc1", "v3.0.0"]);
+
+        assert_eq!(
+            releases(&dependency("v2.1.0"), &tags),
+            Releases {
+                name: dependency("v2.1.0").name,
+                current: "v2.1.0".to_string(),
+                compatible: Some("v2.2.0".to_string()),
+                latest: Some("v3.0.0".to_string()),
+            }
+        );
+
+        let up_to_date = releases(&dependency("v3.0.0"), &tags);
+        assert_eq!(up_to_date.compatible, None);
+        assert_eq!(up_to_date.latest, None);
+    }
+
+    #[test]
+    fn compatibility_before_1_0_0() {
+        let tags = tags(&["0.3.1", "0.3.4", "0.4.0"]);
+
+        let releases = releases(&dependency("0.3.1"), &tags);
+        assert_eq!(releases.compatible, Some("0.3.4".to_string()));
+        assert_eq!(releases.latest, Some("0.4.0".to_string()));
+    }
+}
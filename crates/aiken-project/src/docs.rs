@@ -14,6 +14,7 @@ use aiken_lang::{
 use askama::Template;
 use itertools::Itertools;
 use pulldown_cmark as markdown;
+#[cfg(all(not(windows), feature = "native"))]
 use regex::Regex;
 use serde::Serialize;
 use serde_json as json;
@@ -27,6 +28,8 @@ const MAX_COLUMNS: isize = 80;
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub mod link_tree;
+#[cfg(feature = "native")]
+pub mod server;
 pub mod source_links;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -284,12 +287,12 @@ fn generate_module(
     )
 }
 
-#[cfg(windows)]
+#[cfg(any(windows, not(feature = "native")))]
 fn convert_latex_markers(input: String) -> String {
     input
 }
 
-#[cfg(not(windows))]
+#[cfg(all(not(windows), feature = "native"))]
 fn convert_latex_markers(input: String) -> String {
     let re_inline = Regex::new(r#"<span class="math math-inline">\s*(.+?)\s*</span>"#).unwrap();
     let re_block = Regex::new(r#"<span class="math math-display">\s*(.+?)\s*</span>"#).unwrap();
@@ -0,0 +1,223 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// Path polled by served pages to find out whether the documentation was generated anew.
+const VERSION_PATH: &str = "/__aiken/version";
+
+/// Script injected in served pages, reloading them whenever the documentation is generated anew.
+const RELOAD_SCRIPT: &str = r#"<script>
+  (function () {
+    let current = null;
+    setInterval(function () {
+      fetch("/__aiken/version")
+        .then(function (response) { return response.text(); })
+        .then(function (version) {
+          if (current !== null && version !== current) {
+            window.location.reload();
+          }
+          current = version;
+        })
+        .catch(function () {});
+    }, 500);
+  })();
+</script>
+"#;
+
+/// A handle on a running documentation server, to reload the pages it served.
+#[derive(Debug, Clone)]
+pub struct LiveReload {
+    version: Arc<AtomicU64>,
+}
+
+impl LiveReload {
+    /// Have pages currently open in a browser reload, e.g. once the documentation was generated
+    /// anew.
+    pub fn reload(&self) {
+        self.version.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Serve the files found under `root` on the given address, from a background thread. HTML pages
+/// are served with a script reloading them on [`LiveReload::reload`]. Returns the address
+/// actually listened on, which differs from the given one when binding to port 0.
+pub fn serve(root: PathBuf, address: SocketAddr) -> io::Result<(SocketAddr, LiveReload)> {
+    let listener = TcpListener::bind(address)?;
+
+    let address = listener.local_addr()?;
+
+    let live_reload = LiveReload {
+        version: Arc::new(AtomicU64::new(0)),
+    };
+
+    let version = live_reload.version.clone();
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A client going away mid-request shouldn't bring the server down.
+            let _ = handle(stream, &root, &version);
+        }
+    });
+
+    Ok((address, live_reload))
+}
+
+fn handle(mut stream: TcpStream, root: &Path, version: &AtomicU64) -> io::Result<()> {
+    let path = read_path(&mut stream)?;
+
+    let (status, content_type, body) = if path == VERSION_PATH {
+        (
+            200,
+            "text/plain",
+            version.load(Ordering::SeqCst).to_string().into_bytes(),
+        )
+    } else {
+        match resolve(root, &path).and_then(|file| fs::read(&file).ok().map(|body| (file, body))) {
+            Some((file, body)) => match content_type(&file) {
+                html @ "text/html; charset=utf-8" => (200, html, inject(body)),
+                content_type => (200, content_type, body),
+            },
+            None => (
+                404,
+                "text/plain",
+                format!("nothing to see at {path}").into_bytes(),
+            ),
+        }
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         Cache-Control: no-store\r\n\
+         Connection: close\r\n\r\n",
+        if status == 200 { "OK" } else { "Not Found" },
+        body.len(),
+    )?;
+
+    stream.write_all(&body)?;
+
+    stream.flush()
+}
+
+/// Read a request up to the end of its headers, and return the path it's for.
+fn read_path(stream: &mut TcpStream) -> io::Result<String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 8192];
+
+    loop {
+        let n = stream.read(&mut chunk)?;
+
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        buffer.extend_from_slice(&chunk[..n]);
+
+        let mut headers = [httparse::EMPTY_HEADER; 64];
+        let mut request = httparse::Request::new(&mut headers);
+
+        if let httparse::Status::Complete(..) = request
+            .parse(&buffer)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        {
+            return Ok(request.path.unwrap_or("/").to_string());
+        }
+    }
+}
+
+/// Find the file a request path points to under the root, if any. Directories stand for their
+/// 'index.html', and paths escaping the root point to nothing.
+fn resolve(root: &Path, path: &str) -> Option<PathBuf> {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+
+    let relative = Path::new(path.trim_start_matches('/'));
+
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(..)))
+    {
+        return None;
+    }
+
+    let file = root.join(relative);
+
+    let file = if file.is_dir() {
+        file.join("index.html")
+    } else {
+        file
+    };
+
+    file.is_file().then_some(file)
+}
+
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Add the reload script to an HTML page, at the end of its body.
+fn inject(page: Vec<u8>) -> Vec<u8> {
+    let page = String::from_utf8_lossy(&page);
+
+    match page.rfind("</body>") {
+        Some(ix) => format!("{}{RELOAD_SCRIPT}{}", &page[..ix], &page[ix..]),
+        None => format!("{page}{RELOAD_SCRIPT}"),
+    }
+    .into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_paths() {
+        let root = std::env::temp_dir().join("aiken-docs-server-resolve");
+        fs::create_dir_all(root.join("aiken")).unwrap();
+        fs::write(root.join("index.html"), "").unwrap();
+        fs::write(root.join("aiken").join("list.html"), "").unwrap();
+
+        assert_eq!(resolve(&root, "/"), Some(root.join("index.html")));
+        assert_eq!(
+            resolve(&root, "/index.html?q=foo"),
+            Some(root.join("index.html"))
+        );
+        assert_eq!(
+            resolve(&root, "/aiken/list.html#fn-map"),
+            Some(root.join("aiken").join("list.html"))
+        );
+        assert_eq!(resolve(&root, "/aiken/dict.html"), None);
+        assert_eq!(resolve(&root, "/../index.html"), None);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn inject_reload_script() {
+        let page = inject(b"<html><body><p>hello</p></body></html>".to_vec());
+
+        assert_eq!(
+            String::from_utf8(page).unwrap(),
+            format!("<html><body><p>hello</p>{RELOAD_SCRIPT}</body></html>")
+        );
+    }
+}
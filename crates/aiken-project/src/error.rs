@@ -1,8 +1,13 @@
-use crate::{blueprint, deps::manifest::Package, package_name::PackageName};
+use crate::{
+    blueprint,
+    deps::{advisories::Advisory, manifest::Package},
+    package_name::PackageName,
+};
 use aiken_lang::{
     ast::{self, Span},
-    error::ExtraData,
+    error::{Coded, ErrorCode, ExtraData},
     parser::error::ParseError,
+    plutus_version::PlutusVersion,
     test_framework::{PropertyTestResult, TestResult, UnitTestResult},
     tipo,
 };
@@ -14,11 +19,13 @@ use owo_colors::{
     OwoColorize,
     Stream::{Stderr, Stdout},
 };
+use similar::{ChangeTag, DiffTag, TextDiff};
 use std::{
     fmt::{Debug, Display},
     io,
     path::{Path, PathBuf},
 };
+#[cfg(feature = "native")]
 use zip::result::ZipError;
 
 #[allow(dead_code)]
@@ -35,7 +42,10 @@ pub enum Error {
     FileIo { error: io::Error, path: PathBuf },
 
     #[error("I found some files with incorrectly formatted source code.")]
-    Format { problem_files: Vec<Unformatted> },
+    Format {
+        problem_files: Vec<Unformatted>,
+        diff_context: usize,
+    },
 
     #[error(transparent)]
     Blueprint(#[from] blueprint::Error),
@@ -43,12 +53,15 @@ pub enum Error {
     #[error(transparent)]
     StandardIo(#[from] io::Error),
 
+    #[cfg(feature = "native")]
     #[error(transparent)]
     Http(#[from] reqwest::Error),
 
+    #[cfg(feature = "native")]
     #[error(transparent)]
     ZipExtract(#[from] ZipError),
 
+    #[cfg(feature = "native")]
     #[error(transparent)]
     JoinError(#[from] tokio::task::JoinError),
 
@@ -86,9 +99,9 @@ pub enum Error {
     Type {
         path: PathBuf,
         src: String,
-        named: NamedSource<String>,
+        named: Box<NamedSource<String>>,
         #[source]
-        error: tipo::error::Error,
+        error: Box<tipo::error::Error>,
     },
 
     #[error("{name} failed{}", if *verbose { format!("\n{src}") } else { String::new() } )]
@@ -99,26 +112,57 @@ pub enum Error {
         src: String,
     },
 
+    #[error("{name} failed to run")]
+    BenchmarkFailure {
+        name: String,
+        path: PathBuf,
+        reason: String,
+    },
+
+    #[error("{name} regressed: its {metric} went from {baseline} to {current}")]
+    BenchmarkRegression {
+        name: String,
+        path: PathBuf,
+        metric: String,
+        baseline: i64,
+        current: i64,
+        threshold: f64,
+    },
+
+    #[error("{name} regressed: its {metric} went from {locked} to {current}")]
+    BudgetRegression {
+        name: String,
+        metric: String,
+        locked: i64,
+        current: i64,
+        tolerance: f64,
+    },
+
     #[error(
         "I was unable to resolve '{}' for {}/{}",
         package.version,
         package.name.owner,
         package.name.repo
     )]
-    UnknownPackageVersion { package: Package },
+    UnknownPackageVersion { package: Box<Package> },
 
     #[error(
         "I need to resolve a package {}/{}, but couldn't find it.",
         package.name.owner,
         package.name.repo,
     )]
-    UnableToResolvePackage { package: Package },
+    UnableToResolvePackage { package: Box<Package> },
 
     #[error("I couldn't parse the provided stake address.")]
     MalformedStakeAddress {
         error: Option<pallas_addresses::Error>,
     },
 
+    #[error("I couldn't parse the provided address.")]
+    MalformedAddress {
+        error: Option<pallas_addresses::Error>,
+    },
+
     #[error("I didn't find any validator matching your criteria.")]
     NoValidatorNotFound { known_validators: Vec<String> },
 
@@ -128,6 +172,22 @@ pub enum Error {
     #[error("I couldn't find any exportable function named '{name}' in module '{module}'.")]
     ExportNotFound { module: String, name: String },
 
+    #[error("I failed to run the {hook} hook '{command}'.")]
+    HookFailed {
+        hook: String,
+        command: String,
+        reason: String,
+    },
+
+    #[error("I couldn't set '{key}' in the project configuration.")]
+    InvalidConfigValue { key: String, reason: String },
+
+    #[error("I couldn't find any feature named '{name}' in the project configuration.")]
+    UnknownFeature {
+        name: String,
+        known_features: Vec<String>,
+    },
+
     #[error("No such module '{module}' found in the project.")]
     ModuleNotFound {
         module: String,
@@ -136,6 +196,48 @@ pub enum Error {
 
     #[error("I located conditional modules under 'env', but no default one!")]
     NoDefaultEnvironment,
+
+    #[error("{}", if *handler {
+        format!("I proved that '{name}' can never succeed.")
+    } else {
+        format!("I found a counterexample to '{name}'.")
+    })]
+    Unverified {
+        name: String,
+        handler: bool,
+        counterexample: Box<[(String, String)]>,
+        path: PathBuf,
+        src: String,
+        named: Box<NamedSource<String>>,
+        location: Span,
+    },
+
+    #[error("I found a use of {what}, which isn't available in Plutus {version:?}.")]
+    UnavailableBuiltin {
+        what: String,
+        version: PlutusVersion,
+        path: PathBuf,
+        src: String,
+        named: Box<NamedSource<String>>,
+        location: Span,
+    },
+
+    #[error("I failed to evaluate the constant '{name}'.")]
+    ConstantEvaluation {
+        name: String,
+        /// Why evaluation failed, followed by whatever the constant traced.
+        reason: String,
+        path: PathBuf,
+        src: String,
+        named: Box<NamedSource<String>>,
+        location: Span,
+    },
+
+    #[error("I failed to run the SMT solver '{command}'.")]
+    SolverFailed { command: String, reason: String },
+
+    #[error("I can't report the outcome of 'aiken {command}' as JSON.")]
+    UnsupportedOutput { command: String },
 }
 
 impl Error {
@@ -146,7 +248,17 @@ impl Error {
             }
         }
 
-        println!("{self:?}")
+        println!("{self:?}");
+
+        if let Error::Format {
+            problem_files,
+            diff_context,
+        } = self
+        {
+            for file in problem_files {
+                print!("{}", file.diff(*diff_context));
+            }
+        }
     }
 
     pub fn from_parse_errors(errs: Vec<ParseError>, path: &Path, src: &str) -> Vec<Self> {
@@ -216,24 +328,77 @@ impl ExtraData for Error {
             | Error::ImportCycle { .. }
             | Error::Parse { .. }
             | Error::TestFailure { .. }
-            | Error::Http { .. }
-            | Error::ZipExtract { .. }
-            | Error::JoinError { .. }
+            | Error::BenchmarkFailure { .. }
+            | Error::BenchmarkRegression { .. }
+            | Error::BudgetRegression { .. }
             | Error::UnknownPackageVersion { .. }
             | Error::UnableToResolvePackage { .. }
             | Error::Json { .. }
             | Error::MalformedStakeAddress { .. }
+            | Error::MalformedAddress { .. }
             | Error::NoValidatorNotFound { .. }
             | Error::MoreThanOneValidatorFound { .. }
             | Error::Module { .. }
             | Error::NoDefaultEnvironment { .. }
+            | Error::Unverified { .. }
+            | Error::UnavailableBuiltin { .. }
+            | Error::ConstantEvaluation { .. }
+            | Error::SolverFailed { .. }
+            | Error::UnsupportedOutput { .. }
             | Error::ModuleNotFound { .. }
+            | Error::UnknownFeature { .. }
+            | Error::HookFailed { .. }
+            | Error::InvalidConfigValue { .. }
             | Error::ExportNotFound { .. } => None,
+            #[cfg(feature = "native")]
+            Error::Http(_) | Error::ZipExtract(_) | Error::JoinError(_) => None,
             Error::Type { error, .. } => error.extra_data(),
         }
     }
 }
 
+impl Coded for Error {
+    fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            Error::DuplicateModule { .. }
+            | Error::FileIo { .. }
+            | Error::Format { .. }
+            | Error::StandardIo { .. }
+            | Error::Blueprint { .. }
+            | Error::MissingManifest { .. }
+            | Error::TomlLoading { .. }
+            | Error::ImportCycle { .. }
+            | Error::TestFailure { .. }
+            | Error::BenchmarkFailure { .. }
+            | Error::BenchmarkRegression { .. }
+            | Error::BudgetRegression { .. }
+            | Error::UnknownPackageVersion { .. }
+            | Error::UnableToResolvePackage { .. }
+            | Error::Json { .. }
+            | Error::MalformedStakeAddress { .. }
+            | Error::MalformedAddress { .. }
+            | Error::NoValidatorNotFound { .. }
+            | Error::MoreThanOneValidatorFound { .. }
+            | Error::Module { .. }
+            | Error::NoDefaultEnvironment { .. }
+            | Error::Unverified { .. }
+            | Error::UnavailableBuiltin { .. }
+            | Error::ConstantEvaluation { .. }
+            | Error::SolverFailed { .. }
+            | Error::UnsupportedOutput { .. }
+            | Error::ModuleNotFound { .. }
+            | Error::UnknownFeature { .. }
+            | Error::HookFailed { .. }
+            | Error::InvalidConfigValue { .. }
+            | Error::ExportNotFound { .. } => None,
+            #[cfg(feature = "native")]
+            Error::Http(_) | Error::ZipExtract(_) | Error::JoinError(_) => None,
+            Error::Parse { error, .. } => error.error_code(),
+            Error::Type { error, .. } => error.error_code(),
+        }
+    }
+}
+
 pub trait GetSource {
     fn path(&self) -> Option<PathBuf>;
     fn src(&self) -> Option<String>;
@@ -247,25 +412,36 @@ impl GetSource for Error {
             | Error::StandardIo(_)
             | Error::Blueprint(_)
             | Error::ImportCycle { .. }
-            | Error::Http(_)
-            | Error::ZipExtract(_)
-            | Error::JoinError(_)
+            | Error::BudgetRegression { .. }
             | Error::UnknownPackageVersion { .. }
             | Error::UnableToResolvePackage { .. }
             | Error::Json { .. }
             | Error::MalformedStakeAddress { .. }
+            | Error::MalformedAddress { .. }
             | Error::NoValidatorNotFound { .. }
             | Error::MoreThanOneValidatorFound { .. }
             | Error::ModuleNotFound { .. }
+            | Error::UnknownFeature { .. }
+            | Error::HookFailed { .. }
+            | Error::InvalidConfigValue { .. }
             | Error::ExportNotFound { .. }
             | Error::NoDefaultEnvironment { .. }
+            | Error::SolverFailed { .. }
+            | Error::UnsupportedOutput { .. }
             | Error::Module { .. } => None,
+            #[cfg(feature = "native")]
+            Error::Http(_) | Error::ZipExtract(_) | Error::JoinError(_) => None,
             Error::DuplicateModule { second: path, .. }
             | Error::MissingManifest { path }
             | Error::TomlLoading { path, .. }
             | Error::Parse { path, .. }
             | Error::Type { path, .. }
-            | Error::TestFailure { path, .. } => Some(path.to_path_buf()),
+            | Error::TestFailure { path, .. }
+            | Error::BenchmarkFailure { path, .. }
+            | Error::BenchmarkRegression { path, .. }
+            | Error::Unverified { path, .. }
+            | Error::UnavailableBuiltin { path, .. }
+            | Error::ConstantEvaluation { path, .. } => Some(path.to_path_buf()),
         }
     }
 
@@ -279,22 +455,33 @@ impl GetSource for Error {
             | Error::MissingManifest { .. }
             | Error::ImportCycle { .. }
             | Error::TestFailure { .. }
-            | Error::Http(_)
-            | Error::ZipExtract(_)
-            | Error::JoinError(_)
+            | Error::BenchmarkFailure { .. }
+            | Error::BenchmarkRegression { .. }
+            | Error::BudgetRegression { .. }
             | Error::UnknownPackageVersion { .. }
             | Error::UnableToResolvePackage { .. }
             | Error::Json { .. }
             | Error::MalformedStakeAddress { .. }
+            | Error::MalformedAddress { .. }
             | Error::NoValidatorNotFound { .. }
             | Error::NoDefaultEnvironment { .. }
+            | Error::SolverFailed { .. }
+            | Error::UnsupportedOutput { .. }
             | Error::MoreThanOneValidatorFound { .. }
             | Error::ModuleNotFound { .. }
+            | Error::UnknownFeature { .. }
+            | Error::HookFailed { .. }
+            | Error::InvalidConfigValue { .. }
             | Error::ExportNotFound { .. }
             | Error::Module { .. } => None,
-            Error::TomlLoading { src, .. } | Error::Parse { src, .. } | Error::Type { src, .. } => {
-                Some(src.to_string())
-            }
+            #[cfg(feature = "native")]
+            Error::Http(_) | Error::ZipExtract(_) | Error::JoinError(_) => None,
+            Error::TomlLoading { src, .. }
+            | Error::Parse { src, .. }
+            | Error::Type { src, .. }
+            | Error::Unverified { src, .. }
+            | Error::UnavailableBuiltin { src, .. }
+            | Error::ConstantEvaluation { src, .. } => Some(src.to_string()),
         }
     }
 }
@@ -330,8 +517,14 @@ impl Diagnostic for Error {
             Error::TomlLoading { .. } => Some(boxed(Box::new("aiken::loading::toml"))),
             Error::Format { .. } => None,
             Error::TestFailure { path, .. } => Some(boxed(Box::new(path.to_str().unwrap_or("")))),
+            Error::BenchmarkFailure { .. } => Some(boxed(Box::new("aiken::bench::failure"))),
+            Error::BenchmarkRegression { .. } => Some(boxed(Box::new("aiken::bench::regression"))),
+            Error::BudgetRegression { .. } => Some(boxed(Box::new("aiken::budgets::regression"))),
+            #[cfg(feature = "native")]
             Error::Http(_) => Some(Box::new("aiken::packages::download")),
+            #[cfg(feature = "native")]
             Error::ZipExtract(_) => None,
+            #[cfg(feature = "native")]
             Error::JoinError(_) => None,
             Error::UnknownPackageVersion { .. } => {
                 Some(boxed(Box::new("aiken::packages::resolve")))
@@ -341,11 +534,20 @@ impl Diagnostic for Error {
             }
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
+            Error::MalformedAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
             Error::MoreThanOneValidatorFound { .. } => None,
             Error::ExportNotFound { .. } => None,
             Error::ModuleNotFound { .. } => None,
+            Error::UnknownFeature { .. } => None,
+            Error::HookFailed { .. } => None,
+            Error::InvalidConfigValue { .. } => None,
             Error::NoDefaultEnvironment { .. } => None,
+            Error::Unverified { .. } => Some(boxed(Box::new("aiken::verify"))),
+            Error::UnavailableBuiltin { .. } => Some(boxed(Box::new("aiken::plutus::version"))),
+            Error::ConstantEvaluation { .. } => Some(boxed(Box::new("aiken::constant::eval"))),
+            Error::SolverFailed { .. } => None,
+            Error::UnsupportedOutput { .. } => Some(boxed(Box::new("aiken::output"))),
             Error::Module(e) => e.code().map(boxed),
         }
     }
@@ -363,21 +565,63 @@ impl Diagnostic for Error {
                 "Try moving the shared code to a separate module that the others can depend on\n- {}",
                 modules.join("\n- ")
             ))),
-            Error::Parse { error, .. } => error.help(),
-            Error::Type { error, .. } => error.help(),
+            Error::Parse { error, .. } => explained(error.help(), error.error_code()),
+            Error::Type { error, .. } => explained(error.help(), error.error_code()),
             Error::StandardIo(_) => None,
             Error::MissingManifest { .. } => Some(Box::new(
                 "Try running `aiken new <REPOSITORY/PROJECT>` to initialise a project with an example manifest.",
             )),
+            Error::UnavailableBuiltin { version, .. } => Some(Box::new(format!(
+                "This code runs on Plutus {version:?}, as set by 'plutus' in 'aiken.toml' (or under '[validators.<name>]' for a single validator). BLS12-381 primitives, bitwise operations on byte arrays and other recent builtins are only available from Plutus V3 onwards; either target 'v3', or do without."
+            ))),
+            Error::ConstantEvaluation { reason, .. } => Some(Box::new(reason)),
+            Error::Unverified { handler: true, .. } => Some(Box::new(
+                "Whatever its inputs, this handler either fails or returns False; so anything it guards is locked forever.",
+            )),
+            Error::Unverified { counterexample, .. } if counterexample.is_empty() => None,
+            Error::Unverified { counterexample, .. } => Some(Box::new(format!(
+                "It doesn't hold for:\n{}",
+                counterexample
+                    .iter()
+                    .map(|(name, value)| format!(
+                        "─▶ {} = {}",
+                        name.if_supports_color(Stdout, |s| s.purple()),
+                        value.if_supports_color(Stdout, |s| s.bold())
+                    ))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ))),
+            Error::SolverFailed { reason, .. } => Some(Box::new(format!(
+                "{reason}\n\nMake sure that an SMT solver such as Z3 is installed, or point me to another one using --solver."
+            ))),
+            Error::UnsupportedOutput { .. } => Some(Box::new(
+                "This command only reports its outcome for humans; leave out '--output json'.",
+            )),
             Error::NoDefaultEnvironment { .. } => Some(Box::new(
                 "Environment module names are free, but there must be at least one named 'default.ak'.",
             )),
             Error::TomlLoading { .. } => None,
             Error::Format { .. } => None,
             Error::TestFailure { .. } => None,
-            Error::Http(_) => None,
-            Error::ZipExtract(_) => None,
-            Error::JoinError(_) => None,
+            Error::BenchmarkFailure { reason, .. } => Some(Box::new(reason)),
+            Error::BenchmarkRegression {
+                baseline,
+                current,
+                threshold,
+                ..
+            } => Some(Box::new(format!(
+                "That's {:+.1}% compared to the baseline, beyond the {threshold}% tolerated. If this is expected, record a new baseline using {}.",
+                (*current - *baseline) as f64 * 100.0 / (*baseline).max(1) as f64,
+                "--save-baseline".if_supports_color(Stdout, |s| s.bold()),
+            ))),
+            Error::BudgetRegression {
+                locked,
+                current,
+                tolerance,
+                ..
+            } => Some(Box::new(budget_regression_help(*locked, *current, *tolerance))),
+            #[cfg(feature = "native")]
+            Error::Http(_) | Error::ZipExtract(_) | Error::JoinError(_) => None,
             Error::ExportNotFound { .. } => None,
             Error::ModuleNotFound { known_modules, .. } => Some(Box::new(format!(
                 "I know about the following modules:\n{}",
@@ -387,6 +631,19 @@ impl Diagnostic for Error {
                     .collect::<Vec<_>>()
                     .join("\n")
             ))),
+            Error::HookFailed { reason, .. } => Some(Box::new(reason)),
+            Error::InvalidConfigValue { reason, .. } => Some(Box::new(reason)),
+            Error::UnknownFeature { known_features, .. } if known_features.is_empty() => Some(
+                Box::new("Features must be declared in the [features] section of your aiken.toml."),
+            ),
+            Error::UnknownFeature { known_features, .. } => Some(Box::new(format!(
+                "I know about the following features:\n{}",
+                known_features
+                    .iter()
+                    .map(|s| format!("─▶ {}", s.if_supports_color(Stdout, |s| s.purple())))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ))),
             Error::UnknownPackageVersion { .. } => Some(Box::new(
                 "Perhaps, double-check the package repository and version?",
             )),
@@ -395,7 +652,14 @@ impl Diagnostic for Error {
             )),
             Error::Json(error) => Some(Box::new(format!("{error}"))),
             Error::MalformedStakeAddress { error } => Some(Box::new(format!(
-                "A stake address must be provided either as a base16-encoded string, or as a bech32-encoded string with the 'stake' or 'stake_test' prefix.{hint}",
+                "A stake address must be provided either as a base16-encoded string, or as a bech32-encoded string with the 'stake' or 'stake_test' prefix. Alternatively, provide the base16-encoded hash of a stake key, or the base16-encoded hash of a script prefixed with 'script:'.{hint}",
+                hint = match error {
+                    Some(error) => format!("\n\nHere's the error I encountered: {error}"),
+                    None => String::new(),
+                }
+            ))),
+            Error::MalformedAddress { error } => Some(Box::new(format!(
+                "An address must be provided either as a bech32-encoded string (e.g. with the 'addr', 'addr_test', 'stake' or 'stake_test' prefix), or as a base16-encoded string.{hint}",
                 hint = match error {
                     Some(error) => format!("\n\nHere's the error I encountered: {error}"),
                     None => String::new(),
@@ -449,17 +713,55 @@ impl Diagnostic for Error {
             }
             Error::Format { .. } => None,
             Error::TestFailure { .. } => None,
-            Error::Http(_) => None,
-            Error::ZipExtract(_) => None,
-            Error::JoinError(_) => None,
+            Error::BenchmarkFailure { .. } => None,
+            Error::BenchmarkRegression { .. } => None,
+            Error::BudgetRegression { .. } => None,
+            #[cfg(feature = "native")]
+            Error::Http(_) | Error::ZipExtract(_) | Error::JoinError(_) => None,
             Error::UnknownPackageVersion { .. } => None,
             Error::UnableToResolvePackage { .. } => None,
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
+            Error::MalformedAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
             Error::MoreThanOneValidatorFound { .. } => None,
             Error::NoDefaultEnvironment { .. } => None,
+            Error::Unverified {
+                handler, location, ..
+            } => Some(Box::new(
+                vec![LabeledSpan::new_with_span(
+                    Some(
+                        if *handler {
+                            "never succeeds"
+                        } else {
+                            "refuted"
+                        }
+                        .to_string(),
+                    ),
+                    *location,
+                )]
+                .into_iter(),
+            )),
+            Error::UnavailableBuiltin { location, .. } => Some(Box::new(
+                vec![LabeledSpan::new_with_span(
+                    Some("unavailable".to_string()),
+                    *location,
+                )]
+                .into_iter(),
+            )),
+            Error::ConstantEvaluation { location, .. } => Some(Box::new(
+                vec![LabeledSpan::new_with_span(
+                    Some("failed".to_string()),
+                    *location,
+                )]
+                .into_iter(),
+            )),
+            Error::SolverFailed { .. } => None,
+            Error::UnsupportedOutput { .. } => None,
             Error::ModuleNotFound { .. } => None,
+            Error::UnknownFeature { .. } => None,
+            Error::HookFailed { .. } => None,
+            Error::InvalidConfigValue { .. } => None,
             Error::Module(e) => e.labels(),
         }
     }
@@ -470,23 +772,34 @@ impl Diagnostic for Error {
             Error::FileIo { .. } => None,
             Error::ImportCycle { .. } => None,
             Error::ModuleNotFound { .. } => None,
+            Error::UnknownFeature { .. } => None,
+            Error::HookFailed { .. } => None,
+            Error::InvalidConfigValue { .. } => None,
             Error::ExportNotFound { .. } => None,
             Error::Blueprint(e) => e.source_code(),
             Error::NoDefaultEnvironment { .. } => None,
+            Error::Unverified { named, .. } => Some(named.as_ref()),
+            Error::UnavailableBuiltin { named, .. } => Some(named.as_ref()),
+            Error::ConstantEvaluation { named, .. } => Some(named.as_ref()),
+            Error::SolverFailed { .. } => None,
+            Error::UnsupportedOutput { .. } => None,
             Error::Parse { named, .. } => Some(named.as_ref()),
-            Error::Type { named, .. } => Some(named),
+            Error::Type { named, .. } => Some(named.as_ref()),
             Error::StandardIo(_) => None,
             Error::MissingManifest { .. } => None,
             Error::TomlLoading { named, .. } => Some(named.as_ref()),
             Error::Format { .. } => None,
             Error::TestFailure { .. } => None,
-            Error::Http(_) => None,
-            Error::ZipExtract(_) => None,
-            Error::JoinError(_) => None,
+            Error::BenchmarkFailure { .. } => None,
+            Error::BenchmarkRegression { .. } => None,
+            Error::BudgetRegression { .. } => None,
+            #[cfg(feature = "native")]
+            Error::Http(_) | Error::ZipExtract(_) | Error::JoinError(_) => None,
             Error::UnknownPackageVersion { .. } => None,
             Error::UnableToResolvePackage { .. } => None,
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
+            Error::MalformedAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
             Error::MoreThanOneValidatorFound { .. } => None,
             Error::Module(e) => e.source_code(),
@@ -499,6 +812,9 @@ impl Diagnostic for Error {
             Error::FileIo { .. } => None,
             Error::ImportCycle { .. } => None,
             Error::ModuleNotFound { .. } => None,
+            Error::UnknownFeature { .. } => None,
+            Error::HookFailed { .. } => None,
+            Error::InvalidConfigValue { .. } => None,
             Error::ExportNotFound { .. } => None,
             Error::Blueprint(e) => e.url(),
             Error::Parse { .. } => None,
@@ -508,16 +824,24 @@ impl Diagnostic for Error {
             Error::TomlLoading { .. } => None,
             Error::Format { .. } => None,
             Error::TestFailure { .. } => None,
-            Error::Http { .. } => None,
-            Error::ZipExtract { .. } => None,
-            Error::JoinError { .. } => None,
+            Error::BenchmarkFailure { .. } => None,
+            Error::BenchmarkRegression { .. } => None,
+            Error::BudgetRegression { .. } => None,
+            #[cfg(feature = "native")]
+            Error::Http(_) | Error::ZipExtract(_) | Error::JoinError(_) => None,
             Error::UnknownPackageVersion { .. } => None,
             Error::UnableToResolvePackage { .. } => None,
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
+            Error::MalformedAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
             Error::MoreThanOneValidatorFound { .. } => None,
             Error::NoDefaultEnvironment { .. } => None,
+            Error::Unverified { .. } => None,
+            Error::UnavailableBuiltin { .. } => None,
+            Error::ConstantEvaluation { .. } => None,
+            Error::SolverFailed { .. } => None,
+            Error::UnsupportedOutput { .. } => None,
             Error::Module(e) => e.url(),
         }
     }
@@ -527,6 +851,9 @@ impl Diagnostic for Error {
             Error::DuplicateModule { .. } => None,
             Error::FileIo { .. } => None,
             Error::ModuleNotFound { .. } => None,
+            Error::UnknownFeature { .. } => None,
+            Error::HookFailed { .. } => None,
+            Error::InvalidConfigValue { .. } => None,
             Error::ExportNotFound { .. } => None,
             Error::Blueprint(e) => e.related(),
             Error::ImportCycle { .. } => None,
@@ -534,17 +861,25 @@ impl Diagnostic for Error {
             Error::Type { error, .. } => error.related(),
             Error::StandardIo(_) => None,
             Error::NoDefaultEnvironment { .. } => None,
+            Error::Unverified { .. } => None,
+            Error::UnavailableBuiltin { .. } => None,
+            Error::ConstantEvaluation { .. } => None,
+            Error::SolverFailed { .. } => None,
+            Error::UnsupportedOutput { .. } => None,
             Error::MissingManifest { .. } => None,
             Error::TomlLoading { .. } => None,
             Error::Format { .. } => None,
             Error::TestFailure { .. } => None,
-            Error::Http { .. } => None,
-            Error::ZipExtract { .. } => None,
-            Error::JoinError { .. } => None,
+            Error::BenchmarkFailure { .. } => None,
+            Error::BenchmarkRegression { .. } => None,
+            Error::BudgetRegression { .. } => None,
+            #[cfg(feature = "native")]
+            Error::Http(_) | Error::ZipExtract(_) | Error::JoinError(_) => None,
             Error::UnknownPackageVersion { .. } => None,
             Error::UnableToResolvePackage { .. } => None,
             Error::Json { .. } => None,
             Error::MalformedStakeAddress { .. } => None,
+            Error::MalformedAddress { .. } => None,
             Error::NoValidatorNotFound { .. } => None,
             Error::MoreThanOneValidatorFound { .. } => None,
             Error::Module(e) => e.related(),
@@ -572,6 +907,37 @@ pub enum Warning {
     CompilerVersionMismatch { demanded: String, current: String },
     #[error("No configuration found for environment {env}.")]
     NoConfigurationForEnv { env: String },
+    #[error("Patch for {name} was not used in the dependency graph.")]
+    UnusedPatch { name: PackageName },
+    #[error("The '{key}' key of aiken.toml is deprecated.")]
+    DeprecatedConfigKey { key: String, replacement: String },
+    #[error("{name} {version} is affected by {}: {}", advisory.id, advisory.title)]
+    VulnerableDependency {
+        name: PackageName,
+        version: String,
+        advisory: Advisory,
+    },
+    #[error("{name} {version} has been yanked.")]
+    YankedDependency {
+        name: PackageName,
+        version: String,
+        reason: String,
+    },
+    #[error("I found a branch that can never be taken.")]
+    UnreachableBranch {
+        path: PathBuf,
+        src: String,
+        named: NamedSource<String>,
+        location: Span,
+    },
+    #[error("{name} regressed: its {metric} went from {locked} to {current}")]
+    BudgetRegression {
+        name: String,
+        metric: String,
+        locked: i64,
+        current: i64,
+        tolerance: f64,
+    },
 }
 
 impl ExtraData for Warning {
@@ -581,30 +947,67 @@ impl ExtraData for Warning {
             | Warning::DependencyAlreadyExists { .. }
             | Warning::InvalidModuleName { .. }
             | Warning::CompilerVersionMismatch { .. }
-            | Warning::NoConfigurationForEnv { .. } => None,
+            | Warning::NoConfigurationForEnv { .. }
+            | Warning::UnusedPatch { .. }
+            | Warning::DeprecatedConfigKey { .. }
+            | Warning::VulnerableDependency { .. }
+            | Warning::YankedDependency { .. }
+            | Warning::UnreachableBranch { .. }
+            | Warning::BudgetRegression { .. } => None,
             Warning::Type { warning, .. } => warning.extra_data(),
         }
     }
 }
 
+impl Coded for Warning {
+    fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            Warning::NoValidators { .. }
+            | Warning::DependencyAlreadyExists { .. }
+            | Warning::InvalidModuleName { .. }
+            | Warning::CompilerVersionMismatch { .. }
+            | Warning::NoConfigurationForEnv { .. }
+            | Warning::UnusedPatch { .. }
+            | Warning::DeprecatedConfigKey { .. }
+            | Warning::VulnerableDependency { .. }
+            | Warning::YankedDependency { .. }
+            | Warning::UnreachableBranch { .. }
+            | Warning::BudgetRegression { .. } => None,
+            Warning::Type { warning, .. } => warning.error_code(),
+        }
+    }
+}
+
 impl GetSource for Warning {
     fn path(&self) -> Option<PathBuf> {
         match self {
-            Warning::InvalidModuleName { path } | Warning::Type { path, .. } => Some(path.clone()),
+            Warning::InvalidModuleName { path }
+            | Warning::Type { path, .. }
+            | Warning::UnreachableBranch { path, .. } => Some(path.clone()),
             Warning::NoValidators
             | Warning::DependencyAlreadyExists { .. }
             | Warning::NoConfigurationForEnv { .. }
+            | Warning::UnusedPatch { .. }
+            | Warning::DeprecatedConfigKey { .. }
+            | Warning::VulnerableDependency { .. }
+            | Warning::YankedDependency { .. }
+            | Warning::BudgetRegression { .. }
             | Warning::CompilerVersionMismatch { .. } => None,
         }
     }
 
     fn src(&self) -> Option<String> {
         match self {
-            Warning::Type { src, .. } => Some(src.clone()),
+            Warning::Type { src, .. } | Warning::UnreachableBranch { src, .. } => Some(src.clone()),
             Warning::NoValidators
             | Warning::InvalidModuleName { .. }
             | Warning::DependencyAlreadyExists { .. }
             | Warning::NoConfigurationForEnv { .. }
+            | Warning::UnusedPatch { .. }
+            | Warning::DeprecatedConfigKey { .. }
+            | Warning::VulnerableDependency { .. }
+            | Warning::YankedDependency { .. }
+            | Warning::BudgetRegression { .. }
             | Warning::CompilerVersionMismatch { .. } => None,
         }
     }
@@ -617,11 +1020,16 @@ impl Diagnostic for Warning {
 
     fn source_code(&self) -> Option<&dyn SourceCode> {
         match self {
-            Warning::Type { named, .. } => Some(named),
+            Warning::Type { named, .. } | Warning::UnreachableBranch { named, .. } => Some(named),
             Warning::NoValidators
             | Warning::InvalidModuleName { .. }
             | Warning::NoConfigurationForEnv { .. }
             | Warning::DependencyAlreadyExists { .. }
+            | Warning::UnusedPatch { .. }
+            | Warning::DeprecatedConfigKey { .. }
+            | Warning::VulnerableDependency { .. }
+            | Warning::YankedDependency { .. }
+            | Warning::BudgetRegression { .. }
             | Warning::CompilerVersionMismatch { .. } => None,
         }
     }
@@ -629,10 +1037,22 @@ impl Diagnostic for Warning {
     fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
         match self {
             Warning::Type { warning, .. } => warning.labels(),
+            Warning::UnreachableBranch { location, .. } => Some(Box::new(
+                vec![LabeledSpan::new_with_span(
+                    Some("unreachable".to_string()),
+                    *location,
+                )]
+                .into_iter(),
+            )),
             Warning::InvalidModuleName { .. }
             | Warning::NoValidators
             | Warning::DependencyAlreadyExists { .. }
             | Warning::NoConfigurationForEnv { .. }
+            | Warning::UnusedPatch { .. }
+            | Warning::DeprecatedConfigKey { .. }
+            | Warning::VulnerableDependency { .. }
+            | Warning::YankedDependency { .. }
+            | Warning::BudgetRegression { .. }
             | Warning::CompilerVersionMismatch { .. } => None,
         }
     }
@@ -654,12 +1074,20 @@ impl Diagnostic for Warning {
             Warning::NoConfigurationForEnv { .. } => {
                 Some(Box::new("aiken::project::config::missing::env"))
             }
+            Warning::UnusedPatch { .. } => Some(Box::new("aiken::packages::unused_patch")),
+            Warning::DeprecatedConfigKey { .. } => {
+                Some(Box::new("aiken::project::config::deprecated"))
+            }
+            Warning::VulnerableDependency { .. } => Some(Box::new("aiken::packages::advisory")),
+            Warning::YankedDependency { .. } => Some(Box::new("aiken::packages::yanked")),
+            Warning::UnreachableBranch { .. } => Some(Box::new("aiken::verify::unreachable")),
+            Warning::BudgetRegression { .. } => Some(Box::new("aiken::budgets::regression")),
         }
     }
 
     fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
         match self {
-            Warning::Type { warning, .. } => warning.help(),
+            Warning::Type { warning, .. } => explained(warning.help(), warning.error_code()),
             Warning::NoValidators => None,
             Warning::CompilerVersionMismatch { demanded, .. } => Some(Box::new(format!(
                 "You may want to switch to {}",
@@ -674,6 +1102,44 @@ impl Diagnostic for Warning {
             Warning::NoConfigurationForEnv { .. } => Some(Box::new(
                 "When configuration keys are missing for a target environment, no 'config' module will be created. This may lead to issues down the line.",
             )),
+            Warning::UnusedPatch { .. } => Some(Box::new(
+                "Patches only apply to packages listed under 'dependencies'. Check the package name for typos, or remove the patch.",
+            )),
+            Warning::DeprecatedConfigKey { replacement, .. } => Some(Box::new(format!(
+                "It is still understood for now, but you may want to rename it to '{replacement}'."
+            ))),
+            Warning::VulnerableDependency { advisory, .. } => Some(Box::new(format!(
+                "{description}{url}Consider upgrading to a revision that isn't affected; run 'aiken audit' for details.",
+                description = if advisory.description.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}\n\n", advisory.description.trim())
+                },
+                url = match advisory.url {
+                    Some(ref url) => format!(
+                        "See {}\n\n",
+                        url.if_supports_color(Stdout, |s| s.bright_blue())
+                    ),
+                    None => String::new(),
+                },
+            ))),
+            Warning::YankedDependency { reason, .. } => Some(Box::new(format!(
+                "{reason}Yanked revisions shouldn't be depended upon anymore; consider upgrading to another revision.",
+                reason = if reason.is_empty() {
+                    String::new()
+                } else {
+                    format!("Reason: {}\n\n", reason.trim())
+                },
+            ))),
+            Warning::UnreachableBranch { .. } => Some(Box::new(
+                "Whatever the inputs, earlier conditions or expectations rule this branch out. It is either dead code, or a sign that those conditions aren't the ones intended.",
+            )),
+            Warning::BudgetRegression {
+                locked,
+                current,
+                tolerance,
+                ..
+            } => Some(Box::new(budget_regression_help(*locked, *current, *tolerance))),
         }
     }
 }
@@ -763,6 +1229,79 @@ pub struct Unformatted {
     pub output: String,
 }
 
+impl Unformatted {
+    /// A unified diff from the input to the formatted output, with `context` unchanged lines
+    /// around changes. Each hunk is preceded by the location (line and column) of its first
+    /// change in the input.
+    pub fn diff(&self, context: usize) -> String {
+        let diff = TextDiff::from_lines(&self.input, &self.output);
+
+        let mut doc = format!(
+            "{}\n{}\n",
+            format!("--- {}", self.source.display()).if_supports_color(Stdout, |s| s.bold()),
+            format!("+++ {}", self.destination.display()).if_supports_color(Stdout, |s| s.bold()),
+        );
+
+        for hunk in diff.unified_diff().context_radius(context).iter_hunks() {
+            if let Some((_, old, new)) = hunk
+                .ops()
+                .iter()
+                .map(|op| op.as_tag_tuple())
+                .find(|(tag, _, _)| *tag != DiffTag::Equal)
+            {
+                let column = match (
+                    self.input.lines().nth(old.start),
+                    self.output.lines().nth(new.start),
+                ) {
+                    (Some(before), Some(after)) if !old.is_empty() && !new.is_empty() => {
+                        before
+                            .chars()
+                            .zip(after.chars())
+                            .take_while(|(a, b)| a == b)
+                            .count()
+                            + 1
+                    }
+                    _ => 1,
+                };
+
+                doc.push_str(&format!(
+                    "{}\n",
+                    format!("{}:{}:{column}", self.source.display(), old.start + 1)
+                        .if_supports_color(Stdout, |s| s.bold())
+                ));
+            }
+
+            doc.push_str(&format!(
+                "{}\n",
+                hunk.header().if_supports_color(Stdout, |s| s.cyan())
+            ));
+
+            for change in hunk.iter_changes() {
+                let line = format!("{}{}", change.tag(), change.value());
+                let line = line.trim_end_matches('\n');
+
+                match change.tag() {
+                    ChangeTag::Delete => {
+                        doc.push_str(&line.if_supports_color(Stdout, |s| s.red()).to_string())
+                    }
+                    ChangeTag::Insert => {
+                        doc.push_str(&line.if_supports_color(Stdout, |s| s.green()).to_string())
+                    }
+                    ChangeTag::Equal => doc.push_str(line),
+                }
+
+                doc.push('\n');
+
+                if change.missing_newline() {
+                    doc.push_str("\\ No newline at end of file\n");
+                }
+            }
+        }
+
+        doc
+    }
+}
+
 fn default_miette_handler(context_lines: usize) -> MietteHandler {
     MietteHandlerOpts::new()
         // For better support of terminal themes use the ANSI coloring
@@ -773,3 +1312,36 @@ fn default_miette_handler(context_lines: usize) -> MietteHandler {
         .context_lines(context_lines)
         .build()
 }
+
+/// Point to the extended explanation of a diagnostic, when it has one, after its help.
+fn explained<'a>(
+    help: Option<Box<dyn Display + 'a>>,
+    code: Option<ErrorCode>,
+) -> Option<Box<dyn Display + 'a>> {
+    let Some(code) = code else {
+        return help;
+    };
+
+    let hint = format!(
+        "Run {} for more details and examples.",
+        format!("aiken explain {code}").if_supports_color(Stdout, |s| s.bold())
+    );
+
+    // Some diagnostics always have a help, albeit sometimes empty.
+    let help = help
+        .map(|help| help.to_string())
+        .filter(|help| !help.trim().is_empty());
+
+    Some(Box::new(match help {
+        Some(help) => format!("{help}\n\n{hint}"),
+        None => hint,
+    }))
+}
+
+fn budget_regression_help(locked: i64, current: i64, tolerance: f64) -> String {
+    format!(
+        "That's {:+.1}% compared to 'budgets.lock', beyond the {tolerance}% tolerated. If this is expected, update the lock using {}.",
+        (current - locked) as f64 * 100.0 / locked.max(1) as f64,
+        "--lock-budgets".if_supports_color(Stdout, |s| s.bold()),
+    )
+}
@@ -18,10 +18,17 @@ pub struct Export {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub doc: Option<String>,
 
+    #[serde(rename = "plutusVersion")]
+    #[serde(default)]
+    pub plutus_version: PlutusVersion,
+
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
     pub parameters: Vec<Parameter>,
 
+    /// Schema of the value the function evaluates to, once given all its parameters.
+    pub returns: Parameter,
+
     #[serde(flatten)]
     pub program: SerializableProgram,
 
@@ -54,7 +61,7 @@ impl Export {
                     schema: Declaration::Referenced(schema),
                 })
                 .map_err(|error| blueprint::Error::Schema {
-                    error,
+                    error: error.into(),
                     location: param.location,
                     source_code: NamedSource::new(
                         module.input_path.display().to_string(),
@@ -64,6 +71,24 @@ impl Export {
             })
             .collect::<Result<_, _>>()?;
 
+        let returns = Annotated::from_type(modules.into(), &func.return_type, &mut definitions)
+            .map(|schema| Parameter {
+                title: None,
+                schema: Declaration::Referenced(schema),
+            })
+            .map_err(|error| blueprint::Error::Schema {
+                error: error.into(),
+                location: func
+                    .return_annotation
+                    .as_ref()
+                    .map(|annotation| annotation.location())
+                    .unwrap_or(func.location),
+                source_code: NamedSource::new(
+                    module.input_path.display().to_string(),
+                    module.code.clone(),
+                ),
+            })?;
+
         let program = match plutus_version {
             PlutusVersion::V1 => SerializableProgram::PlutusV1Program,
             PlutusVersion::V2 => SerializableProgram::PlutusV2Program,
@@ -78,7 +103,9 @@ impl Export {
         Ok(Export {
             name: format!("{}.{}", &module.name, &func.name),
             doc: func.doc.clone(),
+            plutus_version: *plutus_version,
             parameters,
+            returns,
             program,
             definitions,
         })
@@ -5,22 +5,34 @@ use std::{
     str::FromStr,
 };
 
-use aiken_lang::{ast::ModuleKind, parser};
+use aiken_lang::{ast::ModuleKind, format::FormatOptions, parser};
 
 use crate::{
     error::{Error, Unformatted},
     is_aiken_path,
 };
 
-pub fn run(stdin: bool, check: bool, files: Vec<String>) -> Result<(), Vec<Error>> {
+/// Format the given files, or the standard input. In check mode, nothing is written; unformatted
+/// inputs are reported instead, along with a diff showing `diff_context` lines around changes.
+pub fn run(
+    stdin: bool,
+    check: bool,
+    diff_context: usize,
+    files: Vec<String>,
+    options: &FormatOptions,
+) -> Result<(), Vec<Error>> {
     if stdin {
-        process_stdin(check)
+        process_stdin(check, diff_context, options)
     } else {
-        process_files(check, files)
+        process_files(check, diff_context, files, options)
     }
 }
 
-fn process_stdin(check: bool) -> Result<(), Vec<Error>> {
+fn process_stdin(
+    check: bool,
+    diff_context: usize,
+    options: &FormatOptions,
+) -> Result<(), Vec<Error>> {
     let src = read_stdin()?;
 
     let mut out = String::new();
@@ -28,7 +40,7 @@ fn process_stdin(check: bool) -> Result<(), Vec<Error>> {
     let (module, extra) = parser::module(&src, ModuleKind::Lib)
         .map_err(|errs| Error::from_parse_errors(errs, Path::new("<stdin>"), &src))?;
 
-    aiken_lang::format::pretty(&mut out, module, extra, &src);
+    aiken_lang::format::pretty_with_options(&mut out, module, extra, &src, options);
 
     if !check {
         print!("{out}");
@@ -43,39 +55,56 @@ fn process_stdin(check: bool) -> Result<(), Vec<Error>> {
                 input: src,
                 output: out,
             }],
+            diff_context,
         }]);
     }
 
     Ok(())
 }
 
-fn process_files(check: bool, files: Vec<String>) -> Result<(), Vec<Error>> {
+fn process_files(
+    check: bool,
+    diff_context: usize,
+    files: Vec<String>,
+    options: &FormatOptions,
+) -> Result<(), Vec<Error>> {
     if check {
-        check_files(files)
+        check_files(files, diff_context, options)
     } else {
-        format_files(files)
+        format_files(files, options)
     }
 }
 
-fn check_files(files: Vec<String>) -> Result<(), Vec<Error>> {
-    let problem_files = unformatted_files(files)?;
+fn check_files(
+    files: Vec<String>,
+    diff_context: usize,
+    options: &FormatOptions,
+) -> Result<(), Vec<Error>> {
+    let problem_files = unformatted_files(files, options)?;
 
     if problem_files.is_empty() {
         Ok(())
     } else {
-        Err(Error::Format { problem_files }.into())
+        Err(Error::Format {
+            problem_files,
+            diff_context,
+        }
+        .into())
     }
 }
 
-fn format_files(files: Vec<String>) -> Result<(), Vec<Error>> {
-    for file in unformatted_files(files)? {
+fn format_files(files: Vec<String>, options: &FormatOptions) -> Result<(), Vec<Error>> {
+    for file in unformatted_files(files, options)? {
         fs::write(file.destination, file.output).map_err(Error::from)?;
     }
 
     Ok(())
 }
 
-fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>, Vec<Error>> {
+fn unformatted_files(
+    files: Vec<String>,
+    options: &FormatOptions,
+) -> Result<Vec<Unformatted>, Vec<Error>> {
     let mut problem_files = Vec::with_capacity(files.len());
     let mut errors = vec![];
 
@@ -84,11 +113,11 @@ fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>, Vec<Error>>
 
         if path.is_dir() {
             for path in aiken_files_excluding_gitignore(&path) {
-                if let Err(mut errs) = format_file(&mut problem_files, path) {
+                if let Err(mut errs) = format_file(&mut problem_files, path, options) {
                     errors.append(&mut errs);
                 };
             }
-        } else if let Err(mut errs) = format_file(&mut problem_files, path) {
+        } else if let Err(mut errs) = format_file(&mut problem_files, path, options) {
             errors.append(&mut errs);
         }
     }
@@ -100,7 +129,11 @@ fn unformatted_files(files: Vec<String>) -> Result<Vec<Unformatted>, Vec<Error>>
     }
 }
 
-fn format_file(problem_files: &mut Vec<Unformatted>, path: PathBuf) -> Result<(), Vec<Error>> {
+fn format_file(
+    problem_files: &mut Vec<Unformatted>,
+    path: PathBuf,
+    options: &FormatOptions,
+) -> Result<(), Vec<Error>> {
     let src = fs::read_to_string(&path).map_err(|error| Error::FileIo {
         error,
         path: path.clone(),
@@ -111,7 +144,7 @@ fn format_file(problem_files: &mut Vec<Unformatted>, path: PathBuf) -> Result<()
     let (module, extra) = parser::module(&src, ModuleKind::Lib)
         .map_err(|errs| Error::from_parse_errors(errs, &path, &src))?;
 
-    aiken_lang::format::pretty(&mut output, module, extra, &src);
+    aiken_lang::format::pretty_with_options(&mut output, module, extra, &src, options);
 
     if src != output {
         problem_files.push(Unformatted {
@@ -125,6 +158,34 @@ fn format_file(problem_files: &mut Vec<Unformatted>, path: PathBuf) -> Result<()
     Ok(())
 }
 
+/// First segments of the modules a project defines, from its 'lib' and 'env' folders; which is
+/// how imports of local modules are told apart from others.
+pub fn local_modules(root: &Path) -> Vec<String> {
+    let mut modules = fs::read_dir(root.join("lib"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.is_dir() {
+                path.file_name()?.to_str().map(str::to_string)
+            } else if path.extension()? == "ak" {
+                let stem = path.file_stem()?.to_str()?;
+                stem.split('.').next().map(str::to_string)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if root.join("env").is_dir() {
+        modules.push("env".to_string());
+    }
+
+    modules.sort();
+    modules.dedup();
+    modules
+}
+
 pub fn read_stdin() -> Result<String, Error> {
     let mut src = String::new();
 
@@ -143,3 +204,42 @@ pub fn aiken_files_excluding_gitignore(dir: &Path) -> impl Iterator<Item = PathB
         .map(ignore::DirEntry::into_path)
         .filter(move |d| is_aiken_path(d, dir))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unformatted_diff() {
+        let unformatted = Unformatted {
+            source: PathBuf::from("lib/foo.ak"),
+            destination: PathBuf::from("lib/foo.ak"),
+            input: "const a = [1,2]\n\nfn foo() {\n  a\n}\n\nfn bar(x) {\n   x+1\n}".to_string(),
+            output: "const a = [1, 2]\n\nfn foo() {\n  a\n}\n\nfn bar(x) {\n  x + 1\n}\n"
+                .to_string(),
+        };
+
+        assert_eq!(
+            unformatted.diff(1),
+            [
+                "--- lib/foo.ak",
+                "+++ lib/foo.ak",
+                "lib/foo.ak:1:14",
+                "@@ -1,2 +1,2 @@",
+                "-const a = [1,2]",
+                "+const a = [1, 2]",
+                " ",
+                "lib/foo.ak:8:3",
+                "@@ -7,3 +7,3 @@",
+                " fn bar(x) {",
+                "-   x+1",
+                "-}",
+                "\\ No newline at end of file",
+                "+  x + 1",
+                "+}",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,489 @@
+//! Aiken stubs for validators built elsewhere: their hashes, and types reconstructed from their
+//! blueprint's schemas. Reconstructed types share their Plutus data representation with the
+//! originals, not their identity; values go from one to the other through 'Data'.
+
+use super::{constructor_title, field_names, pascal_case, scripts, type_names, Script, Shape};
+use crate::blueprint::{
+    definitions::Reference,
+    schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+    Blueprint,
+};
+use aiken_lang::ast::well_known;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+const KEYWORDS: &[&str] = &[
+    "and",
+    "as",
+    "const",
+    "else",
+    "error",
+    "expect",
+    "fail",
+    "fn",
+    "if",
+    "is",
+    "let",
+    "once",
+    "opaque",
+    "or",
+    "pub",
+    "test",
+    "todo",
+    "trace",
+    "type",
+    "use",
+    "validator",
+    "via",
+    "when",
+];
+
+/// Definitions standing for a type of the prelude, used as-is.
+const PRELUDE: &[&str] = &[
+    well_known::BOOL,
+    well_known::BYTE_ARRAY,
+    well_known::DATA,
+    well_known::INT,
+    well_known::NEVER,
+    well_known::ORDERING,
+    well_known::STRING,
+    well_known::VOID,
+];
+
+/// Generate an Aiken module from a (foreign) blueprint, with:
+///
+/// - a type for each named definition (custom types and aliases); others, like lists or options,
+///   are written out where they're used;
+/// - a constant holding the hash of each validator without parameters;
+/// - an alias for the datum and redeemer type of each handler.
+pub fn generate(blueprint: &Blueprint) -> String {
+    let generator = Generator::new(blueprint);
+
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "//// Generated by Aiken from the blueprint of '{}' (v{}). Do not edit by hand.",
+        blueprint.preamble.title, blueprint.preamble.version
+    )
+    .unwrap();
+    writeln!(out, "////").unwrap();
+    writeln!(
+        out,
+        "//// Types share the Plutus data representation of the original ones; go through 'Data'"
+    )
+    .unwrap();
+    writeln!(out, "//// to convert from one to the other.").unwrap();
+
+    for (key, definition) in blueprint.definitions.iter() {
+        if generator.names.contains_key(key) {
+            writeln!(out).unwrap();
+            generator.definition(&mut out, key, definition);
+        }
+    }
+
+    for script in scripts(blueprint) {
+        writeln!(out).unwrap();
+        generator.script(&mut out, &script);
+    }
+
+    out
+}
+
+struct Generator<'a> {
+    blueprint: &'a Blueprint,
+    /// Names of definitions declared in the module.
+    names: BTreeMap<String, String>,
+    /// Names of constructors, by definition and index. Constructors share a same namespace
+    /// across types, so clashing ones are qualified by their type's name.
+    constructors: HashMap<(String, usize), String>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(blueprint: &'a Blueprint) -> Self {
+        let taken = [
+            well_known::LIST,
+            well_known::OPTION,
+            well_known::PAIR,
+            well_known::PAIRS,
+            well_known::FUZZER,
+            well_known::PRNG,
+        ];
+
+        let names = type_names(&blueprint.definitions)
+            .into_iter()
+            .filter(|(key, _)| {
+                let definition = blueprint
+                    .definitions
+                    .lookup(&Reference::new(key))
+                    .expect("named definition exists");
+                is_declared(key, &definition.annotated)
+            })
+            .map(|(key, name)| {
+                let name = if PRELUDE.contains(&name.as_str()) || taken.contains(&name.as_str()) {
+                    pascal_case(&key)
+                } else {
+                    name
+                };
+                (key, name)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mut constructors = vec![];
+        for (key, definition) in blueprint.definitions.iter() {
+            if let (Some(name), Some(variants)) =
+                (names.get(key), custom_type(&definition.annotated))
+            {
+                for (index, title) in constructor_titles(name, variants) {
+                    constructors.push(((key.to_string(), index), name.as_str(), title));
+                }
+            }
+        }
+
+        let mut occurrences = HashMap::new();
+        for (_, _, title) in constructors.iter() {
+            *occurrences.entry(title.clone()).or_insert(0) += 1;
+        }
+
+        let reserved = [
+            well_known::BOOL_CONSTRUCTORS,
+            well_known::OPTION_CONSTRUCTORS,
+            well_known::NEVER_CONSTRUCTORS,
+            well_known::ORDERING_CONSTRUCTORS,
+            well_known::PRNG_CONSTRUCTORS,
+            well_known::VOID_CONSTRUCTORS,
+            &[well_known::PAIR],
+        ]
+        .concat();
+
+        let constructors = constructors
+            .into_iter()
+            .map(|(id, tipo, title)| {
+                if occurrences[&title] > 1 || reserved.contains(&title.as_str()) {
+                    (id, format!("{tipo}{title}"))
+                } else {
+                    (id, title)
+                }
+            })
+            .collect();
+
+        Generator {
+            blueprint,
+            names,
+            constructors,
+        }
+    }
+
+    fn definition(&self, out: &mut String, key: &str, definition: &Annotated<Schema>) {
+        let name = &self.names[key];
+
+        doc_comment(out, "", definition.description.as_deref());
+
+        let Some(variants) = custom_type(&definition.annotated) else {
+            writeln!(
+                out,
+                "pub type {name} = {}",
+                self.schema_type(&definition.annotated)
+            )
+            .unwrap();
+            return;
+        };
+
+        let mut variants = variants.iter().collect::<Vec<_>>();
+        variants.sort_by_key(|variant| variant.annotated.index);
+
+        if let [variant] = variants[..] {
+            let fields = &variant.annotated.fields;
+            if variant.annotated.index == 0
+                && !fields.is_empty()
+                && self.constructors[&(key.to_string(), 0)] == *name
+                && field_names(fields).is_some()
+            {
+                writeln!(out, "pub type {name} {{").unwrap();
+                self.fields(out, "  ", fields);
+                writeln!(out, "}}").unwrap();
+                return;
+            }
+        }
+
+        writeln!(out, "pub type {name} {{").unwrap();
+
+        let mut variants = variants.into_iter().peekable();
+        for index in 0.. {
+            let Some(variant) = variants.next_if(|variant| variant.annotated.index <= index) else {
+                if variants.peek().is_none() {
+                    break;
+                }
+
+                // Aiken indexes constructors by order of declaration; fill in gaps in indexes
+                // with placeholders, so that the others keep their encoding.
+                writeln!(out, "  {name}Reserved{index}").unwrap();
+                continue;
+            };
+
+            doc_comment(out, "  ", variant.description.as_deref());
+
+            let constructor = &self.constructors[&(key.to_string(), variant.annotated.index)];
+            let fields = &variant.annotated.fields;
+
+            if fields.is_empty() {
+                writeln!(out, "  {constructor}").unwrap();
+            } else if field_names(fields).is_some() {
+                writeln!(out, "  {constructor} {{").unwrap();
+                self.fields(out, "    ", fields);
+                writeln!(out, "  }}").unwrap();
+            } else {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        self.declaration_type(&field.annotated, |data| self.data_type(data))
+                    })
+                    .collect::<Vec<_>>();
+                writeln!(out, "  {constructor}({})", fields.join(", ")).unwrap();
+            }
+        }
+
+        writeln!(out, "}}").unwrap();
+    }
+
+    fn fields(&self, out: &mut String, indent: &str, fields: &[Annotated<Declaration<Data>>]) {
+        for field in fields {
+            doc_comment(out, indent, field.description.as_deref());
+            writeln!(
+                out,
+                "{indent}{}: {},",
+                ident(field.title.as_deref().unwrap_or_default()),
+                self.declaration_type(&field.annotated, |data| self.data_type(data)),
+            )
+            .unwrap();
+        }
+    }
+
+    fn script(&self, out: &mut String, script: &Script) {
+        let (_, hash) = script.compiled_code_and_hash();
+
+        if script.validator.parameters.is_empty() {
+            doc_comment(out, "", script.validator.description.as_deref());
+            writeln!(out, "pub const {}_hash: ByteArray =", ident(script.title)).unwrap();
+            writeln!(out, "  #\"{hash}\"").unwrap();
+        } else {
+            writeln!(
+                out,
+                "// '{}' is parameterized: its hash depends on the parameters applied.",
+                script.title
+            )
+            .unwrap();
+        }
+
+        for (handler, validator) in script.handlers.iter() {
+            for (what, parameter) in [
+                ("Datum", &validator.datum),
+                ("Redeemer", &validator.redeemer),
+            ] {
+                let Some(parameter) = parameter else {
+                    continue;
+                };
+
+                writeln!(
+                    out,
+                    "\npub type {}{}{what} = {}",
+                    pascal_case(script.title),
+                    pascal_case(handler),
+                    self.declaration_type(&parameter.schema, |schema| self.schema_type(schema)),
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn reference_type(&self, reference: &Reference) -> String {
+        let key = reference.as_key();
+
+        if let Some(name) = self.names.get(&key) {
+            return name.clone();
+        }
+
+        if PRELUDE.contains(&key.as_str()) {
+            return key;
+        }
+
+        match self.blueprint.definitions.lookup(reference) {
+            Some(definition) => self.schema_type(&definition.annotated),
+            None => well_known::DATA.to_string(),
+        }
+    }
+
+    fn declaration_type<T>(
+        &self,
+        declaration: &Declaration<T>,
+        inline: impl FnOnce(&T) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => self.reference_type(reference),
+            Declaration::Inline(inner) => inline(inner),
+        }
+    }
+
+    fn schema_type(&self, schema: &Schema) -> String {
+        match schema {
+            Schema::Unit => well_known::VOID.to_string(),
+            Schema::Boolean => well_known::BOOL.to_string(),
+            Schema::Integer => well_known::INT.to_string(),
+            Schema::Bytes => well_known::BYTE_ARRAY.to_string(),
+            Schema::String => well_known::STRING.to_string(),
+            Schema::Pair(left, right) => format!(
+                "Pair<{}, {}>",
+                self.declaration_type(left, |schema| self.schema_type(schema)),
+                self.declaration_type(right, |schema| self.schema_type(schema)),
+            ),
+            Schema::List(items) => self.items_type(items, |schema| self.schema_type(schema)),
+            Schema::Data(data) => self.data_type(data),
+        }
+    }
+
+    fn data_type(&self, data: &Data) -> String {
+        match data {
+            Data::Integer(..) => well_known::INT.to_string(),
+            Data::Bytes(..) => well_known::BYTE_ARRAY.to_string(),
+            Data::List(items) => self.items_type(items, |data| self.data_type(data)),
+            Data::Map(keys, values) => format!(
+                "Pairs<{}, {}>",
+                self.declaration_type(keys, |data| self.data_type(data)),
+                self.declaration_type(values, |data| self.data_type(data)),
+            ),
+            Data::AnyOf(constructors) => match Shape::of(constructors) {
+                Shape::Bool { .. } => well_known::BOOL.to_string(),
+                Shape::Option { some, .. } => format!(
+                    "Option<{}>",
+                    self.declaration_type(&some.annotated.fields[0].annotated, |data| {
+                        self.data_type(data)
+                    })
+                ),
+                // Anonymous custom types can't be written out.
+                Shape::Single(..) | Shape::Union => well_known::DATA.to_string(),
+            },
+            Data::Opaque => well_known::DATA.to_string(),
+        }
+    }
+
+    fn items_type<T>(&self, items: &Items<T>, inline: impl Fn(&T) -> String) -> String {
+        match items {
+            Items::One(item) => format!("List<{}>", self.declaration_type(item, &inline)),
+            Items::Many(elems) => format!(
+                "({})",
+                elems
+                    .iter()
+                    .map(|elem| self.declaration_type(elem, &inline))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// Whether a definition gets declared in the module, as opposed to being written out where it's
+/// used. Instances of generic types (e.g. 'List$Int') have no name of their own, unless they're
+/// custom types.
+fn is_declared(key: &str, schema: &Schema) -> bool {
+    !PRELUDE.contains(&key) && (custom_type(schema).is_some() || !key.contains('$'))
+}
+
+/// Constructors of a schema that needs a custom type; booleans and options have their own.
+fn custom_type(schema: &Schema) -> Option<&[Annotated<Constructor>]> {
+    match schema {
+        Schema::Data(Data::AnyOf(constructors)) if !constructors.is_empty() => {
+            match Shape::of(constructors) {
+                Shape::Single(..) | Shape::Union => Some(constructors),
+                Shape::Bool { .. } | Shape::Option { .. } => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Titles of constructors, by index. An untitled single constructor is named after its type.
+fn constructor_titles<'a>(
+    name: &'a str,
+    variants: &'a [Annotated<Constructor>],
+) -> impl Iterator<Item = (usize, String)> + 'a {
+    variants.iter().map(move |variant| {
+        let title = if variants.len() == 1 && variant.title.is_none() {
+            name.to_string()
+        } else {
+            pascal_case(&constructor_title(variant))
+        };
+        (variant.annotated.index, title)
+    })
+}
+
+/// Turn any title into a valid snake_case identifier.
+fn ident(title: &str) -> String {
+    let mut ident = String::new();
+
+    let mut previous: Option<char> = None;
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase()
+                && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+            {
+                ident.push('_');
+            }
+            ident.push(c.to_ascii_lowercase());
+        } else if !ident.is_empty() && !ident.ends_with('_') {
+            ident.push('_');
+        }
+        previous = Some(c);
+    }
+
+    let ident = ident.trim_end_matches('_').to_string();
+
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{ident}")
+    } else if KEYWORDS.contains(&ident.as_str()) {
+        format!("{ident}_")
+    } else {
+        ident
+    }
+}
+
+fn doc_comment(out: &mut String, indent: &str, doc: Option<&str>) {
+    if let Some(doc) = doc.map(str::trim).filter(|doc| !doc.is_empty()) {
+        for line in doc.lines() {
+            writeln!(out, "{indent}/// {}", line.trim()).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestProject;
+
+    fn assert_stubs(blueprint: &str) -> String {
+        let blueprint: Blueprint = serde_json::from_str(blueprint).unwrap();
+
+        let stubs = generate(&blueprint);
+
+        let mut project = TestProject::new();
+        let module = project.parse(&stubs);
+        project.check(module);
+
+        stubs
+    }
+
+    #[test]
+    fn generate_gift_card() {
+        insta::assert_snapshot!(assert_stubs(include_str!(
+            "../../../../examples/gift_card/plutus.json"
+        )));
+    }
+
+    #[test]
+    fn generate_hello_world() {
+        insta::assert_snapshot!(assert_stubs(include_str!(
+            "../../../../examples/hello_world/plutus.json"
+        )));
+    }
+}
@@ -0,0 +1,179 @@
+//! Off-chain bindings generated from a project's blueprint, so that off-chain code stays in sync
+//! with the validators' interfaces; and Aiken stubs generated from a foreign blueprint, to
+//! compose with validators built elsewhere.
+
+pub mod aiken;
+pub mod python;
+pub mod rust;
+pub mod scaffold;
+pub mod typescript;
+
+use crate::blueprint::{
+    definitions::Definitions,
+    schema::{Annotated, Constructor, Data, Declaration},
+    validator::Validator,
+    Blueprint,
+};
+use aiken_lang::plutus_version::PlutusVersion;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use uplc::ast::SerializableProgram;
+
+/// A compiled validator, with all its handlers. Handlers of a same validator share their
+/// parameters and compiled code.
+pub(crate) struct Script<'a> {
+    /// The validator's title, as '{module}.{validator}'.
+    pub title: &'a str,
+    pub validator: &'a Validator,
+    pub handlers: Vec<(&'a str, &'a Validator)>,
+}
+
+impl Script<'_> {
+    pub fn plutus_version(&self) -> PlutusVersion {
+        match self.validator.program {
+            SerializableProgram::PlutusV1Program(..) => PlutusVersion::V1,
+            SerializableProgram::PlutusV2Program(..) => PlutusVersion::V2,
+            SerializableProgram::PlutusV3Program(..) => PlutusVersion::V3,
+        }
+    }
+
+    pub fn compiled_code_and_hash(&self) -> (String, String) {
+        let (compiled_code, hash) = self.validator.program.compiled_code_and_hash();
+        (compiled_code, hash.to_string())
+    }
+}
+
+/// Group the validators of a blueprint by script, in order of appearance. Fallback handlers
+/// ('else') are left out since they don't have any interface of their own.
+pub(crate) fn scripts(blueprint: &Blueprint) -> Vec<Script<'_>> {
+    let mut scripts: Vec<Script> = vec![];
+
+    for validator in &blueprint.validators {
+        let (title, handler) = match validator.title.rsplit_once('.') {
+            Some((title, handler)) if title.contains('.') => (title, handler),
+            _ => (validator.title.as_str(), ""),
+        };
+
+        let ix = match scripts.iter().position(|script| script.title == title) {
+            Some(ix) => ix,
+            None => {
+                scripts.push(Script {
+                    title,
+                    validator,
+                    handlers: vec![],
+                });
+                scripts.len() - 1
+            }
+        };
+
+        if handler != "else" {
+            scripts[ix].handlers.push((handler, validator));
+        }
+    }
+
+    scripts
+}
+
+/// Identifiers for each definition of a blueprint, in PascalCase. Definitions are named after
+/// their type (and type arguments) alone when that is unambiguous, and after their module too
+/// otherwise. For example, 'Option$cardano/address/Credential' becomes 'OptionCredential'.
+pub(crate) fn type_names<T>(definitions: &Definitions<T>) -> BTreeMap<String, String> {
+    let module = Regex::new(r"([a-z0-9_-]+)/").unwrap();
+
+    let short = |key: &str| module.replace_all(key, "").replace(['$', '_'], "");
+
+    let mut occurrences = HashMap::new();
+    for (key, _) in definitions.iter() {
+        *occurrences.entry(short(key)).or_insert(0) += 1;
+    }
+
+    definitions
+        .iter()
+        .map(|(key, _)| {
+            let name = short(key);
+
+            let name = if occurrences[&name] > 1 {
+                module
+                    .replace_all(key, |caps: &regex::Captures| pascal_case(&caps[1]))
+                    .replace(['$', '_'], "")
+            } else {
+                name
+            };
+
+            (key.to_string(), name)
+        })
+        .collect()
+}
+
+/// Turn any title (e.g. 'oneshot.gift_card') into a PascalCase identifier (e.g. 'OneshotGiftCard').
+pub(crate) fn pascal_case(s: &str) -> String {
+    s.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// How constructors are represented off-chain. Booleans and options get a native representation,
+/// types with a single constructor are represented by that constructor alone.
+pub(crate) enum Shape<'a> {
+    Bool {
+        false_ix: usize,
+        true_ix: usize,
+    },
+    Option {
+        some: &'a Annotated<Constructor>,
+        none_ix: usize,
+    },
+    Single(&'a Annotated<Constructor>),
+    Union,
+}
+
+impl<'a> Shape<'a> {
+    pub fn of(constructors: &'a [Annotated<Constructor>]) -> Self {
+        let find = |title: &str, arity: usize| {
+            constructors.iter().find(|constructor| {
+                constructor.title.as_deref() == Some(title)
+                    && constructor.annotated.fields.len() == arity
+            })
+        };
+
+        if constructors.len() == 2 {
+            if let (Some(f), Some(t)) = (find("False", 0), find("True", 0)) {
+                return Shape::Bool {
+                    false_ix: f.annotated.index,
+                    true_ix: t.annotated.index,
+                };
+            }
+
+            if let (Some(some), Some(none)) = (find("Some", 1), find("None", 0)) {
+                return Shape::Option {
+                    some,
+                    none_ix: none.annotated.index,
+                };
+            }
+        }
+
+        match constructors {
+            [constructor] => Shape::Single(constructor),
+            _ => Shape::Union,
+        }
+    }
+}
+
+pub(crate) fn constructor_title(constructor: &Annotated<Constructor>) -> String {
+    constructor
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Constr{}", constructor.annotated.index))
+}
+
+/// Names of fields, when they all have one.
+pub(crate) fn field_names(fields: &[Annotated<Declaration<Data>>]) -> Option<Vec<&str>> {
+    fields.iter().map(|field| field.title.as_deref()).collect()
+}
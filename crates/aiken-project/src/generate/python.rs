@@ -0,0 +1,510 @@
+//! Python bindings, on top of PyCardano. Every constructor is a `PlutusData` dataclass, so values
+//! can be used as datums and redeemers as-is, and serialised with `to_cbor_hex` / `from_cbor`.
+
+use super::{constructor_title, field_names, pascal_case, scripts, type_names, Script};
+use crate::blueprint::{
+    definitions::{Definitions, Reference},
+    parameter::Parameter,
+    schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+    Blueprint,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+const PRELUDE: &str = include_str!("python/prelude.py");
+
+const RESERVED: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Names already taken by the prelude.
+const TAKEN: &[&str] = &[
+    "Address",
+    "Datum",
+    "DeserializeException",
+    "Dict",
+    "List",
+    "Network",
+    "PlutusData",
+    "Redeemer",
+    "ScriptHash",
+    "T",
+    "Tuple",
+    "Type",
+    "TypeVar",
+    "Union",
+];
+
+/// Generate a Python module from a blueprint, with:
+///
+/// - a PyCardano dataclass for each constructor, and a decoder for each definition;
+/// - a class for each validator, holding its compiled code and hash, and helpers to encode its
+///   parameters and build its datum and redeemers.
+///
+/// Definitions come in order of dependencies, since Python evaluates type annotations eagerly.
+pub fn generate(blueprint: &Blueprint) -> String {
+    let order = order(&blueprint.definitions);
+
+    let generator = Generator {
+        names: type_names(&blueprint.definitions)
+            .into_iter()
+            .map(|(key, name)| {
+                if TAKEN.contains(&name.as_str()) {
+                    (key, format!("{name}_"))
+                } else {
+                    (key, name)
+                }
+            })
+            .collect(),
+        position: order
+            .iter()
+            .enumerate()
+            .map(|(ix, key)| (key.to_string(), ix))
+            .collect(),
+    };
+
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "# Generated by Aiken from the blueprint of '{}' (v{}). Do not edit by hand.",
+        blueprint.preamble.title, blueprint.preamble.version
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    out.push_str(PRELUDE);
+
+    for key in order.iter() {
+        if let Some(definition) = blueprint.definitions.lookup(&Reference::new(key)) {
+            writeln!(out).unwrap();
+            writeln!(out).unwrap();
+            generator.definition(&mut out, key, definition);
+        }
+    }
+
+    for script in scripts(blueprint) {
+        writeln!(out).unwrap();
+        writeln!(out).unwrap();
+        generator.script(&mut out, &script);
+    }
+
+    out
+}
+
+struct Generator {
+    names: BTreeMap<String, String>,
+    position: BTreeMap<String, usize>,
+}
+
+impl Generator {
+    fn definition(&self, out: &mut String, key: &str, definition: &Annotated<Schema>) {
+        let name = &self.names[key];
+
+        let doc = definition
+            .description
+            .as_deref()
+            .or(definition.title.as_deref());
+
+        let constructors = match &definition.annotated {
+            Schema::Data(Data::AnyOf(constructors)) => constructors,
+            schema => {
+                comment(out, doc);
+                writeln!(out, "{name} = {}", self.schema_type(key, schema)).unwrap();
+                return;
+            }
+        };
+
+        let classes = match &constructors[..] {
+            [constructor] => {
+                self.class(out, key, name, doc, constructor);
+                vec![name.to_string()]
+            }
+            _ => {
+                let classes = constructors
+                    .iter()
+                    .map(|constructor| {
+                        let class =
+                            format!("{name}{}", pascal_case(&constructor_title(constructor)));
+                        self.class(
+                            out,
+                            key,
+                            &class,
+                            constructor.description.as_deref(),
+                            constructor,
+                        );
+                        writeln!(out).unwrap();
+                        writeln!(out).unwrap();
+                        class
+                    })
+                    .collect::<Vec<_>>();
+
+                comment(out, doc);
+                writeln!(out, "{name} = Union[{}]", classes.join(", ")).unwrap();
+                classes
+            }
+        };
+
+        writeln!(out).unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "def decode_{}(cbor: Union[bytes, str]) -> {name}:",
+            snake_case(name)
+        )
+        .unwrap();
+        match &classes[..] {
+            [class] => writeln!(out, "    return decode(cbor, ({class},))").unwrap(),
+            _ => writeln!(out, "    return decode(cbor, ({}))", classes.join(", ")).unwrap(),
+        }
+    }
+
+    fn class(
+        &self,
+        out: &mut String,
+        key: &str,
+        name: &str,
+        doc: Option<&str>,
+        constructor: &Annotated<Constructor>,
+    ) {
+        let fields = &constructor.annotated.fields;
+
+        writeln!(out, "@dataclass").unwrap();
+        writeln!(out, "class {name}(PlutusData):").unwrap();
+        docstring(out, doc);
+        writeln!(out, "    CONSTR_ID = {}", constructor.annotated.index).unwrap();
+
+        let names: Vec<String> = match field_names(fields) {
+            Some(names) => names.into_iter().map(ident).collect(),
+            None => (0..fields.len()).map(|ix| format!("field{ix}")).collect(),
+        };
+
+        for (name, field) in names.iter().zip(fields) {
+            writeln!(out, "    {name}: {}", self.field_type(key, field)).unwrap();
+        }
+    }
+
+    fn script(&self, out: &mut String, script: &Script) {
+        let (compiled_code, hash) = script.compiled_code_and_hash();
+
+        let name = pascal_case(script.title);
+
+        writeln!(out, "class {name}:").unwrap();
+        docstring(out, script.validator.description.as_deref());
+        writeln!(out, "    TITLE = \"{}\"", script.title).unwrap();
+        writeln!(
+            out,
+            "    PLUTUS_VERSION = {}",
+            format!("{:?}", script.plutus_version()).trim_start_matches('V')
+        )
+        .unwrap();
+        writeln!(out, "    COMPILED_CODE = \"{compiled_code}\"").unwrap();
+
+        if script.validator.parameters.is_empty() {
+            writeln!(out, "    HASH = \"{hash}\"").unwrap();
+            writeln!(out).unwrap();
+            writeln!(out, "    @staticmethod").unwrap();
+            writeln!(out, "    def address(network: Network) -> Address:").unwrap();
+            writeln!(out, "        return script_address(\"{hash}\", network)").unwrap();
+        } else {
+            writeln!(
+                out,
+                "    # The hash of the script *before* applying parameters."
+            )
+            .unwrap();
+            writeln!(out, "    UNAPPLIED_HASH = \"{hash}\"").unwrap();
+            writeln!(out).unwrap();
+            let (args, values) = self.arguments(&script.validator.parameters, "param");
+            writeln!(out, "    @staticmethod").unwrap();
+            writeln!(out, "    def params({args}) -> List[str]:").unwrap();
+            writeln!(
+                out,
+                "        \"\"\"Encode parameters, in order, as CBOR to be applied to the compiled code.\"\"\""
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "        return [{}]",
+                values
+                    .iter()
+                    .map(|value| format!("encode({value})"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .unwrap();
+        }
+
+        for (handler, validator) in script.handlers.iter() {
+            for (what, parameter, tipo) in [
+                ("datum", &validator.datum, "Datum"),
+                ("redeemer", &validator.redeemer, "Redeemer"),
+            ] {
+                let Some(parameter) = parameter.as_ref() else {
+                    continue;
+                };
+
+                let (args, values) = self.arguments(std::slice::from_ref(parameter), what);
+
+                let function = if handler.is_empty() {
+                    what.to_string()
+                } else {
+                    format!("{handler}_{what}")
+                };
+
+                let value = if what == "redeemer" {
+                    format!("Redeemer({})", values[0])
+                } else {
+                    values[0].to_string()
+                };
+
+                writeln!(out).unwrap();
+                writeln!(out, "    @staticmethod").unwrap();
+                writeln!(out, "    def {function}({args}) -> {tipo}:").unwrap();
+                writeln!(out, "        return {value}").unwrap();
+            }
+        }
+    }
+
+    /// Typed arguments of a helper, and their names.
+    fn arguments(&self, parameters: &[Parameter], default: &str) -> (String, Vec<String>) {
+        let mut args = vec![];
+        let mut values = vec![];
+
+        for (ix, parameter) in parameters.iter().enumerate() {
+            let name = match &parameter.title {
+                Some(title) => ident(title),
+                None if parameters.len() == 1 => default.to_string(),
+                None => format!("{default}{ix}"),
+            };
+
+            let tipo = match &parameter.schema {
+                Declaration::Referenced(reference) => self.name(reference).to_string(),
+                Declaration::Inline(schema) => self.schema_type("", schema),
+            };
+
+            args.push(format!("{name}: {tipo}"));
+            values.push(name);
+        }
+
+        (args.join(", "), values)
+    }
+
+    fn name(&self, reference: &Reference) -> &str {
+        &self.names[&reference.as_key()]
+    }
+
+    /// Definitions not yet defined where they're referred to (i.e. in recursive types) are given
+    /// as forward references.
+    fn declaration_type<T>(
+        &self,
+        key: &str,
+        declaration: &Declaration<T>,
+        inline: impl FnOnce(&T) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => {
+                let name = self.name(reference);
+                match (
+                    self.position.get(&reference.as_key()),
+                    self.position.get(key),
+                ) {
+                    (Some(referenced), Some(current)) if referenced >= current => {
+                        format!("\"{name}\"")
+                    }
+                    _ => name.to_string(),
+                }
+            }
+            Declaration::Inline(inner) => inline(inner),
+        }
+    }
+
+    fn schema_type(&self, key: &str, schema: &Schema) -> String {
+        match schema {
+            Schema::Integer => "int".to_string(),
+            Schema::Bytes | Schema::String => "bytes".to_string(),
+            Schema::List(Items::One(item)) => format!(
+                "List[{}]",
+                self.declaration_type(key, item, |schema| self.schema_type(key, schema))
+            ),
+            Schema::Pair(..) | Schema::List(Items::Many(..)) => "List[Datum]".to_string(),
+            Schema::Data(data) => self.data_type(key, data),
+            Schema::Unit | Schema::Boolean => "Datum".to_string(),
+        }
+    }
+
+    /// Tuples, as well as constructors defined inline (i.e. not as a definition of their own),
+    /// are left as any data.
+    fn data_type(&self, key: &str, data: &Data) -> String {
+        match data {
+            Data::Integer(..) => "int".to_string(),
+            Data::Bytes(..) => "bytes".to_string(),
+            Data::List(Items::One(item)) => format!(
+                "List[{}]",
+                self.declaration_type(key, item, |data| self.data_type(key, data))
+            ),
+            Data::List(Items::Many(..)) => "List[Datum]".to_string(),
+            Data::Map(keys, values) => format!(
+                "Dict[{}, {}]",
+                self.declaration_type(key, keys, |data| self.data_type(key, data)),
+                self.declaration_type(key, values, |data| self.data_type(key, data)),
+            ),
+            Data::AnyOf(..) | Data::Opaque => "Datum".to_string(),
+        }
+    }
+
+    fn field_type(&self, key: &str, field: &Annotated<Declaration<Data>>) -> String {
+        self.declaration_type(key, &field.annotated, |data| self.data_type(key, data))
+    }
+}
+
+/// Keys of all definitions, each coming after the definitions it refers to (except for cycles).
+fn order(definitions: &Definitions<Annotated<Schema>>) -> Vec<String> {
+    fn visit(
+        definitions: &Definitions<Annotated<Schema>>,
+        key: &str,
+        visited: &mut BTreeSet<String>,
+        order: &mut Vec<String>,
+    ) {
+        if !visited.insert(key.to_string()) {
+            return;
+        }
+
+        if let Some(definition) = definitions.lookup(&Reference::new(key)) {
+            let mut references = vec![];
+            schema_references(&definition.annotated, &mut references);
+            for reference in references {
+                visit(definitions, &reference.as_key(), visited, order);
+            }
+        }
+
+        order.push(key.to_string());
+    }
+
+    let mut visited = BTreeSet::new();
+    let mut order = vec![];
+
+    for (key, _) in definitions.iter() {
+        visit(definitions, key, &mut visited, &mut order);
+    }
+
+    order
+}
+
+fn schema_references<'a>(schema: &'a Schema, references: &mut Vec<&'a Reference>) {
+    match schema {
+        Schema::Pair(left, right) => {
+            declaration_references(left, references, schema_references);
+            declaration_references(right, references, schema_references);
+        }
+        Schema::List(items) => items_references(items, references, schema_references),
+        Schema::Data(data) => data_references(data, references),
+        Schema::Unit | Schema::Boolean | Schema::Integer | Schema::Bytes | Schema::String => {}
+    }
+}
+
+fn data_references<'a>(data: &'a Data, references: &mut Vec<&'a Reference>) {
+    match data {
+        Data::List(items) => items_references(items, references, data_references),
+        Data::Map(keys, values) => {
+            declaration_references(keys, references, data_references);
+            declaration_references(values, references, data_references);
+        }
+        Data::AnyOf(constructors) => {
+            for constructor in constructors {
+                for field in &constructor.annotated.fields {
+                    declaration_references(&field.annotated, references, data_references);
+                }
+            }
+        }
+        Data::Integer(..) | Data::Bytes(..) | Data::Opaque => {}
+    }
+}
+
+fn items_references<'a, T>(
+    items: &'a Items<T>,
+    references: &mut Vec<&'a Reference>,
+    inline: fn(&'a T, &mut Vec<&'a Reference>),
+) {
+    match items {
+        Items::One(item) => declaration_references(item, references, inline),
+        Items::Many(items) => {
+            for item in items {
+                declaration_references(item, references, inline);
+            }
+        }
+    }
+}
+
+fn declaration_references<'a, T>(
+    declaration: &'a Declaration<T>,
+    references: &mut Vec<&'a Reference>,
+    inline: fn(&'a T, &mut Vec<&'a Reference>),
+) {
+    match declaration {
+        Declaration::Referenced(reference) => references.push(reference),
+        Declaration::Inline(inner) => inline(inner, references),
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut s = String::new();
+    for (ix, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && ix > 0 {
+            s.push('_');
+        }
+        s.push(c.to_ascii_lowercase());
+    }
+    s
+}
+
+fn ident(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+fn comment(out: &mut String, doc: Option<&str>) {
+    if let Some(doc) = doc.map(str::trim).filter(|doc| !doc.is_empty()) {
+        for line in doc.lines() {
+            writeln!(out, "# {}", line.trim()).unwrap();
+        }
+    }
+}
+
+fn docstring(out: &mut String, doc: Option<&str>) {
+    if let Some(doc) = doc.map(str::trim).filter(|doc| !doc.is_empty()) {
+        let doc = doc
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join("\n    ")
+            .replace("\"\"\"", "\\\"\\\"\\\"");
+
+        if doc.contains('\n') {
+            writeln!(out, "    \"\"\"{doc}\n    \"\"\"").unwrap();
+        } else {
+            writeln!(out, "    \"\"\"{doc}\"\"\"").unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_gift_card() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/gift_card/plutus.json"))
+                .unwrap();
+
+        insta::assert_snapshot!(generate(&blueprint));
+    }
+}
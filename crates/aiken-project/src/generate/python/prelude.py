@@ -0,0 +1,36 @@
+from dataclasses import dataclass
+from typing import Dict, List, Tuple, Type, TypeVar, Union
+
+import cbor2
+from pycardano import (
+    Address,
+    Datum,
+    DeserializeException,
+    Network,
+    PlutusData,
+    Redeemer,
+    ScriptHash,
+)
+from pycardano.serialization import default_encoder
+
+T = TypeVar("T", bound=PlutusData)
+
+
+def encode(value: Datum) -> str:
+    """Serialise Plutus data to CBOR, as a base16-encoded string."""
+    return cbor2.dumps(value, default=default_encoder).hex()
+
+
+def decode(cbor: Union[bytes, str], classes: Tuple[Type[T], ...]) -> T:
+    """Deserialise Plutus data from CBOR, as the first of the given classes that matches."""
+    for cls in classes:
+        try:
+            return cls.from_cbor(cbor)
+        except (DeserializeException, TypeError, ValueError):
+            continue
+    raise DeserializeException(f"Expected one of {[cls.__name__ for cls in classes]}")
+
+
+def script_address(hash: str, network: Network) -> Address:
+    """The enterprise address (i.e. without delegation part) of a script, from its hash."""
+    return Address(payment_part=ScriptHash(bytes.fromhex(hash)), network=network)
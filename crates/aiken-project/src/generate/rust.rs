@@ -0,0 +1,568 @@
+//! Rust bindings, built on top of the `uplc` crate (and thus, Pallas). Every type can be converted
+//! to and from Plutus data, and (de)serialised with serde.
+
+use super::{constructor_title, field_names, pascal_case, scripts, type_names, Script, Shape};
+use crate::blueprint::{
+    definitions::Reference,
+    parameter::Parameter,
+    schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+    Blueprint,
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+const PRELUDE: &str = include_str!("rust/prelude.rs");
+
+const DERIVE: &str = "#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]";
+
+const RESERVED: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "try", "type", "unsafe", "use", "where",
+    "while", "yield",
+];
+
+/// Names already taken by the prelude, or by Rust's.
+const TAKEN: &[&str] = &[
+    "Box",
+    "Bytes",
+    "DeBruijn",
+    "DecodeError",
+    "Deserialize",
+    "Deserializer",
+    "FromPlutusData",
+    "Map",
+    "Option",
+    "PlutusData",
+    "Program",
+    "Result",
+    "SerializableProgram",
+    "Serialize",
+    "Serializer",
+    "String",
+    "ToPlutusData",
+    "Vec",
+];
+
+/// Generate a self-contained Rust module from a blueprint, with:
+///
+/// - a type for each definition, convertible to and from Plutus data;
+/// - a struct for each validator, holding its compiled code and hash, a function to apply its
+///   parameters and helpers to build its datum and redeemers.
+///
+/// The module depends on `uplc` and `serde` (with the 'derive' feature).
+pub fn generate(blueprint: &Blueprint) -> String {
+    let generator = Generator {
+        names: type_names(&blueprint.definitions)
+            .into_iter()
+            .map(|(key, name)| {
+                if TAKEN.contains(&name.as_str()) {
+                    (key, format!("{name}_"))
+                } else {
+                    (key, name)
+                }
+            })
+            .collect(),
+    };
+
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "// Generated by Aiken from the blueprint of '{}' (v{}). Do not edit by hand.",
+        blueprint.preamble.title, blueprint.preamble.version
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "#![allow(clippy::all, dead_code, non_camel_case_types, unused_imports)]"
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    out.push_str(PRELUDE);
+
+    for (key, definition) in blueprint.definitions.iter() {
+        writeln!(out).unwrap();
+        generator.definition(&mut out, key, definition);
+    }
+
+    for script in scripts(blueprint) {
+        writeln!(out).unwrap();
+        generator.script(&mut out, &script);
+    }
+
+    out
+}
+
+struct Generator {
+    names: BTreeMap<String, String>,
+}
+
+impl Generator {
+    fn definition(&self, out: &mut String, key: &str, definition: &Annotated<Schema>) {
+        let name = &self.names[key];
+
+        doc_comment(
+            out,
+            "",
+            definition
+                .description
+                .as_deref()
+                .or(definition.title.as_deref()),
+        );
+
+        let constructors = match &definition.annotated {
+            Schema::Data(Data::AnyOf(constructors)) => constructors,
+            schema => {
+                writeln!(out, "pub type {name} = {};", self.schema_type(schema)).unwrap();
+                return;
+            }
+        };
+
+        match Shape::of(constructors) {
+            Shape::Bool {
+                false_ix: 0,
+                true_ix: 1,
+            } => {
+                writeln!(out, "pub type {name} = bool;").unwrap();
+            }
+            Shape::Option { some, none_ix: 1 } if some.annotated.index == 0 => {
+                writeln!(
+                    out,
+                    "pub type {name} = Option<{}>;",
+                    self.field_type(key, &some.annotated.fields[0])
+                )
+                .unwrap();
+            }
+            Shape::Single(constructor) => self.structure(out, key, name, constructor),
+            _ => self.enumeration(out, key, name, constructors),
+        }
+    }
+
+    fn structure(
+        &self,
+        out: &mut String,
+        key: &str,
+        name: &str,
+        constructor: &Annotated<Constructor>,
+    ) {
+        let fields = &constructor.annotated.fields;
+        let names: Option<Vec<String>> =
+            field_names(fields).map(|names| names.into_iter().map(ident).collect());
+
+        writeln!(out, "{DERIVE}").unwrap();
+        match &names {
+            _ if fields.is_empty() => writeln!(out, "pub struct {name};").unwrap(),
+            Some(names) => {
+                writeln!(out, "pub struct {name} {{").unwrap();
+                for (name, field) in names.iter().zip(fields) {
+                    doc_comment(out, "    ", field.description.as_deref());
+                    writeln!(out, "    pub {name}: {},", self.field_type(key, field)).unwrap();
+                }
+                writeln!(out, "}}").unwrap();
+            }
+            None => writeln!(
+                out,
+                "pub struct {name}({});",
+                fields
+                    .iter()
+                    .map(|field| format!("pub {}", self.field_type(key, field)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .unwrap(),
+        }
+
+        let (pattern, values, decoded) = bindings(name, names.as_deref(), fields.len());
+        let ix = constructor.annotated.index;
+
+        self.conversions(
+            out,
+            name,
+            &[(
+                format!("let {pattern} = self;\n{}", encode(ix, &values)),
+                format!("({ix}, [{}]) => Ok({decoded}),", values.join(", ")),
+            )],
+        );
+    }
+
+    fn enumeration(
+        &self,
+        out: &mut String,
+        key: &str,
+        name: &str,
+        constructors: &[Annotated<Constructor>],
+    ) {
+        let mut arms = vec![];
+
+        writeln!(out, "{DERIVE}").unwrap();
+        writeln!(out, "pub enum {name} {{").unwrap();
+        for constructor in constructors {
+            let title = pascal_case(&constructor_title(constructor));
+            let fields = &constructor.annotated.fields;
+            let names: Option<Vec<String>> =
+                field_names(fields).map(|names| names.into_iter().map(ident).collect());
+
+            doc_comment(out, "    ", constructor.description.as_deref());
+            match &names {
+                _ if fields.is_empty() => writeln!(out, "    {title},").unwrap(),
+                Some(names) => {
+                    writeln!(out, "    {title} {{").unwrap();
+                    for (name, field) in names.iter().zip(fields) {
+                        writeln!(out, "        {name}: {},", self.field_type(key, field)).unwrap();
+                    }
+                    writeln!(out, "    }},").unwrap();
+                }
+                None => writeln!(
+                    out,
+                    "    {title}({}),",
+                    fields
+                        .iter()
+                        .map(|field| self.field_type(key, field))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+                .unwrap(),
+            }
+
+            let (pattern, values, decoded) =
+                bindings(&format!("{name}::{title}"), names.as_deref(), fields.len());
+            let ix = constructor.annotated.index;
+
+            arms.push((
+                format!("{pattern} => {}", encode(ix, &values)),
+                format!("({ix}, [{}]) => Ok({decoded}),", values.join(", ")),
+            ));
+        }
+        writeln!(out, "}}").unwrap();
+
+        self.conversions(
+            out,
+            name,
+            &arms
+                .into_iter()
+                .map(|(to, from)| (format!("{to},"), from))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    /// Implementations of 'ToPlutusData' and 'FromPlutusData', from the arms of each constructor,
+    /// along with the standard 'From' and 'TryFrom' conversions they enable. With a single
+    /// constructor, the encoding side isn't an arm but a whole body.
+    fn conversions(&self, out: &mut String, name: &str, arms: &[(String, String)]) {
+        let indent = |s: &str, n: usize| s.replace('\n', &format!("\n{}", " ".repeat(n)));
+
+        writeln!(out).unwrap();
+        writeln!(out, "impl ToPlutusData for {name} {{").unwrap();
+        writeln!(out, "    fn to_plutus_data(&self) -> PlutusData {{").unwrap();
+        if let [(to, _)] = arms {
+            writeln!(out, "        {}", indent(to, 8)).unwrap();
+        } else {
+            writeln!(out, "        match self {{").unwrap();
+            for (to, _) in arms {
+                writeln!(out, "            {}", indent(to, 12)).unwrap();
+            }
+            writeln!(out, "        }}").unwrap();
+        }
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+
+        writeln!(out).unwrap();
+        writeln!(out, "impl FromPlutusData for {name} {{").unwrap();
+        writeln!(
+            out,
+            "    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {{"
+        )
+        .unwrap();
+        writeln!(out, "        match constr(data, \"{name}\")? {{").unwrap();
+        for (_, from) in arms {
+            writeln!(out, "            {from}").unwrap();
+        }
+        writeln!(out, "            _ => unexpected(\"{name}\", data),").unwrap();
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+
+        writeln!(out).unwrap();
+        writeln!(out, "impl From<{name}> for PlutusData {{").unwrap();
+        writeln!(out, "    fn from(value: {name}) -> Self {{").unwrap();
+        writeln!(out, "        value.to_plutus_data()").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+
+        for (data, reference) in [("&PlutusData", "data"), ("PlutusData", "&data")] {
+            writeln!(out).unwrap();
+            writeln!(out, "impl TryFrom<{data}> for {name} {{").unwrap();
+            writeln!(out, "    type Error = DecodeError;").unwrap();
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "    fn try_from(data: {data}) -> Result<Self, DecodeError> {{"
+            )
+            .unwrap();
+            writeln!(out, "        Self::from_plutus_data({reference})").unwrap();
+            writeln!(out, "    }}").unwrap();
+            writeln!(out, "}}").unwrap();
+        }
+    }
+
+    fn script(&self, out: &mut String, script: &Script) {
+        let (compiled_code, hash) = script.compiled_code_and_hash();
+
+        let name = pascal_case(script.title);
+
+        doc_comment(out, "", script.validator.description.as_deref());
+        writeln!(out, "pub struct {name};").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "impl {name} {{").unwrap();
+        writeln!(
+            out,
+            "    pub const TITLE: &'static str = \"{}\";",
+            script.title
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "    pub const PLUTUS_VERSION: u8 = {};",
+            format!("{:?}", script.plutus_version()).trim_start_matches('V')
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "    pub const COMPILED_CODE: &'static str = \"{compiled_code}\";"
+        )
+        .unwrap();
+
+        let (args, values) = self.arguments(&script.validator.parameters, "param");
+
+        if script.validator.parameters.is_empty() {
+            writeln!(out, "    pub const HASH: &'static str = \"{hash}\";").unwrap();
+            writeln!(out).unwrap();
+            writeln!(out, "    /// The script, ready to be used.").unwrap();
+        } else {
+            writeln!(
+                out,
+                "    /// The hash of the script *before* applying parameters."
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "    pub const UNAPPLIED_HASH: &'static str = \"{hash}\";"
+            )
+            .unwrap();
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "    /// The script, with its parameters applied in order."
+            )
+            .unwrap();
+        }
+        writeln!(out, "    pub fn script({args}) -> SerializableProgram {{").unwrap();
+        writeln!(
+            out,
+            "        apply_params(Self::PLUTUS_VERSION, Self::COMPILED_CODE, vec![{values}])"
+        )
+        .unwrap();
+        writeln!(out, "    }}").unwrap();
+
+        for (handler, validator) in script.handlers.iter() {
+            for (what, parameter) in [
+                ("datum", &validator.datum),
+                ("redeemer", &validator.redeemer),
+            ] {
+                let Some(parameter) = parameter.as_ref() else {
+                    continue;
+                };
+
+                let (args, values) = self.arguments(std::slice::from_ref(parameter), what);
+
+                let function = if handler.is_empty() {
+                    what.to_string()
+                } else {
+                    format!("{handler}_{what}")
+                };
+
+                writeln!(out).unwrap();
+                writeln!(out, "    pub fn {function}({args}) -> PlutusData {{").unwrap();
+                writeln!(out, "        {values}").unwrap();
+                writeln!(out, "    }}").unwrap();
+            }
+        }
+
+        writeln!(out, "}}").unwrap();
+    }
+
+    /// Typed arguments of a helper, and their values as Plutus data.
+    fn arguments(&self, parameters: &[Parameter], default: &str) -> (String, String) {
+        let mut args = vec![];
+        let mut values = vec![];
+
+        for (ix, parameter) in parameters.iter().enumerate() {
+            let name = match &parameter.title {
+                Some(title) => ident(title),
+                None if parameters.len() == 1 => default.to_string(),
+                None => format!("{default}{ix}"),
+            };
+
+            let tipo = match &parameter.schema {
+                Declaration::Referenced(reference) => self.name(reference).to_string(),
+                Declaration::Inline(schema) => self.schema_type(schema),
+            };
+
+            args.push(format!("{name}: &{tipo}"));
+            values.push(format!("{name}.to_plutus_data()"));
+        }
+
+        (args.join(", "), values.join(", "))
+    }
+
+    fn name(&self, reference: &Reference) -> &str {
+        &self.names[&reference.as_key()]
+    }
+
+    fn declaration_type<T>(
+        &self,
+        declaration: &Declaration<T>,
+        inline: impl FnOnce(&T) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => self.name(reference).to_string(),
+            Declaration::Inline(inner) => inline(inner),
+        }
+    }
+
+    fn schema_type(&self, schema: &Schema) -> String {
+        match schema {
+            Schema::Unit => "()".to_string(),
+            Schema::Boolean => "bool".to_string(),
+            Schema::Integer => "i128".to_string(),
+            Schema::Bytes => "Bytes".to_string(),
+            Schema::String => "String".to_string(),
+            Schema::Pair(left, right) => format!(
+                "({}, {})",
+                self.declaration_type(left, |schema| self.schema_type(schema)),
+                self.declaration_type(right, |schema| self.schema_type(schema)),
+            ),
+            Schema::List(items) => self.items_type(items, |schema| self.schema_type(schema)),
+            Schema::Data(data) => self.data_type(data),
+        }
+    }
+
+    /// Constructors defined inline (i.e. not as a definition of their own) have no name to refer
+    /// to, so they're left as opaque data.
+    fn data_type(&self, data: &Data) -> String {
+        match data {
+            Data::Integer(..) => "i128".to_string(),
+            Data::Bytes(..) => "Bytes".to_string(),
+            Data::List(items) => self.items_type(items, |data| self.data_type(data)),
+            Data::Map(keys, values) => format!(
+                "Map<{}, {}>",
+                self.declaration_type(keys, |data| self.data_type(data)),
+                self.declaration_type(values, |data| self.data_type(data)),
+            ),
+            Data::AnyOf(..) | Data::Opaque => "PlutusData".to_string(),
+        }
+    }
+
+    fn items_type<T>(&self, items: &Items<T>, inline: impl Fn(&T) -> String) -> String {
+        match items {
+            Items::One(item) => format!("Vec<{}>", self.declaration_type(item, &inline)),
+            Items::Many(items) if (2..=6).contains(&items.len()) => format!(
+                "({})",
+                items
+                    .iter()
+                    .map(|item| self.declaration_type(item, &inline))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Items::Many(..) => "PlutusData".to_string(),
+        }
+    }
+
+    /// Fields referring to the type being defined are boxed, to allow recursive types.
+    fn field_type(&self, key: &str, field: &Annotated<Declaration<Data>>) -> String {
+        match &field.annotated {
+            Declaration::Referenced(reference) if reference.as_key() == key => {
+                format!("Box<{}>", self.name(reference))
+            }
+            declaration => self.declaration_type(declaration, |data| self.data_type(data)),
+        }
+    }
+}
+
+/// A pattern binding all fields of a constructor, the names of those bindings, and an expression
+/// building the constructor back from them (as Plutus data).
+fn bindings(
+    constructor: &str,
+    names: Option<&[String]>,
+    arity: usize,
+) -> (String, Vec<String>, String) {
+    let decode = |value: &String| format!("FromPlutusData::from_plutus_data({value})?");
+
+    match names {
+        _ if arity == 0 => (constructor.to_string(), vec![], constructor.to_string()),
+        Some(names) => (
+            format!("{constructor} {{ {} }}", names.join(", ")),
+            names.to_vec(),
+            format!(
+                "{constructor} {{ {} }}",
+                names
+                    .iter()
+                    .map(|name| format!("{name}: {}", decode(name)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ),
+        None => {
+            let values = (0..arity).map(|ix| format!("f{ix}")).collect::<Vec<_>>();
+            (
+                format!("{constructor}({})", values.join(", ")),
+                values.clone(),
+                format!(
+                    "{constructor}({})",
+                    values.iter().map(decode).collect::<Vec<_>>().join(", ")
+                ),
+            )
+        }
+    }
+}
+
+fn encode(ix: usize, values: &[String]) -> String {
+    format!(
+        "uplc::ast::Data::constr({ix}, vec![{}])",
+        values
+            .iter()
+            .map(|value| format!("{value}.to_plutus_data()"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn ident(name: &str) -> String {
+    match name {
+        "self" | "super" | "crate" => format!("{name}_"),
+        _ if RESERVED.contains(&name) => format!("r#{name}"),
+        _ => name.to_string(),
+    }
+}
+
+fn doc_comment(out: &mut String, indent: &str, doc: Option<&str>) {
+    if let Some(doc) = doc.map(str::trim).filter(|doc| !doc.is_empty()) {
+        for line in doc.lines() {
+            writeln!(out, "{indent}/// {}", line.trim()).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_gift_card() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/gift_card/plutus.json"))
+                .unwrap();
+
+        insta::assert_snapshot!(generate(&blueprint));
+    }
+}
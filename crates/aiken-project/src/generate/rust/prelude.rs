@@ -0,0 +1,301 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use uplc::{
+    ast::{DeBruijn, Program, SerializableProgram},
+    machine::value::{from_pallas_bigint, to_pallas_bigint},
+    PlutusData,
+};
+
+/// Conversion of a value into Plutus data.
+pub trait ToPlutusData {
+    fn to_plutus_data(&self) -> PlutusData;
+}
+
+/// Conversion of Plutus data back into a value.
+pub trait FromPlutusData: Sized {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError>;
+}
+
+/// Plutus data that doesn't have the expected shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub expected: &'static str,
+    pub found: PlutusData,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {} but found {:?}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Raw bytes, (de)serialised as a base16-encoded string.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+/// Key-value pairs, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Map<K, V>(pub Vec<(K, V)>);
+
+/// The compiled code of a script, with the given parameters applied in order.
+pub fn apply_params(
+    plutus_version: u8,
+    compiled_code: &str,
+    params: Vec<PlutusData>,
+) -> SerializableProgram {
+    let mut cbor_buffer = Vec::new();
+    let mut flat_buffer = Vec::new();
+
+    let program =
+        Program::<DeBruijn>::from_hex(compiled_code, &mut cbor_buffer, &mut flat_buffer)
+            .expect("invalid compiled code");
+
+    let program = params
+        .into_iter()
+        .fold(program, |program, param| program.apply_data(param));
+
+    match plutus_version {
+        1 => SerializableProgram::PlutusV1Program(program),
+        2 => SerializableProgram::PlutusV2Program(program),
+        _ => SerializableProgram::PlutusV3Program(program),
+    }
+}
+
+fn unexpected<T>(expected: &'static str, data: &PlutusData) -> Result<T, DecodeError> {
+    Err(DecodeError {
+        expected,
+        found: data.clone(),
+    })
+}
+
+/// The index and fields of a constructor.
+fn constr<'a>(
+    data: &'a PlutusData,
+    expected: &'static str,
+) -> Result<(u64, &'a [PlutusData]), DecodeError> {
+    match data {
+        PlutusData::Constr(constr) => {
+            let ix = match (constr.tag, constr.any_constructor) {
+                (121..=127, _) => constr.tag - 121,
+                (1280..=1400, _) => constr.tag - 1280 + 7,
+                (_, Some(ix)) => ix,
+                _ => return unexpected(expected, data),
+            };
+            Ok((ix, &constr.fields[..]))
+        }
+        _ => unexpected(expected, data),
+    }
+}
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let hex: String = self.0.iter().map(|byte| format!("{byte:02x}")).collect();
+        serializer.serialize_str(&hex)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| hex.get(i..i + 2).and_then(|s| u8::from_str_radix(s, 16).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .map(Bytes)
+            .ok_or_else(|| serde::de::Error::custom("invalid base16-encoded string"))
+    }
+}
+
+impl ToPlutusData for PlutusData {
+    fn to_plutus_data(&self) -> PlutusData {
+        self.clone()
+    }
+}
+
+impl FromPlutusData for PlutusData {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        Ok(data.clone())
+    }
+}
+
+impl ToPlutusData for i128 {
+    fn to_plutus_data(&self) -> PlutusData {
+        PlutusData::BigInt(to_pallas_bigint(&(*self).into()))
+    }
+}
+
+impl FromPlutusData for i128 {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match data {
+            PlutusData::BigInt(n) => i128::try_from(&from_pallas_bigint(n))
+                .map_or_else(|_| unexpected("an integer (of at most 128 bits)", data), Ok),
+            _ => unexpected("an integer", data),
+        }
+    }
+}
+
+impl ToPlutusData for Bytes {
+    fn to_plutus_data(&self) -> PlutusData {
+        uplc::ast::Data::bytestring(self.0.clone())
+    }
+}
+
+impl FromPlutusData for Bytes {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match data {
+            PlutusData::BoundedBytes(bytes) => Ok(Bytes(bytes.to_vec())),
+            _ => unexpected("a byte array", data),
+        }
+    }
+}
+
+impl ToPlutusData for String {
+    fn to_plutus_data(&self) -> PlutusData {
+        uplc::ast::Data::bytestring(self.as_bytes().to_vec())
+    }
+}
+
+impl FromPlutusData for String {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match data {
+            PlutusData::BoundedBytes(bytes) => String::from_utf8(bytes.to_vec())
+                .map_or_else(|_| unexpected("a UTF-8 string", data), Ok),
+            _ => unexpected("a UTF-8 string", data),
+        }
+    }
+}
+
+impl ToPlutusData for () {
+    fn to_plutus_data(&self) -> PlutusData {
+        uplc::ast::Data::constr(0, vec![])
+    }
+}
+
+impl FromPlutusData for () {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match constr(data, "Void")? {
+            (0, []) => Ok(()),
+            _ => unexpected("Void", data),
+        }
+    }
+}
+
+impl ToPlutusData for bool {
+    fn to_plutus_data(&self) -> PlutusData {
+        uplc::ast::Data::constr(u64::from(*self), vec![])
+    }
+}
+
+impl FromPlutusData for bool {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match constr(data, "Bool")? {
+            (0, []) => Ok(false),
+            (1, []) => Ok(true),
+            _ => unexpected("Bool", data),
+        }
+    }
+}
+
+impl<T: ToPlutusData> ToPlutusData for Option<T> {
+    fn to_plutus_data(&self) -> PlutusData {
+        match self {
+            Some(value) => uplc::ast::Data::constr(0, vec![value.to_plutus_data()]),
+            None => uplc::ast::Data::constr(1, vec![]),
+        }
+    }
+}
+
+impl<T: FromPlutusData> FromPlutusData for Option<T> {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match constr(data, "Option")? {
+            (0, [value]) => Ok(Some(T::from_plutus_data(value)?)),
+            (1, []) => Ok(None),
+            _ => unexpected("Option", data),
+        }
+    }
+}
+
+impl<T: ToPlutusData> ToPlutusData for Box<T> {
+    fn to_plutus_data(&self) -> PlutusData {
+        self.as_ref().to_plutus_data()
+    }
+}
+
+impl<T: FromPlutusData> FromPlutusData for Box<T> {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        T::from_plutus_data(data).map(Box::new)
+    }
+}
+
+impl<T: ToPlutusData> ToPlutusData for Vec<T> {
+    fn to_plutus_data(&self) -> PlutusData {
+        uplc::ast::Data::list(self.iter().map(ToPlutusData::to_plutus_data).collect())
+    }
+}
+
+impl<T: FromPlutusData> FromPlutusData for Vec<T> {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match data {
+            PlutusData::Array(items) => items.iter().map(T::from_plutus_data).collect(),
+            _ => unexpected("a list", data),
+        }
+    }
+}
+
+impl<K: ToPlutusData, V: ToPlutusData> ToPlutusData for Map<K, V> {
+    fn to_plutus_data(&self) -> PlutusData {
+        uplc::ast::Data::map(
+            self.0
+                .iter()
+                .map(|(k, v)| (k.to_plutus_data(), v.to_plutus_data()))
+                .collect(),
+        )
+    }
+}
+
+impl<K: FromPlutusData, V: FromPlutusData> FromPlutusData for Map<K, V> {
+    fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+        match data {
+            PlutusData::Map(pairs) => pairs
+                .iter()
+                .map(|(k, v)| Ok((K::from_plutus_data(k)?, V::from_plutus_data(v)?)))
+                .collect::<Result<_, _>>()
+                .map(Map),
+            _ => unexpected("a map", data),
+        }
+    }
+}
+
+// Tuples and pairs are both represented as lists.
+macro_rules! tuple {
+    ($($t:ident),+) => {
+        impl<$($t: ToPlutusData),+> ToPlutusData for ($($t,)+) {
+            #[allow(non_snake_case)]
+            fn to_plutus_data(&self) -> PlutusData {
+                let ($($t,)+) = self;
+                uplc::ast::Data::list(vec![$($t.to_plutus_data()),+])
+            }
+        }
+
+        impl<$($t: FromPlutusData),+> FromPlutusData for ($($t,)+) {
+            #[allow(non_snake_case)]
+            fn from_plutus_data(data: &PlutusData) -> Result<Self, DecodeError> {
+                match data {
+                    PlutusData::Array(items) => match &items[..] {
+                        [$($t),+] => Ok(($($t::from_plutus_data($t)?,)+)),
+                        _ => unexpected("a tuple", data),
+                    },
+                    _ => unexpected("a tuple", data),
+                }
+            }
+        }
+    };
+}
+
+tuple!(A, B);
+tuple!(A, B, C);
+tuple!(A, B, C, D);
+tuple!(A, B, C, D, E);
+tuple!(A, B, C, D, E, F);
@@ -0,0 +1,468 @@
+//! Ready-to-use TypeScript projects, with transaction-building helpers for each validator on top
+//! of the bindings from [`super::typescript`], for a given off-chain library.
+
+use super::{pascal_case, scripts, typescript, Script};
+use crate::blueprint::Blueprint;
+use serde_json::json;
+use std::fmt::Write;
+
+const TSCONFIG: &str = r#"{
+  "compilerOptions": {
+    "target": "ES2020",
+    "module": "ESNext",
+    "moduleResolution": "Bundler",
+    "strict": true,
+    "declaration": true,
+    "outDir": "dist",
+    "skipLibCheck": true
+  },
+  "include": ["src"]
+}
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// Lucid Evolution (i.e. '@lucid-evolution/lucid').
+    Lucid,
+    /// Mesh (i.e. '@meshsdk/core').
+    Mesh,
+}
+
+impl Target {
+    fn package(&self) -> &'static str {
+        match self {
+            Target::Lucid => "@lucid-evolution/lucid",
+            Target::Mesh => "@meshsdk/core",
+        }
+    }
+}
+
+/// Files of a TypeScript project, relative to its root, with:
+///
+/// - the bindings generated from the blueprint, in 'src/plutus.ts';
+/// - a function for each validator in 'src/index.ts', taking the validator's parameters and
+///   giving its script, hash and address, alongside helpers to lock funds at (resp. unlock funds
+///   from) the script, or mint assets, with typed datums and redeemers.
+pub fn scaffold(blueprint: &Blueprint, target: Target) -> Vec<(&'static str, String)> {
+    vec![
+        ("package.json", package(blueprint, target)),
+        ("tsconfig.json", TSCONFIG.to_string()),
+        ("src/plutus.ts", typescript::generate(blueprint)),
+        ("src/index.ts", helpers(blueprint, target)),
+    ]
+}
+
+fn package(blueprint: &Blueprint, target: Target) -> String {
+    let package = json!({
+        "name": blueprint.preamble.title.replace(['/', '_'], "-").to_lowercase(),
+        "version": blueprint.preamble.version,
+        "description": blueprint.preamble.description,
+        "type": "module",
+        "main": "dist/index.js",
+        "types": "dist/index.d.ts",
+        "scripts": {
+            "build": "tsc"
+        },
+        "dependencies": {
+            target.package(): "latest"
+        },
+        "devDependencies": {
+            "typescript": "^5.0.0"
+        }
+    });
+
+    format!("{}\n", serde_json::to_string_pretty(&package).unwrap())
+}
+
+fn helpers(blueprint: &Blueprint, target: Target) -> String {
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "// Generated by Aiken from the blueprint of '{}' (v{}). Feel free to edit.",
+        blueprint.preamble.title, blueprint.preamble.version
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+
+    let imports = match target {
+        Target::Lucid => [
+            "applyDoubleCborEncoding",
+            "applyParamsToScript",
+            "Data",
+            "validatorToAddress",
+            "validatorToScriptHash",
+            "type Assets",
+            "type Network",
+            "type Script",
+            "type TxBuilder",
+            "type UTxO",
+        ]
+        .as_slice(),
+        Target::Mesh => [
+            "applyCborEncoding",
+            "applyParamsToScript",
+            "resolveScriptHash",
+            "serializePlutusScript",
+            "type Asset",
+            "type MeshTxBuilder",
+            "type UTxO",
+        ]
+        .as_slice(),
+    };
+
+    writeln!(out, "import {{").unwrap();
+    for import in imports {
+        writeln!(out, "  {import},").unwrap();
+    }
+    writeln!(out, "}} from \"{}\";", target.package()).unwrap();
+    writeln!(out, "import * as plutus from \"./plutus\";").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "export * from \"./plutus\";").unwrap();
+
+    for script in scripts(blueprint) {
+        writeln!(out).unwrap();
+        script_helpers(&mut out, &script, target);
+    }
+
+    out
+}
+
+/// What a handler is for, as far as helpers are concerned. Handlers of validators without any
+/// (i.e. for Plutus V1 and V2) are told apart by their datum.
+enum Purpose {
+    Spend,
+    Mint,
+}
+
+fn script_helpers(out: &mut String, script: &Script, target: Target) {
+    let name = pascal_case(script.title);
+    let bindings = format!("plutus.{name}");
+    let version = format!("{:?}", script.plutus_version()).to_uppercase();
+
+    let network = match target {
+        Target::Lucid => "network: Network",
+        Target::Mesh => "networkId: 0 | 1",
+    };
+
+    let (params, applied) = if script.validator.parameters.is_empty() {
+        (String::new(), None)
+    } else {
+        (
+            format!(", ...params: Parameters<typeof {bindings}.params>"),
+            Some(format!("{bindings}.params(...params)")),
+        )
+    };
+
+    writeln!(
+        out,
+        "/** Transaction-building helpers for the validator '{}'. */",
+        script.title
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "export function {}({network}{params}) {{",
+        lower_first(&name)
+    )
+    .unwrap();
+
+    match target {
+        Target::Lucid => {
+            writeln!(out, "  const script: Script = {{").unwrap();
+            writeln!(out, "    type: \"Plutus{version}\",").unwrap();
+            match applied {
+                Some(applied) => {
+                    writeln!(out, "    script: applyParamsToScript(").unwrap();
+                    writeln!(
+                        out,
+                        "      applyDoubleCborEncoding({bindings}.compiledCode),"
+                    )
+                    .unwrap();
+                    writeln!(out, "      {applied}.map((param) => Data.from(param)),").unwrap();
+                    writeln!(out, "    ),").unwrap();
+                }
+                None => writeln!(
+                    out,
+                    "    script: applyDoubleCborEncoding({bindings}.compiledCode),"
+                )
+                .unwrap(),
+            }
+            writeln!(out, "  }};").unwrap();
+            writeln!(out).unwrap();
+            writeln!(out, "  const hash = validatorToScriptHash(script);").unwrap();
+            writeln!(
+                out,
+                "  const address = validatorToAddress(network, script);"
+            )
+            .unwrap();
+            writeln!(out).unwrap();
+            writeln!(out, "  return {{").unwrap();
+            writeln!(out, "    script,").unwrap();
+        }
+        Target::Mesh => {
+            writeln!(out, "  const version = \"{version}\" as const;").unwrap();
+            match applied {
+                Some(applied) => writeln!(
+                    out,
+                    "  const code = applyParamsToScript({bindings}.compiledCode, {applied}, \"CBOR\");"
+                )
+                .unwrap(),
+                None => writeln!(
+                    out,
+                    "  const code = applyCborEncoding({bindings}.compiledCode);"
+                )
+                .unwrap(),
+            }
+            writeln!(out).unwrap();
+            writeln!(out, "  const hash = resolveScriptHash(code, version);").unwrap();
+            writeln!(
+                out,
+                "  const {{ address }} = serializePlutusScript({{ code, version }}, undefined, networkId);"
+            )
+            .unwrap();
+            writeln!(out).unwrap();
+            writeln!(out, "  return {{").unwrap();
+            writeln!(out, "    code,").unwrap();
+            writeln!(out, "    version,").unwrap();
+        }
+    }
+
+    writeln!(out, "    hash,").unwrap();
+    writeln!(out, "    address,").unwrap();
+
+    for (handler, validator) in script.handlers.iter() {
+        let purpose = match *handler {
+            "spend" => Purpose::Spend,
+            "mint" => Purpose::Mint,
+            "" if validator.datum.is_some() => Purpose::Spend,
+            "" => Purpose::Mint,
+            _ => continue,
+        };
+
+        let bindings = if handler.is_empty() {
+            bindings.clone()
+        } else {
+            format!("{bindings}.{handler}")
+        };
+
+        let datum = validator
+            .datum
+            .as_ref()
+            .map(|_| format!("Parameters<typeof {bindings}.datum>[0]"));
+
+        let redeemer = validator
+            .redeemer
+            .as_ref()
+            .map(|_| format!("Parameters<typeof {bindings}.redeemer>[0]"));
+
+        match (purpose, target) {
+            (Purpose::Spend, Target::Lucid) => {
+                if let Some(datum) = datum {
+                    writeln!(
+                        out,
+                        "    /** Pay assets to the script, with the given datum inline. */"
+                    )
+                    .unwrap();
+                    writeln!(
+                        out,
+                        "    lock(tx: TxBuilder, datum: {datum}, assets: Assets): TxBuilder {{"
+                    )
+                    .unwrap();
+                    writeln!(out, "      return tx.pay.ToContract(").unwrap();
+                    writeln!(out, "        address,").unwrap();
+                    writeln!(
+                        out,
+                        "        {{ kind: \"inline\", value: {bindings}.datum(datum) }},"
+                    )
+                    .unwrap();
+                    writeln!(out, "        assets,").unwrap();
+                    writeln!(out, "      );").unwrap();
+                    writeln!(out, "    }},").unwrap();
+                }
+
+                if let Some(redeemer) = redeemer {
+                    writeln!(
+                        out,
+                        "    /** Spend outputs locked at the script, with the given redeemer. */"
+                    )
+                    .unwrap();
+                    writeln!(
+                        out,
+                        "    unlock(tx: TxBuilder, utxos: UTxO[], redeemer: {redeemer}): TxBuilder {{"
+                    )
+                    .unwrap();
+                    writeln!(out, "      return tx").unwrap();
+                    writeln!(
+                        out,
+                        "        .collectFrom(utxos, {bindings}.redeemer(redeemer))"
+                    )
+                    .unwrap();
+                    writeln!(out, "        .attach.SpendingValidator(script);").unwrap();
+                    writeln!(out, "    }},").unwrap();
+                }
+            }
+
+            (Purpose::Spend, Target::Mesh) => {
+                if let Some(datum) = datum {
+                    writeln!(
+                        out,
+                        "    /** Pay assets to the script, with the given datum inline. */"
+                    )
+                    .unwrap();
+                    writeln!(
+                        out,
+                        "    lock(tx: MeshTxBuilder, datum: {datum}, amount: Asset[]): MeshTxBuilder {{"
+                    )
+                    .unwrap();
+                    writeln!(out, "      return tx").unwrap();
+                    writeln!(out, "        .txOut(address, amount)").unwrap();
+                    writeln!(
+                        out,
+                        "        .txOutInlineDatumValue({bindings}.datum(datum), \"CBOR\");"
+                    )
+                    .unwrap();
+                    writeln!(out, "    }},").unwrap();
+                }
+
+                if let Some(redeemer) = redeemer {
+                    writeln!(
+                        out,
+                        "    /** Spend an output locked at the script (with an inline datum), with the given redeemer. */"
+                    )
+                    .unwrap();
+                    writeln!(
+                        out,
+                        "    unlock(tx: MeshTxBuilder, utxo: UTxO, redeemer: {redeemer}): MeshTxBuilder {{"
+                    )
+                    .unwrap();
+                    writeln!(out, "      return tx").unwrap();
+                    writeln!(out, "        .spendingPlutusScript(version)").unwrap();
+                    writeln!(
+                        out,
+                        "        .txIn(utxo.input.txHash, utxo.input.outputIndex, utxo.output.amount, utxo.output.address)"
+                    )
+                    .unwrap();
+                    writeln!(out, "        .txInInlineDatumPresent()").unwrap();
+                    writeln!(
+                        out,
+                        "        .txInRedeemerValue({bindings}.redeemer(redeemer), \"CBOR\")"
+                    )
+                    .unwrap();
+                    writeln!(out, "        .txInScript(code);").unwrap();
+                    writeln!(out, "    }},").unwrap();
+                }
+            }
+
+            (Purpose::Mint, Target::Lucid) => {
+                if let Some(redeemer) = redeemer {
+                    writeln!(
+                        out,
+                        "    /** Mint (or burn, with negative quantities) assets whose policy is the script's hash. */"
+                    )
+                    .unwrap();
+                    writeln!(
+                        out,
+                        "    mint(tx: TxBuilder, assets: Assets, redeemer: {redeemer}): TxBuilder {{"
+                    )
+                    .unwrap();
+                    writeln!(out, "      return tx").unwrap();
+                    writeln!(
+                        out,
+                        "        .mintAssets(assets, {bindings}.redeemer(redeemer))"
+                    )
+                    .unwrap();
+                    writeln!(out, "        .attach.MintingPolicy(script);").unwrap();
+                    writeln!(out, "    }},").unwrap();
+                }
+            }
+
+            (Purpose::Mint, Target::Mesh) => {
+                if let Some(redeemer) = redeemer {
+                    writeln!(
+                        out,
+                        "    /** Mint (or burn, with a negative quantity) an asset whose policy is the script's hash. */"
+                    )
+                    .unwrap();
+                    writeln!(
+                        out,
+                        "    mint(tx: MeshTxBuilder, quantity: string, assetName: string, redeemer: {redeemer}): MeshTxBuilder {{"
+                    )
+                    .unwrap();
+                    writeln!(out, "      return tx").unwrap();
+                    writeln!(out, "        .mintPlutusScript(version)").unwrap();
+                    writeln!(out, "        .mint(quantity, hash, assetName)").unwrap();
+                    writeln!(out, "        .mintingScript(code)").unwrap();
+                    writeln!(
+                        out,
+                        "        .mintRedeemerValue({bindings}.redeemer(redeemer), \"CBOR\");"
+                    )
+                    .unwrap();
+                    writeln!(out, "    }},").unwrap();
+                }
+            }
+        }
+    }
+
+    writeln!(out, "  }};").unwrap();
+    writeln!(out, "}}").unwrap();
+}
+
+fn lower_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(blueprint: &Blueprint, target: Target) -> String {
+        scaffold(blueprint, target)
+            .into_iter()
+            .find_map(|(path, source)| (path == "src/index.ts").then_some(source))
+            .unwrap()
+    }
+
+    #[test]
+    fn scaffold_gift_card_lucid() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/gift_card/plutus.json"))
+                .unwrap();
+
+        insta::assert_snapshot!(index(&blueprint, Target::Lucid));
+    }
+
+    #[test]
+    fn scaffold_gift_card_mesh() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/gift_card/plutus.json"))
+                .unwrap();
+
+        insta::assert_snapshot!(index(&blueprint, Target::Mesh));
+    }
+
+    #[test]
+    fn scaffold_hello_world() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/hello_world/plutus.json"))
+                .unwrap();
+
+        let files = scaffold(&blueprint, Target::Lucid);
+
+        assert_eq!(
+            files.iter().map(|(path, _)| *path).collect::<Vec<_>>(),
+            vec![
+                "package.json",
+                "tsconfig.json",
+                "src/plutus.ts",
+                "src/index.ts"
+            ]
+        );
+
+        insta::assert_snapshot!(files[0].1);
+    }
+}
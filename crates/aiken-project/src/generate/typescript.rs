@@ -0,0 +1,749 @@
+//! TypeScript bindings, without any dependency. Datums, redeemers and parameters are encoded as
+//! CBOR (or as cardano-cli's detailed JSON schema), which both Lucid and Mesh accept as-is.
+
+use super::{constructor_title, field_names, pascal_case, scripts, type_names, Script, Shape};
+use crate::blueprint::{
+    definitions::Reference,
+    parameter::Parameter,
+    schema::{Annotated, Constructor, Data, Declaration, Items, Schema},
+    Blueprint,
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+const PRELUDE: &str = include_str!("typescript/prelude.ts");
+
+const RESERVED: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+];
+
+/// Generate a self-contained TypeScript module from a blueprint, with:
+///
+/// - a type, an encoder (to Plutus data) and a decoder (from Plutus data) for each definition;
+/// - an object for each validator, holding its compiled code and hash, and helpers to encode
+///   its parameters, datum and redeemers as CBOR.
+pub fn generate(blueprint: &Blueprint) -> String {
+    let generator = Generator {
+        names: type_names(&blueprint.definitions),
+    };
+
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "// Generated by Aiken from the blueprint of '{}' (v{}). Do not edit by hand.",
+        blueprint.preamble.title, blueprint.preamble.version
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    out.push_str(PRELUDE);
+
+    for (key, definition) in blueprint.definitions.iter() {
+        writeln!(out).unwrap();
+        generator.definition(&mut out, key, definition);
+    }
+
+    for script in scripts(blueprint) {
+        writeln!(out).unwrap();
+        generator.script(&mut out, &script);
+    }
+
+    out
+}
+
+struct Generator {
+    names: BTreeMap<String, String>,
+}
+
+impl Generator {
+    fn definition(&self, out: &mut String, key: &str, definition: &Annotated<Schema>) {
+        let name = &self.names[key];
+
+        doc_comment(
+            out,
+            definition
+                .description
+                .as_deref()
+                .or(definition.title.as_deref()),
+        );
+
+        let (tipo, encoder, decoder) = match &definition.annotated {
+            Schema::Data(Data::AnyOf(constructors)) => (
+                self.constructors_type(constructors),
+                self.constructors_encoder(name, constructors, "value"),
+                self.constructors_decoder(constructors, "value"),
+            ),
+            schema => (
+                self.schema_type(schema),
+                format!("return {};", self.schema_encoder(schema, "value")),
+                format!("return {};", self.schema_decoder(schema, "value")),
+            ),
+        };
+
+        writeln!(out, "export type {name} = {tipo};").unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "export function encode{name}(value: {name}): PlutusData {{"
+        )
+        .unwrap();
+        for line in encoder.lines() {
+            writeln!(out, "  {line}").unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "export function decode{name}(value: PlutusData): {name} {{"
+        )
+        .unwrap();
+        for line in decoder.lines() {
+            writeln!(out, "  {line}").unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+    }
+
+    fn script(&self, out: &mut String, script: &Script) {
+        let (compiled_code, hash) = script.compiled_code_and_hash();
+
+        doc_comment(out, script.validator.description.as_deref());
+        writeln!(out, "export const {} = {{", pascal_case(script.title)).unwrap();
+        writeln!(out, "  title: \"{}\",", script.title).unwrap();
+        writeln!(
+            out,
+            "  plutusVersion: \"{}\",",
+            format!("{:?}", script.plutus_version()).to_uppercase()
+        )
+        .unwrap();
+        writeln!(out, "  compiledCode: \"{compiled_code}\",").unwrap();
+
+        if script.validator.parameters.is_empty() {
+            writeln!(out, "  hash: \"{hash}\",").unwrap();
+            writeln!(out, "  address(network: Network): string {{").unwrap();
+            writeln!(out, "    return scriptAddress(\"{hash}\", network);").unwrap();
+            writeln!(out, "  }},").unwrap();
+        } else {
+            writeln!(
+                out,
+                "  /** The hash of the script *before* applying parameters. */"
+            )
+            .unwrap();
+            writeln!(out, "  unappliedHash: \"{hash}\",").unwrap();
+            writeln!(
+                out,
+                "  /** Encode parameters, in order, as CBOR to be applied to the compiled code. */"
+            )
+            .unwrap();
+            let (args, values) = self.arguments(&script.validator.parameters, "param");
+            writeln!(out, "  params({args}): string[] {{").unwrap();
+            writeln!(out, "    return [{values}];").unwrap();
+            writeln!(out, "  }},").unwrap();
+        }
+
+        for (handler, validator) in script.handlers.iter() {
+            let encoders = [
+                ("datum", &validator.datum),
+                ("redeemer", &validator.redeemer),
+            ]
+            .into_iter()
+            .filter_map(|(what, parameter)| {
+                let parameter = parameter.as_ref()?;
+                let (args, values) = self.arguments(std::slice::from_ref(parameter), what);
+                Some(format!(
+                    "{what}({args}): string {{\n      return {values};\n    }},"
+                ))
+            })
+            .collect::<Vec<_>>();
+
+            if handler.is_empty() {
+                for encoder in encoders {
+                    writeln!(out, "  {}", encoder.replace("\n  ", "\n")).unwrap();
+                }
+            } else {
+                writeln!(out, "  {handler}: {{").unwrap();
+                for encoder in encoders {
+                    writeln!(out, "    {encoder}").unwrap();
+                }
+                writeln!(out, "  }},").unwrap();
+            }
+        }
+
+        writeln!(out, "}} as const;").unwrap();
+    }
+
+    /// Typed arguments of a helper, and their values encoded as CBOR.
+    fn arguments(&self, parameters: &[Parameter], default: &str) -> (String, String) {
+        let mut args = vec![];
+        let mut values = vec![];
+
+        for (ix, parameter) in parameters.iter().enumerate() {
+            let name = match &parameter.title {
+                Some(title) if RESERVED.contains(&title.as_str()) => format!("{title}_"),
+                Some(title) => title.to_string(),
+                None if parameters.len() == 1 => default.to_string(),
+                None => format!("{default}{ix}"),
+            };
+
+            args.push(format!(
+                "{name}: {}",
+                self.declaration_type(&parameter.schema, |schema| self.schema_type(schema))
+            ));
+
+            values.push(format!(
+                "toCbor({})",
+                self.declaration_encoder(&parameter.schema, &name, |schema, value| {
+                    self.schema_encoder(schema, value)
+                })
+            ));
+        }
+
+        (args.join(", "), values.join(", "))
+    }
+
+    fn name(&self, reference: &Reference) -> &str {
+        &self.names[&reference.as_key()]
+    }
+
+    fn declaration_type<T>(
+        &self,
+        declaration: &Declaration<T>,
+        inline: impl FnOnce(&T) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => self.name(reference).to_string(),
+            Declaration::Inline(inner) => inline(inner),
+        }
+    }
+
+    fn declaration_encoder<T>(
+        &self,
+        declaration: &Declaration<T>,
+        value: &str,
+        inline: impl FnOnce(&T, &str) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => {
+                format!("encode{}({value})", self.name(reference))
+            }
+            Declaration::Inline(inner) => inline(inner, value),
+        }
+    }
+
+    fn declaration_decoder<T>(
+        &self,
+        declaration: &Declaration<T>,
+        value: &str,
+        inline: impl FnOnce(&T, &str) -> String,
+    ) -> String {
+        match declaration {
+            Declaration::Referenced(reference) => {
+                format!("decode{}({value})", self.name(reference))
+            }
+            Declaration::Inline(inner) => inline(inner, value),
+        }
+    }
+
+    fn schema_type(&self, schema: &Schema) -> String {
+        match schema {
+            Schema::Unit => "null".to_string(),
+            Schema::Boolean => "boolean".to_string(),
+            Schema::Integer => "bigint".to_string(),
+            Schema::Bytes | Schema::String => "string".to_string(),
+            Schema::Pair(left, right) => format!(
+                "[{}, {}]",
+                self.declaration_type(left, |schema| self.schema_type(schema)),
+                self.declaration_type(right, |schema| self.schema_type(schema)),
+            ),
+            Schema::List(items) => self.items_type(items, |schema| self.schema_type(schema)),
+            Schema::Data(data) => self.data_type(data),
+        }
+    }
+
+    fn data_type(&self, data: &Data) -> String {
+        match data {
+            Data::Integer(..) => "bigint".to_string(),
+            Data::Bytes(..) => "string".to_string(),
+            Data::List(items) => self.items_type(items, |data| self.data_type(data)),
+            Data::Map(keys, values) => format!(
+                "Array<[{}, {}]>",
+                self.declaration_type(keys, |data| self.data_type(data)),
+                self.declaration_type(values, |data| self.data_type(data)),
+            ),
+            Data::AnyOf(constructors) => self.constructors_type(constructors),
+            Data::Opaque => "PlutusData".to_string(),
+        }
+    }
+
+    fn items_type<T>(&self, items: &Items<T>, inline: impl Fn(&T) -> String) -> String {
+        match items {
+            Items::One(item) => format!("Array<{}>", self.declaration_type(item, &inline)),
+            Items::Many(items) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|item| self.declaration_type(item, &inline))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    fn constructors_type(&self, constructors: &[Annotated<Constructor>]) -> String {
+        match Shape::of(constructors) {
+            Shape::Bool { .. } => "boolean".to_string(),
+            Shape::Option { some, .. } => {
+                format!("{} | null", self.field_type(&some.annotated.fields[0]))
+            }
+            Shape::Single(constructor) => self.fields_type(constructor),
+            Shape::Union => constructors
+                .iter()
+                .map(|constructor| {
+                    let title = constructor_title(constructor);
+                    if constructor.annotated.fields.is_empty() {
+                        format!("\"{title}\"")
+                    } else {
+                        format!("{{ {title}: {} }}", self.fields_type(constructor))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" | "),
+        }
+    }
+
+    /// Constructors with named fields are objects, others are tuples. Constructors without any
+    /// field are represented by their name.
+    fn fields_type(&self, constructor: &Annotated<Constructor>) -> String {
+        let fields = &constructor.annotated.fields;
+
+        if fields.is_empty() {
+            return format!("\"{}\"", constructor_title(constructor));
+        }
+
+        match field_names(fields) {
+            Some(names) => format!(
+                "{{ {} }}",
+                names
+                    .iter()
+                    .zip(fields)
+                    .map(|(name, field)| format!("{name}: {};", self.field_type(field)))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            None => format!(
+                "[{}]",
+                fields
+                    .iter()
+                    .map(|field| self.field_type(field))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    fn field_type(&self, field: &Annotated<Declaration<Data>>) -> String {
+        self.declaration_type(&field.annotated, |data| self.data_type(data))
+    }
+
+    fn schema_encoder(&self, schema: &Schema, value: &str) -> String {
+        match schema {
+            Schema::Unit => "{ constructor: 0, fields: [] }".to_string(),
+            Schema::Boolean => format!("{{ constructor: {value} ? 1 : 0, fields: [] }}"),
+            Schema::Integer => format!("{{ int: {value} }}"),
+            Schema::Bytes => format!("{{ bytes: {value} }}"),
+            Schema::String => format!("{{ bytes: utf8ToHex({value}) }}"),
+            Schema::Pair(left, right) => format!(
+                "{{ list: [{}, {}] }}",
+                self.declaration_encoder(left, &format!("{value}[0]"), |schema, value| {
+                    self.schema_encoder(schema, value)
+                }),
+                self.declaration_encoder(right, &format!("{value}[1]"), |schema, value| {
+                    self.schema_encoder(schema, value)
+                }),
+            ),
+            Schema::List(items) => self.items_encoder(items, value, &|schema, value| {
+                self.schema_encoder(schema, value)
+            }),
+            Schema::Data(data) => self.data_encoder(data, value),
+        }
+    }
+
+    fn data_encoder(&self, data: &Data, value: &str) -> String {
+        match data {
+            Data::Integer(..) => format!("{{ int: {value} }}"),
+            Data::Bytes(..) => format!("{{ bytes: {value} }}"),
+            Data::List(items) => {
+                self.items_encoder(items, value, &|data, value| self.data_encoder(data, value))
+            }
+            Data::Map(keys, values) => format!(
+                "{{ map: {value}.map(([k, v]) => ({{ k: {}, v: {} }})) }}",
+                self.declaration_encoder(keys, "k", |data, value| self.data_encoder(data, value)),
+                self.declaration_encoder(values, "v", |data, value| self.data_encoder(data, value)),
+            ),
+            Data::AnyOf(constructors) => format!(
+                "((value: {}): PlutusData => {{ {} }})({value})",
+                self.constructors_type(constructors),
+                self.constructors_encoder("anonymous type", constructors, "value")
+                    .replace('\n', " "),
+            ),
+            Data::Opaque => value.to_string(),
+        }
+    }
+
+    fn items_encoder<T>(
+        &self,
+        items: &Items<T>,
+        value: &str,
+        inline: &dyn Fn(&T, &str) -> String,
+    ) -> String {
+        match items {
+            Items::One(item) => format!(
+                "{{ list: {value}.map((item) => {}) }}",
+                self.declaration_encoder(item, "item", inline)
+            ),
+            Items::Many(items) => format!(
+                "{{ list: [{}] }}",
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, item)| self.declaration_encoder(
+                        item,
+                        &format!("{value}[{ix}]"),
+                        inline
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// The body of a function encoding a value of the given constructors.
+    fn constructors_encoder(
+        &self,
+        name: &str,
+        constructors: &[Annotated<Constructor>],
+        value: &str,
+    ) -> String {
+        match Shape::of(constructors) {
+            Shape::Bool { false_ix, true_ix } => format!(
+                "return {{ constructor: {value} ? {true_ix} : {false_ix}, fields: [] }};"
+            ),
+            Shape::Option { some, none_ix } => format!(
+                "return {value} === null\n  ? {{ constructor: {none_ix}, fields: [] }}\n  : {{ constructor: {}, fields: [{}] }};",
+                some.annotated.index,
+                self.field_encoder(&some.annotated.fields[0], value),
+            ),
+            Shape::Single(constructor) => {
+                format!("return {};", self.constructor_encoder(constructor, value))
+            }
+            Shape::Union => {
+                let (nullary, others): (Vec<_>, Vec<_>) = constructors
+                    .iter()
+                    .partition(|constructor| constructor.annotated.fields.is_empty());
+
+                let mut body = String::new();
+
+                for constructor in nullary.into_iter().chain(others) {
+                    let title = constructor_title(constructor);
+                    if constructor.annotated.fields.is_empty() {
+                        writeln!(body, "if ({value} === \"{title}\") {{").unwrap();
+                    } else {
+                        writeln!(body, "if (\"{title}\" in {value}) {{").unwrap();
+                    }
+                    writeln!(
+                        body,
+                        "  return {};",
+                        self.constructor_encoder(constructor, &format!("{value}.{title}"))
+                    )
+                    .unwrap();
+                    writeln!(body, "}}").unwrap();
+                }
+
+                write!(
+                    body,
+                    "throw new Error(`Invalid {name}: ${{JSON.stringify({value})}}`);"
+                )
+                .unwrap();
+
+                body
+            }
+        }
+    }
+
+    fn constructor_encoder(&self, constructor: &Annotated<Constructor>, value: &str) -> String {
+        let fields = &constructor.annotated.fields;
+
+        let values = match field_names(fields) {
+            Some(names) => names
+                .iter()
+                .zip(fields)
+                .map(|(name, field)| self.field_encoder(field, &format!("{value}.{name}")))
+                .collect::<Vec<_>>(),
+            None => fields
+                .iter()
+                .enumerate()
+                .map(|(ix, field)| self.field_encoder(field, &format!("{value}[{ix}]")))
+                .collect(),
+        };
+
+        format!(
+            "{{ constructor: {}, fields: [{}] }}",
+            constructor.annotated.index,
+            values.join(", ")
+        )
+    }
+
+    fn field_encoder(&self, field: &Annotated<Declaration<Data>>, value: &str) -> String {
+        self.declaration_encoder(&field.annotated, value, |data, value| {
+            self.data_encoder(data, value)
+        })
+    }
+
+    fn schema_decoder(&self, schema: &Schema, value: &str) -> String {
+        match schema {
+            Schema::Unit => format!("asUnit({value})"),
+            Schema::Boolean => format!("asConstr({value}, {{ 0: 0, 1: 0 }}).constructor === 1"),
+            Schema::Integer => format!("asInt({value})"),
+            Schema::Bytes => format!("asBytes({value})"),
+            Schema::String => format!("hexToUtf8(asBytes({value}))"),
+            Schema::Pair(left, right) => format!(
+                "((items): {} => [{}, {}])(asList({value}, 2))",
+                self.schema_type(schema),
+                self.declaration_decoder(left, "items[0]", |schema, value| {
+                    self.schema_decoder(schema, value)
+                }),
+                self.declaration_decoder(right, "items[1]", |schema, value| {
+                    self.schema_decoder(schema, value)
+                }),
+            ),
+            Schema::List(items) => {
+                self.items_decoder(items, value, &self.schema_type(schema), &|schema, value| {
+                    self.schema_decoder(schema, value)
+                })
+            }
+            Schema::Data(data) => self.data_decoder(data, value),
+        }
+    }
+
+    fn data_decoder(&self, data: &Data, value: &str) -> String {
+        match data {
+            Data::Integer(..) => format!("asInt({value})"),
+            Data::Bytes(..) => format!("asBytes({value})"),
+            Data::List(items) => {
+                self.items_decoder(items, value, &self.data_type(data), &|data, value| {
+                    self.data_decoder(data, value)
+                })
+            }
+            Data::Map(keys, values) => format!(
+                "asMap({value}).map(({{ k, v }}): [{}, {}] => [{}, {}])",
+                self.declaration_type(keys, |data| self.data_type(data)),
+                self.declaration_type(values, |data| self.data_type(data)),
+                self.declaration_decoder(keys, "k", |data, value| self.data_decoder(data, value)),
+                self.declaration_decoder(values, "v", |data, value| self.data_decoder(data, value)),
+            ),
+            Data::AnyOf(constructors) => format!(
+                "((value: PlutusData): {} => {{ {} }})({value})",
+                self.constructors_type(constructors),
+                self.constructors_decoder(constructors, "value")
+                    .replace('\n', " "),
+            ),
+            Data::Opaque => value.to_string(),
+        }
+    }
+
+    fn items_decoder<T>(
+        &self,
+        items: &Items<T>,
+        value: &str,
+        tipo: &str,
+        inline: &dyn Fn(&T, &str) -> String,
+    ) -> String {
+        match items {
+            Items::One(item) => format!(
+                "asList({value}).map((item) => {})",
+                self.declaration_decoder(item, "item", inline)
+            ),
+            Items::Many(items) => format!(
+                "((items): {tipo} => [{}])(asList({value}, {}))",
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, item)| self.declaration_decoder(
+                        item,
+                        &format!("items[{ix}]"),
+                        inline
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                items.len(),
+            ),
+        }
+    }
+
+    /// The body of a function decoding a value of the given constructors. Constructor indices
+    /// and number of fields are checked upfront, so the last constructor needs no guard.
+    fn constructors_decoder(&self, constructors: &[Annotated<Constructor>], value: &str) -> String {
+        let arities = constructors
+            .iter()
+            .map(|constructor| {
+                format!(
+                    "{}: {}",
+                    constructor.annotated.index,
+                    constructor.annotated.fields.len()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match Shape::of(constructors) {
+            Shape::Bool { true_ix, .. } => format!(
+                "return asConstr({value}, {{ {arities} }}).constructor === {true_ix};"
+            ),
+            Shape::Option { some, none_ix } => format!(
+                "const {{ constructor, fields }} = asConstr({value}, {{ {arities} }});\nreturn constructor === {none_ix} ? null : {};",
+                self.field_decoder(&some.annotated.fields[0], "fields[0]"),
+            ),
+            Shape::Single(constructor) if constructor.annotated.fields.is_empty() => format!(
+                "asConstr({value}, {{ {arities} }});\nreturn {};",
+                self.constructor_decoder(constructor),
+            ),
+            Shape::Single(constructor) => format!(
+                "const {{ fields }} = asConstr({value}, {{ {arities} }});\nreturn {};",
+                self.constructor_decoder(constructor),
+            ),
+            Shape::Union => {
+                let mut body = String::new();
+
+                let bindings = if constructors
+                    .iter()
+                    .all(|constructor| constructor.annotated.fields.is_empty())
+                {
+                    "constructor"
+                } else {
+                    "constructor, fields"
+                };
+
+                writeln!(
+                    body,
+                    "const {{ {bindings} }} = asConstr({value}, {{ {arities} }});"
+                )
+                .unwrap();
+
+                if let Some((last, others)) = constructors.split_last() {
+                    for constructor in others {
+                        writeln!(
+                            body,
+                            "if (constructor === {}) {{",
+                            constructor.annotated.index
+                        )
+                        .unwrap();
+                        writeln!(body, "  return {};", self.variant_decoder(constructor)).unwrap();
+                        writeln!(body, "}}").unwrap();
+                    }
+                    write!(body, "return {};", self.variant_decoder(last)).unwrap();
+                }
+
+                body
+            }
+        }
+    }
+
+    fn variant_decoder(&self, constructor: &Annotated<Constructor>) -> String {
+        let title = constructor_title(constructor);
+        if constructor.annotated.fields.is_empty() {
+            format!("\"{title}\"")
+        } else {
+            format!("{{ {title}: {} }}", self.constructor_decoder(constructor))
+        }
+    }
+
+    /// Fields of a constructor, decoded from an array named 'fields' in scope.
+    fn constructor_decoder(&self, constructor: &Annotated<Constructor>) -> String {
+        let fields = &constructor.annotated.fields;
+
+        if fields.is_empty() {
+            return format!("\"{}\"", constructor_title(constructor));
+        }
+
+        let values = fields
+            .iter()
+            .enumerate()
+            .map(|(ix, field)| self.field_decoder(field, &format!("fields[{ix}]")));
+
+        match field_names(fields) {
+            Some(names) => format!(
+                "{{ {} }}",
+                names
+                    .iter()
+                    .zip(values)
+                    .map(|(name, value)| format!("{name}: {value}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => format!("[{}]", values.collect::<Vec<_>>().join(", ")),
+        }
+    }
+
+    fn field_decoder(&self, field: &Annotated<Declaration<Data>>, value: &str) -> String {
+        self.declaration_decoder(&field.annotated, value, |data, value| {
+            self.data_decoder(data, value)
+        })
+    }
+}
+
+fn doc_comment(out: &mut String, doc: Option<&str>) {
+    if let Some(doc) = doc.map(str::trim).filter(|doc| !doc.is_empty()) {
+        writeln!(out, "/**").unwrap();
+        for line in doc.lines() {
+            writeln!(out, " * {}", line.trim()).unwrap();
+        }
+        writeln!(out, " */").unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_gift_card() {
+        let blueprint: Blueprint =
+            serde_json::from_str(include_str!("../../../../examples/gift_card/plutus.json"))
+                .unwrap();
+
+        insta::assert_snapshot!(generate(&blueprint));
+    }
+}
@@ -0,0 +1,383 @@
+/** Plutus data, shaped after the detailed JSON schema of cardano-cli. */
+export type PlutusData =
+  | { constructor: number; fields: PlutusData[] }
+  | { int: bigint }
+  | { bytes: string }
+  | { list: PlutusData[] }
+  | { map: { k: PlutusData; v: PlutusData }[] };
+
+export type Network = "mainnet" | "testnet";
+
+/** Serialise Plutus data to CBOR, as a base16-encoded string. */
+export function toCbor(data: PlutusData): string {
+  const out: number[] = [];
+  writeData(data, out);
+  return toHex(out);
+}
+
+/** Deserialise Plutus data from CBOR, given as a base16-encoded string. */
+export function fromCbor(hex: string): PlutusData {
+  const reader = { bytes: fromHex(hex), offset: 0 };
+  const data = readData(reader);
+  if (reader.offset !== reader.bytes.length) {
+    throw new Error(`Unexpected trailing bytes after Plutus data at offset ${reader.offset}`);
+  }
+  return data;
+}
+
+/** The enterprise address (i.e. without delegation part) of a script, from its hash. */
+export function scriptAddress(hash: string, network: Network): string {
+  const header = network === "mainnet" ? 0x71 : 0x70;
+  const prefix = network === "mainnet" ? "addr" : "addr_test";
+  return bech32(prefix, [header, ...fromHex(hash)]);
+}
+
+/** Encode a text string as base16-encoded UTF-8 bytes. */
+export function utf8ToHex(text: string): string {
+  return toHex(Array.from(new TextEncoder().encode(text)));
+}
+
+/** Decode base16-encoded UTF-8 bytes as a text string. */
+export function hexToUtf8(hex: string): string {
+  return new TextDecoder().decode(new Uint8Array(fromHex(hex)));
+}
+
+function toHex(bytes: number[]): string {
+  return bytes.map((byte) => byte.toString(16).padStart(2, "0")).join("");
+}
+
+function fromHex(hex: string): number[] {
+  const bytes: number[] = [];
+  for (let i = 0; i < hex.length; i += 2) {
+    bytes.push(parseInt(hex.slice(i, i + 2), 16));
+  }
+  return bytes;
+}
+
+function writeHead(major: number, arg: bigint, out: number[]): void {
+  if (arg < 24n) {
+    out.push((major << 5) | Number(arg));
+    return;
+  }
+
+  const size = arg < 0x100n ? 1 : arg < 0x10000n ? 2 : arg < 0x100000000n ? 4 : 8;
+  out.push((major << 5) | (24 + Math.log2(size)));
+  for (let i = size - 1; i >= 0; i -= 1) {
+    out.push(Number((arg >> BigInt(8 * i)) & 0xffn));
+  }
+}
+
+// Byte strings longer than 64 bytes are chunked, as mandated by the ledger.
+function writeBytes(bytes: number[], out: number[]): void {
+  if (bytes.length <= 64) {
+    writeHead(2, BigInt(bytes.length), out);
+    out.push(...bytes);
+    return;
+  }
+
+  out.push(0x5f);
+  for (let i = 0; i < bytes.length; i += 64) {
+    writeBytes(bytes.slice(i, i + 64), out);
+  }
+  out.push(0xff);
+}
+
+function writeList(items: PlutusData[], out: number[]): void {
+  if (items.length === 0) {
+    out.push(0x80);
+    return;
+  }
+
+  out.push(0x9f);
+  items.forEach((item) => writeData(item, out));
+  out.push(0xff);
+}
+
+function writeData(data: PlutusData, out: number[]): void {
+  if ("int" in data) {
+    const [major, arg]: [number, bigint] = data.int >= 0n ? [0, data.int] : [1, -data.int - 1n];
+    if (arg < 1n << 64n) {
+      writeHead(major, arg, out);
+    } else {
+      const bytes: number[] = [];
+      for (let n = arg; n > 0n; n >>= 8n) {
+        bytes.unshift(Number(n & 0xffn));
+      }
+      writeHead(6, BigInt(2 + major), out);
+      writeBytes(bytes, out);
+    }
+  } else if ("bytes" in data) {
+    writeBytes(fromHex(data.bytes), out);
+  } else if ("list" in data) {
+    writeList(data.list, out);
+  } else if ("map" in data) {
+    writeHead(5, BigInt(data.map.length), out);
+    data.map.forEach(({ k, v }) => {
+      writeData(k, out);
+      writeData(v, out);
+    });
+  } else {
+    const ix = data.constructor;
+    if (ix < 7) {
+      writeHead(6, BigInt(121 + ix), out);
+    } else if (ix < 128) {
+      writeHead(6, BigInt(1280 + ix - 7), out);
+    } else {
+      writeHead(6, 102n, out);
+      out.push(0x82);
+      writeHead(0, BigInt(ix), out);
+    }
+    writeList(data.fields, out);
+  }
+}
+
+type Reader = { bytes: number[]; offset: number };
+
+function readByte(reader: Reader): number {
+  if (reader.offset >= reader.bytes.length) {
+    throw new Error("Unexpected end of CBOR input");
+  }
+  return reader.bytes[reader.offset++];
+}
+
+// Returns the major type and argument of the next item; the argument is null for indefinite
+// lengths.
+function readHead(reader: Reader): [number, bigint | null] {
+  const byte = readByte(reader);
+  const major = byte >> 5;
+  const info = byte & 0x1f;
+
+  if (info < 24) {
+    return [major, BigInt(info)];
+  }
+
+  if (info === 31) {
+    return [major, null];
+  }
+
+  if (info > 27) {
+    throw new Error(`Unsupported CBOR argument at offset ${reader.offset - 1}`);
+  }
+
+  let arg = 0n;
+  for (let i = 0; i < 1 << (info - 24); i += 1) {
+    arg = (arg << 8n) | BigInt(readByte(reader));
+  }
+  return [major, arg];
+}
+
+function isBreak(reader: Reader): boolean {
+  if (reader.bytes[reader.offset] === 0xff) {
+    reader.offset += 1;
+    return true;
+  }
+  return false;
+}
+
+function readBytes(reader: Reader): number[] {
+  const [major, arg] = readHead(reader);
+  if (major !== 2) {
+    throw new Error(`Expected a byte string at offset ${reader.offset - 1}`);
+  }
+
+  if (arg !== null) {
+    const end = reader.offset + Number(arg);
+    if (end > reader.bytes.length) {
+      throw new Error("Unexpected end of CBOR input");
+    }
+    const bytes = reader.bytes.slice(reader.offset, end);
+    reader.offset = end;
+    return bytes;
+  }
+
+  const bytes: number[] = [];
+  while (!isBreak(reader)) {
+    bytes.push(...readBytes(reader));
+  }
+  return bytes;
+}
+
+function readList(reader: Reader): PlutusData[] {
+  const [major, arg] = readHead(reader);
+  if (major !== 4) {
+    throw new Error(`Expected a list at offset ${reader.offset - 1}`);
+  }
+
+  const items: PlutusData[] = [];
+  if (arg === null) {
+    while (!isBreak(reader)) {
+      items.push(readData(reader));
+    }
+  } else {
+    for (let i = 0n; i < arg; i += 1n) {
+      items.push(readData(reader));
+    }
+  }
+  return items;
+}
+
+function readData(reader: Reader): PlutusData {
+  const start = reader.offset;
+  const [major, arg] = readHead(reader);
+
+  if (major === 0 && arg !== null) {
+    return { int: arg };
+  }
+
+  if (major === 1 && arg !== null) {
+    return { int: -arg - 1n };
+  }
+
+  if (major === 2) {
+    reader.offset = start;
+    return { bytes: toHex(readBytes(reader)) };
+  }
+
+  if (major === 4) {
+    reader.offset = start;
+    return { list: readList(reader) };
+  }
+
+  if (major === 5) {
+    const map: { k: PlutusData; v: PlutusData }[] = [];
+    if (arg === null) {
+      while (!isBreak(reader)) {
+        map.push({ k: readData(reader), v: readData(reader) });
+      }
+    } else {
+      for (let i = 0n; i < arg; i += 1n) {
+        map.push({ k: readData(reader), v: readData(reader) });
+      }
+    }
+    return { map };
+  }
+
+  if (major === 6 && arg !== null) {
+    if (arg === 2n || arg === 3n) {
+      let n = 0n;
+      readBytes(reader).forEach((byte) => {
+        n = (n << 8n) | BigInt(byte);
+      });
+      return { int: arg === 2n ? n : -n - 1n };
+    }
+
+    if (arg >= 121n && arg < 128n) {
+      return { constructor: Number(arg - 121n), fields: readList(reader) };
+    }
+
+    if (arg >= 1280n && arg < 1401n) {
+      return { constructor: Number(arg - 1280n + 7n), fields: readList(reader) };
+    }
+
+    if (arg === 102n) {
+      const [major, length] = readHead(reader);
+      const [tag, ix] = readHead(reader);
+      if (major !== 4 || length !== 2n || tag !== 0 || ix === null) {
+        throw new Error(`Malformed constructor at offset ${start}`);
+      }
+      return { constructor: Number(ix), fields: readList(reader) };
+    }
+  }
+
+  throw new Error(`Unexpected CBOR item at offset ${start}`);
+}
+
+function typeError(expected: string, data: PlutusData): Error {
+  const found = JSON.stringify(data, (_, v) => (typeof v === "bigint" ? v.toString() : v));
+  return new Error(`Expected ${expected}, but got: ${found}`);
+}
+
+function asInt(data: PlutusData): bigint {
+  if (!("int" in data)) {
+    throw typeError("an integer", data);
+  }
+  return data.int;
+}
+
+function asBytes(data: PlutusData): string {
+  if (!("bytes" in data)) {
+    throw typeError("a byte string", data);
+  }
+  return data.bytes;
+}
+
+function asList(data: PlutusData, length?: number): PlutusData[] {
+  if (!("list" in data) || (length !== undefined && data.list.length !== length)) {
+    throw typeError(length === undefined ? "a list" : `a list of ${length} items`, data);
+  }
+  return data.list;
+}
+
+function asMap(data: PlutusData): { k: PlutusData; v: PlutusData }[] {
+  if (!("map" in data)) {
+    throw typeError("a map", data);
+  }
+  return data.map;
+}
+
+// Check a constructor against known indices, mapped to their number of fields.
+function asConstr(
+  data: PlutusData,
+  arities: Record<number, number>,
+): { constructor: number; fields: PlutusData[] } {
+  if (!("fields" in data) || arities[data.constructor] !== data.fields.length) {
+    const expected = Object.entries(arities)
+      .map(([ix, arity]) => `${ix} (with ${arity} fields)`)
+      .join(" or ");
+    throw typeError(`a constructor ${expected}`, data);
+  }
+  return data;
+}
+
+function asUnit(data: PlutusData): null {
+  asConstr(data, { 0: 0 });
+  return null;
+}
+
+const BECH32_CHARSET = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+function bech32(prefix: string, bytes: number[]): string {
+  const words: number[] = [];
+  let acc = 0;
+  let bits = 0;
+  for (const byte of bytes) {
+    acc = ((acc << 8) | byte) & 0xfff;
+    bits += 8;
+    while (bits >= 5) {
+      bits -= 5;
+      words.push((acc >> bits) & 31);
+    }
+  }
+  if (bits > 0) {
+    words.push((acc << (5 - bits)) & 31);
+  }
+
+  const expanded = [
+    ...Array.from(prefix, (c) => c.charCodeAt(0) >> 5),
+    0,
+    ...Array.from(prefix, (c) => c.charCodeAt(0) & 31),
+  ];
+
+  const checksum = bech32Polymod([...expanded, ...words, 0, 0, 0, 0, 0, 0]) ^ 1;
+
+  for (let i = 0; i < 6; i += 1) {
+    words.push((checksum >> (5 * (5 - i))) & 31);
+  }
+
+  return `${prefix}1${words.map((word) => BECH32_CHARSET[word]).join("")}`;
+}
+
+function bech32Polymod(values: number[]): number {
+  const generator = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+  let chk = 1;
+  for (const value of values) {
+    const top = chk >> 25;
+    chk = ((chk & 0x1ffffff) << 5) ^ value;
+    for (let i = 0; i < 5; i += 1) {
+      if ((top >> i) & 1) {
+        chk ^= generator[i];
+      }
+    }
+  }
+  return chk;
+}
@@ -1,6 +1,15 @@
-use reqwest::{blocking::Client, header::USER_AGENT, Error};
+use reqwest::{
+    blocking::Client,
+    header::{ACCEPT, USER_AGENT},
+    Error,
+};
 use serde::Deserialize;
 
+#[derive(Deserialize)]
+struct Tag {
+    name: String,
+}
+
 #[derive(Deserialize)]
 pub struct LatestRelease {
     pub tag_name: String,
@@ -20,3 +29,67 @@ impl LatestRelease {
         })
     }
 }
+
+/// Resolve a git reference (e.g. a branch name) to the full sha of the commit it points to,
+/// trying each registry in order.
+pub fn resolve_commit<Repo: AsRef<str>>(
+    registries: &[String],
+    repo: Repo,
+    reference: &str,
+) -> Result<String, Error> {
+    let client = Client::new();
+
+    let mut result = None;
+
+    for registry in registries {
+        result = Some(
+            client
+                .get(format!(
+                    "{registry}/repos/{}/commits/{}",
+                    repo.as_ref(),
+                    reference
+                ))
+                .header(USER_AGENT, "aiken")
+                .header(ACCEPT, "application/vnd.github.sha")
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.text())
+                .map(|sha| sha.trim().to_string()),
+        );
+
+        if let Some(Ok(..)) = result {
+            break;
+        }
+    }
+
+    result.expect("there's always at least one registry")
+}
+
+/// List the names of a repository's most recent tags (up to a hundred), trying each registry in
+/// order.
+pub fn tags<Repo: AsRef<str>>(registries: &[String], repo: Repo) -> Result<Vec<String>, Error> {
+    let client = Client::new();
+
+    let mut result = None;
+
+    for registry in registries {
+        result = Some(
+            client
+                .get(format!(
+                    "{registry}/repos/{}/tags?per_page=100",
+                    repo.as_ref()
+                ))
+                .header(USER_AGENT, "aiken")
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.json::<Vec<Tag>>())
+                .map(|tags| tags.into_iter().map(|tag| tag.name).collect()),
+        );
+
+        if let Some(Ok(..)) = result {
+            break;
+        }
+    }
+
+    result.expect("there's always at least one registry")
+}
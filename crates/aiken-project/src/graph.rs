@@ -0,0 +1,248 @@
+use crate::module::CheckedModules;
+use aiken_lang::ast::{Definition, ModuleKind, Use, ENV_MODULE};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+/// The modules of a project and of its dependencies, along with the modules each imports.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ModuleGraph {
+    pub modules: BTreeMap<String, ModuleNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleNode {
+    pub package: String,
+    pub kind: ModuleKind,
+    pub imports: BTreeSet<String>,
+}
+
+impl ModuleGraph {
+    pub fn new(modules: &CheckedModules) -> Self {
+        // Importing 'env' imports whichever environment module was selected.
+        let env_modules = modules
+            .values()
+            .filter(|module| module.kind.is_env())
+            .map(|module| module.name.clone())
+            .collect::<Vec<_>>();
+
+        let modules = modules
+            .values()
+            .map(|module| {
+                let imports = module
+                    .ast
+                    .definitions()
+                    .flat_map(|def| match def {
+                        Definition::Use(Use { module, .. }) if module.join("/") == ENV_MODULE => {
+                            env_modules.clone()
+                        }
+                        Definition::Use(Use { module, .. }) => vec![module.join("/")],
+                        _ => vec![],
+                    })
+                    .collect();
+
+                (
+                    module.name.clone(),
+                    ModuleNode {
+                        package: module.package.clone(),
+                        kind: module.kind,
+                        imports,
+                    },
+                )
+            })
+            .collect();
+
+        ModuleGraph { modules }
+    }
+
+    /// Restrict the graph to the given module and those it transitively imports.
+    pub fn reachable_from(&self, root: &str) -> Self {
+        let mut modules = BTreeMap::new();
+
+        let mut queue = vec![root.to_string()];
+
+        while let Some(name) = queue.pop() {
+            if modules.contains_key(&name) {
+                continue;
+            }
+
+            if let Some(node) = self.modules.get(&name) {
+                queue.extend(node.imports.iter().cloned());
+                modules.insert(name, node.clone());
+            }
+        }
+
+        ModuleGraph { modules }
+    }
+
+    /// Packages along with the packages they depend on, as witnessed by imports across them.
+    pub fn packages(&self) -> BTreeMap<&str, BTreeSet<&str>> {
+        let mut packages: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+
+        for node in self.modules.values() {
+            let dependencies = packages.entry(node.package.as_str()).or_default();
+
+            for import in &node.imports {
+                if let Some(imported) = self.modules.get(import) {
+                    if imported.package != node.package {
+                        dependencies.insert(imported.package.as_str());
+                    }
+                }
+            }
+        }
+
+        packages
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "modules": self.modules.iter().map(|(name, node)| json!({
+                "name": name,
+                "package": node.package,
+                "kind": kind(node.kind),
+                "imports": node.imports,
+            })).collect::<Vec<_>>(),
+            "packages": self.packages().into_iter().map(|(name, dependencies)| json!({
+                "name": name,
+                "dependencies": dependencies,
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    /// The graph in Graphviz's DOT language, with the modules of each package clustered together.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+
+        let _ = writeln!(dot, "digraph aiken {{");
+        let _ = writeln!(dot, "  rankdir=LR;");
+        let _ = writeln!(dot, "  node [shape=box];");
+
+        for (ix, package) in self.packages().keys().enumerate() {
+            let _ = writeln!(dot, "  subgraph cluster_{ix} {{");
+            let _ = writeln!(dot, "    label={:?};", package);
+
+            for (name, node) in &self.modules {
+                if node.package == *package {
+                    let _ = writeln!(
+                        dot,
+                        "    {:?}{};",
+                        name,
+                        match node.kind {
+                            ModuleKind::Validator => " [style=bold]",
+                            ModuleKind::Lib | ModuleKind::Env | ModuleKind::Config => "",
+                        }
+                    );
+                }
+            }
+
+            let _ = writeln!(dot, "  }}");
+        }
+
+        for (name, node) in &self.modules {
+            for import in node
+                .imports
+                .iter()
+                .filter(|m| self.modules.contains_key(*m))
+            {
+                let _ = writeln!(dot, "  {:?} -> {:?};", name, import);
+            }
+        }
+
+        let _ = writeln!(dot, "}}");
+
+        dot
+    }
+}
+
+fn kind(kind: ModuleKind) -> &'static str {
+    match kind {
+        ModuleKind::Lib => "lib",
+        ModuleKind::Validator => "validator",
+        ModuleKind::Env => "env",
+        ModuleKind::Config => "config",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(package: &str, kind: ModuleKind, imports: &[&str]) -> ModuleNode {
+        ModuleNode {
+            package: package.to_string(),
+            kind,
+            imports: imports.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn graph() -> ModuleGraph {
+        ModuleGraph {
+            modules: BTreeMap::from([
+                (
+                    "vault".to_string(),
+                    node("acme/vault", ModuleKind::Validator, &["vault/utils"]),
+                ),
+                (
+                    "oracle".to_string(),
+                    node("acme/vault", ModuleKind::Validator, &["aiken/crypto"]),
+                ),
+                (
+                    "vault/utils".to_string(),
+                    node("acme/vault", ModuleKind::Lib, &["aiken/collection/list"]),
+                ),
+                (
+                    "aiken/collection/list".to_string(),
+                    node("aiken-lang/stdlib", ModuleKind::Lib, &["aiken/builtin"]),
+                ),
+                (
+                    "aiken/crypto".to_string(),
+                    node("aiken-lang/stdlib", ModuleKind::Lib, &[]),
+                ),
+            ]),
+        }
+    }
+
+    #[test]
+    fn reachable_from_validator() {
+        let graph = graph().reachable_from("vault");
+
+        assert_eq!(
+            graph.modules.keys().collect::<Vec<_>>(),
+            vec!["aiken/collection/list", "vault", "vault/utils"]
+        );
+
+        assert_eq!(
+            graph.packages(),
+            BTreeMap::from([
+                ("acme/vault", BTreeSet::from(["aiken-lang/stdlib"])),
+                ("aiken-lang/stdlib", BTreeSet::new()),
+            ])
+        );
+    }
+
+    #[test]
+    fn to_dot() {
+        assert_eq!(
+            graph().reachable_from("vault").to_dot(),
+            indoc::indoc! { r#"
+                digraph aiken {
+                  rankdir=LR;
+                  node [shape=box];
+                  subgraph cluster_0 {
+                    label="acme/vault";
+                    "vault" [style=bold];
+                    "vault/utils";
+                  }
+                  subgraph cluster_1 {
+                    label="aiken-lang/stdlib";
+                    "aiken/collection/list";
+                  }
+                  "vault" -> "vault/utils";
+                  "vault/utils" -> "aiken/collection/list";
+                }
+            "# }
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    config::Config,
+    error::Error,
+    telemetry::{Event, EventListener},
+};
+use std::{
+    fmt::{self, Display},
+    io,
+    path::Path,
+    process::Command,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    PreBuild,
+    PostBuild,
+}
+
+impl Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hook::PreBuild => write!(f, "pre_build"),
+            Hook::PostBuild => write!(f, "post_build"),
+        }
+    }
+}
+
+impl Hook {
+    fn commands<'a>(&self, config: &'a Config) -> &'a [String] {
+        match self {
+            Hook::PreBuild => &config.hooks.pre_build,
+            Hook::PostBuild => &config.hooks.post_build,
+        }
+    }
+}
+
+/// What a hook gets to know about the build, exposed to commands as environment variables.
+pub struct HookContext<'a> {
+    pub root: &'a Path,
+    pub blueprint_path: &'a Path,
+    pub env: Option<&'a str>,
+}
+
+impl HookContext<'_> {
+    fn vars(&self, hook: Hook, config: &Config) -> Vec<(&'static str, String)> {
+        vec![
+            ("AIKEN_HOOK", hook.to_string()),
+            ("AIKEN_PROJECT_NAME", config.name.to_string()),
+            ("AIKEN_PROJECT_VERSION", config.version.clone()),
+            ("AIKEN_PROJECT_ROOT", self.root.display().to_string()),
+            ("AIKEN_BLUEPRINT", self.blueprint_path.display().to_string()),
+            (
+                "AIKEN_ENV",
+                self.env
+                    .unwrap_or(aiken_lang::ast::DEFAULT_ENV_MODULE)
+                    .to_string(),
+            ),
+        ]
+    }
+}
+
+/// Run the commands declared for the given hook, in order, from the project's root. Output of
+/// commands goes to stderr, so as to not interfere with any output of the CLI itself. Stops at
+/// the first command failing.
+pub fn run<T>(
+    hook: Hook,
+    config: &Config,
+    context: &HookContext,
+    event_listener: &T,
+) -> Result<(), Error>
+where
+    T: EventListener,
+{
+    for command in hook.commands(config) {
+        event_listener.handle_event(Event::RunningHook {
+            hook: hook.to_string(),
+            command: command.clone(),
+        });
+
+        let failed = |reason: String| Error::HookFailed {
+            hook: hook.to_string(),
+            command: command.clone(),
+            reason,
+        };
+
+        let status = shell(command)
+            .current_dir(context.root)
+            .envs(context.vars(hook, config))
+            .stdout(io::stderr())
+            .status()
+            .map_err(|e| failed(e.to_string()))?;
+
+        if !status.success() {
+            return Err(failed(match status.code() {
+                Some(code) => format!("The command exited with status {code}."),
+                None => "The command was terminated by a signal.".to_string(),
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    struct NoListener;
+
+    impl EventListener for NoListener {}
+
+    fn config(src: &str) -> Config {
+        toml::from_str(src).expect("invalid test config")
+    }
+
+    #[test]
+    fn run_exposes_build_context() {
+        let root = std::env::temp_dir().join("aiken-hooks-context");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        let config = config(
+            r#"
+            name = "aiken-lang/test"
+            version = "1.0.0"
+
+            [hooks]
+            post_build = ["echo \"$AIKEN_HOOK $AIKEN_PROJECT_NAME $AIKEN_ENV $AIKEN_BLUEPRINT\" > out.txt"]
+            "#,
+        );
+
+        let context = HookContext {
+            root: &root,
+            blueprint_path: Path::new("plutus.json"),
+            env: Some("preview"),
+        };
+
+        run(Hook::PreBuild, &config, &context, &NoListener).unwrap();
+        assert!(!root.join("out.txt").exists());
+
+        run(Hook::PostBuild, &config, &context, &NoListener).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("out.txt")).unwrap(),
+            "post_build aiken-lang/test preview plutus.json\n"
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn run_stops_on_failure() {
+        let config = config(
+            r#"
+            name = "aiken-lang/test"
+            version = "1.0.0"
+
+            [hooks]
+            pre_build = ["exit 3", "touch should-not-exist"]
+            "#,
+        );
+
+        let root = std::env::temp_dir();
+
+        let context = HookContext {
+            root: &root,
+            blueprint_path: Path::new("plutus.json"),
+            env: None,
+        };
+
+        assert!(matches!(
+            run(Hook::PreBuild, &config, &context, &NoListener),
+            Err(Error::HookFailed { command, .. }) if command == "exit 3"
+        ));
+        assert!(!root.join("should-not-exist").exists());
+    }
+}
@@ -1,34 +1,67 @@
+pub mod address;
+pub mod benchmark;
 pub mod blueprint;
+pub mod budgets;
+pub mod cache;
 pub mod config;
+pub mod coverage;
+#[cfg(feature = "native")]
+pub mod daemon;
 pub mod deps;
 pub mod docs;
 pub mod error;
 pub mod export;
 pub mod format;
+pub mod generate;
+#[cfg(feature = "native")]
 pub mod github;
+pub mod graph;
+pub mod hooks;
 pub mod module;
 pub mod options;
 pub mod package_name;
 pub mod paths;
 pub mod pretty;
+#[cfg(feature = "native")]
+pub mod provider;
+pub mod repl;
+#[cfg(feature = "native")]
+pub mod serve;
 pub mod telemetry;
+pub mod timings;
+pub mod verify;
+#[cfg(feature = "native")]
 pub mod watch;
 
+mod test_filter;
 mod test_framework;
 
 #[cfg(test)]
 mod tests;
 
 use crate::{
+    address::AddressInfo,
+    benchmark::Baseline,
     blueprint::{
-        definitions::Definitions,
+        definitions::{Definitions, Reference},
+        metadata::Metadata,
+        parameter::Parameter,
         schema::{Annotated, Schema},
+        validator::Validator,
         Blueprint,
     },
+    budgets::BudgetsLock,
+    cache::{BuildCache, MemoryCache},
     config::Config,
+    coverage::{Hits, ModuleCoverage},
+    deps::artifacts::ArtifactsCache,
     error::{Error, Warning},
+    graph::ModuleGraph,
+    hooks::{Hook, HookContext},
     module::{CheckedModule, CheckedModules, ParsedModule, ParsedModules},
     telemetry::Event,
+    test_filter::TestPattern,
+    timings::{Phase, Timings},
 };
 use aiken_lang::{
     ast::{
@@ -38,18 +71,23 @@ use aiken_lang::{
     builtins,
     expr::{TypedExpr, UntypedExpr},
     format::{Formatter, MAX_COLUMNS},
-    gen_uplc::CodeGenerator,
+    gen_uplc::{export::AirExport, CodeGenerator},
     line_numbers::LineNumbers,
-    test_framework::{Test, TestResult},
+    plutus_version::{unavailable_builtins_in, PlutusVersion},
+    smt,
+    test_framework::{Benchmark, BenchmarkError, BenchmarkResult, Test, TestResult},
     tipo::{Type, TypeInfo},
     utils, IdGenerator,
 };
+#[cfg(feature = "native")]
+use deps::advisories::AdvisoryDb;
 use export::Export;
 use indexmap::IndexMap;
 use miette::NamedSource;
-use options::{CodeGenMode, Options};
+use options::{CodeGenMode, Emit, Options};
 use package_name::PackageName;
 use pallas_addresses::{Address, Network, ShelleyAddress, ShelleyDelegationPart, StakePayload};
+use pallas_crypto::hash::Hash;
 use pallas_primitives::conway::PolicyId;
 use std::{
     collections::{BTreeSet, HashMap, HashSet},
@@ -57,14 +95,18 @@ use std::{
     io::BufReader,
     path::{Path, PathBuf},
     rc::Rc,
+    str::FromStr,
+    time::Instant,
 };
 use telemetry::EventListener;
 use uplc::{
     ast::{Constant, Name, Program},
+    machine::profile,
+    optimize::OptimizationLevel,
     PlutusData,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Source {
     pub path: PathBuf,
     pub name: String,
@@ -77,12 +119,6 @@ pub struct Checkpoint {
     defined_modules: HashMap<String, PathBuf>,
 }
 
-#[derive(Debug, Clone)]
-enum AddModuleBy {
-    Source { name: String, code: String },
-    Path(PathBuf),
-}
-
 pub struct Project<T>
 where
     T: EventListener,
@@ -101,6 +137,18 @@ where
     constants: IndexMap<FunctionAccessKey, TypedExpr>,
     data_types: IndexMap<DataTypeKey, TypedDataType>,
     module_sources: HashMap<String, (String, LineNumbers)>,
+    features: BTreeSet<String>,
+    optimization: OptimizationLevel,
+    lock_budgets: bool,
+    /// Whether to load every module of dependencies, rather than only those reachable from the
+    /// project's own modules.
+    all_dependencies: bool,
+    timings: Timings,
+    memory: MemoryCache,
+    /// Sources of the project's own modules, when held in memory rather than read from disk.
+    in_memory: Option<Vec<Source>>,
+    /// Blueprint of the last build.
+    blueprint: Option<Blueprint>,
 }
 
 impl<T> Project<T>
@@ -112,8 +160,17 @@ where
 
         let demanded_compiler_version = format!("v{}", config.compiler);
 
+        let deprecated_plutus_version = config.plutus_version.is_some();
+
         let mut project = Project::new_with_config(config, root, event_listener);
 
+        if deprecated_plutus_version {
+            project.warnings.push(Warning::DeprecatedConfigKey {
+                key: "plutusVersion".to_string(),
+                replacement: "plutus".to_string(),
+            });
+        }
+
         let current_compiler_version = config::compiler_version(false);
 
         if demanded_compiler_version != current_compiler_version {
@@ -138,6 +195,8 @@ where
 
         let data_types = builtins::prelude_data_types(&id_gen);
 
+        let features = config.resolve_features(&[], true).unwrap_or_default();
+
         Project {
             config,
             checked_modules: CheckedModules::default(),
@@ -153,9 +212,55 @@ where
             constants: IndexMap::new(),
             data_types,
             module_sources: HashMap::new(),
+            features,
+            optimization: OptimizationLevel::default(),
+            lock_budgets: false,
+            all_dependencies: false,
+            timings: Timings::default(),
+            memory: MemoryCache::default(),
+            in_memory: None,
+            blueprint: None,
         }
     }
 
+    /// A project made of modules held in memory, e.g. to compile in a browser. Such a project has
+    /// no dependencies and leaves the filesystem alone: builds don't write the blueprint, which is
+    /// available from [`Project::blueprint`] instead, nor do they use the build cache or the budgets
+    /// lock.
+    pub fn in_memory(config: Config, sources: Vec<Source>, event_listener: T) -> Project<T> {
+        let mut project = Project::new_with_config(config, PathBuf::new(), event_listener);
+
+        project.in_memory = Some(sources);
+
+        project
+    }
+
+    /// Select the features to compile with, on top of the default ones unless disabled.
+    pub fn with_features(
+        &mut self,
+        features: &[String],
+        default_features: bool,
+    ) -> Result<(), Vec<Error>> {
+        self.features = self
+            .config
+            .resolve_features(features, default_features)
+            .map_err(|e| vec![e])?;
+
+        Ok(())
+    }
+
+    /// Select how hard the optimizer works on generated programs. Validators with their own
+    /// 'optimization' setting in the configuration keep it.
+    pub fn with_optimization(&mut self, optimization: OptimizationLevel) {
+        self.optimization = optimization;
+    }
+
+    /// Record validator sizes and test budgets in 'budgets.lock', instead of comparing them
+    /// against it.
+    pub fn lock_budgets(&mut self) {
+        self.lock_budgets = true;
+    }
+
     pub fn new_generator(&'_ self, tracing: Tracing) -> CodeGenerator<'_> {
         CodeGenerator::new(
             self.config.plutus,
@@ -166,10 +271,40 @@ where
             utils::indexmap::as_str_ref_values(&self.module_sources),
             tracing,
         )
+        .with_optimization(self.optimization)
     }
 
+    /// Take the warnings raised so far, leaving out those allowed in the configuration.
     pub fn warnings(&mut self) -> Vec<Warning> {
-        std::mem::take(&mut self.warnings)
+        let mut warnings = std::mem::take(&mut self.warnings);
+        warnings.retain(|warning| !self.config.diagnostics.allows(warning));
+        warnings
+    }
+
+    /// Time spent in each phase of the compilation so far.
+    pub fn timings(&self) -> &Timings {
+        &self.timings
+    }
+
+    /// Write the timings of the compilation as JSON in the build folder, returning its path.
+    pub fn write_timings(&self) -> Result<PathBuf, Error> {
+        let path = self.root.join(paths::timings());
+
+        let json = serde_json::to_string_pretty(&self.timings.to_json()).unwrap();
+
+        fs::create_dir_all(self.root.join(paths::build()))
+            .and_then(|_| fs::write(&path, json))
+            .map_err(|error| Error::FileIo {
+                error,
+                path: path.clone(),
+            })?;
+
+        Ok(path)
+    }
+
+    /// Blueprint of the last build, if any.
+    pub fn blueprint(&self) -> Option<&Blueprint> {
+        self.blueprint.as_ref()
     }
 
     pub fn modules(&self) -> Vec<CheckedModule> {
@@ -180,6 +315,19 @@ where
         self.module_types.keys().cloned().collect()
     }
 
+    /// Hand over modules and blueprint kept in memory by a previous project, when compiling the
+    /// same project repeatedly (e.g. in watch mode).
+    pub fn with_memory_cache(&mut self, memory: MemoryCache) {
+        self.id_gen.skip_past(memory.next_id);
+        self.memory = memory;
+    }
+
+    /// Take back the modules and blueprint kept in memory, to hand them over to the next project.
+    pub fn take_memory_cache(&mut self) -> MemoryCache {
+        self.memory.next_id = self.id_gen.next();
+        std::mem::take(&mut self.memory)
+    }
+
     pub fn checkpoint(&self) -> Checkpoint {
         Checkpoint {
             module_types: self.module_types.clone(),
@@ -199,15 +347,30 @@ where
         }
     }
 
+    /// Environments the project can be built against: those defined as modules under 'env', and
+    /// those given a configuration in 'aiken.toml'.
+    pub fn environments(&self) -> BTreeSet<String> {
+        let env = self.root.join("env");
+
+        walkdir::WalkDir::new(&env)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file() && is_aiken_path(e.path(), &env))
+            .map(|e| self.module_name(&env, e.path()))
+            .chain(self.config.config.keys().cloned())
+            .collect()
+    }
+
     pub fn build(
         &mut self,
-        uplc: bool,
+        emit: Emit,
         tracing: Tracing,
         blueprint_path: PathBuf,
         env: Option<String>,
     ) -> Result<(), Vec<Error>> {
         let options = Options {
-            code_gen_mode: CodeGenMode::Build(uplc),
+            code_gen_mode: CodeGenMode::Build(emit),
             tracing,
             env,
             blueprint_path,
@@ -216,6 +379,22 @@ where
         self.compile(options)
     }
 
+    /// Run commands declared in the project's configuration for the given hook.
+    pub fn run_hook(
+        &self,
+        hook: Hook,
+        blueprint_path: &Path,
+        env: Option<&str>,
+    ) -> Result<(), Vec<Error>> {
+        let context = HookContext {
+            root: &self.root,
+            blueprint_path,
+            env,
+        };
+
+        hooks::run(hook, &self.config, &context, &self.event_listener).map_err(|e| vec![e])
+    }
+
     pub fn docs(
         &mut self,
         destination: Option<PathBuf>,
@@ -232,7 +411,10 @@ where
 
         self.read_source_files(config)?;
 
-        let mut modules = self.parse_sources(self.config.name.clone())?;
+        let mut modules = self.parse_sources(self.config.name.clone(), &self.features.clone())?;
+
+        // Documented dependency modules aren't necessarily imported by the project.
+        self.all_dependencies = include_dependencies;
 
         self.type_check(&mut modules, Tracing::silent(), None, false)?;
 
@@ -270,10 +452,13 @@ where
         &mut self,
         skip_tests: bool,
         match_tests: Option<Vec<String>>,
+        skip: Vec<String>,
         verbose: bool,
         exact_match: bool,
         seed: u32,
         property_max_success: usize,
+        exhaustive: bool,
+        profile: Option<PathBuf>,
         tracing: Tracing,
         env: Option<String>,
     ) -> Result<(), Vec<Error>> {
@@ -285,10 +470,13 @@ where
             } else {
                 CodeGenMode::Test {
                     match_tests,
+                    skip,
                     verbose,
                     exact_match,
                     seed,
                     property_max_success,
+                    exhaustive,
+                    profile,
                 }
             },
             blueprint_path: self.blueprint_path(None),
@@ -297,6 +485,99 @@ where
         self.compile(options)
     }
 
+    /// Run the project's benchmarks, and compare them against the baseline file. The baseline is
+    /// written when it doesn't exist yet, or when asked to save it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn benchmark(
+        &mut self,
+        match_benchmarks: Option<Vec<String>>,
+        exact_match: bool,
+        seed: Option<u32>,
+        max_size: usize,
+        baseline: Option<PathBuf>,
+        save_baseline: bool,
+        threshold: f64,
+        tracing: Tracing,
+        env: Option<String>,
+    ) -> Result<(), Vec<Error>> {
+        let options = Options {
+            tracing,
+            env,
+            code_gen_mode: CodeGenMode::Benchmark {
+                match_benchmarks,
+                exact_match,
+                seed,
+                max_size,
+                baseline: self
+                    .root
+                    .join(baseline.unwrap_or_else(|| PathBuf::from(benchmark::DEFAULT_BASELINE))),
+                save_baseline,
+                threshold,
+            },
+            blueprint_path: self.blueprint_path(None),
+        };
+
+        self.compile(options)
+    }
+
+    /// Run the project's tests with instrumented code, and write a coverage report of its
+    /// functions and validators (as HTML and lcov) to the given directory.
+    #[allow(clippy::too_many_arguments)]
+    pub fn coverage(
+        &mut self,
+        match_tests: Option<Vec<String>>,
+        skip: Vec<String>,
+        exact_match: bool,
+        seed: u32,
+        property_max_success: usize,
+        output: Option<PathBuf>,
+        tracing: Tracing,
+        env: Option<String>,
+    ) -> Result<(), Vec<Error>> {
+        let options = Options {
+            tracing,
+            env,
+            code_gen_mode: CodeGenMode::Coverage {
+                match_tests,
+                skip,
+                exact_match,
+                seed,
+                property_max_success,
+                output: self
+                    .root
+                    .join(output.unwrap_or_else(|| PathBuf::from(coverage::DEFAULT_OUTPUT))),
+            },
+            blueprint_path: self.blueprint_path(None),
+        };
+
+        self.compile(options)
+    }
+
+    /// Verify the project's properties and validators with an SMT solver, given as a program
+    /// followed by its arguments. Verification conditions are also written to `emit`, if any.
+    /// With `paths`, the paths through each validator handler are explored as well.
+    pub fn verify(
+        &mut self,
+        solver: Vec<String>,
+        emit: Option<PathBuf>,
+        paths: bool,
+        tracing: Tracing,
+        env: Option<String>,
+    ) -> Result<(), Vec<Error>> {
+        let options = Options {
+            tracing,
+            env,
+            code_gen_mode: CodeGenMode::Verify {
+                solver,
+                emit,
+                paths,
+            },
+            blueprint_path: self.blueprint_path(None),
+        };
+
+        self.compile(options)
+    }
+
     pub fn dump_uplc(&self, blueprint: &Blueprint) -> Result<(), Error> {
         let dir = self.root.join("artifacts");
 
@@ -317,6 +598,31 @@ where
         Ok(())
     }
 
+    pub fn dump_air(&self, tracing: Tracing) -> Result<(), Error> {
+        let dir = self.root.join("artifacts");
+
+        self.event_listener
+            .handle_event(Event::DumpingAIR { path: dir.clone() });
+
+        fs::create_dir_all(&dir)?;
+
+        let mut generator = self.new_generator(tracing);
+
+        for (module, validator) in self.checked_modules.validators() {
+            let title = format!("{}.{}", module.name, validator.name);
+
+            let air = generator.generate_air(validator, &module.name);
+
+            let json = serde_json::to_string_pretty(&AirExport::new(&title, &air)).unwrap();
+
+            let path = dir.clone().join(format!("{title}.air.json"));
+
+            fs::write(&path, json).map_err(|error| Error::FileIo { error, path })?;
+        }
+
+        Ok(())
+    }
+
     fn config_definitions(&mut self, env: Option<&str>) -> Option<Vec<UntypedDefinition>> {
         if !self.config.config.is_empty() {
             let env = env.unwrap_or(ast::DEFAULT_ENV_MODULE);
@@ -357,12 +663,12 @@ where
 
         self.read_source_files(config)?;
 
-        let mut modules = self.parse_sources(self.config.name.clone())?;
+        let mut modules = self.parse_sources(self.config.name.clone(), &self.features.clone())?;
 
         self.type_check(&mut modules, options.tracing, env, true)?;
 
         match options.code_gen_mode {
-            CodeGenMode::Build(uplc_dump) => {
+            CodeGenMode::Build(emit) => {
                 self.event_listener
                     .handle_event(Event::GeneratingBlueprint {
                         path: options.blueprint_path.clone(),
@@ -372,44 +678,141 @@ where
                     m.attach_doc_and_module_comments();
                 });
 
-                let mut generator = self.new_generator(options.tracing);
+                // Only record the target environment when the project is sensitive to it.
+                let has_env = !self.config.config.is_empty()
+                    || self
+                        .checked_modules
+                        .values()
+                        .any(|m| m.kind == ModuleKind::Env);
+
+                let env = match env {
+                    Some(env) => Some(env),
+                    None if has_env => Some(ast::DEFAULT_ENV_MODULE),
+                    None => None,
+                };
+
+                let fingerprint = MemoryCache::blueprint_fingerprint(
+                    &format!(
+                        "{}:{:?}:{env:?}:{:?}:{:?}",
+                        serde_json::to_string(&self.config).unwrap_or_default(),
+                        options.tracing,
+                        self.features,
+                        self.optimization,
+                    ),
+                    self.checked_modules.values(),
+                );
+
+                let mut blueprint = match self.memory.load_blueprint(&fingerprint) {
+                    Some(blueprint) => blueprint,
+                    None => {
+                        let mut generator = self.new_generator(options.tracing);
+
+                        let blueprint = Blueprint::new(
+                            &self.config,
+                            &self.checked_modules,
+                            &mut generator,
+                            env,
+                        )
+                        .map_err(Error::Blueprint)?;
+
+                        for (module, elapsed) in generator.elapsed().clone() {
+                            self.timings.record(Phase::CodeGen, elapsed.generating);
+                            self.timings.record(Phase::Optimize, elapsed.optimizing);
+                            self.timings
+                                .record_module(&module, Phase::CodeGen, elapsed.generating);
+                            self.timings.record_module(
+                                &module,
+                                Phase::Optimize,
+                                elapsed.optimizing,
+                            );
+                        }
+
+                        self.memory.store_blueprint(&fingerprint, &blueprint);
+
+                        blueprint
+                    }
+                };
+
+                if let Some(source) = blueprint.source.as_mut().filter(|_| !self.is_in_memory()) {
+                    let hash =
+                        blueprint::source::Source::hash_tree(&self.root).map_err(|error| {
+                            Error::FileIo {
+                                error,
+                                path: self.root.clone(),
+                            }
+                        })?;
 
-                let blueprint = Blueprint::new(&self.config, &self.checked_modules, &mut generator)
-                    .map_err(Error::Blueprint)?;
+                    source.hash = Some(hash);
+                }
 
                 if blueprint.validators.is_empty() {
                     self.warnings.push(Warning::NoValidators);
                 }
 
-                if uplc_dump {
+                if emit.uplc {
                     self.dump_uplc(&blueprint)?;
                 }
 
+                if emit.air {
+                    self.dump_air(options.tracing)?;
+                }
+
+                let start = Instant::now();
+
                 let json = serde_json::to_string_pretty(&blueprint).unwrap();
 
-                fs::write(options.blueprint_path.as_path(), json).map_err(|error| {
-                    Error::FileIo {
-                        error,
-                        path: options.blueprint_path,
-                    }
-                    .into()
-                })
+                // Leave the blueprint untouched when unchanged, so as not to trigger whatever
+                // watches it (e.g. off-chain code generators) for nothing.
+                if !self.is_in_memory()
+                    && fs::read_to_string(options.blueprint_path.as_path()).ok()
+                        != Some(json.clone())
+                {
+                    fs::write(options.blueprint_path.as_path(), json).map_err(|error| {
+                        Error::FileIo {
+                            error,
+                            path: options.blueprint_path,
+                        }
+                    })?;
+                }
+
+                self.timings.record(Phase::Serialize, start.elapsed());
+
+                let result = self.check_budgets(BudgetsLock::from_blueprint(&blueprint));
+
+                self.blueprint = Some(blueprint);
+
+                result
             }
             CodeGenMode::Test {
                 match_tests,
+                skip,
                 verbose,
                 exact_match,
                 seed,
                 property_max_success,
+                exhaustive,
+                profile,
             } => {
-                let tests =
-                    self.collect_tests(verbose, match_tests, exact_match, options.tracing)?;
+                let tests = self.collect_tests(
+                    verbose,
+                    match_tests,
+                    skip,
+                    exact_match,
+                    options.tracing,
+                    false,
+                )?;
+
+                if let Some(dir) = profile {
+                    self.profile_tests(&tests, &dir)?;
+                }
 
                 if !tests.is_empty() {
-                    self.event_listener.handle_event(Event::RunningTests);
+                    self.event_listener.handle_event(Event::RunningTests {
+                        seed: has_property_tests(&tests).then_some(seed),
+                    });
                 }
 
-                let tests = self.run_tests(tests, seed, property_max_success);
+                let tests = self.run_tests(tests, seed, property_max_success, exhaustive);
 
                 self.checks_count = if tests.is_empty() {
                     None
@@ -422,7 +825,7 @@ where
                     }))
                 };
 
-                let errors: Vec<Error> = tests
+                let mut errors: Vec<Error> = tests
                     .iter()
                     .filter_map(|e| {
                         if e.is_success() {
@@ -433,101 +836,418 @@ where
                     })
                     .collect();
 
+                let budgets = BudgetsLock::from_tests(&tests);
+
                 self.event_listener
                     .handle_event(Event::FinishedTests { seed, tests });
 
+                if let Err(regressions) = self.check_budgets(budgets) {
+                    errors.extend(regressions);
+                }
+
                 if !errors.is_empty() {
                     Err(errors)
                 } else {
                     Ok(())
                 }
             }
-            CodeGenMode::NoOp => Ok(()),
-        }
-    }
+            CodeGenMode::Benchmark {
+                match_benchmarks,
+                exact_match,
+                seed,
+                max_size,
+                baseline: baseline_path,
+                save_baseline,
+                threshold,
+            } => {
+                let baseline = Baseline::load(&baseline_path)?;
 
-    pub fn address(
-        &self,
-        module_name: Option<&str>,
-        validator_name: Option<&str>,
-        stake_address: Option<&str>,
-        blueprint_path: &Path,
-        mainnet: bool,
-    ) -> Result<ShelleyAddress, Error> {
-        // Parse stake address
-        let stake_address = stake_address
-            .map(|s| {
-                Address::from_hex(s)
-                    .or_else(|_| Address::from_bech32(s))
-                    .map_err(|error| Error::MalformedStakeAddress { error: Some(error) })
-                    .and_then(|addr| match addr {
-                        Address::Stake(addr) => Ok(addr),
-                        _ => Err(Error::MalformedStakeAddress { error: None }),
+                let seed = seed
+                    .or(baseline.as_ref().map(|baseline| baseline.seed))
+                    .unwrap_or(benchmark::DEFAULT_SEED);
+
+                let benchmarks =
+                    self.collect_benchmarks(match_benchmarks, exact_match, options.tracing)?;
+
+                if !benchmarks.is_empty() {
+                    self.event_listener.handle_event(Event::RunningBenchmarks);
+                }
+
+                let results = self.run_benchmarks(benchmarks, seed, max_size);
+
+                let mut errors: Vec<Error> = results
+                    .iter()
+                    .filter_map(|result| {
+                        result.error.as_ref().map(|error| Error::BenchmarkFailure {
+                            name: result.bench.name.clone(),
+                            path: result.bench.input_path.clone(),
+                            reason: match error {
+                                BenchmarkError::Run { traces, .. } if !traces.is_empty() => {
+                                    format!("{error}\n{}", traces.join("\n"))
+                                }
+                                _ => error.to_string(),
+                            },
+                        })
                     })
-            })
-            .transpose()?;
-        let delegation_part = match stake_address.map(|addr| addr.payload().to_owned()) {
-            None => ShelleyDelegationPart::Null,
-            Some(StakePayload::Stake(key)) => ShelleyDelegationPart::Key(key),
-            Some(StakePayload::Script(script)) => ShelleyDelegationPart::Script(script),
-        };
+                    .collect();
 
-        // Read blueprint
-        let blueprint = File::open(blueprint_path)
-            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
-        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+                let current = Baseline::new(seed, &results);
 
-        // Calculate the address
-        let when_too_many =
-            |known_validators| Error::MoreThanOneValidatorFound { known_validators };
-        let when_missing = |known_validators| Error::NoValidatorNotFound { known_validators };
+                let paths = results
+                    .iter()
+                    .map(|result| (benchmark::key(result), result.bench.input_path.clone()))
+                    .collect::<HashMap<_, _>>();
 
-        blueprint.with_validator(
-            module_name,
-            validator_name,
-            when_too_many,
-            when_missing,
-            |validator| {
-                let n = validator.parameters.len();
+                self.event_listener.handle_event(Event::FinishedBenchmarks {
+                    seed,
+                    benchmarks: results,
+                });
 
-                if n > 0 {
-                    Err(blueprint::error::Error::ParameterizedValidator { n }.into())
+                match baseline {
+                    Some(baseline) if !save_baseline => {
+                        errors.extend(baseline.regressions(&current, threshold).into_iter().map(
+                            |regression| Error::BenchmarkRegression {
+                                path: paths[&regression.benchmark].clone(),
+                                name: regression.benchmark,
+                                metric: regression.metric,
+                                baseline: regression.baseline,
+                                current: regression.current,
+                                threshold,
+                            },
+                        ));
+                    }
+                    baseline => {
+                        // Keep benchmarks that weren't run this time (e.g. when matching only
+                        // some of them), unless they were measured from another seed.
+                        let mut saved = baseline
+                            .filter(|baseline| baseline.seed == seed)
+                            .unwrap_or_else(|| Baseline {
+                                seed,
+                                benchmarks: Default::default(),
+                            });
+
+                        saved.benchmarks.extend(current.benchmarks);
+
+                        self.event_listener.handle_event(Event::SavingBaseline {
+                            path: baseline_path.clone(),
+                        });
+
+                        saved.save(&baseline_path)?;
+                    }
+                }
+
+                if !errors.is_empty() {
+                    Err(errors)
                 } else {
-                    let network = if mainnet {
-                        Network::Mainnet
-                    } else {
-                        Network::Testnet
-                    };
+                    Ok(())
+                }
+            }
+            CodeGenMode::Coverage {
+                match_tests,
+                skip,
+                exact_match,
+                seed,
+                property_max_success,
+                output,
+            } => {
+                let tests = self.collect_tests(
+                    false,
+                    match_tests,
+                    skip,
+                    exact_match,
+                    options.tracing,
+                    true,
+                )?;
 
-                    Ok(validator.program.inner().address(
-                        network,
-                        delegation_part.to_owned(),
-                        &self.config.plutus.into(),
-                    ))
+                if !tests.is_empty() {
+                    self.event_listener.handle_event(Event::RunningTests {
+                        seed: has_property_tests(&tests).then_some(seed),
+                    });
                 }
-            },
-        )
-    }
 
-    pub fn policy(
-        &self,
-        module_name: Option<&str>,
-        validator_name: Option<&str>,
-        blueprint_path: &Path,
-    ) -> Result<PolicyId, Error> {
-        // Read blueprint
-        let blueprint = File::open(blueprint_path)
-            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
-        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+                let mut tests = self.run_tests(tests, seed, property_max_success, false);
 
-        // Error handlers for ambiguous / missing validators
-        let when_too_many =
-            |known_validators| Error::MoreThanOneValidatorFound { known_validators };
-        let when_missing = |known_validators| Error::NoValidatorNotFound { known_validators };
+                let mut hits = Hits::default();
 
-        blueprint.with_validator(
-            module_name,
-            validator_name,
+                tests.iter_mut().for_each(|test| hits.collect(test));
+
+                let errors: Vec<Error> = tests
+                    .iter()
+                    .filter(|test| !test.is_success())
+                    .map(|test| Error::from_test_result(test, false))
+                    .collect();
+
+                let report = self.coverage_report(&hits);
+
+                for (path, contents) in [
+                    (output.join("lcov.info"), report.to_lcov()),
+                    (
+                        output.join("index.html"),
+                        report.to_html(&self.config.name.to_string()),
+                    ),
+                ] {
+                    fs::create_dir_all(&output)
+                        .and_then(|_| fs::write(&path, contents))
+                        .map_err(|error| Error::FileIo { error, path })?;
+                }
+
+                self.event_listener
+                    .handle_event(Event::FinishedCoverage { report, output });
+
+                if !errors.is_empty() {
+                    Err(errors)
+                } else {
+                    Ok(())
+                }
+            }
+            CodeGenMode::Verify {
+                solver,
+                emit,
+                paths,
+            } => self.verify_modules(&solver, emit.as_deref(), paths),
+            CodeGenMode::NoOp => Ok(()),
+        }
+    }
+
+    /// Prove properties and check that validator handlers can succeed, using an SMT solver. When
+    /// exploring paths, branches of handlers which can never be taken are reported as warnings.
+    fn verify_modules(
+        &mut self,
+        solver: &[String],
+        emit: Option<&Path>,
+        paths: bool,
+    ) -> Result<(), Vec<Error>> {
+        self.event_listener.handle_event(Event::Verifying);
+
+        if let Some(dir) = emit {
+            fs::create_dir_all(dir).map_err(|error| Error::FileIo {
+                error,
+                path: dir.to_path_buf(),
+            })?;
+        }
+
+        let context = smt::Context::new(&self.functions, &self.constants);
+
+        let mut results = Vec::new();
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        let mut modules = self
+            .checked_modules
+            .values()
+            .filter(|module| module.package == self.config.name.to_string())
+            .collect::<Vec<_>>();
+
+        modules.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for module in modules {
+            let mut queries = Vec::new();
+            let mut explorations = HashMap::new();
+            let line_numbers = LineNumbers::new(&module.code);
+
+            for def in module.ast.definitions() {
+                match def {
+                    Definition::Test(test) if !test.arguments.is_empty() => {
+                        queries.push((
+                            test.name.clone(),
+                            false,
+                            test.location,
+                            context.property(test),
+                        ));
+                    }
+                    Definition::Validator(validator) => {
+                        for handler in validator.handlers.iter() {
+                            let name = format!("{}.{}", validator.name, handler.name);
+
+                            if paths {
+                                if let Ok(paths) = context.paths(&validator.params, handler) {
+                                    explorations.insert(name.clone(), paths);
+                                }
+                            }
+
+                            queries.push((
+                                name,
+                                true,
+                                handler.location,
+                                context.handler(&validator.params, handler),
+                            ));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            for (name, handler, location, query) in queries {
+                let outcome = match query {
+                    Err(unsupported) => verify::Outcome::Unsupported(unsupported.reason),
+                    Ok(query) => {
+                        if let Some(dir) = emit {
+                            let path =
+                                dir.join(format!("{}.{name}.smt2", module.name.replace('/', ".")));
+                            fs::write(&path, &query.script)
+                                .map_err(|error| Error::FileIo { error, path })?;
+                        }
+
+                        match verify::solve(solver, &query)? {
+                            smt::Verdict::Holds(_) => verify::Outcome::Holds,
+                            smt::Verdict::Fails(counterexample) => {
+                                errors.push(Error::Unverified {
+                                    name: name.clone(),
+                                    handler,
+                                    counterexample: counterexample.clone().into(),
+                                    path: module.input_path.clone(),
+                                    src: module.code.clone(),
+                                    named: NamedSource::new(
+                                        module.input_path.display().to_string(),
+                                        module.code.clone(),
+                                    )
+                                    .into(),
+                                    location,
+                                });
+                                verify::Outcome::Fails(counterexample)
+                            }
+                            smt::Verdict::Unknown(reason) => verify::Outcome::Unknown(reason),
+                        }
+                    }
+                };
+
+                let mut paths = Vec::new();
+
+                for path in explorations.remove(&name).unwrap_or_default() {
+                    let reachability =
+                        verify::Reachability::from(verify::solve(solver, &path.query)?);
+
+                    if path.point == smt::Point::Branch
+                        && reachability == verify::Reachability::Unreachable
+                    {
+                        warnings.push(Warning::UnreachableBranch {
+                            path: module.input_path.clone(),
+                            src: module.code.clone(),
+                            named: NamedSource::new(
+                                module.input_path.display().to_string(),
+                                module.code.clone(),
+                            ),
+                            location: path.location,
+                        });
+                    }
+
+                    paths.push(verify::PathResult {
+                        point: path.point,
+                        line: line_numbers
+                            .line_number(path.location.start)
+                            .unwrap_or_default(),
+                        reachability,
+                    });
+                }
+
+                results.push(verify::VerificationResult {
+                    module: module.name.clone(),
+                    name,
+                    handler,
+                    outcome,
+                    paths,
+                });
+            }
+        }
+
+        self.event_listener
+            .handle_event(Event::FinishedVerification { results });
+
+        self.warnings.extend(warnings);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn address(
+        &self,
+        module_name: Option<&str>,
+        validator_name: Option<&str>,
+        stake_address: Option<&str>,
+        blueprint_path: &Path,
+        mainnet: bool,
+    ) -> Result<ShelleyAddress, Error> {
+        let delegation_part = delegation_part(stake_address)?;
+
+        // Read blueprint
+        let blueprint = File::open(blueprint_path)
+            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
+        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+
+        // Calculate the address
+        let when_too_many =
+            |known_validators| Error::MoreThanOneValidatorFound { known_validators };
+        let when_missing = |known_validators| Error::NoValidatorNotFound { known_validators };
+
+        blueprint.with_validator(
+            module_name,
+            validator_name,
+            when_too_many,
+            when_missing,
+            |validator| {
+                let n = validator.parameters.len();
+
+                if n > 0 {
+                    Err(blueprint::error::Error::ParameterizedValidator { n }.into())
+                } else {
+                    let network = if mainnet {
+                        Network::Mainnet
+                    } else {
+                        Network::Testnet
+                    };
+
+                    Ok(validator.program.inner().address(
+                        network,
+                        delegation_part.to_owned(),
+                        &self.config.plutus.into(),
+                    ))
+                }
+            },
+        )
+    }
+
+    /// Decode an address, and find the validators of the blueprint (if any) behind its
+    /// credentials.
+    pub fn inspect_address(
+        &self,
+        address: &str,
+        blueprint_path: &Path,
+    ) -> Result<AddressInfo, Error> {
+        let mut info = AddressInfo::decode(address)?;
+
+        if blueprint_path.exists() {
+            let blueprint = File::open(blueprint_path)
+                .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
+            let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+
+            info.match_validators(&blueprint);
+        }
+
+        Ok(info)
+    }
+
+    pub fn policy(
+        &self,
+        module_name: Option<&str>,
+        validator_name: Option<&str>,
+        blueprint_path: &Path,
+    ) -> Result<PolicyId, Error> {
+        // Read blueprint
+        let blueprint = File::open(blueprint_path)
+            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
+        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+
+        // Error handlers for ambiguous / missing validators
+        let when_too_many =
+            |known_validators| Error::MoreThanOneValidatorFound { known_validators };
+        let when_missing = |known_validators| Error::NoValidatorNotFound { known_validators };
+
+        blueprint.with_validator(
+            module_name,
+            validator_name,
             when_too_many,
             when_missing,
             |validator| {
@@ -541,6 +1261,95 @@ where
         )
     }
 
+    /// Compare validator sizes or test budgets against 'budgets.lock', if any. Regressions beyond
+    /// the configured tolerance fail the command, unless configured as mere warnings. When
+    /// locking budgets, the lock is updated instead.
+    fn check_budgets(&mut self, current: BudgetsLock) -> Result<(), Vec<Error>> {
+        if self.is_in_memory() {
+            return Ok(());
+        }
+
+        let path = self.root.join(paths::budgets_lock());
+
+        let locked = BudgetsLock::load(&path)?;
+
+        if self.lock_budgets {
+            let mut lock = locked.unwrap_or_default();
+
+            lock.extend(current);
+
+            self.event_listener
+                .handle_event(Event::SavingBaseline { path: path.clone() });
+
+            return Ok(lock.save(&path)?);
+        }
+
+        let Some(locked) = locked else {
+            return Ok(());
+        };
+
+        let tolerance = self.config.budgets.tolerance.unwrap_or_default();
+
+        let regressions = locked.regressions(&current, tolerance);
+
+        if self.config.budgets.warn_only.unwrap_or_default() {
+            self.warnings
+                .extend(
+                    regressions
+                        .into_iter()
+                        .map(|regression| Warning::BudgetRegression {
+                            name: regression.name,
+                            metric: regression.metric.to_string(),
+                            locked: regression.locked,
+                            current: regression.current,
+                            tolerance,
+                        }),
+                );
+
+            return Ok(());
+        }
+
+        let errors = regressions
+            .into_iter()
+            .map(|regression| Error::BudgetRegression {
+                name: regression.name,
+                metric: regression.metric.to_string(),
+                locked: regression.locked,
+                current: regression.current,
+                tolerance,
+            })
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Dependency graph of the checked modules. When given a validator (as 'module.name'), only
+    /// its module and those it transitively imports are kept.
+    pub fn module_graph(&self, validator: Option<&str>) -> Result<ModuleGraph, Error> {
+        let graph = ModuleGraph::new(&self.checked_modules);
+
+        let Some(title) = validator else {
+            return Ok(graph);
+        };
+
+        let known_validators = self
+            .checked_modules
+            .validators()
+            .map(|(module, def)| format!("{}.{}", module.name, def.name))
+            .collect::<Vec<_>>();
+
+        match title.rsplit_once('.') {
+            Some((module, _)) if known_validators.iter().any(|known| known == title) => {
+                Ok(graph.reachable_from(module))
+            }
+            _ => Err(Error::NoValidatorNotFound { known_validators }),
+        }
+    }
+
     pub fn export(&self, module: &str, name: &str, tracing: Tracing) -> Result<Export, Error> {
         let checked_module =
             self.checked_modules
@@ -575,6 +1384,14 @@ where
             })
     }
 
+    pub fn metadata(&mut self) -> Result<Vec<Metadata>, Error> {
+        self.checked_modules.values_mut().for_each(|m| {
+            m.attach_doc_and_module_comments();
+        });
+
+        Metadata::collect(&self.checked_modules).map_err(Error::Blueprint)
+    }
+
     pub fn construct_parameter_incrementally<F>(
         &self,
         module_name: Option<&str>,
@@ -613,12 +1430,54 @@ where
         Ok(data)
     }
 
-    pub fn apply_parameter(
+    /// Construct a parameter of a validator from JSON, following the schema given by the
+    /// blueprint. The position is relative to the parameters not yet applied; see
+    /// [`blueprint::parameter::Parameter::from_json`].
+    pub fn parameter_from_json(
         &self,
         module_name: Option<&str>,
         validator_name: Option<&str>,
         blueprint_path: &Path,
-        param: &PlutusData,
+        position: usize,
+        json: &serde_json::Value,
+    ) -> Result<PlutusData, Error> {
+        // Read blueprint
+        let blueprint = File::open(blueprint_path)
+            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
+        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+
+        // Construct parameter
+        let when_too_many =
+            |known_validators| Error::MoreThanOneValidatorFound { known_validators };
+        let when_missing = |known_validators| Error::NoValidatorNotFound { known_validators };
+
+        let data = blueprint.with_validator(
+            module_name,
+            validator_name,
+            when_too_many,
+            when_missing,
+            |validator| match validator.parameters.get(position) {
+                None => Err(blueprint::error::Error::NoParametersToApply.into()),
+                Some(parameter) => parameter
+                    .from_json(&blueprint.definitions, json)
+                    .map_err(|e| e.into()),
+            },
+        )?;
+
+        Ok(data)
+    }
+
+    /// Apply the given parameters, in order, to a validator. There may be fewer than the
+    /// validator's parameters, in which case the remaining ones are kept in the blueprint to be
+    /// applied later on. With 'coerce', lists and pairs may be given either as builtin or
+    /// Data-encoded values; see [`blueprint::parameter::Parameter::validate_coerced`].
+    pub fn apply_parameters(
+        &self,
+        module_name: Option<&str>,
+        validator_name: Option<&str>,
+        blueprint_path: &Path,
+        params: &[PlutusData],
+        coerce: bool,
     ) -> Result<Blueprint, Error> {
         // Read blueprint
         let blueprint = File::open(blueprint_path)
@@ -636,36 +1495,226 @@ where
             when_too_many,
             when_missing,
             |validator| {
-                validator
-                    .clone()
-                    .apply(&blueprint.definitions, param)
+                params
+                    .iter()
+                    .try_fold(validator.clone(), |validator, param| {
+                        if coerce {
+                            validator.apply_coerced(&blueprint.definitions, param)
+                        } else {
+                            validator.apply(&blueprint.definitions, param)
+                        }
+                    })
                     .map_err(|e| e.into())
             },
         )?;
 
         let prefix = |v: &str| v.split('.').take(2).collect::<Vec<&str>>().join(".");
 
-        // Overwrite validator
+        // Overwrite validator, for all its handlers
         blueprint.validators = blueprint
             .validators
             .into_iter()
             .map(|validator| {
                 if prefix(&applied_validator.title) == prefix(&validator.title) {
-                    applied_validator.clone()
+                    Validator {
+                        parameters: applied_validator.parameters.clone(),
+                        program: applied_validator.program.clone(),
+                        ..validator
+                    }
                 } else {
                     validator
                 }
             })
             .collect();
 
-        Ok(blueprint)
-    }
+        Ok(blueprint)
+    }
+
+    /// Check some data against the datum (or redeemer) of a validator's handler, as declared in
+    /// the blueprint. Without an explicit handler, picks the first one declaring such schema. The
+    /// data is obtained from the given closure, which can also make use of the schema. Returns
+    /// the title of the handler alongside the checked data.
+    #[allow(clippy::too_many_arguments)]
+    pub fn check_datum<F>(
+        &self,
+        module_name: Option<&str>,
+        validator_name: Option<&str>,
+        handler_name: Option<&str>,
+        blueprint_path: &Path,
+        redeemer: bool,
+        data: F,
+    ) -> Result<(String, PlutusData), Error>
+    where
+        F: Fn(
+            &Parameter,
+            &Definitions<Annotated<Schema>>,
+        ) -> Result<PlutusData, blueprint::error::Error>,
+    {
+        // Read blueprint
+        let blueprint = File::open(blueprint_path)
+            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
+        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+
+        // Find handler
+        let when_too_many =
+            |known_validators| Error::MoreThanOneValidatorFound { known_validators };
+        let when_missing = |known_validators| Error::NoValidatorNotFound { known_validators };
+
+        let prefix = |v: &str| v.split('.').take(2).collect::<Vec<&str>>().join(".");
+
+        let target = blueprint.with_validator(
+            module_name,
+            validator_name,
+            when_too_many,
+            when_missing,
+            |validator| Ok(prefix(&validator.title)),
+        )?;
+
+        let handlers = blueprint
+            .validators
+            .iter()
+            .filter(|validator| prefix(&validator.title) == target)
+            .collect::<Vec<_>>();
+
+        let (title, schema) = handlers
+            .iter()
+            .filter(|validator| match handler_name {
+                Some(handler_name) => validator.title == format!("{target}.{handler_name}"),
+                None => true,
+            })
+            .find_map(|validator| {
+                if redeemer {
+                    validator.redeemer.as_ref()
+                } else {
+                    validator.datum.as_ref()
+                }
+                .map(|schema| (validator.title.clone(), schema))
+            })
+            .ok_or_else(|| blueprint::error::Error::NoSchemaToCheck {
+                what: if redeemer { "redeemer" } else { "datum" }.to_string(),
+                known_handlers: handlers
+                    .iter()
+                    .map(|validator| validator.title.clone())
+                    .collect(),
+            })?;
+
+        // Check data
+        let data = data(schema, &blueprint.definitions)?;
+
+        schema.validate(&blueprint.definitions, &Constant::Data(data.clone()))?;
+
+        Ok((title, data))
+    }
+
+    /// Construct some data following the schema of a definition from the blueprint, designated
+    /// either by its key (e.g. 'aiken/crypto/VerificationKey') or by a JSON pointer (e.g.
+    /// '#/definitions/aiken~1crypto~1VerificationKey'). The data is obtained from the given
+    /// closure, and checked against the schema before being returned.
+    pub fn build_data<F>(
+        &self,
+        blueprint_path: &Path,
+        definition: &str,
+        data: F,
+    ) -> Result<PlutusData, Error>
+    where
+        F: Fn(
+            &Reference,
+            &Definitions<Annotated<Schema>>,
+        ) -> Result<PlutusData, blueprint::error::Error>,
+    {
+        // Read blueprint
+        let blueprint = File::open(blueprint_path)
+            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
+        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+
+        // Build data
+        let reference = Reference::new(
+            definition
+                .strip_prefix("#/definitions/")
+                .unwrap_or(definition),
+        );
+
+        let data = data(&reference, &blueprint.definitions)?;
+
+        Parameter::from(reference)
+            .validate(&blueprint.definitions, &Constant::Data(data.clone()))?;
+
+        Ok(data)
+    }
+
+    /// Check that a blueprint was built from the project's sources, as they currently are.
+    pub fn verify_blueprint(&self, blueprint_path: &Path) -> Result<Blueprint, Error> {
+        // Read blueprint
+        let blueprint = File::open(blueprint_path)
+            .map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
+        let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+
+        // Hash sources
+        let hash =
+            blueprint::source::Source::hash_tree(&self.root).map_err(|error| Error::FileIo {
+                error,
+                path: self.root.clone(),
+            })?;
+
+        blueprint.check_source(&hash)?;
+
+        Ok(blueprint)
+    }
+
+    fn is_in_memory(&self) -> bool {
+        self.in_memory.is_some()
+    }
+
+    #[cfg(not(feature = "native"))]
+    fn with_dependencies(
+        &mut self,
+        _parsed_packages: &mut ParsedModules,
+        _tracing: Tracing,
+    ) -> Result<HashMap<String, ArtifactsCache>, Vec<Error>> {
+        // Dependencies can't be fetched; modules importing them end up reported as unknown.
+        Ok(HashMap::new())
+    }
+
+    #[cfg(feature = "native")]
+    fn with_dependencies(
+        &mut self,
+        parsed_packages: &mut ParsedModules,
+        tracing: Tracing,
+    ) -> Result<HashMap<String, ArtifactsCache>, Vec<Error>> {
+        if self.is_in_memory() {
+            return Ok(HashMap::new());
+        }
+
+        let start = Instant::now();
 
-    fn with_dependencies(&mut self, parsed_packages: &mut ParsedModules) -> Result<(), Vec<Error>> {
         let manifest = deps::download(&self.event_listener, &self.root, &self.config)?;
 
+        self.timings.record(Phase::Resolve, start.elapsed());
+
+        if !manifest.packages.is_empty() {
+            self.warnings
+                .extend(AdvisoryDb::load(false).audit(&manifest));
+        }
+
+        let mut artifacts = HashMap::new();
+
+        for name in self.config.patch.keys() {
+            if !self.config.dependencies.iter().any(|dep| &dep.name == name) {
+                self.warnings
+                    .push(Warning::UnusedPatch { name: name.clone() });
+            }
+        }
+
+        // Sources of dependency modules, by name, along with the index of their package. They're
+        // only parsed (and thus type-checked) when reachable from one of our modules.
+        let mut packages = Vec::new();
+        let mut available: HashMap<String, (Source, usize)> = HashMap::new();
+
         for package in manifest.packages {
-            let lib = self.root.join(paths::build_deps_package(&package.name));
+            let lib = match package.path {
+                Some(ref path) => self.root.join(path),
+                None => self.root.join(paths::build_deps_package(&package.name)),
+            };
 
             self.event_listener
                 .handle_event(Event::StartingCompilation {
@@ -676,43 +1725,108 @@ where
 
             self.read_package_source_files(&lib.join("lib"))?;
 
-            let mut parsed_modules = self.parse_sources(package.name)?;
+            if let Some(cache) = ArtifactsCache::new(&package, tracing.trace_level(false)) {
+                artifacts.insert(package.name.to_string(), cache);
+            }
 
-            use rayon::prelude::*;
+            let features = Config::load(&lib)
+                .and_then(|config| config.resolve_features(&[], true))
+                .unwrap_or_default();
+
+            for source in self.sources.drain(0..) {
+                let first = parsed_packages
+                    .get(&source.name)
+                    .map(|module| &module.path)
+                    .or_else(|| available.get(&source.name).map(|(first, _)| &first.path));
+
+                if let Some(first) = first {
+                    return Err(vec![Error::DuplicateModule {
+                        module: source.name.clone(),
+                        first: first.clone(),
+                        second: source.path,
+                    }]);
+                }
 
-            parsed_modules
-                .par_iter_mut()
-                .for_each(|(_module, parsed_module)| {
-                    parsed_module
-                        .ast
-                        .definitions
-                        .retain(|def| !matches!(def, Definition::Test { .. }))
-                });
+                available.insert(source.name.clone(), (source, packages.len()));
+            }
 
-            parsed_packages.extend(Into::<HashMap<_, _>>::into(parsed_modules));
+            packages.push((package.name, features));
         }
 
-        Ok(())
+        let env_modules = parsed_packages
+            .values()
+            .filter(|module| module.kind == ModuleKind::Env)
+            .map(|module| module.name.clone())
+            .collect::<Vec<_>>();
+
+        let mut wanted = if self.all_dependencies {
+            available.keys().cloned().collect::<Vec<_>>()
+        } else {
+            parsed_packages
+                .values()
+                .flat_map(|module| module.deps_for_graph(&env_modules).1)
+                .collect::<Vec<_>>()
+        };
+
+        while !wanted.is_empty() {
+            let mut batches: std::collections::BTreeMap<usize, Vec<Source>> = Default::default();
+
+            for name in wanted.drain(0..) {
+                if let Some((source, package)) = available.remove(&name) {
+                    batches.entry(package).or_default().push(source);
+                }
+            }
+
+            for (package, sources) in batches {
+                let (package_name, features) = &packages[package];
+
+                self.sources.extend(sources);
+
+                let mut parsed_modules = self.parse_sources(package_name.clone(), features)?;
+
+                use rayon::prelude::*;
+
+                parsed_modules
+                    .par_iter_mut()
+                    .for_each(|(_module, parsed_module)| {
+                        parsed_module.ast.definitions.retain(|def| {
+                            !matches!(def, Definition::Test { .. } | Definition::Benchmark { .. })
+                        })
+                    });
+
+                wanted.extend(
+                    parsed_modules
+                        .values()
+                        .flat_map(|module| module.deps_for_graph(&env_modules).1),
+                );
+
+                parsed_packages.extend(Into::<HashMap<_, _>>::into(parsed_modules));
+            }
+        }
+
+        Ok(artifacts)
     }
 
     fn read_source_files(&mut self, config: Option<Vec<UntypedDefinition>>) -> Result<(), Error> {
+        if let Some(defs) = config {
+            self.sources.push(Source {
+                name: ast::CONFIG_MODULE.to_string(),
+                code: Formatter::new()
+                    .definitions(&defs[..])
+                    .to_pretty_string(MAX_COLUMNS),
+                kind: ModuleKind::Config,
+                path: self.root.clone(),
+            });
+        }
+
+        if let Some(sources) = &self.in_memory {
+            self.sources.extend(sources.iter().cloned());
+            return Ok(());
+        }
+
         let env = self.root.join("env");
         let lib = self.root.join("lib");
         let validators = self.root.join("validators");
-        let root = self.root.clone();
-
-        if let Some(defs) = config {
-            self.add_module(
-                AddModuleBy::Source {
-                    name: ast::CONFIG_MODULE.to_string(),
-                    code: Formatter::new()
-                        .definitions(&defs[..])
-                        .to_pretty_string(MAX_COLUMNS),
-                },
-                &root,
-                ModuleKind::Config,
-            )?;
-        }
 
         self.aiken_files(&validators, ModuleKind::Validator)?;
         self.aiken_files(&lib, ModuleKind::Lib)?;
@@ -721,21 +1835,28 @@ where
         Ok(())
     }
 
+    #[cfg(feature = "native")]
     fn read_package_source_files(&mut self, lib: &Path) -> Result<(), Error> {
         self.aiken_files(lib, ModuleKind::Lib)?;
 
         Ok(())
     }
 
-    fn parse_sources(&mut self, package_name: PackageName) -> Result<ParsedModules, Vec<Error>> {
+    fn parse_sources(
+        &mut self,
+        package_name: PackageName,
+        features: &BTreeSet<String>,
+    ) -> Result<ParsedModules, Vec<Error>> {
         use rayon::prelude::*;
 
-        let (parsed_modules, parse_errors, duplicates) = self
+        let start = Instant::now();
+
+        let (parsed_modules, parse_errors, duplicates, elapsed) = self
             .sources
             .par_drain(0..)
             .fold(
-                || (ParsedModules::new(), Vec::new(), Vec::new()),
-                |(mut parsed_modules, mut parse_errors, mut duplicates), elem| {
+                || (ParsedModules::new(), Vec::new(), Vec::new(), Vec::new()),
+                |(mut parsed_modules, mut parse_errors, mut duplicates, mut elapsed), elem| {
                     let Source {
                         path,
                         name,
@@ -743,11 +1864,19 @@ where
                         kind,
                     } = elem;
 
-                    match aiken_lang::parser::module(&code, kind) {
+                    let start = Instant::now();
+
+                    let parsed = aiken_lang::parser::module(&code, kind);
+
+                    elapsed.push((name.clone(), start.elapsed()));
+
+                    match parsed {
                         Ok((mut ast, extra)) => {
                             // Store the name
                             ast.name.clone_from(&name);
 
+                            ast.strip_cfg(&extra.cfg, |feature| features.contains(feature));
+
                             let module = ParsedModule {
                                 kind,
                                 ast,
@@ -765,7 +1894,7 @@ where
                                 duplicates.push((name, first.path.clone(), path))
                             }
 
-                            (parsed_modules, parse_errors, duplicates)
+                            (parsed_modules, parse_errors, duplicates, elapsed)
                         }
                         Err(errs) => {
                             for error in errs {
@@ -777,15 +1906,15 @@ where
                                 ))
                             }
 
-                            (parsed_modules, parse_errors, duplicates)
+                            (parsed_modules, parse_errors, duplicates, elapsed)
                         }
                     }
                 },
             )
             .reduce(
-                || (ParsedModules::new(), Vec::new(), Vec::new()),
-                |(mut parsed_modules, mut parse_errors, mut duplicates),
-                 (mut parsed, mut errs, mut dups)| {
+                || (ParsedModules::new(), Vec::new(), Vec::new(), Vec::new()),
+                |(mut parsed_modules, mut parse_errors, mut duplicates, mut elapsed),
+                 (mut parsed, mut errs, mut dups, mut times)| {
                     let keys_left = parsed_modules.keys().collect::<HashSet<_>>();
                     let keys_right = parsed.keys().collect::<HashSet<_>>();
 
@@ -804,11 +1933,18 @@ where
 
                     parse_errors.append(&mut errs);
                     duplicates.append(&mut dups);
+                    elapsed.append(&mut times);
 
-                    (parsed_modules, parse_errors, duplicates)
+                    (parsed_modules, parse_errors, duplicates, elapsed)
                 },
             );
 
+        self.timings.record(Phase::Parse, start.elapsed());
+
+        for (module, elapsed) in elapsed {
+            self.timings.record_module(&module, Phase::Parse, elapsed);
+        }
+
         let mut errors: Vec<Error> = Vec::new();
 
         errors.extend(
@@ -863,10 +1999,102 @@ where
     ) -> Result<(), Vec<Error>> {
         let our_modules: BTreeSet<String> = modules.keys().cloned().collect();
 
-        self.with_dependencies(modules)?;
+        let artifacts = self.with_dependencies(modules, tracing)?;
+
+        let build_cache = BuildCache::new(
+            (!self.is_in_memory()).then_some(self.root.as_path()),
+            format!(
+                "{}:{:?}:{tracing:?}:{env:?}:{validate_module_name}:{:?}",
+                self.config.name, self.config.plutus, self.features
+            ),
+        );
+
+        let env_modules = modules
+            .values()
+            .filter(|module| module.kind == ModuleKind::Env)
+            .map(|module| module.name.clone())
+            .collect::<Vec<_>>();
+
+        let mut interfaces = HashMap::new();
+
+        let start = Instant::now();
+
+        let roots = if self.all_dependencies {
+            modules.keys().cloned().collect()
+        } else {
+            our_modules.clone()
+        };
 
-        for name in modules.sequence(&our_modules)? {
+        for name in modules.sequence(&roots)? {
             if let Some(module) = modules.remove(&name) {
+                let cache = artifacts.get(&module.package);
+
+                let (fingerprint, artifacts_fingerprint) = {
+                    let (_, dependencies) = module.deps_for_graph(&env_modules);
+
+                    let dependencies = dependencies
+                        .iter()
+                        .map(|dependency| {
+                            (
+                                dependency.as_str(),
+                                interfaces.get(dependency).map_or("", String::as_str),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    (
+                        build_cache.fingerprint(&module, dependencies.iter().copied()),
+                        ArtifactsCache::fingerprint(&module, dependencies),
+                    )
+                };
+
+                let (cached, in_memory) = match self.memory.load(&module, &fingerprint) {
+                    Some(cached) => (Some(cached), true),
+                    None => (
+                        cache
+                            .and_then(|cache| cache.load(&module, &artifacts_fingerprint))
+                            .map(|checked_module| {
+                                let interface = cache::interface(&checked_module.ast.type_info);
+                                (checked_module, interface)
+                            })
+                            .or_else(|| {
+                                our_modules
+                                    .contains(&name)
+                                    .then(|| build_cache.load(&module, &fingerprint))
+                                    .flatten()
+                            }),
+                        false,
+                    ),
+                };
+
+                if let Some((checked_module, interface)) = cached {
+                    // Modules held in memory come with their own ids (see 'with_memory_cache').
+                    if !in_memory {
+                        self.id_gen.skip_past(cache::largest_id(&checked_module));
+                    }
+
+                    checked_module.register(
+                        &mut self.module_sources,
+                        &mut self.module_types,
+                        &mut self.functions,
+                        &mut self.constants,
+                        &mut self.data_types,
+                    );
+
+                    if !in_memory {
+                        self.memory.store(&checked_module, &fingerprint, &interface);
+                    }
+
+                    interfaces.insert(name, interface);
+
+                    self.checked_modules
+                        .insert(checked_module.name.clone(), checked_module);
+
+                    continue;
+                }
+
+                let inferring = Instant::now();
+
                 let (checked_module, warnings) = module.infer(
                     &self.id_gen,
                     &self.config.name.to_string(),
@@ -880,6 +2108,25 @@ where
                     &mut self.data_types,
                 )?;
 
+                self.timings
+                    .record_module(&name, Phase::TypeCheck, inferring.elapsed());
+
+                if let Some(cache) = cache {
+                    cache.store(&checked_module, &artifacts_fingerprint);
+                }
+
+                let interface = cache::interface(&checked_module.ast.type_info);
+
+                if warnings.is_empty() {
+                    if our_modules.contains(&name) {
+                        build_cache.store(&checked_module, &fingerprint, &interface);
+                    }
+
+                    self.memory.store(&checked_module, &fingerprint, &interface);
+                }
+
+                interfaces.insert(name, interface);
+
                 if our_modules.contains(checked_module.name.as_str())
                     && checked_module.name.as_str() != ast::CONFIG_MODULE
                 {
@@ -891,42 +2138,214 @@ where
             }
         }
 
+        // Builtins unavailable in the targeted version would only fail once on-chain.
+        let errors = self.unavailable_builtins(&our_modules);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let errors = self.eval_constants(&our_modules, tracing);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        self.timings.record(Phase::TypeCheck, start.elapsed());
+
         Ok(())
     }
 
+    /// Check that validators and tests in the given modules, and every function or constant they
+    /// reach, only use builtins available in the Plutus version they run on. Validators may
+    /// target another version than the project's through their configuration.
+    fn unavailable_builtins(&self, modules: &BTreeSet<String>) -> Vec<Error> {
+        let mut definitions: HashMap<(String, String), (&CheckedModule, Vec<&TypedExpr>)> =
+            HashMap::new();
+
+        for module in self.checked_modules.values() {
+            for def in module.ast.definitions() {
+                match def {
+                    Definition::Fn(func) => {
+                        definitions.insert(
+                            (module.name.clone(), func.name.clone()),
+                            (module, vec![&func.body]),
+                        );
+                    }
+                    Definition::ModuleConstant(constant) => {
+                        definitions.insert(
+                            (module.name.clone(), constant.name.clone()),
+                            (module, vec![&constant.value]),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut roots = Vec::new();
+
+        for module in modules
+            .iter()
+            .filter_map(|name| self.checked_modules.get(name))
+        {
+            for def in module.ast.definitions() {
+                match def {
+                    Definition::Validator(validator) => {
+                        let version = self
+                            .config
+                            .validator_config(&module.name, &validator.name)
+                            .and_then(|settings| settings.plutus)
+                            .unwrap_or(self.config.plutus);
+
+                        let mut exprs = validator
+                            .handlers
+                            .iter()
+                            .map(|handler| &handler.body)
+                            .collect::<Vec<_>>();
+                        exprs.push(&validator.fallback.body);
+
+                        roots.push((version, module, exprs));
+                    }
+                    Definition::Test(func) | Definition::Benchmark(func) => {
+                        let mut exprs = func
+                            .arguments
+                            .iter()
+                            .map(|arg| &arg.via)
+                            .collect::<Vec<_>>();
+                        exprs.push(&func.body);
+
+                        roots.push((self.config.plutus, module, exprs));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut reported = HashSet::new();
+        let mut errors = Vec::new();
+
+        for (version, module, exprs) in roots {
+            if version == PlutusVersion::V3 {
+                continue;
+            }
+
+            let mut visited = HashSet::new();
+            let mut queue = vec![(module, exprs)];
+
+            while let Some((module, exprs)) = queue.pop() {
+                for expr in exprs {
+                    let found = unavailable_builtins_in(expr, version);
+
+                    for (what, location) in found.builtins {
+                        if reported.insert((module.name.clone(), what.clone(), location, version)) {
+                            errors.push(Error::UnavailableBuiltin {
+                                what,
+                                version,
+                                path: module.input_path.clone(),
+                                src: module.code.clone(),
+                                named: NamedSource::new(
+                                    module.input_path.display().to_string(),
+                                    module.code.clone(),
+                                )
+                                .into(),
+                                location,
+                            });
+                        }
+                    }
+
+                    for key in found.references {
+                        if let Some((module, exprs)) = definitions.get(&key) {
+                            if visited.insert(key) {
+                                queue.push((*module, exprs.clone()));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// Evaluate the constants of the given modules upfront, so that those failing to evaluate
+    /// are reported as such, rather than crashing code generation wherever they're used. Code
+    /// generation still evaluates constants again with its own generator.
+    fn eval_constants(&self, modules: &BTreeSet<String>, tracing: Tracing) -> Vec<Error> {
+        let mut generator = self.new_generator(tracing);
+
+        modules
+            .iter()
+            .filter_map(|name| self.checked_modules.get(name))
+            .flat_map(|module| {
+                module.ast.definitions().filter_map(move |def| match def {
+                    // Polymorphic constants only get a concrete type where they're used.
+                    Definition::ModuleConstant(constant)
+                        if constant.value.tipo().is_monomorphic() =>
+                    {
+                        Some((module, constant))
+                    }
+                    _ => None,
+                })
+            })
+            .filter_map(|(module, constant)| {
+                let mut result = generator
+                    .eval_constant(&module.name, &constant.name)
+                    .err()?;
+
+                let mut reason = result.result().unwrap_err().to_string();
+
+                let logs = result.logs();
+                if !logs.is_empty() {
+                    reason.push_str("\n\nIt traced:\n");
+                    reason.push_str(
+                        &logs
+                            .iter()
+                            .map(|log| format!("─▶ {log}"))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    );
+                }
+
+                Some(Error::ConstantEvaluation {
+                    name: constant.name.clone(),
+                    reason,
+                    path: module.input_path.clone(),
+                    src: module.code.clone(),
+                    named: NamedSource::new(
+                        module.input_path.display().to_string(),
+                        module.code.clone(),
+                    )
+                    .into(),
+                    location: constant.location,
+                })
+            })
+            .collect()
+    }
+
     fn collect_tests(
         &mut self,
         verbose: bool,
         match_tests: Option<Vec<String>>,
+        skip: Vec<String>,
         exact_match: bool,
         tracing: Tracing,
+        coverage: bool,
     ) -> Result<Vec<Test>, Error> {
         let mut scripts = Vec::new();
 
-        let match_tests = match_tests.map(|mt| {
-            mt.into_iter()
-                .map(|match_test| {
-                    let mut match_split_dot = match_test.split('.');
-
-                    let match_module = if match_test.contains('.') || match_test.contains('/') {
-                        match_split_dot.next().unwrap_or("")
-                    } else {
-                        ""
-                    };
-
-                    let match_names = match_split_dot.next().map(|names| {
-                        let names = names.replace(&['{', '}'][..], "");
-
-                        let names_split_comma = names.split(',');
-
-                        names_split_comma.map(str::to_string).collect()
-                    });
-
-                    (match_module.to_string(), match_names)
-                })
-                .collect::<Vec<(String, Option<Vec<String>>)>>()
+        let match_tests = match_tests.map(|patterns| {
+            patterns
+                .iter()
+                .map(|pattern| TestPattern::parse(pattern))
+                .collect::<Vec<_>>()
         });
 
+        let skip = skip
+            .iter()
+            .map(|pattern| TestPattern::parse(pattern))
+            .collect::<Vec<_>>();
+
         for checked_module in self.checked_modules.values() {
             if checked_module.package != self.config.name.to_string() {
                 continue;
@@ -934,33 +2353,18 @@ where
 
             for def in checked_module.ast.definitions() {
                 if let Definition::Test(func) = def {
-                    if let Some(match_tests) = &match_tests {
-                        let is_match = match_tests.iter().any(|(module, names)| {
-                            let matched_module =
-                                module.is_empty() || checked_module.name.contains(module);
-
-                            let matched_name = match names {
-                                None => true,
-                                Some(names) => names.iter().any(|name| {
-                                    if exact_match {
-                                        name == &func.name
-                                    } else {
-                                        func.name.contains(name)
-                                    }
-                                }),
-                            };
+                    let is_match = |patterns: &[TestPattern]| {
+                        patterns.iter().any(|pattern| {
+                            pattern.matches(&checked_module.name, &func.name, exact_match)
+                        })
+                    };
 
-                            matched_module && matched_name
-                        });
+                    let selected = match &match_tests {
+                        Some(match_tests) => is_match(match_tests),
+                        None => true,
+                    };
 
-                        if is_match {
-                            scripts.push((
-                                checked_module.input_path.clone(),
-                                checked_module.name.clone(),
-                                func,
-                            ))
-                        }
-                    } else {
+                    if selected && !is_match(&skip) {
                         scripts.push((
                             checked_module.input_path.clone(),
                             checked_module.name.clone(),
@@ -973,6 +2377,10 @@ where
 
         let mut generator = self.new_generator(tracing);
 
+        if coverage {
+            generator = generator.with_coverage();
+        }
+
         let mut tests = Vec::new();
 
         for (input_path, module_name, test) in scripts.into_iter() {
@@ -994,11 +2402,180 @@ where
         Ok(tests)
     }
 
+    fn collect_benchmarks(
+        &mut self,
+        match_benchmarks: Option<Vec<String>>,
+        exact_match: bool,
+        tracing: Tracing,
+    ) -> Result<Vec<Benchmark>, Error> {
+        let match_benchmarks = match_benchmarks.map(|patterns| {
+            patterns
+                .iter()
+                .map(|pattern| TestPattern::parse(pattern))
+                .collect::<Vec<_>>()
+        });
+
+        let mut scripts = Vec::new();
+
+        for checked_module in self.checked_modules.values() {
+            if checked_module.package != self.config.name.to_string() {
+                continue;
+            }
+
+            for def in checked_module.ast.definitions() {
+                if let Definition::Benchmark(func) = def {
+                    let selected = match &match_benchmarks {
+                        Some(patterns) => patterns.iter().any(|pattern| {
+                            pattern.matches(&checked_module.name, &func.name, exact_match)
+                        }),
+                        None => true,
+                    };
+
+                    if selected {
+                        scripts.push((
+                            checked_module.input_path.clone(),
+                            checked_module.name.clone(),
+                            func,
+                        ))
+                    }
+                }
+            }
+        }
+
+        let mut generator = self.new_generator(tracing);
+
+        let mut benchmarks = scripts
+            .into_iter()
+            .map(|(input_path, module_name, bench)| {
+                Benchmark::from_function_definition(
+                    &mut generator,
+                    bench.to_owned(),
+                    module_name,
+                    input_path,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        benchmarks.sort_by(|a, b| (&a.module, &a.name).cmp(&(&b.module, &b.name)));
+
+        Ok(benchmarks)
+    }
+
+    fn run_benchmarks(
+        &self,
+        benchmarks: Vec<Benchmark>,
+        seed: u32,
+        max_size: usize,
+    ) -> Vec<BenchmarkResult> {
+        use rayon::prelude::*;
+
+        let plutus_version = &self.config.plutus;
+
+        benchmarks
+            .into_par_iter()
+            .map(|benchmark| benchmark.run(seed, max_size, plutus_version))
+            .collect()
+    }
+
+    /// Write a flamegraph of each unit test to the given directory, both as an SVG and as
+    /// collapsed stacks ('.folded'), with the budget attributed to the functions of the project
+    /// and its dependencies.
+    fn profile_tests(&self, tests: &[Test], dir: &Path) -> Result<(), Error> {
+        fs::create_dir_all(dir).map_err(|error| Error::FileIo {
+            error,
+            path: dir.to_path_buf(),
+        })?;
+
+        let functions = self
+            .checked_modules
+            .values()
+            .flat_map(|checked_module| {
+                checked_module
+                    .ast
+                    .definitions()
+                    .filter_map(|def| match def {
+                        Definition::Fn(func) => Some((
+                            format!("{}_{}", checked_module.name, func.name),
+                            format!("{}.{}", checked_module.name, func.name),
+                        )),
+                        _ => None,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        // Generic functions are suffixed with the types they're specialised for.
+        let rename = |name: &str| {
+            functions
+                .iter()
+                .filter(|(mangled, _)| name.starts_with(mangled.as_str()))
+                .max_by_key(|(mangled, _)| mangled.len())
+                .map(|(_, source)| source.clone())
+        };
+
+        for test in tests {
+            if let Test::UnitTest(unit_test) = test {
+                let mut profile = unit_test.profile(&self.config.plutus);
+
+                profile.rename(rename);
+
+                let stem = format!("{}.{}", unit_test.module.replace('/', "."), unit_test.name);
+
+                for (path, contents) in [
+                    (
+                        dir.join(format!("{stem}.svg")),
+                        profile.to_svg(profile::Unit::Cpu),
+                    ),
+                    (
+                        dir.join(format!("{stem}.folded")),
+                        profile.collapsed(profile::Unit::Cpu),
+                    ),
+                ] {
+                    fs::write(&path, contents).map_err(|error| Error::FileIo { error, path })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Coverage of the functions and validators of our own package, sorted by module name.
+    fn coverage_report(&self, hits: &Hits) -> coverage::Report {
+        let mut modules = self
+            .checked_modules
+            .values()
+            .filter(|checked_module| checked_module.package == self.config.name.to_string())
+            .filter_map(|checked_module| {
+                let regions = aiken_lang::coverage::regions(&checked_module.ast);
+
+                if regions.is_empty() {
+                    return None;
+                }
+
+                Some(ModuleCoverage::new(
+                    &checked_module.name,
+                    checked_module
+                        .input_path
+                        .strip_prefix(&self.root)
+                        .unwrap_or(&checked_module.input_path)
+                        .to_path_buf(),
+                    &checked_module.code,
+                    &regions,
+                    hits,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        modules.sort_by(|a, b| a.name.cmp(&b.name));
+
+        coverage::Report { modules }
+    }
+
     fn run_tests(
         &self,
         tests: Vec<Test>,
         seed: u32,
         property_max_success: usize,
+        exhaustive: bool,
     ) -> Vec<TestResult<UntypedExpr, UntypedExpr>> {
         use rayon::prelude::*;
 
@@ -1010,6 +2587,9 @@ where
             .into_par_iter()
             .map(|test| match test {
                 Test::UnitTest(unit_test) => unit_test.run(plutus_version),
+                Test::PropertyTest(property_test) if exhaustive => {
+                    property_test.run_exhaustive(seed, property_max_success, plutus_version)
+                }
                 Test::PropertyTest(property_test) => {
                     property_test.run(seed, property_max_success, plutus_version)
                 }
@@ -1021,67 +2601,58 @@ where
     }
 
     fn aiken_files(&mut self, dir: &Path, kind: ModuleKind) -> Result<(), Error> {
+        use rayon::prelude::*;
+
         let mut has_default = None;
 
-        walkdir::WalkDir::new(dir)
+        let mut modules = Vec::new();
+
+        for path in walkdir::WalkDir::new(dir)
             .follow_links(true)
             .into_iter()
             .filter_map(Result::ok)
             .filter(|e| e.file_type().is_file())
-            .try_for_each(|d| {
-                if has_default.is_none() {
-                    has_default = Some(false);
-                }
+            .map(walkdir::DirEntry::into_path)
+        {
+            if has_default.is_none() {
+                has_default = Some(false);
+            }
+
+            if !is_aiken_path(&path, dir) {
+                self.warnings.push(Warning::InvalidModuleName { path });
+                continue;
+            }
 
-                let path = d.into_path();
-                let keep = is_aiken_path(&path, dir);
+            let name = self.module_name(dir, &path);
 
-                if !keep {
-                    self.warnings
-                        .push(Warning::InvalidModuleName { path: path.clone() });
-                }
+            if name.as_str() == ast::DEFAULT_ENV_MODULE {
+                has_default = Some(true);
+            }
 
-                if keep {
-                    if self.module_name(dir, &path).as_str() == ast::DEFAULT_ENV_MODULE {
-                        has_default = Some(true);
-                    }
-                    self.add_module(AddModuleBy::Path(path), dir, kind)
-                } else {
-                    Ok(())
-                }
-            })?;
+            modules.push((name, path));
+        }
 
         if kind == ModuleKind::Env && has_default == Some(false) {
             return Err(Error::NoDefaultEnvironment);
         }
 
-        Ok(())
-    }
-
-    fn add_module(
-        &mut self,
-        add_by: AddModuleBy,
-        dir: &Path,
-        kind: ModuleKind,
-    ) -> Result<(), Error> {
-        let (name, code, path) = match add_by {
-            AddModuleBy::Path(path) => {
-                let name = self.module_name(dir, &path);
-                let code = fs::read_to_string(&path).map_err(|error| Error::FileIo {
-                    path: path.clone(),
-                    error,
-                })?;
-                (name, code, path)
-            }
-            AddModuleBy::Source { name, code } => (name, code, dir.to_path_buf()),
-        };
+        // Reading sources is the only part of loading modules that doesn't depend on others, so
+        // it's done in parallel; much like parsing.
+        let sources = modules
+            .into_par_iter()
+            .map(|(name, path)| match fs::read_to_string(&path) {
+                Ok(code) => Ok(Source {
+                    name,
+                    code,
+                    kind,
+                    path,
+                }),
+                Err(error) => Err((path, error)),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|(path, error)| Error::FileIo { path, error })?;
 
-        self.sources.push(Source {
-            name,
-            code,
-            kind,
-            path,
-        });
+        self.sources.extend(sources);
 
         Ok(())
     }
@@ -1109,6 +2680,13 @@ where
     }
 }
 
+/// Whether any of the tests depends on the seed of the pseudo-random generator.
+fn has_property_tests(tests: &[Test]) -> bool {
+    tests
+        .iter()
+        .any(|test| matches!(test, Test::PropertyTest(..)))
+}
+
 fn is_aiken_path(path: &Path, dir: impl AsRef<Path>) -> bool {
     use regex::Regex;
 
@@ -1126,3 +2704,36 @@ fn is_aiken_path(path: &Path, dir: impl AsRef<Path>) -> bool {
             .expect("is_aiken_path(): to_str"),
     )
 }
+
+/// The delegation part of a script address, from either a stake address (base16 or bech32), a
+/// stake key hash, or a script hash prefixed with 'script:' (both base16-encoded).
+fn delegation_part(delegated_to: Option<&str>) -> Result<ShelleyDelegationPart, Error> {
+    let Some(delegated_to) = delegated_to else {
+        return Ok(ShelleyDelegationPart::Null);
+    };
+
+    let stake_hash =
+        |s: &str| Hash::<28>::from_str(s).map_err(|_| Error::MalformedStakeAddress { error: None });
+
+    if let Some(script) = delegated_to.strip_prefix("script:") {
+        return stake_hash(script).map(ShelleyDelegationPart::Script);
+    }
+
+    // Stake addresses carry an extra header byte, so bare hashes are always shorter.
+    if delegated_to.len() == 2 * 28 {
+        return stake_hash(delegated_to).map(ShelleyDelegationPart::Key);
+    }
+
+    let stake_address = Address::from_hex(delegated_to)
+        .or_else(|_| Address::from_bech32(delegated_to))
+        .map_err(|error| Error::MalformedStakeAddress { error: Some(error) })
+        .and_then(|addr| match addr {
+            Address::Stake(addr) => Ok(addr),
+            _ => Err(Error::MalformedStakeAddress { error: None }),
+        })?;
+
+    Ok(match stake_address.payload().to_owned() {
+        StakePayload::Stake(key) => ShelleyDelegationPart::Key(key),
+        StakePayload::Script(script) => ShelleyDelegationPart::Script(script),
+    })
+}
@@ -69,8 +69,8 @@ impl ParsedModule {
             .map_err(|error| Error::Type {
                 path: self.path.clone(),
                 src: self.code.clone(),
-                named: NamedSource::new(self.path.display().to_string(), self.code.clone()),
-                error,
+                named: NamedSource::new(self.path.display().to_string(), self.code.clone()).into(),
+                error: error.into(),
             })?;
 
         let warnings = warnings
@@ -83,31 +83,25 @@ impl ParsedModule {
             ast.validate_module_name()?;
         }
 
-        // Register module sources for an easier access later.
-        module_sources.insert(
-            self.name.clone(),
-            (self.code.clone(), LineNumbers::new(&self.code)),
+        let checked_module = CheckedModule {
+            ast,
+            kind: self.kind,
+            extra: self.extra,
+            name: self.name,
+            code: self.code,
+            package: self.package,
+            input_path: self.path,
+        };
+
+        checked_module.register(
+            module_sources,
+            module_types,
+            functions,
+            constants,
+            data_types,
         );
 
-        // Register the types from this module so they can be
-        // imported into other modules.
-        module_types.insert(self.name.clone(), ast.type_info.clone());
-
-        // Register function definitions & data-types for easier access later.
-        ast.register_definitions(functions, constants, data_types);
-
-        Ok((
-            CheckedModule {
-                ast,
-                kind: self.kind,
-                extra: self.extra,
-                name: self.name,
-                code: self.code,
-                package: self.package,
-                input_path: self.path,
-            },
-            warnings,
-        ))
+        Ok((checked_module, warnings))
     }
 }
 
@@ -302,6 +296,27 @@ impl CheckedModule {
             == "@hidden"
     }
 
+    /// Register the module's sources, types and definitions so that they can be accessed by
+    /// other modules, and later during code generation.
+    pub fn register(
+        &self,
+        module_sources: &mut HashMap<String, (String, LineNumbers)>,
+        module_types: &mut HashMap<String, TypeInfo>,
+        functions: &mut IndexMap<FunctionAccessKey, TypedFunction>,
+        constants: &mut IndexMap<FunctionAccessKey, TypedExpr>,
+        data_types: &mut IndexMap<DataTypeKey, TypedDataType>,
+    ) {
+        module_sources.insert(
+            self.name.clone(),
+            (self.code.clone(), LineNumbers::new(&self.code)),
+        );
+
+        module_types.insert(self.name.clone(), self.ast.type_info.clone());
+
+        self.ast
+            .register_definitions(functions, constants, data_types);
+    }
+
     pub fn to_cbor(&self) -> Vec<u8> {
         let mut module_bytes = vec![];
 
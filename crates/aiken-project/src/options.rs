@@ -22,11 +22,45 @@ impl Default for Options {
 pub enum CodeGenMode {
     Test {
         match_tests: Option<Vec<String>>,
+        skip: Vec<String>,
         verbose: bool,
         exact_match: bool,
         seed: u32,
         property_max_success: usize,
+        exhaustive: bool,
+        profile: Option<PathBuf>,
+    },
+    Benchmark {
+        match_benchmarks: Option<Vec<String>>,
+        exact_match: bool,
+        seed: Option<u32>,
+        max_size: usize,
+        baseline: PathBuf,
+        save_baseline: bool,
+        threshold: f64,
+    },
+    Coverage {
+        match_tests: Option<Vec<String>>,
+        skip: Vec<String>,
+        exact_match: bool,
+        seed: u32,
+        property_max_success: usize,
+        output: PathBuf,
+    },
+    Build(Emit),
+    Verify {
+        solver: Vec<String>,
+        emit: Option<PathBuf>,
+        paths: bool,
     },
-    Build(bool),
     NoOp,
 }
+
+/// Artifacts dumped alongside the blueprint, in the project's 'artifacts' folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Emit {
+    /// Textual UPLC of each validator.
+    pub uplc: bool,
+    /// The AIR of each validator, as JSON (see 'aiken_lang::gen_uplc::export').
+    pub air: bool,
+}
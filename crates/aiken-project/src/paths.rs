@@ -1,13 +1,27 @@
 use crate::deps::manifest::Package;
+use crate::package_name::PackageName;
+#[cfg(feature = "native")]
 use crate::{
     deps::manifest::Manifest,
     error::Error,
-    package_name::PackageName,
     telemetry::{Event, EventListener},
 };
 use regex::Regex;
+#[cfg(feature = "native")]
 use reqwest::Client;
-use std::{fs, path::PathBuf};
+#[cfg(feature = "native")]
+use std::fs;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// Base URL of the default package registry.
+pub const DEFAULT_REGISTRY: &str = "https://api.github.com";
+
+/// Environment variable overriding the mirrors declared in the project's configuration, as a
+/// comma-separated list of base URLs.
+pub const MIRRORS_ENV: &str = "AIKEN_PACKAGES_MIRRORS";
 
 pub fn project_config() -> PathBuf {
     PathBuf::from("aiken.toml")
@@ -17,10 +31,26 @@ pub fn manifest() -> PathBuf {
     PathBuf::from("aiken.lock")
 }
 
+pub fn budgets_lock() -> PathBuf {
+    PathBuf::from("budgets.lock")
+}
+
 pub fn build() -> PathBuf {
     PathBuf::from("build")
 }
 
+pub fn build_cache() -> PathBuf {
+    build().join("cache")
+}
+
+pub fn daemon() -> PathBuf {
+    build().join("daemon.json")
+}
+
+pub fn timings() -> PathBuf {
+    build().join("timings.json")
+}
+
 pub fn packages() -> PathBuf {
     build().join("packages")
 }
@@ -41,6 +71,58 @@ pub fn packages_cache() -> PathBuf {
     default_aiken_cache().join("packages")
 }
 
+pub fn artifacts_cache() -> PathBuf {
+    default_aiken_cache().join("artifacts")
+}
+
+pub fn advisories_cache() -> PathBuf {
+    default_aiken_cache().join("advisories.toml")
+}
+
+/// The blueprint of a given environment, placed next to the default one and named after it. For
+/// example, 'plutus.json' becomes 'plutus.preview.json' for the 'preview' environment.
+pub fn env_blueprint(blueprint: &Path, env: &str) -> PathBuf {
+    let stem = blueprint.file_stem().unwrap_or_default().to_string_lossy();
+
+    blueprint.with_file_name(match blueprint.extension() {
+        Some(extension) => format!("{stem}.{env}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.{env}"),
+    })
+}
+
+/// Base URLs to fetch packages from, in order of preference. Mirrors come first, either from the
+/// environment or from the project configuration, and the default registry always comes last.
+/// Mirrors are expected to expose the same routes as the default registry.
+pub fn registries(mirrors: &[String]) -> Vec<String> {
+    registries_with_override(env::var(MIRRORS_ENV).ok(), mirrors)
+}
+
+fn registries_with_override(env_mirrors: Option<String>, mirrors: &[String]) -> Vec<String> {
+    let mirrors = match env_mirrors {
+        Some(value) => value.split(',').map(str::to_string).collect(),
+        None => mirrors.to_vec(),
+    };
+
+    let mut registries: Vec<String> = mirrors
+        .iter()
+        .map(|mirror| mirror.trim().trim_end_matches('/').to_string())
+        .filter(|mirror| !mirror.is_empty() && mirror != DEFAULT_REGISTRY)
+        .collect();
+
+    registries.push(DEFAULT_REGISTRY.to_string());
+
+    registries
+}
+
+pub fn package_zipball_url(registry: &str, package: &Package) -> String {
+    format!(
+        "{registry}/repos/{}/{}/zipball/{}",
+        package.name.owner,
+        package.name.repo,
+        package.revision()
+    )
+}
+
 pub fn default_aiken_cache() -> PathBuf {
     dirs::cache_dir()
         .expect("Failed to determine user cache directory")
@@ -53,8 +135,10 @@ pub struct CacheKey {
 }
 
 impl CacheKey {
+    #[cfg(feature = "native")]
     pub async fn new<T>(
         http: &Client,
+        registries: &[String],
         event_listener: &T,
         package: &Package,
         manifest: &mut Manifest,
@@ -64,11 +148,11 @@ impl CacheKey {
     {
         Ok(CacheKey::from_package(
             package,
-            if is_git_sha_or_tag(&package.version) {
-                Ok(package.version.to_string())
+            if is_git_sha_or_tag(package.revision()) {
+                Ok(package.revision().to_string())
             } else {
                 match manifest.lookup_etag(package) {
-                    None => match new_etag_from_network(http, package).await {
+                    None => match new_etag_from_network(http, registries, package).await {
                         Err(_) => {
                             event_listener.handle_event(Event::PackageResolveFallback {
                                 name: format!("{}", package.name),
@@ -92,6 +176,7 @@ impl CacheKey {
         ))
     }
 
+    #[cfg(feature = "native")]
     fn from_package(package: &Package, version: String) -> CacheKey {
         CacheKey {
             key: format!("{}-{}-{}", package.name.owner, package.name.repo, version),
@@ -103,25 +188,36 @@ impl CacheKey {
     }
 }
 
-async fn new_etag_from_network(http: &Client, package: &Package) -> Result<String, Error> {
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/zipball/{}",
-        package.name.owner, package.name.repo, package.version
-    );
-    let response = http
-        .head(url)
-        .header("User-Agent", "aiken-lang")
-        .send()
-        .await?;
-    let etag = response
-        .headers()
-        .get("etag")
-        .ok_or(Error::UnknownPackageVersion {
-            package: package.clone(),
-        })?;
-    Ok(etag.to_str().unwrap().replace('"', ""))
+#[cfg(feature = "native")]
+async fn new_etag_from_network(
+    http: &Client,
+    registries: &[String],
+    package: &Package,
+) -> Result<String, Error> {
+    let mut error = Error::UnknownPackageVersion {
+        package: package.clone().into(),
+    };
+
+    for registry in registries {
+        match http
+            .head(package_zipball_url(registry, package))
+            .header("User-Agent", "aiken-lang")
+            .send()
+            .await
+        {
+            Ok(response) => {
+                if let Some(etag) = response.headers().get("etag") {
+                    return Ok(etag.to_str().unwrap().replace('"', ""));
+                }
+            }
+            Err(e) => error = e.into(),
+        }
+    }
+
+    Err(error)
 }
 
+#[cfg(feature = "native")]
 fn new_cache_key_from_cache(target: &Package) -> Result<String, Error> {
     let packages = fs::read_dir(packages_cache())?;
 
@@ -150,7 +246,7 @@ fn new_cache_key_from_cache(target: &Package) -> Result<String, Error> {
 
     match most_recently_modified {
         None => Err(Error::UnableToResolvePackage {
-            package: target.clone(),
+            package: target.clone().into(),
         }),
         Some(pkg) => Ok(format!(
             "{version}{etag}",
@@ -197,4 +293,47 @@ mod tests {
         assert!(!is_git_sha_or_tag("main"), "main branch");
         assert!(!is_git_sha_or_tag("8ba594659468ba"), "not sha");
     }
+
+    #[test]
+    fn test_env_blueprint() {
+        assert_eq!(
+            env_blueprint(Path::new("plutus.json"), "preview"),
+            PathBuf::from("plutus.preview.json")
+        );
+        assert_eq!(
+            env_blueprint(Path::new("/tmp/out/blueprint"), "mainnet"),
+            PathBuf::from("/tmp/out/blueprint.mainnet")
+        );
+    }
+
+    #[test]
+    fn test_registries() {
+        let mirrors = vec![
+            "https://mirror.example/".to_string(),
+            "https://backup.example".to_string(),
+        ];
+
+        assert_eq!(
+            registries_with_override(None, &[]),
+            vec![DEFAULT_REGISTRY.to_string()],
+            "default only"
+        );
+        assert_eq!(
+            registries_with_override(None, &mirrors),
+            vec![
+                "https://mirror.example".to_string(),
+                "https://backup.example".to_string(),
+                DEFAULT_REGISTRY.to_string()
+            ],
+            "mirrors first"
+        );
+        assert_eq!(
+            registries_with_override(Some("https://ci.example, ".to_string()), &mirrors),
+            vec![
+                "https://ci.example".to_string(),
+                DEFAULT_REGISTRY.to_string()
+            ],
+            "env override"
+        );
+    }
 }
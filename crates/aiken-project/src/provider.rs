@@ -0,0 +1,975 @@
+//! Resolve transaction inputs against a chain provider, so that transactions can be simulated
+//! without having to assemble their UTxO by hand. Inputs can also be resolved from a snapshot
+//! of the UTxO set, exported from a node beforehand.
+
+use miette::Diagnostic;
+use pallas_addresses::Address;
+use pallas_codec::{
+    minicbor,
+    utils::{Bytes, CborWrap, KeyValuePairs, NonEmptyKeyValuePairs, PositiveCoin},
+};
+use pallas_primitives::{
+    conway::{
+        AssetName, DatumOption, NativeScript, PlutusData, PlutusScript, PolicyId,
+        PostAlonzoTransactionOutput, ScriptRef, TransactionInput, TransactionOutput, Value,
+    },
+    Fragment,
+};
+use pallas_traverse::MultiEraTx;
+use reqwest::{blocking::Client, header::USER_AGENT};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use uplc::{ast::Data, tx::script_context::ResolvedInput};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Provider {
+    /// A Blockfrost-compatible API, authenticated with a project id.
+    Blockfrost { url: String, project_id: String },
+    /// A Kupo instance, indexing (at least) the outputs being resolved.
+    Kupo { url: String },
+    /// An Ogmios server, queried over HTTP.
+    Ogmios { url: String },
+    /// A snapshot of the UTxO set, in any of the formats understood by 'snapshot'.
+    Snapshot { path: PathBuf },
+}
+
+#[derive(Debug, thiserror::Error, Diagnostic)]
+pub enum Error {
+    #[error("I couldn't reach the provider: {0}")]
+    #[diagnostic(code("aiken::provider::http"))]
+    Http(#[from] reqwest::Error),
+
+    #[error("I couldn't tell which network the Blockfrost project '{project_id}' is for.")]
+    #[diagnostic(code("aiken::provider::blockfrost::network"))]
+    #[diagnostic(help(
+        "Blockfrost project ids start with the name of their network: 'mainnet', 'preprod' or 'preview'."
+    ))]
+    UnknownNetwork { project_id: String },
+
+    #[error("The provider didn't know about the output {reference}.")]
+    #[diagnostic(code("aiken::provider::not_found"))]
+    #[diagnostic(help(
+        "Make sure the provider is connected to the right network; and, for Ogmios, that the output hasn't already been spent."
+    ))]
+    NotFound { reference: String },
+
+    #[error("I couldn't make sense of the provider's response: {reason}")]
+    #[diagnostic(code("aiken::provider::malformed"))]
+    Malformed { reason: String },
+
+    #[error("I couldn't read the UTxO snapshot at {}: {error}", path.display())]
+    #[diagnostic(code("aiken::provider::snapshot"))]
+    Snapshot {
+        path: PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+}
+
+impl Provider {
+    /// Blockfrost's hosted API, whose network is inferred from the project id.
+    pub fn blockfrost(project_id: &str) -> Result<Self, Error> {
+        let network = ["mainnet", "preprod", "preview"]
+            .into_iter()
+            .find(|network| project_id.starts_with(network))
+            .ok_or_else(|| Error::UnknownNetwork {
+                project_id: project_id.to_string(),
+            })?;
+
+        Ok(Provider::Blockfrost {
+            url: format!("https://cardano-{network}.blockfrost.io/api/v0"),
+            project_id: project_id.to_string(),
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::Blockfrost { .. } => "Blockfrost",
+            Provider::Kupo { .. } => "Kupo",
+            Provider::Ogmios { .. } => "Ogmios",
+            Provider::Snapshot { .. } => "UTxO snapshot",
+        }
+    }
+
+    /// Fetch the outputs referenced by the given inputs. Resolved inputs come back in the same
+    /// order as the given ones.
+    pub fn resolve(&self, inputs: &[TransactionInput]) -> Result<Vec<ResolvedInput>, Error> {
+        let client = Client::new();
+
+        let outputs = match self {
+            Provider::Blockfrost { url, project_id } => {
+                resolve_blockfrost(&client, url.trim_end_matches('/'), project_id, inputs)?
+            }
+            Provider::Kupo { url } => resolve_kupo(&client, url.trim_end_matches('/'), inputs)?,
+            Provider::Ogmios { url } => resolve_ogmios(&client, url, inputs)?,
+            Provider::Snapshot { path } => resolve_snapshot(path, inputs)?,
+        };
+
+        Ok(inputs
+            .iter()
+            .cloned()
+            .zip(outputs)
+            .map(|(input, output)| ResolvedInput { input, output })
+            .collect())
+    }
+}
+
+/// Blockfrost gives us the CBOR of transactions, which we can take outputs from as-is. Each
+/// transaction is only fetched once, no matter how many of its outputs are needed.
+fn resolve_blockfrost(
+    client: &Client,
+    url: &str,
+    project_id: &str,
+    inputs: &[TransactionInput],
+) -> Result<Vec<TransactionOutput>, Error> {
+    #[derive(Deserialize)]
+    struct TxCbor {
+        cbor: String,
+    }
+
+    let mut transactions: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+
+    inputs
+        .iter()
+        .map(|input| {
+            let id = input.transaction_id.to_string();
+
+            if !transactions.contains_key(&id) {
+                let response = client
+                    .get(format!("{url}/txs/{id}/cbor"))
+                    .header(USER_AGENT, "aiken")
+                    .header("project_id", project_id)
+                    .send()?;
+
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Err(Error::NotFound {
+                        reference: reference(input),
+                    });
+                }
+
+                let TxCbor { cbor } = response.error_for_status()?.json()?;
+
+                transactions.insert(id.clone(), decode_hex(&cbor)?);
+            }
+
+            output_from_transaction(&transactions[&id], input)
+        })
+        .collect()
+}
+
+fn resolve_kupo(
+    client: &Client,
+    url: &str,
+    inputs: &[TransactionInput],
+) -> Result<Vec<TransactionOutput>, Error> {
+    #[derive(Deserialize)]
+    struct Datum {
+        datum: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Script {
+        language: String,
+        script: String,
+    }
+
+    inputs
+        .iter()
+        .map(|input| {
+            let matches: Vec<KupoMatch> = client
+                .get(format!(
+                    "{url}/matches/{}@{}",
+                    input.index, input.transaction_id
+                ))
+                .header(USER_AGENT, "aiken")
+                .send()?
+                .error_for_status()?
+                .json()?;
+
+            let found = matches.into_iter().next().ok_or_else(|| Error::NotFound {
+                reference: reference(input),
+            })?;
+
+            let datum = match (&found.datum_hash, found.datum_type.as_deref()) {
+                (Some(hash), Some("inline")) => client
+                    .get(format!("{url}/datums/{hash}"))
+                    .header(USER_AGENT, "aiken")
+                    .send()?
+                    .error_for_status()?
+                    .json::<Option<Datum>>()?
+                    .map(|Datum { datum }| datum),
+                _ => None,
+            };
+
+            let script = match &found.script_hash {
+                Some(hash) => client
+                    .get(format!("{url}/scripts/{hash}"))
+                    .header(USER_AGENT, "aiken")
+                    .send()?
+                    .error_for_status()?
+                    .json::<Option<Script>>()?
+                    .map(|Script { language, script }| (language, script)),
+                None => None,
+            };
+
+            found.into_output(datum, script)
+        })
+        .collect()
+}
+
+/// Ogmios only knows about the current UTxO set, so this can't resolve outputs that were
+/// already spent.
+fn resolve_ogmios(
+    client: &Client,
+    url: &str,
+    inputs: &[TransactionInput],
+) -> Result<Vec<TransactionOutput>, Error> {
+    #[derive(Deserialize)]
+    struct Response {
+        result: Option<Vec<OgmiosUtxo>>,
+        error: Option<serde_json::Value>,
+    }
+
+    let output_references = inputs
+        .iter()
+        .map(|input| {
+            json!({
+                "transaction": { "id": input.transaction_id.to_string() },
+                "index": input.index,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let response: Response = client
+        .post(url)
+        .header(USER_AGENT, "aiken")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "method": "queryLedgerState/utxo",
+            "params": { "outputReferences": output_references },
+        }))
+        .send()?
+        .json()?;
+
+    let utxos = match response {
+        Response {
+            result: Some(utxos),
+            ..
+        } => utxos,
+        Response { error, .. } => {
+            return Err(Error::Malformed {
+                reason: error.map(|e| e.to_string()).unwrap_or_default(),
+            })
+        }
+    };
+
+    inputs
+        .iter()
+        .map(|input| {
+            utxos
+                .iter()
+                .find(|utxo| {
+                    utxo.transaction.id == input.transaction_id.to_string()
+                        && utxo.index == input.index
+                })
+                .ok_or_else(|| Error::NotFound {
+                    reference: reference(input),
+                })
+                .and_then(OgmiosUtxo::to_output)
+        })
+        .collect()
+}
+
+fn resolve_snapshot(
+    path: &Path,
+    inputs: &[TransactionInput],
+) -> Result<Vec<TransactionOutput>, Error> {
+    let bytes = fs::read(path).map_err(|error| Error::Snapshot {
+        path: path.to_path_buf(),
+        error,
+    })?;
+
+    let utxo = snapshot(&bytes)?;
+
+    inputs
+        .iter()
+        .map(|input| {
+            utxo.get(input).cloned().ok_or_else(|| Error::NotFound {
+                reference: reference(input),
+            })
+        })
+        .collect()
+}
+
+/// Parse a snapshot of the UTxO set, which may be any of:
+///
+/// - the JSON written by cardano-cli's 'query utxo --out-file';
+/// - the JSON response to Ogmios' 'queryLedgerState/utxo', or only its result;
+/// - the CBOR map from inputs to outputs, as the ledger encodes it, either raw or hex-encoded.
+pub fn snapshot(bytes: &[u8]) -> Result<BTreeMap<TransactionInput, TransactionOutput>, Error> {
+    let malformed = |reason: String| Error::Malformed {
+        reason: format!("invalid UTxO snapshot: {reason}"),
+    };
+
+    if matches!(
+        bytes.iter().find(|byte| !byte.is_ascii_whitespace()),
+        Some(b'{' | b'[')
+    ) {
+        let json: serde_json::Value =
+            serde_json::from_slice(bytes).map_err(|e| malformed(e.to_string()))?;
+
+        return match json {
+            serde_json::Value::Object(ref fields) if fields.contains_key("jsonrpc") => {
+                ogmios_snapshot(json["result"].clone())
+            }
+            serde_json::Value::Array(..) => ogmios_snapshot(json),
+            serde_json::Value::Object(fields) => fields
+                .into_iter()
+                .map(|(reference, output)| {
+                    let (id, index) = reference.split_once('#').ok_or_else(|| {
+                        malformed(format!("invalid output reference {reference}"))
+                    })?;
+
+                    let input = TransactionInput {
+                        transaction_id: parse_hex(id)?,
+                        index: index.parse().map_err(|_| {
+                            malformed(format!("invalid output reference {reference}"))
+                        })?,
+                    };
+
+                    let output: CardanoCliOutput =
+                        serde_json::from_value(output).map_err(|e| malformed(e.to_string()))?;
+
+                    Ok((input, output.to_output()?))
+                })
+                .collect(),
+            _ => Err(malformed("expected an object or an array".to_string())),
+        };
+    }
+
+    let bytes = match std::str::from_utf8(bytes) {
+        Ok(text) => decode_hex(text.trim())?,
+        Err(..) => bytes.to_vec(),
+    };
+
+    minicbor::decode::<KeyValuePairs<TransactionInput, TransactionOutput>>(&bytes)
+        .map(|utxo| Vec::from(utxo).into_iter().collect())
+        .map_err(|e| malformed(e.to_string()))
+}
+
+fn ogmios_snapshot(
+    json: serde_json::Value,
+) -> Result<BTreeMap<TransactionInput, TransactionOutput>, Error> {
+    let utxos: Vec<OgmiosUtxo> = serde_json::from_value(json).map_err(|e| Error::Malformed {
+        reason: format!("invalid UTxO snapshot: {e}"),
+    })?;
+
+    utxos
+        .iter()
+        .map(|utxo| {
+            let input = TransactionInput {
+                transaction_id: parse_hex(&utxo.transaction.id)?,
+                index: utxo.index,
+            };
+
+            Ok((input, utxo.to_output()?))
+        })
+        .collect()
+}
+
+fn output_from_transaction(
+    bytes: &[u8],
+    input: &TransactionInput,
+) -> Result<TransactionOutput, Error> {
+    let tx = MultiEraTx::decode(bytes).map_err(|e| Error::Malformed {
+        reason: e.to_string(),
+    })?;
+
+    let output = tx
+        .output_at(input.index as usize)
+        .ok_or_else(|| Error::NotFound {
+            reference: reference(input),
+        })?;
+
+    TransactionOutput::decode_fragment(&output.encode()).map_err(|e| Error::Malformed {
+        reason: e.to_string(),
+    })
+}
+
+#[derive(Deserialize)]
+struct KupoMatch {
+    address: String,
+    value: KupoValue,
+    datum_hash: Option<String>,
+    datum_type: Option<String>,
+    script_hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KupoValue {
+    coins: u64,
+    #[serde(default)]
+    assets: BTreeMap<String, u64>,
+}
+
+impl KupoMatch {
+    /// Kupo only gives hashes of datums and scripts; their content is fetched separately and
+    /// given here, if any.
+    fn into_output(
+        self,
+        datum: Option<String>,
+        script: Option<(String, String)>,
+    ) -> Result<TransactionOutput, Error> {
+        // Assets are keyed by 'policy.name', or just 'policy' when the asset name is empty.
+        let assets = self
+            .value
+            .assets
+            .iter()
+            .map(|(asset, quantity)| {
+                let (policy, name) = asset.split_once('.').unwrap_or((asset, ""));
+                (policy, name, *quantity)
+            })
+            .collect();
+
+        let datum_option = match (datum, self.datum_hash) {
+            (Some(datum), _) => Some(inline_datum(&datum)?),
+            (None, Some(hash)) => Some(DatumOption::Hash(parse_hex(&hash)?)),
+            (None, None) => None,
+        };
+
+        let script_ref = match script {
+            Some((language, script)) => Some(script_ref(&language, decode_hex(&script)?)?),
+            None => None,
+        };
+
+        post_alonzo_output(
+            &self.address,
+            value(self.value.coins, assets)?,
+            datum_option,
+            script_ref,
+        )
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OgmiosUtxo {
+    transaction: OgmiosTransactionId,
+    index: u64,
+    address: String,
+    value: BTreeMap<String, BTreeMap<String, u64>>,
+    datum_hash: Option<String>,
+    datum: Option<String>,
+    script: Option<OgmiosScript>,
+}
+
+#[derive(Deserialize)]
+struct OgmiosTransactionId {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct OgmiosScript {
+    language: String,
+    cbor: String,
+}
+
+impl OgmiosUtxo {
+    fn to_output(&self) -> Result<TransactionOutput, Error> {
+        // Lovelace are given as any other asset, under 'ada'.
+        let coin = self
+            .value
+            .get("ada")
+            .and_then(|ada| ada.get("lovelace"))
+            .copied()
+            .unwrap_or_default();
+
+        let assets = self
+            .value
+            .iter()
+            .filter(|(policy, _)| policy.as_str() != "ada")
+            .flat_map(|(policy, tokens)| {
+                tokens
+                    .iter()
+                    .map(move |(name, quantity)| (policy.as_str(), name.as_str(), *quantity))
+            })
+            .collect();
+
+        let datum_option = match (&self.datum, &self.datum_hash) {
+            (Some(datum), _) => Some(inline_datum(datum)?),
+            (None, Some(hash)) => Some(DatumOption::Hash(parse_hex(hash)?)),
+            (None, None) => None,
+        };
+
+        let script_ref = match &self.script {
+            Some(OgmiosScript { language, cbor }) => Some(script_ref(language, decode_hex(cbor)?)?),
+            None => None,
+        };
+
+        post_alonzo_output(
+            &self.address,
+            value(coin, assets)?,
+            datum_option,
+            script_ref,
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct CardanoCliOutput {
+    address: String,
+    value: BTreeMap<String, serde_json::Value>,
+    datumhash: Option<String>,
+    #[serde(rename = "inlineDatum")]
+    inline_datum: Option<serde_json::Value>,
+    #[serde(rename = "inlineDatumRaw")]
+    inline_datum_raw: Option<String>,
+    #[serde(rename = "referenceScript")]
+    reference_script: Option<CardanoCliReferenceScript>,
+}
+
+#[derive(Deserialize)]
+struct CardanoCliReferenceScript {
+    script: CardanoCliScript,
+}
+
+#[derive(Deserialize)]
+struct CardanoCliScript {
+    #[serde(rename = "type")]
+    language: String,
+    #[serde(rename = "cborHex")]
+    cbor_hex: String,
+}
+
+impl CardanoCliOutput {
+    fn to_output(&self) -> Result<TransactionOutput, Error> {
+        // Lovelace come as a number, and other assets as objects keyed by policy.
+        let coin = self
+            .value
+            .get("lovelace")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or_default();
+
+        let assets = self
+            .value
+            .iter()
+            .filter(|(policy, _)| policy.as_str() != "lovelace")
+            .filter_map(|(policy, tokens)| Some((policy, tokens.as_object()?)))
+            .flat_map(|(policy, tokens)| {
+                tokens.iter().filter_map(move |(name, quantity)| {
+                    Some((policy.as_str(), name.as_str(), quantity.as_u64()?))
+                })
+            })
+            .collect();
+
+        // Older versions of the cli only give inline datums as JSON, newer ones also as CBOR.
+        let datum_option = match (&self.inline_datum_raw, &self.inline_datum, &self.datumhash) {
+            (Some(datum), _, _) => Some(inline_datum(datum)?),
+            (None, Some(datum), _) => Some(DatumOption::Data(CborWrap(data_from_json(datum)?))),
+            (None, None, Some(hash)) => Some(DatumOption::Hash(parse_hex(hash)?)),
+            (None, None, None) => None,
+        };
+
+        let script_ref = match &self.reference_script {
+            Some(CardanoCliReferenceScript { script }) => {
+                let bytes = decode_hex(&script.cbor_hex)?;
+                Some(match script.language.as_str() {
+                    "SimpleScript" | "SimpleScriptV1" | "SimpleScriptV2" => {
+                        script_ref("native", bytes)?
+                    }
+                    // Text envelopes wrap Plutus scripts in an extra CBOR byte string.
+                    language => {
+                        let bytes = minicbor::decode::<Bytes>(&bytes)
+                            .map_err(|e| Error::Malformed {
+                                reason: format!("invalid plutus script: {e}"),
+                            })?
+                            .to_vec();
+
+                        match language {
+                            "PlutusScriptV1" => script_ref("plutus:v1", bytes)?,
+                            "PlutusScriptV2" => script_ref("plutus:v2", bytes)?,
+                            "PlutusScriptV3" => script_ref("plutus:v3", bytes)?,
+                            _ => script_ref(language, bytes)?,
+                        }
+                    }
+                })
+            }
+            None => None,
+        };
+
+        post_alonzo_output(
+            &self.address,
+            value(coin, assets)?,
+            datum_option,
+            script_ref,
+        )
+    }
+}
+
+fn post_alonzo_output(
+    address: &str,
+    value: Value,
+    datum_option: Option<DatumOption>,
+    script_ref: Option<ScriptRef>,
+) -> Result<TransactionOutput, Error> {
+    let address = address.parse::<Address>().map_err(|e| Error::Malformed {
+        reason: format!("invalid address {address}: {e}"),
+    })?;
+
+    Ok(TransactionOutput::PostAlonzo(PostAlonzoTransactionOutput {
+        address: address.to_vec().into(),
+        value,
+        datum_option,
+        script_ref: script_ref.map(CborWrap),
+    }))
+}
+
+/// Assemble a value from lovelace and a flat list of (policy, asset name, quantity), with
+/// policies and asset names hex-encoded.
+fn value(coin: u64, assets: Vec<(&str, &str, u64)>) -> Result<Value, Error> {
+    let mut multiasset: BTreeMap<PolicyId, Vec<(AssetName, PositiveCoin)>> = BTreeMap::new();
+
+    for (policy, name, quantity) in assets {
+        // Quantities in an output are always positive; there's nothing to keep otherwise.
+        if let Ok(quantity) = PositiveCoin::try_from(quantity) {
+            multiasset
+                .entry(parse_hex(policy)?)
+                .or_default()
+                .push((decode_hex(name)?.into(), quantity));
+        }
+    }
+
+    let multiasset = multiasset
+        .into_iter()
+        .filter_map(|(policy, tokens)| Some((policy, NonEmptyKeyValuePairs::from_vec(tokens)?)))
+        .collect();
+
+    Ok(match NonEmptyKeyValuePairs::from_vec(multiasset) {
+        Some(multiasset) => Value::Multiasset(coin, multiasset),
+        None => Value::Coin(coin),
+    })
+}
+
+fn inline_datum(cbor: &str) -> Result<DatumOption, Error> {
+    PlutusData::decode_fragment(&decode_hex(cbor)?)
+        .map(|data| DatumOption::Data(CborWrap(data)))
+        .map_err(|e| Error::Malformed {
+            reason: format!("invalid inline datum: {e}"),
+        })
+}
+
+fn script_ref(language: &str, bytes: Vec<u8>) -> Result<ScriptRef, Error> {
+    match language {
+        "native" => minicbor::decode::<NativeScript>(&bytes)
+            .map(ScriptRef::NativeScript)
+            .map_err(|e| Error::Malformed {
+                reason: format!("invalid native script: {e}"),
+            }),
+        "plutus:v1" => Ok(ScriptRef::PlutusV1Script(PlutusScript(bytes.into()))),
+        "plutus:v2" => Ok(ScriptRef::PlutusV2Script(PlutusScript(bytes.into()))),
+        "plutus:v3" => Ok(ScriptRef::PlutusV3Script(PlutusScript(bytes.into()))),
+        _ => Err(Error::Malformed {
+            reason: format!("unknown script language '{language}'"),
+        }),
+    }
+}
+
+/// Plutus data from JSON following the so-called 'detailed schema' used by the cardano-cli; the
+/// converse of 'serve::data_to_json'.
+fn data_from_json(json: &serde_json::Value) -> Result<PlutusData, Error> {
+    let malformed = || Error::Malformed {
+        reason: format!("invalid inline datum: {json}"),
+    };
+
+    let fields = json.as_object().ok_or_else(malformed)?;
+
+    if let Some(int) = fields.get("int") {
+        let int = match int {
+            serde_json::Value::Number(n) => n.to_string(),
+            serde_json::Value::String(s) => s.clone(),
+            _ => return Err(malformed()),
+        };
+
+        return int
+            .parse::<num_bigint::BigInt>()
+            .map(Data::integer)
+            .map_err(|_| malformed());
+    }
+
+    if let Some(bytes) = fields.get("bytes").and_then(serde_json::Value::as_str) {
+        return Ok(Data::bytestring(decode_hex(bytes)?));
+    }
+
+    if let Some(items) = fields.get("list").and_then(serde_json::Value::as_array) {
+        return items
+            .iter()
+            .map(data_from_json)
+            .collect::<Result<_, _>>()
+            .map(Data::list);
+    }
+
+    if let Some(pairs) = fields.get("map").and_then(serde_json::Value::as_array) {
+        return pairs
+            .iter()
+            .map(|pair| Ok((data_from_json(&pair["k"])?, data_from_json(&pair["v"])?)))
+            .collect::<Result<_, _>>()
+            .map(Data::map);
+    }
+
+    match (
+        fields
+            .get("constructor")
+            .and_then(serde_json::Value::as_u64),
+        fields.get("fields").and_then(serde_json::Value::as_array),
+    ) {
+        (Some(constructor), Some(items)) => items
+            .iter()
+            .map(data_from_json)
+            .collect::<Result<_, _>>()
+            .map(|items| Data::constr(constructor, items)),
+        _ => Err(malformed()),
+    }
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(s).map_err(|e| Error::Malformed {
+        reason: format!("invalid hex string '{s}': {e}"),
+    })
+}
+
+fn parse_hex<T: std::str::FromStr>(s: &str) -> Result<T, Error> {
+    s.parse().map_err(|_| Error::Malformed {
+        reason: format!("invalid hash or policy id '{s}'"),
+    })
+}
+
+fn reference(input: &TransactionInput) -> String {
+    format!("{}#{}", input.transaction_id, input.index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "addr_test1vqxe3dm7mtezkj06wqplfauzauaaghwnm2q5fqyrftynrqs94krmp";
+
+    const POLICY: &str = "a0028f350aaabe0545fdcb56b039bfb08e4bb4d8c4d7c3c7d481c235";
+
+    const TX_ID: &str = "bb30a42c1e62f0afda5f0a4e8a562f7a13a24cea00ee81917b86b89e801314aa";
+
+    #[test]
+    fn blockfrost_network_from_project_id() {
+        assert_eq!(
+            Provider::blockfrost("preprodABCDEF").unwrap(),
+            Provider::Blockfrost {
+                url: "https://cardano-preprod.blockfrost.io/api/v0".to_string(),
+                project_id: "preprodABCDEF".to_string(),
+            }
+        );
+
+        assert!(matches!(
+            Provider::blockfrost("ABCDEF"),
+            Err(Error::UnknownNetwork { .. })
+        ));
+    }
+
+    #[test]
+    fn kupo_match_to_output() {
+        let found: KupoMatch = serde_json::from_value(json!({
+            "transaction_index": 0,
+            "transaction_id": "bb30a42c1e62f0afda5f0a4e8a562f7a13a24cea00ee81917b86b89e801314aa",
+            "output_index": 0,
+            "address": ADDRESS,
+            "value": {
+                "coins": 2000000,
+                "assets": {
+                    format!("{POLICY}.7468696e67"): 1,
+                    POLICY: 42,
+                }
+            },
+            "datum_hash": "923918e403bf43c34b4ef6b48eb2ee04babed17320d8d1b9ff9ad086e86f44ec",
+            "datum_type": "inline",
+            "script_hash": null,
+            "created_at": { "slot_no": 1, "header_hash": "00" },
+            "spent_at": null
+        }))
+        .unwrap();
+
+        let output = found.into_output(Some("d87980".to_string()), None).unwrap();
+
+        let TransactionOutput::PostAlonzo(output) = output else {
+            panic!("expected a post-alonzo output");
+        };
+
+        let Value::Multiasset(coin, multiasset) = output.value else {
+            panic!("expected a multi-asset value");
+        };
+
+        assert_eq!(coin, 2000000);
+
+        let tokens = multiasset.to_vec().remove(0).1.to_vec();
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].0.to_vec(), Vec::<u8>::new());
+        assert_eq!(u64::from(&tokens[0].1), 42);
+        assert_eq!(tokens[1].0.to_vec(), b"thing".to_vec());
+
+        assert!(matches!(output.datum_option, Some(DatumOption::Data(..))));
+        assert!(output.script_ref.is_none());
+    }
+
+    #[test]
+    fn ogmios_utxo_to_output() {
+        let utxo: OgmiosUtxo = serde_json::from_value(json!({
+            "transaction": {
+                "id": "bb30a42c1e62f0afda5f0a4e8a562f7a13a24cea00ee81917b86b89e801314aa"
+            },
+            "index": 1,
+            "address": ADDRESS,
+            "value": { "ada": { "lovelace": 1500000 } },
+            "datumHash": "923918e403bf43c34b4ef6b48eb2ee04babed17320d8d1b9ff9ad086e86f44ec",
+            "script": { "language": "plutus:v3", "cbor": "46450101002499" }
+        }))
+        .unwrap();
+
+        let TransactionOutput::PostAlonzo(output) = utxo.to_output().unwrap() else {
+            panic!("expected a post-alonzo output");
+        };
+
+        assert_eq!(output.value, Value::Coin(1500000));
+        assert!(matches!(output.datum_option, Some(DatumOption::Hash(..))));
+        assert!(matches!(
+            output.script_ref.map(|script| script.unwrap()),
+            Some(ScriptRef::PlutusV3Script(..))
+        ));
+    }
+
+    #[test]
+    fn snapshot_from_cardano_cli() {
+        let utxo = snapshot(
+            json!({
+                format!("{TX_ID}#0"): {
+                    "address": ADDRESS,
+                    "datum": null,
+                    "inlineDatum": { "constructor": 0, "fields": [{ "int": 42 }] },
+                    "inlineDatumhash": "923918e403bf43c34b4ef6b48eb2ee04babed17320d8d1b9ff9ad086e86f44ec",
+                    "referenceScript": {
+                        "script": {
+                            "cborHex": "4746450101002499",
+                            "description": "",
+                            "type": "PlutusScriptV3"
+                        },
+                        "scriptLanguage": "PlutusScriptLanguage PlutusScriptV3"
+                    },
+                    "value": {
+                        "lovelace": 5000000,
+                        POLICY: { "7468696e67": 1 }
+                    }
+                },
+                format!("{TX_ID}#1"): {
+                    "address": ADDRESS,
+                    "datum": null,
+                    "datumhash": "923918e403bf43c34b4ef6b48eb2ee04babed17320d8d1b9ff9ad086e86f44ec",
+                    "value": { "lovelace": 1000000 }
+                }
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(utxo.len(), 2);
+
+        let TransactionOutput::PostAlonzo(output) = &utxo[&input(0)] else {
+            panic!("expected a post-alonzo output");
+        };
+
+        assert!(matches!(output.value, Value::Multiasset(5000000, _)));
+        assert_eq!(
+            output.datum_option,
+            Some(DatumOption::Data(CborWrap(Data::constr(
+                0,
+                vec![Data::integer(42.into())]
+            ))))
+        );
+        assert_eq!(
+            output.script_ref.clone().map(|script| script.unwrap()),
+            Some(ScriptRef::PlutusV3Script(PlutusScript(
+                hex::decode("46450101002499").unwrap().into()
+            )))
+        );
+
+        let TransactionOutput::PostAlonzo(output) = &utxo[&input(1)] else {
+            panic!("expected a post-alonzo output");
+        };
+
+        assert_eq!(output.value, Value::Coin(1000000));
+        assert!(matches!(output.datum_option, Some(DatumOption::Hash(..))));
+    }
+
+    #[test]
+    fn snapshot_from_ogmios() {
+        let utxo = snapshot(
+            json!({
+                "jsonrpc": "2.0",
+                "method": "queryLedgerState/utxo",
+                "result": [{
+                    "transaction": { "id": TX_ID },
+                    "index": 3,
+                    "address": ADDRESS,
+                    "value": { "ada": { "lovelace": 1500000 } }
+                }]
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(utxo.keys().collect::<Vec<_>>(), vec![&input(3)]);
+    }
+
+    #[test]
+    fn snapshot_from_cbor() {
+        let output = post_alonzo_output(ADDRESS, Value::Coin(2000000), None, None).unwrap();
+
+        let utxo: KeyValuePairs<TransactionInput, TransactionOutput> =
+            vec![(input(0), output.clone())].into_iter().collect();
+
+        let cbor = minicbor::to_vec(&utxo).unwrap();
+
+        let expected = BTreeMap::from([(input(0), output)]);
+
+        assert_eq!(snapshot(&cbor).unwrap(), expected);
+        assert_eq!(snapshot(hex::encode(&cbor).as_bytes()).unwrap(), expected);
+    }
+
+    #[test]
+    fn data_from_json_detailed_schema() {
+        let data = Data::constr(
+            1,
+            vec![
+                Data::map(vec![(
+                    Data::bytestring(vec![0xab, 0xcd]),
+                    Data::list(vec![Data::integer((-1).into())]),
+                )]),
+                Data::integer(num_bigint::BigInt::from(u128::MAX)),
+            ],
+        );
+
+        assert_eq!(
+            data_from_json(&crate::serve::data_to_json(&data)).unwrap(),
+            data
+        );
+    }
+
+    fn input(index: u64) -> TransactionInput {
+        TransactionInput {
+            transaction_id: TX_ID.parse().unwrap(),
+            index,
+        }
+    }
+}
@@ -0,0 +1,496 @@
+//! Evaluation of expressions against a compiled project, one input at a time, for `aiken repl`.
+//!
+//! Inputs are gathered in a scratch module, which imports every module of the project and of
+//! its dependencies (unless their names are ambiguous), along with the imports and definitions
+//! entered so far. An expression becomes the body of a function of that module, which is then
+//! type-checked, compiled and evaluated, much like a unit test.
+
+use crate::{error::Error, module::ParsedModule, telemetry::EventListener, utils, Project};
+use aiken_lang::{
+    ast::{Definition, ModuleKind, Tracing, TypedFunction, UntypedDefinition},
+    expr::UntypedExpr,
+    parser,
+    tipo::Type,
+};
+use miette::NamedSource;
+use std::{path::PathBuf, rc::Rc};
+use uplc::{
+    ast::{NamedDeBruijn, Program, Term},
+    machine::cost_model::ExBudget,
+};
+
+/// Name of the scratch module. It isn't a valid module path, so it can't clash with any module
+/// of the project.
+pub const MODULE: &str = "_repl";
+
+/// Name of the function holding the expression being evaluated.
+const EXPRESSION: &str = "repl";
+
+/// First words of inputs which are definitions or imports, rather than expressions.
+const DEFINITION_KEYWORDS: [&str; 7] = ["use", "pub", "fn", "const", "type", "opaque", "let"];
+
+/// Imports and definitions entered so far.
+#[derive(Debug)]
+pub struct Session {
+    entries: Vec<Entry>,
+    env: Option<String>,
+    tracing: Tracing,
+}
+
+/// An input holding definitions, kept as it was entered.
+#[derive(Debug, Clone)]
+struct Entry {
+    code: String,
+    /// Modules imported, with their alias.
+    imports: Vec<(String, String)>,
+    /// Names of the types, constructors and values defined.
+    names: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    /// Names defined or imported by the input.
+    Defined(Vec<String>),
+    Evaluated(Evaluation),
+}
+
+#[derive(Debug)]
+pub struct Evaluation {
+    pub tipo: Rc<Type>,
+    /// What the expression evaluated to, or why its evaluation failed.
+    pub result: Result<Value, String>,
+    pub spent_budget: ExBudget,
+    pub traces: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum Value {
+    /// A value that reads back as Aiken code.
+    Expr(UntypedExpr),
+    /// Any other value (e.g. a function), as the term it evaluated to.
+    Term(Term<NamedDeBruijn>),
+}
+
+impl Session {
+    pub fn new(tracing: Tracing, env: Option<String>) -> Self {
+        Session {
+            entries: Vec::new(),
+            env,
+            tracing,
+        }
+    }
+
+    /// Whether an input holds definitions (or imports) to add to the session, rather than an
+    /// expression to evaluate.
+    pub fn is_definition(input: &str) -> bool {
+        let input = input.trim_start();
+
+        let first_word = input
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .unwrap_or_default();
+
+        match first_word {
+            // Anonymous functions are expressions.
+            "fn" => !input["fn".len()..].trim_start().starts_with('('),
+            _ => DEFINITION_KEYWORDS.contains(&first_word),
+        }
+    }
+
+    /// Add definitions to the session, or evaluate an expression; depending on the input.
+    pub fn eval<T>(&mut self, project: &mut Project<T>, input: &str) -> Result<Outcome, Vec<Error>>
+    where
+        T: EventListener,
+    {
+        if Session::is_definition(input) {
+            return self.define(project, input).map(Outcome::Defined);
+        }
+
+        let function = self.check(project, &self.entries, Some(input))?;
+
+        let function = function.expect("scratch module holds an expression");
+
+        let mut generator = project.new_generator(self.tracing);
+
+        let program = generator.generate_raw(&function.body, &[], MODULE);
+
+        let mut eval_result = Program::<NamedDeBruijn>::try_from(program)
+            .unwrap()
+            .eval_version(ExBudget::max(), &project.config.plutus.into());
+
+        let data_types = utils::indexmap::as_ref_values(&project.data_types);
+
+        let result = match eval_result.result() {
+            Ok(Term::Constant(constant)) => Ok(UntypedExpr::reify_constant(
+                &data_types,
+                constant.as_ref().clone(),
+                &function.return_type,
+            )
+            .map(Value::Expr)
+            .unwrap_or_else(|_| Value::Term(Term::Constant(constant)))),
+            Ok(term) => Ok(Value::Term(term)),
+            Err(error) => Err(error.to_string()),
+        };
+
+        Ok(Outcome::Evaluated(Evaluation {
+            tipo: function.return_type,
+            result,
+            spent_budget: eval_result.cost(),
+            traces: eval_result.logs(),
+        }))
+    }
+
+    /// Infer the type of an expression, without evaluating it.
+    pub fn infer<T>(&self, project: &mut Project<T>, input: &str) -> Result<Rc<Type>, Vec<Error>>
+    where
+        T: EventListener,
+    {
+        let function = self.check(project, &self.entries, Some(input))?;
+
+        Ok(function
+            .expect("scratch module holds an expression")
+            .return_type)
+    }
+
+    /// Names imported or defined so far, in order.
+    pub fn names(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .flat_map(|entry| {
+                entry
+                    .imports
+                    .iter()
+                    .map(|(_, alias)| alias.clone())
+                    .chain(entry.names.iter().cloned())
+            })
+            .collect()
+    }
+
+    /// Add an input holding definitions, replacing earlier entries that define (or import) any
+    /// of the same names.
+    fn define<T>(
+        &mut self,
+        project: &mut Project<T>,
+        input: &str,
+    ) -> Result<Vec<String>, Vec<Error>>
+    where
+        T: EventListener,
+    {
+        // Bindings are kept around as constants, for lack of a scope to live in.
+        let code = match input.trim_start().strip_prefix("let ") {
+            Some(binding) => format!("const {binding}"),
+            None => input.to_string(),
+        };
+
+        let (module, _) = parser::module(&code, ModuleKind::Validator)
+            .map_err(|errors| parse_errors(&code, errors))?;
+
+        let mut entry = Entry {
+            code,
+            imports: Vec::new(),
+            names: Vec::new(),
+        };
+
+        for definition in module.definitions() {
+            define(&mut entry, definition);
+        }
+
+        let entries = self
+            .entries
+            .iter()
+            .filter(|previous| !previous.overlaps(&entry))
+            .cloned()
+            .chain(std::iter::once(entry.clone()))
+            .collect::<Vec<_>>();
+
+        self.check(project, &entries, None)?;
+
+        self.entries = entries;
+
+        Ok(entry
+            .imports
+            .into_iter()
+            .map(|(_, alias)| alias)
+            .chain(entry.names)
+            .collect())
+    }
+
+    /// Type-check the scratch module, made of the given entries and, maybe, an expression; in
+    /// which case the function holding it is returned.
+    fn check<T>(
+        &self,
+        project: &mut Project<T>,
+        entries: &[Entry],
+        expression: Option<&str>,
+    ) -> Result<Option<TypedFunction>, Vec<Error>>
+    where
+        T: EventListener,
+    {
+        let code = self.module_source(project, entries, expression);
+
+        let (mut ast, extra) = parser::module(&code, ModuleKind::Validator)
+            .map_err(|errors| parse_errors(&code, errors))?;
+
+        ast.name = MODULE.to_string();
+
+        let module = ParsedModule {
+            path: PathBuf::from(EXPRESSION),
+            name: MODULE.to_string(),
+            code,
+            kind: ModuleKind::Validator,
+            package: project.config.name.to_string(),
+            ast,
+            extra,
+        };
+
+        let (checked_module, _warnings) = module
+            .infer(
+                &project.id_gen,
+                &project.config.name.to_string(),
+                self.tracing,
+                self.env.as_deref(),
+                false,
+                &mut project.module_sources,
+                &mut project.module_types,
+                &mut project.functions,
+                &mut project.constants,
+                &mut project.data_types,
+            )
+            .map_err(|error| vec![error])?;
+
+        Ok(checked_module
+            .ast
+            .into_definitions()
+            .find_map(|definition| match definition {
+                Definition::Fn(function) if function.name == EXPRESSION => Some(function),
+                _ => None,
+            }))
+    }
+
+    fn module_source<T>(
+        &self,
+        project: &Project<T>,
+        entries: &[Entry],
+        expression: Option<&str>,
+    ) -> String
+    where
+        T: EventListener,
+    {
+        let mut code = String::new();
+
+        for module in default_imports(project, entries) {
+            code.push_str(&format!("use {module}\n"));
+        }
+
+        for entry in entries {
+            code.push_str(&entry.code);
+            code.push('\n');
+        }
+
+        if let Some(expression) = expression {
+            code.push_str(&format!("fn {EXPRESSION}() {{\n{expression}\n}}\n"));
+        }
+
+        code
+    }
+}
+
+impl Entry {
+    fn overlaps(&self, other: &Entry) -> bool {
+        self.names.iter().any(|name| other.names.contains(name))
+            || self.imports.iter().any(|(module, alias)| {
+                other.imports.iter().any(|(other_module, other_alias)| {
+                    module == other_module || alias == other_alias
+                })
+            })
+    }
+}
+
+fn define(entry: &mut Entry, definition: &UntypedDefinition) {
+    match definition {
+        Definition::Use(import) => {
+            let alias = import
+                .as_name
+                .clone()
+                .or_else(|| import.module.last().cloned())
+                .unwrap_or_default();
+
+            entry.imports.push((import.module.join("/"), alias));
+        }
+        Definition::DataType(data_type) => {
+            entry.names.push(data_type.name.clone());
+            entry.names.extend(
+                data_type
+                    .constructors
+                    .iter()
+                    .map(|constructor| constructor.name.clone()),
+            );
+        }
+        Definition::TypeAlias(alias) => entry.names.push(alias.alias.clone()),
+        Definition::Fn(function) => entry.names.push(function.name.clone()),
+        Definition::ModuleConstant(constant) => entry.names.push(constant.name.clone()),
+        Definition::Test(test) | Definition::Benchmark(test) => entry.names.push(test.name.clone()),
+        Definition::Validator(validator) => entry.names.push(validator.name.clone()),
+    }
+}
+
+/// Modules of the project and its dependencies to import, by their full name. Modules that
+/// share their last segment with another are left out, as are those the entries import (or
+/// whose alias they use) themselves.
+fn default_imports<T>(project: &Project<T>, entries: &[Entry]) -> Vec<String>
+where
+    T: EventListener,
+{
+    let alias = |module: &str| module.rsplit('/').next().unwrap_or(module).to_string();
+
+    let mut modules = project
+        .checked_modules
+        .values()
+        .filter(|module| matches!(module.kind, ModuleKind::Lib | ModuleKind::Validator))
+        .map(|module| module.name.clone())
+        .collect::<Vec<_>>();
+
+    modules.sort();
+
+    modules
+        .iter()
+        .filter(|module| {
+            modules
+                .iter()
+                .filter(|other| alias(other) == alias(module))
+                .count()
+                == 1
+        })
+        .filter(|module| {
+            !entries.iter().any(|entry| {
+                entry
+                    .imports
+                    .iter()
+                    .any(|(imported, as_name)| imported == *module || *as_name == alias(module))
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+fn parse_errors(code: &str, errors: Vec<aiken_lang::parser::error::ParseError>) -> Vec<Error> {
+    errors
+        .into_iter()
+        .map(|error| Error::Parse {
+            path: PathBuf::from(EXPRESSION),
+            src: code.to_string(),
+            named: NamedSource::new(EXPRESSION, code.to_string()).into(),
+            error: error.into(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, options::Options};
+    use aiken_lang::format::Formatter;
+    use std::fs;
+
+    struct NoListener;
+
+    impl EventListener for NoListener {}
+
+    fn project(root: &std::path::Path) -> Project<NoListener> {
+        let _ = fs::remove_dir_all(root);
+        fs::create_dir_all(root.join("lib/foo")).unwrap();
+
+        fs::write(
+            root.join("lib/foo/bar.ak"),
+            "pub fn double(n: Int) -> Int {\n  n * 2\n}\n",
+        )
+        .unwrap();
+
+        let config: Config = toml::from_str(
+            r#"
+            name = "aiken-lang/test"
+            version = "1.0.0"
+            "#,
+        )
+        .unwrap();
+
+        let mut project = Project::new_with_config(config, root.to_path_buf(), NoListener);
+
+        project.compile(Options::default()).unwrap();
+
+        project
+    }
+
+    fn eval(session: &mut Session, project: &mut Project<NoListener>, input: &str) -> Evaluation {
+        match session.eval(project, input) {
+            Ok(Outcome::Evaluated(evaluation)) => evaluation,
+            _ => panic!("expected an evaluation of {input}"),
+        }
+    }
+
+    fn show(evaluation: &Evaluation) -> String {
+        match &evaluation.result {
+            Ok(Value::Expr(expr)) => Formatter::new().expr(expr, false).to_pretty_string(80),
+            _ => panic!("expected a value"),
+        }
+    }
+
+    #[test]
+    fn evaluate_in_project() {
+        let root = std::env::temp_dir().join(format!("aiken-repl-{}", std::process::id()));
+
+        let mut project = project(&root);
+
+        let mut session = Session::new(Tracing::verbose(), None);
+
+        let evaluation = eval(&mut session, &mut project, "bar.double(21)");
+        assert_eq!(show(&evaluation), "42");
+        assert_eq!(evaluation.tipo.to_pretty(0), "Int");
+        assert!(evaluation.spent_budget.cpu > 0);
+
+        assert!(matches!(
+            session.eval(&mut project, "fn triple(n) { n * 3 }"),
+            Ok(Outcome::Defined(names)) if names == ["triple"]
+        ));
+
+        let evaluation = eval(&mut session, &mut project, "trace @\"hello\"\ntriple(2)");
+        assert_eq!(show(&evaluation), "6");
+        assert_eq!(evaluation.traces, ["hello"]);
+
+        assert!(matches!(
+            session.eval(&mut project, "let x = bar.double(4)"),
+            Ok(Outcome::Defined(names)) if names == ["x"]
+        ));
+
+        assert_eq!(show(&eval(&mut session, &mut project, "[x, x]")), "[8, 8]");
+
+        assert_eq!(
+            session
+                .infer(&mut project, "fn(n) { bar.double(n) > 0 }")
+                .unwrap()
+                .to_pretty(0),
+            "fn(Int) -> Bool"
+        );
+
+        assert!(session.eval(&mut project, "triple(True)").is_err());
+        assert!(session.eval(&mut project, "fn oops() { nope }").is_err());
+        assert_eq!(session.names(), ["triple", "x"]);
+
+        let evaluation = eval(&mut session, &mut project, "fail @\"boom\"");
+        assert!(evaluation.result.is_err());
+        assert_eq!(evaluation.traces, ["boom"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn definition_inputs() {
+        assert!(Session::is_definition("use aiken/collection/list"));
+        assert!(Session::is_definition("  pub fn foo() { 1 }"));
+        assert!(Session::is_definition("let x = 14"));
+        assert!(Session::is_definition("type Foo {\n  Bar\n}"));
+        assert!(!Session::is_definition("list.map([1], fn(x) { x })"));
+        assert!(!Session::is_definition("user_defined"));
+        assert!(!Session::is_definition("fn(x) { x }"));
+    }
+}
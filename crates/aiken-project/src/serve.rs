@@ -0,0 +1,579 @@
+//! A small HTTP server exposing a compiled project over JSON, so that off-chain test harnesses
+//! written in other languages can evaluate functions and work with validators without shelling
+//! out to the CLI for every call.
+//!
+//! Requests are handled one at a time, on the calling thread:
+//!
+//! - `GET /`: the project's name, version and validators;
+//! - `POST /eval`: evaluate an exported function applied to arguments;
+//! - `POST /apply`: apply parameters to a validator;
+//! - `POST /address`: compute a validator's address;
+//! - `POST /decode`: decode a datum (or redeemer) against a validator's schema.
+//!
+//! Arguments, parameters and datums are always Plutus data, as hex-encoded CBOR. Failures are
+//! reported with a 4xx status and an `{ "error": ..., "help"?: ... }` body; a script failing
+//! during evaluation however is a successful request, reported with an `error` field instead of
+//! a `result`.
+
+use crate::{
+    blueprint::{self, parameter::Parameter, validator::Validator, Blueprint},
+    error::Error,
+    telemetry::{Event, EventListener},
+    Project,
+};
+use aiken_lang::ast::Tracing;
+use miette::Diagnostic;
+use pallas_addresses::Network;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::Path,
+};
+use uplc::{
+    ast::{Constant, Name, NamedDeBruijn, Program, Term},
+    machine::{cost_model::ExBudget, runtime::convert_tag_to_constr, value::from_pallas_bigint},
+    PlutusData,
+};
+
+/// Largest request body accepted, in bytes.
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// Serve the given (already compiled) project on the given address, until the process is
+/// interrupted. Validators are taken from the blueprint found at `blueprint_path`.
+pub fn serve<T>(
+    project: &Project<T>,
+    address: SocketAddr,
+    blueprint_path: &Path,
+    tracing: Tracing,
+) -> Result<(), Error>
+where
+    T: EventListener,
+{
+    let blueprint =
+        File::open(blueprint_path).map_err(|_| blueprint::error::Error::InvalidOrMissingFile)?;
+    let blueprint: Blueprint = serde_json::from_reader(BufReader::new(blueprint))?;
+
+    let listener = TcpListener::bind(address)?;
+
+    project.event_listener.handle_event(Event::Serving {
+        address: listener.local_addr()?,
+    });
+
+    // Error messages end up in responses, where terminal colors have no business.
+    owo_colors::set_override(false);
+
+    let server = Server {
+        project,
+        blueprint,
+        tracing,
+    };
+
+    for stream in listener.incoming() {
+        // A client going away mid-request shouldn't bring the server down.
+        let _ = server.handle(stream?);
+    }
+
+    Ok(())
+}
+
+struct Server<'a, T>
+where
+    T: EventListener,
+{
+    project: &'a Project<T>,
+    blueprint: Blueprint,
+    tracing: Tracing,
+}
+
+struct Failure {
+    status: u16,
+    error: String,
+    help: Option<String>,
+}
+
+impl Failure {
+    fn bad_request(error: impl ToString) -> Self {
+        Failure {
+            status: 400,
+            error: error.to_string(),
+            help: None,
+        }
+    }
+
+    fn not_found(error: impl ToString) -> Self {
+        Failure {
+            status: 404,
+            error: error.to_string(),
+            help: None,
+        }
+    }
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        let status = match error {
+            Error::ModuleNotFound { .. }
+            | Error::ExportNotFound { .. }
+            | Error::NoValidatorNotFound { .. } => 404,
+            _ => 400,
+        };
+
+        Failure {
+            status,
+            help: error.help().map(|help| help.to_string()),
+            error: error.to_string(),
+        }
+    }
+}
+
+impl From<blueprint::Error> for Failure {
+    fn from(error: blueprint::Error) -> Self {
+        Error::from(error).into()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EvalRequest {
+    module: String,
+    name: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApplyRequest {
+    module: Option<String>,
+    validator: Option<String>,
+    parameters: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddressRequest {
+    module: Option<String>,
+    validator: Option<String>,
+    #[serde(default)]
+    parameters: Vec<String>,
+    delegated_to: Option<String>,
+    #[serde(default)]
+    mainnet: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodeRequest {
+    module: Option<String>,
+    validator: Option<String>,
+    handler: Option<String>,
+    #[serde(default, rename = "as")]
+    target: Target,
+    cbor: String,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum Target {
+    #[default]
+    Datum,
+    Redeemer,
+}
+
+impl<T> Server<'_, T>
+where
+    T: EventListener,
+{
+    fn handle(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        let (status, body) = match read_request(&mut stream)
+            .and_then(|(method, path, body)| self.route(&method, &path, &body))
+        {
+            Ok(value) => (200, value),
+            Err(Failure {
+                status,
+                error,
+                help,
+            }) => (
+                status,
+                match help {
+                    Some(help) => json!({ "error": error, "help": help }),
+                    None => json!({ "error": error }),
+                },
+            ),
+        };
+
+        let body = serde_json::to_vec(&body).expect("JSON values always serialize");
+
+        write!(
+            stream,
+            "HTTP/1.1 {status} {}\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\r\n",
+            reason(status),
+            body.len(),
+        )?;
+
+        stream.write_all(&body)?;
+
+        stream.flush()
+    }
+
+    fn route(&self, method: &str, path: &str, body: &[u8]) -> Result<Value, Failure> {
+        fn parse<'de, R: Deserialize<'de>>(body: &'de [u8]) -> Result<R, Failure> {
+            serde_json::from_slice(body).map_err(Failure::bad_request)
+        }
+
+        match (method, path) {
+            ("GET", "/") => Ok(self.info()),
+            ("POST", "/eval") => self.eval(parse(body)?),
+            ("POST", "/apply") => self.apply(parse(body)?),
+            ("POST", "/address") => self.address(parse(body)?),
+            ("POST", "/decode") => self.decode(parse(body)?),
+            (_, "/" | "/eval" | "/apply" | "/address" | "/decode") => Err(Failure {
+                status: 405,
+                error: format!("{method} isn't allowed on {path}"),
+                help: None,
+            }),
+            _ => Err(Failure::not_found(format!("nothing to see at {path}"))),
+        }
+    }
+
+    fn info(&self) -> Value {
+        json!({
+            "name": self.project.config.name.to_string(),
+            "version": self.project.config.version,
+            "plutusVersion": self.project.config.plutus,
+            "validators": self
+                .blueprint
+                .validators
+                .iter()
+                .map(|validator| &validator.title)
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn eval(&self, request: EvalRequest) -> Result<Value, Failure> {
+        let export = self
+            .project
+            .export(&request.module, &request.name, self.tracing)?;
+
+        let mut program = export.program.inner().clone();
+
+        for arg in request.args.iter() {
+            program = program.apply_data(plutus_data(arg)?);
+        }
+
+        let program: Program<NamedDeBruijn> = program.into();
+
+        let mut eval_result = program.eval(ExBudget::default());
+
+        let cost = eval_result.cost();
+        let logs = eval_result.logs();
+
+        Ok(match eval_result.result() {
+            Ok(term) => json!({
+                "result": Term::<Name>::try_from(term)
+                    .map_err(Failure::bad_request)?
+                    .to_pretty(),
+                "cpu": cost.cpu,
+                "mem": cost.mem,
+                "logs": logs,
+            }),
+            Err(err) => json!({
+                "error": err.to_string(),
+                "cpu": cost.cpu,
+                "mem": cost.mem,
+                "logs": logs,
+            }),
+        })
+    }
+
+    fn apply(&self, request: ApplyRequest) -> Result<Value, Failure> {
+        let validator = self.applied_validator(
+            request.module.as_deref(),
+            request.validator.as_deref(),
+            &request.parameters,
+        )?;
+
+        Ok(serde_json::to_value(validator).expect("validators always serialize"))
+    }
+
+    fn address(&self, request: AddressRequest) -> Result<Value, Failure> {
+        let delegation_part = crate::delegation_part(request.delegated_to.as_deref())?;
+
+        let validator = self.applied_validator(
+            request.module.as_deref(),
+            request.validator.as_deref(),
+            &request.parameters,
+        )?;
+
+        let n = validator.parameters.len();
+        if n > 0 {
+            return Err(blueprint::Error::ParameterizedValidator { n }.into());
+        }
+
+        let network = if request.mainnet {
+            Network::Mainnet
+        } else {
+            Network::Testnet
+        };
+
+        let address = validator.program.inner().address(
+            network,
+            delegation_part,
+            &self.project.config.plutus.into(),
+        );
+
+        Ok(json!({
+            "address": address.to_bech32().map_err(Failure::bad_request)?,
+            "hash": validator.program.compiled_code_and_hash().1.to_string(),
+        }))
+    }
+
+    fn decode(&self, request: DecodeRequest) -> Result<Value, Failure> {
+        let data = plutus_data(&request.cbor)?;
+
+        let schema_of = |validator: &Validator| -> Option<Parameter> {
+            match request.target {
+                Target::Datum => validator.datum.clone(),
+                Target::Redeemer => validator.redeemer.clone(),
+            }
+        };
+
+        let candidates = self
+            .blueprint
+            .validators
+            .iter()
+            .filter(|validator| {
+                let mut title = validator.title.split('.');
+                let mut matches = |want: Option<&str>| {
+                    let known = title.next();
+                    want.is_none() || want == known
+                };
+
+                matches(request.module.as_deref())
+                    && matches(request.validator.as_deref())
+                    && matches(request.handler.as_deref())
+                    && schema_of(validator).is_some()
+            })
+            .collect::<Vec<_>>();
+
+        let known_validators = || {
+            self.blueprint
+                .validators
+                .iter()
+                .map(|validator| validator.title.clone())
+                .collect()
+        };
+
+        let validator = match candidates[..] {
+            [validator] => validator,
+            [] => {
+                return Err(Error::NoValidatorNotFound {
+                    known_validators: known_validators(),
+                }
+                .into())
+            }
+            _ => {
+                return Err(Error::MoreThanOneValidatorFound {
+                    known_validators: known_validators(),
+                }
+                .into())
+            }
+        };
+
+        schema_of(validator)
+            .expect("candidates have a schema")
+            .validate(&self.blueprint.definitions, &Constant::Data(data.clone()))?;
+
+        Ok(json!({
+            "validator": validator.title,
+            "data": data_to_json(&data),
+        }))
+    }
+
+    fn applied_validator(
+        &self,
+        module: Option<&str>,
+        validator: Option<&str>,
+        parameters: &[String],
+    ) -> Result<Validator, Failure> {
+        let parameters = parameters
+            .iter()
+            .map(|param| plutus_data(param))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let when_too_many =
+            |known_validators| Error::MoreThanOneValidatorFound { known_validators };
+        let when_missing = |known_validators| Error::NoValidatorNotFound { known_validators };
+
+        let validator = self.blueprint.with_validator(
+            module,
+            validator,
+            when_too_many,
+            when_missing,
+            |validator| Ok(validator.clone()),
+        )?;
+
+        parameters.iter().try_fold(validator, |validator, param| {
+            validator
+                .apply(&self.blueprint.definitions, param)
+                .map_err(Failure::from)
+        })
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> Result<(String, String, Vec<u8>), Failure> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 8192];
+
+    let (method, path, offset, content_length) = loop {
+        let n = stream.read(&mut chunk).map_err(Failure::bad_request)?;
+
+        if n == 0 {
+            return Err(Failure::bad_request("incomplete request"));
+        }
+
+        buffer.extend_from_slice(&chunk[..n]);
+
+        let mut headers = [httparse::EMPTY_HEADER; 64];
+        let mut request = httparse::Request::new(&mut headers);
+
+        if let httparse::Status::Complete(offset) =
+            request.parse(&buffer).map_err(Failure::bad_request)?
+        {
+            let content_length = request
+                .headers
+                .iter()
+                .find(|header| header.name.eq_ignore_ascii_case("content-length"))
+                .map(|header| {
+                    std::str::from_utf8(header.value)
+                        .ok()
+                        .and_then(|value| value.trim().parse::<usize>().ok())
+                        .ok_or_else(|| Failure::bad_request("invalid Content-Length"))
+                })
+                .transpose()?
+                .unwrap_or(0);
+
+            break (
+                request.method.unwrap_or_default().to_string(),
+                request.path.unwrap_or_default().to_string(),
+                offset,
+                content_length,
+            );
+        }
+    };
+
+    if content_length > MAX_BODY_SIZE {
+        return Err(Failure {
+            status: 413,
+            error: format!("request bodies are limited to {MAX_BODY_SIZE} bytes"),
+            help: None,
+        });
+    }
+
+    while buffer.len() < offset + content_length {
+        let n = stream.read(&mut chunk).map_err(Failure::bad_request)?;
+
+        if n == 0 {
+            return Err(Failure::bad_request("incomplete request body"));
+        }
+
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+
+    let body = buffer[offset..offset + content_length].to_vec();
+
+    Ok((method, path, body))
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Error",
+    }
+}
+
+fn plutus_data(cbor: &str) -> Result<PlutusData, Failure> {
+    let bytes = hex::decode(cbor).map_err(|e| blueprint::Error::MalformedParameter {
+        hint: format!("Invalid hex-encoded string: {e}"),
+    })?;
+
+    uplc::plutus_data(&bytes).map_err(|e| {
+        blueprint::Error::MalformedParameter {
+            hint: format!("Invalid Plutus data; malformed CBOR encoding: {e}"),
+        }
+        .into()
+    })
+}
+
+/// Plutus data as JSON, following the so-called 'detailed schema' also used by the
+/// cardano-cli. Integers that don't fit in 64 bits are given as strings.
+pub fn data_to_json(data: &PlutusData) -> Value {
+    match data {
+        PlutusData::Constr(constr) => json!({
+            "constructor": convert_tag_to_constr(constr.tag)
+                .or(constr.any_constructor)
+                .unwrap_or_default(),
+            "fields": constr.fields.iter().map(data_to_json).collect::<Vec<_>>(),
+        }),
+        PlutusData::Map(pairs) => json!({
+            "map": pairs
+                .iter()
+                .map(|(k, v)| json!({ "k": data_to_json(k), "v": data_to_json(v) }))
+                .collect::<Vec<_>>(),
+        }),
+        PlutusData::BigInt(n) => {
+            let n = from_pallas_bigint(n);
+            match i64::try_from(&n) {
+                Ok(n) => json!({ "int": n }),
+                Err(..) => json!({ "int": n.to_string() }),
+            }
+        }
+        PlutusData::BoundedBytes(bytes) => json!({ "bytes": hex::encode(bytes.as_slice()) }),
+        PlutusData::Array(items) => json!({
+            "list": items.iter().map(data_to_json).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(cbor: &str) -> Value {
+        data_to_json(&uplc::plutus_data(&hex::decode(cbor).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn data_to_json_detailed_schema() {
+        assert_eq!(decode("182a"), json!({ "int": 42 }));
+
+        assert_eq!(decode("3829"), json!({ "int": -42 }));
+
+        assert_eq!(
+            decode("c249010000000000000000"),
+            json!({ "int": "18446744073709551616" })
+        );
+
+        assert_eq!(
+            decode("d8799f43abcdef80ff"),
+            json!({ "constructor": 0, "fields": [{ "bytes": "abcdef" }, { "list": [] }] })
+        );
+
+        assert_eq!(decode("d87a80"), json!({ "constructor": 1, "fields": [] }));
+
+        assert_eq!(
+            decode("a1010e"),
+            json!({ "map": [{ "k": { "int": 1 }, "v": { "int": 14 } }] })
+        );
+    }
+}
@@ -1,17 +1,23 @@
+use crate::{coverage, verify::VerificationResult};
 use aiken_lang::{
     expr::UntypedExpr,
-    test_framework::{PropertyTestResult, TestResult, UnitTestResult},
+    test_framework::{BenchmarkResult, PropertyTestResult, TestResult, UnitTestResult},
 };
-pub use json::{json_schema, Json};
+pub use json::{fmt_diagnostic_json, json_schema, Json};
+pub use junit::Junit;
+pub use sarif::{fmt_sarif, fmt_sarif_result};
 use std::{
     collections::BTreeMap,
     fmt::Display,
     io::{self, IsTerminal},
+    net::SocketAddr,
     path::PathBuf,
 };
 pub use terminal::Terminal;
 
 mod json;
+mod junit;
+mod sarif;
 mod terminal;
 
 pub trait EventListener {
@@ -38,15 +44,39 @@ pub enum Event {
     DumpingUPLC {
         path: PathBuf,
     },
+    DumpingAIR {
+        path: PathBuf,
+    },
+    Serving {
+        address: SocketAddr,
+    },
+    Listening {
+        address: SocketAddr,
+    },
     GeneratingUPLCFor {
         name: String,
         path: PathBuf,
     },
-    RunningTests,
+    RunningTests {
+        /// Seed of the pseudo-random generator, when there are property tests to run.
+        seed: Option<u32>,
+    },
     FinishedTests {
         seed: u32,
         tests: Vec<TestResult<UntypedExpr, UntypedExpr>>,
     },
+    RunningBenchmarks,
+    FinishedBenchmarks {
+        seed: u32,
+        benchmarks: Vec<BenchmarkResult>,
+    },
+    SavingBaseline {
+        path: PathBuf,
+    },
+    FinishedCoverage {
+        report: coverage::Report,
+        output: PathBuf,
+    },
     WaitingForBuildDirLock,
     ResolvingPackages {
         name: String,
@@ -55,15 +85,24 @@ pub enum Event {
         name: String,
     },
     PackagesDownloaded {
-        start: tokio::time::Instant,
+        start: std::time::Instant,
         count: usize,
         source: DownloadSource,
     },
     ResolvingVersions,
+    RunningHook {
+        hook: String,
+        command: String,
+    },
+    Verifying,
+    FinishedVerification {
+        results: Vec<VerificationResult>,
+    },
 }
 
 pub enum EventTarget {
     Json(Json),
+    Junit(Junit),
     Terminal(Terminal),
 }
 
@@ -72,7 +111,17 @@ impl Default for EventTarget {
         if io::stdout().is_terminal() {
             EventTarget::Terminal(Terminal)
         } else {
-            EventTarget::Json(Json)
+            EventTarget::Json(Json::default())
+        }
+    }
+}
+
+impl EventTarget {
+    /// Take the JSON report produced so far, if any; see [`Json::take_report`].
+    pub fn take_report(&self) -> Option<serde_json::Value> {
+        match self {
+            EventTarget::Json(json) => json.take_report(),
+            EventTarget::Junit(..) | EventTarget::Terminal(..) => None,
         }
     }
 }
@@ -82,6 +131,7 @@ impl EventListener for EventTarget {
         match self {
             EventTarget::Terminal(term) => term.handle_event(event),
             EventTarget::Json(json) => json.handle_event(event),
+            EventTarget::Junit(junit) => junit.handle_event(event),
         }
     }
 }
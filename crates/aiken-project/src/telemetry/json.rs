@@ -1,14 +1,40 @@
 use super::{group_by_module, Event, EventListener};
+use crate::{
+    benchmark::Summary,
+    coverage::{ModuleCoverage, Ratio},
+    error::GetSource,
+};
 use aiken_lang::{
     ast::OnTestFailure,
+    error::Coded,
     expr::UntypedExpr,
     format::Formatter,
-    test_framework::{AssertionStyleOptions, PropertyTestResult, TestResult, UnitTestResult},
+    test_framework::{
+        AssertionStyleOptions, BenchmarkResult, PropertyTestResult, TestResult, UnitTestResult,
+    },
 };
+use miette::{Diagnostic, Severity};
 use serde_json::json;
+use std::cell::RefCell;
+
+/// Reports test results as a JSON document, which is held until the end of the compilation so
+/// that diagnostics can be added to it (see [`Json::take_report`]).
+#[derive(Debug, Default)]
+pub struct Json {
+    report: RefCell<Option<serde_json::Value>>,
+}
 
-#[derive(Debug, Default, Clone, Copy)]
-pub struct Json;
+impl Json {
+    /// Take the report produced so far, if any.
+    pub fn take_report(&self) -> Option<serde_json::Value> {
+        self.report.borrow_mut().take()
+    }
+
+    /// Attach the outcome of a command to the report, e.g. a validator's address.
+    pub fn set_result(&self, result: serde_json::Value) {
+        self.report.borrow_mut().get_or_insert_with(|| json!({}))["result"] = result;
+    }
+}
 
 impl EventListener for Json {
     fn handle_event(&self, event: Event) {
@@ -37,7 +63,26 @@ impl EventListener for Json {
                         })
                     }).collect::<Vec<_>>(),
                 });
-                println!("{}", serde_json::to_string_pretty(&json_output).unwrap());
+                *self.report.borrow_mut() = Some(json_output);
+            }
+            Event::FinishedBenchmarks { seed, benchmarks } => {
+                let json_output = serde_json::json!({
+                    "seed": seed,
+                    "benchmarks": benchmarks.iter().map(fmt_benchmark_json).collect::<Vec<_>>(),
+                });
+                *self.report.borrow_mut() = Some(json_output);
+            }
+            Event::FinishedCoverage { report, output } => {
+                let json_output = serde_json::json!({
+                    "output": output,
+                    "summary": json!({
+                        "lines": fmt_ratio_json(report.lines_ratio()),
+                        "branches": fmt_ratio_json(report.branches_ratio()),
+                        "functions": fmt_ratio_json(report.functions_ratio()),
+                    }),
+                    "modules": report.modules.iter().map(fmt_module_coverage_json).collect::<Vec<_>>(),
+                });
+                *self.report.borrow_mut() = Some(json_output);
             }
             _ => super::Terminal.handle_event(event),
         }
@@ -83,9 +128,13 @@ fn fmt_test_json(result: &TestResult<UntypedExpr, UntypedExpr>) -> serde_json::V
             iterations,
             labels,
             counterexample,
+            exhaustive,
             ..
         }) => {
             test["iterations"] = json!(iterations);
+            if *exhaustive {
+                test["exhaustive"] = json!(true);
+            }
             if !labels.is_empty() {
                 test["labels"] = json!(labels);
             }
@@ -104,6 +153,104 @@ fn fmt_test_json(result: &TestResult<UntypedExpr, UntypedExpr>) -> serde_json::V
     test
 }
 
+fn fmt_benchmark_json(result: &BenchmarkResult) -> serde_json::Value {
+    let mut bench = json!({
+        "module": result.bench.module,
+        "title": result.bench.name,
+        "status": if result.is_success() { "pass" } else { "fail" },
+    });
+
+    if let Some(summary) = Summary::new(result) {
+        bench["mem"] = json!(summary.mem);
+        bench["cpu"] = json!(summary.cpu);
+        bench["measures"] = json!(summary.measures);
+    }
+
+    if let Some(error) = &result.error {
+        bench["error"] = json!(error.to_string());
+    }
+
+    bench
+}
+
+fn fmt_module_coverage_json(module: &ModuleCoverage) -> serde_json::Value {
+    json!({
+        "name": module.name,
+        "path": module.path,
+        "lines": fmt_ratio_json(module.lines_ratio()),
+        "branches": fmt_ratio_json(module.branches_ratio()),
+        "functions": fmt_ratio_json(module.functions_ratio()),
+        "missed_lines": module
+            .lines
+            .iter()
+            .filter(|(_, hits)| **hits == 0)
+            .map(|(line, _)| line)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn fmt_ratio_json(ratio: Ratio) -> serde_json::Value {
+    json!({
+        "hit": ratio.hit,
+        "found": ratio.found,
+    })
+}
+
+/// A diagnostic (i.e. an error or a warning) as JSON. Spans are given both as byte offsets and as
+/// line and column numbers (starting at 1), when the diagnostic points at a source file.
+pub fn fmt_diagnostic_json<D>(diagnostic: &D) -> serde_json::Value
+where
+    D: Diagnostic + GetSource + Coded + ?Sized,
+{
+    let src = diagnostic.src();
+
+    let position = |offset: usize| {
+        let before = src.as_deref()?.get(..offset)?;
+        let line_start = before.rfind('\n').map_or(0, |ix| ix + 1);
+        Some(json!({
+            "line": before.matches('\n').count() + 1,
+            "column": offset - line_start + 1,
+        }))
+    };
+
+    let labels = diagnostic
+        .labels()
+        .map(|labels| {
+            labels
+                .map(|label| {
+                    let start = label.offset();
+                    let end = start + label.len();
+                    json!({
+                        "label": label.label().filter(|label| !label.is_empty()),
+                        "span": { "start": start, "end": end },
+                        "start": position(start),
+                        "end": position(end),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    json!({
+        "severity": match diagnostic.severity() {
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice) => "advice",
+            Some(Severity::Error) | None => "error",
+        },
+        "code": diagnostic.code().map(|code| {
+            code.to_string()
+                .trim()
+                .replace("Warning ", "")
+                .replace("Error ", "")
+        }),
+        "error_code": diagnostic.error_code().map(|code| code.to_string()),
+        "message": diagnostic.to_string(),
+        "help": diagnostic.help().map(|help| help.to_string()),
+        "file": diagnostic.path().map(|path| path.display().to_string()),
+        "labels": labels,
+    })
+}
+
 fn fmt_test_summary_json(tests: &[&TestResult<UntypedExpr, UntypedExpr>]) -> serde_json::Value {
     let total = tests.len();
     let passed = tests.iter().filter(|t| t.is_success()).count();
@@ -161,6 +308,72 @@ pub fn json_schema() -> serde_json::Value {
        "type": "string",
        "enum": [ "pass", "fail" ]
       },
+      "Position": {
+        "type": "object",
+        "required": ["line", "column"],
+        "properties": {
+          "line": { "type": "integer" },
+          "column": { "type": "integer" }
+        }
+      },
+      "Diagnostic": {
+        "type": "object",
+        "required": ["severity", "code", "error_code", "message", "help", "file", "labels"],
+        "properties": {
+          "severity": {
+            "type": "string",
+            "enum": [ "error", "warning", "advice" ]
+          },
+          "code": { "type": ["string", "null"] },
+          "error_code": { "type": ["string", "null"] },
+          "message": { "type": "string" },
+          "help": { "type": ["string", "null"] },
+          "file": { "type": ["string", "null"] },
+          "labels": {
+            "type": "array",
+            "items": {
+              "type": "object",
+              "required": ["label", "span", "start", "end"],
+              "properties": {
+                "label": { "type": ["string", "null"] },
+                "span": {
+                  "type": "object",
+                  "required": ["start", "end"],
+                  "properties": {
+                    "start": { "type": "integer" },
+                    "end": { "type": "integer" }
+                  }
+                },
+                "start": {
+                  "oneOf": [ { "$ref": "#/properties/definitions/Position" }, { "type": "null" } ]
+                },
+                "end": {
+                  "oneOf": [ { "$ref": "#/properties/definitions/Position" }, { "type": "null" } ]
+                }
+              }
+            }
+          }
+        }
+      },
+      "Percentiles": {
+        "type": "object",
+        "required": ["min", "p50", "p90", "p99", "max"],
+        "properties": {
+          "min": { "type": "integer" },
+          "p50": { "type": "integer" },
+          "p90": { "type": "integer" },
+          "p99": { "type": "integer" },
+          "max": { "type": "integer" }
+        }
+      },
+      "Ratio": {
+        "type": "object",
+        "required": ["hit", "found"],
+        "properties": {
+          "hit": { "type": "integer" },
+          "found": { "type": "integer" }
+        }
+      },
       "OnFailure": {
        "type": "string",
        "enum": [
@@ -218,6 +431,7 @@ pub fn json_schema() -> serde_json::Value {
         "status": { "$ref": "#/properties/definitions/Status" },
         "on_failure": { "$ref": "#/properties/definitions/OnFailure" },
         "iterations": { "type": "integer" },
+        "exhaustive": { "type": "boolean" },
         "labels": {
           "type": "object",
           "additionalProperties": { "type": "integer" }
@@ -248,6 +462,10 @@ pub fn json_schema() -> serde_json::Value {
       "type": "object",
       "properties": {
         "command[check]": {
+          "diagnostics": {
+            "type": "array",
+            "items": { "$ref": "#/properties/definitions/Diagnostic" }
+          },
           "seed": { "type": "integer" },
           "summary": { "$ref": "#/properties/definitions/Summary" },
           "modules": {
@@ -267,7 +485,112 @@ pub fn json_schema() -> serde_json::Value {
             }
           }
         },
+        "command[bench]": {
+          "diagnostics": {
+            "type": "array",
+            "items": { "$ref": "#/properties/definitions/Diagnostic" }
+          },
+          "seed": { "type": "integer" },
+          "benchmarks": {
+            "type": "array",
+            "items": {
+              "type": "object",
+              "required": ["module", "title", "status"],
+              "properties": {
+                "module": { "type": "string" },
+                "title": { "type": "string" },
+                "status": { "$ref": "#/properties/definitions/Status" },
+                "error": { "type": "string" },
+                "mem": { "$ref": "#/properties/definitions/Percentiles" },
+                "cpu": { "$ref": "#/properties/definitions/Percentiles" },
+                "measures": {
+                  "type": "array",
+                  "items": {
+                    "type": "object",
+                    "required": ["size", "mem", "cpu"],
+                    "properties": {
+                      "size": { "type": "integer" },
+                      "mem": { "type": "integer" },
+                      "cpu": { "type": "integer" }
+                    }
+                  }
+                }
+              }
+            }
+          }
+        },
+        "command[coverage]": {
+          "diagnostics": {
+            "type": "array",
+            "items": { "$ref": "#/properties/definitions/Diagnostic" }
+          },
+          "output": { "type": "string" },
+          "summary": {
+            "type": "object",
+            "required": ["lines", "branches", "functions"],
+            "properties": {
+              "lines": { "$ref": "#/properties/definitions/Ratio" },
+              "branches": { "$ref": "#/properties/definitions/Ratio" },
+              "functions": { "$ref": "#/properties/definitions/Ratio" }
+            }
+          },
+          "modules": {
+            "type": "array",
+            "items": {
+              "type": "object",
+              "required": ["name", "path", "lines", "branches", "functions", "missed_lines"],
+              "properties": {
+                "name": { "type": "string" },
+                "path": { "type": "string" },
+                "lines": { "$ref": "#/properties/definitions/Ratio" },
+                "branches": { "$ref": "#/properties/definitions/Ratio" },
+                "functions": { "$ref": "#/properties/definitions/Ratio" },
+                "missed_lines": {
+                  "type": "array",
+                  "items": { "type": "integer" }
+                }
+              }
+            }
+          }
+        },
         "definitions": definitions
       }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Warning;
+    use aiken_lang::{ast::Span, tipo};
+
+    #[test]
+    fn diagnostic_json() {
+        let src = "fn foo() {\n  let x = 1\n  True\n}";
+
+        let warning = Warning::from_type_warning(
+            tipo::error::Warning::UnusedVariable {
+                location: Span::create(17, 1),
+                name: "x".to_string(),
+            },
+            "lib/foo.ak".into(),
+            src.to_string(),
+        );
+
+        let diagnostic = fmt_diagnostic_json(&warning);
+
+        assert_eq!(diagnostic["severity"], json!("warning"));
+        assert_eq!(diagnostic["code"], json!("aiken::check::unused::variable"));
+        assert_eq!(diagnostic["error_code"], json!("AK0513"));
+        assert_eq!(diagnostic["file"], json!("lib/foo.ak"));
+        assert_eq!(
+            diagnostic["labels"],
+            json!([{
+                "label": "unused identifier",
+                "span": { "start": 17, "end": 18 },
+                "start": { "line": 2, "column": 7 },
+                "end": { "line": 2, "column": 8 },
+            }])
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use super::{group_by_module, Event, EventListener};
+use aiken_lang::{
+    expr::UntypedExpr,
+    format::Formatter,
+    test_framework::{AssertionStyleOptions, PropertyTestResult, TestResult, UnitTestResult},
+};
+use std::{cell::RefCell, fmt::Write};
+
+/// Reports test results as JUnit XML, as understood by most CI systems (GitLab, Jenkins, GitHub
+/// actions, ...). Modules are test suites, and execution budgets are given as properties of each
+/// test case. The report is held until the end of the compilation (see [`Junit::take_report`]).
+#[derive(Debug, Default)]
+pub struct Junit {
+    report: RefCell<Option<String>>,
+}
+
+impl Junit {
+    /// Take the report produced so far, if any.
+    pub fn take_report(&self) -> Option<String> {
+        self.report.borrow_mut().take()
+    }
+}
+
+impl EventListener for Junit {
+    fn handle_event(&self, event: Event) {
+        match event {
+            Event::FinishedTests { seed, tests } => {
+                *self.report.borrow_mut() = Some(fmt_junit(seed, &tests));
+            }
+            _ => super::Terminal.handle_event(event),
+        }
+    }
+}
+
+fn fmt_junit(seed: u32, tests: &[TestResult<UntypedExpr, UntypedExpr>]) -> String {
+    let mut xml = String::new();
+
+    let failures = tests.iter().filter(|t| !t.is_success()).count();
+
+    let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="aiken" tests="{}" failures="{failures}">"#,
+        tests.len(),
+    );
+
+    for (module, results) in group_by_module(tests) {
+        let failures = results.iter().filter(|t| !t.is_success()).count();
+
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{failures}">"#,
+            escape(&module),
+            results.len(),
+        );
+        let _ = writeln!(xml, "    <properties>");
+        let _ = writeln!(xml, r#"      <property name="seed" value="{seed}"/>"#);
+        let _ = writeln!(xml, "    </properties>");
+
+        for result in results {
+            fmt_test_case(&mut xml, &module, result);
+        }
+
+        let _ = writeln!(xml, "  </testsuite>");
+    }
+
+    let _ = writeln!(xml, "</testsuites>");
+
+    xml
+}
+
+fn fmt_test_case(xml: &mut String, module: &str, result: &TestResult<UntypedExpr, UntypedExpr>) {
+    let mut properties = vec![];
+
+    let (message, details) = match result {
+        TestResult::UnitTestResult(UnitTestResult {
+            spent_budget,
+            assertion,
+            ..
+        }) => {
+            properties.push(("mem".to_string(), spent_budget.mem.to_string()));
+            properties.push(("cpu".to_string(), spent_budget.cpu.to_string()));
+            let details = assertion
+                .as_ref()
+                .map(|assertion| assertion.to_string(false, &AssertionStyleOptions::new(None)))
+                .unwrap_or_default();
+            ("test failed", details)
+        }
+        TestResult::PropertyTestResult(PropertyTestResult {
+            iterations,
+            labels,
+            counterexample,
+            ..
+        }) => {
+            properties.push(("iterations".to_string(), iterations.to_string()));
+            for (label, count) in labels {
+                properties.push((format!("label:{label}"), count.to_string()));
+            }
+            match counterexample {
+                Ok(Some(expr)) => (
+                    "counterexample found",
+                    Formatter::new().expr(expr, false).to_pretty_string(60),
+                ),
+                Ok(None) => ("property failed", String::new()),
+                Err(err) => ("property failed", err.to_string()),
+            }
+        }
+    };
+
+    let _ = writeln!(
+        xml,
+        r#"    <testcase name="{}" classname="{}">"#,
+        escape(result.title()),
+        escape(module),
+    );
+
+    let _ = writeln!(xml, "      <properties>");
+    for (name, value) in properties {
+        let _ = writeln!(
+            xml,
+            r#"        <property name="{}" value="{}"/>"#,
+            escape(&name),
+            escape(&value),
+        );
+    }
+    let _ = writeln!(xml, "      </properties>");
+
+    if !result.is_success() {
+        let _ = writeln!(
+            xml,
+            r#"      <failure message="{message}">{}</failure>"#,
+            escape(&details),
+        );
+    }
+
+    if !result.traces().is_empty() {
+        let _ = writeln!(
+            xml,
+            "      <system-out>{}</system-out>",
+            escape(&result.traces().join("\n")),
+        );
+    }
+
+    let _ = writeln!(xml, "    </testcase>");
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!(
+            escape(r#"<foo bar="baz" & 'qux'>"#),
+            "&lt;foo bar=&quot;baz&quot; &amp; &apos;qux&apos;&gt;"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use crate::error::GetSource;
+use aiken_lang::{
+    error::{Coded, ErrorCode, ExtraData},
+    version::compiler_version,
+};
+use miette::{Diagnostic, Severity};
+use serde_json::json;
+use std::{
+    collections::BTreeSet,
+    path::{Component, Path},
+};
+
+/// Gather results into a SARIF 2.1.0 log (e.g. for GitHub code scanning), along with the rules
+/// they refer to. Results are obtained from diagnostics with [`fmt_sarif_result`].
+pub fn fmt_sarif(results: Vec<serde_json::Value>) -> serde_json::Value {
+    let rules = results
+        .iter()
+        .filter_map(|result| result["ruleId"].as_str())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|id| match id.parse::<ErrorCode>() {
+            Ok(code) => json!({
+                "id": id,
+                "shortDescription": { "text": code.summary() },
+                "fullDescription": { "text": code.explanation() },
+                "help": { "text": code.explanation(), "markdown": code.explanation() },
+            }),
+            Err(..) => json!({ "id": id }),
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "aiken",
+                    "informationUri": "https://aiken-lang.org",
+                    "version": compiler_version(false),
+                    "rules": rules,
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }]
+    })
+}
+
+/// A diagnostic as a SARIF result. Its rule is the diagnostic's stable code when it has one, and
+/// its location is that of its first label, relative to the project's root. Other labels are
+/// given as related locations, and diagnostics with an obvious fix come with it.
+pub fn fmt_sarif_result<D>(diagnostic: &D, root: &Path) -> serde_json::Value
+where
+    D: Diagnostic + GetSource + Coded + ExtraData + ?Sized,
+{
+    let src = diagnostic.src();
+
+    let uri = diagnostic.path().map(|path| relative_uri(&path, root));
+
+    let region = |start: usize, end: usize| {
+        let (start_line, start_column) = position(src.as_deref()?, start)?;
+        let (end_line, end_column) = position(src.as_deref()?, end)?;
+        Some(json!({
+            "startLine": start_line,
+            "startColumn": start_column,
+            "endLine": end_line,
+            "endColumn": end_column,
+        }))
+    };
+
+    let location = |start: usize, end: usize| {
+        let mut physical_location = json!({
+            "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+        });
+
+        if let Some(region) = region(start, end) {
+            physical_location["region"] = region;
+        }
+
+        json!({ "physicalLocation": physical_location })
+    };
+
+    let labels = diagnostic
+        .labels()
+        .map(|labels| labels.collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let code = diagnostic.error_code();
+
+    let mut message = diagnostic.to_string();
+    if let Some(help) = diagnostic.help() {
+        message = format!("{message}\n\n{help}");
+    }
+
+    let mut result = json!({
+        "ruleId": code
+            .map(|code| code.to_string())
+            .or_else(|| diagnostic.code().map(|code| code.to_string().trim().to_string()))
+            .unwrap_or_else(|| "aiken".to_string()),
+        "level": match diagnostic.severity() {
+            Some(Severity::Warning) => "warning",
+            Some(Severity::Advice) => "note",
+            Some(Severity::Error) | None => "error",
+        },
+        "message": { "text": message },
+        "locations": [],
+    });
+
+    if uri.is_none() {
+        return result;
+    }
+
+    match labels.split_first() {
+        Some((primary, others)) => {
+            let (start, end) = (primary.offset(), primary.offset() + primary.len());
+
+            result["locations"] = json!([location(start, end)]);
+
+            if !others.is_empty() {
+                result["relatedLocations"] = others
+                    .iter()
+                    .enumerate()
+                    .map(|(id, label)| {
+                        let mut related = location(label.offset(), label.offset() + label.len());
+                        related["id"] = json!(id + 1);
+                        if let Some(text) = label.label().filter(|text| !text.is_empty()) {
+                            related["message"] = json!({ "text": text });
+                        }
+                        related
+                    })
+                    .collect();
+            }
+
+            if let Some((description, replacement)) =
+                code.and_then(|code| fix(code, diagnostic.extra_data()))
+            {
+                if let Some(region) = region(start, end) {
+                    result["fixes"] = json!([{
+                        "description": { "text": description },
+                        "artifactChanges": [{
+                            "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+                            "replacements": [{
+                                "deletedRegion": region,
+                                "insertedContent": { "text": replacement },
+                            }],
+                        }],
+                    }]);
+                }
+            }
+        }
+        None => {
+            result["locations"] = json!([{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+                }
+            }]);
+        }
+    }
+
+    result
+}
+
+/// Fixes replacing the primary span of a diagnostic, mirroring the language server's quickfixes.
+fn fix(code: ErrorCode, extra_data: Option<String>) -> Option<(&'static str, String)> {
+    match code.to_string().as_str() {
+        // Single constructor expect
+        "AK0504" => Some(("Use 'let' instead of 'expect'", "let".to_string())),
+        // UTF-8 byte array is a valid hex string
+        "AK0517" => extra_data.map(|value| ("Prefix with #", format!("#\"{value}\""))),
+        _ => None,
+    }
+}
+
+/// One-based line and column (in characters) of a byte offset.
+fn position(src: &str, offset: usize) -> Option<(usize, usize)> {
+    let before = src.get(..offset)?;
+    let line_start = before.rfind('\n').map_or(0, |ix| ix + 1);
+    Some((
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    ))
+}
+
+/// A path as a URI relative to the project's root, with forward slashes.
+fn relative_uri(path: &Path, root: &Path) -> String {
+    let path = path.strip_prefix(root).unwrap_or(path);
+
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => Some(segment.to_string_lossy()),
+            Component::ParentDir => Some("..".into()),
+            Component::CurDir | Component::RootDir | Component::Prefix(..) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Warning;
+    use aiken_lang::{ast::Span, tipo};
+    use std::path::PathBuf;
+
+    #[test]
+    fn sarif_result_with_fix() {
+        let src = "fn foo() {\n  \"0123\"\n}\n";
+        let start = src.find('"').unwrap();
+
+        let warning = Warning::Type {
+            path: PathBuf::from("./lib/foo.ak"),
+            src: src.to_string(),
+            named: miette::NamedSource::new("lib/foo.ak", src.to_string()),
+            warning: tipo::error::Warning::Utf8ByteArrayIsValidHexString {
+                location: Span::create(start, 6),
+                value: "0123".to_string(),
+            },
+        };
+
+        let result = fmt_sarif_result(&warning, Path::new("."));
+
+        assert_eq!(result["ruleId"], "AK0517");
+        assert_eq!(result["level"], "warning");
+
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "lib/foo.ak");
+        assert_eq!(
+            location["region"],
+            json!({ "startLine": 2, "startColumn": 3, "endLine": 2, "endColumn": 9 })
+        );
+
+        let replacement = &result["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["insertedContent"]["text"], "#\"0123\"");
+        assert_eq!(replacement["deletedRegion"], location["region"]);
+
+        let sarif = fmt_sarif(vec![result]);
+        assert_eq!(
+            sarif["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "AK0517"
+        );
+    }
+
+    #[test]
+    fn relative_uris() {
+        assert_eq!(
+            relative_uri(Path::new("./validators/foo.ak"), Path::new(".")),
+            "validators/foo.ak"
+        );
+        assert_eq!(
+            relative_uri(
+                Path::new("/tmp/project/lib/bar.ak"),
+                Path::new("/tmp/project")
+            ),
+            "lib/bar.ak"
+        );
+    }
+}
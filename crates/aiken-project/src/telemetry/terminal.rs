@@ -1,11 +1,20 @@
 use super::{find_max_execution_units, group_by_module, DownloadSource, Event, EventListener};
-use crate::pretty;
+use crate::{
+    benchmark::{Percentiles, Summary},
+    coverage::{ModuleCoverage, Ratio},
+    pretty,
+    verify::{Outcome, PathResult, Reachability, VerificationResult},
+};
 use aiken_lang::{
     ast::OnTestFailure,
     expr::UntypedExpr,
     format::Formatter,
-    test_framework::{AssertionStyleOptions, PropertyTestResult, TestResult, UnitTestResult},
+    smt::Point,
+    test_framework::{
+        AssertionStyleOptions, BenchmarkResult, PropertyTestResult, TestResult, UnitTestResult,
+    },
 };
+use itertools::Itertools;
 use owo_colors::{OwoColorize, Stream::Stderr};
 use uplc::machine::cost_model::ExBudget;
 
@@ -49,6 +58,16 @@ impl EventListener for Terminal {
                         .if_supports_color(Stderr, |s| s.bright_blue())
                 );
             }
+            Event::RunningHook { hook, command } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "      Running"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    format!("{hook} hook").if_supports_color(Stderr, |s| s.bold()),
+                    command.if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
             Event::WaitingForBuildDirLock => {
                 eprintln!(
                     "{}",
@@ -68,6 +87,36 @@ impl EventListener for Terminal {
                         .if_supports_color(Stderr, |s| s.bright_blue())
                 );
             }
+            Event::DumpingAIR { path } => {
+                eprintln!(
+                    "{} {} ({})",
+                    "    Exporting"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "AIR".if_supports_color(Stderr, |s| s.bold()),
+                    path.display()
+                        .if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
+            Event::Serving { address } => {
+                eprintln!(
+                    "{} {} on http://{}",
+                    "      Serving"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "project".if_supports_color(Stderr, |s| s.bold()),
+                    address.if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
+            Event::Listening { address } => {
+                eprintln!(
+                    "{} for requests on {}",
+                    "    Listening"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    address.if_supports_color(Stderr, |s| s.bright_blue())
+                );
+            }
             Event::GeneratingBlueprint { path } => {
                 eprintln!(
                     "{} {} ({})",
@@ -107,13 +156,21 @@ impl EventListener for Terminal {
                     name.if_supports_color(Stderr, |s| s.bright_blue()),
                 );
             }
-            Event::RunningTests => {
+            Event::RunningTests { seed } => {
                 eprintln!(
-                    "{} {}",
+                    "{} {}{}",
                     "      Testing"
                         .if_supports_color(Stderr, |s| s.bold())
                         .if_supports_color(Stderr, |s| s.purple()),
-                    "...".if_supports_color(Stderr, |s| s.bold())
+                    "...".if_supports_color(Stderr, |s| s.bold()),
+                    match seed {
+                        Some(seed) => format!(
+                            " (with {opt}={seed})",
+                            opt = "--seed".if_supports_color(Stderr, |s| s.bold()),
+                            seed = format!("{seed}").if_supports_color(Stderr, |s| s.bold())
+                        ),
+                        None => String::new(),
+                    }
                 );
             }
             Event::FinishedTests { seed, tests } => {
@@ -164,6 +221,106 @@ impl EventListener for Terminal {
                     println!();
                 }
             }
+            Event::RunningBenchmarks => {
+                eprintln!(
+                    "{} {}",
+                    " Benchmarking"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "...".if_supports_color(Stderr, |s| s.bold())
+                );
+            }
+            Event::FinishedBenchmarks { seed, benchmarks } => {
+                for (module, results) in &benchmarks.iter().group_by(|r| &r.bench.module) {
+                    let results = results.collect::<Vec<_>>();
+
+                    let title = module
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.blue())
+                        .to_string();
+
+                    let lines = results
+                        .iter()
+                        .map(|result| fmt_benchmark(result))
+                        .collect::<Vec<String>>()
+                        .join("\n");
+
+                    let failed = results.iter().filter(|r| !r.is_success()).count();
+
+                    let summary = format!(
+                        "with {opt}={seed} → {} benchmarks | {} ok | {} failed",
+                        results.len(),
+                        results.len() - failed,
+                        failed,
+                        opt = "--seed".if_supports_color(Stderr, |s| s.bold()),
+                        seed = format!("{seed}").if_supports_color(Stderr, |s| s.bold()),
+                    );
+
+                    println!(
+                        "\n{}\n",
+                        pretty::indent(
+                            &pretty::open_box(&title, &lines, &summary, |border| border
+                                .if_supports_color(Stderr, |s| s.bright_black())
+                                .to_string()),
+                            4
+                        )
+                    );
+                }
+            }
+            Event::SavingBaseline { path } => {
+                eprintln!(
+                    "{} {}",
+                    "       Saving"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    path.display().if_supports_color(Stderr, |s| s.bold())
+                );
+            }
+            Event::FinishedCoverage { report, output } => {
+                let width = report
+                    .modules
+                    .iter()
+                    .map(|module| module.name.len())
+                    .max()
+                    .unwrap_or_default();
+
+                let title = "coverage"
+                    .if_supports_color(Stderr, |s| s.bold())
+                    .if_supports_color(Stderr, |s| s.blue())
+                    .to_string();
+
+                let lines = report
+                    .modules
+                    .iter()
+                    .map(|module| fmt_module_coverage(module, width))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+
+                let summary = format!(
+                    "lines {} | branches {} | functions {}",
+                    fmt_ratio(report.lines_ratio()),
+                    fmt_ratio(report.branches_ratio()),
+                    fmt_ratio(report.functions_ratio()),
+                );
+
+                println!(
+                    "\n{}\n",
+                    pretty::indent(
+                        &pretty::open_box(&title, &lines, &summary, |border| border
+                            .if_supports_color(Stderr, |s| s.bright_black())
+                            .to_string()),
+                        4
+                    )
+                );
+
+                eprintln!(
+                    "{} {}",
+                    "      Writing"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    output.display().if_supports_color(Stderr, |s| s.bold())
+                );
+            }
             Event::ResolvingPackages { name } => {
                 eprintln!(
                     "{} {}",
@@ -215,7 +372,289 @@ impl EventListener for Terminal {
                     "dependencies".if_supports_color(Stderr, |s| s.bold())
                 )
             }
+            Event::Verifying => {
+                eprintln!(
+                    "{} {}",
+                    "    Verifying"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    "...".if_supports_color(Stderr, |s| s.bold())
+                );
+            }
+            Event::FinishedVerification { results } => {
+                for (module, results) in &results.iter().group_by(|result| &result.module) {
+                    let results = results.collect::<Vec<_>>();
+
+                    let title = module
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.blue())
+                        .to_string();
+
+                    let lines = results
+                        .iter()
+                        .map(|result| fmt_verification(result))
+                        .collect::<Vec<String>>()
+                        .join("\n");
+
+                    let count = |f: fn(&Outcome) -> bool| {
+                        results.iter().filter(|result| f(&result.outcome)).count()
+                    };
+
+                    let summary = format!(
+                        "{} proven | {} refuted | {} unknown | {} skipped",
+                        count(|o| matches!(o, Outcome::Holds)),
+                        count(|o| matches!(o, Outcome::Fails(..))),
+                        count(|o| matches!(o, Outcome::Unknown(..))),
+                        count(|o| matches!(o, Outcome::Unsupported(..))),
+                    );
+
+                    println!(
+                        "\n{}\n",
+                        pretty::indent(
+                            &pretty::open_box(&title, &lines, &summary, |border| border
+                                .if_supports_color(Stderr, |s| s.bright_black())
+                                .to_string()),
+                            4
+                        )
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn fmt_benchmark(result: &BenchmarkResult) -> String {
+    let name = result
+        .bench
+        .name
+        .if_supports_color(Stderr, |s| s.bright_blue());
+
+    match (&result.error, Summary::new(result)) {
+        (None, Some(summary)) => {
+            let width = [summary.mem.max, summary.cpu.max]
+                .iter()
+                .map(|n| n.to_string().len())
+                .max()
+                .unwrap_or_default();
+
+            format!(
+                "{} {name} {}\n      {}\n      {}",
+                "BENCH"
+                    .if_supports_color(Stderr, |s| s.bold())
+                    .if_supports_color(Stderr, |s| s.green()),
+                format!(
+                    "(sizes 0..{})",
+                    result
+                        .measures
+                        .last()
+                        .map(|(size, _)| *size)
+                        .unwrap_or_default()
+                )
+                .if_supports_color(Stderr, |s| s.bright_black()),
+                fmt_percentiles("mem", &summary.mem, width),
+                fmt_percentiles("cpu", &summary.cpu, width),
+            )
         }
+        (error, _) => format!(
+            "{} {name} {}",
+            "FAIL "
+                .if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.red()),
+            format!(
+                "({})",
+                error
+                    .as_ref()
+                    .map(|e| e.to_string())
+                    .unwrap_or_else(|| "no measures".to_string())
+            )
+            .if_supports_color(Stderr, |s| s.bright_black()),
+        ),
+    }
+}
+
+fn fmt_percentiles(unit: &str, percentiles: &Percentiles, width: usize) -> String {
+    [
+        ("min", percentiles.min),
+        ("p50", percentiles.p50),
+        ("p90", percentiles.p90),
+        ("p99", percentiles.p99),
+        ("max", percentiles.max),
+    ]
+    .iter()
+    .fold(
+        unit.if_supports_color(Stderr, |s| s.bold()).to_string(),
+        |line, (label, value)| {
+            format!(
+                "{line} {} {:>width$}",
+                label.if_supports_color(Stderr, |s| s.bright_black()),
+                value,
+            )
+        },
+    )
+}
+
+fn fmt_module_coverage(module: &ModuleCoverage, width: usize) -> String {
+    let lines = module.lines_ratio();
+
+    let name = format!("{:<width$}", module.name);
+
+    let line = format!(
+        "{} lines {:>6} branches {:>6} functions {:>6}",
+        if lines.hit == lines.found {
+            name.if_supports_color(Stderr, |s| s.green()).to_string()
+        } else {
+            name.if_supports_color(Stderr, |s| s.yellow()).to_string()
+        },
+        fmt_percent(lines),
+        fmt_percent(module.branches_ratio()),
+        fmt_percent(module.functions_ratio()),
+    );
+
+    // Consecutive missed lines are shown as ranges.
+    let missed = module
+        .lines
+        .iter()
+        .filter(|(_, hits)| **hits == 0)
+        .map(|(line, _)| *line)
+        .fold(Vec::<(usize, usize)>::new(), |mut ranges, line| {
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == line => *end = line,
+                _ => ranges.push((line, line)),
+            }
+            ranges
+        });
+
+    if missed.is_empty() {
+        return line;
+    }
+
+    format!(
+        "{line}\n  {}",
+        format!(
+            "missed lines {}",
+            missed
+                .iter()
+                .map(|(start, end)| if start == end {
+                    start.to_string()
+                } else {
+                    format!("{start}-{end}")
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .if_supports_color(Stderr, |s| s.bright_black())
+    )
+}
+
+fn fmt_percent(ratio: Ratio) -> String {
+    format!("{:.1}%", ratio.percent())
+}
+
+fn fmt_ratio(ratio: Ratio) -> String {
+    format!("{} ({}/{})", fmt_percent(ratio), ratio.hit, ratio.found)
+}
+
+fn fmt_verification(result: &VerificationResult) -> String {
+    let name = result.name.if_supports_color(Stderr, |s| s.bright_blue());
+
+    let outcome = match &result.outcome {
+        Outcome::Holds => format!(
+            "{} {name}{}",
+            "PROVEN "
+                .if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.green()),
+            if result.handler { " (can succeed)" } else { "" }
+                .if_supports_color(Stderr, |s| s.bright_black()),
+        ),
+        Outcome::Fails(counterexample) => format!(
+            "{} {name}{}",
+            "REFUTED"
+                .if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.red()),
+            if result.handler {
+                " (never succeeds)".to_string()
+            } else {
+                format!(
+                    " ({})",
+                    counterexample
+                        .iter()
+                        .map(|(name, value)| format!("{name} = {value}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+            .if_supports_color(Stderr, |s| s.bright_black()),
+        ),
+        Outcome::Unknown(reason) => format!(
+            "{} {name} {}",
+            "UNKNOWN"
+                .if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.yellow()),
+            format!("({reason})").if_supports_color(Stderr, |s| s.bright_black()),
+        ),
+        Outcome::Unsupported(reason) => format!(
+            "{} {name} {}",
+            "SKIPPED"
+                .if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.bright_black()),
+            format!("(unsupported: {reason})").if_supports_color(Stderr, |s| s.bright_black()),
+        ),
+    };
+
+    let count = result.paths.len();
+
+    result
+        .paths
+        .iter()
+        .enumerate()
+        .fold(outcome, |outcome, (ix, path)| {
+            format!(
+                "{outcome}\n        {} {}",
+                if ix + 1 == count { "└" } else { "├" }
+                    .if_supports_color(Stderr, |s| s.bright_black()),
+                fmt_path(path)
+            )
+        })
+}
+
+fn fmt_path(path: &PathResult) -> String {
+    let point = format!(
+        "{:<7} line {:<4}",
+        match path.point {
+            Point::Branch => "branch",
+            Point::Failure => "fail",
+            Point::Success => "success",
+        },
+        path.line
+    );
+
+    match &path.reachability {
+        Reachability::Reachable(witness) => format!(
+            "{point} {}",
+            if witness.is_empty() {
+                "reachable".to_string()
+            } else {
+                format!(
+                    "reachable with {}",
+                    witness
+                        .iter()
+                        .map(|(name, value)| format!("{name} = {value}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+            .if_supports_color(Stderr, |s| s.bright_black()),
+        ),
+        Reachability::Unreachable => format!(
+            "{point} {}",
+            "unreachable"
+                .if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.yellow()),
+        ),
+        Reachability::Unknown(reason) => format!(
+            "{point} {}",
+            format!("unknown ({reason})").if_supports_color(Stderr, |s| s.bright_black()),
+        ),
     }
 }
 
@@ -258,6 +697,17 @@ fn fmt_test(
                     .to_string()),
             );
         }
+        TestResult::PropertyTestResult(PropertyTestResult {
+            iterations,
+            exhaustive: true,
+            ..
+        }) => {
+            test = format!(
+                "{test} [all {} value{}]",
+                pretty::pad_left(iterations.to_string(), max_iter, " "),
+                if *iterations > 1 { "s" } else { "" }
+            );
+        }
         TestResult::PropertyTestResult(PropertyTestResult { iterations, .. }) => {
             test = format!(
                 "{test} [after {} test{}]",
@@ -0,0 +1,121 @@
+use regex::Regex;
+
+/// A pattern selecting tests, as given to '--match-tests' or '--skip': a module, names of tests
+/// (within braces, after a dot) or both. For example: 'aiken/list', 'aiken/list.{map,filter}' or
+/// 'map'.
+///
+/// Modules and names match by substring, unless they contain globs ('*' or '?'), in which case
+/// they must match as a whole. So 'marketplace/*' only selects modules under 'marketplace'.
+#[derive(Debug)]
+pub struct TestPattern {
+    module: Option<Matcher>,
+    names: Option<Vec<Matcher>>,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Substring(String),
+    Glob(Regex),
+}
+
+impl TestPattern {
+    pub fn parse(pattern: &str) -> Self {
+        let mut split_dot = pattern.split('.');
+
+        let module = if pattern.contains('.') || pattern.contains('/') {
+            split_dot.next().filter(|module| !module.is_empty())
+        } else {
+            None
+        };
+
+        let names = split_dot.next().map(|names| {
+            names
+                .replace(&['{', '}'][..], "")
+                .split(',')
+                .map(Matcher::new)
+                .collect()
+        });
+
+        TestPattern {
+            module: module.map(Matcher::new),
+            names,
+        }
+    }
+
+    /// Whether a test matches the pattern. With `exact_match`, names without globs must be equal
+    /// to the test's name, instead of being part of it.
+    pub fn matches(&self, module: &str, name: &str, exact_match: bool) -> bool {
+        let matched_module = match &self.module {
+            None => true,
+            Some(matcher) => matcher.matches(module, false),
+        };
+
+        let matched_name = match &self.names {
+            None => true,
+            Some(names) => names
+                .iter()
+                .any(|matcher| matcher.matches(name, exact_match)),
+        };
+
+        matched_module && matched_name
+    }
+}
+
+impl Matcher {
+    fn new(pattern: &str) -> Self {
+        if pattern.contains(['*', '?']) {
+            let regex = regex::escape(pattern)
+                .replace(r"\*", ".*")
+                .replace(r"\?", ".");
+
+            Matcher::Glob(Regex::new(&format!("^{regex}$")).expect("escaped glob is a valid regex"))
+        } else {
+            Matcher::Substring(pattern.to_string())
+        }
+    }
+
+    fn matches(&self, text: &str, exact_match: bool) -> bool {
+        match self {
+            Matcher::Substring(pattern) if exact_match => text == pattern,
+            Matcher::Substring(pattern) => text.contains(pattern.as_str()),
+            Matcher::Glob(regex) => regex.is_match(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_substrings() {
+        let pattern = TestPattern::parse("aiken/list.{map,filter}");
+
+        assert!(pattern.matches("aiken/list", "map_1", false));
+        assert!(pattern.matches("aiken/list/extra", "filter", false));
+        assert!(!pattern.matches("aiken/list", "map_1", true));
+        assert!(pattern.matches("aiken/list", "map", true));
+        assert!(!pattern.matches("aiken/dict", "map", false));
+
+        let pattern = TestPattern::parse("map");
+
+        assert!(pattern.matches("aiken/dict", "map_2", false));
+        assert!(!pattern.matches("aiken/dict", "foldr", false));
+    }
+
+    #[test]
+    fn match_globs() {
+        let pattern = TestPattern::parse("marketplace/*");
+
+        assert!(pattern.matches("marketplace/buy", "foo", false));
+        assert!(pattern.matches("marketplace/listing/cancel", "foo", false));
+        assert!(!pattern.matches("my/marketplace/buy", "foo", false));
+        assert!(!pattern.matches("marketplace", "foo", false));
+
+        let pattern = TestPattern::parse("marketplace/b?y.{prop_*}");
+
+        assert!(pattern.matches("marketplace/buy", "prop_price", true));
+        assert!(!pattern.matches("marketplace/buy", "price_prop", false));
+        assert!(!pattern.matches("marketplace/bought", "prop_price", false));
+    }
+}
@@ -6,7 +6,7 @@ mod test {
     };
     use aiken_lang::{
         ast::{DataTypeKey, Definition, ModuleKind, TraceLevel, Tracing, TypedDataType},
-        builtins,
+        builtins, coverage,
         expr::UntypedExpr,
         format::Formatter,
         gen_uplc::CodeGenerator,
@@ -27,6 +27,13 @@ mod test {
     const TEST_KIND: ModuleKind = ModuleKind::Lib;
 
     pub fn test_from_source(src: &str) -> (Test, IndexMap<DataTypeKey, TypedDataType>) {
+        test_from_source_with(src, false)
+    }
+
+    fn test_from_source_with(
+        src: &str,
+        coverage: bool,
+    ) -> (Test, IndexMap<DataTypeKey, TypedDataType>) {
         let id_gen = IdGenerator::new();
 
         let module_name = "";
@@ -95,6 +102,10 @@ mod test {
             Tracing::All(TraceLevel::Verbose),
         );
 
+        if coverage {
+            generator = generator.with_coverage();
+        }
+
         (
             Test::from_function_definition(
                 &mut generator,
@@ -474,6 +485,105 @@ mod test {
         assert_eq!(reify(counterexample.value), "[0, 0, 0]");
     }
 
+    #[test]
+    fn test_prop_exhaustive_enum() {
+        let (prop, _) = property(indoc! { r#"
+            type Temperature {
+                Hot
+                Cold
+            }
+
+            fn temperature() -> Fuzzer<Temperature> {
+                bool() |> map(fn(is_cold) {
+                    if is_cold { Cold } else { Hot }
+                })
+            }
+
+            test foo(t via temperature()) {
+                t == Hot || t == Cold
+            }
+        "#});
+
+        match prop.run_exhaustive::<()>(
+            42,
+            PropertyTest::DEFAULT_MAX_SUCCESS,
+            &PlutusVersion::default(),
+        ) {
+            TestResult::UnitTestResult(..) => unreachable!("property returned unit-test result ?!"),
+            TestResult::PropertyTestResult(result) => {
+                assert!(result.exhaustive);
+                assert_eq!(result.iterations, 2);
+                assert!(matches!(result.counterexample, Ok(None)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_prop_exhaustive_counterexample() {
+        let (prop, reify) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 42
+            }
+        "#});
+
+        match prop.run_exhaustive::<()>(
+            42,
+            PropertyTest::DEFAULT_MAX_SUCCESS,
+            &PlutusVersion::default(),
+        ) {
+            TestResult::UnitTestResult(..) => unreachable!("property returned unit-test result ?!"),
+            TestResult::PropertyTestResult(result) => {
+                assert!(result.exhaustive);
+                assert_eq!(result.iterations, 43);
+                assert_eq!(reify(result.counterexample.unwrap().unwrap()), "42");
+            }
+        }
+    }
+
+    #[test]
+    fn test_prop_exhaustive_fallback() {
+        let (prop, _) = property(indoc! { r#"
+            fn pair(fuzz_a: Fuzzer<a>, fuzz_b: Fuzzer<b>) -> Fuzzer<(a, b)> {
+                map2(fuzz_a, fuzz_b, fn(a, b) { (a, b) })
+            }
+
+            test foo(t: (Bool, Bool) via pair(bool(), bool())) {
+                t.1st || t.2nd
+            }
+        "#});
+
+        assert_eq!(prop.enumerate(1000).unwrap(), None);
+
+        let (prop, _) = property(indoc! { r#"
+            fn list(elem: Fuzzer<a>) -> Fuzzer<List<a>> {
+              bool()
+                |> and_then(fn(continue) {
+                    if continue {
+                      map2(elem, list(elem), fn(head, tail) { [head, ..tail] })
+                    } else {
+                      constant([])
+                    }
+                })
+            }
+
+            test foo(es: List<Int> via list(int())) {
+                es == es
+            }
+        "#});
+
+        match prop.run_exhaustive::<()>(
+            42,
+            PropertyTest::DEFAULT_MAX_SUCCESS,
+            &PlutusVersion::default(),
+        ) {
+            TestResult::UnitTestResult(..) => unreachable!("property returned unit-test result ?!"),
+            TestResult::PropertyTestResult(result) => {
+                assert!(!result.exhaustive);
+                assert_eq!(result.iterations, PropertyTest::DEFAULT_MAX_SUCCESS);
+            }
+        }
+    }
+
     #[test]
     fn test_prop_opaque_dict() {
         let (prop, reify) = property(indoc! { r#"
@@ -546,4 +656,45 @@ mod test {
             "Dict([(#\"2cd15ed0\", Dict([]))])"
         );
     }
+
+    #[test]
+    fn test_coverage_markers() {
+        let src = indoc! { r#"
+            fn sign(n: Int) -> Int {
+              if n > 0 {
+                1
+              } else if n < 0 {
+                -1
+              } else {
+                0
+              }
+            }
+
+            test foo() {
+              sign(-14) == -1
+            }
+        "#};
+
+        let (test, _) = test_from_source_with(src, true);
+
+        let Test::UnitTest(test) = test else {
+            unreachable!("unit test expected");
+        };
+
+        let TestResult::UnitTestResult::<_, ()>(result) = test.run(&PlutusVersion::default())
+        else {
+            unreachable!("unit test result expected");
+        };
+
+        assert!(result.success);
+
+        let covered = result
+            .traces
+            .iter()
+            .filter_map(|trace| coverage::parse_marker(trace))
+            .filter_map(|(_, location)| src[location.start..location.end].lines().next())
+            .collect::<Vec<_>>();
+
+        assert_eq!(covered, vec!["if n > 0 {", "-1"]);
+    }
 }
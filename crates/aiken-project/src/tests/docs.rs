@@ -0,0 +1,73 @@
+use crate::{telemetry::EventListener, Project};
+use std::fs;
+
+struct NoListener;
+
+impl EventListener for NoListener {}
+
+#[test]
+fn docs_include_unimported_dependency_modules() {
+    let root = std::env::temp_dir().join("aiken-docs-dependencies");
+    let _ = fs::remove_dir_all(&root);
+
+    let project = root.join("project");
+    let dependency = root.join("dependency");
+
+    fs::create_dir_all(project.join("lib")).unwrap();
+    fs::create_dir_all(dependency.join("lib/acme")).unwrap();
+
+    fs::write(
+        project.join("aiken.toml"),
+        r#"
+        name = "test/project"
+        version = "0.0.0"
+
+        [[dependencies]]
+        name = "acme/lib"
+        version = "main"
+        source = "github"
+
+        [patch."acme/lib"]
+        path = "../dependency"
+        "#,
+    )
+    .unwrap();
+
+    fs::write(project.join("lib/main.ak"), "pub fn main() { 42 }\n").unwrap();
+
+    fs::write(
+        dependency.join("aiken.toml"),
+        r#"
+        name = "acme/lib"
+        version = "0.0.0"
+        "#,
+    )
+    .unwrap();
+
+    fs::write(
+        dependency.join("lib/acme/unused.ak"),
+        "/// Nobody imports me.\npub fn unused() { 14 }\n",
+    )
+    .unwrap();
+
+    let docs = root.join("docs");
+
+    Project::new(project.clone(), NoListener)
+        .unwrap()
+        .docs(Some(docs.clone()), true)
+        .unwrap();
+
+    assert!(docs.join("acme/unused.html").exists());
+
+    fs::remove_dir_all(&docs).unwrap();
+
+    Project::new(project, NoListener)
+        .unwrap()
+        .docs(Some(docs.clone()), false)
+        .unwrap();
+
+    assert!(docs.join("main.html").exists());
+    assert!(!docs.join("acme/unused.html").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -6449,3 +6449,87 @@ fn dangling_trace_expect_in_trace() {
 
     assert_uplc(src, program, false, true)
 }
+
+#[test]
+fn export_air() {
+    use aiken_lang::gen_uplc::export::{AirExport, AIR_FORMAT_VERSION};
+
+    let src = r#"
+        validator foo {
+          mint(redeemer: Int, _policy_id: ByteArray, _self: Data) {
+            redeemer == 42
+          }
+
+          else(_) {
+            fail
+          }
+        }
+    "#;
+
+    let mut project = TestProject::new();
+
+    let modules = CheckedModules::singleton(project.check(project.parse(src)));
+
+    let (_, validator) = modules
+        .validators()
+        .next()
+        .expect("a validator in the module");
+
+    let mut generator = project.new_generator(Tracing::All(TraceLevel::Silent));
+
+    let air = generator.generate_air(validator, "test_module");
+
+    let export = serde_json::to_value(AirExport::new("test_module.foo", &air)).unwrap();
+
+    assert_eq!(export["version"], AIR_FORMAT_VERSION);
+    assert_eq!(export["title"], "test_module.foo");
+    assert!(export["tree"]["node"].is_string());
+
+    // Exporting the AIR leaves the generator as it found it.
+    let program = generator.generate(validator, "test_module");
+
+    let expected = project
+        .new_generator(Tracing::All(TraceLevel::Silent))
+        .generate(validator, "test_module");
+
+    assert_eq!(program.to_pretty(), expected.to_pretty());
+}
+
+#[test]
+fn eval_constant() {
+    let src = r#"
+        fn sum(xs: List<Int>) -> Int {
+          when xs is {
+            [] -> 0
+            [x, ..rest] -> x + sum(rest)
+          }
+        }
+
+        const total = sum([1, 2, 3]) * 2
+
+        fn divide_by_zero(n: Int) -> Int {
+          trace @"about to divide"
+          n / 0
+        }
+
+        const failing = divide_by_zero(total)
+    "#;
+
+    let mut project = TestProject::new();
+
+    let module = project.check(project.parse(src));
+
+    let mut generator = project.new_generator(Tracing::All(TraceLevel::Verbose));
+
+    assert_eq!(
+        generator.eval_constant(&module.name, "total").unwrap(),
+        Term::integer(12.into())
+    );
+
+    let mut result = generator
+        .eval_constant(&module.name, "failing")
+        .expect_err("dividing by zero should fail");
+
+    assert!(result.result().is_err());
+    assert_eq!(result.logs(), vec!["about to divide".to_string()]);
+}
@@ -20,7 +20,9 @@ use aiken_lang::{
 use indexmap::IndexMap;
 use std::{collections::HashMap, path::PathBuf};
 
+mod docs;
 mod gen_uplc;
+mod plutus_version;
 
 // TODO: Possible refactor this out of the module and have it used by `Project`. The idea would
 // be to make this struct below the actual project, and wrap it in another metadata struct
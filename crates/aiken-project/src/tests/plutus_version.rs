@@ -0,0 +1,115 @@
+use crate::{error::Error, options::Options, telemetry::EventListener, Project};
+use aiken_lang::plutus_version::PlutusVersion;
+use std::fs;
+
+struct NoListener;
+
+impl EventListener for NoListener {}
+
+fn unavailable_builtins(name: &str, manifest: &str) -> Vec<(String, PlutusVersion)> {
+    let root = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(root.join("lib")).unwrap();
+    fs::create_dir_all(root.join("validators")).unwrap();
+
+    fs::write(root.join("aiken.toml"), manifest).unwrap();
+
+    fs::write(
+        root.join("lib/points.ak"),
+        r#"
+        use aiken/builtin
+
+        pub fn double(point: G1Element) -> G1Element {
+          builtin.bls12_381_g1_add(point, point)
+        }
+        "#,
+    )
+    .unwrap();
+
+    fs::write(
+        root.join("validators/pairing.ak"),
+        r#"
+        use aiken/builtin
+        use points
+
+        validator pairing {
+          mint(redeemer: ByteArray, _policy_id: ByteArray, _self: Data) {
+            let point = builtin.bls12_381_g1_uncompress(redeemer)
+            builtin.bls12_381_g1_equal(points.double(point), point)
+          }
+
+          else(_) {
+            fail
+          }
+        }
+        "#,
+    )
+    .unwrap();
+
+    let result = Project::new(root.clone(), NoListener)
+        .unwrap()
+        .compile(Options::default());
+
+    fs::remove_dir_all(&root).unwrap();
+
+    match result {
+        Ok(()) => vec![],
+        Err(errors) => errors
+            .into_iter()
+            .map(|error| match error {
+                Error::UnavailableBuiltin { what, version, .. } => (what, version),
+                error => panic!("unexpected error: {error:?}"),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn validator_targeting_v3_in_v2_project() {
+    let errors = unavailable_builtins(
+        "aiken-plutus-version-v3-override",
+        r#"
+        name = "test/project"
+        version = "0.0.0"
+        plutus = "v2"
+
+        [validators."pairing.pairing"]
+        plutus = "v3"
+        "#,
+    );
+
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[test]
+fn validator_targeting_v2_in_v3_project() {
+    let mut errors = unavailable_builtins(
+        "aiken-plutus-version-v2-override",
+        r#"
+        name = "test/project"
+        version = "0.0.0"
+        plutus = "v3"
+
+        [validators."pairing.pairing"]
+        plutus = "v2"
+        "#,
+    );
+
+    errors.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    assert_eq!(
+        errors,
+        vec![
+            ("builtin 'bls12_381_g1_add'".to_string(), PlutusVersion::V2),
+            (
+                "builtin 'bls12_381_g1_equal'".to_string(),
+                PlutusVersion::V2
+            ),
+            (
+                "builtin 'bls12_381_g1_uncompress'".to_string(),
+                PlutusVersion::V2
+            ),
+        ]
+    );
+}
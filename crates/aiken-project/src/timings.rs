@@ -0,0 +1,182 @@
+use owo_colors::{OwoColorize, Stream::Stderr};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    time::Duration,
+};
+
+/// How many modules to show in the console report, slowest first.
+const SLOWEST_MODULES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Phase {
+    Parse,
+    Resolve,
+    TypeCheck,
+    #[serde(rename = "codegen")]
+    CodeGen,
+    Optimize,
+    Serialize,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Phase::Parse => "parse",
+            Phase::Resolve => "resolve",
+            Phase::TypeCheck => "type-check",
+            Phase::CodeGen => "codegen",
+            Phase::Optimize => "optimize",
+            Phase::Serialize => "serialize",
+        })
+    }
+}
+
+/// Time spent by the compiler in each phase, overall and per module. Not every phase is
+/// attributable to a module (e.g. resolving dependencies), so module timings don't necessarily
+/// add up to the phase totals.
+#[derive(Debug, Default, Clone)]
+pub struct Timings {
+    phases: BTreeMap<Phase, Duration>,
+    modules: BTreeMap<String, BTreeMap<Phase, Duration>>,
+}
+
+impl Timings {
+    pub fn record(&mut self, phase: Phase, elapsed: Duration) {
+        *self.phases.entry(phase).or_default() += elapsed;
+    }
+
+    pub fn record_module(&mut self, module: &str, phase: Phase, elapsed: Duration) {
+        *self
+            .modules
+            .entry(module.to_string())
+            .or_default()
+            .entry(phase)
+            .or_default() += elapsed;
+    }
+
+    pub fn total(&self) -> Duration {
+        self.phases.values().sum()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        fn millis(phases: &BTreeMap<Phase, Duration>) -> BTreeMap<Phase, f64> {
+            phases
+                .iter()
+                .map(|(phase, elapsed)| (*phase, as_millis(*elapsed)))
+                .collect()
+        }
+
+        serde_json::json!({
+            "total": as_millis(self.total()),
+            "phases": millis(&self.phases),
+            "modules": self
+                .modules
+                .iter()
+                .map(|(module, phases)| (module.clone(), millis(phases)))
+                .collect::<BTreeMap<_, _>>(),
+        })
+    }
+}
+
+impl Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>13} {}",
+            "Timings"
+                .if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.purple()),
+            pretty(self.total()).if_supports_color(Stderr, |s| s.bold()),
+        )?;
+
+        for (phase, elapsed) in self.phases.iter() {
+            writeln!(
+                f,
+                "{:>13} {:>10}",
+                phase.if_supports_color(Stderr, |s| s.bright_blue()),
+                pretty(*elapsed),
+            )?;
+        }
+
+        if self.modules.is_empty() {
+            return Ok(());
+        }
+
+        let mut modules = self
+            .modules
+            .iter()
+            .map(|(module, phases)| (module, phases.values().sum::<Duration>(), phases))
+            .collect::<Vec<_>>();
+
+        modules.sort_by(|(_, left, _), (_, right, _)| right.cmp(left));
+
+        writeln!(
+            f,
+            "\n{:>13}",
+            "Modules"
+                .if_supports_color(Stderr, |s| s.bold())
+                .if_supports_color(Stderr, |s| s.purple()),
+        )?;
+
+        for (module, total, phases) in modules.into_iter().take(SLOWEST_MODULES) {
+            writeln!(
+                f,
+                "{:>13} {} {}",
+                pretty(total),
+                module.if_supports_color(Stderr, |s| s.bright_blue()),
+                phases
+                    .iter()
+                    .map(|(phase, elapsed)| format!("{phase}: {}", pretty(*elapsed)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .if_supports_color(Stderr, |s| s.dimmed()),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn as_millis(elapsed: Duration) -> f64 {
+    (elapsed.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+fn pretty(elapsed: Duration) -> String {
+    format!("{:.2}ms", elapsed.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timings_to_json() {
+        let mut timings = Timings::default();
+
+        timings.record(Phase::Parse, Duration::from_micros(1500));
+        timings.record(Phase::TypeCheck, Duration::from_millis(3));
+        timings.record(Phase::TypeCheck, Duration::from_millis(2));
+        timings.record_module("foo/bar", Phase::TypeCheck, Duration::from_millis(4));
+        timings.record_module("foo/bar", Phase::CodeGen, Duration::from_micros(250));
+
+        assert_eq!(
+            timings.to_json(),
+            serde_json::json!({
+                "total": 6.5,
+                "phases": {
+                    "parse": 1.5,
+                    "type-check": 5.0,
+                },
+                "modules": {
+                    "foo/bar": {
+                        "type-check": 4.0,
+                        "codegen": 0.25,
+                    },
+                },
+            })
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::Error;
+use aiken_lang::smt::{Point, Query, Verdict};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// The solver used when none is specified: Z3, reading its script from stdin.
+pub const DEFAULT_SOLVER: &str = "z3 -in";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Holds,
+    Fails(Vec<(String, String)>),
+    Unknown(String),
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reachability {
+    /// Reached by the given inputs.
+    Reachable(Vec<(String, String)>),
+    Unreachable,
+    Unknown(String),
+}
+
+impl From<Verdict> for Reachability {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Holds(witness) => Reachability::Reachable(witness),
+            Verdict::Fails(_) => Reachability::Unreachable,
+            Verdict::Unknown(reason) => Reachability::Unknown(reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PathResult {
+    pub point: Point,
+    pub line: usize,
+    pub reachability: Reachability,
+}
+
+#[derive(Debug, Clone)]
+pub struct VerificationResult {
+    pub module: String,
+    pub name: String,
+    pub handler: bool,
+    pub outcome: Outcome,
+    /// Paths through the program, when explored.
+    pub paths: Vec<PathResult>,
+}
+
+/// Run a solver on a query, feeding it the query's script on stdin. The solver command is given
+/// as a program followed by its arguments.
+pub fn solve(solver: &[String], query: &Query) -> Result<Verdict, Error> {
+    let failed = |reason: String| Error::SolverFailed {
+        command: solver.join(" "),
+        reason,
+    };
+
+    let (program, args) = solver
+        .split_first()
+        .ok_or_else(|| failed("No solver command was given.".to_string()))?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(query.script.as_bytes())
+        .map_err(|e| failed(e.to_string()))?;
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failed(e.to_string()))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Solvers may complain about asking for a model of an unsatisfiable query, and exit with a
+    // non-zero status, so the answer matters more than the status.
+    if stdout.trim().is_empty() {
+        return Err(failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(query.verdict(&stdout))
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+    use aiken_lang::smt::Goal;
+
+    #[test]
+    fn solve_with_external_command() {
+        let query = Query {
+            goal: Goal::Valid,
+            inputs: vec![("n".to_string(), "n!1".to_string())],
+            script: "(check-sat)\n".to_string(),
+        };
+
+        let solver = |script: &str| {
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!("cat > /dev/null; printf '{script}'"),
+            ]
+        };
+
+        assert_eq!(
+            solve(&solver("unsat\\n"), &query).unwrap(),
+            Verdict::Holds(vec![])
+        );
+
+        assert_eq!(
+            solve(&solver("sat\\n((define-fun n!1 () Int 0))\\n"), &query).unwrap(),
+            Verdict::Fails(vec![("n".to_string(), "0".to_string())])
+        );
+
+        assert!(matches!(
+            solve(&["aiken-no-such-solver".to_string()], &query),
+            Err(Error::SolverFailed { .. })
+        ));
+    }
+}
@@ -1,14 +1,22 @@
-use crate::{telemetry::EventTarget, Project};
+use crate::{
+    cache::MemoryCache,
+    error::Warning,
+    telemetry::{
+        fmt_diagnostic_json, fmt_sarif, fmt_sarif_result, EventTarget, Json, Junit, Terminal,
+    },
+    Project,
+};
 use miette::{Diagnostic, IntoDiagnostic};
 use notify::{Event, RecursiveMode, Watcher};
 use owo_colors::{OwoColorize, Stream::Stderr};
+use serde_json::json;
 use std::{
     collections::VecDeque,
     env,
     ffi::OsStr,
     fmt::{self, Display},
     path::Path,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, OnceLock},
 };
 
 #[derive(Debug, Diagnostic, thiserror::Error)]
@@ -88,10 +96,63 @@ pub fn default_filter(evt: &Event) -> bool {
     }
 }
 
+/// How to report the outcome of a compilation on stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    /// Same as [`Output::Pretty`] when stdout is a terminal, except that test results are
+    /// printed as JSON otherwise.
+    Default,
+    /// Diagnostics and test results for humans.
+    Pretty,
+    /// Diagnostics and test results as a single JSON document.
+    Json,
+    /// Test results as JUnit XML; diagnostics are for humans, on stderr.
+    Junit,
+    /// Diagnostics as a SARIF log, e.g. for code scanning; test results are left out.
+    Sarif,
+}
+
+static DEFAULT_OUTPUT: OnceLock<Output> = OnceLock::new();
+
+/// Set how commands report their outcome when they don't say otherwise, for the rest of the
+/// process; see [`default_output`].
+pub fn set_default_output(output: Output) {
+    let _ = DEFAULT_OUTPUT.set(output);
+}
+
+/// The output used in place of [`Output::Default`], as set with [`set_default_output`] (e.g.
+/// from 'aiken --output json').
+pub fn default_output() -> Output {
+    DEFAULT_OUTPUT.get().copied().unwrap_or(Output::Default)
+}
+
+/// Print the outcome of a command (e.g. an address): as is for humans, or as the 'result' of
+/// the JSON document printed at the end of the command when the output is JSON.
+pub fn print_result(project: &Project<EventTarget>, human: impl Display, json: serde_json::Value) {
+    match (&project.event_listener, default_output()) {
+        (EventTarget::Json(listener), Output::Json) => listener.set_result(json),
+        _ => println!("{human}"),
+    }
+}
+
 pub fn with_project<A>(
     directory: Option<&Path>,
     deny: bool,
     json: bool,
+    action: A,
+) -> miette::Result<()>
+where
+    A: FnMut(&mut Project<EventTarget>) -> Result<(), Vec<crate::error::Error>>,
+{
+    let output = if json { Output::Json } else { Output::Default };
+
+    with_project_output(directory, deny, output, action)
+}
+
+pub fn with_project_output<A>(
+    directory: Option<&Path>,
+    deny: bool,
+    output: Output,
     mut action: A,
 ) -> miette::Result<()>
 where
@@ -107,10 +168,33 @@ where
         current_dir
     };
 
-    let mut project = match Project::new(project_path, EventTarget::default()) {
+    let output = match output {
+        Output::Default => default_output(),
+        output => output,
+    };
+
+    let target = match output {
+        Output::Default => EventTarget::default(),
+        Output::Pretty => EventTarget::Terminal(Terminal),
+        Output::Json | Output::Sarif => EventTarget::Json(Json::default()),
+        Output::Junit => EventTarget::Junit(Junit::default()),
+    };
+
+    let mut project = match Project::new(project_path.clone(), target) {
         Ok(p) => Ok(p),
         Err(e) => {
-            e.report();
+            match output {
+                Output::Default | Output::Pretty => e.report(),
+                Output::Json => {
+                    let report = json!({ "diagnostics": [fmt_diagnostic_json(&e)] });
+                    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+                }
+                Output::Junit => eprintln!("{e:?}"),
+                Output::Sarif => {
+                    let report = fmt_sarif(vec![fmt_sarif_result(&e, &project_path)]);
+                    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+                }
+            }
             Err(ExitFailure::into_report())
         }
     }?;
@@ -121,7 +205,83 @@ where
 
     let warning_count = warnings.len();
 
-    if !json {
+    // Warnings denied in the configuration fail the command, as with '--deny'.
+    let deny = deny
+        || warnings
+            .iter()
+            .any(|warning| project.config.diagnostics.denies(warning));
+
+    if output == Output::Junit {
+        if let EventTarget::Junit(junit) = &project.event_listener {
+            if let Some(report) = junit.take_report() {
+                print!("{report}");
+            }
+        }
+
+        for warning in &warnings {
+            warning.report()
+        }
+
+        // Failing tests are already part of the report; other errors go to stderr.
+        if let Err(errs) = build_result {
+            for err in &errs {
+                if !matches!(err, crate::error::Error::TestFailure { .. }) {
+                    eprintln!("{err:?}");
+                }
+            }
+
+            return Err(ExitFailure::into_report());
+        }
+
+        return if warning_count > 0 && deny {
+            Err(ExitFailure::into_report())
+        } else {
+            Ok(())
+        };
+    }
+
+    if output == Output::Sarif {
+        // Failing tests are left out, as they aren't findings about the code itself.
+        let report = fmt_sarif(
+            warnings
+                .iter()
+                .map(|warning| fmt_sarif_result(warning, &project.root))
+                .chain(
+                    build_result
+                        .as_ref()
+                        .err()
+                        .into_iter()
+                        .flatten()
+                        .filter(|err| !matches!(err, crate::error::Error::TestFailure { .. }))
+                        .map(|err| fmt_sarif_result(err, &project.root)),
+                )
+                .collect(),
+        );
+
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+
+        return if build_result.is_err() || (warning_count > 0 && deny) {
+            Err(ExitFailure::into_report())
+        } else {
+            Ok(())
+        };
+    }
+
+    let report = project.event_listener.take_report();
+
+    if output == Output::Json {
+        let report = json_document(report, &warnings, &build_result);
+
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+
+        if build_result.is_err() {
+            return Err(ExitFailure::into_report());
+        }
+    } else {
+        if let Some(report) = report {
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+
         for warning in &warnings {
             warning.report()
         }
@@ -162,6 +322,33 @@ where
     }
 }
 
+/// The JSON document reporting the outcome of a command: its report (e.g. test results) if any,
+/// along with warnings and errors as diagnostics.
+pub(crate) fn json_document(
+    report: Option<serde_json::Value>,
+    warnings: &[Warning],
+    result: &Result<(), Vec<crate::error::Error>>,
+) -> serde_json::Value {
+    let mut report = report.unwrap_or_else(|| json!({}));
+
+    // Failing tests are already part of the report, along with their outcome.
+    report["diagnostics"] = warnings
+        .iter()
+        .map(fmt_diagnostic_json)
+        .chain(
+            result
+                .as_ref()
+                .err()
+                .into_iter()
+                .flatten()
+                .filter(|err| !matches!(err, crate::error::Error::TestFailure { .. }))
+                .map(fmt_diagnostic_json),
+        )
+        .collect();
+
+    report
+}
+
 /// Run a function each time a file in the project changes
 ///
 /// ```text
@@ -218,6 +405,10 @@ where
     // Start watching for any changes in the project directory
     let _ = watcher.watch(project_path.as_path(), RecursiveMode::Recursive);
 
+    // Modules and blueprint kept in memory from one iteration to the next, so that each only
+    // re-checks the modules affected by a change, and only generates code when validators change.
+    let mut memory = MemoryCache::default();
+
     // And then start reading from the queue
     let queue_read = queue.clone();
     loop {
@@ -248,7 +439,13 @@ where
                     .if_supports_color(Stderr, |s| s.bold())
                     .if_supports_color(Stderr, |s| s.purple()),
             );
-            with_project(directory, false, false, &mut action).unwrap_or(())
+            with_project(directory, false, false, |project| {
+                project.with_memory_cache(std::mem::take(&mut memory));
+                let result = action(project);
+                memory = project.take_memory_cache();
+                result
+            })
+            .unwrap_or(())
         }
     }
 }
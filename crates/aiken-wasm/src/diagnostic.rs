@@ -0,0 +1,64 @@
+use aiken_project::error::{Error, GetSource, Warning};
+use serde::Serialize;
+use std::path::Path;
+
+/// An error or a warning, as a plain value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// Name of the module the diagnostic relates to.
+    pub module: String,
+    pub message: String,
+    pub help: Option<String>,
+    /// Byte offsets, within the module's source code.
+    pub location: Option<Location>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Diagnostic {
+    pub fn new(module: &str, diagnostic: &dyn miette::Diagnostic) -> Self {
+        Diagnostic {
+            module: module.to_string(),
+            message: diagnostic.to_string(),
+            help: diagnostic.help().map(|help| help.to_string()),
+            location: diagnostic
+                .labels()
+                .and_then(|mut labels| labels.next())
+                .map(|label| Location {
+                    start: label.offset(),
+                    end: label.offset() + label.len(),
+                }),
+        }
+    }
+
+    /// Errors about a module's code are reported as such, with locations within that module.
+    pub fn from_error(error: &Error) -> Self {
+        match error {
+            Error::Parse { path, error, .. } => Diagnostic::new(&module(path), error.as_ref()),
+            Error::Type { path, error, .. } => Diagnostic::new(&module(path), error.as_ref()),
+            error => Diagnostic::new(
+                &error.path().as_deref().map(module).unwrap_or_default(),
+                error,
+            ),
+        }
+    }
+
+    pub fn from_warning(warning: &Warning) -> Self {
+        match warning {
+            Warning::Type { path, warning, .. } => Diagnostic::new(&module(path), warning),
+            warning => Diagnostic::new(
+                &warning.path().as_deref().map(module).unwrap_or_default(),
+                warning,
+            ),
+        }
+    }
+}
+
+/// Modules of projects held in memory have their name as path.
+fn module(path: &Path) -> String {
+    path.display().to_string()
+}
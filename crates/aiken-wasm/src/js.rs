@@ -0,0 +1,62 @@
+//! JavaScript bindings. Inputs and outputs are plain JavaScript values, (de)serialised through
+//! serde; failures are thrown as exceptions.
+//!
+//! ```js
+//! const validators = compile([{ name: "foo", code, kind: "validator" }], { plutus: "v3" });
+//! const { warnings, tests } = check([{ name: "foo", code }], {});
+//! const { result, cpu, mem, logs } = evalUplc(validators[0].compiledCode, ["(con data #00)"]);
+//! const { compiledCode, hash } = applyParams(validators[0].compiledCode, ["182a"], {});
+//! ```
+
+use crate::{Module, Options};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub fn compile(modules: JsValue, options: JsValue) -> Result<JsValue, JsValue> {
+    let (modules, options) = inputs(modules, options)?;
+    output(crate::compile(modules, &options))
+}
+
+#[wasm_bindgen]
+pub fn check(modules: JsValue, options: JsValue) -> Result<JsValue, JsValue> {
+    let (modules, options) = inputs(modules, options)?;
+    output(crate::check(modules, &options))
+}
+
+#[wasm_bindgen(js_name = evalUplc)]
+pub fn eval_uplc(program: &str, args: Vec<String>) -> Result<JsValue, JsValue> {
+    output(crate::eval(program, &args))
+}
+
+#[wasm_bindgen(js_name = applyParams)]
+pub fn apply_params(
+    compiled_code: &str,
+    params: Vec<String>,
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = options_from(options)?;
+    output(crate::apply_params(compiled_code, &params, &options))
+}
+
+fn inputs(modules: JsValue, options: JsValue) -> Result<(Vec<Module>, Options), JsValue> {
+    let modules = serde_wasm_bindgen::from_value(modules)?;
+    Ok((modules, options_from(options)?))
+}
+
+fn options_from(options: JsValue) -> Result<Options, JsValue> {
+    if options.is_undefined() || options.is_null() {
+        Ok(Options::default())
+    } else {
+        Ok(serde_wasm_bindgen::from_value(options)?)
+    }
+}
+
+fn output<T: Serialize, E: Serialize>(result: Result<T, E>) -> Result<JsValue, JsValue> {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+
+    match result {
+        Ok(value) => Ok(value.serialize(&serializer)?),
+        Err(error) => Err(error.serialize(&serializer)?),
+    }
+}
@@ -0,0 +1,312 @@
+//! Aiken's compiler and evaluator, working solely on in-memory sources. Projects are compiled by
+//! 'aiken-project', without its 'native' feature, so that nothing here touches the network or any
+//! process and the crate compiles to `wasm32-unknown-unknown`. JavaScript bindings are available
+//! behind the 'js' feature.
+
+mod diagnostic;
+mod project;
+
+#[cfg(feature = "js")]
+pub mod js;
+
+pub use diagnostic::{Diagnostic, Location};
+pub use project::{Module, ModuleKind, Options};
+
+use aiken_project::options::Emit;
+use project::Tests;
+use serde::Serialize;
+use std::path::PathBuf;
+use uplc::{
+    ast::{DeBruijn, FakeNamedDeBruijn, Name, NamedDeBruijn, Program, SerializableProgram, Term},
+    machine::cost_model::ExBudget,
+    parser, PlutusData,
+};
+
+/// A compiled validator handler, as found in blueprints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompiledValidator {
+    /// The handler's title, as '{module}.{validator}.{handler}'.
+    pub title: String,
+    pub compiled_code: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TestOutcome {
+    pub module: String,
+    pub title: String,
+    pub success: bool,
+    pub traces: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckOutcome {
+    pub warnings: Vec<Diagnostic>,
+    pub tests: Vec<TestOutcome>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EvalOutcome {
+    /// The resulting term, pretty-printed; or the evaluation error.
+    pub result: Result<String, String>,
+    pub cpu: i64,
+    pub mem: i64,
+    pub logs: Vec<String>,
+}
+
+/// Type-check and compile all validators found in the given modules.
+pub fn compile(
+    modules: Vec<Module>,
+    options: &Options,
+) -> Result<Vec<CompiledValidator>, Vec<Diagnostic>> {
+    let mut project = project::new(modules, options, Tests::default())?;
+
+    project
+        .build(Emit::default(), options.tracing(), PathBuf::new(), None)
+        .map_err(project::diagnostics)?;
+
+    let blueprint = project
+        .blueprint()
+        .expect("successful build has a blueprint");
+
+    Ok(blueprint
+        .validators
+        .iter()
+        .map(|validator| {
+            let (compiled_code, hash) = validator.program.compiled_code_and_hash();
+            CompiledValidator {
+                title: validator.title.clone(),
+                compiled_code,
+                hash: hash.to_string(),
+            }
+        })
+        .collect())
+}
+
+/// Type-check the given modules, and run all their tests.
+pub fn check(modules: Vec<Module>, options: &Options) -> Result<CheckOutcome, Vec<Diagnostic>> {
+    let tests = Tests::default();
+
+    let mut project = project::new(modules, options, tests.clone())?;
+
+    let result = project.check(
+        false,
+        None,
+        vec![],
+        false,
+        false,
+        options.seed,
+        options.property_max_success,
+        false,
+        None,
+        options.tracing(),
+        None,
+    );
+
+    // Once tests have run, the only errors left are failing tests, which are part of the outcome.
+    let tests = match tests.take() {
+        Some(tests) => tests,
+        None => {
+            result.map_err(project::diagnostics)?;
+            vec![]
+        }
+    };
+
+    Ok(CheckOutcome {
+        warnings: project
+            .warnings()
+            .iter()
+            .map(Diagnostic::from_warning)
+            .collect(),
+        tests,
+    })
+}
+
+/// Evaluate a program, given either as hex-encoded CBOR (as found in blueprints) or as textual
+/// UPLC, applied to the given arguments (as textual UPLC terms).
+pub fn eval(program: &str, args: &[String]) -> Result<EvalOutcome, String> {
+    let mut program: Program<Name> = match hex::decode(program.trim()) {
+        Ok(cbor) => {
+            let program = Program::<FakeNamedDeBruijn>::from_cbor(&cbor, &mut Vec::new())
+                .map_err(|e| e.to_string())?;
+
+            let program: Program<NamedDeBruijn> = program.into();
+
+            Program::<Name>::try_from(program).map_err(|e| e.to_string())?
+        }
+        Err(..) => parser::program(program).map_err(|e| e.to_string())?,
+    };
+
+    for arg in args {
+        let term = parser::term(arg).map_err(|e| e.to_string())?;
+
+        program = program.apply_term(&term);
+    }
+
+    let program = Program::<NamedDeBruijn>::try_from(program).map_err(|e| e.to_string())?;
+
+    let mut eval_result = program.eval(ExBudget::default());
+
+    let cost = eval_result.cost();
+    let logs = eval_result.logs();
+
+    let result = match eval_result.result() {
+        Ok(term) => Ok(Term::<Name>::try_from(term)
+            .map_err(|e| e.to_string())?
+            .to_pretty()),
+        Err(err) => Err(err.to_string()),
+    };
+
+    Ok(EvalOutcome {
+        result,
+        cpu: cost.cpu,
+        mem: cost.mem,
+        logs,
+    })
+}
+
+/// Apply parameters (as hex-encoded CBOR Plutus data), in order, to a script's compiled code.
+pub fn apply_params(
+    compiled_code: &str,
+    params: &[String],
+    options: &Options,
+) -> Result<CompiledValidator, String> {
+    let mut cbor_buffer = Vec::new();
+    let mut flat_buffer = Vec::new();
+
+    let mut program =
+        Program::<DeBruijn>::from_hex(compiled_code, &mut cbor_buffer, &mut flat_buffer)
+            .map_err(|e| e.to_string())?;
+
+    for param in params {
+        let bytes = hex::decode(param).map_err(|e| format!("invalid hex-encoded string: {e}"))?;
+
+        let data: PlutusData = uplc::plutus_data(&bytes)
+            .map_err(|e| format!("invalid Plutus data; malformed CBOR encoding: {e}"))?;
+
+        program = program.apply_data(data);
+    }
+
+    let (compiled_code, hash) = options.plutus_version(program).compiled_code_and_hash();
+
+    Ok(CompiledValidator {
+        title: String::new(),
+        compiled_code,
+        hash: hash.to_string(),
+    })
+}
+
+impl Options {
+    fn plutus_version(&self, program: Program<DeBruijn>) -> SerializableProgram {
+        use aiken_lang::plutus_version::PlutusVersion;
+
+        match self.plutus {
+            PlutusVersion::V1 => SerializableProgram::PlutusV1Program(program),
+            PlutusVersion::V2 => SerializableProgram::PlutusV2Program(program),
+            PlutusVersion::V3 => SerializableProgram::PlutusV3Program(program),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn modules(code: &str) -> Vec<Module> {
+        vec![Module {
+            name: "foo".to_string(),
+            code: code.to_string(),
+            kind: ModuleKind::Validator,
+        }]
+    }
+
+    #[test]
+    fn compile_and_apply() {
+        let validators = compile(
+            modules(indoc! {r#"
+                validator foo(n: Int) {
+                  mint(redeemer: Int, _policy_id: ByteArray, _self: Data) {
+                    redeemer == n
+                  }
+
+                  else(_) {
+                    fail
+                  }
+                }
+            "#}),
+            &Options::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            validators
+                .iter()
+                .map(|validator| validator.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["foo.foo.mint", "foo.foo.else"]
+        );
+
+        // Handlers of a validator all share the same script.
+        assert_eq!(validators[0].hash, validators[1].hash);
+
+        let applied = apply_params(
+            &validators[0].compiled_code,
+            &["182a".to_string()],
+            &Options::default(),
+        )
+        .unwrap();
+
+        assert_ne!(applied.hash, validators[0].hash);
+    }
+
+    #[test]
+    fn check_reports_errors() {
+        let errors = check(modules("fn foo() -> Int { True }"), &Options::default()).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].module, "foo");
+        assert!(errors[0].location.is_some());
+    }
+
+    #[test]
+    fn check_runs_tests() {
+        let outcome = check(
+            modules(indoc! {r#"
+                test ok() {
+                  1 + 1 == 2
+                }
+
+                test ko() {
+                  trace @"nope"
+                  False
+                }
+            "#}),
+            &Options::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            outcome
+                .tests
+                .iter()
+                .map(|test| (test.title.as_str(), test.success))
+                .collect::<Vec<_>>(),
+            vec![("ok", true), ("ko", false)]
+        );
+        assert_eq!(outcome.tests[1].traces, vec!["nope".to_string()]);
+    }
+
+    #[test]
+    fn eval_textual_uplc() {
+        let outcome = eval(
+            "(program 1.0.0 (lam x [(builtin addInteger) x (con integer 1)]))",
+            &["(con integer 41)".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(outcome.result, Ok("(con integer 42)".to_string()));
+        assert!(outcome.cpu > 0);
+    }
+}
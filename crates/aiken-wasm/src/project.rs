@@ -0,0 +1,153 @@
+use crate::{Diagnostic, TestOutcome};
+use aiken_lang::{
+    ast::{self, TraceLevel, Tracing},
+    plutus_version::PlutusVersion,
+};
+use aiken_project::{
+    config::Config,
+    error::Error,
+    telemetry::{Event, EventListener},
+    Project, Source,
+};
+use serde::Deserialize;
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+const CONFIG: &str = r#"
+name = "aiken-lang/playground"
+version = "0.0.0"
+"#;
+
+/// A module's source code, e.g. 'lib/foo/bar.ak' given as 'foo/bar'.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Module {
+    pub name: String,
+    pub code: String,
+    #[serde(default)]
+    pub kind: ModuleKind,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModuleKind {
+    #[default]
+    Lib,
+    Validator,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Options {
+    pub plutus: PlutusVersion,
+    pub trace_level: TraceLevel,
+    /// Features enabled for `@cfg` attributes.
+    pub features: Vec<String>,
+    pub seed: u32,
+    pub property_max_success: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            plutus: PlutusVersion::default(),
+            trace_level: TraceLevel::Verbose,
+            features: vec![],
+            seed: 0,
+            property_max_success: 100,
+        }
+    }
+}
+
+impl Options {
+    pub(crate) fn tracing(&self) -> Tracing {
+        Tracing::All(self.trace_level)
+    }
+
+    /// Configuration of the project, declaring the features to enable.
+    fn config(&self) -> Config {
+        let mut config = Config::parse(Path::new("aiken.toml"), CONFIG.to_string())
+            .expect("playground configuration is valid");
+
+        config.plutus = self.plutus;
+
+        config.features = self
+            .features
+            .iter()
+            .map(|feature| (feature.clone(), vec![]))
+            .collect();
+
+        config
+    }
+}
+
+/// Outcomes of the tests run while checking a project. Shared with the project, which takes
+/// ownership of its event listener.
+#[derive(Clone, Default)]
+pub(crate) struct Tests(Rc<RefCell<Option<Vec<TestOutcome>>>>);
+
+impl Tests {
+    /// The outcomes reported, if the project got as far as running tests.
+    pub fn take(&self) -> Option<Vec<TestOutcome>> {
+        self.0.borrow_mut().take()
+    }
+}
+
+impl EventListener for Tests {
+    fn handle_event(&self, event: Event) {
+        if let Event::FinishedTests { tests, .. } = event {
+            *self.0.borrow_mut() = Some(
+                tests
+                    .iter()
+                    .map(|result| TestOutcome {
+                        module: result.module().to_string(),
+                        title: result.title().to_string(),
+                        success: result.is_success(),
+                        traces: result.traces().to_vec(),
+                    })
+                    .collect(),
+            );
+        }
+    }
+}
+
+/// A project made of the given modules, held in memory.
+pub(crate) fn new(
+    modules: Vec<Module>,
+    options: &Options,
+    tests: Tests,
+) -> Result<Project<Tests>, Vec<Diagnostic>> {
+    let sources = modules
+        .into_iter()
+        .map(|module| Source {
+            // Diagnostics refer to modules by path, so we use their name as such.
+            path: PathBuf::from(&module.name),
+            name: module.name,
+            code: module.code,
+            kind: module.kind.into(),
+        })
+        .collect();
+
+    let mut project = Project::in_memory(options.config(), sources, tests);
+
+    project
+        .with_features(&options.features, true)
+        .map_err(diagnostics)?;
+
+    Ok(project)
+}
+
+pub(crate) fn diagnostics(errors: Vec<Error>) -> Vec<Diagnostic> {
+    errors.iter().map(Diagnostic::from_error).collect()
+}
+
+impl From<ModuleKind> for ast::ModuleKind {
+    fn from(kind: ModuleKind) -> Self {
+        match kind {
+            ModuleKind::Lib => ast::ModuleKind::Lib,
+            ModuleKind::Validator => ast::ModuleKind::Validator,
+        }
+    }
+}
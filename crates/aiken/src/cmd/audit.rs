@@ -0,0 +1,72 @@
+use aiken_project::{
+    config::Config,
+    deps::{advisories::AdvisoryDb, manifest::Manifest},
+    pretty,
+    telemetry::EventTarget,
+};
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+/// Check locked dependencies against known security advisories and yanked revisions
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Report issues without failing
+    #[clap(long)]
+    warn_only: bool,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        warn_only,
+    }: Args,
+) -> miette::Result<()> {
+    let root = directory.unwrap_or_else(|| PathBuf::from("."));
+
+    let config = match Config::load(&root) {
+        Ok(config) => config,
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    let manifest = match Manifest::load(&EventTarget::default(), &config, &root) {
+        Ok((manifest, _)) => manifest,
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    eprintln!(
+        "{} {} package(s)",
+        pretty::pad_left("Auditing".to_string(), 13, " ")
+            .if_supports_color(Stderr, |s| s.purple())
+            .if_supports_color(Stderr, |s| s.bold()),
+        manifest.packages.len(),
+    );
+
+    let warnings = AdvisoryDb::load(true).audit(&manifest);
+
+    for warning in &warnings {
+        warning.report();
+    }
+
+    eprintln!(
+        "{} {} issue(s) found",
+        pretty::pad_left("Summary".to_string(), 13, " ")
+            .if_supports_color(Stderr, |s| s.purple())
+            .if_supports_color(Stderr, |s| s.bold()),
+        warnings.len(),
+    );
+
+    if !warnings.is_empty() && !warn_only {
+        process::exit(1);
+    }
+
+    Ok(())
+}
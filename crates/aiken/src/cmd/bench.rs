@@ -0,0 +1,182 @@
+use super::build::{trace_filter_parser, trace_level_parser};
+use aiken_lang::{
+    ast::{TraceLevel, Tracing},
+    test_framework::Benchmark,
+};
+use aiken_project::{
+    benchmark::{DEFAULT_BASELINE, DEFAULT_THRESHOLD},
+    watch::{self, watch_project, with_project},
+};
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+#[command(
+    verbatim_doc_comment,
+    about = color_print::cstr!(r#"
+Run the benchmarks of an Aiken project.
+
+Benchmarks are declared with the 'bench' keyword, and draw their argument via a
+Sampler; that is, a function from a size to a Fuzzer:
+
+  fn list_of_size(elem: Fuzzer<<a>) -> Sampler<<List<<a>>>> {
+    fn(size) { fuzz.list_between(elem, size, size) }
+  }
+
+  bench sum(xs via list_of_size(fuzz.int())) {
+    list.foldl(xs, 0, fn(x, acc) { x + acc })
+  }
+
+Each benchmark runs once for every size from 0 to --max-size, and its memory and cpu
+budgets are summarized as percentiles.
+
+Results are saved to a baseline file, which subsequent runs compare against: a benchmark
+fails when its median, 90th percentile or maximum budget exceeds the baseline by more than
+--threshold percents.
+"#),
+    after_long_help = color_print::cstr!(r#"You are seeing the extended help. Use `-h` instead of `--help` for a more compact view.
+"#
+))]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Deny warnings; warnings will be treated as errors
+    #[clap(short = 'D', long)]
+    deny: bool,
+
+    /// When enabled, re-run the command on file changes instead of exiting
+    #[clap(long)]
+    watch: bool,
+
+    /// Only run benchmarks if they match any of these strings, following the same syntax as
+    /// `aiken check --match-tests`.
+    #[clap(short, long)]
+    match_benchmarks: Option<Vec<String>>,
+
+    /// This is meant to be used with `--match-benchmarks`.
+    /// It forces benchmark names without globs to match exactly
+    #[clap(short, long)]
+    exact_match: bool,
+
+    /// The seed to initialize the pseudo-random generator for samplers. Defaults to the seed
+    /// recorded in the baseline, if any.
+    #[clap(long, value_name = "UINT")]
+    seed: Option<u32>,
+
+    /// The largest size to run benchmarks for.
+    #[clap(long, default_value_t = Benchmark::DEFAULT_MAX_SIZE, value_name = "UINT")]
+    max_size: usize,
+
+    /// Path to the baseline file, relative to the project's root.
+    #[clap(long, value_name = "FILEPATH", default_value = DEFAULT_BASELINE)]
+    baseline: PathBuf,
+
+    /// Overwrite the baseline with the results of this run, instead of comparing against it.
+    #[clap(long)]
+    save_baseline: bool,
+
+    /// How much, in percents, a budget may grow beyond the baseline before it counts as a
+    /// regression.
+    #[clap(long, default_value_t = DEFAULT_THRESHOLD, value_name = "PERCENT")]
+    threshold: f64,
+
+    /// Environment to build against.
+    #[clap(long)]
+    env: Option<String>,
+
+    /// Comma-separated list of features to enable, on top of the default ones.
+    #[clap(long, value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Do not enable the 'default' feature.
+    #[clap(long)]
+    no_default_features: bool,
+
+    /// Filter traces to be included in the generated program(s). Note that traces are part
+    /// of the measured budget.
+    ///
+    ///   - user-defined:
+    ///       only consider traces that you've explicitly defined
+    ///       either through the 'trace' keyword of via the trace-if-false
+    ///       ('?') operator.
+    ///
+    ///   - compiler-generated:
+    ///       only included internal traces generated by the
+    ///       Aiken compiler, for example in usage of 'expect'.
+    ///
+    ///   - all:
+    ///       include both user-defined and compiler-generated traces.
+    ///
+    /// [optional]
+    #[clap(short = 'f', long, value_parser=trace_filter_parser(), default_missing_value="all", verbatim_doc_comment, alias="filter_traces")]
+    trace_filter: Option<fn(TraceLevel) -> Tracing>,
+
+    /// Choose the verbosity level of traces:
+    ///
+    ///   - silent: disable traces altogether
+    ///   - compact: only culprit line numbers are shown on failures
+    ///   - verbose: enable full verbose traces as provided by the user or the compiler
+    ///
+    /// [optional]
+    #[clap(short, long, value_parser=trace_level_parser(), default_value_t=TraceLevel::Silent, verbatim_doc_comment)]
+    trace_level: TraceLevel,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        deny,
+        watch,
+        match_benchmarks,
+        exact_match,
+        seed,
+        max_size,
+        baseline,
+        save_baseline,
+        threshold,
+        env,
+        features,
+        no_default_features,
+        trace_filter,
+        trace_level,
+    }: Args,
+) -> miette::Result<()> {
+    let tracing = match trace_filter {
+        Some(trace_filter) => trace_filter(trace_level),
+        None => Tracing::All(trace_level),
+    };
+
+    let result = if watch {
+        watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
+            p.with_features(&features, !no_default_features)?;
+            p.benchmark(
+                match_benchmarks.clone(),
+                exact_match,
+                seed,
+                max_size,
+                Some(baseline.clone()),
+                save_baseline,
+                threshold,
+                tracing,
+                env.clone(),
+            )
+        })
+    } else {
+        with_project(directory.as_deref(), deny, false, |p| {
+            p.with_features(&features, !no_default_features)?;
+            p.benchmark(
+                match_benchmarks.clone(),
+                exact_match,
+                seed,
+                max_size,
+                Some(baseline.clone()),
+                save_baseline,
+                threshold,
+                tracing,
+                env.clone(),
+            )
+        })
+    };
+
+    result.map_err(|_| process::exit(1))
+}
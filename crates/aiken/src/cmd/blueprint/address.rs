@@ -1,9 +1,14 @@
-use aiken_project::watch::with_project;
+use aiken_project::watch::{default_output, print_result, with_project, Output};
+use serde_json::json;
 use std::path::PathBuf;
 
-/// Compute a validator's address.
+/// Compute a validator's address, or inspect an existing one.
 #[derive(clap::Args)]
+#[clap(args_conflicts_with_subcommands = true)]
 pub struct Args {
+    #[clap(subcommand)]
+    cmd: Option<Cmd>,
+
     /// Path to project
     directory: Option<PathBuf>,
 
@@ -27,8 +32,10 @@ pub struct Args {
     #[clap(short, long)]
     validator: Option<String>,
 
-    /// Stake address to attach, if any
-    #[clap(long)]
+    /// Stake credential to attach, if any. Either a stake address (bech32 or base16), the
+    /// base16-encoded hash of a stake key, or the base16-encoded hash of a script prefixed with
+    /// 'script:' (e.g. script:6e1f...).
+    #[clap(long, value_name = "STAKE_ADDRESS | KEY_HASH | script:SCRIPT_HASH")]
     delegated_to: Option<String>,
 
     /// Output the address for mainnet (this command defaults to testnet)
@@ -36,8 +43,43 @@ pub struct Args {
     mainnet: bool,
 }
 
+#[derive(clap::Subcommand)]
+pub enum Cmd {
+    Inspect(InspectArgs),
+}
+
+/// Decode an address (bech32 or base16), showing its network and the credentials of its
+/// payment and stake parts. Script credentials are matched against the validators of the
+/// project's blueprint, if any.
+#[derive(clap::Args)]
+pub struct InspectArgs {
+    /// The address to inspect
+    address: String,
+
+    /// Path to project
+    #[clap(short, long)]
+    directory: Option<PathBuf>,
+
+    /// Optional path to the Plutus blueprint file to match validators against.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+
+    /// Output the details as JSON
+    #[clap(long)]
+    json: bool,
+}
+
 pub fn exec(
     Args {
+        cmd,
         directory,
         input,
         module,
@@ -46,6 +88,10 @@ pub fn exec(
         mainnet,
     }: Args,
 ) -> miette::Result<()> {
+    if let Some(Cmd::Inspect(args)) = cmd {
+        return inspect(args);
+    }
+
     with_project(directory.as_deref(), false, false, |p| {
         let address = p.address(
             module.as_deref(),
@@ -55,7 +101,31 @@ pub fn exec(
             mainnet,
         )?;
 
-        println!("{}", address.to_bech32().unwrap());
+        let address = address.to_bech32().unwrap();
+
+        print_result(p, &address, json!({ "address": address }));
+
+        Ok(())
+    })
+    .map_err(|_| std::process::exit(1))
+}
+
+fn inspect(
+    InspectArgs {
+        address,
+        directory,
+        input,
+        json,
+    }: InspectArgs,
+) -> miette::Result<()> {
+    with_project(directory.as_deref(), false, false, |p| {
+        let info = p.inspect_address(&address, p.blueprint_path(input.as_deref()).as_path())?;
+
+        if json && default_output() != Output::Json {
+            println!("{}", serde_json::to_string_pretty(&info.to_json()).unwrap());
+        } else {
+            print_result(p, info.to_string().trim_end(), info.to_json());
+        }
 
         Ok(())
     })
@@ -16,16 +16,43 @@ use pallas_primitives::alonzo::PlutusData;
 use std::{fs, path::PathBuf, process, str::FromStr};
 use uplc::ast::Data as UplcData;
 
-/// Apply a parameter to a parameterized validator.
+/// Apply parameters to a parameterized validator.
 #[derive(clap::Args)]
 pub struct Args {
-    /// The parameter, as a Plutus Data (CBOR, hex-encoded).
+    /// The parameters, as Plutus Data (CBOR, hex-encoded), applied in order.
     ///
     /// For example, `182A` designates an integer of value 42. If you're unsure about the shape of
-    /// the parameter, look at the schema specified in the project's blueprint (i.e.
+    /// a parameter, look at the schema specified in the project's blueprint (i.e.
     /// `plutus.json`), or use the `cbor.serialise` function from the Aiken standard library.
+    ///
+    /// There may be fewer parameters than the validator expects; the remaining ones are kept in
+    /// the resulting blueprint to be applied later on. When omitted, the next parameter is
+    /// constructed interactively.
+    ///
+    /// With `--json`, parameters are given as JSON instead, following their schema. For example,
+    /// `{ "Some": 42 }` designates an `Option<Int>`, and `{ "owner": "abcd", "deadline": 42 }` a
+    /// record with two fields.
     #[clap(value_name = "CBOR")]
-    parameter: Option<String>,
+    parameters: Vec<String>,
+
+    /// Interpret the parameters as JSON, following the schemas specified in the blueprint.
+    /// Integers are numbers, byte arrays hex-encoded strings, lists and tuples arrays, and maps
+    /// objects. Constructors are designated by their title, and their fields given as an object
+    /// keyed by field titles or as an array.
+    #[clap(long, requires = "parameters")]
+    json: bool,
+
+    /// Path to a file holding a parameter as raw Plutus Data (CBOR, not hex-encoded), e.g. as
+    /// produced by deployment scripts. May be repeated; those parameters are applied in order,
+    /// after any given on the command-line.
+    #[clap(long = "cbor-file", value_name = "FILEPATH")]
+    cbor_files: Vec<PathBuf>,
+
+    /// Accept Data-encoded lists and pairs (i.e. Plutus lists and maps), as tools frequently
+    /// produce, for parameters whose schema expects builtin ones. Those are converted to builtin
+    /// values before being applied.
+    #[clap(long)]
+    coerce: bool,
 
     /// Optional path to the blueprint file to be used as input.
     ///
@@ -55,7 +82,10 @@ pub struct Args {
 
 pub fn exec(
     Args {
-        parameter,
+        parameters,
+        json,
+        cbor_files,
+        coerce,
         input,
         output,
         module,
@@ -72,19 +102,23 @@ pub fn exec(
 
         let blueprint_input_path = p.blueprint_path(input.as_deref());
 
-        let data: PlutusData = match &parameter {
-            Some(param) => {
-                eprintln!(
-                    "{} inputs",
-                    "      Parsing"
-                        .if_supports_color(Stderr, |s| s.purple())
-                        .if_supports_color(Stderr, |s| s.bold()),
-                );
+        if !parameters.is_empty() || !cbor_files.is_empty() {
+            eprintln!(
+                "{} inputs",
+                "      Parsing"
+                    .if_supports_color(Stderr, |s| s.purple())
+                    .if_supports_color(Stderr, |s| s.bold()),
+            );
+        }
 
-                let bytes = hex::decode(param)
+        let mut data: Vec<PlutusData> = vec![];
+
+        for (position, param) in parameters.iter().enumerate() {
+            data.push(if json {
+                let value: serde_json::Value = serde_json::from_str(param)
                     .map_err::<Error, _>(|e| {
                         blueprint::error::Error::MalformedParameter {
-                            hint: format!("Invalid hex-encoded string: {e}"),
+                            hint: format!("Invalid JSON: {e}"),
                         }
                         .into()
                     })
@@ -94,10 +128,18 @@ pub fn exec(
                         process::exit(1)
                     });
 
-                uplc::plutus_data(&bytes)
+                p.parameter_from_json(
+                    module.as_deref(),
+                    validator.as_deref(),
+                    &blueprint_input_path,
+                    position,
+                    &value,
+                )?
+            } else {
+                let bytes = hex::decode(param)
                     .map_err::<Error, _>(|e| {
                         blueprint::error::Error::MalformedParameter {
-                            hint: format!("Invalid Plutus data; malformed CBOR encoding: {e}"),
+                            hint: format!("Invalid hex-encoded string: {e}"),
                         }
                         .into()
                     })
@@ -105,33 +147,55 @@ pub fn exec(
                         println!();
                         e.report();
                         process::exit(1)
-                    })
-            }
+                    });
 
-            None => p.construct_parameter_incrementally(
+                decode_plutus_data(&bytes)
+            });
+        }
+
+        for path in cbor_files.iter() {
+            let bytes = fs::read(path)
+                .map_err(|error| Error::FileIo {
+                    error,
+                    path: path.clone(),
+                })
+                .unwrap_or_else(|e| {
+                    println!();
+                    e.report();
+                    process::exit(1)
+                });
+
+            data.push(decode_plutus_data(&bytes));
+        }
+
+        if data.is_empty() {
+            data.push(p.construct_parameter_incrementally(
                 module.as_deref(),
                 validator.as_deref(),
                 &blueprint_input_path,
                 ask_schema,
-            )?,
-        };
+            )?);
+        }
 
-        eprintln!(
-            "{} {}",
-            "     Applying"
-                .if_supports_color(Stderr, |s| s.purple())
-                .if_supports_color(Stderr, |s| s.bold()),
-            {
-                let padding = "\n              ";
-                multiline(48, UplcData::to_hex(data.clone())).join(padding)
-            }
-        );
+        for param in data.iter() {
+            eprintln!(
+                "{} {}",
+                "     Applying"
+                    .if_supports_color(Stderr, |s| s.purple())
+                    .if_supports_color(Stderr, |s| s.bold()),
+                {
+                    let padding = "\n              ";
+                    multiline(48, UplcData::to_hex(param.clone())).join(padding)
+                }
+            );
+        }
 
-        let blueprint = p.apply_parameter(
+        let blueprint = p.apply_parameters(
             module.as_deref(),
             validator.as_deref(),
             &blueprint_input_path,
             &data,
+            coerce,
         )?;
 
         let json = serde_json::to_string_pretty(&blueprint).unwrap();
@@ -162,12 +226,27 @@ pub fn exec(
     .map_err(|_| std::process::exit(1))
 }
 
-fn ask_schema(
+fn decode_plutus_data(bytes: &[u8]) -> PlutusData {
+    uplc::plutus_data(bytes)
+        .map_err::<Error, _>(|e| {
+            blueprint::error::Error::MalformedParameter {
+                hint: format!("Invalid Plutus data; malformed CBOR encoding: {e}"),
+            }
+            .into()
+        })
+        .unwrap_or_else(|e| {
+            println!();
+            e.report();
+            process::exit(1)
+        })
+}
+
+pub fn ask_schema(
     schema: &Annotated<Schema>,
     definitions: &Definitions<Annotated<Schema>>,
 ) -> Result<PlutusData, blueprint::error::Error> {
     match schema.annotated {
-        Schema::Data(Data::Integer) => {
+        Schema::Data(Data::Integer(..)) => {
             let input = prompt_primitive("an integer", schema)?;
 
             let n = BigInt::from_str(input.as_str()).map_err(|e| {
@@ -179,7 +258,7 @@ fn ask_schema(
             Ok(UplcData::integer(n))
         }
 
-        Schema::Data(Data::Bytes) => {
+        Schema::Data(Data::Bytes(..)) => {
             let input = prompt_primitive("a byte-array", schema)?;
 
             let bytes =
@@ -283,6 +362,7 @@ fn lookup_declaration(
         Declaration::Inline(ref data) => Annotated {
             title: decl.title.clone(),
             description: decl.description.clone(),
+            examples: decl.examples.clone(),
             annotated: Schema::Data(*(*data).clone()),
         },
         Declaration::Referenced(ref reference) => {
@@ -295,6 +375,7 @@ fn lookup_declaration(
                     .description
                     .clone()
                     .or_else(|| schema.description.clone()),
+                examples: schema.examples.clone(),
                 annotated: schema.annotated.clone(),
             }
         }
@@ -0,0 +1,116 @@
+use super::apply::ask_schema;
+use aiken_project::{
+    blueprint::{self, parameter::Parameter},
+    serve::data_to_json,
+    watch::{print_result, with_project},
+};
+use clap::ValueEnum;
+use owo_colors::{OwoColorize, Stream::Stderr};
+use serde_json::json;
+use std::path::PathBuf;
+use uplc::ast::Data as UplcData;
+
+/// Build some Plutus data (e.g. a datum or a redeemer) following a schema from the blueprint.
+#[derive(clap::Args)]
+pub struct Args {
+    /// The definition whose schema to follow, by its key in the blueprint (e.g.
+    /// 'aiken/crypto/VerificationKey') or as a JSON pointer (e.g.
+    /// '#/definitions/aiken~1crypto~1VerificationKey').
+    #[clap(value_name = "DEFINITION")]
+    definition: String,
+
+    /// Build the data from JSON instead of constructing it interactively (see
+    /// `aiken blueprint apply --help` for how values are given).
+    #[clap(long, value_name = "JSON")]
+    from_json: Option<String>,
+
+    /// Format to output the data in: either hex-encoded CBOR, or JSON following the so-called
+    /// 'detailed schema' expected by the cardano-cli.
+    #[clap(long, default_value = "cbor")]
+    to: Format,
+
+    /// Optional path to the blueprint file to be used as input.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Format {
+    Cbor,
+    Json,
+}
+
+pub fn exec(
+    Args {
+        definition,
+        from_json,
+        to,
+        input,
+    }: Args,
+) -> miette::Result<()> {
+    with_project(None, false, false, |p| {
+        eprintln!(
+            "{} blueprint",
+            "    Analyzing"
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+        );
+
+        let blueprint_input_path = p.blueprint_path(input.as_deref());
+
+        let data = p.build_data(
+            &blueprint_input_path,
+            &definition,
+            |reference, definitions| match from_json {
+                Some(ref json) => {
+                    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| {
+                        blueprint::error::Error::MalformedParameter {
+                            hint: format!("Invalid JSON: {e}"),
+                        }
+                    })?;
+
+                    Parameter::from(reference.clone()).from_json(definitions, &value)
+                }
+                None => {
+                    let schema = definitions.lookup(reference).ok_or_else(|| {
+                        blueprint::error::Error::UnresolvedSchemaReference {
+                            reference: reference.clone(),
+                        }
+                    })?;
+
+                    ask_schema(schema, definitions)
+                }
+            },
+        )?;
+
+        eprintln!(
+            "{} {}",
+            "        Built"
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+            definition.if_supports_color(Stderr, |s| s.bright_blue()),
+        );
+
+        match to {
+            Format::Cbor => {
+                let cbor = UplcData::to_hex(data);
+                print_result(p, &cbor, json!({ "cbor": cbor }));
+            }
+            Format::Json => {
+                let json = data_to_json(&data);
+                print_result(p, serde_json::to_string_pretty(&json).unwrap(), json);
+            }
+        }
+
+        Ok(())
+    })
+    .map_err(|_| std::process::exit(1))
+}
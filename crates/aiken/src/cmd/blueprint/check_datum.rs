@@ -0,0 +1,123 @@
+use aiken_project::{
+    blueprint,
+    watch::{print_result, with_project},
+};
+use owo_colors::{OwoColorize, Stream::Stderr};
+use serde_json::json;
+use std::path::PathBuf;
+use uplc::ast::Data as UplcData;
+
+/// Check a datum (or redeemer) against the schema of a validator.
+#[derive(clap::Args)]
+pub struct Args {
+    /// The datum (or redeemer), as a Plutus Data (CBOR, hex-encoded), or as JSON with `--json`.
+    ///
+    /// The data is checked against the schema declared in the project's blueprint (i.e.
+    /// `plutus.json`); its CBOR is printed out when it matches.
+    #[clap(value_name = "DATA")]
+    data: String,
+
+    /// Interpret the data as JSON, following the schema specified in the blueprint (see
+    /// `aiken blueprint apply --help`).
+    #[clap(long)]
+    json: bool,
+
+    /// Check a redeemer instead of a datum.
+    #[clap(long)]
+    redeemer: bool,
+
+    /// Optional path to the blueprint file to be used as input.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+
+    /// Name of the validator's module within the project. Optional if there's only one validator.
+    #[clap(short, long)]
+    module: Option<String>,
+
+    /// Name of the validator within the module. Optional if there's only one validator.
+    #[clap(short, long)]
+    validator: Option<String>,
+
+    /// Name of the validator's handler (e.g. 'spend' or 'mint'). Defaults to the first one
+    /// declaring a datum (resp. a redeemer).
+    #[clap(long)]
+    handler: Option<String>,
+}
+
+pub fn exec(
+    Args {
+        data,
+        json,
+        redeemer,
+        input,
+        module,
+        validator,
+        handler,
+    }: Args,
+) -> miette::Result<()> {
+    with_project(None, false, false, |p| {
+        eprintln!(
+            "{} blueprint",
+            "    Analyzing"
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+        );
+
+        let blueprint_input_path = p.blueprint_path(input.as_deref());
+
+        let (title, data) = p.check_datum(
+            module.as_deref(),
+            validator.as_deref(),
+            handler.as_deref(),
+            &blueprint_input_path,
+            redeemer,
+            |schema, definitions| {
+                if json {
+                    let value: serde_json::Value = serde_json::from_str(&data).map_err(|e| {
+                        blueprint::error::Error::MalformedParameter {
+                            hint: format!("Invalid JSON: {e}"),
+                        }
+                    })?;
+
+                    schema.from_json(definitions, &value)
+                } else {
+                    let bytes = hex::decode(&data).map_err(|e| {
+                        blueprint::error::Error::MalformedParameter {
+                            hint: format!("Invalid hex-encoded string: {e}"),
+                        }
+                    })?;
+
+                    uplc::plutus_data(&bytes).map_err(|e| {
+                        blueprint::error::Error::MalformedParameter {
+                            hint: format!("Invalid Plutus data; malformed CBOR encoding: {e}"),
+                        }
+                    })
+                }
+            },
+        )?;
+
+        eprintln!(
+            "{} {} of {}",
+            "      Checked"
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+            if redeemer { "redeemer" } else { "datum" },
+            title.if_supports_color(Stderr, |s| s.bright_blue()),
+        );
+
+        let cbor = UplcData::to_hex(data);
+
+        print_result(p, &cbor, json!({ "validator": title, "cbor": cbor }));
+
+        Ok(())
+    })
+    .map_err(|_| std::process::exit(1))
+}
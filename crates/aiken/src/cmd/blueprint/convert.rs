@@ -1,3 +1,4 @@
+use aiken_lang::plutus_version::PlutusVersion;
 use aiken_project::{
     blueprint::{error::Error as BlueprintError, Blueprint},
     config::Config,
@@ -25,11 +26,39 @@ pub struct Args {
     // Format to convert to
     #[clap(long, default_value = "cardano-cli")]
     to: Format,
+
+    /// Plutus version to convert to. With '--to blueprint', the whole blueprint is converted;
+    /// otherwise, only the chosen validator. Conversion fails when the compiled code relies on
+    /// builtins or terms unavailable in that version.
+    ///
+    /// Beware that neither the way validators are invoked, nor the script context they're given
+    /// are the same across Plutus versions. Converting is only sound for code that is oblivious
+    /// to those.
+    #[clap(long, value_name = "VERSION")]
+    plutus: Option<Plutus>,
 }
 
 #[derive(Copy, Clone, ValueEnum)]
 pub enum Format {
     CardanoCli,
+    Blueprint,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Plutus {
+    V1,
+    V2,
+    V3,
+}
+
+impl From<Plutus> for PlutusVersion {
+    fn from(plutus: Plutus) -> PlutusVersion {
+        match plutus {
+            Plutus::V1 => PlutusVersion::V1,
+            Plutus::V2 => PlutusVersion::V2,
+            Plutus::V3 => PlutusVersion::V3,
+        }
+    }
 }
 
 pub fn exec(
@@ -38,6 +67,7 @@ pub fn exec(
         module,
         validator,
         to,
+        plutus,
     }: Args,
 ) -> miette::Result<()> {
     let project_path = if let Some(d) = directory {
@@ -56,10 +86,25 @@ pub fn exec(
     let blueprint: Blueprint =
         serde_json::from_reader(BufReader::new(blueprint)).into_diagnostic()?;
 
+    let plutus = plutus.map(PlutusVersion::from);
+
+    if let Format::Blueprint = to {
+        let blueprint = match plutus {
+            Some(version) => blueprint
+                .with_plutus_version(version)
+                .map_err(miette::Report::new)?,
+            None => blueprint,
+        };
+
+        println!("{}", serde_json::to_string_pretty(&blueprint).unwrap());
+
+        return Ok(());
+    }
+
     let opt_config = Config::load(&project_path).ok();
 
-    let cardano_cli_type = opt_config
-        .map(|config| config.plutus)
+    let cardano_cli_type = plutus
+        .or(opt_config.map(|config| config.plutus))
         .unwrap_or_default()
         .cardano_cli_type();
 
@@ -75,7 +120,18 @@ pub fn exec(
         when_missing,
         |validator| match to {
             Format::CardanoCli => {
-                let cbor_bytes = validator.program.inner().to_cbor().unwrap();
+                let converted = match plutus {
+                    Some(version) => Some(validator.clone().with_plutus_version(&version)?),
+                    None => None,
+                };
+
+                let cbor_bytes = converted
+                    .as_ref()
+                    .unwrap_or(validator)
+                    .program
+                    .inner()
+                    .to_cbor()
+                    .unwrap();
 
                 let mut double_cbor_bytes = Vec::new();
 
@@ -91,6 +147,7 @@ pub fn exec(
                     "cborHex": cbor_hex
                 }))
             }
+            Format::Blueprint => unreachable!("handled above"),
         },
     );
 
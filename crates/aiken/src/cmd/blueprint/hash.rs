@@ -1,4 +1,5 @@
-use aiken_project::watch::with_project;
+use aiken_project::watch::{print_result, with_project};
+use serde_json::json;
 use std::path::PathBuf;
 
 /// Compute a validator's hash
@@ -45,7 +46,9 @@ pub fn exec(
             false,
         )?;
 
-        println!("{}", address.payment().to_hex());
+        let hash = address.payment().to_hex();
+
+        print_result(p, &hash, json!({ "hash": hash }));
 
         Ok(())
     })
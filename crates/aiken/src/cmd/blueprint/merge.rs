@@ -0,0 +1,74 @@
+use aiken_project::blueprint::{error::Error as BlueprintError, Blueprint};
+use miette::IntoDiagnostic;
+use std::{fs, fs::File, io::BufReader, path::PathBuf};
+
+/// Merge the blueprints of several packages (e.g. the members of a monorepo) into one.
+///
+/// Validator titles are prefixed with the title of the package they come from. Definitions
+/// shared by several packages are kept once; clashing ones are prefixed like validators.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Blueprints to merge, or paths to projects whose 'plutus.json' to merge.
+    #[clap(value_name = "BLUEPRINTS", required = true, num_args = 2..)]
+    inputs: Vec<PathBuf>,
+
+    /// Title of the merged blueprint. Defaults to the title of the first blueprint.
+    #[clap(long)]
+    title: Option<String>,
+
+    /// Description of the merged blueprint. Defaults to the description of the first blueprint.
+    #[clap(long)]
+    description: Option<String>,
+
+    /// Optional relative filepath to the merged blueprint. Default to printing to stdout
+    /// when omitted.
+    #[clap(short, long("out"), value_parser, value_name = "FILEPATH")]
+    output: Option<PathBuf>,
+}
+
+pub fn exec(
+    Args {
+        inputs,
+        title,
+        description,
+        output,
+    }: Args,
+) -> miette::Result<()> {
+    let blueprints = inputs
+        .into_iter()
+        .map(|input| {
+            let path = if input.is_dir() {
+                input.join("plutus.json")
+            } else {
+                input
+            };
+
+            let blueprint = File::open(path)
+                .map_err(|_| BlueprintError::InvalidOrMissingFile)
+                .into_diagnostic()?;
+
+            serde_json::from_reader(BufReader::new(blueprint)).into_diagnostic()
+        })
+        .collect::<miette::Result<Vec<Blueprint>>>()?;
+
+    let mut preamble = blueprints[0].preamble.clone();
+
+    if let Some(title) = title {
+        preamble.title = title;
+    }
+
+    if description.is_some() {
+        preamble.description = description;
+    }
+
+    let blueprint = Blueprint::merge(preamble, blueprints).map_err(miette::Report::new)?;
+
+    let json = serde_json::to_string_pretty(&blueprint).unwrap();
+
+    match output {
+        None => println!("{json}"),
+        Some(path) => fs::write(path, json).into_diagnostic()?,
+    }
+
+    Ok(())
+}
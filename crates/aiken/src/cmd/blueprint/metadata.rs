@@ -0,0 +1,42 @@
+use aiken_project::{
+    options::Options,
+    watch::{print_result, with_project},
+};
+use std::{fs, path::PathBuf};
+
+/// Generate CIP-25 metadata templates and CIP-68 datum schemas from types annotated with
+/// `@cip25` or `@cip68` in their doc comment, along with JSON schemas to validate them.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Optional relative filepath to the generated metadata. Default to printing to stdout when
+    /// omitted.
+    #[clap(short, long("out"), value_parser, value_name = "FILEPATH")]
+    output: Option<PathBuf>,
+}
+
+pub fn exec(Args { directory, output }: Args) -> miette::Result<()> {
+    with_project(directory.as_deref(), false, false, |p| {
+        p.compile(Options::default())?;
+
+        let metadata = p.metadata().map_err(|e| vec![e])?;
+
+        let json = serde_json::to_string_pretty(&metadata).unwrap();
+
+        match output {
+            None => {
+                print_result(p, json, serde_json::to_value(&metadata).unwrap());
+                Ok(())
+            }
+            Some(ref path) => fs::write(path, json).map_err(|error| {
+                vec![aiken_project::error::Error::FileIo {
+                    error,
+                    path: path.clone(),
+                }]
+            }),
+        }
+    })
+    .map_err(|_| std::process::exit(1))
+}
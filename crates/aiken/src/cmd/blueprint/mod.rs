@@ -1,9 +1,19 @@
 pub mod address;
 pub mod apply;
+pub mod build_data;
+pub mod check_datum;
 pub mod convert;
 pub mod hash;
+pub mod merge;
+pub mod metadata;
 pub mod policy;
+pub mod scaffold;
+pub mod schema;
+pub mod summary;
+pub mod verify;
 
+use super::{generate, import};
+use aiken_project::generate::{python, rust, typescript};
 use clap::Subcommand;
 
 /// Commands for working with Plutus blueprints
@@ -13,7 +23,56 @@ pub enum Cmd {
     Policy(policy::Args),
     Hash(hash::Args),
     Apply(apply::Args),
+    BuildData(build_data::Args),
+    CheckDatum(check_datum::Args),
     Convert(convert::Args),
+    Merge(merge::Args),
+    Metadata(metadata::Args),
+    Schema(schema::Args),
+    Summary(summary::Args),
+    Scaffold(scaffold::Args),
+    Verify(verify::Args),
+
+    /// Generate TypeScript types, encoders and decoders for the blueprint's definitions and
+    /// validators. Same as 'aiken generate typescript'.
+    Ts(generate::Args),
+
+    /// Generate Rust types, convertible to and from Plutus data, for the blueprint's definitions
+    /// and validators. Same as 'aiken generate rust'.
+    Rust(generate::Args),
+
+    /// Generate Python dataclasses, convertible to and from Plutus data with PyCardano, for the
+    /// blueprint's definitions and validators. Same as 'aiken generate python'.
+    Py(generate::Args),
+
+    /// Generate an Aiken module with the hashes and types of validators from a foreign
+    /// blueprint. Same as 'aiken import blueprint'.
+    Import(import::blueprint::Args),
+}
+
+impl Cmd {
+    /// The name of the command, unless it can report its outcome as JSON.
+    pub fn without_json_output(&self) -> Option<&'static str> {
+        match self {
+            Cmd::Address(..)
+            | Cmd::Policy(..)
+            | Cmd::Hash(..)
+            | Cmd::BuildData(..)
+            | Cmd::CheckDatum(..)
+            | Cmd::Metadata(..)
+            | Cmd::Schema(..)
+            | Cmd::Summary(..)
+            | Cmd::Verify(..) => None,
+            Cmd::Apply(..) => Some("blueprint apply"),
+            Cmd::Convert(..) => Some("blueprint convert"),
+            Cmd::Merge(..) => Some("blueprint merge"),
+            Cmd::Scaffold(..) => Some("blueprint scaffold"),
+            Cmd::Ts(..) => Some("blueprint ts"),
+            Cmd::Rust(..) => Some("blueprint rust"),
+            Cmd::Py(..) => Some("blueprint py"),
+            Cmd::Import(..) => Some("blueprint import"),
+        }
+    }
 }
 
 pub fn exec(cmd: Cmd) -> miette::Result<()> {
@@ -22,6 +81,18 @@ pub fn exec(cmd: Cmd) -> miette::Result<()> {
         Cmd::Policy(args) => policy::exec(args),
         Cmd::Hash(args) => hash::exec(args),
         Cmd::Apply(args) => apply::exec(args),
+        Cmd::BuildData(args) => build_data::exec(args),
+        Cmd::CheckDatum(args) => check_datum::exec(args),
         Cmd::Convert(args) => convert::exec(args),
+        Cmd::Merge(args) => merge::exec(args),
+        Cmd::Metadata(args) => metadata::exec(args),
+        Cmd::Schema(args) => schema::exec(args),
+        Cmd::Summary(args) => summary::exec(args),
+        Cmd::Scaffold(args) => scaffold::exec(args),
+        Cmd::Verify(args) => verify::exec(args),
+        Cmd::Ts(args) => generate::exec_with(args, typescript::generate),
+        Cmd::Rust(args) => generate::exec_with(args, rust::generate),
+        Cmd::Py(args) => generate::exec_with(args, python::generate),
+        Cmd::Import(args) => import::blueprint::exec(args),
     }
 }
@@ -1,4 +1,5 @@
-use aiken_project::watch::with_project;
+use aiken_project::watch::{print_result, with_project};
+use serde_json::json;
 use std::path::PathBuf;
 
 /// Compute a minting scripts Policy ID
@@ -43,7 +44,7 @@ pub fn exec(
             p.blueprint_path(input.as_deref()).as_path(),
         )?;
 
-        println!("{}", policy);
+        print_result(p, policy, json!({ "policy": policy.to_string() }));
 
         Ok(())
     })
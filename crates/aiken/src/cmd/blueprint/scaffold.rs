@@ -0,0 +1,105 @@
+use aiken_project::{
+    blueprint::{error::Error as BlueprintError, Blueprint},
+    generate::scaffold,
+};
+use clap::ValueEnum;
+use miette::IntoDiagnostic;
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::{env, fs, fs::File, io::BufReader, path::PathBuf};
+
+/// Scaffold an off-chain TypeScript project from a blueprint, with typed helpers to build
+/// transactions for each validator.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Optional path to the blueprint file to be used as input.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+
+    /// Off-chain library to build transactions with.
+    #[clap(long)]
+    target: Target,
+
+    /// Directory to create the project in. It must not exist already.
+    #[clap(
+        short,
+        long("out"),
+        value_parser,
+        value_name = "DIRECTORY",
+        default_value = "offchain"
+    )]
+    output: PathBuf,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Target {
+    Lucid,
+    Mesh,
+}
+
+impl From<Target> for scaffold::Target {
+    fn from(target: Target) -> scaffold::Target {
+        match target {
+            Target::Lucid => scaffold::Target::Lucid,
+            Target::Mesh => scaffold::Target::Mesh,
+        }
+    }
+}
+
+pub fn exec(
+    Args {
+        directory,
+        input,
+        target,
+        output,
+    }: Args,
+) -> miette::Result<()> {
+    let project_path = if let Some(d) = directory {
+        d
+    } else {
+        env::current_dir().into_diagnostic()?
+    };
+
+    let blueprint_path = input.unwrap_or_else(|| project_path.join("plutus.json"));
+
+    let blueprint = File::open(blueprint_path)
+        .map_err(|_| BlueprintError::InvalidOrMissingFile)
+        .into_diagnostic()?;
+
+    let blueprint: Blueprint =
+        serde_json::from_reader(BufReader::new(blueprint)).into_diagnostic()?;
+
+    if output.exists() {
+        miette::bail!("{} already exists", output.display());
+    }
+
+    for (path, source) in scaffold::scaffold(&blueprint, target.into()) {
+        let path = output.join(path);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).into_diagnostic()?;
+        }
+
+        fs::write(path, source).into_diagnostic()?;
+    }
+
+    eprintln!(
+        "{} {}",
+        "      Created"
+            .if_supports_color(Stderr, |s| s.purple())
+            .if_supports_color(Stderr, |s| s.bold()),
+        output.display().if_supports_color(Stderr, |s| s.blue()),
+    );
+
+    Ok(())
+}
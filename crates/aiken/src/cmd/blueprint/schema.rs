@@ -0,0 +1,86 @@
+use aiken_project::{
+    blueprint::{error::Error as BlueprintError, Blueprint},
+    watch::{default_output, Output},
+};
+use clap::ValueEnum;
+use miette::IntoDiagnostic;
+use serde_json::json;
+use std::{env, fs, fs::File, io::BufReader, path::PathBuf};
+
+/// Export the schemas of a blueprint's definitions and validators (datums, redeemers and
+/// parameters) in a standard format.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Optional path to the blueprint file to be used as input.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+
+    /// Optional relative filepath to the generated schemas. Default to printing to stdout when
+    /// omitted.
+    #[clap(short, long("out"), value_parser, value_name = "FILEPATH")]
+    output: Option<PathBuf>,
+
+    /// Format to export to. With 'json-schema', a JSON Schema (draft 2020-12) document whose
+    /// '$defs' describe values in cardano-cli's detailed JSON representation of Plutus data.
+    #[clap(long, default_value = "json-schema")]
+    format: Format,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Format {
+    JsonSchema,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        input,
+        output,
+        format,
+    }: Args,
+) -> miette::Result<()> {
+    let project_path = if let Some(d) = directory {
+        d
+    } else {
+        env::current_dir().into_diagnostic()?
+    };
+
+    let blueprint_path = input.unwrap_or_else(|| project_path.join("plutus.json"));
+
+    let blueprint = File::open(blueprint_path)
+        .map_err(|_| BlueprintError::InvalidOrMissingFile)
+        .into_diagnostic()?;
+
+    let blueprint: Blueprint =
+        serde_json::from_reader(BufReader::new(blueprint)).into_diagnostic()?;
+
+    let schema = match format {
+        Format::JsonSchema => blueprint.to_json_schema(),
+    };
+
+    let json = serde_json::to_string_pretty(&schema).unwrap();
+
+    match output {
+        None => println!("{json}"),
+        Some(path) => {
+            fs::write(&path, json).into_diagnostic()?;
+
+            if default_output() == Output::Json {
+                println!("{}", json!({ "output": path }));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use aiken_project::{
+    blueprint::{error::Error as BlueprintError, Blueprint},
+    watch::{default_output, Output},
+};
+use miette::IntoDiagnostic;
+use owo_colors::{OwoColorize, Stream::Stdout};
+use std::{env, fs::File, io::BufReader, path::PathBuf};
+
+/// Summarize the validators of a blueprint: their hash, addresses, parameters and size.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Optional path to the blueprint file to be used as input.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+
+    /// Output the summary as a single JSON document, e.g. for deployment pipelines. Addresses
+    /// are 'null' for validators with parameters left to apply.
+    #[clap(long)]
+    json: bool,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        input,
+        json,
+    }: Args,
+) -> miette::Result<()> {
+    let project_path = if let Some(d) = directory {
+        d
+    } else {
+        env::current_dir().into_diagnostic()?
+    };
+
+    let blueprint_path = input.unwrap_or_else(|| project_path.join("plutus.json"));
+
+    let blueprint = File::open(blueprint_path)
+        .map_err(|_| BlueprintError::InvalidOrMissingFile)
+        .into_diagnostic()?;
+
+    let blueprint: Blueprint =
+        serde_json::from_reader(BufReader::new(blueprint)).into_diagnostic()?;
+
+    let summary = blueprint.summary();
+
+    if json || default_output() == Output::Json {
+        println!("{}", serde_json::to_string_pretty(&summary).unwrap());
+        return Ok(());
+    }
+
+    println!(
+        "{} v{} (plutus {:?})",
+        summary
+            .title
+            .if_supports_color(Stdout, |s| s.bold())
+            .if_supports_color(Stdout, |s| s.purple()),
+        summary.version,
+        summary.plutus_version,
+    );
+
+    for validator in summary.validators {
+        println!(
+            "\n{}",
+            validator.title.if_supports_color(Stdout, |s| s.bold())
+        );
+        println!("  hash       {}", validator.hash);
+        println!("  size       {} bytes", validator.size);
+
+        match validator.address {
+            Some(address) => {
+                println!("  mainnet    {}", address.mainnet);
+                println!("  testnet    {}", address.testnet);
+            }
+            None => println!(
+                "  parameters {}",
+                validator
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.title.as_deref().unwrap_or("_"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use aiken_project::{config, watch::with_project};
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::path::PathBuf;
+
+/// Check that a blueprint was built from the project's sources, as they currently are.
+///
+/// Blueprints record a hash of the sources they were built from: the project's configuration,
+/// its manifest and its modules. Verification fails if any of them changed since.
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Optional path to the blueprint file to be used as input.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+}
+
+pub fn exec(Args { directory, input }: Args) -> miette::Result<()> {
+    with_project(directory.as_deref(), false, false, |p| {
+        eprintln!(
+            "{} blueprint",
+            "    Verifying"
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+        );
+
+        let blueprint = p.verify_blueprint(p.blueprint_path(input.as_deref()).as_path())?;
+
+        eprintln!(
+            "{} {} {}",
+            "     Verified"
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+            blueprint
+                .preamble
+                .title
+                .if_supports_color(Stderr, |s| s.bright_blue()),
+            "was built from these sources".if_supports_color(Stderr, |s| s.dimmed()),
+        );
+
+        // The same sources only yield the same validators when compiled with the same compiler.
+        let version = config::compiler_version(true);
+        match blueprint.preamble.compiler {
+            Some(compiler) if compiler.version != version => eprintln!(
+                "{} built with {} v{}, but this is v{}; validators may differ when rebuilt",
+                "      Warning"
+                    .if_supports_color(Stderr, |s| s.yellow())
+                    .if_supports_color(Stderr, |s| s.bold()),
+                compiler.name,
+                compiler.version,
+                version,
+            ),
+            _ => {}
+        }
+
+        Ok(())
+    })
+    .map_err(|_| std::process::exit(1))
+}
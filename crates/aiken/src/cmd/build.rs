@@ -1,7 +1,16 @@
 use aiken_lang::ast::{TraceLevel, Tracing};
-use aiken_project::watch::{self, watch_project, with_project};
+use aiken_project::{
+    hooks::Hook,
+    options::Emit,
+    paths,
+    telemetry::EventTarget,
+    watch::{self, watch_project, with_project},
+    Project,
+};
 use clap::builder::{MapValueParser, PossibleValuesParser, TypedValueParser};
+use owo_colors::{OwoColorize, Stream::Stderr};
 use std::{path::PathBuf, process};
+use uplc::optimize::OptimizationLevel;
 
 #[derive(clap::Args)]
 /// Build an Aiken project
@@ -13,7 +22,7 @@ pub struct Args {
     #[clap(short = 'D', long)]
     deny: bool,
 
-    /// When enabled, re-run the command on file changes instead of exiting
+    /// When enabled, re-run the command on file changes instead of exiting (skipping hooks)
     #[clap(short, long)]
     watch: bool,
 
@@ -21,10 +30,66 @@ pub struct Args {
     #[clap(short, long)]
     uplc: bool,
 
+    /// Comma-separated list of artifacts to dump to the 'artifacts' folder, alongside the
+    /// blueprint:
+    ///
+    ///   - uplc: textual UPLC of each validator (same as --uplc);
+    ///   - air:  the compiler's intermediate representation (AIR) of each validator, as JSON.
+    #[clap(long, value_delimiter = ',', value_parser = PossibleValuesParser::new(["uplc", "air"]), verbatim_doc_comment)]
+    emit: Vec<String>,
+
+    /// Optimization level of the generated validators:
+    ///
+    ///   - 0:
+    ///       no optimization beyond the mandatory passes, for fast
+    ///       builds and UPLC that stays close to the source; also
+    ///       defaults traces to 'verbose'.
+    ///
+    ///   - 1:
+    ///       a single round of reductions.
+    ///
+    ///   - 2:
+    ///       aggressive inlining and builtin currying, until nothing
+    ///       more can be reduced.
+    ///
+    ///   - size:
+    ///       same as 2, but only keep builtin currying when it makes
+    ///       scripts smaller.
+    ///
+    /// Validators with their own 'optimization' setting in aiken.toml keep it.
+    ///
+    /// [optional] [default: 2]
+    #[clap(short = 'O', value_name = "LEVEL", value_parser=optimization_parser(), verbatim_doc_comment)]
+    optimization: Option<OptimizationLevel>,
+
     /// Environment to build against.
     #[clap(long)]
     env: Option<String>,
 
+    /// Build against every environment defined by the project, each to its own blueprint named
+    /// after the environment (e.g. plutus.preview.json).
+    #[clap(long, conflicts_with = "env")]
+    all_envs: bool,
+
+    /// Comma-separated list of features to enable, on top of the default ones.
+    #[clap(long, value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Do not enable the 'default' feature.
+    #[clap(long)]
+    no_default_features: bool,
+
+    /// Record the size of each validator in 'budgets.lock', instead of comparing them against
+    /// it. Once locked, builds fail when a validator grows beyond the tolerance configured under
+    /// '[budgets]' in aiken.toml.
+    #[clap(long)]
+    lock_budgets: bool,
+
+    /// Report the time spent in each compilation phase and module, on the console and as JSON
+    /// in 'build/timings.json'.
+    #[clap(long)]
+    timings: bool,
+
     /// Optional relative filepath to the generated Plutus blueprint.
     ///
     /// [default: plutus.json]
@@ -66,9 +131,9 @@ pub struct Args {
     ///   - verbose:
     ///       enable full verbose traces as provided by the user or the compiler
     ///
-    /// [optional]
-    #[clap(short, long, value_parser=trace_level_parser(), default_value_t=TraceLevel::Silent, verbatim_doc_comment)]
-    trace_level: TraceLevel,
+    /// [optional] [default: silent, or verbose with -O0]
+    #[clap(short, long, value_parser=trace_level_parser(), verbatim_doc_comment)]
+    trace_level: Option<TraceLevel>,
 }
 
 pub fn exec(
@@ -77,36 +142,97 @@ pub fn exec(
         deny,
         watch,
         uplc,
+        emit,
+        optimization,
         trace_filter,
         trace_level,
         output,
         env,
+        all_envs,
+        features,
+        no_default_features,
+        lock_budgets,
+        timings,
     }: Args,
 ) -> miette::Result<()> {
+    let trace_level = trace_level.unwrap_or(match optimization {
+        Some(OptimizationLevel::Off) => TraceLevel::Verbose,
+        _ => TraceLevel::Silent,
+    });
+
+    let tracing = match trace_filter {
+        Some(trace_filter) => trace_filter(trace_level),
+        None => Tracing::All(trace_level),
+    };
+
+    let emit = Emit {
+        uplc: uplc || emit.iter().any(|e| e == "uplc"),
+        air: emit.iter().any(|e| e == "air"),
+    };
+
+    let build = |p: &mut Project<EventTarget>| {
+        p.with_features(&features, !no_default_features)?;
+
+        if let Some(optimization) = optimization {
+            p.with_optimization(optimization);
+        }
+
+        if lock_budgets {
+            p.lock_budgets();
+        }
+
+        let blueprint_path = p.blueprint_path(output.as_deref());
+
+        let envs = match p.environments() {
+            envs if all_envs && !envs.is_empty() => envs.into_iter().map(Some).collect(),
+            _ => vec![env.clone()],
+        };
+
+        for (ix, env) in envs.into_iter().enumerate() {
+            let blueprint_path = match env {
+                Some(ref env) if all_envs => paths::env_blueprint(&blueprint_path, env),
+                _ => blueprint_path.clone(),
+            };
+
+            // Warnings are the same for every environment, only report those of the last build.
+            if ix > 0 {
+                p.warnings();
+            }
+
+            let checkpoint = p.checkpoint();
+
+            // Hooks may write files in the project, which would re-trigger the watcher forever.
+            if !watch {
+                p.run_hook(Hook::PreBuild, &blueprint_path, env.as_deref())?;
+            }
+
+            p.build(emit, tracing, blueprint_path.clone(), env.clone())?;
+
+            if !watch {
+                p.run_hook(Hook::PostBuild, &blueprint_path, env.as_deref())?;
+            }
+
+            p.restore(checkpoint);
+        }
+
+        if timings {
+            eprintln!("{}", p.timings());
+            eprintln!(
+                "{:>13} {}",
+                "Written"
+                    .if_supports_color(Stderr, |s| s.bold())
+                    .if_supports_color(Stderr, |s| s.purple()),
+                p.write_timings()?.display()
+            );
+        }
+
+        Ok(())
+    };
+
     let result = if watch {
-        watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
-            p.build(
-                uplc,
-                match trace_filter {
-                    Some(trace_filter) => trace_filter(trace_level),
-                    None => Tracing::All(trace_level),
-                },
-                p.blueprint_path(output.as_deref()),
-                env.clone(),
-            )
-        })
+        watch_project(directory.as_deref(), watch::default_filter, 500, build)
     } else {
-        with_project(directory.as_deref(), deny, false, |p| {
-            p.build(
-                uplc,
-                match trace_filter {
-                    Some(trace_filter) => trace_filter(trace_level),
-                    None => Tracing::All(trace_level),
-                },
-                p.blueprint_path(output.as_deref()),
-                env.clone(),
-            )
-        })
+        with_project(directory.as_deref(), deny, false, build)
     };
 
     result.map_err(|_| process::exit(1))
@@ -134,3 +260,15 @@ pub fn trace_level_parser() -> MapValueParser<PossibleValuesParser, fn(String) -
         _ => unreachable!(),
     })
 }
+
+#[allow(clippy::type_complexity)]
+pub fn optimization_parser() -> MapValueParser<PossibleValuesParser, fn(String) -> OptimizationLevel>
+{
+    PossibleValuesParser::new(["0", "1", "2", "size"]).map(|s| match s.as_str() {
+        "0" => OptimizationLevel::Off,
+        "1" => OptimizationLevel::Basic,
+        "2" => OptimizationLevel::Full,
+        "size" => OptimizationLevel::Size,
+        _ => unreachable!(),
+    })
+}
@@ -5,8 +5,9 @@ use aiken_lang::{
 };
 use aiken_project::{
     telemetry::json_schema,
-    watch::{self, watch_project, with_project},
+    watch::{self, watch_project, with_project_output, Output},
 };
+use clap::ValueEnum;
 use rand::prelude::*;
 use std::{
     io::{self, IsTerminal},
@@ -22,7 +23,8 @@ Type-check an Aiken project and run any tests found.
 
 Test results are printed as stylized outputs when `stdout` is a TTY-capable terminal. If it
 isn't, (e.g. because you are redirecting the output to a file), test results are printed as
-a JSON structured object. Use `--show-json-schema` to see the whole schema.
+a JSON structured object, along with errors and warnings. Use `--format` to choose either
+explicitly, and `--show-json-schema` to see the whole schema.
 "#),
     after_long_help = color_print::cstr!(r#"You are seeing the extended help. Use `-h` instead of `--help` for a more compact view.
 "#
@@ -48,11 +50,21 @@ pub struct Args {
     #[clap(long, required = false)]
     show_json_schema: bool,
 
+    /// Output format of test results and diagnostics. Defaults to 'pretty' when stdout is a
+    /// terminal, and to 'json' otherwise. With 'junit', test results are printed as JUnit XML
+    /// (e.g. for CI test summaries), with execution budgets as properties of each test case.
+    /// With 'sarif', warnings and errors are printed as a SARIF log (e.g. for GitHub code
+    /// scanning), leaving test results out.
+    #[clap(long, alias = "report", value_name = "FORMAT")]
+    format: Option<Format>,
+
     /// When enabled, re-run the command on file changes instead of exiting
     #[clap(long)]
     watch: bool,
 
-    /// An initial seed to initialize the pseudo-random generator for property-tests.
+    /// An initial seed to initialize the pseudo-random generator for property-tests. The seed
+    /// of each run is printed before running tests; passing it again reproduces the same
+    /// samples, and thus the same failures and shrinking steps.
     #[clap(long, value_name = "UINT")]
     seed: Option<u32>,
 
@@ -60,21 +72,53 @@ pub struct Args {
     #[clap(long, default_value_t = PropertyTest::DEFAULT_MAX_SUCCESS, value_name="UINT")]
     max_success: usize,
 
+    /// Run property-based tests over every value their fuzzers can produce, when there are few
+    /// enough of them (e.g. booleans, enums or small ranges of integers). Other properties are
+    /// run from random samples as usual.
+    #[clap(long)]
+    exhaustive: bool,
+
+    /// Record the execution budget of each unit test in 'budgets.lock', instead of comparing
+    /// them against it. Once locked, checks fail when a test spends more than the tolerance
+    /// configured under '[budgets]' in aiken.toml.
+    #[clap(long)]
+    lock_budgets: bool,
+
+    /// Profile unit tests, writing a flamegraph of each one to the given directory: as an SVG
+    /// and as collapsed stacks ('.folded'), with the execution budget attributed to the Aiken
+    /// functions they call.
+    #[clap(long, value_name = "DIRECTORY")]
+    profile: Option<PathBuf>,
+
     /// Only run tests if they match any of these strings.
     /// You can match a module with `-m aiken/list` or `-m list`.
     /// You can match a test with `-m "aiken/list.{map}"` or `-m "aiken/option.{flatten_1}"`
+    /// Modules and test names may contain globs ('*' and '?'), in which case they must match
+    /// as a whole; e.g. `-m "marketplace/*"` or `-m "marketplace/*.{prop_*}"`.
     #[clap(short, long, verbatim_doc_comment)]
     match_tests: Option<Vec<String>>,
 
-    /// This is meant to be used with `--match-tests`.
-    /// It forces test names to match exactly
+    /// This is meant to be used with `--match-tests` and `--skip`.
+    /// It forces test names without globs to match exactly
     #[clap(short, long)]
     exact_match: bool,
 
+    /// Skip tests matching any of these strings, following the same syntax as `--match-tests`.
+    #[clap(long, value_name = "PATTERN")]
+    skip: Vec<String>,
+
     /// Environment to build against.
     #[clap(long)]
     env: Option<String>,
 
+    /// Comma-separated list of features to enable, on top of the default ones.
+    #[clap(long, value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Do not enable the 'default' feature.
+    #[clap(long)]
+    no_default_features: bool,
+
     /// Filter traces to be included in the generated program(s).
     ///
     ///   - user-defined:
@@ -104,6 +148,14 @@ pub struct Args {
     trace_level: TraceLevel,
 }
 
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    Pretty,
+    Json,
+    Junit,
+    Sarif,
+}
+
 pub fn exec(
     Args {
         directory,
@@ -111,14 +163,21 @@ pub fn exec(
         skip_tests,
         debug,
         show_json_schema,
+        format,
         match_tests,
         exact_match,
+        skip,
         watch,
         trace_filter,
         trace_level,
         seed,
         max_success,
+        exhaustive,
+        lock_budgets,
+        profile,
         env,
+        features,
+        no_default_features,
     }: Args,
 ) -> miette::Result<()> {
     if show_json_schema {
@@ -130,15 +189,37 @@ pub fn exec(
 
     let seed = seed.unwrap_or_else(|| rng.gen());
 
+    let output = match format {
+        Some(Format::Pretty) => Output::Pretty,
+        Some(Format::Json) => Output::Json,
+        Some(Format::Junit) => Output::Junit,
+        Some(Format::Sarif) => Output::Sarif,
+        None if io::stdout().is_terminal() => Output::Pretty,
+        None => Output::Json,
+    };
+
+    if matches!(output, Output::Json | Output::Sarif) {
+        owo_colors::set_override(false);
+    }
+
     let result = if watch {
         watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
+            p.with_features(&features, !no_default_features)?;
+
+            if lock_budgets {
+                p.lock_budgets();
+            }
+
             p.check(
                 skip_tests,
                 match_tests.clone(),
+                skip.clone(),
                 debug,
                 exact_match,
                 seed,
                 max_success,
+                exhaustive,
+                profile.clone(),
                 match trace_filter {
                     Some(trace_filter) => trace_filter(trace_level),
                     None => Tracing::All(trace_level),
@@ -147,26 +228,30 @@ pub fn exec(
             )
         })
     } else {
-        with_project(
-            directory.as_deref(),
-            deny,
-            !io::stdout().is_terminal(),
-            |p| {
-                p.check(
-                    skip_tests,
-                    match_tests.clone(),
-                    debug,
-                    exact_match,
-                    seed,
-                    max_success,
-                    match trace_filter {
-                        Some(trace_filter) => trace_filter(trace_level),
-                        None => Tracing::All(trace_level),
-                    },
-                    env.clone(),
-                )
-            },
-        )
+        with_project_output(directory.as_deref(), deny, output, |p| {
+            p.with_features(&features, !no_default_features)?;
+
+            if lock_budgets {
+                p.lock_budgets();
+            }
+
+            p.check(
+                skip_tests,
+                match_tests.clone(),
+                skip.clone(),
+                debug,
+                exact_match,
+                seed,
+                max_success,
+                exhaustive,
+                profile.clone(),
+                match trace_filter {
+                    Some(trace_filter) => trace_filter(trace_level),
+                    None => Tracing::All(trace_level),
+                },
+                env.clone(),
+            )
+        })
     };
 
     result.map_err(|_| process::exit(1))
@@ -1,5 +1,5 @@
-use crate::{cmd::Cmd as MainCmd, pretty};
-use clap::{Command, Subcommand};
+use crate::{cmd::Cli, pretty};
+use clap::Command;
 use clap_complete::{generate, Shell};
 use std::{
     fmt::{self, Display},
@@ -19,7 +19,7 @@ pub struct Args {
 fn generate_wrapper(shell: Shell, buf: &mut dyn Write) {
     let cli = Command::new("aiken").disable_version_flag(true);
 
-    let mut main = MainCmd::augment_subcommands(cli);
+    let mut main = <Cli as clap::Args>::augment_args(cli);
 
     generate(shell, &mut main, "aiken".to_string(), buf);
 }
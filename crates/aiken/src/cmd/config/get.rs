@@ -0,0 +1,34 @@
+use aiken_project::watch::{default_output, Output};
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+/// Print the value(s) under a configuration key
+pub struct Args {
+    /// A dotted key, such as 'plutus' or 'dependencies.0.version'. Keys designating a table
+    /// print every value within that table.
+    key: String,
+}
+
+pub fn exec(args: Args) -> miette::Result<()> {
+    let config = super::load(&PathBuf::from("."));
+
+    let entries = config.entries(Some(&args.key));
+
+    if default_output() == Output::Json {
+        super::print_entries_json(&entries);
+        return if entries.is_empty() {
+            process::exit(1)
+        } else {
+            Ok(())
+        };
+    }
+
+    match &entries[..] {
+        [] => process::exit(1),
+        [(key, toml::Value::String(value))] if key == &args.key => println!("{value}"),
+        [(key, value)] if key == &args.key => println!("{value}"),
+        entries => super::print_entries(entries),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use aiken_project::watch::{default_output, Output};
+use std::path::PathBuf;
+
+pub fn exec() -> miette::Result<()> {
+    let config = super::load(&PathBuf::from("."));
+
+    let entries = config.entries(None);
+
+    if default_output() == Output::Json {
+        super::print_entries_json(&entries);
+    } else {
+        super::print_entries(&entries);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+pub mod get;
+pub mod list;
+pub mod set;
+
+use aiken_project::config::Config;
+use clap::Subcommand;
+use std::{path::Path, process};
+
+/// Read or change the project's configuration
+#[derive(Subcommand)]
+pub enum Cmd {
+    /// Print the value(s) under a configuration key
+    Get(get::Args),
+
+    /// Change the value of a configuration key
+    Set(set::Args),
+
+    /// Print all configuration keys and their values
+    List,
+}
+
+impl Cmd {
+    /// The name of the command, unless it can report its outcome as JSON.
+    pub fn without_json_output(&self) -> Option<&'static str> {
+        match self {
+            Cmd::Get(..) | Cmd::List => None,
+            Cmd::Set(..) => Some("config set"),
+        }
+    }
+}
+
+pub fn exec(cmd: Cmd) -> miette::Result<()> {
+    match cmd {
+        Cmd::Get(args) => get::exec(args),
+        Cmd::Set(args) => set::exec(args),
+        Cmd::List => list::exec(),
+    }
+}
+
+fn load(root: &Path) -> Config {
+    match Config::load(root) {
+        Ok(config) => config,
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    }
+}
+
+fn print_entries(entries: &[(String, toml::Value)]) {
+    for (key, value) in entries {
+        println!("{key} = {value}");
+    }
+}
+
+fn print_entries_json(entries: &[(String, toml::Value)]) {
+    let entries = entries
+        .iter()
+        .map(|(key, value)| (key.clone(), serde_json::to_value(value).unwrap()))
+        .collect::<serde_json::Map<_, _>>();
+
+    println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+}
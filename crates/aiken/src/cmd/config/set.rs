@@ -0,0 +1,34 @@
+use aiken_project::{config::Config, pretty};
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+/// Change the value of a configuration key
+pub struct Args {
+    /// A dotted key, such as 'plutus' or 'dependencies.0.version'.
+    key: String,
+
+    /// The new value, as a TOML value (e.g. 'true', '42' or '["foo"]'). Anything else is taken
+    /// as a plain string.
+    value: String,
+}
+
+pub fn exec(args: Args) -> miette::Result<()> {
+    let root = PathBuf::from(".");
+
+    if let Err(e) = Config::set_in_place(&root, &args.key, &args.value) {
+        e.report();
+        process::exit(1);
+    }
+
+    eprintln!(
+        "{} {} → {}",
+        pretty::pad_left("Changed".to_string(), 13, " ")
+            .if_supports_color(Stderr, |s| s.purple())
+            .if_supports_color(Stderr, |s| s.bold()),
+        args.key,
+        args.value.if_supports_color(Stderr, |s| s.yellow())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use super::build::{trace_filter_parser, trace_level_parser};
+use aiken_lang::{
+    ast::{TraceLevel, Tracing},
+    test_framework::PropertyTest,
+};
+use aiken_project::{
+    coverage::DEFAULT_OUTPUT,
+    watch::{self, watch_project, with_project},
+};
+use rand::prelude::*;
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+#[command(
+    verbatim_doc_comment,
+    about = color_print::cstr!(r#"
+Run the tests of an Aiken project, and report which parts of its code they cover.
+
+Function bodies and branches of 'if' and 'when' expressions are instrumented while running
+tests. Coverage is then reported for the functions and validators of the project (but not of
+its dependencies) by lines, branches and functions:
+
+  - as an lcov tracefile ('lcov.info'), for genhtml or coverage services;
+  - as an HTML page ('index.html'), with the source of each module annotated.
+
+Execution budgets of instrumented tests aren't representative, and thus not reported.
+"#),
+    after_long_help = color_print::cstr!(r#"You are seeing the extended help. Use `-h` instead of `--help` for a more compact view.
+"#
+))]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Deny warnings; warnings will be treated as errors
+    #[clap(short = 'D', long)]
+    deny: bool,
+
+    /// When enabled, re-run the command on file changes instead of exiting
+    #[clap(long)]
+    watch: bool,
+
+    /// Directory where to write the reports, relative to the project's root.
+    #[clap(short, long, default_value = DEFAULT_OUTPUT, value_name = "DIRECTORY")]
+    out: PathBuf,
+
+    /// An initial seed to initialize the pseudo-random generator for property-tests.
+    #[clap(long, value_name = "UINT")]
+    seed: Option<u32>,
+
+    /// Maximum number of successful test run for considering a property-based test valid.
+    #[clap(long, default_value_t = PropertyTest::DEFAULT_MAX_SUCCESS, value_name="UINT")]
+    max_success: usize,
+
+    /// Only run tests if they match any of these strings, following the same syntax as
+    /// `aiken check --match-tests`.
+    #[clap(short, long)]
+    match_tests: Option<Vec<String>>,
+
+    /// This is meant to be used with `--match-tests` and `--skip`.
+    /// It forces test names without globs to match exactly
+    #[clap(short, long)]
+    exact_match: bool,
+
+    /// Skip tests matching any of these strings, following the same syntax as `--match-tests`.
+    #[clap(long, value_name = "PATTERN")]
+    skip: Vec<String>,
+
+    /// Environment to build against.
+    #[clap(long)]
+    env: Option<String>,
+
+    /// Comma-separated list of features to enable, on top of the default ones.
+    #[clap(long, value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Do not enable the 'default' feature.
+    #[clap(long)]
+    no_default_features: bool,
+
+    /// Filter traces to be included in the generated program(s).
+    ///
+    ///   - user-defined:
+    ///       only consider traces that you've explicitly defined
+    ///       either through the 'trace' keyword of via the trace-if-false
+    ///       ('?') operator.
+    ///
+    ///   - compiler-generated:
+    ///       only included internal traces generated by the
+    ///       Aiken compiler, for example in usage of 'expect'.
+    ///
+    ///   - all:
+    ///       include both user-defined and compiler-generated traces.
+    ///
+    /// [default: all]
+    #[clap(short = 'f', long, value_parser=trace_filter_parser(), default_missing_value="all", verbatim_doc_comment, alias="filter_traces")]
+    trace_filter: Option<fn(TraceLevel) -> Tracing>,
+
+    /// Choose the verbosity level of traces:
+    ///
+    ///   - silent: disable traces altogether
+    ///   - compact: only culprit line numbers are shown on failures
+    ///   - verbose: enable full verbose traces as provided by the user or the compiler
+    ///
+    /// [optional]
+    #[clap(short, long, value_parser=trace_level_parser(), default_value_t=TraceLevel::Verbose, verbatim_doc_comment)]
+    trace_level: TraceLevel,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        deny,
+        watch,
+        out,
+        seed,
+        max_success,
+        match_tests,
+        exact_match,
+        skip,
+        env,
+        features,
+        no_default_features,
+        trace_filter,
+        trace_level,
+    }: Args,
+) -> miette::Result<()> {
+    let mut rng = rand::thread_rng();
+
+    let seed = seed.unwrap_or_else(|| rng.gen());
+
+    let tracing = match trace_filter {
+        Some(trace_filter) => trace_filter(trace_level),
+        None => Tracing::All(trace_level),
+    };
+
+    let result = if watch {
+        watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
+            p.with_features(&features, !no_default_features)?;
+            p.coverage(
+                match_tests.clone(),
+                skip.clone(),
+                exact_match,
+                seed,
+                max_success,
+                Some(out.clone()),
+                tracing,
+                env.clone(),
+            )
+        })
+    } else {
+        with_project(directory.as_deref(), deny, false, |p| {
+            p.with_features(&features, !no_default_features)?;
+            p.coverage(
+                match_tests.clone(),
+                skip.clone(),
+                exact_match,
+                seed,
+                max_success,
+                Some(out.clone()),
+                tracing,
+                env.clone(),
+            )
+        })
+    };
+
+    result.map_err(|_| process::exit(1))
+}
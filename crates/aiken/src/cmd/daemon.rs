@@ -0,0 +1,181 @@
+use aiken_project::{
+    daemon,
+    watch::{default_output, Output},
+};
+use clap::Subcommand;
+use miette::IntoDiagnostic;
+use rand::prelude::*;
+use serde_json::json;
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream},
+    path::PathBuf,
+    process,
+};
+
+/// Keep a project in memory, so that builds, checks and test runs sent to it skip the cold start
+///
+/// The daemon holds the parsed and type-checked modules of the project between requests, and
+/// only re-checks modules affected by changes. Other commands send it a request, and print its
+/// response as a JSON document on stdout, as with 'aiken --output json'. 'aiken build' and
+/// 'aiken check' don't go through the daemon.
+///
+/// Requests must carry the token the daemon records in 'build/daemon.json', along with its
+/// address; only the owner of the project can read that file.
+#[derive(Subcommand)]
+#[clap(verbatim_doc_comment)]
+pub enum Cmd {
+    /// Start a daemon for the project, running until stopped
+    Start(StartArgs),
+
+    /// Build the project's validators, and write the blueprint
+    Build(RequestArgs),
+
+    /// Type-check the project, without running tests
+    Check(RequestArgs),
+
+    /// Run the project's tests
+    Test(TestArgs),
+
+    /// Stop the daemon of the project
+    Stop(RequestArgs),
+}
+
+#[derive(clap::Args)]
+pub struct StartArgs {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Port to listen on, on the local interface; any free port by default
+    #[clap(short, long, default_value_t = 0)]
+    port: u16,
+}
+
+#[derive(clap::Args)]
+pub struct RequestArgs {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Environment to build against.
+    #[clap(long)]
+    env: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct TestArgs {
+    #[clap(flatten)]
+    request: RequestArgs,
+
+    /// Only run tests if they match any of these strings.
+    #[clap(short, long)]
+    match_tests: Option<Vec<String>>,
+
+    /// This is meant to be used with `--match-tests`.
+    /// It forces test names to match exactly
+    #[clap(short, long)]
+    exact_match: bool,
+
+    /// An initial seed to initialize the pseudo-random generator for property-tests.
+    #[clap(long, value_name = "UINT")]
+    seed: Option<u32>,
+}
+
+impl Cmd {
+    /// The name of the command, unless it can report its outcome as JSON.
+    pub fn without_json_output(&self) -> Option<&'static str> {
+        match self {
+            Cmd::Build(..) | Cmd::Check(..) | Cmd::Test(..) | Cmd::Stop(..) => None,
+            Cmd::Start(..) => Some("daemon start"),
+        }
+    }
+}
+
+pub fn exec(cmd: Cmd) -> miette::Result<()> {
+    match cmd {
+        Cmd::Start(StartArgs { directory, port }) => {
+            let root = root(directory)?;
+
+            if let Some(lock) = daemon::lock(&root) {
+                if TcpStream::connect(lock.address).is_ok() {
+                    eprintln!(
+                        "A daemon is already running for this project, on {}.",
+                        lock.address
+                    );
+                    process::exit(1);
+                }
+            }
+
+            daemon::run(root, SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)).map_err(|e| {
+                e.report();
+                process::exit(1)
+            })
+        }
+        Cmd::Build(RequestArgs { directory, env }) => {
+            send(directory, json!({ "command": "build", "env": env }))
+        }
+        Cmd::Check(RequestArgs { directory, env }) => {
+            send(directory, json!({ "command": "check", "env": env }))
+        }
+        Cmd::Test(TestArgs {
+            request: RequestArgs { directory, env },
+            match_tests,
+            exact_match,
+            seed,
+        }) => {
+            let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+
+            send(
+                directory,
+                json!({
+                    "command": "test",
+                    "env": env,
+                    "seed": seed,
+                    "match_tests": match_tests,
+                    "exact_match": exact_match,
+                }),
+            )
+        }
+        Cmd::Stop(RequestArgs { directory, .. }) => send(directory, json!({ "command": "stop" })),
+    }
+}
+
+fn root(directory: Option<PathBuf>) -> miette::Result<PathBuf> {
+    match directory {
+        Some(directory) => Ok(directory),
+        None => env::current_dir().into_diagnostic(),
+    }
+}
+
+fn send(directory: Option<PathBuf>, request: serde_json::Value) -> miette::Result<()> {
+    let root = root(directory)?;
+
+    let Some(lock) = daemon::lock(&root) else {
+        let reason = "No daemon is running for this project; start one with 'aiken daemon start'.";
+        if default_output() == Output::Json {
+            println!("{}", json!({ "ok": false, "error": reason }));
+        } else {
+            eprintln!("{reason}");
+        }
+        process::exit(1);
+    };
+
+    let response = match daemon::request(&lock, &request) {
+        Ok(response) => response,
+        Err(e) if default_output() == Output::Json => {
+            println!("{}", json!({ "ok": false, "error": e.to_string() }));
+            process::exit(1);
+        }
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+
+    if response["ok"] != json!(true) {
+        process::exit(1);
+    }
+
+    Ok(())
+}
@@ -1,5 +1,15 @@
-use aiken_project::watch::{self, watch_project, with_project};
-use std::{path::PathBuf, process};
+use aiken_project::{
+    docs::server,
+    pretty,
+    watch::{self, watch_project, with_project},
+};
+use miette::IntoDiagnostic;
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    process,
+};
 
 #[derive(clap::Args)]
 /// Build the documentation for an Aiken project
@@ -22,6 +32,19 @@ pub struct Args {
     /// Output directory for the documentation
     #[clap(short = 'o', long)]
     destination: Option<PathBuf>,
+
+    /// Serve the documentation over HTTP, re-generating it and reloading pages open in a browser
+    /// on file changes
+    #[clap(long, conflicts_with = "watch")]
+    serve: bool,
+
+    /// Address to listen on, with '--serve'
+    #[clap(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST), requires = "serve")]
+    host: IpAddr,
+
+    /// Port to listen on, with '--serve'
+    #[clap(short, long, default_value_t = 8000, requires = "serve")]
+    port: u16,
 }
 
 pub fn exec(
@@ -31,8 +54,39 @@ pub fn exec(
         watch,
         destination,
         include_dependencies,
+        serve,
+        host,
+        port,
     }: Args,
 ) -> miette::Result<()> {
+    if serve {
+        let destination = destination.unwrap_or_else(|| {
+            directory
+                .clone()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("docs")
+        });
+
+        let (address, live_reload) =
+            server::serve(destination.clone(), SocketAddr::new(host, port)).into_diagnostic()?;
+
+        return watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
+            p.docs(Some(destination.clone()), include_dependencies)?;
+
+            live_reload.reload();
+
+            eprintln!(
+                "{} documentation on http://{address}",
+                pretty::pad_left("Serving".to_string(), 13, " ")
+                    .if_supports_color(Stderr, |s| s.purple())
+                    .if_supports_color(Stderr, |s| s.bold()),
+            );
+
+            Ok(())
+        })
+        .map_err(|_| process::exit(1));
+    }
+
     let result = if watch {
         watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
             p.docs(destination.clone(), include_dependencies)
@@ -0,0 +1,47 @@
+use aiken_lang::error::ErrorCode;
+use aiken_project::watch::{default_output, Output};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use serde_json::json;
+
+#[derive(clap::Args)]
+/// Explain a diagnostic in detail, with examples and common fixes
+pub struct Args {
+    /// Code of the diagnostic (e.g. AK0513). Lists all known codes when omitted.
+    code: Option<ErrorCode>,
+}
+
+pub fn exec(Args { code }: Args) -> miette::Result<()> {
+    if default_output() == Output::Json {
+        let explain = |code: ErrorCode| {
+            json!({
+                "code": code.to_string(),
+                "summary": code.summary(),
+                "explanation": code.explanation(),
+            })
+        };
+
+        let json = match code {
+            Some(code) => explain(code),
+            None => json!(ErrorCode::all().map(explain).collect::<Vec<_>>()),
+        };
+
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+
+        return Ok(());
+    }
+
+    match code {
+        Some(code) => print!("{}", code.explanation()),
+        None => {
+            for code in ErrorCode::all() {
+                println!(
+                    "{}  {}",
+                    code.if_supports_color(Stdout, |s| s.bold()),
+                    code.summary()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,11 +1,23 @@
 use super::build::{trace_filter_parser, trace_level_parser};
 use aiken_lang::ast::{TraceLevel, Tracing};
-use aiken_project::{options::Options, watch::with_project};
-use std::path::PathBuf;
+use aiken_project::{
+    error::Error,
+    options::Options,
+    watch::{default_output, print_result, with_project, Output},
+};
+use clap::ValueEnum;
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
 
 #[derive(clap::Args)]
 /// Export a function as a standalone UPLC program. Arguments to the function can be applied using
 /// `aiken apply`.
+///
+/// By default, prints a blueprint-like description of the function: its Plutus version, the
+/// schemas of its parameters and of its return value, and its compiled code.
 pub struct Args {
     /// Path to project
     directory: Option<PathBuf>,
@@ -50,6 +62,26 @@ pub struct Args {
     /// [optional]
     #[clap(short, long, value_parser=trace_level_parser(), default_value_t=TraceLevel::Verbose, verbatim_doc_comment)]
     trace_level: TraceLevel,
+
+    /// Output format:
+    ///
+    ///   - json:
+    ///       the function's description, including its compiled code
+    ///
+    ///   - cbor:
+    ///       the compiled code alone, as hex-encoded CBOR (e.g. for 'aiken uplc eval --cbor')
+    ///
+    ///   - flat:
+    ///       the program alone, as raw flat bytes (e.g. for 'aiken uplc eval --flat')
+    #[clap(long, default_value = "json", verbatim_doc_comment)]
+    format: Format,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Format {
+    Json,
+    Cbor,
+    Flat,
 }
 
 pub fn exec(
@@ -59,6 +91,7 @@ pub fn exec(
         name,
         trace_filter,
         trace_level,
+        format,
     }: Args,
 ) -> miette::Result<()> {
     with_project(directory.as_deref(), false, false, |p| {
@@ -73,9 +106,33 @@ pub fn exec(
             },
         )?;
 
-        let json = serde_json::to_string_pretty(&export).unwrap();
+        match format {
+            Format::Json => {
+                let json = serde_json::to_string_pretty(&export).unwrap();
+
+                print_result(p, json, serde_json::to_value(&export).unwrap());
+            }
+            Format::Cbor => {
+                let (compiled_code, _) = export.program.compiled_code_and_hash();
+
+                print_result(p, &compiled_code, json!({ "cbor": compiled_code }));
+            }
+            Format::Flat if default_output() == Output::Json => {
+                let bytes = export.program.inner().to_flat().unwrap();
+
+                print_result(p, "", json!({ "flat": hex::encode(bytes) }));
+            }
+            Format::Flat => {
+                let bytes = export.program.inner().to_flat().unwrap();
+
+                let mut stdout = io::stdout();
 
-        println!("{}", json);
+                stdout
+                    .write_all(&bytes)
+                    .and_then(|_| stdout.flush())
+                    .map_err(|error| vec![Error::StandardIo(error)])?;
+            }
+        }
 
         Ok(())
     })
@@ -1,3 +1,10 @@
+use aiken_project::{
+    config::{Config, Fmt},
+    error::Error,
+    format::local_modules,
+};
+use std::{path::Path, process};
+
 #[derive(clap::Args)]
 /// Format an Aiken project
 pub struct Args {
@@ -12,16 +19,44 @@ pub struct Args {
     /// Check if inputs are formatted without changing them
     #[clap(long)]
     check: bool,
+
+    /// Number of unchanged lines shown around each change, in the diff printed by --check
+    #[clap(long, value_name = "LINES", default_value_t = 3, requires = "check")]
+    diff_context: usize,
+
+    /// Maximum width of lines [default: the project's 'fmt.max_width', or 80]
+    #[clap(long, value_name = "COLUMNS")]
+    max_width: Option<usize>,
 }
 
 pub fn exec(
     Args {
         check,
+        diff_context,
         stdin,
         files,
+        max_width,
     }: Args,
 ) -> miette::Result<()> {
-    if let Err(errs) = aiken_project::format::run(stdin, check, files) {
+    let root = Path::new(".");
+
+    // Formatting works outside of projects too, so a missing manifest isn't a problem.
+    let fmt = match Config::load(root) {
+        Ok(config) => config.fmt,
+        Err(Error::MissingManifest { .. }) => Fmt::default(),
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    let mut options = fmt.options(local_modules(root));
+
+    if let Some(max_width) = max_width {
+        options.max_width = max_width as isize;
+    }
+
+    if let Err(errs) = aiken_project::format::run(stdin, check, diff_context, files, &options) {
         for err in &errs {
             err.report();
         }
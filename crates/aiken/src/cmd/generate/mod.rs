@@ -0,0 +1,84 @@
+use aiken_project::{
+    blueprint::{error::Error as BlueprintError, Blueprint},
+    generate,
+};
+use clap::Subcommand;
+use miette::IntoDiagnostic;
+use std::{env, fs, fs::File, io::BufReader, path::PathBuf};
+
+/// Generate off-chain bindings from a Plutus blueprint
+#[derive(Subcommand)]
+pub enum Cmd {
+    /// Generate a TypeScript module with types, encoders, decoders and helpers for each validator
+    Typescript(Args),
+
+    /// Generate a Rust module with types, Plutus data conversions and helpers for each validator
+    Rust(Args),
+
+    /// Generate a Python module with PyCardano data classes and helpers for each validator
+    Python(Args),
+}
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Optional path to the blueprint file to be used as input.
+    ///
+    /// [default: plutus.json]
+    #[clap(
+        short,
+        long = "in",
+        value_parser,
+        value_name = "FILEPATH",
+        verbatim_doc_comment
+    )]
+    input: Option<PathBuf>,
+
+    /// Optional relative filepath to the generated module. Default to printing to stdout when
+    /// omitted.
+    #[clap(short, long("out"), value_parser, value_name = "FILEPATH")]
+    output: Option<PathBuf>,
+}
+
+pub fn exec(cmd: Cmd) -> miette::Result<()> {
+    match cmd {
+        Cmd::Typescript(args) => exec_with(args, generate::typescript::generate),
+        Cmd::Rust(args) => exec_with(args, generate::rust::generate),
+        Cmd::Python(args) => exec_with(args, generate::python::generate),
+    }
+}
+
+pub fn exec_with(
+    Args {
+        directory,
+        input,
+        output,
+    }: Args,
+    generate: fn(&Blueprint) -> String,
+) -> miette::Result<()> {
+    let project_path = if let Some(d) = directory {
+        d
+    } else {
+        env::current_dir().into_diagnostic()?
+    };
+
+    let blueprint_path = input.unwrap_or_else(|| project_path.join("plutus.json"));
+
+    let blueprint = File::open(blueprint_path)
+        .map_err(|_| BlueprintError::InvalidOrMissingFile)
+        .into_diagnostic()?;
+
+    let blueprint: Blueprint =
+        serde_json::from_reader(BufReader::new(blueprint)).into_diagnostic()?;
+
+    let source = generate(&blueprint);
+
+    match output {
+        None => print!("{source}"),
+        Some(path) => fs::write(path, source).into_diagnostic()?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use aiken_project::{
+    options::Options,
+    watch::{print_result, with_project},
+};
+use clap::ValueEnum;
+use std::path::PathBuf;
+
+#[derive(clap::Args)]
+/// Print the dependency graph of a project's modules and packages
+///
+/// Each module comes with the modules it imports, and each package with the packages it depends
+/// on through those imports. Useful for auditing what code ends up on-chain.
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Only show what a validator (e.g. 'vault.spend_vault') transitively imports, starting
+    /// from its module
+    #[clap(short, long, value_name = "MODULE.NAME")]
+    validator: Option<String>,
+
+    /// Output format:
+    ///
+    ///   - dot:
+    ///       a Graphviz digraph, modules clustered by package (e.g. for 'dot -Tsvg')
+    ///
+    ///   - json:
+    ///       modules with their imports, and packages with their dependencies
+    #[clap(long, default_value = "dot", verbatim_doc_comment)]
+    format: Format,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Format {
+    Dot,
+    Json,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        validator,
+        format,
+    }: Args,
+) -> miette::Result<()> {
+    with_project(directory.as_deref(), false, false, |p| {
+        p.compile(Options::default())?;
+
+        let graph = p.module_graph(validator.as_deref())?;
+
+        let json = graph.to_json();
+
+        match format {
+            Format::Dot => print_result(p, graph.to_dot().trim_end(), json),
+            Format::Json => print_result(p, serde_json::to_string_pretty(&json).unwrap(), json),
+        }
+
+        Ok(())
+    })
+    .map_err(|_| std::process::exit(1))
+}
@@ -0,0 +1,37 @@
+use aiken_project::{
+    blueprint::{error::Error as BlueprintError, Blueprint},
+    generate,
+};
+use miette::IntoDiagnostic;
+use std::{fs, fs::File, io::BufReader, path::PathBuf};
+
+/// Generate an Aiken module with the hashes and types of validators from a foreign blueprint
+#[derive(clap::Args)]
+pub struct Args {
+    /// Path to the blueprint to import (e.g. other.plutus.json)
+    #[clap(value_parser, value_name = "FILEPATH")]
+    input: PathBuf,
+
+    /// Optional relative filepath to the generated module (e.g. lib/other.ak). Default to
+    /// printing to stdout when omitted.
+    #[clap(short, long("out"), value_parser, value_name = "FILEPATH")]
+    output: Option<PathBuf>,
+}
+
+pub fn exec(Args { input, output }: Args) -> miette::Result<()> {
+    let blueprint = File::open(input)
+        .map_err(|_| BlueprintError::InvalidOrMissingFile)
+        .into_diagnostic()?;
+
+    let blueprint: Blueprint =
+        serde_json::from_reader(BufReader::new(blueprint)).into_diagnostic()?;
+
+    let source = generate::aiken::generate(&blueprint);
+
+    match output {
+        None => print!("{source}"),
+        Some(path) => fs::write(path, source).into_diagnostic()?,
+    }
+
+    Ok(())
+}
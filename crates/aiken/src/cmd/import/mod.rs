@@ -0,0 +1,15 @@
+pub mod blueprint;
+
+use clap::Subcommand;
+
+/// Import validators built elsewhere
+#[derive(Subcommand)]
+pub enum Cmd {
+    Blueprint(blueprint::Args),
+}
+
+pub fn exec(cmd: Cmd) -> miette::Result<()> {
+    match cmd {
+        Cmd::Blueprint(args) => blueprint::exec(args),
+    }
+}
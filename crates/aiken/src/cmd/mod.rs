@@ -1,25 +1,68 @@
-use aiken_project::config;
-use clap::Parser;
+use aiken_project::{error::Error, telemetry::fmt_diagnostic_json, watch::Output};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use std::process;
 
+pub mod audit;
+pub mod bench;
 pub mod blueprint;
 pub mod build;
 pub mod check;
+pub mod config;
+pub mod coverage;
+pub mod daemon;
 // only windows
 #[cfg(not(target_os = "windows"))]
 pub mod completion;
 pub mod docs;
+pub mod explain;
 pub mod export;
 pub mod fmt;
+pub mod generate;
+pub mod graph;
+pub mod import;
 pub mod lsp;
 pub mod new;
 pub mod packages;
+pub mod repl;
+pub mod serve;
 pub mod tx;
 pub mod uplc;
+pub mod verify;
 
 /// Aiken: a smart-contract language and toolchain for Cardano
 #[derive(Parser)]
-#[clap(version = config::compiler_version(true), about, long_about = None)]
+#[clap(version = aiken_project::config::compiler_version(true), about, long_about = None)]
 #[clap(propagate_version = true)]
+pub struct Cli {
+    /// How commands report their outcome on stdout. With 'json', each command prints a single
+    /// JSON document instead of human-readable text: diagnostics, test results and whatever the
+    /// command computes (e.g. an address), for wrappers and editors to drive the toolchain.
+    /// Progress messages still go to stderr. Commands that only report for humans (e.g.
+    /// 'aiken fmt' or 'aiken uplc eval') refuse to run, with a diagnostic as JSON.
+    #[clap(long = "output", global = true, value_name = "FORMAT")]
+    output_format: Option<OutputFormat>,
+
+    #[clap(subcommand)]
+    pub cmd: Cmd,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum OutputFormat {
+    Pretty,
+    Json,
+}
+
+impl From<OutputFormat> for Output {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Pretty => Output::Pretty,
+            OutputFormat::Json => Output::Json,
+        }
+    }
+}
+
+#[derive(Subcommand)]
 pub enum Cmd {
     New(new::Args),
     Fmt(fmt::Args),
@@ -30,14 +73,36 @@ pub enum Cmd {
     Build(build::Args),
     Address(blueprint::address::Args),
 
-    #[clap(visible_alias("c"))]
+    #[clap(visible_alias("c"), visible_alias("test"))]
     Check(check::Args),
+    Bench(bench::Args),
+    Coverage(coverage::Args),
+    Verify(verify::Args),
     Docs(docs::Args),
+    Graph(graph::Args),
+
+    #[clap(disable_version_flag = true)]
     Add(packages::add::Args),
+    Audit(audit::Args),
+    Explain(explain::Args),
+    Serve(serve::Args),
+    Repl(repl::Args),
 
     #[clap(subcommand)]
     Blueprint(blueprint::Cmd),
 
+    #[clap(subcommand)]
+    Config(config::Cmd),
+
+    #[clap(subcommand)]
+    Daemon(daemon::Cmd),
+
+    #[clap(subcommand)]
+    Generate(generate::Cmd),
+
+    #[clap(subcommand)]
+    Import(import::Cmd),
+
     #[clap(subcommand)]
     Packages(packages::Cmd),
 
@@ -55,8 +120,68 @@ pub enum Cmd {
     Lsp(lsp::Args),
 }
 
-impl Default for Cmd {
+impl Default for Cli {
     fn default() -> Self {
         Self::parse()
     }
 }
+
+impl Cli {
+    /// Parse the command line, and apply global options to the rest of the process.
+    pub fn init() -> Cmd {
+        let Cli { output_format, cmd } = Self::default();
+
+        if let Some(format) = output_format {
+            aiken_project::watch::set_default_output(format.into());
+        }
+
+        // Commands printing for humans only would otherwise break whatever parses their output.
+        if let (Some(OutputFormat::Json), Some(command)) =
+            (output_format, cmd.without_json_output())
+        {
+            let error = Error::UnsupportedOutput {
+                command: command.to_string(),
+            };
+            let report = json!({ "diagnostics": [fmt_diagnostic_json(&error)] });
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            process::exit(1);
+        }
+
+        cmd
+    }
+}
+
+impl Cmd {
+    /// The name of the command, unless it can report its outcome as JSON.
+    pub fn without_json_output(&self) -> Option<&'static str> {
+        match self {
+            Cmd::Build(..)
+            | Cmd::Address(..)
+            | Cmd::Check(..)
+            | Cmd::Bench(..)
+            | Cmd::Coverage(..)
+            | Cmd::Verify(..)
+            | Cmd::Docs(..)
+            | Cmd::Graph(..)
+            | Cmd::Explain(..)
+            | Cmd::Export(..) => None,
+            Cmd::Blueprint(cmd) => cmd.without_json_output(),
+            Cmd::Config(cmd) => cmd.without_json_output(),
+            Cmd::Daemon(cmd) => cmd.without_json_output(),
+            Cmd::Packages(cmd) => cmd.without_json_output(),
+            Cmd::New(..) => Some("new"),
+            Cmd::Fmt(..) => Some("fmt"),
+            Cmd::Add(..) => Some("add"),
+            Cmd::Audit(..) => Some("audit"),
+            Cmd::Serve(..) => Some("serve"),
+            Cmd::Repl(..) => Some("repl"),
+            Cmd::Generate(..) => Some("generate"),
+            Cmd::Import(..) => Some("import"),
+            Cmd::Tx(..) => Some("tx"),
+            Cmd::Uplc(..) => Some("uplc"),
+            #[cfg(not(target_os = "windows"))]
+            Cmd::Completion(..) => Some("completion"),
+            Cmd::Lsp(..) => Some("lsp"),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+mod template;
+
 use aiken_project::{
     config::{self, Config},
     package_name::{self, PackageName},
@@ -8,8 +10,10 @@ use owo_colors::{OwoColorize, Stream::Stderr};
 use std::{
     fs,
     path::{Path, PathBuf},
+    process::{self, Command},
     str::FromStr,
 };
+use template::Template;
 
 #[derive(clap::Args)]
 /// Create a new Aiken project
@@ -19,6 +23,30 @@ pub struct Args {
     /// Library only
     #[clap(long, short)]
     lib: bool,
+    /// Start from a template rather than a placeholder validator:
+    ///
+    ///   - empty:
+    ///       no validator at all
+    ///
+    ///   - vesting:
+    ///       funds locked until a given time, for a beneficiary
+    ///
+    ///   - nft-mint:
+    ///       a one-shot minting policy, for a unique token
+    ///
+    ///   - multisig:
+    ///       funds spendable with enough signatures, out of a set
+    ///
+    /// Templates may also be cloned from a git repository holding an Aiken project, given its
+    /// URL (e.g. 'https://github.com/<owner>/<repo>.git').
+    #[clap(
+        long,
+        short,
+        value_name = "TEMPLATE",
+        conflicts_with = "lib",
+        verbatim_doc_comment
+    )]
+    template: Option<Template>,
 }
 
 pub fn exec(args: Args) -> miette::Result<()> {
@@ -37,14 +65,18 @@ fn create_project(args: Args, package_name: &PackageName) -> miette::Result<()>
         })?;
     }
 
+    if let Some(Template::Git(url)) = &args.template {
+        return clone_template(&root, url, package_name);
+    }
+
     create_lib(&root)?;
 
     if !args.lib {
         create_env(&root)?;
-        create_validators(&root)?;
+        create_validators(&root, args.template.as_ref())?;
     }
 
-    readme(&root, &package_name.repo)?;
+    readme(&root, &package_name.repo, args.template.as_ref())?;
 
     Config::default(package_name)
         .save(&root)
@@ -105,10 +137,48 @@ fn create_lib(root: &Path) -> miette::Result<()> {
     fs::create_dir_all(lib).into_diagnostic()
 }
 
-fn create_validators(root: &Path) -> miette::Result<()> {
+fn create_validators(root: &Path, template: Option<&Template>) -> miette::Result<()> {
     let validators = root.join("validators");
     fs::create_dir_all(&validators).into_diagnostic()?;
-    create_validator_placeholder(&validators)
+
+    match template {
+        None => create_validator_placeholder(&validators),
+        Some(template) => {
+            for (file_name, source) in template.validators() {
+                fs::write(validators.join(file_name), source).into_diagnostic()?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Clone a template from a git repository, and make it the project's own: without the
+/// repository's history, and named after the project.
+fn clone_template(root: &Path, url: &str, package_name: &PackageName) -> miette::Result<()> {
+    let status = Command::new("git")
+        .args(["clone", "--quiet", "--depth", "1", url])
+        .arg(root)
+        .status()
+        .into_diagnostic()?;
+
+    if !status.success() {
+        miette::bail!("failed to clone the template at {url}");
+    }
+
+    fs::remove_dir_all(root.join(".git")).into_diagnostic()?;
+
+    let mut config = match Config::load(root) {
+        Ok(config) => config,
+        Err(e) => {
+            fs::remove_dir_all(root).into_diagnostic()?;
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    config.name = package_name.clone();
+
+    config.save(root).into_diagnostic()
 }
 
 fn create_validator_placeholder(validators: &Path) -> miette::Result<()> {
@@ -162,10 +232,8 @@ fn create_validator_placeholder(validators: &Path) -> miette::Result<()> {
     ).into_diagnostic()
 }
 
-fn readme(root: &Path, project_name: &str) -> miette::Result<()> {
-    fs::write(
-        root.join("README.md"),
-        formatdoc! {
+fn readme(root: &Path, project_name: &str, template: Option<&Template>) -> miette::Result<()> {
+    let mut readme = formatdoc! {
             r#"
                 # {name}
 
@@ -234,8 +302,14 @@ fn readme(root: &Path, project_name: &str) -> miette::Result<()> {
                 Find more on the [Aiken's user manual](https://aiken-lang.org).
             "#,
             name = project_name
-        },
-    ).into_diagnostic()
+    };
+
+    if let Some(description) = template.and_then(Template::description) {
+        readme.push('\n');
+        readme.push_str(description);
+    }
+
+    fs::write(root.join("README.md"), readme).into_diagnostic()
 }
 
 fn create_github_action(root: &Path) -> miette::Result<()> {
@@ -287,6 +361,8 @@ fn gitignore(root: &Path) -> miette::Result<()> {
                 build/
                 # Aiken's default documentation export
                 docs/
+                # Aiken's default coverage reports
+                coverage/
             "#
         },
     )
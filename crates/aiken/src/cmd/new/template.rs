@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+/// A starter project for `aiken new`: either one of the templates shipped with the compiler, or a
+/// git repository holding an Aiken project.
+#[derive(Clone, Debug)]
+pub enum Template {
+    Empty,
+    Vesting,
+    NftMint,
+    Multisig,
+    Git(String),
+}
+
+impl Template {
+    /// Validators of the template, as file names (under 'validators') and sources.
+    pub fn validators(&self) -> Vec<(&'static str, &'static str)> {
+        match self {
+            Template::Empty | Template::Git(..) => vec![],
+            Template::Vesting => vec![("vesting.ak", include_str!("templates/vesting.ak"))],
+            Template::NftMint => vec![("nft.ak", include_str!("templates/nft_mint.ak"))],
+            Template::Multisig => vec![("multisig.ak", include_str!("templates/multisig.ak"))],
+        }
+    }
+
+    /// What the template is about, and what's left to write off-chain, for the README.
+    pub fn description(&self) -> Option<&'static str> {
+        match self {
+            Template::Empty | Template::Git(..) => None,
+            Template::Vesting => Some(indoc::indoc! {
+                r#"
+                ## Vesting
+
+                The `vesting` validator locks funds until a given time (`lock_until`, in milliseconds), after which a `beneficiary` may collect them. The `owner` may take them back at any time.
+
+                ### Off-chain
+
+                _TODO: describe how to lock funds (with a `VestingDatum` as inline datum) and unlock them (signed by the owner, or by the beneficiary with a validity range starting after `lock_until`)._
+                "#
+            }),
+            Template::NftMint => Some(indoc::indoc! {
+                r#"
+                ## NFT minting
+
+                The `nft` minting policy mints a single token named `token_name`, only in the transaction spending `utxo_ref`; which makes the token unique. It may be burnt at any time.
+
+                ### Off-chain
+
+                _TODO: describe how to apply the policy's parameters (e.g. with `aiken blueprint apply`), mint the token while spending `utxo_ref`, and burn it._
+                "#
+            }),
+            Template::Multisig => Some(indoc::indoc! {
+                r#"
+                ## Multisig
+
+                The `multisig` validator locks funds that can only be spent once at least `threshold` of its `signatories` have signed the transaction.
+
+                ### Off-chain
+
+                _TODO: describe how to apply the validator's parameters (e.g. with `aiken blueprint apply`), lock funds at its address and gather signatures to spend them._
+                "#
+            }),
+        }
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "empty" => Ok(Template::Empty),
+            "vesting" => Ok(Template::Vesting),
+            "nft-mint" => Ok(Template::NftMint),
+            "multisig" => Ok(Template::Multisig),
+            _ if s.contains("://") || s.starts_with("git@") => Ok(Template::Git(s.to_string())),
+            _ => Err(format!(
+                "unknown template '{s}'; expected one of 'empty', 'vesting', 'nft-mint', 'multisig', or the URL of a git repository"
+            )),
+        }
+    }
+}
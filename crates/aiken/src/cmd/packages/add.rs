@@ -22,6 +22,10 @@ pub struct Args {
     #[clap(long)]
     pub version: String,
 
+    /// Location of the package within its repository, when it isn't at the root.
+    #[clap(long)]
+    pub subdir: Option<PathBuf>,
+
     #[clap(hide = true, long)]
     pub overwrite: bool,
 }
@@ -33,6 +37,7 @@ pub fn exec(args: Args) -> miette::Result<()> {
         name: PackageName::from_str(&args.package)?,
         version: args.version,
         source: Platform::Github,
+        subdir: args.subdir,
     };
 
     let config = match Config::load(&root) {
@@ -1,10 +1,26 @@
 use aiken_project::{paths, pretty};
 use miette::IntoDiagnostic;
 use owo_colors::{OwoColorize, Stream::Stderr};
-use std::fs;
+use std::{fs, path::Path};
 
 pub fn exec() -> miette::Result<()> {
-    let dir = paths::packages_cache();
+    clear(&paths::packages_cache())?;
+
+    let artifacts = paths::artifacts_cache();
+    if artifacts.is_dir() {
+        clear(&artifacts)?;
+    }
+
+    println!(
+        "{}",
+        pretty::pad_left("Done".to_string(), 13, " ")
+            .if_supports_color(Stderr, |s| s.purple())
+            .if_supports_color(Stderr, |s| s.bold())
+    );
+    Ok(())
+}
+
+fn clear(dir: &Path) -> miette::Result<()> {
     eprintln!(
         "{} {}",
         pretty::pad_left("Clearing".to_string(), 13, " ")
@@ -12,9 +28,9 @@ pub fn exec() -> miette::Result<()> {
             .if_supports_color(Stderr, |s| s.bold()),
         dir.display().if_supports_color(Stderr, |s| s.bold()),
     );
-    let packages = fs::read_dir(&dir).into_diagnostic()?;
-    for package in packages {
-        let path = package.into_diagnostic()?.path();
+    let entries = fs::read_dir(dir).into_diagnostic()?;
+    for entry in entries {
+        let path = entry.into_diagnostic()?.path();
         eprintln!(
             "{} {}",
             pretty::pad_left("Removing".to_string(), 13, " ")
@@ -26,13 +42,11 @@ pub fn exec() -> miette::Result<()> {
                 .unwrap_or_default()
                 .if_supports_color(Stderr, |s| s.bright_blue()),
         );
-        fs::remove_file(path).into_diagnostic()?;
+        if path.is_dir() {
+            fs::remove_dir_all(path).into_diagnostic()?;
+        } else {
+            fs::remove_file(path).into_diagnostic()?;
+        }
     }
-    println!(
-        "{}",
-        pretty::pad_left("Done".to_string(), 13, " ")
-            .if_supports_color(Stderr, |s| s.purple())
-            .if_supports_color(Stderr, |s| s.bold())
-    );
     Ok(())
 }
@@ -1,5 +1,6 @@
 pub mod add;
 pub mod clear_cache;
+pub mod outdated;
 pub mod upgrade;
 
 use clap::Subcommand;
@@ -8,19 +9,37 @@ use clap::Subcommand;
 #[derive(Subcommand)]
 pub enum Cmd {
     /// Add a new package dependency
+    #[clap(disable_version_flag = true)]
     Add(add::Args),
 
-    /// Change the version of an installed dependency
+    /// Upgrade dependencies, or change the version of one
+    #[clap(disable_version_flag = true)]
     Upgrade(upgrade::Args),
 
+    /// List dependencies lagging behind their latest release
+    Outdated(outdated::Args),
+
     /// Clear the system-wide dependencies cache
     ClearCache,
 }
 
+impl Cmd {
+    /// The name of the command, unless it can report its outcome as JSON.
+    pub fn without_json_output(&self) -> Option<&'static str> {
+        match self {
+            Cmd::Outdated(..) => None,
+            Cmd::Add(..) => Some("packages add"),
+            Cmd::Upgrade(..) => Some("packages upgrade"),
+            Cmd::ClearCache => Some("packages clear-cache"),
+        }
+    }
+}
+
 pub fn exec(cmd: Cmd) -> miette::Result<()> {
     match cmd {
         Cmd::Add(args) => add::exec(args),
         Cmd::ClearCache => clear_cache::exec(),
         Cmd::Upgrade(args) => upgrade::exec(args),
+        Cmd::Outdated(args) => outdated::exec(args),
     }
 }
@@ -0,0 +1,116 @@
+use aiken_project::{
+    config::Config,
+    deps::releases,
+    pretty,
+    watch::{default_output, Output},
+};
+use owo_colors::{OwoColorize, Stream::Stderr};
+use serde_json::json;
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+/// List dependencies lagging behind their latest release
+///
+/// Only dependencies on a release tag are considered; those on a branch or a commit have no
+/// version to compare with. Compatible releases share the major version of the current one (or
+/// its minor version, before 1.0.0), and can be installed with 'aiken packages upgrade'.
+pub struct Args {}
+
+pub fn exec(_args: Args) -> miette::Result<()> {
+    let root = PathBuf::from(".");
+
+    let config = match Config::load(&root) {
+        Ok(config) => config,
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    eprintln!(
+        "{} {} package(s)",
+        pretty::pad_left("Checking".to_string(), 13, " ")
+            .if_supports_color(Stderr, |s| s.purple())
+            .if_supports_color(Stderr, |s| s.bold()),
+        config.dependencies.len(),
+    );
+
+    let outdated = match releases::newer(&config, &[]) {
+        Ok(releases) => releases
+            .into_iter()
+            .filter(|release| release.latest.is_some())
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    if default_output() == Output::Json {
+        let packages = outdated
+            .iter()
+            .map(|release| {
+                json!({
+                    "name": release.name.to_string(),
+                    "current": release.current,
+                    "compatible": release.compatible,
+                    "latest": release.latest,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({ "outdated": packages })).unwrap()
+        );
+    } else if !outdated.is_empty() {
+        let rows = outdated
+            .iter()
+            .map(|release| {
+                [
+                    release.name.to_string(),
+                    release.current.clone(),
+                    release
+                        .compatible
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                    release.latest.clone().unwrap_or_else(|| "-".to_string()),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let header = ["Package", "Current", "Compatible", "Latest"].map(str::to_string);
+
+        let widths = (0..header.len())
+            .map(|column| {
+                rows.iter()
+                    .chain([&header])
+                    .map(|row| row[column].len())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        for row in [&header].into_iter().chain(rows.iter()) {
+            println!(
+                "{}",
+                row.iter()
+                    .zip(widths.iter())
+                    .map(|(cell, width)| format!("{cell:<width$}"))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+                    .trim_end()
+            );
+        }
+    }
+
+    eprintln!(
+        "{} {} outdated package(s)",
+        pretty::pad_left("Summary".to_string(), 13, " ")
+            .if_supports_color(Stderr, |s| s.purple())
+            .if_supports_color(Stderr, |s| s.bold()),
+        outdated.len(),
+    );
+
+    Ok(())
+}
@@ -1,24 +1,154 @@
 use super::add;
+use aiken_project::{
+    config::{Config, Dependency},
+    deps::{self, releases},
+    package_name::PackageName,
+    pretty,
+    telemetry::Terminal,
+};
+use miette::IntoDiagnostic;
+use owo_colors::{OwoColorize, Stream::Stderr};
+use std::{path::PathBuf, process, str::FromStr};
 
 #[derive(clap::Args)]
-/// Change the version of an installed dependency
+/// Upgrade dependencies to their latest compatible release, or change the version of one
+///
+/// Without '--version', dependencies on a release tag move to the most recent release of the same
+/// major version (or minor version, before 1.0.0); dependencies on a branch get pinned to its
+/// latest commit. The lock file is updated accordingly.
 pub struct Args {
-    /// Package name, in the form of {owner}/{repository}.
+    /// Package name, in the form of {owner}/{repository}. Upgrades every dependency when omitted.
     ///
-    /// For example → 'add aiken-lang/stdlib'
+    /// For example → 'upgrade aiken-lang/stdlib'
     ///
     /// Note that by default, this assumes the package is located
     /// on Github.
-    package: String,
+    package: Option<String>,
+
     /// The package version, as a git commit hash, a tag or a branch name.
-    #[clap(long)]
-    version: String,
+    #[clap(long, requires = "package")]
+    version: Option<String>,
+
+    /// Move to the most recent release, even when it isn't compatible with the current one.
+    #[clap(long, conflicts_with = "version")]
+    latest: bool,
 }
 
 pub fn exec(args: Args) -> miette::Result<()> {
-    add::exec(add::Args {
-        package: args.package,
-        version: args.version,
-        overwrite: true,
-    })
+    let root = PathBuf::from(".");
+
+    let mut config = match Config::load(&root) {
+        Ok(config) => config,
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    if let (Some(package), Some(version)) = (&args.package, &args.version) {
+        let name = PackageName::from_str(package)?;
+
+        // Keep pointing at the same sub-directory of the repository, if any.
+        let subdir = config
+            .dependencies
+            .iter()
+            .find(|dep| dep.name == name)
+            .and_then(|dep| dep.subdir.clone());
+
+        return add::exec(add::Args {
+            package: package.clone(),
+            version: version.clone(),
+            subdir,
+            overwrite: true,
+        });
+    }
+
+    let only = args
+        .package
+        .as_deref()
+        .map(PackageName::from_str)
+        .transpose()?
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    if let Some(name) = only
+        .iter()
+        .find(|name| !config.dependencies.iter().any(|dep| &dep.name == *name))
+    {
+        miette::bail!("{name} isn't a dependency of this project");
+    }
+
+    let upgrades = match releases::newer(&config, &only) {
+        Ok(upgrades) => upgrades,
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    let mut bumped = false;
+
+    for release in upgrades {
+        let version = if args.latest {
+            release.latest
+        } else {
+            release.compatible
+        };
+
+        if let Some(version) = version {
+            let dependency = config
+                .dependencies
+                .iter()
+                .find(|dep| dep.name == release.name)
+                .expect("releases of a dependency")
+                .clone();
+
+            config = config
+                .insert(
+                    &Dependency {
+                        version,
+                        ..dependency
+                    },
+                    true,
+                )
+                .expect("replacing an existing dependency");
+
+            bumped = true;
+        }
+    }
+
+    if bumped {
+        config.save(&root).into_diagnostic()?;
+    }
+
+    let changes = match deps::relock(&Terminal, &root, &config, &only) {
+        Ok(changes) => changes,
+        Err(e) => {
+            e.report();
+            process::exit(1);
+        }
+    };
+
+    if changes.is_empty() {
+        eprintln!(
+            "{} dependencies are up to date",
+            pretty::pad_left("Done".to_string(), 13, " ")
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+        );
+    }
+
+    for change in changes {
+        eprintln!(
+            "{} {} {} → {}",
+            pretty::pad_left("Upgraded".to_string(), 13, " ")
+                .if_supports_color(Stderr, |s| s.purple())
+                .if_supports_color(Stderr, |s| s.bold()),
+            change.name.if_supports_color(Stderr, |s| s.bright_blue()),
+            change.from.as_deref().unwrap_or("(unlocked)"),
+            change.to.if_supports_color(Stderr, |s| s.yellow()),
+        );
+    }
+
+    Ok(())
 }
@@ -0,0 +1,242 @@
+use aiken_lang::{ast::Tracing, format::Formatter};
+use aiken_project::{
+    options::Options,
+    repl::{Evaluation, Outcome, Session, Value},
+    telemetry::EventTarget,
+    watch::{with_project_output, Output},
+    Project,
+};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    process,
+};
+
+#[derive(clap::Args)]
+/// Start an interactive session, to evaluate expressions against the project
+///
+/// Every module of the project and of its dependencies is imported, under the last segment of
+/// its name (e.g. 'list' for 'aiken/collection/list') unless another module shares it. Enter an
+/// expression to see its value, type and execution budget; or imports and definitions (fn,
+/// const, type, ...) to use in later expressions. 'let' bindings are kept as constants.
+///
+/// Commands:
+///
+///   :type <expr>   show the type of an expression, without evaluating it
+///   :list          list imports and definitions entered so far
+///   :reload        recompile the project, keeping imports and definitions
+///   :help          show this help
+///   :quit          leave the session
+#[clap(verbatim_doc_comment)]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Environment to build against.
+    #[clap(long)]
+    env: Option<String>,
+}
+
+const HELP: &str = "\
+  <expr>         evaluate an expression
+  use, fn, ...   add imports or definitions
+  :type <expr>   show the type of an expression, without evaluating it
+  :list          list imports and definitions entered so far
+  :reload        recompile the project, keeping imports and definitions
+  :help          show this help
+  :quit          leave the session";
+
+enum Next {
+    Quit,
+    Reload,
+}
+
+pub fn exec(Args { directory, env }: Args) -> miette::Result<()> {
+    let mut session = Session::new(Tracing::verbose(), env.clone());
+
+    let mut lines = io::stdin().lock().lines();
+
+    let mut reloading = false;
+
+    loop {
+        let mut next = Next::Quit;
+
+        let result = with_project_output(directory.as_deref(), false, Output::Pretty, |p| {
+            p.compile(Options {
+                tracing: Tracing::verbose(),
+                env: env.clone(),
+                ..Options::default()
+            })?;
+
+            // The session runs until the user leaves; report warnings now rather than then.
+            for warning in p.warnings() {
+                warning.report();
+            }
+
+            next = repl(p, &mut session, &mut lines);
+
+            Ok(())
+        });
+
+        if result.is_err() {
+            if !reloading {
+                process::exit(1);
+            }
+
+            // Give a chance to fix the project, rather than losing the session.
+            eprintln!("Press enter to reload, once fixed.");
+
+            match lines.next() {
+                Some(Ok(_)) => continue,
+                _ => break,
+            }
+        }
+
+        match next {
+            Next::Quit => break,
+            Next::Reload => reloading = true,
+        }
+    }
+
+    Ok(())
+}
+
+fn repl(
+    project: &mut Project<EventTarget>,
+    session: &mut Session,
+    lines: &mut impl Iterator<Item = io::Result<String>>,
+) -> Next {
+    println!("Type :help for help, :quit to leave.");
+
+    while let Some(input) = read_input(lines) {
+        let trimmed = input.trim();
+
+        let (command, argument) = trimmed
+            .split_once(char::is_whitespace)
+            .map(|(command, argument)| (command, argument.trim()))
+            .unwrap_or((trimmed, ""));
+
+        match command {
+            "" => continue,
+            ":q" | ":quit" => return Next::Quit,
+            ":r" | ":reload" => return Next::Reload,
+            ":h" | ":help" => println!("{HELP}"),
+            ":l" | ":list" => {
+                for name in session.names() {
+                    println!("{name}");
+                }
+            }
+            ":t" | ":type" => match session.infer(project, argument) {
+                Ok(tipo) => println!("{}", fmt_type(&tipo.to_pretty(0))),
+                Err(errors) => errors.iter().for_each(|error| error.report()),
+            },
+            _ if command.starts_with(':') => {
+                eprintln!("Unknown command {command}; type :help for help.")
+            }
+            _ => match session.eval(project, &input) {
+                Ok(Outcome::Defined(names)) => {
+                    println!(
+                        "{}",
+                        format!("defined {}", names.join(", "))
+                            .if_supports_color(Stdout, |s| s.bright_black())
+                    )
+                }
+                Ok(Outcome::Evaluated(evaluation)) => println!("{}", fmt_evaluation(&evaluation)),
+                Err(errors) => errors.iter().for_each(|error| error.report()),
+            },
+        }
+    }
+
+    Next::Quit
+}
+
+/// Read lines until their brackets are balanced, so that an input may span over several lines.
+fn read_input(lines: &mut impl Iterator<Item = io::Result<String>>) -> Option<String> {
+    let mut input = String::new();
+
+    loop {
+        print!(
+            "{}",
+            if input.is_empty() {
+                "aiken> "
+            } else {
+                "   ... "
+            }
+        );
+
+        let _ = io::stdout().flush();
+
+        input.push_str(&lines.next()?.ok()?);
+        input.push('\n');
+
+        if depth(&input) <= 0 {
+            return Some(input);
+        }
+    }
+}
+
+/// How many brackets are left open, outside of strings.
+fn depth(input: &str) -> i64 {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for c in input.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '(' | '[' | '{' if !in_string => depth += 1,
+            ')' | ']' | '}' if !in_string => depth -= 1,
+            _ => {}
+        }
+    }
+
+    depth
+}
+
+fn fmt_evaluation(evaluation: &Evaluation) -> String {
+    let mut lines = evaluation
+        .traces
+        .iter()
+        .map(|trace| {
+            format!("| {trace}")
+                .if_supports_color(Stdout, |s| s.bright_black())
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+
+    lines.push(match &evaluation.result {
+        Ok(value) => format!(
+            "{} : {}",
+            match value {
+                Value::Expr(expr) => Formatter::new().expr(expr, false).to_pretty_string(80),
+                Value::Term(term) => term.to_pretty(),
+            },
+            fmt_type(&evaluation.tipo.to_pretty(0)),
+        ),
+        Err(error) => format!("× {error}")
+            .if_supports_color(Stdout, |s| s.red())
+            .to_string(),
+    });
+
+    lines.push(format!(
+        "[mem: {}, cpu: {}]",
+        evaluation
+            .spent_budget
+            .mem
+            .if_supports_color(Stdout, |s| s.cyan()),
+        evaluation
+            .spent_budget
+            .cpu
+            .if_supports_color(Stdout, |s| s.cyan()),
+    ));
+
+    lines.join("\n")
+}
+
+fn fmt_type(tipo: &str) -> String {
+    tipo.if_supports_color(Stdout, |s| s.bright_blue())
+        .to_string()
+}
@@ -0,0 +1,85 @@
+use aiken_lang::ast::Tracing;
+use aiken_project::{
+    options::{Emit, Options},
+    serve::serve,
+    watch::with_project,
+};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+};
+
+#[derive(clap::Args)]
+/// Build the project and serve it over a local HTTP API, to evaluate exported functions, apply
+/// parameters, compute addresses and decode datums from other languages.
+///
+/// Endpoints take and return JSON; Plutus data are given as hex-encoded CBOR:
+///
+///   GET  /         project's name, version and validators
+///   POST /eval     { module, name, args }
+///   POST /apply    { module?, validator?, parameters }
+///   POST /address  { module?, validator?, parameters?, delegatedTo?, mainnet? }
+///   POST /decode   { module?, validator?, handler?, as?: "datum" | "redeemer", cbor }
+#[clap(verbatim_doc_comment)]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Address to listen on
+    #[clap(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    host: IpAddr,
+
+    /// Port to listen on
+    #[clap(short, long, default_value_t = 8080)]
+    port: u16,
+
+    /// Environment to build against.
+    #[clap(long)]
+    env: Option<String>,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        host,
+        port,
+        env,
+    }: Args,
+) -> miette::Result<()> {
+    with_project(directory.as_deref(), false, false, |p| {
+        let blueprint_path = p.blueprint_path(None);
+
+        // Validators are built as 'aiken build' would, so that hashes and addresses match.
+        let checkpoint = p.checkpoint();
+        p.build(
+            Emit::default(),
+            Tracing::silent(),
+            blueprint_path.clone(),
+            env.clone(),
+        )?;
+        p.restore(checkpoint);
+
+        // Exported functions however are meant for testing, so they keep all their traces.
+        // Warnings are the same for both, only report those of the last compilation.
+        p.warnings();
+        p.compile(Options {
+            tracing: Tracing::verbose(),
+            env: env.clone(),
+            ..Options::default()
+        })?;
+
+        // The server runs until interrupted; report warnings now rather than never.
+        for warning in p.warnings() {
+            warning.report();
+        }
+
+        serve(
+            p,
+            SocketAddr::new(host, port),
+            &blueprint_path,
+            Tracing::verbose(),
+        )
+        .map_err(|e| vec![e])
+    })
+    .map_err(|_| std::process::exit(1))
+}
@@ -1,3 +1,4 @@
+use aiken_project::provider::Provider;
 use miette::IntoDiagnostic;
 use owo_colors::{OwoColorize, Stream::Stderr};
 use pallas_primitives::{
@@ -5,17 +6,17 @@ use pallas_primitives::{
     Fragment,
 };
 use pallas_traverse::{Era, MultiEraTx};
+use serde_json::json;
 use std::{fmt, fs, path::PathBuf, process};
-use uplc::{
-    machine::cost_model::ExBudget,
-    tx::{
-        self, redeemer_tag_to_string,
-        script_context::{ResolvedInput, SlotConfig},
-    },
+use uplc::tx::{
+    self, redeemer_tag_to_string,
+    script_context::{ResolvedInput, SlotConfig},
 };
 
 #[derive(clap::Args)]
-/// Simulate a transaction by evaluating it's script
+/// Simulate a transaction by evaluating its scripts
+///
+/// Prints the execution budget spent by each redeemer, as a JSON list.
 pub struct Args {
     /// A file containing cbor hex for a transaction
     #[clap(value_name = "FILEPATH")]
@@ -26,12 +27,38 @@ pub struct Args {
     cbor: bool,
 
     /// A file containing cbor hex for the raw inputs
-    #[clap(value_name = "FILEPATH")]
-    raw_inputs: PathBuf,
+    #[clap(
+        value_name = "FILEPATH",
+        required_unless_present_any = ["blockfrost", "kupo", "ogmios", "utxo"],
+        requires = "raw_outputs"
+    )]
+    raw_inputs: Option<PathBuf>,
 
     /// A file containing cbor hex for the raw outputs
     #[clap(value_name = "FILEPATH")]
-    raw_outputs: PathBuf,
+    raw_outputs: Option<PathBuf>,
+
+    /// Resolve inputs using Blockfrost, with the given project id
+    #[clap(
+        long,
+        value_name = "PROJECT_ID",
+        conflicts_with_all = ["raw_inputs", "kupo", "ogmios", "utxo"]
+    )]
+    blockfrost: Option<String>,
+
+    /// Resolve inputs using the Kupo instance at the given url
+    #[clap(long, value_name = "URL", conflicts_with_all = ["raw_inputs", "ogmios", "utxo"])]
+    kupo: Option<String>,
+
+    /// Resolve inputs using the Ogmios server at the given url
+    #[clap(long, value_name = "URL", conflicts_with_all = ["raw_inputs", "utxo"])]
+    ogmios: Option<String>,
+
+    /// Resolve inputs from a snapshot of the UTxO set: the JSON output of `cardano-cli query
+    /// utxo` or of Ogmios' `queryLedgerState/utxo`, or a CBOR map of inputs to outputs (raw or
+    /// hex-encoded)
+    #[clap(long, value_name = "FILEPATH", conflicts_with = "raw_inputs")]
+    utxo: Option<PathBuf>,
 
     /// Time between each slot
     #[clap(short, long, default_value_t = 1000, value_name = "MILLISECOND")]
@@ -52,6 +79,10 @@ pub fn exec(
         cbor,
         raw_inputs,
         raw_outputs,
+        blockfrost,
+        kupo,
+        ogmios,
+        utxo,
         slot_length,
         zero_time,
         zero_slot,
@@ -64,26 +95,76 @@ pub fn exec(
             .if_supports_color(Stderr, |s| s.bold())
     );
 
-    let (tx_bytes, inputs_bytes, outputs_bytes) = if cbor {
-        (
-            fs::read(input).into_diagnostic()?,
-            fs::read(raw_inputs).into_diagnostic()?,
-            fs::read(raw_outputs).into_diagnostic()?,
-        )
+    let tx_bytes = if cbor {
+        fs::read(input).into_diagnostic()?
     } else {
         let cbor_hex = fs::read_to_string(input).into_diagnostic()?;
-        let inputs_hex = fs::read_to_string(raw_inputs).into_diagnostic()?;
-        let outputs_hex = fs::read_to_string(raw_outputs).into_diagnostic()?;
-
-        (
-            hex::decode(cbor_hex.trim()).into_diagnostic()?,
-            hex::decode(inputs_hex.trim()).into_diagnostic()?,
-            hex::decode(outputs_hex.trim()).into_diagnostic()?,
-        )
+        hex::decode(cbor_hex.trim()).into_diagnostic()?
     };
 
     let tx = MultiEraTx::decode_for_era(Era::Conway, &tx_bytes).into_diagnostic()?;
 
+    let provider = match (blockfrost, kupo, ogmios, utxo) {
+        (Some(project_id), _, _, _) => Some(Provider::blockfrost(&project_id)?),
+        (_, Some(url), _, _) => Some(Provider::Kupo { url }),
+        (_, _, Some(url), _) => Some(Provider::Ogmios { url }),
+        (_, _, _, Some(path)) => Some(Provider::Snapshot { path }),
+        (None, None, None, None) => None,
+    };
+
+    let resolved_inputs: Vec<ResolvedInput> = match (provider, raw_inputs, raw_outputs) {
+        (Some(provider), _, _) => {
+            eprintln!(
+                "{} inputs from {}",
+                "    Resolving"
+                    .if_supports_color(Stderr, |s| s.purple())
+                    .if_supports_color(Stderr, |s| s.bold()),
+                provider.name()
+            );
+
+            let inputs = tx
+                .inputs()
+                .iter()
+                .chain(tx.reference_inputs().iter())
+                .map(|input| TransactionInput {
+                    transaction_id: *input.hash(),
+                    index: input.index(),
+                })
+                .collect::<Vec<_>>();
+
+            provider.resolve(&inputs)?
+        }
+        (None, Some(raw_inputs), Some(raw_outputs)) => {
+            let (inputs_bytes, outputs_bytes) = if cbor {
+                (
+                    fs::read(raw_inputs).into_diagnostic()?,
+                    fs::read(raw_outputs).into_diagnostic()?,
+                )
+            } else {
+                let inputs_hex = fs::read_to_string(raw_inputs).into_diagnostic()?;
+                let outputs_hex = fs::read_to_string(raw_outputs).into_diagnostic()?;
+
+                (
+                    hex::decode(inputs_hex.trim()).into_diagnostic()?,
+                    hex::decode(outputs_hex.trim()).into_diagnostic()?,
+                )
+            };
+
+            let inputs = Vec::<TransactionInput>::decode_fragment(&inputs_bytes).unwrap();
+            let outputs = Vec::<TransactionOutput>::decode_fragment(&outputs_bytes).unwrap();
+
+            inputs
+                .iter()
+                .zip(outputs.iter())
+                .map(|(input, output)| ResolvedInput {
+                    input: input.clone(),
+                    output: output.clone(),
+                })
+                .collect()
+        }
+        _ => unreachable!("clap ensures either a provider or raw inputs and outputs are given"),
+    };
+
     eprintln!(
         "{} {}",
         "   Simulating"
@@ -92,18 +173,6 @@ pub fn exec(
         tx.hash()
     );
 
-    let inputs = Vec::<TransactionInput>::decode_fragment(&inputs_bytes).unwrap();
-    let outputs = Vec::<TransactionOutput>::decode_fragment(&outputs_bytes).unwrap();
-
-    let resolved_inputs: Vec<ResolvedInput> = inputs
-        .iter()
-        .zip(outputs.iter())
-        .map(|(input, output)| ResolvedInput {
-            input: input.clone(),
-            output: output.clone(),
-        })
-        .collect();
-
     if let Some(tx_conway) = tx.as_conway() {
         let slot_config = SlotConfig {
             zero_time,
@@ -134,19 +203,46 @@ pub fn exec(
 
         match result {
             Ok(redeemers) => {
-                // this should allow N scripts to be
-                let total_budget_used: Vec<ExBudget> = redeemers
+                eprintln!();
+
+                for redeemer in redeemers.iter() {
+                    eprintln!(
+                        "{} {}[{}] [mem: {}, cpu: {}]",
+                        "        Spent"
+                            .if_supports_color(Stderr, |s| s.purple())
+                            .if_supports_color(Stderr, |s| s.bold()),
+                        redeemer_tag_to_string(&redeemer.tag),
+                        redeemer.index,
+                        redeemer
+                            .ex_units
+                            .mem
+                            .if_supports_color(Stderr, |s| s.cyan()),
+                        redeemer
+                            .ex_units
+                            .steps
+                            .if_supports_color(Stderr, |s| s.cyan()),
+                    );
+                }
+
+                let budgets = redeemers
                     .iter()
-                    .map(|curr| ExBudget {
-                        mem: curr.ex_units.mem as i64,
-                        cpu: curr.ex_units.steps as i64,
+                    .map(|redeemer| {
+                        json!({
+                            "redeemer": format!(
+                                "{}[{}]",
+                                redeemer_tag_to_string(&redeemer.tag),
+                                redeemer.index
+                            ),
+                            "mem": redeemer.ex_units.mem,
+                            "cpu": redeemer.ex_units.steps,
+                        })
                     })
-                    .collect();
+                    .collect::<Vec<_>>();
 
-                eprintln!("\n");
+                eprintln!();
                 println!(
                     "{}",
-                    serde_json::to_string_pretty(&total_budget_used)
+                    serde_json::to_string_pretty(&budgets)
                         .map_err(|_| fmt::Error)
                         .into_diagnostic()?
                 );
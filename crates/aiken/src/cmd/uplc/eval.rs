@@ -1,14 +1,27 @@
+use aiken_lang::plutus_version::PlutusVersion;
+use clap::ValueEnum;
 use miette::IntoDiagnostic;
+use pallas_primitives::conway::Language;
 use serde_json::json;
-use std::{path::PathBuf, process};
+use std::{
+    path::{Path, PathBuf},
+    process,
+};
 use uplc::{
     ast::{FakeNamedDeBruijn, Name, NamedDeBruijn, Program, Term},
-    machine::cost_model::ExBudget,
+    machine::{
+        breakdown::{Breakdown, Step},
+        cost_model::ExBudget,
+        profile,
+    },
     parser,
 };
 
 #[derive(clap::Args)]
 /// Evaluate an Untyped Plutus Core program
+///
+/// Prints the resulting term along with the execution budget spent, broken down by kind of machine
+/// step and by builtin, and the traces emitted during evaluation.
 pub struct Args {
     script: PathBuf,
 
@@ -20,6 +33,77 @@ pub struct Args {
 
     /// Arguments to pass to the UPLC program
     args: Vec<String>,
+
+    /// Write a flamegraph of the execution budget, attributed to the functions of the program, to
+    /// the given file. Produces an SVG when the file ends with '.svg', and collapsed stacks
+    /// otherwise. Functions are only named in programs using named variables; to profile Aiken
+    /// tests, see 'aiken check --profile'.
+    #[clap(long, value_name = "FILEPATH")]
+    flamegraph: Option<PathBuf>,
+
+    /// Which execution unit the flamegraph is weighted by.
+    #[clap(
+        long,
+        value_name = "UNIT",
+        default_value = "cpu",
+        requires = "flamegraph"
+    )]
+    flamegraph_unit: Unit,
+
+    /// Plutus version to evaluate the program as; it determines the builtins available and the
+    /// default cost model.
+    #[clap(long, value_name = "VERSION", default_value = "v3")]
+    plutus_version: Plutus,
+
+    /// Path to a JSON array of cost model parameters to evaluate the program against, as found in
+    /// protocol parameters (e.g. 'costModels.PlutusV3'). Defaults to the current mainnet
+    /// parameters of the Plutus version.
+    #[clap(long, value_name = "FILEPATH")]
+    cost_model: Option<PathBuf>,
+
+    /// CPU budget available to the program.
+    #[clap(long, value_name = "CPU", default_value_t = ExBudget::default().cpu)]
+    cpu: i64,
+
+    /// Memory budget available to the program.
+    #[clap(long, value_name = "MEM", default_value_t = ExBudget::default().mem)]
+    mem: i64,
+
+    /// Also print every step taken by the machine, with the budget spent so far.
+    #[clap(short, long)]
+    verbose: bool,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Plutus {
+    V1,
+    V2,
+    V3,
+}
+
+impl From<Plutus> for PlutusVersion {
+    fn from(plutus: Plutus) -> PlutusVersion {
+        match plutus {
+            Plutus::V1 => PlutusVersion::V1,
+            Plutus::V2 => PlutusVersion::V2,
+            Plutus::V3 => PlutusVersion::V3,
+        }
+    }
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Unit {
+    Cpu,
+    Mem,
+}
+
+impl From<Unit> for profile::Unit {
+    fn from(unit: Unit) -> Self {
+        match unit {
+            Unit::Cpu => profile::Unit::Cpu,
+            Unit::Mem => profile::Unit::Mem,
+        }
+    }
 }
 
 pub fn exec(
@@ -28,6 +112,13 @@ pub fn exec(
         flat,
         args,
         cbor,
+        flamegraph,
+        flamegraph_unit,
+        plutus_version,
+        cost_model,
+        cpu,
+        mem,
+        verbose,
     }: Args,
 ) -> miette::Result<()> {
     let mut program: Program<Name> = if cbor {
@@ -61,11 +152,30 @@ pub fn exec(
         program = program.apply_term(&term)
     }
 
-    let budget = ExBudget::default();
+    let version: Language = PlutusVersion::from(plutus_version).into();
+
+    let costs = cost_model
+        .map(|path| read_cost_model(&path, &version))
+        .transpose()?;
+
+    let budget = ExBudget { mem, cpu };
 
     let program = Program::<NamedDeBruijn>::try_from(program).into_diagnostic()?;
 
-    let mut eval_result = program.eval(budget);
+    if let Some(path) = flamegraph {
+        let (_, profile) = program.clone().eval_with_profile(budget, &version);
+
+        let output = if path.extension().is_some_and(|ext| ext == "svg") {
+            profile.to_svg(flamegraph_unit.into())
+        } else {
+            profile.collapsed(flamegraph_unit.into())
+        };
+
+        std::fs::write(&path, output).into_diagnostic()?;
+    }
+
+    let (mut eval_result, breakdown) =
+        program.eval_with_breakdown(budget, &version, costs.as_deref(), verbose);
 
     let cost = eval_result.cost();
     let logs = eval_result.logs();
@@ -74,12 +184,18 @@ pub fn exec(
         Ok(term) => {
             let term = Term::<Name>::try_from(term).into_diagnostic()?;
 
-            let output = json!({
+            let mut output = json!({
                 "result": term.to_pretty(),
                 "cpu": cost.cpu,
                 "mem": cost.mem,
+                "budget": budget_json(&breakdown),
+                "traces": logs,
             });
 
+            if let Some(steps) = breakdown.steps.as_ref() {
+                output["steps"] = steps_json(steps);
+            }
+
             println!(
                 "{}",
                 serde_json::to_string_pretty(&output).into_diagnostic()?
@@ -92,11 +208,122 @@ pub fn exec(
 
             eprintln!("\nCosts\n-----\ncpu: {}\nmemory: {}", cost.cpu, cost.mem);
 
+            eprintln!("\nBudget\n------\n{}", fmt_breakdown(&breakdown));
+
             if !logs.is_empty() {
                 eprintln!("\nLogs\n----\n{}", logs.join("\n"))
             }
 
+            if let Some(steps) = breakdown.steps.as_ref() {
+                eprintln!("\nSteps\n-----\n{}", fmt_steps(steps));
+            }
+
             process::exit(1)
         }
     }
 }
+
+/// Read cost model parameters, making sure there are enough of them for the given version.
+fn read_cost_model(path: &Path, version: &Language) -> miette::Result<Vec<i64>> {
+    let costs: Vec<i64> = serde_json::from_str(&std::fs::read_to_string(path).into_diagnostic()?)
+        .into_diagnostic()?;
+
+    let expected = match version {
+        Language::PlutusV1 => 166,
+        Language::PlutusV2 => 175,
+        Language::PlutusV3 => 251,
+    };
+
+    if costs.len() < expected {
+        miette::bail!(
+            "expected at least {expected} cost model parameters for {version:?}, found {}",
+            costs.len()
+        );
+    }
+
+    Ok(costs)
+}
+
+fn budget_json(breakdown: &Breakdown) -> serde_json::Value {
+    let usage = |name: String, count: usize, cost: ExBudget| json!({ "name": name, "count": count, "cpu": cost.cpu, "mem": cost.mem });
+
+    json!({
+        "startup": { "cpu": breakdown.startup.cpu, "mem": breakdown.startup.mem },
+        "machine": breakdown
+            .machine
+            .iter()
+            .filter(|(_, usage)| usage.count > 0)
+            .map(|(kind, step)| usage(format!("{kind:?}").to_lowercase(), step.count, step.cost))
+            .collect::<Vec<_>>(),
+        "builtins": breakdown
+            .builtins
+            .iter()
+            .map(|(fun, call)| usage(fun.to_string(), call.count, call.cost))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn steps_json(steps: &[Step]) -> serde_json::Value {
+    steps
+        .iter()
+        .map(|step| match step {
+            Step::Compute { term, spent } => {
+                json!({ "compute": term, "cpu": spent.cpu, "mem": spent.mem })
+            }
+            Step::Builtin { fun, cost, spent } => json!({
+                "call": fun.to_string(),
+                "cost": { "cpu": cost.cpu, "mem": cost.mem },
+                "cpu": spent.cpu,
+                "mem": spent.mem,
+            }),
+        })
+        .collect()
+}
+
+fn fmt_breakdown(breakdown: &Breakdown) -> String {
+    let mut lines = vec![format!(
+        "{:<24} {:>8} {:>16} {:>12}",
+        "startup", 1, breakdown.startup.cpu, breakdown.startup.mem
+    )];
+
+    for (kind, usage) in breakdown
+        .machine
+        .iter()
+        .filter(|(_, usage)| usage.count > 0)
+    {
+        lines.push(format!(
+            "{:<24} {:>8} {:>16} {:>12}",
+            format!("{kind:?}").to_lowercase(),
+            usage.count,
+            usage.cost.cpu,
+            usage.cost.mem
+        ));
+    }
+
+    for (fun, usage) in breakdown.builtins.iter() {
+        lines.push(format!(
+            "{:<24} {:>8} {:>16} {:>12}",
+            fun.to_string(),
+            usage.count,
+            usage.cost.cpu,
+            usage.cost.mem
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn fmt_steps(steps: &[Step]) -> String {
+    steps
+        .iter()
+        .map(|step| match step {
+            Step::Compute { term, spent } => {
+                format!("{:>16} {:>12}  {term}", spent.cpu, spent.mem)
+            }
+            Step::Builtin { fun, spent, .. } => {
+                format!("{:>16} {:>12}  call {fun}", spent.cpu, spent.mem)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,85 @@
+use aiken_lang::ast::Tracing;
+use aiken_project::{verify::DEFAULT_SOLVER, watch::with_project};
+use std::{path::PathBuf, process};
+
+#[derive(clap::Args)]
+#[command(
+    verbatim_doc_comment,
+    about = color_print::cstr!(r#"
+Verify properties and validators of an Aiken project using an SMT solver.
+
+Property tests are proven for all possible values of their arguments, rather than only for
+those their fuzzers produce. Validator handlers are checked for being able to succeed at all.
+
+Only programs over integers and booleans are supported (arithmetic, comparisons, if/when,
+let/expect, fail and calls to non-recursive functions); others are reported as skipped.
+
+With --paths, each validator handler is also executed symbolically: every branch, failure and
+success gets inputs reaching it, or is reported unreachable.
+"#),
+    after_long_help = color_print::cstr!(r#"You are seeing the extended help. Use `-h` instead of `--help` for a more compact view.
+"#
+))]
+pub struct Args {
+    /// Path to project
+    directory: Option<PathBuf>,
+
+    /// Deny warnings; warnings will be treated as errors
+    #[clap(short = 'D', long)]
+    deny: bool,
+
+    /// The SMT solver to run, as a command reading an SMT-LIB script from stdin.
+    #[clap(long, default_value = DEFAULT_SOLVER, value_name = "COMMAND")]
+    solver: String,
+
+    /// Also write each verification condition as an SMT-LIB script (.smt2) to the given folder.
+    #[clap(long, value_name = "DIRECTORY")]
+    emit_smt: Option<PathBuf>,
+
+    /// Also explore the paths through validator handlers, warning about branches never taken.
+    #[clap(long)]
+    paths: bool,
+
+    /// Environment to verify against.
+    #[clap(long)]
+    env: Option<String>,
+
+    /// Comma-separated list of features to enable, on top of the default ones.
+    #[clap(long, value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Do not enable the 'default' feature.
+    #[clap(long)]
+    no_default_features: bool,
+}
+
+pub fn exec(
+    Args {
+        directory,
+        deny,
+        solver,
+        emit_smt,
+        paths,
+        env,
+        features,
+        no_default_features,
+    }: Args,
+) -> miette::Result<()> {
+    let solver = solver
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    let result = with_project(directory.as_deref(), deny, false, |p| {
+        p.with_features(&features, !no_default_features)?;
+        p.verify(
+            solver.clone(),
+            emit_smt.clone(),
+            paths,
+            Tracing::silent(),
+            env.clone(),
+        )
+    });
+
+    result.map_err(|_| process::exit(1))
+}
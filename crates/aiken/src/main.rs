@@ -1,11 +1,13 @@
-use aiken_project::{config, pretty};
+use aiken_project::pretty;
 #[cfg(not(target_os = "windows"))]
 use cmd::completion;
 use cmd::{
+    audit, bench,
     blueprint::{self, address},
-    build, check, docs, export, fmt, lsp, new,
+    build, check, config, coverage, daemon, docs, explain, export, fmt, generate, graph, import,
+    lsp, new,
     packages::{self, add},
-    tx, uplc, Cmd,
+    repl, serve, tx, uplc, verify, Cli, Cmd,
 };
 use owo_colors::OwoColorize;
 
@@ -17,15 +19,27 @@ fn main() -> miette::Result<()> {
     #[cfg(target_env = "musl")]
     openssl_probe::init_ssl_cert_env_vars();
 
-    match Cmd::default() {
+    match Cli::init() {
         Cmd::New(args) => new::exec(args),
         Cmd::Fmt(args) => fmt::exec(args),
         Cmd::Build(args) => build::exec(args),
         Cmd::Address(args) => address::exec(args),
         Cmd::Check(args) => check::exec(args),
+        Cmd::Bench(args) => bench::exec(args),
+        Cmd::Coverage(args) => coverage::exec(args),
+        Cmd::Verify(args) => verify::exec(args),
         Cmd::Docs(args) => docs::exec(args),
+        Cmd::Graph(args) => graph::exec(args),
         Cmd::Add(args) => add::exec(args),
+        Cmd::Audit(args) => audit::exec(args),
+        Cmd::Explain(args) => explain::exec(args),
+        Cmd::Serve(args) => serve::exec(args),
+        Cmd::Repl(args) => repl::exec(args),
         Cmd::Blueprint(args) => blueprint::exec(args),
+        Cmd::Config(sub_cmd) => config::exec(sub_cmd),
+        Cmd::Daemon(sub_cmd) => daemon::exec(sub_cmd),
+        Cmd::Generate(sub_cmd) => generate::exec(sub_cmd),
+        Cmd::Import(sub_cmd) => import::exec(sub_cmd),
         Cmd::Packages(args) => packages::exec(args),
         Cmd::Lsp(args) => lsp::exec(args),
         Cmd::Tx(sub_cmd) => tx::exec(sub_cmd),
@@ -71,7 +85,7 @@ fn panic_handler() {
                 {info}
 
                 {location}{message}"#,
-            info = config::compiler_info(),
+            info = aiken_project::config::compiler_info(),
             fatal = "aiken::fatal::error".red().bold(),
             location = location.purple(),
         };
@@ -0,0 +1,175 @@
+//! With 'aiken --output json', every command prints a single JSON document on stdout: either
+//! its outcome, or a diagnostic saying it can't report it as JSON.
+
+use std::{fs, path::Path, process::Command};
+
+const AIKEN: &str = env!("CARGO_BIN_EXE_aiken");
+
+/// Arguments to run each command with, in the project set up by [`project`].
+fn arguments(command: &str) -> &'static [&'static str] {
+    match command {
+        "new" => &["acme/app"],
+        "export" => &["--module", "numbers", "--name", "increment"],
+        "address" | "blueprint address" | "blueprint policy" | "blueprint hash" => {
+            &["--module", "counter", "--validator", "counter"]
+        }
+        "address inspect" | "blueprint address inspect" => {
+            &["addr_test1wru4335mcq478qmu7ssp56whhavtuxc7efkl57gtr866vfgw3faf2"]
+        }
+        "add" | "packages add" => &["acme/lib", "--version", "main"],
+        "explain" => &["AK0513"],
+        "blueprint build-data" => &["Int", "--from-json", "42"],
+        "blueprint check-datum" => &["--redeemer", "182a"],
+        "blueprint merge" => &["plutus.json", "plutus.json"],
+        "blueprint scaffold" => &["--target", "mesh"],
+        "blueprint import" | "import blueprint" => &["plutus.json"],
+        "config get" => &["plutus"],
+        "config set" => &["plutus", "v3"],
+        "tx simulate" => &["tx.cbor", "inputs.cbor", "outputs.cbor"],
+        "uplc fmt" | "uplc eval" | "uplc encode" | "uplc decode" | "uplc shrink" => {
+            &["program.uplc"]
+        }
+        _ => &[],
+    }
+}
+
+fn project(root: &Path) {
+    let _ = fs::remove_dir_all(root);
+
+    fs::create_dir_all(root.join("lib")).unwrap();
+    fs::create_dir_all(root.join("validators")).unwrap();
+
+    fs::write(
+        root.join("aiken.toml"),
+        "name = \"test/project\"\nversion = \"0.0.0\"\n",
+    )
+    .unwrap();
+
+    fs::write(
+        root.join("lib/numbers.ak"),
+        r#"
+pub fn increment(n: Int) -> Int {
+  n + 1
+}
+
+fn sized(size: Int) -> Fuzzer<Int> {
+  fn(prng) { Some((prng, size)) }
+}
+
+test increment_once() {
+  increment(1) == 2
+}
+
+bench increment_bench(n via sized) {
+  increment(n)
+}
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        root.join("validators/counter.ak"),
+        r#"
+validator counter {
+  mint(redeemer: Int, _policy_id: ByteArray, _self: Data) {
+    redeemer == 42
+  }
+
+  else(_) {
+    fail
+  }
+}
+"#,
+    )
+    .unwrap();
+
+    let status = Command::new(AIKEN)
+        .arg("build")
+        .current_dir(root)
+        .output()
+        .unwrap()
+        .status;
+
+    assert!(status.success(), "couldn't build the test project");
+}
+
+/// Whether a command runs on its own, and the names of its subcommands, as listed by its help.
+fn help(command: &[String]) -> (bool, Vec<String>) {
+    let output = Command::new(AIKEN)
+        .args(command)
+        .arg("--help")
+        .output()
+        .unwrap();
+
+    let help = String::from_utf8(output.stdout).unwrap();
+
+    let runnable = help
+        .lines()
+        .find(|line| line.starts_with("Usage:"))
+        .is_some_and(|usage| !usage.ends_with("<COMMAND>"));
+
+    let subcommands = help
+        .lines()
+        .skip_while(|line| *line != "Commands:")
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| {
+            let name = line.strip_prefix("  ")?.split_whitespace().next()?;
+            (!line.starts_with("   ") && name != "help").then(|| name.to_string())
+        })
+        .collect();
+
+    (runnable, subcommands)
+}
+
+/// Every command that runs on its own, down from the given one.
+fn commands(command: Vec<String>) -> Vec<Vec<String>> {
+    let (runnable, subcommands) = help(&command);
+
+    let mut commands = if runnable {
+        vec![command.clone()]
+    } else {
+        vec![]
+    };
+
+    for name in subcommands {
+        let mut subcommand = command.clone();
+        subcommand.push(name);
+        commands.extend(self::commands(subcommand));
+    }
+
+    commands
+}
+
+#[test]
+fn every_command_prints_json() {
+    let root = std::env::temp_dir().join("aiken-output-json");
+
+    project(&root);
+
+    let commands = commands(vec![]);
+
+    assert!(commands.len() > 40, "{commands:?}");
+
+    for command in commands {
+        let name = command.join(" ");
+
+        let output = Command::new(AIKEN)
+            .args(["--output", "json"])
+            .args(&command)
+            .args(arguments(&name))
+            .current_dir(&root)
+            .output()
+            .unwrap();
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            serde_json::from_str::<serde_json::Value>(&stdout).is_ok(),
+            "'aiken --output json {name}' didn't print a JSON document:\n{stdout}\n{}",
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+
+    fs::remove_dir_all(&root).unwrap();
+}
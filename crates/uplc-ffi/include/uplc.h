@@ -0,0 +1,108 @@
+/*
+ * A small and stable C ABI over the UPLC evaluator.
+ *
+ * Every function returns a `uplc_status`; on anything but `UPLC_OK`, a description of the failure
+ * is available from `uplc_last_error`. Values handed over to the caller are owned by the library
+ * and must be released with the corresponding `*_free` function.
+ *
+ * Scripts are always given as found on-chain (i.e. CBOR-wrapped flat), and Plutus data as CBOR.
+ */
+
+#ifndef UPLC_H
+#define UPLC_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define UPLC_SCRIPT_HASH_SIZE 28
+
+typedef enum {
+  UPLC_OK = 0,
+  /* A null pointer, an unknown Plutus version or a malformed cost model. */
+  UPLC_INVALID_ARGUMENT = 1,
+  /* The script isn't a valid CBOR-wrapped flat-encoded program. */
+  UPLC_INVALID_SCRIPT = 2,
+  /* An argument or parameter isn't valid CBOR-encoded Plutus data. */
+  UPLC_INVALID_DATA = 3,
+  /* Something unexpected happened; this is a bug. */
+  UPLC_INTERNAL_ERROR = 4,
+} uplc_status;
+
+/* A read-only view over bytes owned by the caller. */
+typedef struct {
+  const uint8_t *ptr;
+  size_t len;
+} uplc_slice;
+
+/* Bytes owned by the library; release with `uplc_bytes_free`. */
+typedef struct {
+  uint8_t *ptr;
+  size_t len;
+} uplc_bytes;
+
+/* The outcome of an evaluation; release with `uplc_eval_result_free`. */
+typedef struct {
+  /* Whether the script evaluated to something else than an error. */
+  bool success;
+  /* Budget consumed by the evaluation. */
+  int64_t cpu;
+  int64_t mem;
+  /* The resulting term, pretty-printed; or the evaluation error. */
+  char *result;
+  /* Traces emitted during evaluation, in order. */
+  char **logs;
+  size_t logs_len;
+} uplc_eval_result;
+
+/*
+ * A description of the last failure that occurred on the calling thread, or NULL. The string
+ * remains valid until the next call to the library on the same thread.
+ */
+const char *uplc_last_error(void);
+
+/*
+ * Evaluate a script, applied to the given arguments.
+ *
+ * - plutus_version: 1, 2 or 3;
+ * - args: CBOR-encoded Plutus data, applied in order;
+ * - cost_model: the protocol parameters' cost model for that Plutus version, or none
+ *   (cost_model_len == 0) to use the default one;
+ * - cpu_budget & mem_budget: the execution budget available.
+ *
+ * A script failing is not an error: `out` then reports it as unsuccessful.
+ */
+uplc_status uplc_eval(uint8_t plutus_version,
+                      uplc_slice script,
+                      const uplc_slice *args,
+                      size_t args_len,
+                      const int64_t *cost_model,
+                      size_t cost_model_len,
+                      int64_t cpu_budget,
+                      int64_t mem_budget,
+                      uplc_eval_result *out);
+
+/* Apply parameters (CBOR-encoded Plutus data), in order, to a script. */
+uplc_status uplc_apply_params(uplc_slice script,
+                              const uplc_slice *params,
+                              size_t params_len,
+                              uplc_bytes *out);
+
+/* Compute the hash of a script, writing its UPLC_SCRIPT_HASH_SIZE bytes to `out`. */
+uplc_status uplc_script_hash(uint8_t plutus_version, uplc_slice script, uint8_t *out);
+
+/* Release bytes returned by the library. */
+void uplc_bytes_free(uplc_bytes bytes);
+
+/* Release an evaluation result, leaving it zeroed. */
+void uplc_eval_result_free(uplc_eval_result *result);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* UPLC_H */
@@ -0,0 +1,567 @@
+//! A small and stable C ABI over the UPLC evaluator, for embedding it in non-Rust
+//! infrastructure. See 'include/uplc.h' for the C declarations.
+//!
+//! Every function returns a [`Status`]; on anything but [`Status::Ok`], a description of the
+//! failure is available from [`uplc_last_error`]. Values handed over to the caller are owned by
+//! this library and must be released with the corresponding `*_free` function.
+
+use pallas_primitives::conway::{Language, PlutusScript};
+use pallas_traverse::ComputeHash;
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+use uplc::{
+    ast::{DeBruijn, FakeNamedDeBruijn, Name, NamedDeBruijn, Program, Term},
+    machine::cost_model::ExBudget,
+    PlutusData,
+};
+
+/// Size, in bytes, of a script hash.
+pub const SCRIPT_HASH_SIZE: usize = 28;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok = 0,
+    /// A null pointer, an unknown Plutus version or a malformed cost model.
+    InvalidArgument = 1,
+    /// The script isn't a valid CBOR-wrapped flat-encoded program.
+    InvalidScript = 2,
+    /// An argument or parameter isn't valid CBOR-encoded Plutus data.
+    InvalidData = 3,
+    /// Something unexpected happened; this is a bug.
+    InternalError = 4,
+}
+
+/// A read-only view over bytes owned by the caller.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Slice {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+/// Bytes owned by this library; release with [`uplc_bytes_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct Bytes {
+    pub ptr: *mut u8,
+    pub len: usize,
+}
+
+/// The outcome of an evaluation; release with [`uplc_eval_result_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct EvalResult {
+    /// Whether the script evaluated to something else than an error.
+    pub success: bool,
+    /// Budget consumed by the evaluation.
+    pub cpu: i64,
+    pub mem: i64,
+    /// The resulting term, pretty-printed; or the evaluation error.
+    pub result: *mut c_char,
+    /// Traces emitted during evaluation, in order.
+    pub logs: *mut *mut c_char,
+    pub logs_len: usize,
+}
+
+struct Error {
+    status: Status,
+    message: String,
+}
+
+impl Error {
+    fn new(status: Status, message: impl ToString) -> Self {
+        Error {
+            status,
+            message: message.to_string(),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// A description of the last failure that occurred on the calling thread, or null. The string
+/// remains valid until the next call to this library on the same thread.
+#[no_mangle]
+pub extern "C" fn uplc_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map(|message| message.as_ptr())
+            .unwrap_or(ptr::null())
+    })
+}
+
+/// Evaluate a script, applied to the given arguments.
+///
+/// - `plutus_version`: 1, 2 or 3;
+/// - `script`: the script as found on-chain (i.e. CBOR-wrapped flat);
+/// - `args`: CBOR-encoded Plutus data, applied in order;
+/// - `cost_model`: the protocol parameters' cost model for that Plutus version, or none
+///   (`cost_model_len == 0`) to use the default one;
+/// - `cpu_budget` & `mem_budget`: the execution budget available.
+///
+/// A script failing is not an error: `out` then reports it as unsuccessful.
+///
+/// # Safety
+///
+/// All pointers must be valid for reads of their given length, and `out` for a write.
+#[no_mangle]
+pub unsafe extern "C" fn uplc_eval(
+    plutus_version: u8,
+    script: Slice,
+    args: *const Slice,
+    args_len: usize,
+    cost_model: *const i64,
+    cost_model_len: usize,
+    cpu_budget: i64,
+    mem_budget: i64,
+    out: *mut EvalResult,
+) -> Status {
+    guard(|| {
+        let language = language(plutus_version)?;
+
+        let costs = as_slice(cost_model, cost_model_len)?;
+        check_cost_model(&language, costs)?;
+
+        let mut buffer = Vec::new();
+        let mut program = Program::<FakeNamedDeBruijn>::from_cbor(script.as_bytes()?, &mut buffer)
+            .map_err(|e| Error::new(Status::InvalidScript, e))?;
+
+        for arg in as_slice(args, args_len)? {
+            program = program.apply_data(arg.as_data()?);
+        }
+
+        let program: Program<NamedDeBruijn> = program.into();
+
+        let budget = ExBudget {
+            cpu: cpu_budget,
+            mem: mem_budget,
+        };
+
+        let mut eval_result = if costs.is_empty() {
+            program.eval_version(budget, &language)
+        } else {
+            program.eval_as(&language, costs, Some(&budget))
+        };
+
+        let cost = eval_result.cost();
+        let logs = eval_result.logs();
+
+        let (success, result) = match eval_result.result() {
+            Ok(term) => (
+                true,
+                Term::<Name>::try_from(term)
+                    .map_err(|e| Error::new(Status::InternalError, e))?
+                    .to_pretty(),
+            ),
+            Err(err) => (false, err.to_string()),
+        };
+
+        let logs = Box::leak(logs.into_iter().map(c_string).collect::<Box<[_]>>());
+        let logs_len = logs.len();
+
+        write(
+            out,
+            EvalResult {
+                success,
+                cpu: cost.cpu,
+                mem: cost.mem,
+                result: c_string(result),
+                logs: logs.as_mut_ptr(),
+                logs_len,
+            },
+        )
+    })
+}
+
+/// Apply parameters (CBOR-encoded Plutus data), in order, to a script. The resulting script is
+/// written to `out`, in the same format.
+///
+/// # Safety
+///
+/// All pointers must be valid for reads of their given length, and `out` for a write.
+#[no_mangle]
+pub unsafe extern "C" fn uplc_apply_params(
+    script: Slice,
+    params: *const Slice,
+    params_len: usize,
+    out: *mut Bytes,
+) -> Status {
+    guard(|| {
+        let mut buffer = Vec::new();
+        let mut program = Program::<DeBruijn>::from_cbor(script.as_bytes()?, &mut buffer)
+            .map_err(|e| Error::new(Status::InvalidScript, e))?;
+
+        for param in as_slice(params, params_len)? {
+            program = program.apply_data(param.as_data()?);
+        }
+
+        let cbor = program
+            .to_cbor()
+            .map_err(|e| Error::new(Status::InternalError, e))?;
+
+        write(out, Bytes::from(cbor))
+    })
+}
+
+/// Compute the hash of a script, as found on-chain (i.e. CBOR-wrapped flat), writing its
+/// [`SCRIPT_HASH_SIZE`] bytes to `out`.
+///
+/// # Safety
+///
+/// `script` must be valid for reads of its length, and `out` for writes of
+/// [`SCRIPT_HASH_SIZE`] bytes.
+#[no_mangle]
+pub unsafe extern "C" fn uplc_script_hash(
+    plutus_version: u8,
+    script: Slice,
+    out: *mut u8,
+) -> Status {
+    guard(|| {
+        let bytes = script.as_bytes()?.to_vec();
+
+        let hash = match language(plutus_version)? {
+            Language::PlutusV1 => PlutusScript::<1>(bytes.into()).compute_hash(),
+            Language::PlutusV2 => PlutusScript::<2>(bytes.into()).compute_hash(),
+            Language::PlutusV3 => PlutusScript::<3>(bytes.into()).compute_hash(),
+        };
+
+        if out.is_null() {
+            return Err(Error::new(Status::InvalidArgument, "null output pointer"));
+        }
+
+        ptr::copy_nonoverlapping(hash.as_ref().as_ptr(), out, SCRIPT_HASH_SIZE);
+
+        Ok(())
+    })
+}
+
+/// Release bytes returned by this library.
+///
+/// # Safety
+///
+/// `bytes` must come from this library, and not have been released already.
+#[no_mangle]
+pub unsafe extern "C" fn uplc_bytes_free(bytes: Bytes) {
+    if !bytes.ptr.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            bytes.ptr, bytes.len,
+        )));
+    }
+}
+
+/// Release an evaluation result, leaving it zeroed.
+///
+/// # Safety
+///
+/// `result` must be null or come from [`uplc_eval`], and not have been released already.
+#[no_mangle]
+pub unsafe extern "C" fn uplc_eval_result_free(result: *mut EvalResult) {
+    let Some(result) = result.as_mut() else {
+        return;
+    };
+
+    if !result.result.is_null() {
+        drop(CString::from_raw(result.result));
+    }
+
+    if !result.logs.is_null() {
+        let logs = Box::from_raw(ptr::slice_from_raw_parts_mut(result.logs, result.logs_len));
+        for log in logs.iter() {
+            drop(CString::from_raw(*log));
+        }
+    }
+
+    *result = EvalResult {
+        success: false,
+        cpu: 0,
+        mem: 0,
+        result: ptr::null_mut(),
+        logs: ptr::null_mut(),
+        logs_len: 0,
+    };
+}
+
+impl Slice {
+    unsafe fn as_bytes<'a>(&self) -> Result<&'a [u8], Error> {
+        as_slice(self.ptr, self.len)
+    }
+
+    unsafe fn as_data(&self) -> Result<PlutusData, Error> {
+        uplc::plutus_data(self.as_bytes()?).map_err(|e| Error::new(Status::InvalidData, e))
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        let bytes = Box::leak(bytes.into_boxed_slice());
+
+        Bytes {
+            ptr: bytes.as_mut_ptr(),
+            len: bytes.len(),
+        }
+    }
+}
+
+/// Run a function at the boundary, recording its failure (panics included) as the last error.
+fn guard(f: impl FnOnce() -> Result<(), Error>) -> Status {
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+
+        Err(Error::new(Status::InternalError, message))
+    });
+
+    let (status, last_error) = match result {
+        Ok(()) => (Status::Ok, None),
+        Err(Error { status, message }) => (status, Some(c_string_owned(message))),
+    };
+
+    LAST_ERROR.with(|last| *last.borrow_mut() = last_error);
+
+    status
+}
+
+fn language(plutus_version: u8) -> Result<Language, Error> {
+    match plutus_version {
+        1 => Ok(Language::PlutusV1),
+        2 => Ok(Language::PlutusV2),
+        3 => Ok(Language::PlutusV3),
+        _ => Err(Error::new(
+            Status::InvalidArgument,
+            format!("unknown Plutus version: {plutus_version}"),
+        )),
+    }
+}
+
+/// Cost models are given positionally, and may grow with new builtins; they can't be shorter
+/// than what the evaluator knows of, though.
+fn check_cost_model(language: &Language, costs: &[i64]) -> Result<(), Error> {
+    let min_len = match language {
+        Language::PlutusV1 => 166,
+        Language::PlutusV2 => 175,
+        Language::PlutusV3 => 251,
+    };
+
+    if costs.is_empty() || costs.len() >= min_len {
+        Ok(())
+    } else {
+        Err(Error::new(
+            Status::InvalidArgument,
+            format!(
+                "cost model too short: expected at least {min_len} entries, got {}",
+                costs.len()
+            ),
+        ))
+    }
+}
+
+unsafe fn as_slice<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T], Error> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(Error::new(Status::InvalidArgument, "null pointer"))
+    } else {
+        Ok(slice::from_raw_parts(ptr, len))
+    }
+}
+
+unsafe fn write<T>(out: *mut T, value: T) -> Result<(), Error> {
+    match out.as_mut() {
+        Some(out) => {
+            ptr::write(out, value);
+            Ok(())
+        }
+        None => Err(Error::new(Status::InvalidArgument, "null output pointer")),
+    }
+}
+
+fn c_string(s: String) -> *mut c_char {
+    c_string_owned(s).into_raw()
+}
+
+fn c_string_owned(s: String) -> CString {
+    CString::new(s.replace('\0', "")).expect("no interior nul byte")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use uplc::parser;
+
+    unsafe fn read_c_string(s: *const c_char) -> String {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+
+    fn script(code: &str) -> Vec<u8> {
+        parser::program(code)
+            .unwrap()
+            .to_debruijn()
+            .unwrap()
+            .to_cbor()
+            .unwrap()
+    }
+
+    fn slice(bytes: &[u8]) -> Slice {
+        Slice {
+            ptr: bytes.as_ptr(),
+            len: bytes.len(),
+        }
+    }
+
+    const BUDGET: ExBudget = ExBudget {
+        cpu: 10_000_000_000,
+        mem: 14_000_000,
+    };
+
+    unsafe fn eval(version: u8, script: &[u8], args: &[Vec<u8>], costs: &[i64]) -> EvalResult {
+        let args = args.iter().map(|arg| slice(arg)).collect::<Vec<_>>();
+        let mut out = std::mem::zeroed::<EvalResult>();
+
+        let status = uplc_eval(
+            version,
+            slice(script),
+            args.as_ptr(),
+            args.len(),
+            costs.as_ptr(),
+            costs.len(),
+            BUDGET.cpu,
+            BUDGET.mem,
+            &mut out,
+        );
+
+        assert_eq!(status, Status::Ok, "{}", read_c_string(uplc_last_error()));
+
+        out
+    }
+
+    #[test]
+    fn eval_with_args() {
+        let script = script(
+            "(program 1.1.0 (lam d [(force (builtin trace)) (con string \"hello\") [(builtin unIData) d]]))",
+        );
+
+        unsafe {
+            let mut out = eval(3, &script, &[vec![0x18, 0x2a]], &[]);
+
+            assert!(out.success);
+            assert!(out.cpu > 0 && out.mem > 0);
+            assert_eq!(read_c_string(out.result), "(con integer 42)");
+            assert_eq!(out.logs_len, 1);
+            assert_eq!(read_c_string(*out.logs), "hello");
+
+            uplc_eval_result_free(&mut out);
+            assert!(out.result.is_null());
+        }
+    }
+
+    #[test]
+    fn eval_failure() {
+        let script = script("(program 1.1.0 (lam d [(builtin unBData) d]))");
+
+        unsafe {
+            let mut out = eval(3, &script, &[vec![0x18, 0x2a]], &[]);
+
+            assert!(!out.success);
+            assert_eq!(out.logs_len, 0);
+
+            uplc_eval_result_free(&mut out);
+        }
+    }
+
+    #[test]
+    fn eval_invalid_inputs() {
+        let script = script("(program 1.1.0 (lam d d))");
+        let mut out = unsafe { std::mem::zeroed::<EvalResult>() };
+
+        let status = unsafe {
+            uplc_eval(
+                3,
+                slice(&script),
+                ptr::null(),
+                0,
+                [0i64; 10].as_ptr(),
+                10,
+                BUDGET.cpu,
+                BUDGET.mem,
+                &mut out,
+            )
+        };
+        assert_eq!(status, Status::InvalidArgument);
+        assert!(unsafe { read_c_string(uplc_last_error()) }.contains("cost model too short"));
+
+        let status = unsafe {
+            uplc_eval(
+                3,
+                slice(&[0xde, 0xad]),
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+                BUDGET.cpu,
+                BUDGET.mem,
+                &mut out,
+            )
+        };
+        assert_eq!(status, Status::InvalidScript);
+
+        let status = unsafe {
+            uplc_eval(
+                4,
+                slice(&script),
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+                BUDGET.cpu,
+                BUDGET.mem,
+                &mut out,
+            )
+        };
+        assert_eq!(status, Status::InvalidArgument);
+    }
+
+    #[test]
+    fn apply_params_and_hash() {
+        let script = script("(program 1.1.0 (lam p (lam d [(builtin equalsData) p d])))");
+        let param = [0x18, 0x2a];
+
+        unsafe {
+            let mut applied = std::mem::zeroed::<Bytes>();
+            let status = uplc_apply_params(slice(&script), &slice(&param), 1, &mut applied);
+            assert_eq!(status, Status::Ok);
+
+            let applied_script = slice::from_raw_parts(applied.ptr, applied.len).to_vec();
+            uplc_bytes_free(applied);
+
+            let mut out = eval(3, &applied_script, &[param.to_vec()], &[]);
+            assert_eq!(read_c_string(out.result), "(con bool True)");
+            uplc_eval_result_free(&mut out);
+
+            let mut hash = [0u8; SCRIPT_HASH_SIZE];
+            let status = uplc_script_hash(3, slice(&applied_script), hash.as_mut_ptr());
+            assert_eq!(status, Status::Ok);
+
+            assert_eq!(
+                hash.to_vec(),
+                uplc::ast::SerializableProgram::PlutusV3Program(
+                    Program::<DeBruijn>::from_cbor(&applied_script, &mut Vec::new()).unwrap()
+                )
+                .compiled_code_and_hash()
+                .1
+                .to_vec()
+            );
+        }
+    }
+}
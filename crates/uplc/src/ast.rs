@@ -3,8 +3,10 @@ use crate::{
     debruijn::{self, Converter},
     flat::Binder,
     machine::{
+        breakdown::Breakdown,
         cost_model::{initialize_cost_model, CostModel, ExBudget},
         eval_result::EvalResult,
+        profile::Profile,
         Machine,
     },
     optimize::interner::CodeGenInterner,
@@ -69,6 +71,19 @@ where
             term: applied_term,
         }
     }
+
+    /// Same as `apply_data`, for any constant.
+    pub fn apply_constant(&self, constant: Constant) -> Self {
+        let applied_term = Term::Apply {
+            function: Rc::new(self.term.clone()),
+            argument: Rc::new(Term::Constant(constant.into())),
+        };
+
+        Program {
+            version: self.version,
+            term: applied_term,
+        }
+    }
 }
 
 impl Program<Name> {
@@ -864,6 +879,62 @@ impl Program<NamedDeBruijn> {
         EvalResult::new(term, machine.ex_budget, initial_budget, machine.logs)
     }
 
+    /// Evaluate a Program while attributing the spent budget to the functions it goes through.
+    pub fn eval_with_profile(
+        self,
+        initial_budget: ExBudget,
+        version: &Language,
+    ) -> (EvalResult, Profile) {
+        let mut machine = Machine::new(version.clone(), CostModel::default(), initial_budget, 200)
+            .with_profile(&self.term);
+
+        let term = machine.run(self.term);
+
+        let profile = machine
+            .profile
+            .take()
+            .expect("machine created with a profile");
+
+        (
+            EvalResult::new(term, machine.ex_budget, initial_budget, machine.logs),
+            profile,
+        )
+    }
+
+    /// Evaluate a Program, breaking the spent budget down by kind of step and by builtin. Costs
+    /// default to those of the given version when none are provided.
+    pub fn eval_with_breakdown(
+        self,
+        initial_budget: ExBudget,
+        version: &Language,
+        costs: Option<&[i64]>,
+        with_steps: bool,
+    ) -> (EvalResult, Breakdown) {
+        let cost_model = match costs {
+            Some(costs) => initialize_cost_model(version, costs),
+            None => match version {
+                Language::PlutusV1 => CostModel::v1(),
+                Language::PlutusV2 => CostModel::v2(),
+                Language::PlutusV3 => CostModel::v3(),
+            },
+        };
+
+        let mut machine = Machine::new(version.clone(), cost_model, initial_budget, 200)
+            .with_breakdown(with_steps);
+
+        let term = machine.run(self.term);
+
+        let breakdown = machine
+            .breakdown
+            .take()
+            .expect("machine created with a breakdown");
+
+        (
+            EvalResult::new(term, machine.ex_budget, initial_budget, machine.logs),
+            breakdown,
+        )
+    }
+
     pub fn eval_as(
         self,
         version: &Language,
@@ -1,5 +1,6 @@
 use crate::ast::Term;
 use pallas_codec::flat::de;
+use pallas_primitives::conway::Language;
 use std::{fmt::Display, rc::Rc, str::FromStr};
 use strum_macros::EnumIter;
 
@@ -503,6 +504,19 @@ impl Display for DefaultFunction {
 }
 
 impl DefaultFunction {
+    /// Whether the builtin may be used in scripts of the given Plutus version. Builtins are
+    /// numbered in order of introduction: the first 51 came with Plutus V1, then data
+    /// serialisation and ECDSA/Schnorr signatures with V2, and everything else with V3.
+    pub fn is_available_in(&self, language: &Language) -> bool {
+        match language {
+            Language::PlutusV1 => (*self as u8) <= DefaultFunction::MkNilPairData as u8,
+            Language::PlutusV2 => {
+                (*self as u8) <= DefaultFunction::VerifySchnorrSecp256k1Signature as u8
+            }
+            Language::PlutusV3 => true,
+        }
+    }
+
     pub fn aiken_name(&self) -> String {
         use DefaultFunction::*;
 
@@ -2,10 +2,12 @@ use std::rc::Rc;
 
 use crate::ast::{Constant, NamedDeBruijn, Term, Type};
 
+pub mod breakdown;
 pub mod cost_model;
 mod discharge;
 mod error;
 pub mod eval_result;
+pub mod profile;
 pub mod runtime;
 pub mod value;
 
@@ -14,7 +16,9 @@ pub use error::Error;
 use pallas_primitives::conway::Language;
 
 use self::{
+    breakdown::Breakdown,
     cost_model::CostModel,
+    profile::Profile,
     runtime::BuiltinRuntime,
     value::{Env, Value},
 };
@@ -39,6 +43,7 @@ enum Context {
         Box<Context>,
     ),
     FrameCases(Env, Vec<Term<NamedDeBruijn>>, Box<Context>),
+    FrameProfile(Box<Context>),
     NoFrame,
 }
 
@@ -48,6 +53,8 @@ pub struct Machine {
     slippage: u32,
     unbudgeted_steps: [u32; 10],
     pub logs: Vec<String>,
+    pub profile: Option<Profile>,
+    pub breakdown: Option<Breakdown>,
     version: Language,
 }
 
@@ -64,10 +71,25 @@ impl Machine {
             slippage,
             unbudgeted_steps: [0; 10],
             logs: vec![],
+            profile: None,
+            breakdown: None,
             version,
         }
     }
 
+    /// Attribute the budget spent while running the given term to the functions it goes through.
+    pub fn with_profile(mut self, term: &Term<NamedDeBruijn>) -> Self {
+        self.profile = Some(Profile::new(term));
+        self
+    }
+
+    /// Break the spent budget down by kind of step and by builtin, recording every step taken
+    /// along the way if asked to.
+    pub fn with_breakdown(mut self, with_steps: bool) -> Self {
+        self.breakdown = Some(Breakdown::new(with_steps));
+        self
+    }
+
     pub fn run(&mut self, term: Term<NamedDeBruijn>) -> Result<Term<NamedDeBruijn>, Error> {
         use MachineState::*;
 
@@ -75,6 +97,14 @@ impl Machine {
 
         self.spend_budget(startup_budget)?;
 
+        if let Some(profile) = self.profile.as_mut() {
+            profile.spend(startup_budget);
+        }
+
+        if let Some(breakdown) = self.breakdown.as_mut() {
+            breakdown.start(startup_budget);
+        }
+
         let mut state = Compute(Context::NoFrame, Rc::new(vec![]), term);
 
         loop {
@@ -94,6 +124,10 @@ impl Machine {
         env: Env,
         term: Term<NamedDeBruijn>,
     ) -> Result<MachineState, Error> {
+        if let Some(breakdown) = self.breakdown.as_mut() {
+            breakdown.compute(&term, &self.costs.machine_costs);
+        }
+
         match term {
             Term::Var(name) => {
                 self.step_and_maybe_spend(StepKind::Var)?;
@@ -249,12 +283,21 @@ impl Machine {
                 },
                 v => Err(Error::NonConstrScrutinized(v)),
             },
+            Context::FrameProfile(ctx) => {
+                if let Some(profile) = self.profile.as_mut() {
+                    profile.leave();
+                }
+
+                Ok(MachineState::Return(*ctx, value))
+            }
         }
     }
 
     fn force_evaluate(&mut self, context: Context, value: Value) -> Result<MachineState, Error> {
         match value {
             Value::Delay(body, env) => {
+                let context = self.enter(context, &body);
+
                 Ok(MachineState::Compute(context, env, body.as_ref().clone()))
             }
             Value::Builtin { fun, mut runtime } => {
@@ -290,6 +333,8 @@ impl Machine {
 
                 e.push(argument);
 
+                let context = self.enter(context, &body);
+
                 Ok(MachineState::Compute(
                     context,
                     Rc::new(e.clone()),
@@ -324,9 +369,31 @@ impl Machine {
 
         self.spend_budget(cost)?;
 
+        if let Some(profile) = self.profile.as_mut() {
+            profile.spend_builtin(runtime.fun, cost);
+        }
+
+        if let Some(breakdown) = self.breakdown.as_mut() {
+            breakdown.call(runtime.fun, cost);
+        }
+
         runtime.call(&self.version, &mut self.logs)
     }
 
+    fn enter(&mut self, context: Context, body: &Rc<Term<NamedDeBruijn>>) -> Context {
+        let entered = self
+            .profile
+            .as_mut()
+            .map(|profile| profile.enter(body))
+            .unwrap_or(false);
+
+        if entered {
+            Context::FrameProfile(context.into())
+        } else {
+            context
+        }
+    }
+
     fn lookup_var(&mut self, name: &NamedDeBruijn, env: &[Value]) -> Result<Value, Error> {
         env.get::<usize>(env.len() - usize::from(name.index))
             .cloned()
@@ -334,6 +401,10 @@ impl Machine {
     }
 
     fn step_and_maybe_spend(&mut self, step: StepKind) -> Result<(), Error> {
+        if let Some(profile) = self.profile.as_mut() {
+            profile.spend(self.costs.machine_costs.get(step));
+        }
+
         let index = step as u8;
         self.unbudgeted_steps[index as usize] += 1;
         self.unbudgeted_steps[9] += 1;
@@ -0,0 +1,238 @@
+use super::cost_model::{ExBudget, MachineCosts, StepKind};
+use crate::{
+    ast::{NamedDeBruijn, Term},
+    builtins::DefaultFunction,
+};
+
+/// Longest rendering of a constant in the steps of an evaluation, past which it's truncated.
+const MAX_CONSTANT_WIDTH: usize = 60;
+
+/// Execution budget spent during an evaluation, broken down by kind of machine step and by
+/// builtin; along with every step taken, when asked for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakdown {
+    pub startup: ExBudget,
+    /// Machine steps of each kind, in the order of 'StepKind'.
+    pub machine: Vec<(StepKind, Usage)>,
+    /// Builtins called, in the order they were first called.
+    pub builtins: Vec<(DefaultFunction, Usage)>,
+    pub steps: Option<Vec<Step>>,
+    spent: ExBudget,
+}
+
+/// How many times something happened, and what it cost altogether.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    pub count: usize,
+    pub cost: ExBudget,
+}
+
+impl Default for Usage {
+    fn default() -> Self {
+        Usage {
+            count: 0,
+            cost: ExBudget { mem: 0, cpu: 0 },
+        }
+    }
+}
+
+impl Usage {
+    fn add(&mut self, cost: ExBudget) {
+        self.count += 1;
+        self.cost.mem += cost.mem;
+        self.cost.cpu += cost.cpu;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Computing a term, shown by its head (e.g. '[...]' for an application).
+    Compute { term: String, spent: ExBudget },
+    /// Calling a builtin, once given all its arguments.
+    Builtin {
+        fun: DefaultFunction,
+        cost: ExBudget,
+        spent: ExBudget,
+    },
+}
+
+impl Breakdown {
+    pub fn new(with_steps: bool) -> Self {
+        Breakdown {
+            startup: ExBudget { mem: 0, cpu: 0 },
+            machine: [
+                StepKind::Constant,
+                StepKind::Var,
+                StepKind::Lambda,
+                StepKind::Apply,
+                StepKind::Delay,
+                StepKind::Force,
+                StepKind::Builtin,
+                StepKind::Constr,
+                StepKind::Case,
+            ]
+            .into_iter()
+            .map(|kind| (kind, Usage::default()))
+            .collect(),
+            builtins: Vec::new(),
+            steps: with_steps.then(Vec::new),
+            spent: ExBudget { mem: 0, cpu: 0 },
+        }
+    }
+
+    /// Total budget accounted for so far.
+    pub fn spent(&self) -> ExBudget {
+        self.spent
+    }
+
+    pub(super) fn start(&mut self, cost: ExBudget) {
+        self.startup = cost;
+        self.spend(cost);
+    }
+
+    pub(super) fn compute(&mut self, term: &Term<NamedDeBruijn>, costs: &MachineCosts) {
+        if let Some(kind) = step_kind(term) {
+            let cost = costs.get(kind);
+
+            self.machine[kind as usize].1.add(cost);
+
+            self.spend(cost);
+        }
+
+        let spent = self.spent;
+
+        if let Some(steps) = self.steps.as_mut() {
+            steps.push(Step::Compute {
+                term: head(term),
+                spent,
+            });
+        }
+    }
+
+    pub(super) fn call(&mut self, fun: DefaultFunction, cost: ExBudget) {
+        match self
+            .builtins
+            .iter_mut()
+            .find(|(builtin, _)| *builtin == fun)
+        {
+            Some((_, usage)) => usage.add(cost),
+            None => {
+                let mut usage = Usage::default();
+                usage.add(cost);
+                self.builtins.push((fun, usage));
+            }
+        }
+
+        self.spend(cost);
+
+        let spent = self.spent;
+
+        if let Some(steps) = self.steps.as_mut() {
+            steps.push(Step::Builtin { fun, cost, spent });
+        }
+    }
+
+    fn spend(&mut self, cost: ExBudget) {
+        self.spent.mem += cost.mem;
+        self.spent.cpu += cost.cpu;
+    }
+}
+
+fn step_kind(term: &Term<NamedDeBruijn>) -> Option<StepKind> {
+    match term {
+        Term::Var(_) => Some(StepKind::Var),
+        Term::Delay(_) => Some(StepKind::Delay),
+        Term::Lambda { .. } => Some(StepKind::Lambda),
+        Term::Apply { .. } => Some(StepKind::Apply),
+        Term::Constant(_) => Some(StepKind::Constant),
+        Term::Force(_) => Some(StepKind::Force),
+        Term::Error => None,
+        Term::Builtin(_) => Some(StepKind::Builtin),
+        Term::Constr { .. } => Some(StepKind::Constr),
+        Term::Case { .. } => Some(StepKind::Case),
+    }
+}
+
+/// The outermost part of a term, leaving out sub-terms.
+fn head(term: &Term<NamedDeBruijn>) -> String {
+    match term {
+        Term::Var(name) => name.text.clone(),
+        Term::Delay(_) => "(delay ...)".to_string(),
+        Term::Lambda { parameter_name, .. } => format!("(lam {} ...)", parameter_name.text),
+        Term::Apply { .. } => "[...]".to_string(),
+        Term::Constant(constant) => {
+            let constant = constant
+                .to_pretty()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            match constant.char_indices().nth(MAX_CONSTANT_WIDTH) {
+                Some((end, _)) => format!("(con {}...)", &constant[..end]),
+                None => format!("(con {constant})"),
+            }
+        }
+        Term::Force(_) => "(force ...)".to_string(),
+        Term::Error => "(error)".to_string(),
+        Term::Builtin(fun) => format!("(builtin {fun})"),
+        Term::Constr { tag, .. } => format!("(constr {tag} ...)"),
+        Term::Case { .. } => "(case ...)".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ast::{Name, Program},
+        parser,
+    };
+    use pallas_primitives::conway::Language;
+
+    #[test]
+    fn breakdown_adds_up() {
+        let program: Program<Name> = parser::program(
+            "(program 1.0.0 [(lam x [(builtin addInteger) x (con integer 14)]) (con integer 28)])",
+        )
+        .unwrap();
+
+        let program = Program::<NamedDeBruijn>::try_from(program).unwrap();
+
+        let (eval_result, breakdown) =
+            program.eval_with_breakdown(ExBudget::max(), &Language::PlutusV3, None, true);
+
+        assert_eq!(breakdown.spent(), eval_result.cost());
+
+        let count = |kind: StepKind| breakdown.machine[kind as usize].1.count;
+        assert_eq!(count(StepKind::Apply), 3);
+        assert_eq!(count(StepKind::Constant), 2);
+        assert_eq!(count(StepKind::Var), 1);
+        assert_eq!(count(StepKind::Builtin), 1);
+
+        assert_eq!(breakdown.builtins.len(), 1);
+        assert_eq!(breakdown.builtins[0].0, DefaultFunction::AddInteger);
+        assert_eq!(breakdown.builtins[0].1.count, 1);
+
+        let steps = breakdown.steps.unwrap();
+        assert_eq!(
+            steps
+                .iter()
+                .map(|step| match step {
+                    Step::Compute { term, .. } => term.clone(),
+                    Step::Builtin { fun, .. } => format!("call {fun}"),
+                })
+                .collect::<Vec<_>>(),
+            [
+                "[...]",
+                "(lam x ...)",
+                "(con integer 28)",
+                "[...]",
+                "[...]",
+                "(builtin addInteger)",
+                "x",
+                "(con integer 14)",
+                "call addInteger",
+            ]
+        );
+    }
+}
@@ -5344,6 +5344,7 @@ pub struct TwoArgumentsQuadraticFunction {
     coeff_02: i64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum StepKind {
     Constant = 0,
@@ -0,0 +1,468 @@
+use std::{collections::HashMap, fmt::Write, rc::Rc};
+
+use super::cost_model::ExBudget;
+use crate::{
+    ast::{NamedDeBruijn, Term},
+    builtins::DefaultFunction,
+};
+
+/// Execution budget attributed to the functions a program goes through while being evaluated.
+///
+/// Functions are recognised from the shape of the program: any lambda bound to a name, either
+/// through a let-binding (`[(lam name rest) (lam x body)]`) or through the self-application used
+/// for recursion, is reported under that name. Each call pushes a frame on top of the caller's;
+/// builtins show up as leaves of the function invoking them. This makes it possible to render the
+/// result as a flamegraph, either as collapsed stacks or directly as an SVG.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    functions: HashMap<*const Term<NamedDeBruijn>, usize>,
+    // Keeps the function bodies alive, so that their addresses are never reused while profiling.
+    bodies: Vec<Rc<Term<NamedDeBruijn>>>,
+    names: Vec<String>,
+    frames: Vec<Frame>,
+    current: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Cpu,
+    Mem,
+}
+
+impl Unit {
+    fn of(&self, budget: &ExBudget) -> i64 {
+        match self {
+            Unit::Cpu => budget.cpu,
+            Unit::Mem => budget.mem,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Label {
+    Program,
+    Function(usize),
+    Builtin(DefaultFunction),
+}
+
+#[derive(Debug, Clone)]
+struct Frame {
+    label: Label,
+    parent: usize,
+    children: Vec<usize>,
+    cost: ExBudget,
+}
+
+impl Frame {
+    fn new(label: Label, parent: usize) -> Self {
+        Frame {
+            label,
+            parent,
+            children: vec![],
+            cost: ExBudget { mem: 0, cpu: 0 },
+        }
+    }
+}
+
+impl Profile {
+    pub fn new(term: &Term<NamedDeBruijn>) -> Self {
+        let mut profile = Profile {
+            functions: HashMap::new(),
+            bodies: vec![],
+            names: vec![],
+            frames: vec![Frame::new(Label::Program, 0)],
+            current: 0,
+        };
+
+        profile.collect_functions(term);
+
+        profile
+    }
+
+    fn collect_functions(&mut self, term: &Term<NamedDeBruijn>) {
+        match term {
+            Term::Apply { function, argument } => {
+                if let Term::Lambda { parameter_name, .. } = function.as_ref() {
+                    let body = match argument.as_ref() {
+                        Term::Lambda { body, .. } => {
+                            let mut body = body;
+                            while let Term::Lambda { body: inner, .. } = body.as_ref() {
+                                body = inner;
+                            }
+                            // Functions without arguments are delayed, and only entered once forced.
+                            if let Term::Delay(inner) = body.as_ref() {
+                                body = inner;
+                            }
+                            Some(body)
+                        }
+                        Term::Delay(body) => Some(body),
+                        _ => None,
+                    };
+
+                    if let Some(body) = body {
+                        self.register(&parameter_name.text, body);
+                    }
+                }
+
+                self.collect_functions(function);
+                self.collect_functions(argument);
+            }
+            Term::Delay(body) | Term::Force(body) | Term::Lambda { body, .. } => {
+                self.collect_functions(body)
+            }
+            Term::Constr { fields, .. } => {
+                for field in fields {
+                    self.collect_functions(field);
+                }
+            }
+            Term::Case { constr, branches } => {
+                self.collect_functions(constr);
+                for branch in branches {
+                    self.collect_functions(branch);
+                }
+            }
+            Term::Var(_) | Term::Constant(_) | Term::Error | Term::Builtin(_) => {}
+        }
+    }
+
+    fn register(&mut self, name: &str, body: &Rc<Term<NamedDeBruijn>>) {
+        if self.functions.contains_key(&Rc::as_ptr(body)) {
+            return;
+        }
+
+        let index = match self.names.iter().position(|known| known == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_string());
+                self.names.len() - 1
+            }
+        };
+
+        self.functions.insert(Rc::as_ptr(body), index);
+        self.bodies.push(body.clone());
+    }
+
+    /// Give functions a more meaningful name, e.g. the one they have in the source code.
+    pub fn rename(&mut self, rename: impl Fn(&str) -> Option<String>) {
+        for name in self.names.iter_mut() {
+            if let Some(renamed) = rename(name) {
+                *name = renamed;
+            }
+        }
+    }
+
+    /// Enter the given function body, if it belongs to a known function. Returns whether a frame
+    /// was pushed, in which case it must be left once the body has been reduced to a value.
+    pub(super) fn enter(&mut self, body: &Rc<Term<NamedDeBruijn>>) -> bool {
+        match self.functions.get(&Rc::as_ptr(body)) {
+            Some(name) => {
+                self.current = self.child(Label::Function(*name));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(super) fn leave(&mut self) {
+        self.current = self.frames[self.current].parent;
+    }
+
+    pub(super) fn spend(&mut self, cost: ExBudget) {
+        let frame = &mut self.frames[self.current];
+        frame.cost.mem += cost.mem;
+        frame.cost.cpu += cost.cpu;
+    }
+
+    pub(super) fn spend_builtin(&mut self, fun: DefaultFunction, cost: ExBudget) {
+        let index = self.child(Label::Builtin(fun));
+        let frame = &mut self.frames[index];
+        frame.cost.mem += cost.mem;
+        frame.cost.cpu += cost.cpu;
+    }
+
+    fn child(&mut self, label: Label) -> usize {
+        let existing = self.frames[self.current]
+            .children
+            .iter()
+            .find(|child| self.frames[**child].label == label);
+
+        match existing {
+            Some(index) => *index,
+            None => {
+                let index = self.frames.len();
+                self.frames.push(Frame::new(label, self.current));
+                self.frames[self.current].children.push(index);
+                index
+            }
+        }
+    }
+
+    fn name(&self, frame: &Frame) -> String {
+        match frame.label {
+            Label::Program => "program".to_string(),
+            Label::Function(name) => self.names[name].clone(),
+            Label::Builtin(fun) => fun.to_string(),
+        }
+    }
+
+    /// The budget spent by each frame and all the frames above it.
+    fn totals(&self) -> Vec<ExBudget> {
+        let mut totals: Vec<ExBudget> = self.frames.iter().map(|frame| frame.cost).collect();
+
+        // Children are always allocated after their parent.
+        for index in (1..self.frames.len()).rev() {
+            let parent = self.frames[index].parent;
+            totals[parent].mem += totals[index].mem;
+            totals[parent].cpu += totals[index].cpu;
+        }
+
+        totals
+    }
+
+    pub fn total(&self) -> ExBudget {
+        self.totals()[0]
+    }
+
+    /// Render the profile as collapsed stacks; one line per stack, with its frames separated by
+    /// semicolons and followed by the units spent by the topmost frame. This is the format
+    /// expected by most flamegraph tools (e.g. inferno, flamegraph.pl or speedscope).
+    pub fn collapsed(&self, unit: Unit) -> String {
+        let mut stacks = self
+            .frames
+            .iter()
+            .enumerate()
+            .filter(|(_, frame)| unit.of(&frame.cost) > 0)
+            .map(|(index, frame)| {
+                let mut stack = vec![self.name(frame)];
+                let mut index = index;
+                while index != 0 {
+                    index = self.frames[index].parent;
+                    stack.push(self.name(&self.frames[index]));
+                }
+                stack.reverse();
+                format!("{} {}", stack.join(";"), unit.of(&frame.cost))
+            })
+            .collect::<Vec<_>>();
+
+        stacks.sort();
+
+        stacks.join("\n")
+    }
+
+    /// Render the profile as a self-contained SVG flamegraph, where the width of each frame is
+    /// proportional to the units spent by it and everything it calls.
+    pub fn to_svg(&self, unit: Unit) -> String {
+        const WIDTH: f64 = 1200.0;
+        const PADDING: f64 = 10.0;
+        const FRAME_HEIGHT: f64 = 16.0;
+        const CHAR_WIDTH: f64 = 7.0;
+
+        let totals = self.totals();
+        let total = unit.of(&totals[0]).max(1) as f64;
+        let scale = (WIDTH - 2.0 * PADDING) / total;
+
+        let depth = |mut index: usize| {
+            let mut depth = 0;
+            while index != 0 {
+                index = self.frames[index].parent;
+                depth += 1;
+            }
+            depth
+        };
+
+        let max_depth = (0..self.frames.len()).map(depth).max().unwrap_or(0);
+        let height = (max_depth + 1) as f64 * FRAME_HEIGHT + 3.0 * PADDING + FRAME_HEIGHT;
+
+        let mut svg = String::new();
+
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}" font-family="monospace" font-size="12">"#
+        );
+        let _ = writeln!(
+            svg,
+            r##"<rect x="0" y="0" width="{WIDTH}" height="{height}" fill="#f8f8f8"/>"##
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle" font-size="16">Flame Graph ({})</text>"#,
+            WIDTH / 2.0,
+            PADDING + FRAME_HEIGHT,
+            match unit {
+                Unit::Cpu => "cpu",
+                Unit::Mem => "mem",
+            }
+        );
+
+        let mut queue = vec![(0, PADDING)];
+
+        while let Some((index, x)) = queue.pop() {
+            let frame = &self.frames[index];
+            let width = unit.of(&totals[index]) as f64 * scale;
+
+            if width < 0.1 {
+                continue;
+            }
+
+            let y = height - PADDING - (depth(index) + 1) as f64 * FRAME_HEIGHT;
+            let name = self.name(frame);
+
+            let _ = writeln!(
+                svg,
+                r#"<g><title>{} (cpu: {}, mem: {}, {:.2}%)</title><rect x="{x:.2}" y="{y:.2}" width="{width:.2}" height="{}" fill="{}" rx="2"/>"#,
+                escape(&name),
+                totals[index].cpu,
+                totals[index].mem,
+                100.0 * unit.of(&totals[index]) as f64 / total,
+                FRAME_HEIGHT - 1.0,
+                color(&frame.label, &name),
+            );
+
+            let fits = ((width - 6.0) / CHAR_WIDTH).floor() as usize;
+            if fits >= 3 {
+                let text = if name.chars().count() > fits {
+                    format!("{}..", name.chars().take(fits - 2).collect::<String>())
+                } else {
+                    name
+                };
+                let _ = write!(
+                    svg,
+                    r#"<text x="{:.2}" y="{:.2}">{}</text>"#,
+                    x + 3.0,
+                    y + FRAME_HEIGHT - 4.0,
+                    escape(&text)
+                );
+            }
+
+            let _ = writeln!(svg, "</g>");
+
+            let mut children = frame.children.clone();
+            children.sort_by_key(|child| self.name(&self.frames[*child]));
+
+            let mut offset = x;
+            for child in children {
+                queue.push((child, offset));
+                offset += unit.of(&totals[child]) as f64 * scale;
+            }
+        }
+
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn color(label: &Label, name: &str) -> String {
+    let hash = name
+        .bytes()
+        .fold(5381u32, |hash, byte| hash.wrapping_mul(33) ^ byte as u32);
+
+    match label {
+        Label::Builtin(..) => format!(
+            "rgb({},{},{})",
+            80 + hash % 55,
+            150 + (hash >> 8) % 55,
+            200 + (hash >> 16) % 55
+        ),
+        Label::Program | Label::Function(..) => format!(
+            "rgb({},{},{})",
+            205 + hash % 50,
+            (hash >> 8) % 230,
+            (hash >> 16) % 55
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Profile, Unit};
+    use crate::{
+        ast::{Name, NamedDeBruijn, Program},
+        machine::cost_model::ExBudget,
+        parser,
+    };
+    use pallas_primitives::conway::Language;
+
+    fn profile(source: &str) -> (ExBudget, Profile) {
+        let program: Program<Name> = parser::program(source).unwrap();
+        let program: Program<NamedDeBruijn> = program.try_into().unwrap();
+
+        let (result, profile) = program.eval_with_profile(ExBudget::default(), &Language::PlutusV2);
+
+        assert!(result.result().is_ok());
+
+        (result.cost(), profile)
+    }
+
+    #[test]
+    fn attributes_budget_to_functions() {
+        let (cost, profile) = profile(
+            r#"
+            (program 1.0.0
+              [
+                (lam double [(lam quadruple [quadruple (con integer 1)]) (lam y [double [double y]])])
+                (lam x [(builtin addInteger) x x])
+              ]
+            )
+            "#,
+        );
+
+        assert_eq!(profile.total(), cost);
+
+        let stacks = profile.collapsed(Unit::Cpu);
+        let stacks = stacks
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            stacks,
+            vec![
+                "program",
+                "program;quadruple",
+                "program;quadruple;double",
+                "program;quadruple;double;addInteger",
+            ]
+        );
+    }
+
+    #[test]
+    fn recursive_calls_stack_up() {
+        let (cost, profile) = profile(
+            r#"
+            (program 1.0.0
+              [
+                (lam countdown [countdown countdown (con integer 2)])
+                (lam self (lam n
+                  (force [
+                    (force (builtin ifThenElse))
+                    [(builtin equalsInteger) n (con integer 0)]
+                    (delay (con integer 0))
+                    (delay [self self [(builtin subtractInteger) n (con integer 1)]])
+                  ])
+                ))
+              ]
+            )
+            "#,
+        );
+
+        assert_eq!(profile.total(), cost);
+
+        let stacks = profile.collapsed(Unit::Mem);
+
+        assert!(stacks.contains("program;countdown;countdown;countdown;equalsInteger "));
+        assert!(!stacks.contains("program;countdown;countdown;countdown;countdown"));
+
+        let svg = profile.to_svg(Unit::Cpu);
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("<title>countdown (cpu: "));
+    }
+}
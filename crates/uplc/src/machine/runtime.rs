@@ -53,7 +53,7 @@ impl From<&Language> for BuiltinSemantics {
 #[derive(Clone, Debug, PartialEq)]
 pub struct BuiltinRuntime {
     pub(super) args: Vec<Value>,
-    fun: DefaultFunction,
+    pub(super) fun: DefaultFunction,
     pub(super) forces: u32,
 }
 
@@ -1,42 +1,108 @@
 use crate::ast::{Name, Program};
+use serde::{Deserialize, Serialize};
 
 pub mod interner;
 pub mod shrinker;
 
+/// How hard the optimizer works on a program. Lower levels trade script size and execution
+/// costs for faster compilation and output that stays closer to the generated code.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptimizationLevel {
+    /// Only the mandatory passes (builtins wrapping & interning).
+    Off,
+    /// A single round of reductions, without builtin currying.
+    Basic,
+    /// Reduce until a fixpoint is reached, including builtin currying.
+    #[default]
+    Full,
+    /// Same as [`OptimizationLevel::Full`], but only keep builtin currying when it makes the
+    /// serialized program smaller.
+    Size,
+}
+
 pub fn aiken_optimize_and_intern(program: Program<Name>) -> Program<Name> {
-    let mut prog = program.run_once_pass();
+    optimize_and_intern(program, OptimizationLevel::Full)
+}
 
-    let mut prev_count = 0;
+pub fn optimize_and_intern(program: Program<Name>, level: OptimizationLevel) -> Program<Name> {
+    let prog = program.run_once_pass();
 
-    loop {
-        let (current_program, context) = prog.multi_pass();
+    let prog = match level {
+        OptimizationLevel::Off => return prog.clean_up(),
+        OptimizationLevel::Basic => return prog.multi_pass().0.clean_up(),
+        OptimizationLevel::Full | OptimizationLevel::Size => reduce(prog),
+    };
 
-        if context.node_count == prev_count {
-            prog = current_program;
-            break;
-        } else {
-            prog = current_program;
-            prev_count = context.node_count;
-        }
+    let uncurried = match level {
+        OptimizationLevel::Size => Some(prog.clone().clean_up()),
+        _ => None,
+    };
+
+    let prog = reduce(
+        prog.builtin_curry_reducer()
+            .multi_pass()
+            .0
+            .builtin_curry_reducer(),
+    )
+    .clean_up();
+
+    match uncurried {
+        Some(uncurried) if serialized_size(&uncurried) < serialized_size(&prog) => uncurried,
+        _ => prog,
     }
+}
 
-    prog = prog
-        .builtin_curry_reducer()
-        .multi_pass()
-        .0
-        .builtin_curry_reducer();
+/// Run reductions over and over, until they no longer shrink the program.
+fn reduce(mut prog: Program<Name>) -> Program<Name> {
+    let mut prev_count = 0;
 
     loop {
         let (current_program, context) = prog.multi_pass();
 
+        prog = current_program;
+
         if context.node_count == prev_count {
-            prog = current_program;
-            break;
-        } else {
-            prog = current_program;
-            prev_count = context.node_count;
+            return prog;
         }
+
+        prev_count = context.node_count;
     }
+}
+
+/// Size of a program once flat-encoded, or 'usize::MAX' should it fail to encode.
+fn serialized_size(program: &Program<Name>) -> usize {
+    program
+        .clone()
+        .to_debruijn()
+        .ok()
+        .and_then(|program| program.to_flat().ok())
+        .map_or(usize::MAX, |bytes| bytes.len())
+}
 
-    prog.clean_up()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Term;
+
+    #[test]
+    fn size_is_never_larger_than_full() {
+        // The same builtin applied to the same constant, over and over: a candidate for currying.
+        let term = (0..4).fold(Term::var("x"), |term, _| {
+            Term::add_integer()
+                .apply(Term::integer(42.into()))
+                .apply(term)
+        });
+
+        let program: Program<Name> = Program {
+            version: (1, 0, 0),
+            term: term.lambda("x"),
+        };
+
+        let full = optimize_and_intern(program.clone(), OptimizationLevel::Full);
+        let size = optimize_and_intern(program, OptimizationLevel::Size);
+
+        assert!(serialized_size(&size) <= serialized_size(&full));
+        assert!(serialized_size(&size) < usize::MAX);
+    }
 }
@@ -3,7 +3,10 @@ use crate::{
     ast::{Constant, Data, Name, NamedDeBruijn, Program, Term, Type},
     builder::{CONSTR_FIELDS_EXPOSER, CONSTR_INDEX_EXPOSER},
     builtins::DefaultFunction,
-    machine::{cost_model::ExBudget, runtime::Compressable},
+    machine::{
+        cost_model::ExBudget,
+        runtime::{Compressable, INTEGER_TO_BYTE_STRING_MAXIMUM_OUTPUT_LENGTH},
+    },
 };
 use blst::{blst_p1, blst_p2};
 use indexmap::IndexMap;
@@ -195,6 +198,8 @@ impl DefaultFunction {
                 | DefaultFunction::Bls12_381_G1_Add
                 | DefaultFunction::Bls12_381_G2_Add
                 | DefaultFunction::ConstrData
+                | DefaultFunction::IntegerToByteString
+                | DefaultFunction::ByteStringToInteger
         )
     }
 
@@ -334,6 +339,46 @@ impl DefaultFunction {
                 }
             }
 
+            DefaultFunction::IntegerToByteString => {
+                if let (Term::Constant(c), Term::Constant(c2), Term::Constant(c3)) =
+                    (&arg_stack[0], &arg_stack[1], &arg_stack[2])
+                {
+                    if let (Constant::Bool(_), Constant::Integer(size), Constant::Integer(i)) =
+                        (c.as_ref(), c2.as_ref(), c3.as_ref())
+                    {
+                        let required: num_bigint::BigInt = ((i.bits() + 7) / 8).into();
+
+                        let max_size: num_bigint::BigInt =
+                            INTEGER_TO_BYTE_STRING_MAXIMUM_OUTPUT_LENGTH.into();
+
+                        // A size of 0 means 'as many bytes as needed', up to the maximum.
+                        i >= &0.into()
+                            && size >= &0.into()
+                            && size <= &max_size
+                            && if size == &0.into() {
+                                required <= max_size
+                            } else {
+                                &required <= size
+                            }
+                    } else {
+                        false
+                    }
+                } else {
+                    false
+                }
+            }
+
+            DefaultFunction::ByteStringToInteger => {
+                if let (Term::Constant(c), Term::Constant(c2)) = (&arg_stack[0], &arg_stack[1]) {
+                    matches!(
+                        (c.as_ref(), c2.as_ref()),
+                        (Constant::Bool(_), Constant::ByteString(_))
+                    )
+                } else {
+                    false
+                }
+            }
+
             _ => false,
         }
     }
@@ -2721,6 +2766,72 @@ mod tests {
         });
     }
 
+    #[test]
+    fn builtin_eval_integer_to_bytearray() {
+        let program: Program<Name> = Program {
+            version: (1, 0, 0),
+            term: Term::Builtin(DefaultFunction::IntegerToByteString)
+                .apply(Term::bool(true))
+                .apply(Term::integer(4.into()))
+                .apply(Term::integer(258.into()))
+                .lambda("x"),
+        };
+
+        let expected = Program {
+            version: (1, 0, 0),
+            term: Term::byte_string(vec![0, 0, 1, 2]).lambda("x"),
+        };
+
+        compare_optimization(expected, program, |p| {
+            p.run_one_opt(true, &mut |id, term, arg_stack, scope, context| {
+                term.builtin_eval_reducer(id, arg_stack, scope, context);
+            })
+        });
+    }
+
+    #[test]
+    fn builtin_eval_integer_to_bytearray_too_small() {
+        // 258 doesn't fit in a single byte, so evaluating would fail at runtime.
+        let program: Program<Name> = Program {
+            version: (1, 0, 0),
+            term: Term::Builtin(DefaultFunction::IntegerToByteString)
+                .apply(Term::bool(true))
+                .apply(Term::integer(1.into()))
+                .apply(Term::integer(258.into()))
+                .lambda("x"),
+        };
+
+        let expected = program.clone();
+
+        compare_optimization(expected, program, |p| {
+            p.run_one_opt(true, &mut |id, term, arg_stack, scope, context| {
+                term.builtin_eval_reducer(id, arg_stack, scope, context);
+            })
+        });
+    }
+
+    #[test]
+    fn builtin_eval_bytearray_to_integer() {
+        let program: Program<Name> = Program {
+            version: (1, 0, 0),
+            term: Term::Builtin(DefaultFunction::ByteStringToInteger)
+                .apply(Term::bool(false))
+                .apply(Term::byte_string(vec![2, 1]))
+                .lambda("x"),
+        };
+
+        let expected = Program {
+            version: (1, 0, 0),
+            term: Term::integer(258.into()).lambda("x"),
+        };
+
+        compare_optimization(expected, program, |p| {
+            p.run_one_opt(true, &mut |id, term, arg_stack, scope, context| {
+                term.builtin_eval_reducer(id, arg_stack, scope, context);
+            })
+        });
+    }
+
     #[test]
     fn curry_reducer_test_1() {
         let program: Program<Name> = Program {